@@ -0,0 +1,122 @@
+//! `GEN` - `proptest` strategies of generated `Arbitrary` impls, enabled by the `arbitrary` feature.
+
+use proptest::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// The largest number of elements of generated lists and maps.
+pub const MAX_COLLECTION_SIZE: usize = 8;
+
+/// The `Arbitrary::Parameters` of generated types: how many more levels of user-defined types
+/// may be nested within lists, maps and options.
+///
+/// Bounds the size of values of recursive types, e.g. trees. Once exhausted, lists and maps
+/// are empty and options are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Depth(pub u32);
+
+impl Default for Depth {
+    fn default() -> Self {
+        Depth(4)
+    }
+}
+
+impl Depth {
+    /// The depth of the user-defined types nested in a value at this depth.
+    pub fn nested(self) -> Depth {
+        Depth(self.0.saturating_sub(1))
+    }
+
+    pub fn is_exhausted(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Strategy of a user-defined type nested in a value at `depth`.
+pub fn user_defined<T>(depth: Depth) -> BoxedStrategy<T>
+where
+    T: Arbitrary<Parameters = Depth, Strategy = BoxedStrategy<T>>,
+{
+    T::arbitrary_with(depth.nested())
+}
+
+/// Strategy of `list[T]`. `element` is only invoked unless `depth` is exhausted,
+/// which ends the recursion of recursive types.
+pub fn list<T: Clone + Debug + 'static>(
+    depth: Depth,
+    element: impl FnOnce() -> BoxedStrategy<T>,
+) -> BoxedStrategy<Vec<T>> {
+    if depth.is_exhausted() {
+        Just(Vec::new()).boxed()
+    } else {
+        proptest::collection::vec(element(), 0..=MAX_COLLECTION_SIZE).boxed()
+    }
+}
+
+/// Strategy of `option[T]`, see `list`.
+pub fn option<T: Clone + Debug + 'static>(
+    depth: Depth,
+    inner: impl FnOnce() -> BoxedStrategy<T>,
+) -> BoxedStrategy<Option<T>> {
+    if depth.is_exhausted() {
+        Just(None).boxed()
+    } else {
+        proptest::option::of(inner()).boxed()
+    }
+}
+
+/// Strategy of `map[K][V]`, see `list`.
+pub fn map<K, V>(
+    depth: Depth,
+    key: impl FnOnce() -> BoxedStrategy<K>,
+    value: impl FnOnce() -> BoxedStrategy<V>,
+) -> BoxedStrategy<HashMap<K, V>>
+where
+    K: Clone + Debug + Hash + Eq + 'static,
+    V: Clone + Debug + 'static,
+{
+    if depth.is_exhausted() {
+        Just(HashMap::new()).boxed()
+    } else {
+        proptest::collection::hash_map(key(), value(), 0..=MAX_COLLECTION_SIZE).boxed()
+    }
+}
+
+/// Strategy of `result[T][E]`.
+pub fn result<T: Debug + 'static, E: Debug + 'static>(
+    ok: BoxedStrategy<T>,
+    err: BoxedStrategy<E>,
+) -> BoxedStrategy<Result<T, E>> {
+    prop_oneof![ok.prop_map(Ok), err.prop_map(Err)].boxed()
+}
+
+/// Strategy of `f64`, without NaN and infinities, which JSON cannot represent.
+pub fn f64() -> BoxedStrategy<f64> {
+    (proptest::num::f64::NORMAL | proptest::num::f64::ZERO).boxed()
+}
+
+/// Strategy of `datetime`, between years 1 and 9999.
+pub fn datetime() -> BoxedStrategy<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+    (-62_135_596_800i64..=253_402_300_799, 0u32..1_000_000_000)
+        .prop_map(|(secs, nanos)| chrono::Utc.timestamp(secs, nanos))
+        .boxed()
+}
+
+/// Strategy of `date`, between years 1 and 9999.
+pub fn date() -> BoxedStrategy<chrono::NaiveDate> {
+    (1i32..=3_652_059)
+        .prop_map(chrono::NaiveDate::from_num_days_from_ce)
+        .boxed()
+}
+
+/// Strategy of `uuid`.
+pub fn uuid() -> BoxedStrategy<uuid::Uuid> {
+    any::<u128>().prop_map(uuid::Uuid::from_u128).boxed()
+}
+
+/// Strategy of `bytes`.
+pub fn bytes() -> BoxedStrategy<Vec<u8>> {
+    proptest::collection::vec(any::<u8>(), 0..=64).boxed()
+}
@@ -0,0 +1,23 @@
+//! `HANDLER` Authentication of requests to endpoints annotated with `@auth`.
+
+use crate::handler::ServiceError;
+
+/// Authenticates requests, yielding the principal on whose behalf a request is made.
+///
+/// Handler traits of services that contain `@auth` endpoints expose their authenticator
+/// through the `authenticator` trait function. Generated code invokes it after the
+/// interceptor and before the handler of every `@auth` endpoint, and passes the resulting
+/// principal to the handler. An `Err` is returned to the client instead of invoking the handler.
+#[async_trait_with_sync::async_trait(Sync)]
+pub trait Authenticator: Send + Sync {
+    /// The authenticated entity, e.g. a user or an API client.
+    type Principal: Send + Sync;
+
+    /// Authenticates a request by its `headers`, e.g. by validating a bearer token.
+    /// Implementations should return `ServiceError::Authentication` for missing
+    /// or invalid credentials.
+    async fn authenticate(
+        &self,
+        headers: &hyper::HeaderMap,
+    ) -> Result<Self::Principal, ServiceError>;
+}
@@ -0,0 +1,496 @@
+//! `GEN` - runtime of generated `reqwest`-based clients, enabled by the `client` feature.
+
+use crate::compression::{self, Encoding};
+use crate::handler::WithHeaders;
+use crate::response_headers::{InvalidHeader, ResponseHeaders};
+use crate::service_protocol::ErrorResponse;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+use crate::wire_format::WireFormat;
+
+pub use futures::Stream;
+pub use reqwest::header::{HeaderName, HeaderValue};
+pub use reqwest::{Client, Method, RequestBuilder, Url};
+
+pub use crate::serialization_helpers::ToParam;
+
+/// Errors of requests sent by generated clients.
+#[derive(Debug)]
+pub enum ClientError {
+    /// The request could not be built, e.g., because the base URL cannot have path segments.
+    InvalidRequest(String),
+    /// The request could not be sent or the response could not be received.
+    Transport(reqwest::Error),
+    /// The server responded with an error response of the humble service protocol.
+    ErrorResponse(ErrorResponse),
+    /// The server responded with an unexpected status code and a body that is no error response.
+    UnexpectedStatus { status: u16, body: String },
+    /// The response body is not a JSON value of the route's return type.
+    InvalidResponse(serde_json::Error),
+    /// The response body cannot be decompressed by its `Content-Encoding`.
+    InvalidCompressedResponse(std::io::Error),
+    /// The response body is not a CBOR or MessagePack value of the route's return type.
+    #[cfg(any(feature = "cbor", feature = "msgpack"))]
+    InvalidWireFormatResponse { content_type: String, error: String },
+    /// A response header declared by the route is missing or is no value of its type.
+    InvalidResponseHeader(InvalidHeader),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::InvalidRequest(e) => write!(f, "invalid request: {}", e),
+            ClientError::Transport(e) => write!(f, "transport error: {}", e),
+            ClientError::ErrorResponse(e) => write!(f, "error response: {:?}", e),
+            ClientError::UnexpectedStatus { status, body } => {
+                write!(f, "unexpected status {}: {}", status, body)
+            }
+            ClientError::InvalidResponse(e) => write!(f, "invalid response: {}", e),
+            ClientError::InvalidCompressedResponse(e) => {
+                write!(f, "invalid compressed response: {}", e)
+            }
+            #[cfg(any(feature = "cbor", feature = "msgpack"))]
+            ClientError::InvalidWireFormatResponse {
+                content_type,
+                error,
+            } => write!(f, "invalid {} response: {}", content_type, error),
+            ClientError::InvalidResponseHeader(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Transport(e)
+    }
+}
+
+/// Appends the path `segments` of a route to `base_url`.
+/// The `User-Agent` and the default headers of the requests of a generated client, set once
+/// instead of per request, e.g. to attribute traffic to the calling application.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    user_agent: HeaderValue,
+    default_headers: reqwest::header::HeaderMap,
+    timeout: Option<Duration>,
+}
+
+impl ClientConfig {
+    /// A config sending `user_agent`, which generated clients default to their `USER_AGENT`,
+    /// and accepting compressed responses (see `compression::ACCEPT_ENCODING`).
+    pub fn new(user_agent: &'static str) -> Self {
+        let mut default_headers = reqwest::header::HeaderMap::new();
+        default_headers.insert(
+            reqwest::header::ACCEPT_ENCODING,
+            HeaderValue::from_static(compression::ACCEPT_ENCODING),
+        );
+        Self {
+            user_agent: HeaderValue::from_static(user_agent),
+            default_headers,
+            timeout: None,
+        }
+    }
+
+    /// Replace the `User-Agent`, e.g. to prefix it with the name of the calling application.
+    pub fn with_user_agent(mut self, user_agent: HeaderValue) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Send header `name` with `value` in every request, replacing a previous value.
+    pub fn with_default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Fail requests that are not completed within `timeout`, from sending the request until the
+    /// response body is received.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The `User-Agent` sent by clients using the config.
+    pub fn user_agent(&self) -> &HeaderValue {
+        &self.user_agent
+    }
+
+    /// Build a client sending the `User-Agent` and default headers with every request.
+    pub fn build_client(&self) -> Result<Client, ClientError> {
+        let mut builder = Client::builder()
+            .user_agent(self.user_agent.clone())
+            .default_headers(self.default_headers.clone());
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// The segments of the `rest` of a path, e.g. of a route ending in `{*path: str}`.
+pub fn rest_segments(rest: &str) -> Vec<String> {
+    rest.split('/').map(str::to_owned).collect()
+}
+
+pub fn route_url(base_url: &Url, segments: &[String]) -> Result<Url, ClientError> {
+    let mut url = base_url.clone();
+    url.path_segments_mut()
+        .map_err(|_| {
+            ClientError::InvalidRequest(format!("base URL {} cannot have a path", base_url))
+        })?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+/// Sends `req` and deserializes the JSON response body, or the CBOR or MessagePack response body
+/// if the response has their content type (see `accept_wire_format`), decompressed by its
+/// `Content-Encoding`.
+/// Responses with status code 204 No Content have no body, they deserialize like `null`,
+/// i.e., to the `()` return value of the route.
+///
+/// Besides successful responses, responses with one of the `domain_error_statuses` carry
+/// a serialized return value, i.e., the domain error of a `result[T][E]` return type.
+pub async fn send<T: DeserializeOwned>(
+    req: RequestBuilder,
+    domain_error_statuses: &[u16],
+) -> Result<T, ClientError> {
+    receive(req.send().await?, domain_error_statuses).await
+}
+
+/// Like `send`, but also reads the response headers declared by the route.
+pub async fn send_with_headers<T: DeserializeOwned, H: ResponseHeaders>(
+    req: RequestBuilder,
+    domain_error_statuses: &[u16],
+) -> Result<WithHeaders<T, H>, ClientError> {
+    receive_with_headers(req.send().await?, domain_error_statuses).await
+}
+
+/// Like `send`, but streams the body of `req` in chunks, reporting the upload to `progress`.
+///
+/// Multipart bodies report their progress themselves, see `MultipartForm::into_form_with_progress`.
+pub async fn send_with_progress<T: DeserializeOwned>(
+    client: &Client,
+    req: RequestBuilder,
+    progress: &UploadProgressReporter,
+    domain_error_statuses: &[u16],
+) -> Result<T, ClientError> {
+    receive(
+        execute_with_progress(client, req, progress).await?,
+        domain_error_statuses,
+    )
+    .await
+}
+
+/// Sends `req` like `send_with_progress`, but returns the response as is.
+pub async fn execute_with_progress(
+    client: &Client,
+    req: RequestBuilder,
+    progress: &UploadProgressReporter,
+) -> Result<reqwest::Response, ClientError> {
+    let mut request = req.build()?;
+    let bytes = request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .map(<[u8]>::to_vec);
+    if let Some(bytes) = bytes {
+        // streamed bodies have no implicit length
+        request
+            .headers_mut()
+            .insert(reqwest::header::CONTENT_LENGTH, bytes.len().into());
+        progress.add_total(bytes.len() as u64);
+        *request.body_mut() = Some(progress.stream(bytes));
+    }
+    Ok(client.execute(request).await?)
+}
+
+/// Deserializes the return value of `resp` like `send`, and reads the response headers
+/// declared by the route. Headers are only read from responses carrying a return value.
+pub async fn receive_with_headers<T: DeserializeOwned, H: ResponseHeaders>(
+    resp: reqwest::Response,
+    domain_error_statuses: &[u16],
+) -> Result<WithHeaders<T, H>, ClientError> {
+    let headers = resp.headers().clone();
+    let value = receive(resp, domain_error_statuses).await?;
+    let headers = H::read(&headers).map_err(ClientError::InvalidResponseHeader)?;
+    Ok(WithHeaders { value, headers })
+}
+
+async fn receive<T: DeserializeOwned>(
+    resp: reqwest::Response,
+    domain_error_statuses: &[u16],
+) -> Result<T, ClientError> {
+    let status = resp.status().as_u16();
+    #[cfg(any(feature = "cbor", feature = "msgpack"))]
+    let format = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(WireFormat::from_content_type)
+        .filter(|format| *format != WireFormat::Json);
+    let encoding = resp
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .and_then(Encoding::from_coding);
+    let body = resp.bytes().await?;
+    let body = match encoding {
+        Some(encoding) => encoding
+            .decode(&body)
+            .map_err(ClientError::InvalidCompressedResponse)?
+            .into(),
+        None => body,
+    };
+    if status == 204 {
+        serde_json::from_value(serde_json::Value::Null).map_err(ClientError::InvalidResponse)
+    } else if resp_is_return_value(status, domain_error_statuses) {
+        #[cfg(any(feature = "cbor", feature = "msgpack"))]
+        if let Some(format) = format {
+            return format.from_slice(&body).map_err(|error| {
+                ClientError::InvalidWireFormatResponse {
+                    content_type: format.content_type().to_owned(),
+                    error,
+                }
+            });
+        }
+        serde_json::from_slice(&body).map_err(ClientError::InvalidResponse)
+    } else {
+        Err(match serde_json::from_slice::<ErrorResponse>(&body) {
+            Ok(error_response) => ClientError::ErrorResponse(error_response),
+            Err(_) => ClientError::UnexpectedStatus {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            },
+        })
+    }
+}
+
+/// Sets the encoding of `value` in `format` as the body of `req`, as sent by clients of services
+/// annotated with `@format(...)`.
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub fn wire_format_body<T: serde::Serialize>(
+    req: RequestBuilder,
+    format: WireFormat,
+    value: &T,
+) -> Result<RequestBuilder, ClientError> {
+    let body = format.to_vec(value).map_err(ClientError::InvalidRequest)?;
+    Ok(req
+        .header(reqwest::header::CONTENT_TYPE, format.content_type())
+        .body(body))
+}
+
+/// Asks for a response to `req` in `format`, as sent by clients of services annotated with `@format(...)`.
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub fn accept_wire_format(req: RequestBuilder, format: WireFormat) -> RequestBuilder {
+    req.header(reqwest::header::ACCEPT, format.content_type())
+}
+
+/// A new random idempotency key, sent by generated clients in the `Idempotency-Key` header of
+/// requests to routes annotated with `@idempotent`.
+pub fn new_idempotency_key() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The pages of a paginated route, fetched lazily, as returned by the `*_pages` methods of
+/// generated clients.
+///
+/// `fetch` requests the page at a cursor, starting with `cursor`, and `next_cursor` returns the
+/// cursor of the page following a page. The stream ends after the last page, i.e., a page
+/// without a next cursor, or after the first error.
+pub fn pages<P, F, Fut>(
+    cursor: Option<String>,
+    fetch: F,
+    next_cursor: fn(&P) -> Option<String>,
+) -> impl Stream<Item = Result<P, ClientError>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<P, ClientError>>,
+{
+    // the state is the cursor of the next page to fetch, `None` after the last page
+    futures::stream::unfold(
+        (fetch, Some(cursor)),
+        move |(mut fetch, cursor)| async move {
+            let page = fetch(cursor?).await;
+            let cursor = page.as_ref().ok().and_then(next_cursor).map(Some);
+            Some((page, (fetch, cursor)))
+        },
+    )
+}
+
+fn resp_is_return_value(status: u16, domain_error_statuses: &[u16]) -> bool {
+    (200..300).contains(&status) || domain_error_statuses.contains(&status)
+}
+
+/// Implemented by generated code for structs used as `multipart[...]` request bodies.
+pub trait ToMultipart {
+    fn to_multipart(self) -> Result<MultipartForm, ClientError>;
+}
+
+/// A `multipart/form-data` request body, built by generated `ToMultipart` impls.
+#[derive(Debug, Default)]
+pub struct MultipartForm(Vec<(&'static str, MultipartPart)>);
+
+#[derive(Debug)]
+enum MultipartPart {
+    Text(String),
+    File(Vec<u8>),
+}
+
+impl MultipartForm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `value` as text part `name`.
+    pub fn text(mut self, name: &'static str, value: String) -> Self {
+        self.0.push((name, MultipartPart::Text(value)));
+        self
+    }
+
+    /// Add `value` as text part `name`, if present.
+    pub fn opt_text(self, name: &'static str, value: Option<String>) -> Self {
+        match value {
+            Some(value) => self.text(name, value),
+            None => self,
+        }
+    }
+
+    /// Add `value` as file part `name`.
+    pub fn file(mut self, name: &'static str, value: Vec<u8>) -> Self {
+        self.0.push((name, MultipartPart::File(value)));
+        self
+    }
+
+    /// Add `value` as file part `name`, if present.
+    pub fn opt_file(self, name: &'static str, value: Option<Vec<u8>>) -> Self {
+        match value {
+            Some(value) => self.file(name, value),
+            None => self,
+        }
+    }
+
+    /// Add `value` as JSON-encoded text part `name`.
+    pub fn json<T: serde::Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self, ClientError> {
+        let value = serde_json::to_string(value)
+            .map_err(|e| ClientError::InvalidRequest(format!("{}", e)))?;
+        Ok(self.text(name, value))
+    }
+
+    pub fn into_form(self) -> reqwest::multipart::Form {
+        self.build_form(reqwest::multipart::Part::bytes)
+    }
+
+    /// Like `into_form`, but reports the upload of the file parts to `progress`.
+    pub fn into_form_with_progress(
+        self,
+        progress: &UploadProgressReporter,
+    ) -> reqwest::multipart::Form {
+        let total = self
+            .0
+            .iter()
+            .map(|(_, part)| match part {
+                MultipartPart::File(value) => value.len() as u64,
+                MultipartPart::Text(_) => 0,
+            })
+            .sum();
+        progress.add_total(total);
+        self.build_form(|value| {
+            let len = value.len() as u64;
+            reqwest::multipart::Part::stream_with_length(progress.stream(value), len)
+        })
+    }
+
+    fn build_form(
+        self,
+        mut file_part: impl FnMut(Vec<u8>) -> reqwest::multipart::Part,
+    ) -> reqwest::multipart::Form {
+        self.0.into_iter().fold(
+            reqwest::multipart::Form::new(),
+            |form, (name, part)| match part {
+                MultipartPart::Text(value) => form.text(name, value),
+                MultipartPart::File(value) => form.part(name, file_part(value).file_name(name)),
+            },
+        )
+    }
+}
+
+/// Progress of an upload, reported by the `*_with_progress` methods of generated clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// The number of bytes handed to the connection so far.
+    pub sent: u64,
+    /// The size of the upload in bytes.
+    /// For multipart bodies, only the file parts count towards `sent` and `total`.
+    pub total: u64,
+}
+
+/// The size of the chunks bodies are streamed in, i.e., the granularity of progress reports.
+pub const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Reports the progress of an upload to a callback, shared by the streamed parts of the body.
+#[derive(Clone)]
+pub struct UploadProgressReporter(Arc<UploadProgressState>);
+
+struct UploadProgressState {
+    callback: Box<dyn Fn(UploadProgress) + Send + Sync>,
+    sent: AtomicU64,
+    total: AtomicU64,
+}
+
+impl UploadProgressReporter {
+    pub fn new(callback: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(UploadProgressState {
+            callback: Box::new(callback),
+            sent: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+        }))
+    }
+
+    fn add_total(&self, bytes: u64) {
+        self.0.total.fetch_add(bytes, Ordering::SeqCst);
+    }
+
+    fn advance(&self, bytes: u64) {
+        let sent = self.0.sent.fetch_add(bytes, Ordering::SeqCst) + bytes;
+        (self.0.callback)(UploadProgress {
+            sent,
+            total: self.0.total.load(Ordering::SeqCst),
+        });
+    }
+
+    /// `value` as a body streamed in chunks of `UPLOAD_CHUNK_SIZE`, reporting each chunk once
+    /// the connection takes it.
+    fn stream(&self, value: Vec<u8>) -> reqwest::Body {
+        let reporter = self.clone();
+        let chunks: Vec<Vec<u8>> = value
+            .chunks(UPLOAD_CHUNK_SIZE)
+            .map(<[u8]>::to_vec)
+            .collect();
+        reqwest::Body::wrap_stream(futures::stream::iter(chunks).map(move |chunk| {
+            reporter.advance(chunk.len() as u64);
+            Ok::<_, std::io::Error>(chunk)
+        }))
+    }
+}
+
+impl fmt::Debug for UploadProgressReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadProgressReporter")
+            .field("sent", &self.0.sent)
+            .field("total", &self.0.total)
+            .finish()
+    }
+}
@@ -0,0 +1,305 @@
+//! `GEN` Compression of the responses of generated servers and decompression of request bodies.
+//!
+//! Responses to requests with an `Accept-Encoding` header listing `gzip`, `deflate` or `br` are
+//! compressed, unless their route is annotated with `@uncompressed`, e.g. because it returns
+//! already compressed data. Only responses of a known size of at least `MIN_COMPRESSED_SIZE`
+//! bytes are compressed, such that small and streamed responses are sent as is.
+//!
+//! Request bodies with a `Content-Encoding` of `gzip`, `deflate` or `br` are decompressed while
+//! they are read, at most `DECOMPRESSED_CHUNK_SIZE` bytes at a time, such that `@max_body_size`
+//! limits their decompressed size before more is decompressed, e.g. of a zip bomb. Requests with
+//! another encoding are answered with 415 Unsupported Media Type.
+
+use crate::server::BoxSyncFuture;
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+use flate2::bufread::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use futures::StreamExt;
+use hyper::body::{Bytes, HttpBody};
+use hyper::header::{self, HeaderMap, HeaderValue};
+use hyper::{Body, Request, Response};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Read, Write};
+
+/// The smallest response body in bytes that is compressed, smaller ones gain too little.
+pub const MIN_COMPRESSED_SIZE: u64 = 1024;
+
+/// The largest chunk in bytes that request bodies are decompressed into at a time.
+pub const DECOMPRESSED_CHUNK_SIZE: usize = 8 * 1024;
+
+/// The `Accept-Encoding` sent by generated clients.
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+/// A content coding of request and response bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    /// The zlib format, as `deflate` is defined by HTTP.
+    Deflate,
+    Brotli,
+}
+
+impl Encoding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Brotli => "br",
+        }
+    }
+
+    /// The encoding named `coding` in a `Content-Encoding` or `Accept-Encoding` header.
+    pub fn from_coding(coding: &str) -> Option<Self> {
+        match coding.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Encoding::Gzip),
+            "deflate" => Some(Encoding::Deflate),
+            "br" => Some(Encoding::Brotli),
+            _ => None,
+        }
+    }
+
+    /// The encoding of responses to a request with `headers`, the one listed in its
+    /// `Accept-Encoding` header with the highest quality, the first one among equals.
+    ///
+    /// `*` stands for the encodings that are not listed, e.g. `gzip;q=0, *` accepts any but
+    /// `gzip`, and is only chosen if no listed encoding has at least its quality.
+    pub fn negotiate(headers: &HeaderMap) -> Option<Self> {
+        let mut negotiated = None;
+        let mut best = 0.0;
+        let mut listed = vec![];
+        let mut any_quality = None;
+        let codings = headers
+            .get_all(header::ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for coding in codings {
+            let mut params = coding.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if name == "*" {
+                any_quality = Some(quality);
+                continue;
+            }
+            let encoding = match Encoding::from_coding(name) {
+                Some(encoding) => encoding,
+                None => continue,
+            };
+            listed.push(encoding);
+            if quality > best {
+                negotiated = Some(encoding);
+                best = quality;
+            }
+        }
+        let unlisted = [Encoding::Gzip, Encoding::Deflate, Encoding::Brotli]
+            .iter()
+            .copied()
+            .find(|encoding| !listed.contains(encoding));
+        match (any_quality, unlisted) {
+            (Some(quality), Some(encoding)) if quality > best => Some(encoding),
+            _ => negotiated,
+        }
+    }
+
+    /// Compresses `bytes`.
+    pub fn encode(self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        let level = flate2::Compression::default();
+        match self {
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), level);
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            Encoding::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), level);
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            Encoding::Brotli => {
+                // quality 5 of 11 and a 4 MiB window, which compress about as fast as gzip
+                let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+                encoder.write_all(bytes)?;
+                Ok(encoder.into_inner())
+            }
+        }
+    }
+
+    /// Decompresses `bytes`.
+    pub fn decode(self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        let mut decoder = Decoder::new(self);
+        decoder
+            .input()
+            .chunks
+            .push_back(Bytes::copy_from_slice(bytes));
+        decoder.input().is_complete = true;
+        let mut decoded = vec![];
+        decoder.read_to_end(&mut decoded)?;
+        Ok(decoded)
+    }
+}
+
+/// The compressed chunks of a body received so far.
+///
+/// Reading beyond them fails with `io::ErrorKind::WouldBlock` until the next chunk is received,
+/// which the decoders of `flate2` and `brotli` resume from.
+#[derive(Default)]
+struct Input {
+    chunks: VecDeque<Bytes>,
+    /// Whether all chunks of the body were received.
+    is_complete: bool,
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for Input {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self.chunks.front() {
+            Some(chunk) => Ok(chunk),
+            None if self.is_complete => Ok(&[]),
+            None => Err(io::ErrorKind::WouldBlock.into()),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Some(chunk) = self.chunks.front_mut() {
+            *chunk = chunk.slice(amt..);
+            if chunk.is_empty() {
+                self.chunks.pop_front();
+            }
+        }
+    }
+}
+
+/// Decompresses a body into buffers of a fixed size, reading its chunks as they are received.
+enum Decoder {
+    Gzip(GzDecoder<Input>),
+    Deflate(ZlibDecoder<Input>),
+    Brotli(Box<brotli::Decompressor<Input>>),
+}
+
+impl Decoder {
+    fn new(encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Gzip => Decoder::Gzip(GzDecoder::new(Input::default())),
+            Encoding::Deflate => Decoder::Deflate(ZlibDecoder::new(Input::default())),
+            Encoding::Brotli => {
+                Decoder::Brotli(Box::new(brotli::Decompressor::new(Input::default(), 4096)))
+            }
+        }
+    }
+
+    fn input(&mut self) -> &mut Input {
+        match self {
+            Decoder::Gzip(decoder) => decoder.get_mut(),
+            Decoder::Deflate(decoder) => decoder.get_mut(),
+            Decoder::Brotli(decoder) => decoder.get_mut(),
+        }
+    }
+}
+
+impl Read for Decoder {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Decoder::Gzip(decoder) => decoder.read(buf),
+            Decoder::Deflate(decoder) => decoder.read(buf),
+            Decoder::Brotli(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+/// Replaces the body of `req` with its decompressed body if it has a `Content-Encoding`.
+///
+/// Invoked from generated code before reading the body.
+pub fn decompress_request(req: &mut Request<Body>) -> Result<(), ErrorResponse> {
+    let content_encoding = match req.headers_mut().remove(header::CONTENT_ENCODING) {
+        None => return Ok(()),
+        Some(content_encoding) => content_encoding,
+    };
+    let encoding = match content_encoding.to_str().map(str::trim) {
+        Ok("identity") => return Ok(()),
+        Ok(coding) => Encoding::from_coding(coding),
+        Err(_) => None,
+    };
+    let encoding = encoding.ok_or_else(|| {
+        RuntimeError::UnsupportedContentEncoding {
+            content_encoding: String::from_utf8_lossy(content_encoding.as_bytes()).into_owned(),
+        }
+        .to_error_response()
+    })?;
+    // the length of the compressed body does not limit the decompressed one
+    req.headers_mut().remove(header::CONTENT_LENGTH);
+    let body = std::mem::replace(req.body_mut(), Body::empty());
+    // chunks are only decompressed when the next one is read, e.g. by `limits::limit_body`
+    let decompressed =
+        futures::stream::unfold(Some((body, Decoder::new(encoding))), |state| async move {
+            let (mut body, mut decoder) = state?;
+            let mut decompressed = vec![0; DECOMPRESSED_CHUNK_SIZE];
+            loop {
+                match decoder.read(&mut decompressed) {
+                    Ok(0) => return None,
+                    Ok(len) => {
+                        decompressed.truncate(len);
+                        return Some((Ok(decompressed), Some((body, decoder))));
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => match body.next().await {
+                        Some(Ok(chunk)) => decoder.input().chunks.push_back(chunk),
+                        Some(Err(e)) => return Some((Err(io::Error::other(e)), None)),
+                        None => decoder.input().is_complete = true,
+                    },
+                    Err(e) => return Some((Err(e), None)),
+                }
+            }
+        });
+    *req.body_mut() = Body::wrap_stream(decompressed);
+    Ok(())
+}
+
+/// Compresses the response of `dispatch` in `encoding`, as negotiated by `Encoding::negotiate`.
+///
+/// Invoked from generated code within a `Router`.
+pub fn compress(
+    encoding: Option<Encoding>,
+    dispatch: BoxSyncFuture<Result<Response<Body>, ErrorResponse>>,
+) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> {
+    Box::pin(async move {
+        let mut response = dispatch.await?;
+        // the response depends on `Accept-Encoding` also if it is sent as is, e.g. for caches
+        response
+            .headers_mut()
+            .append(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => return Ok(response),
+        };
+        let size = response.body().size_hint().exact();
+        let compressible = size.is_some_and(|size| size >= MIN_COMPRESSED_SIZE)
+            && !response.headers().contains_key(header::CONTENT_ENCODING);
+        if !compressible {
+            return Ok(response);
+        }
+        let (mut parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body).await.map_err(|e| {
+            RuntimeError::SerializeHandlerResponse(e.to_string()).to_error_response()
+        })?;
+        let compressed = encoding.encode(&body).map_err(|e| {
+            RuntimeError::SerializeHandlerResponse(e.to_string()).to_error_response()
+        })?;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.headers.insert(
+            header::CONTENT_ENCODING,
+            HeaderValue::from_static(encoding.as_str()),
+        );
+        Ok(Response::from_parts(parts, compressed.into()))
+    })
+}
@@ -0,0 +1,126 @@
+//! `GEN` Cross-origin resource sharing (CORS) of services annotated with
+//! `@cors(origins = ["https://app.example.com"])`.
+//!
+//! The generated router of such a service answers preflight requests, i.e., `OPTIONS` requests
+//! with an `Access-Control-Request-Method` header, to any path within the service, and adds the
+//! `Access-Control-Allow-Origin` header to the responses of its routes to allowed origins.
+//! `origins = ["*"]` allows any origin.
+
+use crate::server::BoxSyncFuture;
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+use hyper::header::{self, HeaderValue};
+use hyper::{Body, Method, Request, Response, StatusCode};
+
+/// How long browsers may cache the response to a preflight request, in seconds.
+const MAX_AGE: &str = "3600";
+
+/// The CORS policy of a service, generated from its `@cors` annotation.
+#[derive(Debug, Clone, Copy)]
+pub struct Cors {
+    /// The allowed origins, e.g. `https://app.example.com`, or `*` for any origin.
+    pub origins: &'static [&'static str],
+    /// The methods of the routes of the service, e.g. `GET`.
+    pub methods: &'static [&'static str],
+    /// The response headers declared by the routes of the service, e.g. `ETag`.
+    pub expose_headers: &'static [&'static str],
+}
+
+impl Cors {
+    fn allows_any_origin(&self) -> bool {
+        self.origins.contains(&"*")
+    }
+
+    /// The `Access-Control-Allow-Origin` header of a response to `origin`, if allowed.
+    fn allow_origin(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        if self.allows_any_origin() {
+            Some(HeaderValue::from_static("*"))
+        } else if self
+            .origins
+            .iter()
+            .any(|o| o.as_bytes() == origin.as_bytes())
+        {
+            Some(origin.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Adds the CORS headers of a response to `origin`, if allowed.
+    fn write_headers(&self, origin: Option<&HeaderValue>, response: &mut Response<Body>) {
+        let headers = response.headers_mut();
+        if !self.allows_any_origin() {
+            // the response depends on the origin, also if it is not allowed
+            headers.append(header::VARY, HeaderValue::from_static("Origin"));
+        }
+        if let Some(allow_origin) = origin.and_then(|o| self.allow_origin(o)) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+            if !self.expose_headers.is_empty() {
+                let expose_headers = HeaderValue::from_str(&self.expose_headers.join(", "))
+                    .expect("header names are valid header values");
+                headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, expose_headers);
+            }
+        }
+    }
+}
+
+/// Answers `req` if it is a preflight request, with 204 No Content if its origin is allowed, and
+/// with 403 Forbidden otherwise.
+///
+/// Invoked from generated code within a `Router`.
+pub fn preflight(
+    cors: &'static Cors,
+    req: &Request<Body>,
+) -> Option<BoxSyncFuture<Result<Response<Body>, ErrorResponse>>> {
+    let headers = req.headers();
+    let origin = headers.get(header::ORIGIN)?;
+    if req.method() != Method::OPTIONS
+        || !headers.contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+    {
+        return None;
+    }
+    if cors.allow_origin(origin).is_none() {
+        let origin = String::from_utf8_lossy(origin.as_bytes()).into_owned();
+        tracing::debug!(origin = origin.as_str(), "CORS origin not allowed");
+        return Some(Box::pin(async move {
+            Ok(RuntimeError::CorsOriginNotAllowed { origin }
+                .to_error_response()
+                .to_hyper_response())
+        }));
+    }
+
+    let mut response = Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            cors.methods.join(", "),
+        )
+        .header(header::ACCESS_CONTROL_MAX_AGE, MAX_AGE)
+        .body(Body::empty())
+        .expect("preflight responses must always be buildable");
+    if let Some(request_headers) = headers.get(header::ACCESS_CONTROL_REQUEST_HEADERS) {
+        response.headers_mut().insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            request_headers.clone(),
+        );
+    }
+    cors.write_headers(Some(origin), &mut response);
+    Some(Box::pin(async move { Ok(response) }))
+}
+
+/// Adds the CORS headers to the response of the dispatcher `dispatch` of a request from `origin`.
+///
+/// Invoked from generated code within a `Router`.
+pub fn with_headers(
+    cors: &'static Cors,
+    origin: Option<HeaderValue>,
+    dispatch: BoxSyncFuture<Result<Response<Body>, ErrorResponse>>,
+) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> {
+    Box::pin(async move {
+        let mut response = dispatch.await.unwrap_or_else(|e| {
+            tracing::error!(err = ?e, "handler returned error");
+            e.to_hyper_response()
+        });
+        cors.write_headers(origin.as_ref(), &mut response);
+        Ok(response)
+    })
+}
@@ -0,0 +1,55 @@
+//! `SERVER` Runtime gating of routes annotated with `@flag("name")`, enabled by passing
+//! `FeatureFlags` to the generated `Builder`.
+//!
+//! Flagged routes are disabled unless `FeatureFlags` enable them, such that generated endpoints
+//! can be shipped dark and enabled dynamically, e.g. per tenant or for a percentage of requests.
+
+use crate::server::BoxSyncFuture;
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+use hyper::{Body, Request, Response};
+use std::fmt;
+
+/// The state of a feature flag for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagState {
+    /// The flagged routes serve the request.
+    Enabled,
+    /// The flagged routes do not exist, i.e., the request is answered with 404.
+    Hidden,
+    /// The flagged routes exist but are unavailable, i.e., the request is answered with 503.
+    Unavailable,
+}
+
+/// `HANDLER` Decides per request whether routes annotated with `@flag("name")` are enabled.
+pub trait FeatureFlags: Send + Sync {
+    /// The state of flag `flag` for `req`, which is consulted after routing and before the
+    /// interceptor.
+    fn state(&self, flag: &str, req: &Request<Body>) -> FlagState;
+}
+
+impl fmt::Debug for dyn FeatureFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FeatureFlags")
+    }
+}
+
+/// `GEN` The state of flag `flag` for `req` according to `feature_flags`, `Hidden` if none were
+/// passed to the `Builder`.
+pub fn flag_state(
+    feature_flags: Option<&dyn FeatureFlags>,
+    flag: &str,
+    req: &Request<Body>,
+) -> FlagState {
+    match feature_flags {
+        Some(feature_flags) => feature_flags.state(flag, req),
+        None => FlagState::Hidden,
+    }
+}
+
+/// `GEN` The response to requests to routes whose flag `flag` is `Unavailable`.
+pub fn unavailable(flag: &str) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> {
+    let error = RuntimeError::RouteUnavailable {
+        flag: flag.to_owned(),
+    };
+    Box::pin(async move { Err(error.to_error_response()) })
+}
@@ -1,10 +1,16 @@
 //! `HANDLER` Types used by a handler implementation, re-exported by generated code.
 
 use core::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
 
 /// The response type returned by implementors of a humblegen service trait function.
 pub type HandlerResponse<T> = Result<T, ServiceError>;
 
+/// The future returned by service trait functions of handler traits generated
+/// with the `BoxedFuture` handler trait style.
+pub type HandlerFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + Sync + 'a>>;
+
 /// A service-level error.
 ///
 /// This type is returned by implementors of a humblegen service trait function
@@ -27,3 +33,20 @@ impl Display for ServiceError {
         }
     }
 }
+
+/// The return value of a route declaring response headers, e.g. `-> FileMeta with header "ETag": str`.
+///
+/// `headers` is a generated struct with a field per declared header,
+/// see `crate::response_headers::ResponseHeaders`.
+/// Recorded responses of mocked routes are JSON objects with both fields.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WithHeaders<T, H> {
+    pub value: T,
+    pub headers: H,
+}
+
+impl<T, H> WithHeaders<T, H> {
+    pub fn new(value: T, headers: H) -> Self {
+        Self { value, headers }
+    }
+}
@@ -0,0 +1,302 @@
+//! `GEN` Deduplication of the requests to routes annotated with `@idempotent`, using the
+//! `IdempotencyStore` passed to `Builder::with_idempotency_store`, or else a `MemoryStore`.
+//!
+//! Requests to idempotent routes carry a unique `Idempotency-Key` header. Generated Rust, Kotlin
+//! and Swift clients send a new key per call, Elm clients the key they are passed, see
+//! `idempotencyKeyGenerator`. The response to the first request with a key is stored, and repeated
+//! requests with the key, e.g. retries after a timeout, are answered with the stored response
+//! instead of running the handler again:
+//!
+//! - Requests without a key are answered with 400 Bad Request.
+//! - Requests with the key of a request still in progress are answered with 409 Conflict.
+//! - Responses with a 5xx status code are not stored, so the request can be retried.
+//! - Requests dropped before their response is stored, e.g. because the client disconnected,
+//!   release their key, so the request can be retried.
+//!
+//! Keys are scoped to the service, the path and the `Authorization` header of the request.
+//! Servers running as several instances pass a store shared by all instances.
+
+use crate::middleware::RouteInfo;
+use crate::recording::RecordedResponse;
+use crate::server::BoxSyncFuture;
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+use hyper::header::{self, HeaderName, HeaderValue};
+use hyper::{Body, Request, Response};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The header carrying the idempotency key of a request.
+pub const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
+/// The state of an idempotency key, see `IdempotencyStore::claim`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Claim {
+    /// The key was unused and is now claimed by the request.
+    Claimed,
+    /// The key is claimed by a request that is still in progress.
+    InProgress,
+    /// The key was used by a request answered with the response.
+    Completed(RecordedResponse),
+}
+
+/// `HANDLER` Stores the responses to the requests to idempotent routes by their key.
+///
+/// Stores evict keys after a while, e.g. a day, after which a request with the key runs the
+/// handler again.
+#[async_trait_with_sync::async_trait(Sync)]
+pub trait IdempotencyStore: Send + Sync {
+    /// Claims `key` for a request, unless it is claimed by a request in progress or completed.
+    async fn claim(&self, key: &str) -> Claim;
+
+    /// Stores the `response` to the request that claimed `key`.
+    async fn complete(&self, key: &str, response: RecordedResponse);
+
+    /// Releases `key` after the request that claimed it failed or was dropped, such that it can
+    /// be retried.
+    async fn release(&self, key: &str);
+}
+
+impl fmt::Debug for dyn IdempotencyStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IdempotencyStore")
+    }
+}
+
+/// An in-memory `IdempotencyStore` of a single server instance, evicting keys after a time to
+/// live of 24 hours by default.
+#[derive(Debug)]
+pub struct MemoryStore {
+    ttl: Duration,
+    entries: Mutex<Entries>,
+}
+
+/// The claimed keys of a `MemoryStore`.
+#[derive(Debug, Default)]
+struct Entries {
+    by_key: HashMap<String, (Instant, Option<RecordedResponse>)>,
+    // the keys in the order they were claimed, evicted from the front once expired
+    claims: VecDeque<(Instant, String)>,
+}
+
+impl Entries {
+    /// Evict the keys claimed before `expired_before`, in time linear in their number.
+    fn evict(&mut self, expired_before: Instant) {
+        while let Some((claimed_at, _)) = self.claims.front() {
+            if *claimed_at >= expired_before {
+                break;
+            }
+            let (claimed_at, key) = self.claims.pop_front().unwrap();
+            // released keys may have been claimed again since
+            if matches!(self.by_key.get(&key), Some((at, _)) if *at == claimed_at) {
+                self.by_key.remove(&key);
+            }
+        }
+    }
+}
+
+impl MemoryStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(24 * 60 * 60))
+    }
+}
+
+#[async_trait_with_sync::async_trait(Sync)]
+impl IdempotencyStore for MemoryStore {
+    async fn claim(&self, key: &str) -> Claim {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(expired_before) = now.checked_sub(self.ttl) {
+            entries.evict(expired_before);
+        }
+        match entries.by_key.get(key) {
+            Some((_, Some(response))) => Claim::Completed(response.clone()),
+            Some((_, None)) => Claim::InProgress,
+            None => {
+                entries.by_key.insert(key.to_owned(), (now, None));
+                entries.claims.push_back((now, key.to_owned()));
+                Claim::Claimed
+            }
+        }
+    }
+
+    async fn complete(&self, key: &str, response: RecordedResponse) {
+        if let Some((_, stored)) = self.entries.lock().unwrap().by_key.get_mut(key) {
+            *stored = Some(response);
+        }
+    }
+
+    async fn release(&self, key: &str) {
+        self.entries.lock().unwrap().by_key.remove(key);
+    }
+}
+
+/// The key of `req` to route `route` in the store, scoped to the service, the path and the
+/// `Authorization` header of the request.
+///
+/// Invoked from generated code before the request is dispatched.
+pub fn key(route: &RouteInfo, req: &Request<Body>) -> Result<String, ErrorResponse> {
+    let key = req
+        .headers()
+        .get(IDEMPOTENCY_KEY)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| {
+            RuntimeError::HeaderInvalid {
+                header_name: IDEMPOTENCY_KEY.to_owned(),
+                parse_error: "missing idempotency key".to_owned(),
+            }
+            .to_error_response()
+        })?;
+    // FNV-1a, such that credentials are not stored
+    let authorization = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .map_or(&[][..], HeaderValue::as_bytes)
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    Ok(format!(
+        "{} {} {:016x} {}",
+        route.service,
+        req.uri().path(),
+        authorization,
+        key
+    ))
+}
+
+/// Answers the request with the stored response if its `key` was used before, or else runs
+/// `dispatch` and stores its response.
+///
+/// Invoked from generated code within a `Router`.
+pub fn deduplicate(
+    store: Arc<dyn IdempotencyStore>,
+    key: Result<String, ErrorResponse>,
+    dispatch: BoxSyncFuture<Result<Response<Body>, ErrorResponse>>,
+) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> {
+    Box::pin(async move {
+        let key = key?;
+        match store.claim(&key).await {
+            Claim::Claimed => {}
+            Claim::InProgress => {
+                return Err(RuntimeError::IdempotencyKeyInUse.to_error_response());
+            }
+            Claim::Completed(response) => {
+                tracing::debug!("answering repeated request with stored response");
+                return into_response(response);
+            }
+        }
+        let claim = ClaimGuard {
+            store,
+            key: Some(key),
+        };
+        // like the responses of other routes, errors are answered with their error response
+        let response = dispatch.await.unwrap_or_else(|e| e.to_hyper_response());
+        if response.status().is_server_error() {
+            claim.release().await;
+            return Ok(response);
+        }
+        let (parts, body) = response.into_parts();
+        let body = match hyper::body::to_bytes(body).await {
+            Ok(body) => body,
+            Err(e) => {
+                claim.release().await;
+                return Err(
+                    RuntimeError::SerializeHandlerResponse(e.to_string()).to_error_response()
+                );
+            }
+        };
+        let headers = parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_owned(), value.to_str().ok()?.to_owned()))
+            })
+            .collect();
+        let stored = RecordedResponse {
+            status: parts.status.as_u16(),
+            headers,
+            body: body.to_vec(),
+        };
+        claim.complete(stored).await;
+        Ok(Response::from_parts(parts, body.into()))
+    })
+}
+
+/// The claim of a key by a request in progress, which is released if the request is dropped
+/// before its response is stored, e.g. because the client disconnected.
+struct ClaimGuard {
+    store: Arc<dyn IdempotencyStore>,
+    // `None` once the key was completed or released
+    key: Option<String>,
+}
+
+impl ClaimGuard {
+    async fn complete(mut self, response: RecordedResponse) {
+        if let Some(key) = self.key.clone() {
+            self.store.complete(&key, response).await;
+            self.key = None;
+        }
+    }
+
+    async fn release(mut self) {
+        if let Some(key) = self.key.clone() {
+            self.store.release(&key).await;
+            self.key = None;
+        }
+    }
+}
+
+impl Drop for ClaimGuard {
+    fn drop(&mut self) {
+        let key = match self.key.take() {
+            Some(key) => key,
+            None => return,
+        };
+        // dropping cannot wait for the store, so the key is released by a task of its own
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let store = Arc::clone(&self.store);
+                runtime.spawn(async move { store.release(&key).await });
+            }
+            Err(_) => tracing::warn!(
+                key = key.as_str(),
+                "cannot release idempotency key outside of a tokio runtime"
+            ),
+        }
+    }
+}
+
+/// The stored response, or an error response if its status code is invalid, e.g. because an
+/// external store was modified.
+fn into_response(stored: RecordedResponse) -> Result<Response<Body>, ErrorResponse> {
+    let status = hyper::StatusCode::from_u16(stored.status).map_err(|_| {
+        RuntimeError::SerializeHandlerResponse(format!(
+            "stored response has invalid status code {}",
+            stored.status
+        ))
+        .to_error_response()
+    })?;
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = status;
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    Ok(response)
+}
@@ -0,0 +1,250 @@
+//! `GEN` - serde adapters representing `i64` and `u64` as JSON strings, used by code generated
+//! with `LargeIntRepresentation::String`.
+//!
+//! Integers above 2^53 are not exactly representable as JavaScript numbers. Fields containing
+//! large integers are annotated with `#[serde(with = "As::<C>")]`, where the codec `C` mirrors
+//! the type of the field, e.g. `Option<AsString>` for `Option<i64>` or
+//! `(Same, Vec<AsString>)` for `(String, Vec<u64>)`. Large integers are serialized as strings
+//! and deserialized from both strings and numbers.
+
+use serde::de::{self, DeserializeOwned, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// How values of type `T` are serialized and deserialized.
+pub trait Codec<T> {
+    fn serialize<S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error>;
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>;
+}
+
+/// The module-like type referenced by `#[serde(with = "As::<C>")]`, delegating to codec `C`.
+pub struct As<C>(PhantomData<C>);
+
+impl<C> As<C> {
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        C: Codec<T>,
+        S: Serializer,
+    {
+        C::serialize(value, serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        C: Codec<T>,
+        D: Deserializer<'de>,
+    {
+        C::deserialize(deserializer)
+    }
+}
+
+/// Values without large integers, serialized as usual.
+pub struct Same;
+
+impl<T: Serialize + DeserializeOwned> Codec<T> for Same {
+    fn serialize<S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Large integers, serialized as strings of the decimal number.
+pub struct AsString;
+
+macro_rules! impl_as_string {
+    ($($int:ty),*) => {
+        $(
+            impl Codec<$int> for AsString {
+                fn serialize<S: Serializer>(value: &$int, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(value)
+                }
+
+                fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<$int, D::Error> {
+                    deserializer.deserialize_any(LargeIntVisitor(PhantomData))
+                }
+            }
+        )*
+    };
+}
+
+impl_as_string!(i64, u64);
+
+struct LargeIntVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for LargeIntVisitor<T>
+where
+    T: TryFrom<i64> + TryFrom<u64> + std::str::FromStr,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "an integer, or a string of an integer")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> {
+        T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+        T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        v.parse()
+            .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+/// `optional` fields, which are skipped if `None` and deserialized into `Some` if present, see
+/// `serialization_helpers::deser_present`.
+pub struct Present<C>(PhantomData<C>);
+
+impl<T, C: Codec<T>> Codec<Option<T>> for Present<C> {
+    fn serialize<S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => C::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+        C::deserialize(deserializer).map(Some)
+    }
+}
+
+/// A value serialized with codec `C`.
+struct SerializeWith<'a, T, C>(&'a T, PhantomData<C>);
+
+fn with<T, C>(value: &T) -> SerializeWith<'_, T, C> {
+    SerializeWith(value, PhantomData)
+}
+
+impl<T, C: Codec<T>> Serialize for SerializeWith<'_, T, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        C::serialize(self.0, serializer)
+    }
+}
+
+/// A value deserialized with codec `C`.
+struct DeserializeWith<T, C>(T, PhantomData<C>);
+
+impl<'de, T, C: Codec<T>> Deserialize<'de> for DeserializeWith<T, C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        C::deserialize(deserializer).map(|value| DeserializeWith(value, PhantomData))
+    }
+}
+
+impl<T, C: Codec<T>> Codec<Option<T>> for Option<C> {
+    fn serialize<S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&with::<T, C>(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+        let value = Option::<DeserializeWith<T, C>>::deserialize(deserializer)?;
+        Ok(value.map(|value| value.0))
+    }
+}
+
+impl<T, C: Codec<T>> Codec<Vec<T>> for Vec<C> {
+    fn serialize<S: Serializer>(value: &Vec<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(value.iter().map(with::<T, C>))
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<T>, D::Error> {
+        let values = Vec::<DeserializeWith<T, C>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|value| value.0).collect())
+    }
+}
+
+impl<K, V, KC, VC> Codec<HashMap<K, V>> for HashMap<KC, VC>
+where
+    K: Eq + Hash,
+    KC: Codec<K>,
+    VC: Codec<V>,
+{
+    fn serialize<S: Serializer>(value: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            value
+                .iter()
+                .map(|(key, value)| (with::<K, KC>(key), with::<V, VC>(value))),
+        )
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<K, V>, D::Error> {
+        deserializer.deserialize_map(MapVisitor::<K, V, KC, VC>(PhantomData))
+    }
+}
+
+struct MapVisitor<K, V, KC, VC>(PhantomData<(K, V, KC, VC)>);
+
+impl<'de, K, V, KC, VC> Visitor<'de> for MapVisitor<K, V, KC, VC>
+where
+    K: Eq + Hash,
+    KC: Codec<K>,
+    VC: Codec<V>,
+{
+    type Value = HashMap<K, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a map")
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut access: A) -> Result<HashMap<K, V>, A::Error> {
+        let mut map = HashMap::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some((key, value)) =
+            access.next_entry::<DeserializeWith<K, KC>, DeserializeWith<V, VC>>()?
+        {
+            map.insert(key.0, value.0);
+        }
+        Ok(map)
+    }
+}
+
+impl<T, E, TC: Codec<T>, EC: Codec<E>> Codec<Result<T, E>> for Result<TC, EC> {
+    fn serialize<S: Serializer>(value: &Result<T, E>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Ok(ok) => Ok::<_, SerializeWith<E, EC>>(with::<T, TC>(ok)).serialize(serializer),
+            Err(err) => Err::<SerializeWith<T, TC>, _>(with::<E, EC>(err)).serialize(serializer),
+        }
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Result<T, E>, D::Error> {
+        let value =
+            Result::<DeserializeWith<T, TC>, DeserializeWith<E, EC>>::deserialize(deserializer)?;
+        Ok(value.map(|ok| ok.0).map_err(|err| err.0))
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident $c:ident $i:tt),*) => {
+        impl<$($t, $c: Codec<$t>),*> Codec<($($t,)*)> for ($($c,)*) {
+            fn serialize<S: Serializer>(value: &($($t,)*), serializer: S) -> Result<S::Ok, S::Error> {
+                ($(with::<$t, $c>(&value.$i),)*).serialize(serializer)
+            }
+
+            fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<($($t,)*), D::Error> {
+                let value = <($(DeserializeWith<$t, $c>,)*)>::deserialize(deserializer)?;
+                Ok(($(value.$i.0,)*))
+            }
+        }
+    };
+}
+
+impl_tuple!(T0 C0 0);
+impl_tuple!(T0 C0 0, T1 C1 1);
+impl_tuple!(T0 C0 0, T1 C1 1, T2 C2 2);
+impl_tuple!(T0 C0 0, T1 C1 1, T2 C2 2, T3 C3 3);
+impl_tuple!(T0 C0 0, T1 C1 1, T2 C2 2, T3 C3 3, T4 C4 4);
+impl_tuple!(T0 C0 0, T1 C1 1, T2 C2 2, T3 C3 3, T4 C4 4, T5 C5 5);
+impl_tuple!(T0 C0 0, T1 C1 1, T2 C2 2, T3 C3 3, T4 C4 4, T5 C5 5, T6 C6 6);
+impl_tuple!(T0 C0 0, T1 C1 1, T2 C2 2, T3 C3 3, T4 C4 4, T5 C5 5, T6 C6 6, T7 C7 7);
@@ -8,16 +8,49 @@
 
 pub mod serialization_helpers;
 pub use serialization_helpers as deser_helpers; // compat
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+pub mod auth;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod compression;
+pub mod cors;
+pub mod feature_flags;
 pub mod handler;
-pub mod regexset_map;
+pub mod idempotency;
+pub mod large_int;
+pub mod limits;
+pub mod metrics;
+pub mod middleware;
+pub mod mock;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+pub mod multipart;
+pub mod recording;
+pub mod response_headers;
 pub mod server;
 pub mod service_protocol;
+pub mod shutdown;
+pub mod spec_endpoint;
+#[cfg(feature = "tls")]
+pub mod tls;
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub mod wire_format;
+pub mod wire_hash;
 
 pub extern crate anyhow;
+pub extern crate bitflags;
 pub extern crate chrono;
 pub extern crate downcast_rs;
 pub extern crate hyper;
-pub extern crate regex;
+#[cfg(feature = "arbitrary")]
+pub extern crate proptest;
+#[cfg(feature = "client")]
+pub extern crate reqwest;
+#[cfg(feature = "tls")]
+pub extern crate rustls;
+#[cfg(feature = "cbor")]
+pub extern crate serde_cbor;
 pub extern crate tokio;
 pub extern crate tracing;
 pub extern crate tracing_futures;
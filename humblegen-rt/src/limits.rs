@@ -0,0 +1,74 @@
+//! `GEN` Limits of the requests to routes, from the `@max_body_size(1MB)` and `@timeout(30s)`
+//! annotations of routes, or else the defaults passed to `Builder::with_max_body_size` and
+//! `Builder::with_timeout`.
+//!
+//! Requests with a larger body are answered with 413 Payload Too Large, and requests whose
+//! handler does not respond in time with 504 Gateway Timeout.
+
+use crate::server::BoxSyncFuture;
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+use futures::StreamExt;
+use hyper::{header, Body, Request, Response};
+use std::time::Duration;
+
+/// The default limits of the routes of a server, see `Builder::with_max_body_size` and
+/// `Builder::with_timeout`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum size of request bodies in bytes.
+    pub max_body_size: Option<u64>,
+    /// The time after which the handler of a request is cancelled.
+    pub timeout: Option<Duration>,
+}
+
+/// Reads the body of `req` into memory, unless larger than `max_body_size` bytes.
+///
+/// Invoked from generated code before deserializing the body.
+pub async fn limit_body(req: &mut Request<Body>, max_body_size: u64) -> Result<(), ErrorResponse> {
+    let too_large = || RuntimeError::PostBodyTooLarge { max_body_size }.to_error_response();
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if content_length.is_some_and(|len| len > max_body_size) {
+        return Err(too_large());
+    }
+
+    // the content length may be absent or wrong, thus count the bytes read
+    let mut body = Vec::with_capacity(content_length.unwrap_or(0) as usize);
+    while let Some(chunk) = req.body_mut().next().await {
+        let chunk = chunk
+            .map_err(|e| RuntimeError::PostBodyReadError(format!("{}", e)).to_error_response())?;
+        if (body.len() + chunk.len()) as u64 > max_body_size {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    *req.body_mut() = Body::from(body);
+    Ok(())
+}
+
+/// Answers the request with 504 Gateway Timeout if `dispatch` does not complete within `timeout`.
+///
+/// Invoked from generated code within a `Router`.
+pub fn with_timeout(
+    timeout: Option<Duration>,
+    dispatch: BoxSyncFuture<Result<Response<Body>, ErrorResponse>>,
+) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> {
+    match timeout {
+        None => dispatch,
+        Some(timeout) => Box::pin(async move {
+            match tokio::time::timeout(timeout, dispatch).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!(?timeout, "handler timed out");
+                    Err(RuntimeError::Timeout {
+                        timeout_ms: timeout.as_millis() as u64,
+                    }
+                    .to_error_response())
+                }
+            }
+        }),
+    }
+}
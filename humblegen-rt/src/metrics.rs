@@ -0,0 +1,310 @@
+//! `SERVER` Request latency histograms and status code counts per route, enabled by passing
+//! `Metrics` to the generated `Builder`, and served in the Prometheus text format at `/metrics`
+//! by `Builder::with_metrics_endpoint`.
+
+use crate::server::{BoxSyncFuture, Router, Service};
+use crate::service_protocol::ErrorResponse;
+use hyper::{header, Body, Method, Request, Response};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default upper bounds of the latency histogram buckets, from 5ms to 10s.
+pub const DEFAULT_BUCKETS: &[Duration] = &[
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// A latency service level objective of a route, generated from its `@slo(pNN = duration)` annotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slo {
+    /// The percentile of requests that must meet the target, e.g. `99.0`.
+    pub percentile: f64,
+    pub target: Duration,
+}
+
+/// Registry of the latency histograms of all routes served by a server.
+///
+/// All routes share the histogram buckets configured globally. The buckets of a route with
+/// an SLO additionally contain its target, such that its attainment can be read off exactly.
+#[derive(Debug)]
+pub struct Metrics {
+    buckets: Vec<Duration>,
+    routes: Mutex<BTreeMap<String, Arc<LatencyHistogram>>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS.to_vec())
+    }
+}
+
+impl Metrics {
+    /// Metrics with `DEFAULT_BUCKETS`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Metrics with histogram buckets bounded by `buckets`, in any order.
+    pub fn with_buckets(mut buckets: Vec<Duration>) -> Self {
+        buckets.sort();
+        buckets.dedup();
+        Self {
+            buckets,
+            routes: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Registers the histogram of route `method` `path` (e.g. `GET /api/monsters/{id}`).
+    ///
+    /// Invoked by generated code when a handler is mounted.
+    pub fn route_histogram(
+        &self,
+        method: &str,
+        path: &str,
+        slo: Option<Slo>,
+    ) -> Arc<LatencyHistogram> {
+        let route = format!("{} {}", method, path);
+        let mut buckets = self.buckets.clone();
+        if let Some(slo) = slo {
+            if let Err(i) = buckets.binary_search(&slo.target) {
+                buckets.insert(i, slo.target);
+            }
+        }
+        let histogram = Arc::new(LatencyHistogram::new(buckets, slo));
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(route, Arc::clone(&histogram));
+        histogram
+    }
+
+    /// The histograms of all routes, keyed by route (e.g. `GET /api/monsters/{id}`).
+    pub fn snapshot(&self) -> BTreeMap<String, HistogramSnapshot> {
+        self.routes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(route, histogram)| (route.clone(), histogram.snapshot()))
+            .collect()
+    }
+
+    /// The metrics of all routes in the Prometheus text format, i.e., the counter
+    /// `humblegen_requests_total` and the histogram `humblegen_request_duration_seconds`,
+    /// labeled by `method` and `route` (e.g. `/api/monsters/{id}`), and by `status` code.
+    pub fn to_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let labels = |route: &str| {
+            let (method, path) = route.split_at(route.find(' ').unwrap_or(0));
+            format!(
+                "method=\"{}\",route=\"{}\"",
+                escape_label_value(method),
+                escape_label_value(path.trim_start())
+            )
+        };
+        let mut out = String::new();
+        out.push_str("# HELP humblegen_requests_total Requests by route and status code.\n");
+        out.push_str("# TYPE humblegen_requests_total counter\n");
+        for (route, histogram) in &snapshot {
+            for (status, count) in &histogram.statuses {
+                let _ = writeln!(
+                    out,
+                    "humblegen_requests_total{{{},status=\"{}\"}} {}",
+                    labels(route),
+                    status,
+                    count
+                );
+            }
+        }
+        out.push_str("# HELP humblegen_request_duration_seconds Latencies of requests by route.\n");
+        out.push_str("# TYPE humblegen_request_duration_seconds histogram\n");
+        for (route, histogram) in &snapshot {
+            let labels = labels(route);
+            for (bound, count) in &histogram.buckets {
+                let _ = writeln!(
+                    out,
+                    "humblegen_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels,
+                    bound.as_secs_f64(),
+                    count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "humblegen_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "humblegen_request_duration_seconds_sum{{{}}} {}",
+                labels,
+                histogram.sum.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "humblegen_request_duration_seconds_count{{{}}} {}",
+                labels, histogram.count
+            );
+        }
+        out
+    }
+}
+
+/// Escapes `value` for a label value of the Prometheus text format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The path the metrics endpoint is mounted at.
+pub const ROOT: &str = "/metrics";
+
+/// A service serving `GET /metrics` with `metrics` in the Prometheus text format.
+///
+/// Invoked by generated code, see `Builder::with_metrics_endpoint`.
+pub fn service(metrics: Arc<Metrics>) -> Service {
+    let router: Box<Router> = Box::new(move |req: Request<Body>, suffix_start: usize| {
+        if req.method() != Method::GET || !req.uri().path()[suffix_start..].is_empty() {
+            return None;
+        }
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, "text/plain; version=0.0.4")
+            .body(Body::from(metrics.to_prometheus()))
+            .expect("metrics responses must always be buildable");
+        Some(("GET /", Box::pin(async move { Ok(response) })))
+    });
+    Service {
+        root: ROOT.to_owned(),
+        route_prefix: String::new(),
+        router,
+        pretty_json: false,
+    }
+}
+
+/// Histogram of the latencies of a route's requests.
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    slo: Option<Slo>,
+    buckets: Vec<Duration>,
+    /// Non-cumulative counts of `buckets`, plus one for latencies above the largest bound.
+    counts: Vec<AtomicU64>,
+    sum_micros: AtomicU64,
+    statuses: Mutex<BTreeMap<u16, u64>>,
+}
+
+impl LatencyHistogram {
+    fn new(buckets: Vec<Duration>, slo: Option<Slo>) -> Self {
+        let counts = (0..=buckets.len()).map(|_| AtomicU64::new(0)).collect();
+        Self {
+            slo,
+            buckets,
+            counts,
+            sum_micros: AtomicU64::new(0),
+            statuses: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn observe(&self, latency: Duration) {
+        let bucket = self
+            .buckets
+            .iter()
+            .position(|bound| latency <= *bound)
+            .unwrap_or(self.buckets.len());
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Counts a response with status code `status`.
+    pub fn observe_status(&self, status: u16) {
+        *self.statuses.lock().unwrap().entry(status).or_insert(0) += 1;
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .zip(&self.counts)
+            .map(|(bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (*bound, cumulative)
+            })
+            .collect();
+        HistogramSnapshot {
+            slo: self.slo,
+            buckets,
+            count: cumulative + self.counts[self.buckets.len()].load(Ordering::Relaxed),
+            sum: Duration::from_micros(self.sum_micros.load(Ordering::Relaxed)),
+            statuses: self.statuses.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Point-in-time copy of a `LatencyHistogram`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSnapshot {
+    pub slo: Option<Slo>,
+    /// Upper bounds of the buckets with the cumulative number of requests at most that slow.
+    pub buckets: Vec<(Duration, u64)>,
+    /// The number of requests.
+    pub count: u64,
+    /// The sum of all latencies.
+    pub sum: Duration,
+    /// The number of responses by status code.
+    pub statuses: BTreeMap<u16, u64>,
+}
+
+impl HistogramSnapshot {
+    /// Whether the route meets its SLO, i.e., the SLO's percentile of requests was served
+    /// within its target. `None` if the route has no SLO or has not been requested.
+    pub fn slo_met(&self) -> Option<bool> {
+        let slo = self.slo?;
+        if self.count == 0 {
+            return None;
+        }
+        let within_target = self
+            .buckets
+            .iter()
+            .find(|(bound, _)| *bound == slo.target)
+            .map(|(_, count)| *count)
+            .expect("SLO target is a bucket bound");
+        Some(within_target as f64 * 100.0 >= slo.percentile * self.count as f64)
+    }
+}
+
+/// Records the latency and the status code of the response of the dispatcher future `f`
+/// of a route in `histogram`, if any.
+///
+/// Invoked from generated code within a `Router`.
+pub fn observe_latency(
+    histogram: Option<Arc<LatencyHistogram>>,
+    f: BoxSyncFuture<Result<Response<Body>, ErrorResponse>>,
+) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> {
+    match histogram {
+        None => f,
+        Some(histogram) => Box::pin(async move {
+            let start = Instant::now();
+            let result = f.await;
+            histogram.observe(start.elapsed());
+            histogram.observe_status(match &result {
+                Ok(response) => response.status().as_u16(),
+                Err(e) => e.code,
+            });
+            result
+        }),
+    }
+}
@@ -0,0 +1,136 @@
+//! `SERVER` Middleware around the requests to generated routes, e.g. for logging, metrics,
+//! tracing or rate limiting, enabled by passing `Middleware` to the generated `Builder`.
+//!
+//! Unlike a wrapper around the hyper service, middleware knows the route that matched a request,
+//! see `RouteInfo`. It runs after routing and the feature flags of the route, and before the
+//! interceptor, the authenticator and the deserialization of the request.
+
+use crate::server::BoxSyncFuture;
+use crate::service_protocol::ErrorResponse;
+use hyper::header::HeaderValue;
+use hyper::{Body, Request, Response};
+use std::fmt;
+use std::sync::Arc;
+
+/// The metadata of a generated route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// The name of the service, e.g. `MonsterApi`.
+    pub service: &'static str,
+    /// The route relative to the root of its service, e.g. `GET /monsters/{id}`.
+    pub route: &'static str,
+    /// The name of the handler trait method of the route, e.g. `get_monsters_id`.
+    pub handler: &'static str,
+}
+
+/// `HANDLER` Runs around the requests to the routes of a server.
+///
+/// Middleware passes the request on to the rest of the chain by `next.run(req)`, and may inspect
+/// or modify the request before and the response after. Returning a response without running
+/// `next` answers the request right away, e.g. with status 429 Too Many Requests.
+#[async_trait_with_sync::async_trait(Sync)]
+pub trait Middleware: Send + Sync {
+    async fn handle(
+        &self,
+        req: Request<Body>,
+        route: &'static RouteInfo,
+        next: Next,
+    ) -> Response<Body>;
+}
+
+impl fmt::Debug for dyn Middleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Middleware")
+    }
+}
+
+/// The header in which `ApiVersion` sends the version of the API.
+pub const API_VERSION_HEADER: &str = "api-version";
+
+/// `GEN` Middleware sending the version of the API, declared in the `meta` block of the spec, in
+/// the `Api-Version` header of all responses. Generated builders run it first if the spec declares
+/// a version.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiVersion(pub &'static str);
+
+#[async_trait_with_sync::async_trait(Sync)]
+impl Middleware for ApiVersion {
+    async fn handle(
+        &self,
+        req: Request<Body>,
+        _route: &'static RouteInfo,
+        next: Next,
+    ) -> Response<Body> {
+        let mut response = next.run(req).await;
+        response
+            .headers_mut()
+            .insert(API_VERSION_HEADER, HeaderValue::from_static(self.0));
+        response
+    }
+}
+
+/// The dispatcher of a route, which deserializes the request and invokes the handler.
+type Endpoint = Box<
+    dyn FnOnce(Request<Body>) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> + Send + Sync,
+>;
+
+/// The rest of the middleware chain of a request, ending in the route's handler.
+pub struct Next {
+    chain: Arc<[Arc<dyn Middleware>]>,
+    position: usize,
+    route: &'static RouteInfo,
+    endpoint: Endpoint,
+}
+
+impl Next {
+    /// Passes `req` on to the next middleware, or to the route's handler at the end of the chain.
+    pub async fn run(self, req: Request<Body>) -> Response<Body> {
+        match self.chain.get(self.position) {
+            Some(middleware) => {
+                let middleware = Arc::clone(middleware);
+                let next = Next {
+                    position: self.position + 1,
+                    ..self
+                };
+                middleware.handle(req, next.route, next).await
+            }
+            None => (self.endpoint)(req).await.unwrap_or_else(|e| {
+                tracing::error!(err = ?e, "handler returned error");
+                e.to_hyper_response()
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for Next {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Next")
+            .field("route", self.route)
+            .field("remaining", &(self.chain.len() - self.position))
+            .finish()
+    }
+}
+
+/// Runs the middleware `chain` around the request `req` to `route`, whose dispatcher is `endpoint`.
+///
+/// Invoked from generated code within a `Router`.
+pub fn run(
+    chain: &Arc<[Arc<dyn Middleware>]>,
+    route: &'static RouteInfo,
+    req: Request<Body>,
+    endpoint: impl FnOnce(Request<Body>) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>>
+        + Send
+        + Sync
+        + 'static,
+) -> BoxSyncFuture<Result<Response<Body>, ErrorResponse>> {
+    if chain.is_empty() {
+        return endpoint(req);
+    }
+    let next = Next {
+        chain: Arc::clone(chain),
+        position: 0,
+        route,
+        endpoint: Box::new(endpoint),
+    };
+    Box::pin(async move { Ok(next.run(req).await) })
+}
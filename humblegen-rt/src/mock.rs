@@ -0,0 +1,152 @@
+//! `GEN` - canned responses of generated mock handlers, used by the `mock-server` artifact.
+
+use crate::auth::Authenticator;
+use crate::handler::{HandlerResponse, ServiceError};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+
+/// The responses of a mocked route.
+///
+/// Requests are answered with the responses queued using `respond_once`, in order,
+/// and with the canned response set using `respond_with` once the queue is empty.
+/// Requests without a response are answered with an internal server error.
+pub struct MockRoute<T> {
+    route: &'static str,
+    state: Mutex<MockRouteState<T>>,
+}
+
+struct MockRouteState<T> {
+    queued: VecDeque<T>,
+    canned: Option<T>,
+    calls: usize,
+}
+
+impl<T: Clone + DeserializeOwned> MockRoute<T> {
+    /// A route without responses. `route` names it in errors. (example: `GET /monsters/{id}`)
+    pub fn new(route: &'static str) -> Self {
+        Self {
+            route,
+            state: Mutex::new(MockRouteState {
+                queued: VecDeque::new(),
+                canned: None,
+                calls: 0,
+            }),
+        }
+    }
+
+    /// Respond to all requests with `response`, once no queued responses are left.
+    pub fn respond_with(&self, response: T) {
+        self.state.lock().unwrap().canned = Some(response);
+    }
+
+    /// Respond to a single request with `response`.
+    pub fn respond_once(&self, response: T) {
+        self.state.lock().unwrap().queued.push_back(response);
+    }
+
+    /// Like `respond_with`, but with a recorded JSON response body.
+    pub fn respond_with_json(&self, json: serde_json::Value) -> Result<(), MockError> {
+        let response = serde_json::from_value(json).map_err(|e| MockError::InvalidRecording {
+            route: self.route.to_owned(),
+            error: e,
+        })?;
+        self.respond_with(response);
+        Ok(())
+    }
+
+    /// The number of requests answered so far.
+    pub fn calls(&self) -> usize {
+        self.state.lock().unwrap().calls
+    }
+
+    /// Used by generated mock handlers to answer a request.
+    pub fn next_response(&self) -> HandlerResponse<T> {
+        let mut state = self.state.lock().unwrap();
+        state.calls += 1;
+        match state.queued.pop_front().or_else(|| state.canned.clone()) {
+            Some(response) => Ok(response),
+            None => Err(ServiceError::Internal(
+                format!("no mock response for route {}", self.route).into(),
+            )),
+        }
+    }
+}
+
+/// Recorded JSON response bodies, keyed by the name of the handler trait method
+/// of their route, e.g. `{"get_monsters_id": {"Ok": {"name": "Godzilla"}}}`.
+/// Responses of routes declaring response headers are a `WithHeaders` object,
+/// e.g. `{"get_files_id": {"value": "...", "headers": {"e_tag": "1"}}}`.
+pub struct Recordings(BTreeMap<String, serde_json::Value>);
+
+impl Recordings {
+    pub fn parse(recordings: &str) -> Result<Self, MockError> {
+        serde_json::from_str(recordings)
+            .map(Recordings)
+            .map_err(MockError::InvalidJson)
+    }
+
+    /// Set the recorded response of `route_fn`, if any, as canned response of `route`.
+    pub fn apply<T: Clone + DeserializeOwned>(
+        &mut self,
+        route_fn: &str,
+        route: &MockRoute<T>,
+    ) -> Result<(), MockError> {
+        match self.0.remove(route_fn) {
+            Some(json) => route.respond_with_json(json),
+            None => Ok(()),
+        }
+    }
+
+    /// Fails if recordings of unknown routes are left after applying all routes.
+    pub fn finish(self) -> Result<(), MockError> {
+        match self.0.into_iter().next() {
+            Some((route_fn, _)) => Err(MockError::UnknownRoute(route_fn)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Errors loading recorded responses.
+#[derive(Debug)]
+pub enum MockError {
+    /// The recordings are no JSON object.
+    InvalidJson(serde_json::Error),
+    /// A recorded response does not match the route's return type.
+    InvalidRecording {
+        route: String,
+        error: serde_json::Error,
+    },
+    /// A recording is keyed by an unknown handler trait method.
+    UnknownRoute(String),
+}
+
+impl fmt::Display for MockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MockError::InvalidJson(e) => write!(f, "invalid recordings: {}", e),
+            MockError::InvalidRecording { route, error } => {
+                write!(f, "invalid recording of route {}: {}", route, error)
+            }
+            MockError::UnknownRoute(route_fn) => {
+                write!(f, "recording of unknown route {}", route_fn)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MockError {}
+
+/// Authenticates all requests, used by mock handlers of services with `@auth` endpoints.
+#[derive(Debug, Default)]
+pub struct AllowAll;
+
+#[async_trait_with_sync::async_trait(Sync)]
+impl Authenticator for AllowAll {
+    type Principal = ();
+
+    async fn authenticate(&self, _headers: &hyper::HeaderMap) -> Result<(), ServiceError> {
+        Ok(())
+    }
+}
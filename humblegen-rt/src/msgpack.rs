@@ -0,0 +1,272 @@
+//! `GEN` - [MessagePack](https://msgpack.org) encoding of the JSON representation of humblespec
+//! types, enabled by the `msgpack` feature.
+//!
+//! Values are transcoded from and to their JSON representation (see
+//! `data_types_json_representation.md`), i.e., structs are maps keyed by field name and enums are
+//! externally tagged. Map keys are always encoded as strings.
+//! When decoding, integer and boolean map keys are accepted as their string representation and
+//! binary data (`bin`) as its base64 encoding, i.e., like a `bytes` field.
+
+use serde_json::{Map, Number, Value};
+use std::convert::TryInto;
+use std::fmt;
+
+/// Nesting limit of decoded values, protecting against stack overflows.
+const MAX_DEPTH: usize = 128;
+
+/// Errors encoding or decoding MessagePack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error(e.to_string())
+    }
+}
+
+/// Encode `value` as MessagePack.
+pub fn to_vec<T: serde::Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    write_value(&mut out, &serde_json::to_value(value)?);
+    Ok(out)
+}
+
+/// Decode a `T` from MessagePack.
+pub fn from_slice<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.read_value(0)?;
+    if reader.pos != bytes.len() {
+        return Err(Error(format!(
+            "trailing bytes after value at offset {}",
+            reader.pos
+        )));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                write_uint(out, u)
+            } else if let Some(i) = n.as_i64() {
+                write_negative_int(out, i)
+            } else {
+                out.push(0xcb);
+                out.extend_from_slice(&n.as_f64().expect("JSON numbers are finite").to_be_bytes());
+            }
+        }
+        Value::String(s) => {
+            write_len(
+                out,
+                s.len(),
+                (0xa0, 32),
+                [Some(0xd9), Some(0xda), Some(0xdb)],
+            );
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(elements) => {
+            write_len(
+                out,
+                elements.len(),
+                (0x90, 16),
+                [None, Some(0xdc), Some(0xdd)],
+            );
+            for element in elements {
+                write_value(out, element);
+            }
+        }
+        Value::Object(fields) => {
+            write_len(
+                out,
+                fields.len(),
+                (0x80, 16),
+                [None, Some(0xde), Some(0xdf)],
+            );
+            for (key, value) in fields {
+                write_value(out, &Value::String(key.clone()));
+                write_value(out, value);
+            }
+        }
+    }
+}
+
+fn write_uint(out: &mut Vec<u8>, u: u64) {
+    if u < 0x80 {
+        out.push(u as u8);
+    } else if u <= u64::from(u8::MAX) {
+        out.extend_from_slice(&[0xcc, u as u8]);
+    } else if u <= u64::from(u16::MAX) {
+        out.push(0xcd);
+        out.extend_from_slice(&(u as u16).to_be_bytes());
+    } else if u <= u64::from(u32::MAX) {
+        out.push(0xce);
+        out.extend_from_slice(&(u as u32).to_be_bytes());
+    } else {
+        out.push(0xcf);
+        out.extend_from_slice(&u.to_be_bytes());
+    }
+}
+
+fn write_negative_int(out: &mut Vec<u8>, i: i64) {
+    if i >= -32 {
+        out.push(i as u8);
+    } else if i >= i64::from(i8::MIN) {
+        out.extend_from_slice(&[0xd0, i as u8]);
+    } else if i >= i64::from(i16::MIN) {
+        out.push(0xd1);
+        out.extend_from_slice(&(i as i16).to_be_bytes());
+    } else if i >= i64::from(i32::MIN) {
+        out.push(0xd2);
+        out.extend_from_slice(&(i as i32).to_be_bytes());
+    } else {
+        out.push(0xd3);
+        out.extend_from_slice(&i.to_be_bytes());
+    }
+}
+
+/// Write the header of a string, array or map of `len` elements: the `fix` marker or'ed with
+/// the length if the length is below the `fix` limit, otherwise the first of the 8, 16 and 32 bit
+/// length `markers` that fits the length.
+fn write_len(out: &mut Vec<u8>, len: usize, fix: (u8, usize), markers: [Option<u8>; 3]) {
+    let (fix_marker, fix_limit) = fix;
+    match markers {
+        _ if len < fix_limit => out.push(fix_marker | len as u8),
+        [Some(marker), _, _] if len <= usize::from(u8::MAX) => {
+            out.extend_from_slice(&[marker, len as u8])
+        }
+        [_, Some(marker), _] if len <= usize::from(u16::MAX) => {
+            out.push(marker);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        [_, _, Some(marker)] => {
+            out.push(marker);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => unreachable!("all headers have a 32 bit length marker"),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() - self.pos < n {
+            return Err(Error("unexpected end of input".to_owned()));
+        }
+        let taken = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+
+    fn read_len(&mut self, bits: u8) -> Result<usize, Error> {
+        Ok(match bits {
+            8 => self.take_array::<1>()?[0].into(),
+            16 => u16::from_be_bytes(self.take_array()?).into(),
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<Value, Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error("values nested too deeply".to_owned()));
+        }
+        let marker = self.take_array::<1>()?[0];
+        Ok(match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => self.read_map(usize::from(marker & 0x0f), depth)?,
+            0x90..=0x9f => self.read_array(usize::from(marker & 0x0f), depth)?,
+            0xa0..=0xbf => self.read_str(usize::from(marker & 0x1f))?,
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4..=0xc6 => {
+                let len = self.read_len(8 << (marker - 0xc4))?;
+                Value::String(base64::encode(self.take(len)?))
+            }
+            0xca => float(f32::from_be_bytes(self.take_array()?).into())?,
+            0xcb => float(f64::from_be_bytes(self.take_array()?))?,
+            0xcc => Value::from(self.take_array::<1>()?[0]),
+            0xcd => Value::from(u16::from_be_bytes(self.take_array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.take_array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.take_array()?)),
+            0xd0 => Value::from(i8::from_be_bytes(self.take_array()?)),
+            0xd1 => Value::from(i16::from_be_bytes(self.take_array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.take_array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.take_array()?)),
+            0xd9..=0xdb => {
+                let len = self.read_len(8 << (marker - 0xd9))?;
+                self.read_str(len)?
+            }
+            0xdc | 0xdd => {
+                let len = self.read_len(16 << (marker - 0xdc))?;
+                self.read_array(len, depth)?
+            }
+            0xde | 0xdf => {
+                let len = self.read_len(16 << (marker - 0xde))?;
+                self.read_map(len, depth)?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            0xc1 | 0xc7..=0xc9 | 0xd4..=0xd8 => {
+                return Err(Error(format!(
+                    "unsupported marker 0x{:02x} at offset {}",
+                    marker,
+                    self.pos - 1
+                )))
+            }
+        })
+    }
+
+    fn read_str(&mut self, len: usize) -> Result<Value, Error> {
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes)
+            .map(|s| Value::String(s.to_owned()))
+            .map_err(|e| Error(format!("invalid string: {}", e)))
+    }
+
+    fn read_array(&mut self, len: usize, depth: usize) -> Result<Value, Error> {
+        // every element takes at least one byte
+        let mut elements = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            elements.push(self.read_value(depth + 1)?);
+        }
+        Ok(Value::Array(elements))
+    }
+
+    fn read_map(&mut self, len: usize, depth: usize) -> Result<Value, Error> {
+        let mut fields = Map::new();
+        for _ in 0..len {
+            let key = match self.read_value(depth + 1)? {
+                Value::String(s) => s,
+                key @ Value::Number(_) | key @ Value::Bool(_) => key.to_string(),
+                key => return Err(Error(format!("unsupported map key {}", key))),
+            };
+            fields.insert(key, self.read_value(depth + 1)?);
+        }
+        Ok(Value::Object(fields))
+    }
+}
+
+fn float(f: f64) -> Result<Value, Error> {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| Error(format!("unsupported non-finite float {}", f)))
+}
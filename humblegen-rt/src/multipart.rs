@@ -0,0 +1,94 @@
+//! `GEN` - extraction of `multipart/form-data` request bodies used by dispatcher.
+
+use crate::service_protocol::ErrorResponse;
+use crate::service_protocol::RuntimeError;
+use crate::service_protocol::ToErrorResponse;
+
+use hyper::{header, Body, Request};
+use std::collections::HashMap;
+
+/// Implemented by generated code for every struct used as a `multipart[...]` request body.
+pub trait FromMultipart: Sized {
+    fn from_multipart(form: MultipartForm) -> Result<Self, ErrorResponse>;
+}
+
+/// The parts of a `multipart/form-data` body, keyed by part name.
+#[derive(Debug, Default)]
+pub struct MultipartForm {
+    parts: HashMap<String, Vec<u8>>,
+}
+
+impl MultipartForm {
+    /// Take the part `name` as UTF-8 text.
+    pub fn text(&mut self, name: &str) -> Result<String, ErrorResponse> {
+        self.opt_text(name)?.ok_or_else(|| missing_part(name))
+    }
+
+    /// Take the part `name` as UTF-8 text, if present.
+    pub fn opt_text(&mut self, name: &str) -> Result<Option<String>, ErrorResponse> {
+        self.opt_file(name)?
+            .map(|bytes| {
+                String::from_utf8(bytes).map_err(|e| {
+                    RuntimeError::PostBodyInvalid(format!("part `{}`: {}", name, e))
+                        .to_error_response()
+                })
+            })
+            .transpose()
+    }
+
+    /// Take the part `name` as raw bytes.
+    pub fn file(&mut self, name: &str) -> Result<Vec<u8>, ErrorResponse> {
+        self.opt_file(name)?.ok_or_else(|| missing_part(name))
+    }
+
+    /// Take the part `name` as raw bytes, if present.
+    pub fn opt_file(&mut self, name: &str) -> Result<Option<Vec<u8>>, ErrorResponse> {
+        Ok(self.parts.remove(name))
+    }
+
+    /// Take the part `name` as a JSON-encoded value.
+    ///
+    /// A missing part is decoded as JSON `null`, so optional fields may be omitted by the client.
+    pub fn json<T: serde::de::DeserializeOwned>(&mut self, name: &str) -> Result<T, ErrorResponse> {
+        let bytes = self.parts.remove(name).unwrap_or_else(|| b"null".to_vec());
+        serde_json::from_slice(&bytes).map_err(|e| {
+            RuntimeError::PostBodyInvalid(format!("part `{}`: {}", name, e)).to_error_response()
+        })
+    }
+}
+
+fn missing_part(name: &str) -> ErrorResponse {
+    RuntimeError::PostBodyInvalid(format!("missing part `{}`", name)).to_error_response()
+}
+
+/// Helper function used by generated code to deserialize a `multipart/form-data` POST body.
+pub async fn deser_multipart<T: FromMultipart>(
+    req: &mut Request<Body>,
+) -> Result<T, ErrorResponse> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let boundary = multer::parse_boundary(content_type)
+        .map_err(|e| RuntimeError::PostBodyInvalid(format!("{}", e)).to_error_response())?;
+
+    let body = std::mem::take(req.body_mut());
+    let mut multipart = multer::Multipart::new(body, boundary);
+
+    let mut form = MultipartForm::default();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| RuntimeError::PostBodyReadError(format!("{}", e)).to_error_response())?
+    {
+        let name = field.name().unwrap_or_default().to_owned();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| RuntimeError::PostBodyReadError(format!("{}", e)).to_error_response())?;
+        form.parts.insert(name, bytes.to_vec());
+    }
+
+    T::from_multipart(form)
+}
@@ -0,0 +1,357 @@
+//! `SERVER` Recording of requests and their responses, enabled by passing a `Recorder` to the
+//! generated `Builder`, and their replay, e.g. to turn production traffic into regression tests.
+//!
+//! A recording is a JSON Lines file with one `Exchange` per line. `replay` sends the recorded
+//! requests to services in memory and compares their responses with the recorded responses:
+//! ```text
+//! let services = Arc::new(Builder::new().add("/api", handler).into_services());
+//! let exchanges = recording::read_recording("monsters.jsonl")?;
+//! let mismatches = recording::replay(services, &exchanges).await?;
+//! assert!(mismatches.is_empty(), "{:#?}", mismatches);
+//! ```
+
+use crate::server::{self, MatchedRoute, Service};
+use crate::service_protocol::{RuntimeError, ToErrorResponse};
+use chrono::{DateTime, Utc};
+use hyper::header::{self, HeaderMap, HeaderName};
+use hyper::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// The value recorded in place of redacted headers.
+pub const REDACTED: &str = "<redacted>";
+
+/// A request and its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    /// When the request was received.
+    pub timestamp: DateTime<Utc>,
+    /// The route that answered the request, see `server::MatchedRoute`. `None` if no route
+    /// matched.
+    pub route: Option<String>,
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The path and query of the request, e.g. `/api/monsters?name=dragon`.
+    pub uri: String,
+    pub headers: Vec<(String, String)>,
+    #[serde(with = "recorded_body")]
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    #[serde(with = "recorded_body")]
+    pub body: Vec<u8>,
+}
+
+/// Bodies are recorded as `{"text": "..."}` if they are UTF-8, and as `{"base64": "..."}` otherwise.
+mod recorded_body {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum RecordedBody {
+        Text(String),
+        Base64(String),
+    }
+
+    pub fn serialize<S: Serializer>(body: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(body) {
+            Ok(text) => RecordedBody::Text(text.to_owned()),
+            Err(_) => RecordedBody::Base64(base64::encode(body)),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        match RecordedBody::deserialize(deserializer)? {
+            RecordedBody::Text(text) => Ok(text.into_bytes()),
+            RecordedBody::Base64(base64) => {
+                base64::decode(&base64).map_err(serde::de::Error::custom)
+            }
+        }
+    }
+}
+
+/// Records requests and their responses, appending them to a recording as they are answered.
+///
+/// Bodies are buffered in memory to be recorded. The values of the `Authorization`, `Cookie`
+/// and `Set-Cookie` headers are redacted by default. Failures to write the recording are
+/// logged and do not affect the response.
+pub struct Recorder {
+    out: Mutex<Box<dyn Write + Send>>,
+    redacted_headers: Vec<HeaderName>,
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("redacted_headers", &self.redacted_headers)
+            .finish()
+    }
+}
+
+impl Recorder {
+    /// A recorder writing the recording to `out`.
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        Self {
+            out: Mutex::new(Box::new(out)),
+            redacted_headers: vec![header::AUTHORIZATION, header::COOKIE, header::SET_COOKIE],
+        }
+    }
+
+    /// A recorder appending to the recording at `path`, which is created if it does not exist.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(file))
+    }
+
+    /// Records the values of header `name` of requests and responses as `REDACTED`.
+    pub fn with_redacted_header(mut self, name: HeaderName) -> Self {
+        self.redacted_headers.push(name);
+        self
+    }
+
+    /// Answers `req` using `server::handle_request` and records it with its response.
+    pub async fn handle_request(
+        &self,
+        services: Arc<Vec<Service>>,
+        req: Request<Body>,
+    ) -> Response<Body> {
+        let timestamp = Utc::now();
+        let (parts, body) = req.into_parts();
+        let body = match hyper::body::to_bytes(body).await {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!(error = ?e, "cannot read request body for recording");
+                return RuntimeError::PostBodyReadError(e.to_string())
+                    .to_error_response()
+                    .to_hyper_response();
+            }
+        };
+        let request = RecordedRequest {
+            method: parts.method.to_string(),
+            uri: parts.uri.to_string(),
+            headers: self.recorded_headers(&parts.headers),
+            body: body.to_vec(),
+        };
+
+        let response =
+            server::handle_request(services, Request::from_parts(parts, Body::from(body))).await;
+        let (parts, body) = response.into_parts();
+        let body = match hyper::body::to_bytes(body).await {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!(error = ?e, "cannot read response body for recording");
+                return RuntimeError::SerializeHandlerResponse(e.to_string())
+                    .to_error_response()
+                    .to_hyper_response();
+            }
+        };
+
+        self.record(&Exchange {
+            timestamp,
+            route: parts
+                .extensions
+                .get::<MatchedRoute>()
+                .map(|route| route.0.to_owned()),
+            request,
+            response: RecordedResponse {
+                status: parts.status.as_u16(),
+                headers: self.recorded_headers(&parts.headers),
+                body: body.to_vec(),
+            },
+        });
+        Response::from_parts(parts, Body::from(body))
+    }
+
+    fn recorded_headers(&self, headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.redacted_headers.contains(name) {
+                    REDACTED.to_owned()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.as_str().to_owned(), value)
+            })
+            .collect()
+    }
+
+    fn record(&self, exchange: &Exchange) {
+        let line = serde_json::to_string(exchange).expect("exchanges are serializable");
+        let mut out = self.out.lock().unwrap();
+        if let Err(e) = writeln!(out, "{}", line).and_then(|()| out.flush()) {
+            tracing::error!(error = ?e, "cannot write recording");
+        }
+    }
+}
+
+/// Reads the exchanges of the recording at `path`.
+pub fn read_recording<P: AsRef<Path>>(path: P) -> Result<Vec<Exchange>, RecordingError> {
+    let recording = std::fs::read_to_string(path).map_err(RecordingError::Io)?;
+    parse_recording(&recording)
+}
+
+/// Parses the exchanges of a recording, skipping empty lines.
+pub fn parse_recording(recording: &str) -> Result<Vec<Exchange>, RecordingError> {
+    recording
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|error| RecordingError::InvalidJson { line: i + 1, error })
+        })
+        .collect()
+}
+
+/// A difference between a recorded response and the response to its replayed request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// The index of the exchange in the recording.
+    pub index: usize,
+    /// The method and URI of the request, e.g. `GET /api/monsters/3`.
+    pub request: String,
+    /// What differs, i.e. `status`, `header <name>` or `body`.
+    pub part: String,
+    pub recorded: String,
+    pub replayed: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "exchange {} ({}): {} was `{}`, replayed `{}`",
+            self.index, self.request, self.part, self.recorded, self.replayed
+        )
+    }
+}
+
+/// Sends the recorded requests of `exchanges` in order to `services` using
+/// `server::handle_request`, and compares the responses with the recorded responses.
+///
+/// Responses match if their status, their recorded headers and their bodies are equal, where
+/// JSON bodies are compared as JSON values. The random `Request-ID` header and redacted headers
+/// are not compared.
+pub async fn replay(
+    services: Arc<Vec<Service>>,
+    exchanges: &[Exchange],
+) -> Result<Vec<Mismatch>, RecordingError> {
+    let mut mismatches = vec![];
+    for (index, exchange) in exchanges.iter().enumerate() {
+        let recorded = &exchange.request;
+        let mut req = Request::builder()
+            .method(recorded.method.as_str())
+            .uri(recorded.uri.as_str());
+        for (name, value) in &recorded.headers {
+            req = req.header(name.as_str(), value.as_str());
+        }
+        let req = req.body(Body::from(recorded.body.clone())).map_err(|e| {
+            RecordingError::InvalidRequest {
+                index,
+                error: e.to_string(),
+            }
+        })?;
+
+        let response = server::handle_request(Arc::clone(&services), req).await;
+        let (parts, body) = response.into_parts();
+        let body =
+            hyper::body::to_bytes(body)
+                .await
+                .map_err(|e| RecordingError::InvalidRequest {
+                    index,
+                    error: e.to_string(),
+                })?;
+
+        let mut mismatch = |part: String, recorded: String, replayed: String| {
+            mismatches.push(Mismatch {
+                index,
+                request: format!("{} {}", exchange.request.method, exchange.request.uri),
+                part,
+                recorded,
+                replayed,
+            })
+        };
+        let expected = &exchange.response;
+        if parts.status.as_u16() != expected.status {
+            mismatch(
+                "status".to_owned(),
+                expected.status.to_string(),
+                parts.status.as_u16().to_string(),
+            );
+        }
+        for (name, value) in &expected.headers {
+            if name.eq_ignore_ascii_case("Request-ID") || value == REDACTED {
+                continue;
+            }
+            let replayed = parts
+                .headers
+                .get(name.as_str())
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .unwrap_or_default();
+            if *value != replayed {
+                mismatch(format!("header {}", name), value.clone(), replayed);
+            }
+        }
+        if !bodies_match(&expected.body, &body) {
+            mismatch(
+                "body".to_owned(),
+                String::from_utf8_lossy(&expected.body).into_owned(),
+                String::from_utf8_lossy(&body).into_owned(),
+            );
+        }
+    }
+    Ok(mismatches)
+}
+
+fn bodies_match(recorded: &[u8], replayed: &[u8]) -> bool {
+    let json = |body| serde_json::from_slice::<serde_json::Value>(body).ok();
+    match (json(recorded), json(replayed)) {
+        (Some(recorded), Some(replayed)) => recorded == replayed,
+        _ => recorded == replayed,
+    }
+}
+
+/// Errors reading or replaying recordings.
+#[derive(Debug)]
+pub enum RecordingError {
+    /// The recording cannot be read.
+    Io(io::Error),
+    /// A line of the recording is no exchange.
+    InvalidJson {
+        line: usize,
+        error: serde_json::Error,
+    },
+    /// The recorded request of an exchange cannot be replayed.
+    InvalidRequest { index: usize, error: String },
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(e) => write!(f, "cannot read recording: {}", e),
+            RecordingError::InvalidJson { line, error } => {
+                write!(f, "invalid exchange in line {}: {}", line, error)
+            }
+            RecordingError::InvalidRequest { index, error } => {
+                write!(f, "cannot replay exchange {}: {}", index, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
@@ -0,0 +1,81 @@
+//! `GEN` - headers of responses, declared by routes like `GET /files/{id: str} -> FileMeta with header "ETag": str`.
+
+use crate::serialization_helpers::ToParam;
+use hyper::header::{HeaderMap, HeaderName, HeaderValue};
+use std::fmt;
+
+/// Implemented by the generated structs of the response headers of a route.
+///
+/// The server writes them to the responses of the route, the client reads them from the responses.
+pub trait ResponseHeaders: Sized {
+    fn write(&self, headers: &mut HeaderMap) -> Result<(), InvalidHeader>;
+    fn read(headers: &HeaderMap) -> Result<Self, InvalidHeader>;
+}
+
+/// A response header that is missing, or whose value cannot be written or read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidHeader {
+    pub name: &'static str,
+    pub error: String,
+}
+
+impl fmt::Display for InvalidHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid response header {}: {}", self.name, self.error)
+    }
+}
+
+impl std::error::Error for InvalidHeader {}
+
+/// Helper function used by generated code to write header `name` with `value`.
+pub fn write_header<T: ToParam>(
+    headers: &mut HeaderMap,
+    name: &'static str,
+    value: &T,
+) -> Result<(), InvalidHeader> {
+    let invalid = |error: String| InvalidHeader { name, error };
+    let value = HeaderValue::from_str(&value.to_param()).map_err(|e| invalid(e.to_string()))?;
+    let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid(e.to_string()))?;
+    headers.insert(name, value);
+    Ok(())
+}
+
+/// Helper function used by generated code to write header `name` if `value` is present.
+pub fn write_opt_header<T: ToParam>(
+    headers: &mut HeaderMap,
+    name: &'static str,
+    value: &Option<T>,
+) -> Result<(), InvalidHeader> {
+    match value {
+        Some(value) => write_header(headers, name, value),
+        None => Ok(()),
+    }
+}
+
+/// Helper function used by generated code to read the required header `name`.
+pub fn read_header<E: fmt::Display, T: std::str::FromStr<Err = E>>(
+    headers: &HeaderMap,
+    name: &'static str,
+) -> Result<T, InvalidHeader> {
+    read_opt_header(headers, name)?.ok_or_else(|| InvalidHeader {
+        name,
+        error: "missing".to_owned(),
+    })
+}
+
+/// Helper function used by generated code to read the optional header `name`.
+pub fn read_opt_header<E: fmt::Display, T: std::str::FromStr<Err = E>>(
+    headers: &HeaderMap,
+    name: &'static str,
+) -> Result<Option<T>, InvalidHeader> {
+    let invalid = |error: String| InvalidHeader { name, error };
+    match headers.get(name) {
+        None => Ok(None),
+        Some(value) => {
+            let value = value.to_str().map_err(|e| invalid(e.to_string()))?;
+            std::primitive::str::parse(value)
+                .map(Some)
+                .map_err(|e: E| invalid(e.to_string()))
+        }
+    }
+}
@@ -6,18 +6,24 @@ use crate::service_protocol::ToErrorResponse;
 
 use serde::{Deserializer, Serializer};
 
+/// Helper function used by generated code to parse the percent-encoded path segment `value`
+/// of the route param `name`.
 pub fn deser_param<T, E>(name: &str, value: &str) -> Result<T, ErrorResponse>
 where
     E: std::fmt::Display,
     T: std::str::FromStr<Err = E>,
 {
-    std::primitive::str::parse(value).map_err(|e| {
+    let invalid = |parse_error| {
         RuntimeError::RouteParamInvalid {
             param_name: name.to_owned(),
-            parse_error: format!("{}", e),
+            parse_error,
         }
         .to_error_response()
-    })
+    };
+    let value = percent_encoding::percent_decode_str(value)
+        .decode_utf8()
+        .map_err(|e| invalid(format!("{}", e)))?;
+    std::primitive::str::parse(&value).map_err(|e| invalid(format!("{}", e)))
 }
 
 /// Helper function used by generated code to deserialize POST body data.
@@ -52,6 +58,45 @@ pub fn deser_query_primitive<E: std::fmt::Display, T: std::str::FromStr<Err = E>
         .map_err(|e| RuntimeError::QueryInvalid(format!("{}", e)).to_error_response())
 }
 
+/// Helper function used by generated code to deserialize a required header parameter.
+pub fn deser_header<E: std::fmt::Display, T: std::str::FromStr<Err = E>>(
+    headers: &hyper::HeaderMap,
+    name: &str,
+) -> Result<T, ErrorResponse> {
+    deser_opt_header(headers, name)?.ok_or_else(|| {
+        RuntimeError::HeaderInvalid {
+            header_name: name.to_owned(),
+            parse_error: "missing".to_owned(),
+        }
+        .to_error_response()
+    })
+}
+
+/// Helper function used by generated code to deserialize an optional header parameter.
+pub fn deser_opt_header<E: std::fmt::Display, T: std::str::FromStr<Err = E>>(
+    headers: &hyper::HeaderMap,
+    name: &str,
+) -> Result<Option<T>, ErrorResponse> {
+    let header_invalid = |parse_error: String| {
+        RuntimeError::HeaderInvalid {
+            header_name: name.to_owned(),
+            parse_error,
+        }
+        .to_error_response()
+    };
+    match headers.get(name) {
+        None => Ok(None),
+        Some(value) => {
+            let value = value
+                .to_str()
+                .map_err(|e| header_invalid(format!("{}", e)))?;
+            std::primitive::str::parse(value)
+                .map(Some)
+                .map_err(|e| header_invalid(format!("{}", e)))
+        }
+    }
+}
+
 /// Helper function used by generate code to deserialize a humblegen `bytes` field.
 pub fn deser_bytes<'de, D>(input: D) -> Result<Vec<u8>, D::Error>
 where
@@ -86,3 +131,158 @@ where
 {
     serializer.serialize_str(&base64::encode(v))
 }
+
+/// Helper function used by generated code to deserialize `optional` fields and fields renamed
+/// with `@renamed_from`, which are `#[serde(default)]` in order to accept absent keys.
+///
+/// A present key deserializes to `Some`, which tells absent keys (`None`) from `null` values
+/// of `option[T]` fields (`Some(None)`).
+pub fn deser_present<'de, D, T>(input: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    T::deserialize(input).map(Some)
+}
+
+/// Like `deser_present`, for `bytes` fields.
+pub fn deser_present_bytes<'de, D>(input: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deser_bytes(input).map(Some)
+}
+
+/// Helper function used by generated code to serialize an `optional` `bytes` field, which is
+/// skipped if `None`.
+pub fn ser_present_bytes<S>(v: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match v {
+        Some(v) => ser_bytes(v, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// The variants of an enum annotated with `@non_exhaustive`, see `UnknownVariant`.
+///
+/// Implemented by generated code.
+pub trait KnownVariants {
+    /// The key of the variant name of internally and adjacently tagged enums, `None` for
+    /// externally tagged ones.
+    const TAG: Option<&'static str>;
+    /// The names of the variants on the wire.
+    const NAMES: &'static [&'static str];
+}
+
+/// A value of an enum annotated with `@non_exhaustive` whose variant is not among the
+/// `KnownVariants` of `V`, e.g. one added by a newer version of the spec.
+///
+/// Used by generated code to deserialize unknown variants, while values of known variants that
+/// fail to deserialize remain errors.
+pub struct UnknownVariant<V>(std::marker::PhantomData<V>);
+
+impl<'de, V: KnownVariants> serde::Deserialize<'de> for UnknownVariant<V> {
+    fn deserialize<D>(input: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct UnknownVariantVisitor<V>(std::marker::PhantomData<V>);
+
+        impl<V> UnknownVariantVisitor<V>
+        where
+            V: KnownVariants,
+        {
+            fn unknown<E: serde::de::Error>(name: &str) -> Result<UnknownVariant<V>, E> {
+                if V::NAMES.contains(&name) {
+                    return Err(E::custom(format!("invalid value of variant `{}`", name)));
+                }
+                Ok(UnknownVariant(std::marker::PhantomData))
+            }
+        }
+
+        impl<'de, V: KnownVariants> serde::de::Visitor<'de> for UnknownVariantVisitor<V> {
+            type Value = UnknownVariant<V>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match V::TAG {
+                    Some(tag) => write!(formatter, "an object with a `{}` key", tag),
+                    None => write!(formatter, "a variant name or an object with a single key"),
+                }
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match V::TAG {
+                    Some(_) => Err(E::invalid_type(serde::de::Unexpected::Str(v), &self)),
+                    None => Self::unknown(v),
+                }
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut name = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match V::TAG {
+                        Some(tag) if key == tag => name = Some(map.next_value::<String>()?),
+                        None if name.is_none() => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                            name = Some(key);
+                        }
+                        _ => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                match name {
+                    Some(name) => Self::unknown(&name),
+                    None => Err(serde::de::Error::invalid_length(0, &self)),
+                }
+            }
+        }
+
+        input.deserialize_any(UnknownVariantVisitor(std::marker::PhantomData))
+    }
+}
+
+/// Formats route params, primitive queries and header values such that the server
+/// can parse them with `FromStr` (see `deser_param` and `deser_header`).
+pub trait ToParam {
+    fn to_param(&self) -> String;
+}
+
+macro_rules! impl_to_param_via_display {
+    ($($t:ty),*) => {
+        $(
+            impl ToParam for $t {
+                fn to_param(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_to_param_via_display!(
+    String,
+    i32,
+    u32,
+    i64,
+    u64,
+    u8,
+    f64,
+    bool,
+    chrono::NaiveDate,
+    uuid::Uuid
+);
+
+impl ToParam for chrono::DateTime<chrono::Utc> {
+    fn to_param(&self) -> String {
+        self.to_rfc3339()
+    }
+}
@@ -1,13 +1,16 @@
 //! `GEN` Generic parts of the humblegen HTTP service server implementation, based on [`hyper`](https://hyper.rs).
 
-use crate::handler::HandlerResponse;
-use crate::regexset_map;
-use crate::regexset_map::RegexSetMap;
+use crate::handler::{HandlerResponse, WithHeaders};
+use crate::recording::Recorder;
+use crate::response_headers::ResponseHeaders;
 use crate::service_protocol::{self, RuntimeError, ToErrorResponse};
+use crate::shutdown::{self, ShutdownHandle};
 use derivative::Derivative;
 use tracing_futures::Instrument;
 
 use anyhow::Context;
+use hyper::server::accept::Accept;
+use hyper::server::conn::AddrIncoming;
 use hyper::Body;
 use hyper::Request;
 use hyper::Response;
@@ -15,28 +18,60 @@ use hyper::Response;
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
 
 use rand::Rng;
 
 /// Serve `services` via HTTP, binding to the given `addr`.
-/// Invokes `handle_request`.
+/// Invokes `handle_request`, and records all requests and their responses in `recorder`, if any.
+/// Returns once the requests in flight completed after the shutdown of `shutdown`, which stops
+/// accepting connections `drain_delay` after it started.
 ///
 /// Invoked by generated code.
 pub async fn listen_and_run_forever(
-    services: RegexSetMap<Request<Body>, Service>,
+    services: Vec<Service>,
     addr: &SocketAddr,
+    recorder: Option<Arc<Recorder>>,
+    shutdown: ShutdownHandle,
+    drain_delay: Duration,
 ) -> anyhow::Result<()> {
+    let incoming = AddrIncoming::bind(addr).context("cannot bind")?;
+    serve(incoming, services, recorder, shutdown, drain_delay).await
+}
+
+/// Like `listen_and_run_forever`, but serves the connections accepted by `incoming`,
+/// e.g. the TLS connections of a `humblegen_rt::tls::TlsIncoming`.
+///
+/// Invoked by generated code.
+pub async fn serve<I>(
+    incoming: I,
+    services: Vec<Service>,
+    recorder: Option<Arc<Recorder>>,
+    shutdown: ShutdownHandle,
+    drain_delay: Duration,
+) -> anyhow::Result<()>
+where
+    I: Accept,
+    I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
     // Note: this is the standard (noisy) dance for handling hyper requests.
     let services = Arc::new(services);
-    let server = hyper::Server::bind(addr).serve(hyper::service::make_service_fn(
-        move |_sock: &hyper::server::conn::AddrStream| {
+    let server = hyper::Server::builder(incoming).serve(hyper::service::make_service_fn(
+        move |_conn: &I::Conn| {
             let services = Arc::clone(&services);
+            let recorder = recorder.clone();
             async move {
                 Ok::<_, Infallible>(hyper::service::service_fn(
                     move |req: hyper::Request<hyper::Body>| {
                         let services = Arc::clone(&services);
+                        let recorder = recorder.clone();
                         async move {
-                            let resp = handle_request(services, req).await;
+                            let resp = match recorder {
+                                Some(recorder) => recorder.handle_request(services, req).await,
+                                None => handle_request(services, req).await,
+                            };
                             Ok::<Response<hyper::Body>, Infallible>(resp)
                         }
                     },
@@ -45,18 +80,18 @@ pub async fn listen_and_run_forever(
         },
     ));
 
-    server.await.context("server error")?;
+    server
+        .with_graceful_shutdown(shutdown::stop_accepting(shutdown, drain_delay))
+        .await
+        .context("server error")?;
     Ok(())
 }
 
-const REQUEST_ID_HEADER_NAME: &'static str = "Request-ID";
+const REQUEST_ID_HEADER_NAME: &str = "Request-ID";
 
 /// The routine that maps an incoming hyper request to a service in `services`,
 /// and invokes the service's dispatcher.
-pub async fn handle_request(
-    services: Arc<RegexSetMap<Request<Body>, Service>>,
-    req: Request<Body>,
-) -> Response<Body> {
+pub async fn handle_request(services: Arc<Vec<Service>>, req: Request<Body>) -> Response<Body> {
     let request_id: String = rand::thread_rng()
         .sample_iter(&rand::distributions::Alphanumeric)
         .take(30)
@@ -68,44 +103,39 @@ pub async fn handle_request(
 }
 
 pub async fn handle_request_impl(
-    services: Arc<RegexSetMap<Request<Body>, Service>>,
+    services: Arc<Vec<Service>>,
     req: Request<Body>,
     request_id: String,
 ) -> Response<Body> {
-    let path = req.uri().path().to_string(); // necessary because we need to move req into dispatcher, but also need to move captures into dispatcher
+    let (service, ambiguous) = {
+        let path = req.uri().path();
+        let mut matching_services = services.iter().filter(|s| s.matches(path));
+        (matching_services.next(), matching_services.next().is_some())
+    };
+    let pretty_json =
+        service.is_some_and(|s| s.pretty_json) || query_requests_pretty_json(req.uri());
 
-    let mut response = match services.get(&path, &req) {
-        regexset_map::GetResult::None => RuntimeError::NoServiceMounted
+    let mut response = match service {
+        None => RuntimeError::NoServiceMounted
             .to_error_response()
             .to_hyper_response(),
-        regexset_map::GetResult::Ambiguous => RuntimeError::ServiceMountsAmbiguous
+        Some(_) if ambiguous => RuntimeError::ServiceMountsAmbiguous
             .to_error_response()
             .to_hyper_response(),
-        regexset_map::GetResult::One(service) => {
-            tracing::debug!(service_regex = (service.0).0.as_str(), "service matched");
-            let tuple = &service.0;
-            let service_regex_captures = tuple.0.captures(&path).unwrap();
-            let service = service_regex_captures["root"].to_string();
-            let suffix = &service_regex_captures["suffix"];
-            match tuple.1.get(&suffix, &req) {
-                regexset_map::GetResult::None => RuntimeError::NoRouteMountedInService { service }
-                    .to_error_response()
-                    .to_hyper_response(),
-                regexset_map::GetResult::Ambiguous => {
-                    RuntimeError::RouteMountsAmbiguous { service }
-                        .to_error_response()
-                        .to_hyper_response()
+        Some(service) => {
+            tracing::debug!(service_root = service.root.as_str(), "service matched");
+            match (service.router)(req, service.root.len()) {
+                None => RuntimeError::NoRouteMountedInService {
+                    service: service.root.clone(),
                 }
-                regexset_map::GetResult::One(route) => {
-                    tracing::debug!(route_regex = route.regex.as_str(), "route matched");
-                    let captures = route.regex.captures(suffix).unwrap();
-                    let dispatcher = &route.dispatcher;
-
+                .to_error_response()
+                .to_hyper_response(),
+                Some((route, dispatcher_future)) => {
                     let dispatcher_result = {
                         let dispatcher_span = tracing::error_span!("invoke_dispatcher");
-                        dispatcher(req, captures).instrument(dispatcher_span).await
+                        dispatcher_future.instrument(dispatcher_span).await
                     };
-                    match dispatcher_result {
+                    let mut response = match dispatcher_result {
                         Ok(r) => {
                             tracing::debug!("handler returned Ok");
                             r
@@ -114,7 +144,9 @@ pub async fn handle_request_impl(
                             tracing::error!(err = ?e, "handler returned error");
                             e.to_hyper_response()
                         }
-                    }
+                    };
+                    response.extensions_mut().insert(MatchedRoute(route));
+                    response
                 }
             }
         }
@@ -126,82 +158,337 @@ pub async fn handle_request_impl(
             .expect("request ID is expected to be valid header value"),
     );
 
-    response.headers_mut().insert(
-        hyper::header::CONTENT_TYPE,
-        hyper::header::HeaderValue::from_static("application/json"),
-    );
+    // responses are JSON, unless the router says otherwise or they have no content,
+    // compressed responses are not pretty-printed
+    let compressed = response
+        .headers()
+        .contains_key(hyper::header::CONTENT_ENCODING);
+    if response.status() != hyper::StatusCode::NO_CONTENT {
+        let content_type = response
+            .headers_mut()
+            .entry(hyper::header::CONTENT_TYPE)
+            .or_insert(hyper::header::HeaderValue::from_static("application/json"));
+        if pretty_json && content_type == "application/json" && !compressed {
+            response = pretty_print_json_response(response).await;
+        }
+    }
 
     tracing::debug!(http_status = ?response.status(), "finished request");
 
     response
 }
 
-/// A service is a collection of Routes that share a common `prefix`.
+/// Whether the query of `uri` asks for pretty-printed JSON, i.e., contains `pretty`, `pretty=1` or `pretty=true`.
+fn query_requests_pretty_json(uri: &hyper::Uri) -> bool {
+    uri.query().is_some_and(|query| {
+        query
+            .split('&')
+            .any(|param| matches!(param, "pretty" | "pretty=1" | "pretty=true"))
+    })
+}
+
+/// Replaces the JSON body of `response` with its pretty-printed equivalent.
+async fn pretty_print_json_response(response: Response<Body>) -> Response<Body> {
+    let (parts, body) = response.into_parts();
+    match hyper::body::to_bytes(body).await {
+        Ok(body) => Response::from_parts(parts, Body::from(pretty_print_json(&body))),
+        Err(e) => {
+            tracing::error!(error = ?e, "cannot read response body for pretty-printing");
+            RuntimeError::SerializeHandlerResponse(e.to_string())
+                .to_error_response()
+                .to_hyper_response()
+        }
+    }
+}
+
+/// Indents the JSON document `json` like `serde_json::to_string_pretty`.
+///
+/// Works on the serialized text instead of a `serde_json::Value`, which preserves the order of
+/// object fields.
+fn pretty_print_json(json: &[u8]) -> Vec<u8> {
+    fn newline(out: &mut Vec<u8>, indent: usize) {
+        out.push(b'\n');
+        out.extend(std::iter::repeat_n(b' ', 2 * indent));
+    }
+
+    let mut out = Vec::with_capacity(2 * json.len());
+    let mut indent = 0;
+    let (mut in_string, mut escaped) = (false, false);
+    let mut bytes = json.iter().copied().peekable();
+    while let Some(b) = bytes.next() {
+        if in_string {
+            out.push(b);
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => {
+                in_string = true;
+                out.push(b);
+            }
+            b'{' | b'[' => {
+                out.push(b);
+                match bytes.peek() {
+                    // empty objects and arrays stay on one line
+                    Some(b'}') | Some(b']') => out.push(bytes.next().unwrap()),
+                    _ => {
+                        indent += 1;
+                        newline(&mut out, indent);
+                    }
+                }
+            }
+            b'}' | b']' => {
+                indent = indent.saturating_sub(1);
+                newline(&mut out, indent);
+                out.push(b);
+            }
+            b',' => {
+                out.push(b);
+                newline(&mut out, indent);
+            }
+            b':' => out.extend_from_slice(b": "),
+            b' ' | b'\t' | b'\n' | b'\r' => {}
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
+/// A service is a collection of routes that share a common `root` path prefix.
+///
+/// Services may share a `root` if their routes start with distinct literal segments, e.g. the
+/// versions `MonsterApiV1` and `MonsterApiV2` of a service, whose routes start with `/v1` and
+/// `/v2`, respectively.
 ///
 /// Instantiated by generated code.
-#[derive(Debug)]
-pub struct Service(pub (regex::Regex, RegexSetMap<Request<Body>, Route>));
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Service {
+    pub root: String,
+    /// The literal segments that all routes of the service start with, e.g. `/v2`, or empty.
+    pub route_prefix: String,
+    #[derivative(Debug = "ignore")]
+    pub router: Box<Router>,
+    /// Whether all responses of this service are pretty-printed JSON, regardless of the `pretty` query parameter.
+    pub pretty_json: bool,
+}
+
+impl Service {
+    /// Whether `path` points into this service, i.e., is `root` and `route_prefix` itself or
+    /// followed by a `/`.
+    fn matches(&self, path: &str) -> bool {
+        let suffix = path
+            .strip_prefix(self.root.as_str())
+            .and_then(|path| path.strip_prefix(self.route_prefix.as_str()));
+        matches!(suffix, Some(suffix) if suffix.is_empty() || suffix.starts_with('/'))
+    }
+}
 
-// helper type that avoids bloating the type signature of `DispatcherClosure`.
-type BoxSyncFuture<Output> =
+// helper type that avoids bloating the type signature of `Router`.
+pub(crate) type BoxSyncFuture<Output> =
     std::pin::Pin<Box<dyn Send + Sync + std::future::Future<Output = Output>>>;
 
 /// Closure with an internal reference to the handler trait object that implements a humblegen service trait.
-/// It decodes request into the arguments required to invoke the trait function and then does the call.
-type DispatcherClosure = dyn Fn(
+/// It matches the method and the path of a request, starting at the given byte offset (behind the service root),
+/// against the service's routes without allocating.
+/// If a route matches, it returns the route's name and a future that decodes the request into the arguments
+/// required to invoke the trait function and then does the call.
+pub type Router = dyn Fn(
         Request<Body>,
-        regex::Captures,
-    ) -> BoxSyncFuture<Result<Response<Body>, service_protocol::ErrorResponse>>
-    + Send
+        usize,
+    ) -> Option<(
+        &'static str,
+        BoxSyncFuture<Result<Response<Body>, service_protocol::ErrorResponse>>,
+    )> + Send
     + Sync;
 
-/// A route associates an HTTP method + URL path regex with a `DispatcherClosure`.
-/// It implements `regexset_map::Entry` and only makes sense within a `Service`.
+/// The name of the route that answered a request relative to its service's root,
+/// e.g. `GET /monsters/{id}`, stored in the extensions of the response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchedRoute(pub &'static str);
+
+/// Runs the dispatcher future `f` of a route within `span`, recording the status code of its
+/// response in the span's `status` field.
 ///
-/// Instantiated by generated code.
-#[derive(Derivative)]
-#[derivative(Debug)]
-pub struct Route {
-    pub method: hyper::Method,
-    pub regex: regex::Regex,
-    #[derivative(Debug = "ignore")]
-    pub dispatcher: Box<DispatcherClosure>,
+/// Invoked from generated code within a `Router`, unless generated without tracing spans.
+pub fn trace_route(
+    span: tracing::Span,
+    f: BoxSyncFuture<Result<Response<Body>, service_protocol::ErrorResponse>>,
+) -> BoxSyncFuture<Result<Response<Body>, service_protocol::ErrorResponse>> {
+    Box::pin(async move {
+        let result = f.instrument(span.clone()).await;
+        let status = match &result {
+            Ok(response) => response.status().as_u16(),
+            Err(e) => e.code,
+        };
+        span.record("status", &status);
+        result
+    })
 }
 
-impl<'a> regexset_map::Entry<Request<Body>> for Route {
-    fn regex(&self) -> &regex::Regex {
-        &self.regex
-    }
-    fn matches_input(&self, req: &Request<Body>) -> bool {
-        self.method == req.method()
+/// Splits a service-relative `path` like `/monsters/3` into its segments `["monsters", "3"]`,
+/// stored in `segments`. Returns the number of segments.
+///
+/// Returns `None` if `path` does not start with a `/`, contains empty segments, or has more segments
+/// than fit into `segments`, since no route can match such a path.
+///
+/// Invoked from generated code within a `Router`.
+pub fn split_path_segments<'a>(path: &'a str, segments: &mut [&'a str]) -> Option<usize> {
+    let mut num_segments = 0;
+    for segment in path.strip_prefix('/')?.split('/') {
+        if segment.is_empty() {
+            return None;
+        }
+        *segments.get_mut(num_segments)? = segment;
+        num_segments += 1;
     }
+    Some(num_segments)
 }
 
-impl<'a> regexset_map::Entry<Request<Body>> for Service {
-    fn regex(&self) -> &regex::Regex {
-        let pair = &self.0;
-        &pair.0
-    }
-    fn matches_input(&self, _req: &Request<Body>) -> bool {
-        true
+/// Like `split_path_segments`, but a `path` with more segments than fit into `segments` fills all of
+/// them instead of not matching, for services with routes capturing the rest of the path, e.g.
+/// `GET /files/{*path: str}`. Generated code reserves a slot more than any of their routes has
+/// segments, such that only routes capturing the rest of the path match a filled `segments`, and
+/// take the rest from `path_rest`.
+///
+/// Invoked from generated code within a `Router`.
+pub fn split_path_segments_with_rest<'a>(path: &'a str, segments: &mut [&'a str]) -> Option<usize> {
+    let mut num_segments = 0;
+    for segment in path.strip_prefix('/')?.split('/') {
+        if segment.is_empty() {
+            return None;
+        }
+        if let Some(slot) = segments.get_mut(num_segments) {
+            *slot = segment;
+            num_segments += 1;
+        }
     }
+    Some(num_segments)
+}
+
+/// The rest of a service-relative `path` behind its first `num_segments` segments, without the
+/// leading `/`, e.g. `css/site.css` of `/files/css/site.css` behind 1 segment.
+///
+/// Invoked from generated code within a `Router`.
+pub fn path_rest(path: &str, num_segments: usize) -> &str {
+    path.strip_prefix('/')
+        .and_then(|path| path.splitn(num_segments + 1, '/').nth(num_segments))
+        .unwrap_or("")
+}
+
+/// HTTP status codes of a domain error type, i.e., the error type of a handler response `result[T][E]`.
+///
+/// Implemented by generated code for enums whose variants are annotated with `@status(code)`.
+pub trait ErrorStatus {
+    /// The status code of the response if `self` is returned as domain error.
+    fn status_code(&self) -> u16;
+}
+
+/// Conversion of a `HandlerResponse` with a domain error that determines the status code to a hyper response.
+/// Invoked from generated code within a `Router`.
+pub fn handler_result_response_to_hyper_response<T, E>(
+    handler_response: HandlerResponse<Result<T, E>>,
+) -> Response<Body>
+where
+    T: serde::Serialize,
+    E: serde::Serialize + ErrorStatus,
+{
+    encode_handler_result_response(handler_response, encode_json)
 }
 
 /// Conversion of a `HandlerResponse` to a hyper response.
-/// Invoked from generated code within a `DispatcherClosure`.
+/// Invoked from generated code within a `Router`.
 pub fn handler_response_to_hyper_response<T>(handler_response: HandlerResponse<T>) -> Response<Body>
 where
     T: serde::Serialize,
 {
+    encode_handler_response(handler_response, encode_json)
+}
+
+/// Conversion of a `HandlerResponse` of a route returning `()` to a hyper response
+/// with status code 204 No Content and an empty body.
+/// Invoked from generated code within a `Router`.
+pub fn handler_no_content_response_to_hyper_response(
+    handler_response: HandlerResponse<()>,
+) -> Response<Body> {
+    encode_handler_response(handler_response, |()| {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = hyper::StatusCode::NO_CONTENT;
+        Ok(response)
+    })
+}
+
+/// Conversion of a `HandlerResponse` of a route declaring response headers to a hyper response,
+/// i.e., the conversion of the value by `to_hyper_response` with the headers added.
+/// Invoked from generated code within a `Router`.
+pub fn with_response_headers<T, H: ResponseHeaders>(
+    to_hyper_response: impl FnOnce(HandlerResponse<T>) -> Response<Body>,
+    handler_response: HandlerResponse<WithHeaders<T, H>>,
+) -> Response<Body> {
+    let (value, headers) = match handler_response {
+        Ok(WithHeaders { value, headers }) => (Ok(value), Some(headers)),
+        Err(e) => (Err(e), None),
+    };
+    let mut response = to_hyper_response(value);
+    // a failed serialization already replaced the response
+    if let Some(headers) = headers.filter(|_| !response.status().is_server_error()) {
+        if let Err(e) = headers.write(response.headers_mut()) {
+            tracing::error!(error = ?e, "cannot serialize handler response headers");
+            return RuntimeError::SerializeHandlerResponse(e.to_string())
+                .to_error_response()
+                .to_hyper_response();
+        }
+    }
+    response
+}
+
+fn encode_json<T: serde::Serialize>(x: &T) -> Result<Response<Body>, String> {
+    serde_json::to_string(x)
+        .map(|s| Response::new(Body::from(s)))
+        .map_err(|e| e.to_string())
+}
+
+/// Like `handler_result_response_to_hyper_response`, but with the response body encoded by `encode`.
+pub(crate) fn encode_handler_result_response<T, E>(
+    handler_response: HandlerResponse<Result<T, E>>,
+    encode: impl FnOnce(&Result<T, E>) -> Result<Response<Body>, String>,
+) -> Response<Body>
+where
+    E: ErrorStatus,
+{
+    let status = match &handler_response {
+        Ok(Err(e)) => Some(e.status_code()),
+        _ => None,
+    };
+    let mut response = encode_handler_response(handler_response, encode);
+    if let Some(status) = status {
+        // a failed serialization already set the status code
+        if response.status() == hyper::StatusCode::OK {
+            *response.status_mut() =
+                hyper::StatusCode::from_u16(status).expect("status codes are validated by parser");
+        }
+    }
+    response
+}
+
+/// Like `handler_response_to_hyper_response`, but with the response body encoded by `encode`.
+/// Errors are always encoded as JSON.
+pub(crate) fn encode_handler_response<T>(
+    handler_response: HandlerResponse<T>,
+    encode: impl FnOnce(&T) -> Result<Response<Body>, String>,
+) -> Response<Body> {
     match handler_response {
-        Ok(x) => serde_json::to_string(&x)
-            .map(|s| Response::new(Body::from(s)))
-            .unwrap_or_else(|e| {
-                tracing::error!(error = ?e, "cannot serialize handler response");
-                RuntimeError::SerializeHandlerResponse(e.to_string())
-                    .to_error_response()
-                    .to_hyper_response()
-            }),
+        Ok(x) => encode(&x).unwrap_or_else(|e| {
+            tracing::error!(error = ?e, "cannot serialize handler response");
+            RuntimeError::SerializeHandlerResponse(e)
+                .to_error_response()
+                .to_hyper_response()
+        }),
         Err(e) => {
             tracing::error!(error = ?e, "handler returned error");
             service_protocol::ServiceError::from(e)
@@ -61,10 +61,30 @@ pub enum RuntimeError {
         parse_error: String,
     },
     QueryInvalid(String),
+    HeaderInvalid {
+        header_name: String,
+        parse_error: String,
+    },
     PostBodyReadError(String),
     PostBodyInvalid(String),
     SerializeHandlerResponse(String),
     SerializeErrorResponse(String),
+    RouteUnavailable {
+        flag: String,
+    },
+    CorsOriginNotAllowed {
+        origin: String,
+    },
+    PostBodyTooLarge {
+        max_body_size: u64,
+    },
+    Timeout {
+        timeout_ms: u64,
+    },
+    IdempotencyKeyInUse,
+    UnsupportedContentEncoding {
+        content_encoding: String,
+    },
 }
 
 impl ErrorResponse {
@@ -107,10 +127,17 @@ impl RuntimeError {
             RuntimeError::ServiceMountsAmbiguous => 500,
             RuntimeError::RouteParamInvalid { .. } => 400,
             RuntimeError::QueryInvalid(_) => 400,
+            RuntimeError::HeaderInvalid { .. } => 400,
             RuntimeError::PostBodyReadError(_) => 400,
             RuntimeError::PostBodyInvalid(_) => 400,
             RuntimeError::SerializeHandlerResponse(_) => 500,
             RuntimeError::SerializeErrorResponse(_) => 500,
+            RuntimeError::RouteUnavailable { .. } => 503,
+            RuntimeError::CorsOriginNotAllowed { .. } => 403,
+            RuntimeError::PostBodyTooLarge { .. } => 413,
+            RuntimeError::Timeout { .. } => 504,
+            RuntimeError::IdempotencyKeyInUse => 409,
+            RuntimeError::UnsupportedContentEncoding { .. } => 415,
         }
     }
 }
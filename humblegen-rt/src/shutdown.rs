@@ -0,0 +1,127 @@
+//! `SERVER` Graceful shutdown of servers, which stop accepting connections and complete the requests
+//! in flight before `listen_and_run_forever` returns, and the liveness and readiness endpoints
+//! served at `/health/live` and `/health/ready` by `Builder::with_health_endpoints`.
+//!
+//! A server stays ready until it is shut down using its `ShutdownHandle`. During the drain delay
+//! passed to `Builder::with_drain_delay`, it keeps serving requests while reporting that it is not
+//! ready, such that load balancers (e.g. Kubernetes) stop routing requests to it before it stops
+//! accepting connections.
+
+use crate::server::{Router, Service};
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Shuts down a server gracefully, obtained from `Builder::shutdown_handle`.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle(Arc<ShutdownState>);
+
+#[derive(Debug, Default)]
+struct ShutdownState {
+    shutting_down: AtomicBool,
+    notify: Notify,
+}
+
+impl ShutdownHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts the graceful shutdown of the server: it reports that it is not ready and stops
+    /// accepting connections after the drain delay.
+    pub fn shutdown(&self) {
+        self.0.shutting_down.store(true, Ordering::SeqCst);
+        self.0.notify.notify();
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.0.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Completes once `shutdown` was called.
+    pub async fn shutting_down(&self) {
+        while !self.is_shutting_down() {
+            self.0.notify.notified().await;
+        }
+    }
+
+    /// Shuts down on SIGTERM, as sent by Kubernetes to terminate a pod, or on Ctrl-C.
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn shutdown_on_signal(&self) {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            signal().await;
+            tracing::info!("received signal, shutting down");
+            handle.shutdown();
+        });
+    }
+}
+
+#[cfg(unix)]
+async fn signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::terminate()) {
+        Ok(mut sigterm) => {
+            futures::future::select(Box::pin(sigterm.recv()), Box::pin(tokio::signal::ctrl_c()))
+                .await;
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "cannot listen for SIGTERM, shutting down on Ctrl-C only");
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Completes once the server must stop accepting connections, i.e., `drain_delay` after the
+/// shutdown of `handle` started.
+///
+/// Invoked by `server::listen_and_run_forever`.
+pub(crate) async fn stop_accepting(handle: ShutdownHandle, drain_delay: Duration) {
+    handle.shutting_down().await;
+    tokio::time::delay_for(drain_delay).await;
+    tracing::info!("stopped accepting connections, completing requests in flight");
+}
+
+/// The path the health endpoints are mounted at.
+pub const ROOT: &str = "/health";
+
+/// A service serving `GET /health/live`, which always responds with 200 OK, and
+/// `GET /health/ready`, which responds with 503 Service Unavailable once the shutdown of
+/// `handle` started.
+///
+/// Invoked by generated code, see `Builder::with_health_endpoints`.
+pub fn health_service(handle: ShutdownHandle) -> Service {
+    let router: Box<Router> = Box::new(move |req: Request<Body>, suffix_start: usize| {
+        if req.method() != Method::GET {
+            return None;
+        }
+        let (route, status) = match &req.uri().path()[suffix_start..] {
+            "/live" => ("GET /live", StatusCode::OK),
+            "/ready" if handle.is_shutting_down() => {
+                ("GET /ready", StatusCode::SERVICE_UNAVAILABLE)
+            }
+            "/ready" => ("GET /ready", StatusCode::OK),
+            _ => return None,
+        };
+        let response = Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(Body::from(status.canonical_reason().unwrap_or_default()))
+            .expect("health responses must always be buildable");
+        Some((route, Box::pin(async move { Ok(response) })))
+    });
+    Service {
+        root: ROOT.to_owned(),
+        route_prefix: String::new(),
+        router,
+        pretty_json: false,
+    }
+}
@@ -0,0 +1,35 @@
+//! `GEN` - the `/__spec` endpoint of generated servers, describing the contract of their services.
+
+use crate::server::{Router, Service};
+use hyper::{header, Body, Method, Request, Response};
+
+/// The path the spec endpoint is mounted at.
+pub const ROOT: &str = "/__spec";
+
+/// A service serving `GET /__spec` with the resolved humblespec `spec_json` as a JSON document
+/// and `GET /__spec/index.html` with the HTML index of the routes `index_html`.
+///
+/// Invoked by generated code with the spec it was generated from.
+pub fn service(spec_json: &'static str, index_html: &'static str) -> Service {
+    let router: Box<Router> = Box::new(move |req: Request<Body>, suffix_start: usize| {
+        if req.method() != Method::GET {
+            return None;
+        }
+        let (route, content_type, body) = match &req.uri().path()[suffix_start..] {
+            "" => ("GET /", "application/json", spec_json),
+            "/index.html" => ("GET /index.html", "text/html; charset=utf-8", index_html),
+            _ => return None,
+        };
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .expect("spec responses must always be buildable");
+        Some((route, Box::pin(async move { Ok(response) })))
+    });
+    Service {
+        root: ROOT.to_owned(),
+        route_prefix: String::new(),
+        router,
+        pretty_json: false,
+    }
+}
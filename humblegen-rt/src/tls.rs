@@ -0,0 +1,299 @@
+//! `SERVER` TLS termination of generated servers using [`rustls`](https://docs.rs/rustls),
+//! enabled by the `tls` feature of this crate.
+//!
+//! ```ignore
+//! let acceptor = TlsAcceptor::from_pem(&std::fs::read("cert.pem")?, &std::fs::read("key.pem")?)?;
+//! Builder::new()
+//!     .add("/api", handler)
+//!     .serve(acceptor.bind(&addr).await?)
+//!     .await
+//! ```
+//!
+//! An acceptor can also be built from a `rustls::ServerConfig`, e.g. to require client
+//! certificates or to resolve the certificate by the server name of the client.
+//! The TLS handshake of a connection runs within the task serving it, not within the accept loop.
+
+use anyhow::Context as _;
+use hyper::server::accept::Accept;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::{ServerConfig, ServerConnection};
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Terminates TLS of incoming connections using a `rustls::ServerConfig`.
+#[derive(Debug, Clone)]
+pub struct TlsAcceptor {
+    config: Arc<ServerConfig>,
+}
+
+impl TlsAcceptor {
+    /// An acceptor presenting the PEM-encoded certificate chain `cert_chain`, leaf certificate
+    /// first, and its PEM-encoded private key `key`, negotiating HTTP/2 or HTTP/1.1 via ALPN.
+    pub fn from_pem(cert_chain: &[u8], key: &[u8]) -> anyhow::Result<Self> {
+        let certs = CertificateDer::pem_slice_iter(cert_chain)
+            .collect::<Result<Vec<_>, _>>()
+            .context("invalid PEM certificate chain")?;
+        if certs.is_empty() {
+            anyhow::bail!("PEM certificate chain contains no certificates");
+        }
+        let key = PrivateKeyDer::from_pem_slice(key).context("invalid PEM private key")?;
+        let mut config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .context("invalid TLS protocol versions")?
+                .with_no_client_auth()
+                .with_single_cert(certs, key)
+                .context("private key does not match certificate")?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Self::from(Arc::new(config)))
+    }
+
+    /// Binds to `addr` and terminates TLS of the connections accepted, to be served using
+    /// `Builder::serve`.
+    pub async fn bind(&self, addr: &SocketAddr) -> io::Result<TlsIncoming> {
+        Ok(self.incoming(TcpListener::bind(addr).await?))
+    }
+
+    /// Terminates TLS of the connections accepted by `listener`.
+    pub fn incoming(&self, listener: TcpListener) -> TlsIncoming {
+        TlsIncoming {
+            listener,
+            acceptor: self.clone(),
+        }
+    }
+
+    /// Starts the TLS handshake with the client of `io`, which completes on the first read or
+    /// write of the returned stream.
+    pub fn accept<IO>(&self, io: IO) -> io::Result<TlsStream<IO>> {
+        let conn = ServerConnection::new(Arc::clone(&self.config)).map_err(io::Error::other)?;
+        Ok(TlsStream {
+            io,
+            conn,
+            closing: false,
+        })
+    }
+}
+
+impl From<Arc<ServerConfig>> for TlsAcceptor {
+    fn from(config: Arc<ServerConfig>) -> Self {
+        Self { config }
+    }
+}
+
+/// The TLS connections accepted by a `TcpListener`, see `TlsAcceptor::bind`.
+#[derive(Debug)]
+pub struct TlsIncoming {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+}
+
+impl TlsIncoming {
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+impl Accept for TlsIncoming {
+    type Conn = TlsStream<TcpStream>;
+    type Error = io::Error;
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        let this = self.get_mut();
+        loop {
+            match this.listener.poll_accept(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok((stream, _))) => {
+                    return Poll::Ready(Some(this.acceptor.accept(stream)));
+                }
+                // errors of a single connection must not stop the server
+                Poll::Ready(Err(e)) if is_connection_error(&e) => {
+                    tracing::debug!(error = ?e, "accepted connection failed");
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
+
+fn is_connection_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+    )
+}
+
+/// A TLS connection over `IO`, see `TlsAcceptor::accept`.
+#[derive(Debug)]
+pub struct TlsStream<IO> {
+    io: IO,
+    conn: ServerConnection,
+    closing: bool,
+}
+
+impl<IO> TlsStream<IO> {
+    pub fn get_ref(&self) -> (&IO, &ServerConnection) {
+        (&self.io, &self.conn)
+    }
+}
+
+/// Adapts `io` to the blocking `Read` and `Write` traits used by rustls, with
+/// `io::ErrorKind::WouldBlock` in place of `Poll::Pending`.
+struct SyncIo<'a, 'b, IO> {
+    io: &'a mut IO,
+    cx: &'a mut Context<'b>,
+}
+
+impl<IO: AsyncRead + Unpin> Read for SyncIo<'_, '_, IO> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match Pin::new(&mut *self.io).poll_read(self.cx, buf) {
+            Poll::Ready(result) => result,
+            Poll::Pending => Err(io::ErrorKind::WouldBlock.into()),
+        }
+    }
+}
+
+impl<IO: AsyncWrite + Unpin> Write for SyncIo<'_, '_, IO> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match Pin::new(&mut *self.io).poll_write(self.cx, buf) {
+            Poll::Ready(result) => result,
+            Poll::Pending => Err(io::ErrorKind::WouldBlock.into()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match Pin::new(&mut *self.io).poll_flush(self.cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => Err(io::ErrorKind::WouldBlock.into()),
+        }
+    }
+}
+
+impl<IO: AsyncRead + AsyncWrite + Unpin> TlsStream<IO> {
+    /// Writes the TLS records pending in `conn` to `io`.
+    fn poll_write_tls(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.conn.wants_write() {
+            let mut io = SyncIo {
+                io: &mut self.io,
+                cx,
+            };
+            match self.conn.write_tls(&mut io) {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Poll::Pending,
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Reads TLS records from `io` into `conn`, returning whether `io` reached its end.
+    fn poll_read_tls(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<bool>> {
+        let mut io = SyncIo {
+            io: &mut self.io,
+            cx,
+        };
+        match self.conn.read_tls(&mut io) {
+            Ok(0) => return Poll::Ready(Ok(true)),
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Poll::Pending,
+            Err(e) => return Poll::Ready(Err(e)),
+        }
+        if let Err(e) = self.conn.process_new_packets() {
+            // best effort to send the alert describing the error to the client
+            let _ = self.poll_write_tls(cx);
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, e)));
+        }
+        Poll::Ready(Ok(false))
+    }
+
+    fn poll_handshake(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.conn.is_handshaking() {
+            futures::ready!(self.poll_write_tls(cx))?;
+            if !self.conn.is_handshaking() {
+                break;
+            }
+            if futures::ready!(self.poll_read_tls(cx))? {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed during TLS handshake",
+                )));
+            }
+        }
+        self.poll_write_tls(cx)
+    }
+}
+
+impl<IO: AsyncRead + AsyncWrite + Unpin> AsyncRead for TlsStream<IO> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        futures::ready!(this.poll_handshake(cx))?;
+        loop {
+            match this.conn.reader().read(buf) {
+                Ok(n) => return Poll::Ready(Ok(n)),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+            // the peer may e.g. update its keys, to be answered before reading on
+            futures::ready!(this.poll_write_tls(cx))?;
+            if futures::ready!(this.poll_read_tls(cx))? {
+                // the connection was closed without a TLS close_notify alert
+                return Poll::Ready(Ok(0));
+            }
+        }
+    }
+}
+
+impl<IO: AsyncRead + AsyncWrite + Unpin> AsyncWrite for TlsStream<IO> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        futures::ready!(this.poll_handshake(cx))?;
+        let mut written = 0;
+        loop {
+            let n = this.conn.writer().write(&buf[written..])?;
+            written += n;
+            match this.poll_write_tls(cx) {
+                Poll::Ready(result) => result?,
+                Poll::Pending if written == 0 => return Poll::Pending,
+                Poll::Pending => return Poll::Ready(Ok(written)),
+            }
+            if written == buf.len() || n == 0 {
+                return Poll::Ready(Ok(written));
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.conn.writer().flush()?;
+        futures::ready!(this.poll_write_tls(cx))?;
+        Pin::new(&mut this.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if !this.closing {
+            this.conn.send_close_notify();
+            this.closing = true;
+        }
+        futures::ready!(this.poll_write_tls(cx))?;
+        Pin::new(&mut this.io).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,217 @@
+//! `GEN` - alternative body encodings of services annotated with `@format(...)`, enabled by the
+//! `cbor` and `msgpack` features.
+//!
+//! Services annotated with `@format(cbor)` or `@format(msgpack)` accept that format besides JSON,
+//! and services annotated with `@format(cbor, msgpack)` both.
+//! The format is negotiated per request, see `Negotiated`: request bodies are decoded in the
+//! format of their `Content-Type` and as JSON otherwise, responses are encoded in the format
+//! of the service the `Accept` header of the request prefers and as JSON otherwise.
+//! Middleware finds the negotiated formats in the extensions of the request, see `Negotiated::of`.
+//! Error responses of the humble service protocol are always JSON.
+
+use crate::handler::HandlerResponse;
+use crate::server::{self, ErrorStatus};
+use crate::service_protocol::{ErrorResponse, RuntimeError, ToErrorResponse};
+use hyper::header::{HeaderValue, ACCEPT, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Request, Response};
+
+/// The content type of JSON request and response bodies.
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// The content type of CBOR request and response bodies.
+#[cfg(feature = "cbor")]
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+/// The content type of MessagePack request and response bodies.
+#[cfg(feature = "msgpack")]
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// The encoding of a request or response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    Json,
+    #[cfg(feature = "cbor")]
+    Cbor,
+    #[cfg(feature = "msgpack")]
+    Msgpack,
+}
+
+impl WireFormat {
+    /// All formats enabled by features of this crate.
+    const ALL: &'static [WireFormat] = &[
+        WireFormat::Json,
+        #[cfg(feature = "cbor")]
+        WireFormat::Cbor,
+        #[cfg(feature = "msgpack")]
+        WireFormat::Msgpack,
+    ];
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            WireFormat::Json => JSON_CONTENT_TYPE,
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => CBOR_CONTENT_TYPE,
+            #[cfg(feature = "msgpack")]
+            WireFormat::Msgpack => MSGPACK_CONTENT_TYPE,
+        }
+    }
+
+    /// The format with `content_type`, ignoring parameters such as `charset`.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|format| media_type.eq_ignore_ascii_case(format.content_type()))
+    }
+
+    /// The format of the body of a request with `headers` to a service accepting `self` besides
+    /// JSON, determined by its `Content-Type`.
+    pub fn of_request_body(self, headers: &HeaderMap) -> Self {
+        Negotiated::negotiate(&[self], headers).request
+    }
+
+    /// The format of the response to a request with `headers` to a service accepting `self`
+    /// besides JSON, determined by its `Accept` header.
+    pub fn of_response(self, headers: &HeaderMap) -> Self {
+        Negotiated::negotiate(&[self], headers).response
+    }
+
+    /// Encode `value` in this format.
+    pub fn to_vec<T: serde::Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            WireFormat::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => serde_cbor::to_vec(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "msgpack")]
+            WireFormat::Msgpack => crate::msgpack::to_vec(value).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Decode a `T` in this format.
+    pub fn from_slice<T: serde::de::DeserializeOwned>(self, bytes: &[u8]) -> Result<T, String> {
+        match self {
+            WireFormat::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => serde_cbor::from_slice(bytes).map_err(|e| e.to_string()),
+            #[cfg(feature = "msgpack")]
+            WireFormat::Msgpack => crate::msgpack::from_slice(bytes).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn encode_response<T: serde::Serialize>(self, value: &T) -> Result<Response<Body>, String> {
+        let mut response = Response::new(Body::from(self.to_vec(value)?));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type()));
+        Ok(response)
+    }
+}
+
+/// The formats of the request body and the response of a request, negotiated by its headers
+/// among the formats of the service and JSON.
+///
+/// Generated servers insert the negotiation into the extensions of the request before running
+/// the middleware of the route, which may replace it, e.g. to encode all responses to a client
+/// in JSON during a migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Negotiated {
+    /// The format of the request body, by its `Content-Type`.
+    pub request: WireFormat,
+    /// The format of the response, by the `Accept` header of the request.
+    pub response: WireFormat,
+}
+
+impl Default for Negotiated {
+    fn default() -> Self {
+        Self {
+            request: WireFormat::Json,
+            response: WireFormat::Json,
+        }
+    }
+}
+
+impl Negotiated {
+    /// Negotiates the formats of a request with `headers` to a service accepting `formats`
+    /// besides JSON.
+    ///
+    /// The response format is the format of the service listed in the `Accept` header with the
+    /// highest quality, e.g. MessagePack for `application/msgpack, application/cbor;q=0.5`, the
+    /// first one among equals. Clients listing none of them, e.g. only `application/json`, get
+    /// JSON.
+    pub fn negotiate(formats: &[WireFormat], headers: &HeaderMap) -> Self {
+        let accepts = |format: &WireFormat| formats.contains(format);
+        let request = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(WireFormat::from_content_type)
+            .filter(accepts)
+            .unwrap_or(WireFormat::Json);
+        let mut response = (WireFormat::Json, 0.0);
+        let media_ranges = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for media_range in media_ranges {
+            let format = match WireFormat::from_content_type(media_range).filter(accepts) {
+                Some(format) => format,
+                None => continue,
+            };
+            let quality = media_range
+                .split(';')
+                .skip(1)
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > response.1 {
+                response = (format, quality);
+            }
+        }
+        Self {
+            request,
+            response: response.0,
+        }
+    }
+
+    /// The formats negotiated for `req`, JSON if not negotiated.
+    pub fn of(req: &Request<Body>) -> Self {
+        req.extensions().get().copied().unwrap_or_default()
+    }
+}
+
+/// Like `serialization_helpers::deser_post_data`, but decodes the body in `format`.
+pub async fn deser_post_data<T: serde::de::DeserializeOwned>(
+    format: WireFormat,
+    req_body: &mut hyper::Body,
+) -> Result<T, ErrorResponse> {
+    let bytes = hyper::body::to_bytes(req_body)
+        .await
+        .map_err(|e| RuntimeError::PostBodyReadError(format!("{}", e)).to_error_response())?;
+    format
+        .from_slice(&bytes[..])
+        .map_err(|e| RuntimeError::PostBodyReadError(e).to_error_response())
+}
+
+/// Like `server::handler_response_to_hyper_response`, but encodes the response in `format`.
+pub fn handler_response_to_hyper_response<T>(
+    format: WireFormat,
+    handler_response: HandlerResponse<T>,
+) -> Response<Body>
+where
+    T: serde::Serialize,
+{
+    server::encode_handler_response(handler_response, |x| format.encode_response(x))
+}
+
+/// Like `server::handler_result_response_to_hyper_response`, but encodes the response in `format`.
+pub fn handler_result_response_to_hyper_response<T, E>(
+    format: WireFormat,
+    handler_response: HandlerResponse<Result<T, E>>,
+) -> Response<Body>
+where
+    T: serde::Serialize,
+    E: serde::Serialize + ErrorStatus,
+{
+    server::encode_handler_result_response(handler_response, |x| format.encode_response(x))
+}
@@ -0,0 +1,27 @@
+//! `GEN` Hashes of the wire shape of generated types, to verify schema compatibility when
+//! connecting to another party, e.g. by exchanging the generated `WIRE_MANIFEST`.
+//!
+//! Types have the same hash if their JSON representation is the same, regardless of their names.
+
+use std::collections::BTreeMap;
+
+/// A type with a hash of its wire shape, implemented by all generated types.
+pub trait WireHash {
+    /// 16 hexadecimal digits, equal for types with the same JSON representation.
+    const WIRE_HASH: &'static str;
+}
+
+/// `HANDLER` The types named in both manifests whose hashes differ, i.e., which the parties
+/// represent differently, sorted by name.
+///
+/// Manifests are JSON objects from the name of each type to its hash, like the generated
+/// `WIRE_MANIFEST`. Types named in only one of them are ignored.
+pub fn incompatible_types(ours: &str, theirs: &str) -> Result<Vec<String>, serde_json::Error> {
+    let ours: BTreeMap<String, String> = serde_json::from_str(ours)?;
+    let theirs: BTreeMap<String, String> = serde_json::from_str(theirs)?;
+    Ok(ours
+        .into_iter()
+        .filter(|(name, hash)| matches!(theirs.get(name), Some(other) if other != hash))
+        .map(|(name, _)| name)
+        .collect())
+}
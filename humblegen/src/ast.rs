@@ -1,12 +1,70 @@
 //! Humble language abstract syntax tree
 
+use crate::WireCasing;
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
 /// A spec node.
 ///
-/// A spec is the top-level item in humble.
-#[derive(Debug)]
-pub struct Spec(pub Vec<SpecItem>);
+/// A spec is the top-level item in humble: its definitions and its `meta` block.
+///
+/// Specs without metadata serialize as the list of their items, otherwise as an object
+/// `{"items": [...], "meta": {...}}`, such that the JSON of specs predating `meta` blocks is
+/// unchanged.
+#[derive(Debug, Clone)]
+pub struct Spec(pub Vec<SpecItem>, pub Meta);
+
+impl Serialize for Spec {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct WithMeta<'a> {
+            items: &'a [SpecItem],
+            meta: &'a Meta,
+        }
+
+        if self.1.is_empty() {
+            self.0.serialize(serializer)
+        } else {
+            WithMeta {
+                items: &self.0,
+                meta: &self.1,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Spec {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SpecJson {
+            Items(Vec<SpecItem>),
+            WithMeta { items: Vec<SpecItem>, meta: Meta },
+        }
+
+        Ok(match SpecJson::deserialize(deserializer)? {
+            SpecJson::Items(items) => Spec::new(items),
+            SpecJson::WithMeta { items, meta } => Spec(items, meta),
+        })
+    }
+}
 
 impl Spec {
+    /// A spec of `items`, without metadata.
+    pub fn new(items: Vec<SpecItem>) -> Self {
+        Spec(items, Meta::default())
+    }
+
+    /// The metadata declared in the `meta` block of the spec.
+    pub fn meta(&self) -> &Meta {
+        &self.1
+    }
+
     /// Iterate over items in spec.
     pub fn iter(&self) -> impl Iterator<Item = &SpecItem> {
         self.0.iter()
@@ -16,20 +74,600 @@ impl Spec {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut SpecItem> {
         self.0.iter_mut()
     }
+
+    /// Iterate over items in spec with their ids.
+    pub fn iter_with_ids(&self) -> impl Iterator<Item = (ItemId, &SpecItem)> {
+        self.0.iter().enumerate().map(|(i, item)| (ItemId(i), item))
+    }
+
+    /// The item with id `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not the id of an item of `self`.
+    pub fn get(&self, id: ItemId) -> &SpecItem {
+        &self.0[id.0]
+    }
+
+    /// The mutable item with id `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not the id of an item of `self`.
+    pub fn get_mut(&mut self, id: ItemId) -> &mut SpecItem {
+        &mut self.0[id.0]
+    }
+
+    /// Index of the items by name, to look up the definitions of user-defined types without
+    /// scanning the spec.
+    ///
+    /// Build it once for many lookups: building takes time linear in the number of items.
+    pub fn index(&self) -> SpecIndex<'_> {
+        SpecIndex {
+            spec: self,
+            ids: self
+                .iter_with_ids()
+                .map(|(id, item)| (item.name(), id))
+                .collect(),
+        }
+    }
+
+    /// Whether values of `type_ident` can contain `bytes`, e.g. a struct with a `bytes` field.
+    pub fn contains_bytes(&self, type_ident: &TypeIdent) -> bool {
+        self.index()
+            .contains_bytes_impl(type_ident, &mut BTreeSet::new())
+    }
+
+    /// Call `f` on every type identifier in the spec, i.e., the types of fields, elements,
+    /// variants and endpoints, and the types nested in them. Nested types are visited before the
+    /// types containing them.
+    pub fn for_each_type_ident_mut(&mut self, f: &mut impl FnMut(&mut TypeIdent)) {
+        fn visit_fields(fields: &mut StructFields, f: &mut impl FnMut(&mut TypeIdent)) {
+            for field in &mut fields.0 {
+                field.pair.type_ident.for_each_mut(f);
+            }
+        }
+
+        for item in self.iter_mut() {
+            match item {
+                SpecItem::StructDef(sdef) => visit_fields(&mut sdef.fields, f),
+                SpecItem::TupleStructDef(tdef) => {
+                    for ty in &mut tdef.elements.0 {
+                        ty.for_each_mut(f);
+                    }
+                }
+                SpecItem::EnumDef(edef) => {
+                    for variant in &mut edef.variants {
+                        match &mut variant.variant_type {
+                            VariantType::Simple => {}
+                            VariantType::Newtype(ty) => ty.for_each_mut(f),
+                            VariantType::Tuple(tdef) => {
+                                for ty in &mut tdef.0 {
+                                    ty.for_each_mut(f);
+                                }
+                            }
+                            VariantType::Struct(fields) => visit_fields(fields, f),
+                        }
+                    }
+                }
+                SpecItem::FlagsDef(_) => {}
+                SpecItem::ServiceDef(service) => {
+                    for endpoint in &mut service.endpoints {
+                        let (components, query, headers, body, ret) = match &mut endpoint.route {
+                            ServiceRoute::Get {
+                                components,
+                                query,
+                                headers,
+                                ret,
+                                ..
+                            }
+                            | ServiceRoute::Delete {
+                                components,
+                                query,
+                                headers,
+                                ret,
+                                ..
+                            } => (components, query, headers, None, ret),
+                            ServiceRoute::Post {
+                                components,
+                                query,
+                                headers,
+                                body,
+                                ret,
+                                ..
+                            }
+                            | ServiceRoute::Put {
+                                components,
+                                query,
+                                headers,
+                                body,
+                                ret,
+                                ..
+                            }
+                            | ServiceRoute::Patch {
+                                components,
+                                query,
+                                headers,
+                                body,
+                                ret,
+                                ..
+                            } => (components, query, headers, Some(body), ret),
+                        };
+                        for component in components {
+                            if let ServiceRouteComponent::Variable(pair)
+                            | ServiceRouteComponent::Rest(pair) = component
+                            {
+                                pair.type_ident.for_each_mut(f);
+                            }
+                        }
+                        for ty in query.iter_mut().chain(body).chain(Some(ret)) {
+                            ty.for_each_mut(f);
+                        }
+                        for header in headers {
+                            header.type_ident.for_each_mut(f);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Make all occurrences of each identifier in the spec, i.e. the names of definitions and of
+    /// the user-defined types referencing them, share one allocation (see `Ident`).
+    ///
+    /// Parsing and loading specs intern their identifiers.
+    pub fn intern_idents(&mut self) {
+        let mut idents: HashSet<Ident> = HashSet::new();
+        let mut intern = |ident: &mut Ident| match idents.get(ident.as_str()) {
+            Some(interned) => *ident = interned.clone(),
+            None => {
+                idents.insert(ident.clone());
+            }
+        };
+        for item in self.iter_mut() {
+            intern(item.name_mut());
+        }
+        self.for_each_type_ident_mut(&mut |ty| {
+            if let TypeIdent::UserDefined(name) = ty {
+                intern(name);
+            }
+        });
+    }
+
+    /// Call `f` on every use of a type in the spec with its location, e.g. `Monster.experience`
+    /// or `MonsterApi: GET /monsters/{id}`, and the annotations of the definitions, variants,
+    /// fields and endpoints enclosing it. Nested types are not visited separately.
+    pub fn for_each_type_use(&self, f: &mut impl FnMut(&str, &TypeIdent, &[&Vec<Annotation>])) {
+        fn visit_fields(
+            location: &str,
+            fields: &StructFields,
+            annotations: &[&Vec<Annotation>],
+            f: &mut impl FnMut(&str, &TypeIdent, &[&Vec<Annotation>]),
+        ) {
+            for field in fields.iter() {
+                let location = format!("{}.{}", location, field.pair.name);
+                let mut annotations = annotations.to_vec();
+                annotations.push(&field.annotations);
+                f(&location, &field.pair.type_ident, &annotations);
+            }
+        }
+
+        for item in self.iter() {
+            match item {
+                SpecItem::StructDef(sdef) => {
+                    visit_fields(&sdef.name, &sdef.fields, &[&sdef.annotations], f)
+                }
+                SpecItem::TupleStructDef(tdef) => {
+                    for (i, ty) in tdef.elements.elements().iter().enumerate() {
+                        let location = format!("{}.{}", tdef.name, i);
+                        f(&location, ty, &[&tdef.annotations]);
+                    }
+                }
+                SpecItem::EnumDef(edef) => {
+                    for variant in &edef.variants {
+                        let location = format!("{}::{}", edef.name, variant.name);
+                        let annotations = [&edef.annotations, &variant.annotations];
+                        match &variant.variant_type {
+                            VariantType::Simple => {}
+                            VariantType::Newtype(ty) => f(&location, ty, &annotations),
+                            VariantType::Tuple(tdef) => {
+                                for ty in tdef.elements() {
+                                    f(&location, ty, &annotations);
+                                }
+                            }
+                            VariantType::Struct(fields) => {
+                                visit_fields(&location, fields, &annotations, f)
+                            }
+                        }
+                    }
+                }
+                SpecItem::FlagsDef(_) => {}
+                SpecItem::ServiceDef(service) => {
+                    for endpoint in &service.endpoints {
+                        let route = &endpoint.route;
+                        let location = service.endpoint_location(endpoint);
+                        let annotations = [&service.annotations, &endpoint.annotations];
+                        let components = route
+                            .components()
+                            .iter()
+                            .filter_map(|c| c.param().map(|pair| &pair.type_ident));
+                        let types = components
+                            .chain(route.query())
+                            .chain(route.headers().iter().map(|h| &h.type_ident))
+                            .chain(route.request_body())
+                            .chain(Some(route.return_type()));
+                        for ty in types {
+                            f(&location, ty, &annotations);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Id of an item of a spec, its position in the spec.
+///
+/// Ids are only meaningful for the spec they were obtained from, and change if items are
+/// inserted or removed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ItemId(usize);
+
+/// An identifier of a definition: the name of a struct, tuple struct, enum, flags or service,
+/// or of a user-defined type.
+///
+/// Identifiers are reference counted, so copies of an identifier, e.g. in the types of fields
+/// or of embedded and derived definitions, share its allocation. Parsing interns the
+/// identifiers of a spec, such that all occurrences of a name share one allocation (see
+/// `Spec::intern_idents`). Identifiers deref to `str` and serialize as strings.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ident(Arc<str>);
+
+impl Ident {
+    /// The identifier as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Ident {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Ident {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Ident {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Ident {
+    fn from(name: &str) -> Self {
+        Ident(name.into())
+    }
+}
+
+impl From<String> for Ident {
+    fn from(name: String) -> Self {
+        Ident(name.into())
+    }
+}
+
+impl From<Ident> for String {
+    fn from(ident: Ident) -> Self {
+        ident.0.as_ref().to_owned()
+    }
+}
+
+impl PartialEq<str> for Ident {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Ident {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Ident {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Ident> for str {
+    fn eq(&self, other: &Ident) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Ident> for &str {
+    fn eq(&self, other: &Ident) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Ident> for String {
+    fn eq(&self, other: &Ident) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl fmt::Debug for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl Serialize for Ident {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ident {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Ident::from)
+    }
+}
+
+/// Index of the items of a spec by name, see `Spec::index`.
+///
+/// The index borrows the names from the spec instead of copying them.
+#[derive(Debug, Clone)]
+pub struct SpecIndex<'a> {
+    spec: &'a Spec,
+    ids: HashMap<&'a str, ItemId>,
+}
+
+impl<'a> SpecIndex<'a> {
+    /// The id of the item named `name`.
+    pub fn id(&self, name: &str) -> Option<ItemId> {
+        self.ids.get(name).copied()
+    }
+
+    /// The item named `name`.
+    pub fn get(&self, name: &str) -> Option<&'a SpecItem> {
+        self.id(name).map(|id| self.spec.get(id))
+    }
+
+    /// The struct named `name`.
+    pub fn struct_def(&self, name: &str) -> Option<&'a StructDef> {
+        match self.get(name)? {
+            SpecItem::StructDef(sdef) => Some(sdef),
+            _ => None,
+        }
+    }
+
+    /// The tuple struct named `name`.
+    pub fn tuple_struct_def(&self, name: &str) -> Option<&'a TupleStructDef> {
+        match self.get(name)? {
+            SpecItem::TupleStructDef(tdef) => Some(tdef),
+            _ => None,
+        }
+    }
+
+    /// The enum named `name`.
+    pub fn enum_def(&self, name: &str) -> Option<&'a EnumDef> {
+        match self.get(name)? {
+            SpecItem::EnumDef(edef) => Some(edef),
+            _ => None,
+        }
+    }
+
+    /// The type `T` of the `items: list[T]` of the pages returned by `endpoint`, if paginated.
+    pub fn page_item_type(&self, endpoint: &ServiceEndpoint) -> Option<&'a TypeIdent> {
+        if !endpoint.is_paginated() {
+            return None;
+        }
+        let page = self.struct_def(endpoint.route.return_type().user_defined()?)?;
+        match &page
+            .fields
+            .iter()
+            .find(|f| f.pair.name == "items")?
+            .pair
+            .type_ident
+        {
+            TypeIdent::List(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    fn contains_bytes_impl(
+        &self,
+        type_ident: &'a TypeIdent,
+        visited: &mut BTreeSet<&'a str>,
+    ) -> bool {
+        match type_ident {
+            TypeIdent::BuiltIn(atom) => matches!(atom, AtomType::Bytes),
+            TypeIdent::List(inner) | TypeIdent::Option(inner) => {
+                self.contains_bytes_impl(inner, visited)
+            }
+            TypeIdent::Result(a, b) | TypeIdent::Map(a, b) => {
+                self.contains_bytes_impl(a, visited) || self.contains_bytes_impl(b, visited)
+            }
+            TypeIdent::Tuple(tdef) => tdef
+                .elements()
+                .iter()
+                .any(|e| self.contains_bytes_impl(e, visited)),
+            TypeIdent::UserDefined(name) => {
+                if !visited.insert(name) {
+                    return false;
+                }
+                let fields_contain_bytes = |fields: &'a StructFields, visited: &mut _| {
+                    fields
+                        .iter()
+                        .any(|f| self.contains_bytes_impl(&f.pair.type_ident, visited))
+                };
+                match self.get(name) {
+                    Some(SpecItem::StructDef(sdef)) => fields_contain_bytes(&sdef.fields, visited),
+                    Some(SpecItem::TupleStructDef(tdef)) => tdef
+                        .elements
+                        .elements()
+                        .iter()
+                        .any(|e| self.contains_bytes_impl(e, visited)),
+                    Some(SpecItem::EnumDef(edef)) => {
+                        edef.variants
+                            .iter()
+                            .any(|variant| match &variant.variant_type {
+                                VariantType::Simple => false,
+                                VariantType::Newtype(ty) => self.contains_bytes_impl(ty, visited),
+                                VariantType::Tuple(tdef) => tdef
+                                    .elements()
+                                    .iter()
+                                    .any(|e| self.contains_bytes_impl(e, visited)),
+                                VariantType::Struct(fields) => {
+                                    fields_contain_bytes(fields, visited)
+                                }
+                            })
+                    }
+                    Some(SpecItem::FlagsDef(_)) | Some(SpecItem::ServiceDef(_)) | None => false,
+                }
+            }
+        }
+    }
+}
+
+/// The metadata of a spec, declared in its `meta` block, e.g.
+/// ```text
+/// meta {
+///     title: "Monster API",
+///     version: "1.2.0",
+///     base_url: "https://api.example.com/v1",
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Meta {
+    /// The title of the API, e.g. of its documentation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The version of the API, which servers send in the `Api-Version` header of their responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The absolute URL the services are mounted at, which clients send requests to by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+impl Meta {
+    /// Whether no metadata is declared, e.g. since the spec has no `meta` block.
+    pub fn is_empty(&self) -> bool {
+        self == &Meta::default()
+    }
+
+    /// The version if it is of the form `major.minor.patch`, as required of package versions.
+    pub fn semver(&self) -> Option<&str> {
+        self.version.as_deref().filter(|version| {
+            let parts: Vec<_> = version.split('.').collect();
+            parts.len() == 3
+                && parts
+                    .iter()
+                    .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        })
+    }
 }
 
 /// A Spec item node.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SpecItem {
     /// `struct` definition.
     StructDef(StructDef),
+    /// Tuple `struct` definition.
+    TupleStructDef(TupleStructDef),
     /// `enum` definition.
     EnumDef(EnumDef),
+    /// `flags` definition.
+    FlagsDef(FlagsDef),
     /// `service` definition
     ServiceDef(ServiceDef),
 }
 
 impl SpecItem {
+    /// The name of the defined struct, enum or service.
+    pub fn name(&self) -> &str {
+        match self {
+            SpecItem::StructDef(s) => &s.name,
+            SpecItem::TupleStructDef(t) => &t.name,
+            SpecItem::EnumDef(e) => &e.name,
+            SpecItem::FlagsDef(f) => &f.name,
+            SpecItem::ServiceDef(s) => &s.name,
+        }
+    }
+
+    /// The mutable name of the defined struct, enum or service.
+    pub fn name_mut(&mut self) -> &mut Ident {
+        match self {
+            SpecItem::StructDef(s) => &mut s.name,
+            SpecItem::TupleStructDef(t) => &mut t.name,
+            SpecItem::EnumDef(e) => &mut e.name,
+            SpecItem::FlagsDef(f) => &mut f.name,
+            SpecItem::ServiceDef(s) => &mut s.name,
+        }
+    }
+
+    /// The traits the Rust backend derives for the defined type, as annotated with
+    /// `@derive(Trait, ..)`, e.g. `@derive(PartialEq, Hash)`.
+    pub fn derives(&self) -> Vec<Derive> {
+        let annotations = match self {
+            SpecItem::StructDef(s) => &s.annotations,
+            SpecItem::TupleStructDef(t) => &t.annotations,
+            SpecItem::EnumDef(e) => &e.annotations,
+            SpecItem::FlagsDef(_) | SpecItem::ServiceDef(_) => return vec![],
+        };
+        annotations
+            .iter()
+            .filter(|a| a.name == "derive")
+            .flat_map(|a| &a.args)
+            .map(|arg| Derive::from_annotation_arg(arg).expect("validated by parser"))
+            .collect()
+    }
+
+    /// The module and the name within the module of a definition of a `mod` block, e.g.
+    /// `("billing", "Invoice")` for `BillingInvoice` of `mod billing { struct Invoice { .. } }`.
+    pub fn module(&self) -> Option<(&str, &str)> {
+        let annotations = match self {
+            SpecItem::StructDef(s) => &s.annotations,
+            SpecItem::TupleStructDef(t) => &t.annotations,
+            SpecItem::EnumDef(e) => &e.annotations,
+            SpecItem::FlagsDef(f) => &f.annotations,
+            SpecItem::ServiceDef(_) => return None,
+        };
+        annotations
+            .iter()
+            .find(|a| a.name == "module")
+            .map(|a| (a.args[0].as_str(), a.args[1].as_str()))
+    }
+
+    /// Whether the defined type is annotated with `@export`, i.e., used even if no service uses
+    /// it, see `resolve::unused_definitions`.
+    pub fn is_exported(&self) -> bool {
+        let annotations = match self {
+            SpecItem::StructDef(s) => &s.annotations,
+            SpecItem::TupleStructDef(t) => &t.annotations,
+            SpecItem::EnumDef(e) => &e.annotations,
+            SpecItem::FlagsDef(f) => &f.annotations,
+            SpecItem::ServiceDef(_) => return false,
+        };
+        annotations.iter().any(|a| a.name == "export")
+    }
+
     /// The service definition if `self` is a `ServiceDef`.
     pub fn service_def(&self) -> Option<&ServiceDef> {
         match self {
@@ -40,18 +678,83 @@ impl SpecItem {
 }
 
 /// A struct definition.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructDef {
     /// Name of the struct.
-    pub name: String,
+    pub name: Ident,
     /// Fields of the struct.
     pub fields: StructFields,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// The annotations of the struct. (example: `@rust_from("crate::domain::Customer")`)
+    pub annotations: Vec<Annotation>,
+}
+
+impl StructDef {
+    /// The paths of the Rust domain types the struct converts from and into, given by
+    /// `@rust_from(path)` annotations.
+    pub fn rust_from(&self) -> Vec<&str> {
+        self.annotations
+            .iter()
+            .filter(|a| a.name == "rust_from")
+            .map(|a| a.args[0].as_str())
+            .collect()
+    }
+
+    /// The Rust attributes emitted verbatim on the generated struct, as annotated with
+    /// `@rust_attr("#[attr]")`.
+    pub fn rust_attrs(&self) -> Vec<&str> {
+        rust_attrs(&self.annotations)
+    }
+
+    /// Whether the struct is annotated with `@db_row`, i.e., is loaded from database rows by the
+    /// Rust backend.
+    pub fn is_db_row(&self) -> bool {
+        self.annotations.iter().any(|a| a.name == "db_row")
+    }
+
+    /// The name of the partial struct derived from the struct, if annotated with
+    /// `@partial("name")`.
+    pub fn partial(&self) -> Option<&str> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "partial")
+            .map(|a| a.args[0].as_str())
+    }
+}
+
+/// A tuple struct definition.
+/// Example:
+/// ```text
+/// struct Point(i32, i32);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TupleStructDef {
+    /// Name of the struct.
+    pub name: Ident,
+    /// Types of the elements of the struct.
+    pub elements: TupleDef,
+    /// Documentation comment.
+    pub doc_comment: Option<String>,
+    /// Annotations of the struct, e.g. `@lossy(u32)`.
+    pub annotations: Vec<Annotation>,
+}
+
+impl TupleStructDef {
+    /// The Rust attributes emitted verbatim on the generated tuple struct, as annotated with
+    /// `@rust_attr("#[attr]")`.
+    pub fn rust_attrs(&self) -> Vec<&str> {
+        rust_attrs(&self.annotations)
+    }
+
+    /// Whether the struct has a single element, which it is represented as on the wire.
+    pub fn is_newtype(&self) -> bool {
+        self.elements.elements().len() == 1
+    }
 }
 
 /// Container of struct fields.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructFields(pub Vec<FieldNode>);
 
 impl StructFields {
@@ -62,17 +765,65 @@ impl StructFields {
 }
 
 /// Enum definition.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumDef {
     /// Name of the `enum`.
-    pub name: String,
+    pub name: Ident,
     /// Container of variants.
     pub variants: Vec<VariantDef>,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// Annotations of the enum, e.g. `@tag("type")`.
+    pub annotations: Vec<Annotation>,
 }
 
 impl EnumDef {
+    /// The Rust attributes emitted verbatim on the generated enum, as annotated with
+    /// `@rust_attr("#[attr]")`.
+    pub fn rust_attrs(&self) -> Vec<&str> {
+        rust_attrs(&self.annotations)
+    }
+
+    /// Whether the enum is annotated with `@non_exhaustive`, i.e., may gain variants that
+    /// decoders decode as an unknown variant.
+    pub fn is_non_exhaustive(&self) -> bool {
+        self.annotations.iter().any(|a| a.name == "non_exhaustive")
+    }
+
+    /// Whether the enum is annotated with `@numeric`, i.e., its simple variants are their
+    /// discriminants on the wire.
+    pub fn is_numeric(&self) -> bool {
+        self.annotations.iter().any(|a| a.name == "numeric")
+    }
+
+    /// The discriminants of the variants, in order, like in Rust: explicit ones as given,
+    /// others one more than the previous variant's, starting at `0`.
+    pub fn discriminants(&self) -> Vec<i64> {
+        let mut next = 0;
+        self.variants
+            .iter()
+            .map(|variant| {
+                let discriminant = variant.discriminant.unwrap_or(next);
+                next = discriminant.wrapping_add(1);
+                discriminant
+            })
+            .collect()
+    }
+
+    /// The JSON representation of the variants, chosen by the `@tag` and `@untagged`
+    /// annotations.
+    pub fn tagging(&self) -> EnumTagging<'_> {
+        for annotation in &self.annotations {
+            match (annotation.name.as_str(), annotation.args.as_slice()) {
+                ("tag", [tag]) => return EnumTagging::Internal { tag },
+                ("tag", [tag, content]) => return EnumTagging::Adjacent { tag, content },
+                ("untagged", []) => return EnumTagging::Untagged,
+                _ => {}
+            }
+        }
+        EnumTagging::External
+    }
+
     /// Iterate over all complex variants.
     ///
     /// Complex variants are all that are not simple.
@@ -80,6 +831,19 @@ impl EnumDef {
         self.variants.iter().filter(|v| !v.is_simple())
     }
 
+    /// The HTTP status codes of all variants annotated with `@status(code)`,
+    /// sorted and deduplicated.
+    pub fn http_statuses(&self) -> Vec<u16> {
+        let mut statuses: Vec<_> = self
+            .variants
+            .iter()
+            .filter_map(VariantDef::http_status)
+            .collect();
+        statuses.sort_unstable();
+        statuses.dedup();
+        statuses
+    }
+
     /// Iterate over all simple variants.
     ///
     /// C-style enum variants are considered simple.
@@ -88,8 +852,77 @@ impl EnumDef {
     }
 }
 
+/// The JSON representation of the variants of an enum, like serde's enum representations.
+///
+/// Simple variants of externally tagged enums are just their name, e.g. `"Red"`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EnumTagging<'a> {
+    /// `{"Variant": content}`, the default.
+    External,
+    /// `{"tag": "Variant", ...fields}`, selected by `@tag("tag")`. Only simple and struct
+    /// variants are allowed, the fields of the latter are next to the tag.
+    Internal { tag: &'a str },
+    /// `{"tag": "Variant", "content": content}`, selected by `@tag("tag", "content")`. Simple
+    /// variants have no content.
+    Adjacent { tag: &'a str, content: &'a str },
+    /// Just the content, selected by `@untagged`. Simple variants are `null`, values are
+    /// decoded as the first variant that matches.
+    Untagged,
+}
+
+/// A flags definition, a set of flags represented as the list of the names of the flags in it.
+/// Example:
+/// ```text
+/// flags Permissions { Read, Write, Admin }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagsDef {
+    /// Name of the flags type.
+    pub name: Ident,
+    /// The flags, in order.
+    pub flags: Vec<FlagDef>,
+    /// Documentation comment.
+    pub doc_comment: Option<String>,
+    /// Annotations of the flags type.
+    pub annotations: Vec<Annotation>,
+}
+
+impl FlagsDef {
+    /// The Rust attributes emitted verbatim on the generated flags type, as annotated with
+    /// `@rust_attr("#[attr]")`.
+    pub fn rust_attrs(&self) -> Vec<&str> {
+        rust_attrs(&self.annotations)
+    }
+}
+
+/// A flag of a flags definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagDef {
+    /// Name of the flag.
+    pub name: String,
+    /// Documentation comment.
+    pub doc_comment: Option<String>,
+    /// The annotations of the flag, e.g. `@json_name("read")`.
+    pub annotations: Vec<Annotation>,
+}
+
+impl FlagDef {
+    /// The name of the flag on the wire, if annotated with `@json_name("name")`.
+    pub fn json_name(&self) -> Option<&str> {
+        json_name(&self.annotations)
+    }
+
+    /// The name of the flag on the wire: its `@json_name`, otherwise its name converted to
+    /// `casing`.
+    pub fn wire_name(&self, casing: WireCasing) -> String {
+        self.json_name()
+            .map(str::to_owned)
+            .unwrap_or_else(|| casing.variant_name(&self.name))
+    }
+}
+
 /// A variant definition.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VariantDef {
     /// Name of the variant.
     pub name: String,
@@ -97,10 +930,15 @@ pub struct VariantDef {
     pub variant_type: VariantType,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// The annotations of the variant. (example: `@status(404)`)
+    pub annotations: Vec<Annotation>,
+    /// The explicit discriminant of a simple variant, e.g. `1` of `Red = 1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminant: Option<i64>,
 }
 
 /// An (enum-)variant type.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VariantType {
     /// Simple C-style variant.
     Simple,
@@ -130,55 +968,409 @@ impl VariantType {
 
 impl VariantDef {
     /// Returns whether or not a variant is simple.
-    fn is_simple(&self) -> bool {
-        if let VariantType::Simple = self.variant_type {
-            true
-        } else {
-            false
-        }
+    pub fn is_simple(&self) -> bool {
+        matches!(self.variant_type, VariantType::Simple)
+    }
+
+    /// The Rust attributes emitted verbatim on the generated variant, as annotated with
+    /// `@rust_attr("#[attr]")`.
+    pub fn rust_attrs(&self) -> Vec<&str> {
+        rust_attrs(&self.annotations)
+    }
+
+    /// The name of the variant on the wire, if annotated with `@json_name("name")`.
+    pub fn json_name(&self) -> Option<&str> {
+        json_name(&self.annotations)
     }
+
+    /// The name of the variant on the wire: its `@json_name`, otherwise its name converted to
+    /// `casing`.
+    pub fn wire_name(&self, casing: WireCasing) -> String {
+        self.json_name()
+            .map(str::to_owned)
+            .unwrap_or_else(|| casing.variant_name(&self.name))
+    }
+
+    /// The HTTP status code of the variant when returned as a domain error,
+    /// if annotated with `@status(code)`.
+    pub fn http_status(&self) -> Option<u16> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "status")
+            .map(|a| a.args[0].parse().expect("validated by parser"))
+    }
+}
+
+/// The arguments of the `@rust_attr("#[attr]")` annotations among `annotations`.
+fn rust_attrs(annotations: &[Annotation]) -> Vec<&str> {
+    annotations
+        .iter()
+        .filter(|a| a.name == "rust_attr")
+        .map(|a| a.args[0].as_str())
+        .collect()
+}
+
+/// The argument of the `@json_name("name")` annotation among `annotations`, if any.
+fn json_name(annotations: &[Annotation]) -> Option<&str> {
+    annotations
+        .iter()
+        .find(|a| a.name == "json_name")
+        .map(|a| a.args[0].as_str())
+}
+
+/// An annotation attached to a definition.
+/// Example:
+/// ```text
+/// @status(404)
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    /// The annotation name without the leading `@`. (example: `status`)
+    pub name: String,
+    /// The annotation arguments. (example: `404`)
+    pub args: Vec<String>,
 }
 
 /// A service definition.
 /// Example:
 /// ```text
 /// /// Monster management service.
+/// @auth
 /// service MonsterApi {
 ///    GET  /monsters -> vec[Monster],
 ///    POST /monsters -> MonsterData -> result[Monster][MonsterError]
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceDef {
     /// The service name. (example: `MonsterApi`)
-    pub name: String,
+    pub name: Ident,
     /// The doc comment of the service. (example: `Monster management service.`)
     pub doc_comment: Option<String>,
+    /// The annotations of the service. (example: `@auth`)
+    pub annotations: Vec<Annotation>,
     /// The service endpoints. (example: see struct `ServiceEndpoint`)
     pub endpoints: Vec<ServiceEndpoint>,
 }
 
+impl ServiceDef {
+    /// Whether the service carries the annotation `@name`.
+    pub fn has_annotation(&self, name: &str) -> bool {
+        self.annotations.iter().any(|a| a.name == name)
+    }
+
+    /// Where `endpoint` is in the spec, e.g. `MonsterApi: GET /monsters/{id}`.
+    pub fn endpoint_location(&self, endpoint: &ServiceEndpoint) -> String {
+        format!(
+            "{}: {} {}",
+            self.name,
+            endpoint.route.http_method_as_str(),
+            endpoint.route.path()
+        )
+    }
+
+    /// The encoding of the request and response bodies of the service sent by clients,
+    /// `Json` unless annotated with `@format(name)`, the first of several formats.
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_formats()
+            .first()
+            .copied()
+            .unwrap_or(WireFormat::Json)
+    }
+
+    /// The encodings the service accepts besides JSON, as annotated with `@format(name, ..)`,
+    /// e.g. `@format(cbor, msgpack)`. Servers negotiate the encoding per request.
+    pub fn wire_formats(&self) -> Vec<WireFormat> {
+        let mut formats = Vec::new();
+        let annotated = self
+            .annotations
+            .iter()
+            .filter(|a| a.name == "format")
+            .flat_map(|a| &a.args)
+            .map(|arg| WireFormat::from_annotation_arg(arg).expect("validated by parser"));
+        for format in annotated {
+            if format != WireFormat::Json && !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+        formats
+    }
+
+    /// The origins allowed to access the service from browsers, as annotated with
+    /// `@cors(origins = ["https://app.example.com"])`, where `*` allows any origin.
+    pub fn cors_origins(&self) -> Option<Vec<&str>> {
+        self.annotations.iter().find(|a| a.name == "cors").map(|a| {
+            a.args
+                .iter()
+                .map(|arg| arg.trim_start_matches("origins="))
+                .collect()
+        })
+    }
+
+    /// Whether `endpoint` requires an authenticated principal,
+    /// i.e., either the service or the endpoint itself is annotated with `@auth`.
+    pub fn endpoint_requires_auth(&self, endpoint: &ServiceEndpoint) -> bool {
+        self.has_annotation("auth") || endpoint.has_annotation("auth")
+    }
+}
+
+/// The encoding of request and response bodies of a service.
+/// Example:
+/// ```text
+/// @format(cbor)
+/// ```
+/// results in `Cbor`, and `@format(cbor, msgpack)` in `Cbor` and `Msgpack`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum WireFormat {
+    /// JSON only.
+    Json,
+    /// [CBOR](https://cbor.io) or JSON, negotiated by the `Content-Type` and `Accept` headers.
+    Cbor,
+    /// [MessagePack](https://msgpack.org) or JSON, negotiated like `Cbor`.
+    Msgpack,
+}
+
+impl WireFormat {
+    /// Parses an argument of a `@format(name, ..)` annotation.
+    pub fn from_annotation_arg(arg: &str) -> Option<WireFormat> {
+        match arg {
+            "json" => Some(WireFormat::Json),
+            "cbor" => Some(WireFormat::Cbor),
+            "msgpack" => Some(WireFormat::Msgpack),
+            _ => None,
+        }
+    }
+
+    /// The argument of the `@format(name)` annotation selecting this format.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WireFormat::Json => "json",
+            WireFormat::Cbor => "cbor",
+            WireFormat::Msgpack => "msgpack",
+        }
+    }
+}
+
+/// A trait that the Rust backend derives for generated types besides `Debug`, `Clone` and the
+/// serde traits.
+/// Example:
+/// ```text
+/// @derive(PartialEq, Hash)
+/// ```
+/// results in `PartialEq` and `Hash`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Derive {
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    /// The default value of each field, or the first variant of enums.
+    Default,
+}
+
+impl Derive {
+    /// All derivable traits, in the order they are derived.
+    pub const ALL: &'static [Derive] = &[
+        Derive::PartialEq,
+        Derive::Eq,
+        Derive::Hash,
+        Derive::PartialOrd,
+        Derive::Ord,
+        Derive::Default,
+    ];
+
+    /// Parses an argument of a `@derive(Trait, ..)` annotation.
+    pub fn from_annotation_arg(arg: &str) -> Option<Derive> {
+        Derive::ALL.iter().copied().find(|d| d.as_str() == arg)
+    }
+
+    /// The name of the trait.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Derive::PartialEq => "PartialEq",
+            Derive::Eq => "Eq",
+            Derive::Hash => "Hash",
+            Derive::PartialOrd => "PartialOrd",
+            Derive::Ord => "Ord",
+            Derive::Default => "Default",
+        }
+    }
+
+    /// The traits that deriving this trait requires, e.g. `PartialEq` for `Eq`.
+    pub fn required(self) -> &'static [Derive] {
+        match self {
+            Derive::Eq | Derive::PartialOrd => &[Derive::PartialEq],
+            Derive::Ord => &[Derive::PartialEq, Derive::Eq, Derive::PartialOrd],
+            Derive::PartialEq | Derive::Hash | Derive::Default => &[],
+        }
+    }
+}
+
+impl fmt::Display for Derive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// An endpoint within a service definition.
 /// Example:
 /// ```text
 /// /// Retrieve all monsters.
 /// GET /monsters -> vec[Monster],
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceEndpoint {
     /// The doc comment of the endpoint. (example: `Retrieve all monsters.`)
     pub doc_comment: Option<String>,
+    /// The annotations of the endpoint. (example: `@auth`)
+    pub annotations: Vec<Annotation>,
     /// The route of the endpoint. (example: see struct `ServiceRoute`)
     pub route: ServiceRoute,
 }
 
+impl ServiceEndpoint {
+    /// Whether the endpoint carries the annotation `@name`.
+    pub fn has_annotation(&self, name: &str) -> bool {
+        self.annotations.iter().any(|a| a.name == name)
+    }
+
+    /// Whether the endpoint is annotated with `@internal`, i.e., omitted from the documentation.
+    pub fn is_internal(&self) -> bool {
+        self.has_annotation("internal")
+    }
+
+    /// Whether the endpoint is annotated with `@paginated`, i.e., returns the items of a
+    /// collection one page at a time.
+    ///
+    /// The query of a paginated endpoint is a struct with a `cursor: option[str]` field, and it
+    /// returns a struct with an `items: list[T]` and a `next_cursor: option[str]` field. The
+    /// first page is requested without cursor, every further page with the `next_cursor` of the
+    /// preceding page, which is `null` for the last page.
+    pub fn is_paginated(&self) -> bool {
+        self.has_annotation("paginated")
+    }
+
+    /// Whether the endpoint is annotated with `@idempotent`, i.e., requests carry an
+    /// `Idempotency-Key` header and repeated requests with the same key are answered with the
+    /// response to the first one instead of running the handler again.
+    ///
+    /// Only mutating endpoints, i.e., all but `GET` endpoints, are idempotent.
+    pub fn is_idempotent(&self) -> bool {
+        self.has_annotation("idempotent")
+    }
+
+    /// Whether the endpoint is annotated with `@uncompressed`, i.e., its responses are never
+    /// compressed, e.g. because they are compressed already.
+    pub fn is_uncompressed(&self) -> bool {
+        self.has_annotation("uncompressed")
+    }
+
+    /// The feature flag gating the endpoint at runtime, if annotated with `@flag("name")`.
+    pub fn flag(&self) -> Option<&str> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "flag")
+            .map(|a| a.args[0].as_str())
+    }
+
+    /// The maximum size of request bodies in bytes, if annotated with `@max_body_size(size)`,
+    /// e.g. `@max_body_size(1MB)`.
+    pub fn max_body_size(&self) -> Option<u64> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "max_body_size")
+            .map(|a| parse_byte_size(&a.args[0]).expect("validated by parser"))
+    }
+
+    /// The time in milliseconds after which requests are cancelled, if annotated with
+    /// `@timeout(duration)`, e.g. `@timeout(30s)`.
+    pub fn timeout_ms(&self) -> Option<u64> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "timeout")
+            .map(|a| parse_duration_ms(&a.args[0]).expect("validated by parser"))
+    }
+
+    /// The latency target of the endpoint, if annotated with `@slo(pNN = duration)`.
+    pub fn slo(&self) -> Option<Slo> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "slo")
+            .map(|a| Slo::from_annotation_arg(&a.args[0]).expect("validated by parser"))
+    }
+}
+
+/// A latency service level objective of an endpoint.
+/// Example:
+/// ```text
+/// @slo(p99 = 200ms)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Slo {
+    /// The percentile of requests that must meet the target. (example: `99.0`, `99.9` for `p999`)
+    pub percentile: f64,
+    /// The latency target in milliseconds. (example: `200`)
+    pub target_ms: u64,
+}
+
+impl Slo {
+    /// Parses the argument of an `@slo` annotation without whitespace, e.g. `p99=200ms`.
+    /// Durations are given in `ms` or `s`.
+    pub fn from_annotation_arg(arg: &str) -> Option<Slo> {
+        let (percentile, target) = arg.split_once('=')?;
+        let digits = percentile.strip_prefix('p')?;
+        if digits.len() < 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        // p50 = 50%, p999 = 99.9%
+        let percentile = digits.parse::<f64>().ok()? / 10f64.powi(digits.len() as i32 - 2);
+
+        Some(Slo {
+            percentile,
+            target_ms: parse_duration_ms(target)?,
+        })
+    }
+}
+
+/// Splits `arg` into its leading number and the unit after it, e.g. `200` and `ms`.
+fn split_unit(arg: &str) -> Option<(u64, &str)> {
+    let unit_start = arg.find(|c: char| !c.is_ascii_digit())?;
+    Some((arg[..unit_start].parse().ok()?, &arg[unit_start..]))
+}
+
+/// Parses a duration in `ms` or `s`, e.g. `200ms`, into milliseconds.
+pub(crate) fn parse_duration_ms(arg: &str) -> Option<u64> {
+    match split_unit(arg)? {
+        (value, "ms") => Some(value),
+        (value, "s") => value.checked_mul(1000),
+        _ => None,
+    }
+}
+
+/// Parses a size in `B`, `KB`, `MB` or `GB`, or in `KiB`, `MiB` or `GiB`, e.g. `1MB`, into bytes.
+pub(crate) fn parse_byte_size(arg: &str) -> Option<u64> {
+    let (value, unit) = split_unit(arg)?;
+    let factor: u64 = match unit {
+        "B" => 1,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return None,
+    };
+    value.checked_mul(factor)
+}
+
 /// And endpoint's route.
 /// Example:
 /// ```text
 /// GET  /monsters?{GetMonstersQuery} -> vec[Monster],
 /// POST /monsters -> MonsterData -> result[Monster][MonsterError]
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServiceRoute {
     /// A GET endpoint.
     Get {
@@ -186,8 +1378,13 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// The header parameters.
+        headers: Vec<HeaderParam>,
         /// The route return type.
         ret: TypeIdent,
+        /// The headers of responses. (example: `with header "ETag": str`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        response_headers: Vec<HeaderParam>,
     },
     /// A POST endpoint.
     Post {
@@ -195,10 +1392,17 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// The header parameters.
+        headers: Vec<HeaderParam>,
         /// The POST body type. (example: `MonsterData`)
         body: TypeIdent,
+        /// How the POST body is encoded.
+        body_kind: BodyKind,
         /// The route return type.
         ret: TypeIdent,
+        /// The headers of responses. (example: `with header "ETag": str`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        response_headers: Vec<HeaderParam>,
     },
     /// A DELETE endpoint
     Delete {
@@ -206,8 +1410,13 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// The header parameters.
+        headers: Vec<HeaderParam>,
         /// The route return type.
         ret: TypeIdent,
+        /// The headers of responses. (example: `with header "ETag": str`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        response_headers: Vec<HeaderParam>,
     },
     /// A PUT endpoint.
     Put {
@@ -215,10 +1424,17 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// The header parameters.
+        headers: Vec<HeaderParam>,
         /// The POST body type. (example: `MonsterData`)
         body: TypeIdent,
+        /// How the POST body is encoded.
+        body_kind: BodyKind,
         /// The route return type.
         ret: TypeIdent,
+        /// The headers of responses. (example: `with header "ETag": str`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        response_headers: Vec<HeaderParam>,
     },
     /// A PATCH endpoint.
     Patch {
@@ -226,10 +1442,17 @@ pub enum ServiceRoute {
         components: Vec<ServiceRouteComponent>,
         /// The query type, if specified. (example: `GetMonstersQuery`)
         query: Option<TypeIdent>,
+        /// The header parameters.
+        headers: Vec<HeaderParam>,
         /// The POST body type. (example: `MonsterData`)
         body: TypeIdent,
+        /// How the POST body is encoded.
+        body_kind: BodyKind,
         /// The route return type.
         ret: TypeIdent,
+        /// The headers of responses. (example: `with header "ETag": str`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        response_headers: Vec<HeaderParam>,
     },
 }
 
@@ -245,6 +1468,17 @@ impl ServiceRoute {
         }
     }
 
+    /// The route components, mutably.
+    pub fn components_mut(&mut self) -> &mut Vec<ServiceRouteComponent> {
+        match self {
+            ServiceRoute::Get { components, .. } => components,
+            ServiceRoute::Delete { components, .. } => components,
+            ServiceRoute::Post { components, .. } => components,
+            ServiceRoute::Put { components, .. } => components,
+            ServiceRoute::Patch { components, .. } => components,
+        }
+    }
+
     /// The query type, if specified. (example: `GetMonstersQuery`)
     pub fn query(&self) -> &Option<TypeIdent> {
         match self {
@@ -267,6 +1501,16 @@ impl ServiceRoute {
         }
     }
 
+    pub fn return_type_mut(&mut self) -> &mut TypeIdent {
+        match self {
+            ServiceRoute::Get { ret, .. } => ret,
+            ServiceRoute::Delete { ret, .. } => ret,
+            ServiceRoute::Post { ret, .. } => ret,
+            ServiceRoute::Put { ret, .. } => ret,
+            ServiceRoute::Patch { ret, .. } => ret,
+        }
+    }
+
     pub fn request_body(&self) -> Option<&TypeIdent> {
         match self {
             ServiceRoute::Get { .. } => None,
@@ -277,6 +1521,61 @@ impl ServiceRoute {
         }
     }
 
+    /// The header parameters. See struct `HeaderParam`.
+    pub fn headers(&self) -> &Vec<HeaderParam> {
+        match self {
+            ServiceRoute::Get { headers, .. } => headers,
+            ServiceRoute::Delete { headers, .. } => headers,
+            ServiceRoute::Post { headers, .. } => headers,
+            ServiceRoute::Put { headers, .. } => headers,
+            ServiceRoute::Patch { headers, .. } => headers,
+        }
+    }
+
+    /// The headers of responses, declared after the return type.
+    pub fn response_headers(&self) -> &Vec<HeaderParam> {
+        match self {
+            ServiceRoute::Get {
+                response_headers, ..
+            } => response_headers,
+            ServiceRoute::Delete {
+                response_headers, ..
+            } => response_headers,
+            ServiceRoute::Post {
+                response_headers, ..
+            } => response_headers,
+            ServiceRoute::Put {
+                response_headers, ..
+            } => response_headers,
+            ServiceRoute::Patch {
+                response_headers, ..
+            } => response_headers,
+        }
+    }
+
+    /// How the request body is encoded, if the endpoint has a body.
+    pub fn request_body_kind(&self) -> Option<BodyKind> {
+        match self {
+            ServiceRoute::Get { .. } => None,
+            ServiceRoute::Delete { .. } => None,
+            ServiceRoute::Post { body_kind, .. } => Some(*body_kind),
+            ServiceRoute::Put { body_kind, .. } => Some(*body_kind),
+            ServiceRoute::Patch { body_kind, .. } => Some(*body_kind),
+        }
+    }
+
+    /// The path of the route, e.g. `/monsters/{id}`.
+    pub fn path(&self) -> String {
+        self.components()
+            .iter()
+            .map(|c| match c {
+                ServiceRouteComponent::Literal(lit) => format!("/{}", lit),
+                ServiceRouteComponent::Variable(var) => format!("/{{{}}}", var.name),
+                ServiceRouteComponent::Rest(var) => format!("/{{*{}}}", var.name),
+            })
+            .collect()
+    }
+
     pub fn http_method_as_str(&self) -> &'static str {
         match self {
             ServiceRoute::Get { .. } => "GET",
@@ -288,6 +1587,35 @@ impl ServiceRoute {
     }
 }
 
+/// A typed HTTP header parameter of an endpoint.
+/// Example:
+/// ```text
+/// GET /monsters header "X-Request-Id": uuid header "X-Tenant": option[str] -> vec[Monster],
+/// ```
+/// results in a required `X-Request-Id` and an optional `X-Tenant` header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderParam {
+    /// The header name. (example: `X-Request-Id`)
+    pub name: String,
+    /// The header type, an atom or an option of an atom. (example: `uuid`)
+    pub type_ident: TypeIdent,
+}
+
+/// The encoding of a request body.
+/// Example:
+/// ```text
+/// POST /monsters -> MonsterData -> Monster,
+/// POST /monsters/{id: i32}/portrait -> multipart[PortraitUpload] -> (),
+/// ```
+/// results in `Json` for the first and `Multipart` for the second endpoint.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BodyKind {
+    /// The body type, encoded as JSON.
+    Json,
+    /// The fields of the body struct, each encoded as one part of a `multipart/form-data` body.
+    Multipart,
+}
+
 /// A component of a `ServiceRoute`.
 /// Example:
 /// ```text
@@ -297,21 +1625,123 @@ impl ServiceRoute {
 /// - `Literal("monsters")
 /// - `Variable(FieldDefPair{ name: "id", type_ident: TypeIdent::BuiltIn(AtomType::Str) })`
 ///
-#[derive(Debug, Clone)]
+/// Path parameters without a name, like `{:uuid}` in `/monsters/{:uuid}`, are `Variable`s
+/// named after the literal before them, i.e., `monster`.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServiceRouteComponent {
     Literal(String),
     Variable(FieldDefPair),
+    /// A trailing `{*path: str}` segment, capturing the rest of the path with its slashes,
+    /// e.g. `css/site.css` of `/files/css/site.css` for `GET /files/{*path: str}`.
+    Rest(FieldDefPair),
+}
+
+impl ServiceRouteComponent {
+    /// The path parameter, unless the component is a literal.
+    pub fn param(&self) -> Option<&FieldDefPair> {
+        match self {
+            ServiceRouteComponent::Literal(_) => None,
+            ServiceRouteComponent::Variable(pair) | ServiceRouteComponent::Rest(pair) => Some(pair),
+        }
+    }
 }
 
 /// A field node (field definition inside struct).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldNode {
     pub pair: FieldDefPair,
     /// Documentation comment.
     pub doc_comment: Option<String>,
+    /// The annotations of the field. (example: `@renamed_from("name")`)
+    pub annotations: Vec<Annotation>,
+    /// Whether the field is `optional`, i.e., may be absent, as opposed to an `option[T]` field,
+    /// which is always present but may be `null`.
+    pub optional: bool,
 }
 
-#[derive(Debug, Clone)]
+impl FieldNode {
+    /// The type of the values of the field: `option[T]` for an `optional` field of type `T`,
+    /// which is `None` if the field is absent, otherwise the type of the field.
+    pub fn value_type(&self) -> TypeIdent {
+        if self.optional {
+            TypeIdent::Option(Box::new(self.pair.type_ident.clone()))
+        } else {
+            self.pair.type_ident.clone()
+        }
+    }
+
+    /// Like `value_type`, but `option[T]` for an `optional` field of type `option[T]`, which is
+    /// the type of the field in languages that do not tell absent fields from `null` ones.
+    pub fn flat_value_type(&self) -> TypeIdent {
+        match self.pair.type_ident {
+            TypeIdent::Option(_) => self.pair.type_ident.clone(),
+            _ => self.value_type(),
+        }
+    }
+
+    /// The former names of the field, newest first, if annotated with `@renamed_from("name")`.
+    ///
+    /// Decoders accept the former names as keys in addition to the current one, which takes precedence.
+    pub fn renamed_from(&self) -> Vec<&str> {
+        self.annotations
+            .iter()
+            .filter(|a| a.name == "renamed_from")
+            .map(|a| a.args[0].as_str())
+            .collect()
+    }
+
+    /// The Rust attributes emitted verbatim on the generated field, as annotated with
+    /// `@rust_attr("#[attr]")`.
+    pub fn rust_attrs(&self) -> Vec<&str> {
+        rust_attrs(&self.annotations)
+    }
+
+    /// The key of the field on the wire, if annotated with `@json_name("name")`.
+    pub fn json_name(&self) -> Option<&str> {
+        json_name(&self.annotations)
+    }
+
+    /// The value of the field if it is absent on the wire, if annotated with `@default(value)`,
+    /// e.g. `0`, or `unknown` for a `str` field.
+    pub fn default_value(&self) -> Option<&str> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "default")
+            .map(|a| a.args[0].as_str())
+    }
+
+    /// Whether the field is populated by the server, if annotated with `@server_set`.
+    ///
+    /// Client decoders fill it with the empty value of its type if absent, e.g. in values the
+    /// server has not stored yet.
+    pub fn is_server_set(&self) -> bool {
+        self.annotations.iter().any(|a| a.name == "server_set")
+    }
+
+    /// The name of the database column of the field in structs annotated with `@db_row`, if
+    /// annotated with `@column("name")`.
+    pub fn column(&self) -> Option<&str> {
+        self.annotations
+            .iter()
+            .find(|a| a.name == "column")
+            .map(|a| a.args[0].as_str())
+    }
+
+    /// The name of the database column of the field: its `@column`, otherwise its name.
+    pub fn column_name(&self) -> &str {
+        self.column().unwrap_or(&self.pair.name)
+    }
+
+    /// The key of the field on the wire: its `@json_name`, otherwise its name converted to `casing`.
+    pub fn wire_name(&self, casing: WireCasing) -> String {
+        self.json_name()
+            .map(str::to_owned)
+            .unwrap_or_else(|| casing.field_name(&self.pair.name))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldDefPair {
     /// Name of the field.
     pub name: String,
@@ -331,7 +1761,7 @@ impl FieldDefPair {
 }
 
 /// A type identifier.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeIdent {
     /// Built-in (atomic) type.
     BuiltIn(AtomType),
@@ -346,20 +1776,53 @@ pub enum TypeIdent {
     /// Tuple type.
     Tuple(TupleDef),
     /// Type defined in humble file.
-    UserDefined(String),
+    UserDefined(Ident),
 }
 
 impl TypeIdent {
-    pub fn user_defined(&self) -> Option<&String> {
+    pub fn user_defined(&self) -> Option<&Ident> {
         match self {
             TypeIdent::UserDefined(s) => Some(s),
             _ => None,
         }
     }
+
+    /// Call `f` on the types nested in `self`, then on `self`.
+    pub fn for_each_mut(&mut self, f: &mut impl FnMut(&mut TypeIdent)) {
+        match self {
+            TypeIdent::BuiltIn(_) | TypeIdent::UserDefined(_) => {}
+            TypeIdent::List(inner) | TypeIdent::Option(inner) => inner.for_each_mut(f),
+            TypeIdent::Result(a, b) | TypeIdent::Map(a, b) => {
+                a.for_each_mut(f);
+                b.for_each_mut(f);
+            }
+            TypeIdent::Tuple(tdef) => {
+                for ty in &mut tdef.0 {
+                    ty.for_each_mut(f);
+                }
+            }
+        }
+        f(self)
+    }
+}
+
+/// Formats the type in humblespec syntax, e.g. `map[str][list[Monster]]`.
+impl fmt::Display for TypeIdent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeIdent::BuiltIn(atom) => write!(f, "{}", atom),
+            TypeIdent::List(inner) => write!(f, "list[{}]", inner),
+            TypeIdent::Option(inner) => write!(f, "option[{}]", inner),
+            TypeIdent::Result(ok, err) => write!(f, "result[{}][{}]", ok, err),
+            TypeIdent::Map(key, value) => write!(f, "map[{}][{}]", key, value),
+            TypeIdent::Tuple(tdef) => write!(f, "{}", tdef),
+            TypeIdent::UserDefined(name) => write!(f, "{}", name),
+        }
+    }
 }
 
 /// An atomic type.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AtomType {
     /// Empty type
     Empty,
@@ -369,6 +1832,10 @@ pub enum AtomType {
     I32,
     /// Unsigned 32-bit integer.
     U32,
+    /// Signed 64-bit integer.
+    I64,
+    /// Unsigned 64-bit integer.
+    U64,
     /// Unsigned 8-bit integer.
     U8,
     /// 64-bit IEEE floating-point number.
@@ -385,8 +1852,29 @@ pub enum AtomType {
     Bytes,
 }
 
+impl fmt::Display for AtomType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keyword = match self {
+            AtomType::Empty => "()",
+            AtomType::Str => "str",
+            AtomType::I32 => "i32",
+            AtomType::U32 => "u32",
+            AtomType::I64 => "i64",
+            AtomType::U64 => "u64",
+            AtomType::U8 => "u8",
+            AtomType::F64 => "f64",
+            AtomType::Bool => "bool",
+            AtomType::DateTime => "datetime",
+            AtomType::Date => "date",
+            AtomType::Uuid => "uuid",
+            AtomType::Bytes => "bytes",
+        };
+        write!(f, "{}", keyword)
+    }
+}
+
 /// A tuple definition.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TupleDef(pub Vec<TypeIdent>);
 
 impl TupleDef {
@@ -395,3 +1883,16 @@ impl TupleDef {
         &self.0
     }
 }
+
+impl fmt::Display for TupleDef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, element) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", element)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,83 @@
+//! The AST of specs as versioned JSON, for tooling that consumes specs without parsing them,
+//! e.g. linters, diff tools or generators written in other languages.
+//!
+//! A dump is an object holding the `AST_VERSION` and the spec as parsed, i.e., with includes
+//! and embeds resolved and partial structs derived:
+//! ```
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! let spec = humblegen::parse("struct Monster { name: str }".as_bytes())?;
+//! let json = humblegen::ast_json::dump_ast(&spec);
+//! assert!(json.starts_with("{\n  \"ast_version\": 1,\n  \"spec\": ["));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `spec` is the `serde_json` serialization of `ast::Spec`. Dumps are deterministic, and
+//! the version is incremented whenever a change of the AST changes the JSON of existing specs.
+//!
+//! `load_ast` reads dumps back, e.g. to cache parsing separately from code generation. Dumps
+//! may be written by other tools, so loaded specs are validated like parsed specs.
+
+use crate::{LibError, Spec};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The version of the JSON representation of the AST.
+pub const AST_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct AstDumpRef<'a> {
+    ast_version: u32,
+    spec: &'a Spec,
+}
+
+/// The AST of `spec` as pretty-printed JSON, see the module documentation.
+pub fn dump_ast(spec: &Spec) -> String {
+    let dump = AstDumpRef {
+        ast_version: AST_VERSION,
+        spec,
+    };
+    serde_json::to_string_pretty(&dump).expect("the AST is serializable")
+}
+
+#[derive(Deserialize)]
+struct AstDump {
+    ast_version: u32,
+    // deserialized after checking the version, whose mismatch explains errors better
+    spec: serde_json::Value,
+}
+
+/// Load a spec from its AST JSON dumped by `dump_ast`.
+///
+/// Fails if the dump is of another `AST_VERSION` or its spec breaks a rule of the language,
+/// e.g. has an annotation with invalid arguments or references undefined types.
+pub fn load_ast(json: &str) -> Result<Spec, LibError> {
+    let spec = load_ast_unresolved(json)?;
+    crate::resolve::check_references(&spec)?;
+    Ok(spec)
+}
+
+/// Like `load_ast`, but the spec may reference types it does not define, e.g. the common types
+/// of a workspace.
+pub(crate) fn load_ast_unresolved(json: &str) -> Result<Spec, LibError> {
+    let dump: AstDump =
+        serde_json::from_str(json).map_err(|e| LibError::InvalidAst(e.to_string()))?;
+    if dump.ast_version != AST_VERSION {
+        return Err(LibError::InvalidAst(format!(
+            "unsupported AST version {}, expected {}",
+            dump.ast_version, AST_VERSION
+        )));
+    }
+    let mut spec: Spec =
+        serde_json::from_value(dump.spec).map_err(|e| LibError::InvalidAst(e.to_string()))?;
+    crate::parser::validate::validate_spec(&spec)?;
+    spec.intern_idents();
+    Ok(spec)
+}
+
+/// Load a spec from the AST JSON file at `path`, see `load_ast`.
+pub fn load_ast_file<P: AsRef<Path>>(path: P) -> Result<Spec, LibError> {
+    let path = path.as_ref();
+    let json = std::fs::read_to_string(path).map_err(LibError::file(path))?;
+    load_ast(&json)
+}
@@ -1,3 +1,34 @@
 pub mod docs;
 pub mod elm;
+pub mod graphql;
+pub mod kotlin;
+pub mod protobuf;
 pub mod rust;
+pub mod swift;
+
+use crate::{ast, LibError, Spec};
+
+/// Check that the `multipart[T]` request bodies of `spec` are structs, whose fields are the
+/// parts, which `backend` requires to generate them.
+pub(crate) fn check_multipart_bodies(spec: &Spec, backend: &'static str) -> Result<(), LibError> {
+    let index = spec.index();
+    for service in spec.iter().filter_map(ast::SpecItem::service_def) {
+        for endpoint in &service.endpoints {
+            if endpoint.route.request_body_kind() != Some(ast::BodyKind::Multipart) {
+                continue;
+            }
+            let is_struct = match endpoint.route.request_body() {
+                Some(ast::TypeIdent::UserDefined(name)) => index.struct_def(name).is_some(),
+                _ => false,
+            };
+            if !is_struct {
+                return Err(LibError::UnsupportedFeature {
+                    feature: "multipart bodies that are not structs".to_owned(),
+                    backend,
+                    span: service.endpoint_location(endpoint),
+                });
+            }
+        }
+    }
+    Ok(())
+}
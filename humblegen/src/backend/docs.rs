@@ -3,9 +3,8 @@
 // Reading this file, you should be aware that we use `format!(include_str!(...), ...)`
 // as a simple HTML template engine. Since `format!` does not support loops,
 // listings are generated using `...map(|thing| format!(include_str!(...), ...)).join("")`.
-use crate::{ast, LibError};
+use crate::{ast, workspace::Workspace, LibError};
 
-use anyhow::Result;
 use comrak::{markdown_to_html, ComrakOptions};
 use itertools::Itertools;
 
@@ -14,9 +13,13 @@ use std::{fmt, fs::File, path::Path};
 
 use ast::Spec;
 
+const BACKEND_NAME: &str = "docs";
+
 #[derive(Default)]
 struct Context {
     body: String,
+    /// The title of the page, from the `meta` block of the spec.
+    title: String,
 }
 
 /// Wrapper struct which will emit the HTML-escaped version of the contained
@@ -58,10 +61,11 @@ impl<'a> fmt::Display for Escape<'a> {
 
 impl Context {
     fn add_spec(&mut self, spec: &ast::Spec) -> &mut Self {
+        self.add_meta(spec.meta());
+
         let spec_html = spec
             .iter()
-            .map(|item| item.service_def())
-            .filter_map(|service| service)
+            .filter_map(|item| item.service_def())
             .map(|service| {
                 format!(
                     include_str!("docs/service.html"),
@@ -70,7 +74,8 @@ impl Context {
                         service.doc_comment.as_deref().unwrap_or(""),
                         &ComrakOptions::default()
                     ),
-                    serviceEndpoints = self.endpoints_to_html(&service.endpoints),
+                    serviceCors = Self::cors_to_html(service),
+                    serviceEndpoints = self.endpoints_to_html(service),
                 )
             })
             .join("\n");
@@ -79,7 +84,7 @@ impl Context {
 
         let usertype_html = format!(
             include_str!("docs/user_defined_type_listing.html"),
-            userDefinedTypes = self.user_defined_types_to_html(&spec)
+            userDefinedTypes = self.user_defined_types_to_html(spec)
         );
 
         self.body.push_str(&usertype_html);
@@ -87,8 +92,10 @@ impl Context {
         self
     }
 
+    /// The user defined types, each preceded by the types it uses, see `resolve::topological_order`.
     fn user_defined_types_to_html(&mut self, spec: &ast::Spec) -> String {
-        spec.iter()
+        crate::resolve::topological_order(spec)
+            .into_iter()
             .filter_map(|item| match item {
                 ast::SpecItem::StructDef(struct_def) => Some(format!(
                     include_str!("docs/user_defined_type.html"),
@@ -101,6 +108,20 @@ impl Context {
                     codeSamples = Self::struct_definition_to_html(struct_def),
                     id = Self::link_to_user_defined_type(&struct_def.name)
                 )),
+                ast::SpecItem::TupleStructDef(tuple_def) => Some(format!(
+                    include_str!("docs/user_defined_type.html"),
+                    kind = "tuple structure",
+                    name = Escape(&tuple_def.name),
+                    description = markdown_to_html(
+                        tuple_def.doc_comment.as_deref().unwrap_or(""),
+                        &ComrakOptions::default()
+                    ),
+                    codeSamples = Self::tabbed_navigation_to_html(vec![(
+                        "Language Agnostic",
+                        Self::tuple_def_to_html(&tuple_def.elements)
+                    )]),
+                    id = Self::link_to_user_defined_type(&tuple_def.name)
+                )),
                 ast::SpecItem::EnumDef(enum_def) => Some(format!(
                     include_str!("docs/user_defined_type.html"),
                     kind = "enumeration",
@@ -112,6 +133,20 @@ impl Context {
                     codeSamples = Self::enum_definition_to_html(enum_def),
                     id = Self::link_to_user_defined_type(&enum_def.name)
                 )),
+                ast::SpecItem::FlagsDef(flags_def) => Some(format!(
+                    include_str!("docs/user_defined_type.html"),
+                    kind = "flags",
+                    name = Escape(&flags_def.name),
+                    description = markdown_to_html(
+                        flags_def.doc_comment.as_deref().unwrap_or(""),
+                        &ComrakOptions::default()
+                    ),
+                    codeSamples = Self::tabbed_navigation_to_html(vec![(
+                        "Language Agnostic",
+                        Self::generate_flags_table(flags_def)
+                    )]),
+                    id = Self::link_to_user_defined_type(&flags_def.name)
+                )),
                 _ => None,
             })
             .join("\n")
@@ -145,17 +180,46 @@ impl Context {
                     format!(
                         include_str!("docs/typedef_table_struct_field.html"),
                         fieldName = Escape(&field_node.pair.name),
-                        fieldType = Self::type_ident_to_html(&field_node.pair.type_ident),
+                        fieldType = Self::field_type_to_html(field_node),
                         fieldComment = markdown_to_html(
-                            &field_node.doc_comment.as_deref().unwrap_or(""),
+                            field_node.doc_comment.as_deref().unwrap_or(""),
                             &ComrakOptions::default()
-                        )
+                        ),
+                        fieldRenamedFrom = format!(
+                            "{}{}",
+                            Self::json_name_to_html(field_node.json_name()),
+                            Self::renamed_from_to_html(field_node)
+                        ),
                     )
                 })
                 .join("")
         )
     }
 
+    fn json_name_to_html(json_name: Option<&str>) -> String {
+        match json_name {
+            Some(json_name) => format!(
+                r#"<div class="field--json-name">JSON name <code>{}</code>.</div>"#,
+                Escape(json_name)
+            ),
+            None => String::new(),
+        }
+    }
+
+    fn renamed_from_to_html(field_node: &ast::FieldNode) -> String {
+        let renamed_from = field_node.renamed_from();
+        if renamed_from.is_empty() {
+            return String::new();
+        }
+        format!(
+            r#"<div class="field--renamed-from">Formerly {}.</div>"#,
+            renamed_from
+                .iter()
+                .map(|n| format!("<code>{}</code>", Escape(n)))
+                .join(", ")
+        )
+    }
+
     fn struct_definition_to_html(struct_def: &ast::StructDef) -> String {
         // TODO: make a common interface/trait for all languages?! why does this not exist in the first place
         let tabs = vec![(
@@ -172,29 +236,29 @@ impl Context {
             tableBody = struct_def
                 .variants
                 .iter()
-                .map(|variant| {
+                .zip(struct_def.discriminants())
+                .map(|(variant, discriminant)| {
                     match &variant.variant_type {
                         ast::VariantType::Simple => format!(
                             include_str!("docs/typedef_table_enum_field.html"),
                             variantNestingDepth = 0,
                             variantNestingParent = "",
                             variantName = Escape(&variant.name),
-                            variantValue = "<i>empty</i>",
-                            variantComment = markdown_to_html(
-                                &variant.doc_comment.as_deref().unwrap_or(""),
-                                &ComrakOptions::default()
-                            )
+                            // numeric enums are their discriminants on the wire
+                            variantValue = if struct_def.is_numeric() {
+                                format!("<code>{}</code>", discriminant)
+                            } else {
+                                "<i>empty</i>".to_owned()
+                            },
+                            variantComment = Self::variant_comment_to_html(variant)
                         ),
                         ast::VariantType::Newtype(ty) => format!(
                             include_str!("docs/typedef_table_enum_field.html"),
                             variantNestingDepth = 0,
                             variantNestingParent = "",
                             variantName = Escape(&variant.name),
-                            variantValue = Self::type_ident_to_html(&ty),
-                            variantComment = markdown_to_html(
-                                &variant.doc_comment.as_deref().unwrap_or(""),
-                                &ComrakOptions::default()
-                            )
+                            variantValue = Self::type_ident_to_html(ty),
+                            variantComment = Self::variant_comment_to_html(variant)
                         ),
 
                         ast::VariantType::Tuple(tuple) => format!(
@@ -203,10 +267,7 @@ impl Context {
                             variantNestingParent = "",
                             variantName = Escape(&variant.name),
                             variantValue = Self::tuple_def_to_html(tuple),
-                            variantComment = markdown_to_html(
-                                &variant.doc_comment.as_deref().unwrap_or(""),
-                                &ComrakOptions::default()
-                            )
+                            variantComment = Self::variant_comment_to_html(variant)
                         ),
                         ast::VariantType::Struct(fields) => {
                             let mut rows = vec![format!(
@@ -215,10 +276,7 @@ impl Context {
                                 variantNestingParent = "",
                                 variantName = Escape(&variant.name),
                                 variantValue = "<i>anonymous structure</i>",
-                                variantComment = markdown_to_html(
-                                    &variant.doc_comment.as_deref().unwrap_or(""),
-                                    &ComrakOptions::default()
-                                )
+                                variantComment = Self::variant_comment_to_html(variant)
                             )];
 
                             for field in fields.iter() {
@@ -227,11 +285,12 @@ impl Context {
                                     variantNestingDepth = 1,
                                     variantNestingParent = struct_def.name,
                                     variantName = Escape(&field.pair.name),
-                                    variantValue = Self::type_ident_to_html(&field.pair.type_ident),
-                                    variantComment = markdown_to_html(
-                                        &field.doc_comment.as_deref().unwrap_or(""),
-                                        &ComrakOptions::default(),
-                                    ),
+                                    variantValue = Self::field_type_to_html(field),
+                                    variantComment =
+                                        markdown_to_html(
+                                            field.doc_comment.as_deref().unwrap_or(""),
+                                            &ComrakOptions::default(),
+                                        ) + &Self::json_name_to_html(field.json_name()),
                                 ));
                             }
                             rows.join("")
@@ -242,6 +301,41 @@ impl Context {
         )
     }
 
+    /// The variant's doc comment, preceded by its HTTP status code when returned as a domain error.
+    fn variant_comment_to_html(variant: &ast::VariantDef) -> String {
+        let comment = markdown_to_html(
+            variant.doc_comment.as_deref().unwrap_or(""),
+            &ComrakOptions::default(),
+        );
+        let comment = match variant.http_status() {
+            Some(status) => format!("<p>HTTP status <code>{}</code></p>{}", status, comment),
+            None => comment,
+        };
+        comment + &Self::json_name_to_html(variant.json_name())
+    }
+
+    /// The flags of a flags type, whose values are lists of the names of the contained flags.
+    fn generate_flags_table(flags_def: &ast::FlagsDef) -> String {
+        format!(
+            include_str!("docs/typedef_table_enum.html"),
+            tableBody = flags_def
+                .flags
+                .iter()
+                .map(|flag| format!(
+                    include_str!("docs/typedef_table_enum_field.html"),
+                    variantNestingDepth = 0,
+                    variantNestingParent = "",
+                    variantName = Escape(&flag.name),
+                    variantValue = "<i>flag</i>",
+                    variantComment = markdown_to_html(
+                        flag.doc_comment.as_deref().unwrap_or(""),
+                        &ComrakOptions::default()
+                    ) + &Self::json_name_to_html(flag.json_name())
+                ))
+                .join("")
+        )
+    }
+
     fn enum_definition_to_html(enum_def: &ast::EnumDef) -> String {
         // TODO: make a common interface/trait for all languages?! why does this not exist in the first place
         let tabs = vec![(
@@ -252,9 +346,11 @@ impl Context {
         Self::tabbed_navigation_to_html(tabs)
     }
 
-    fn endpoints_to_html(&mut self, endpoints: &[ast::ServiceEndpoint]) -> String {
-        endpoints
+    fn endpoints_to_html(&mut self, service: &ast::ServiceDef) -> String {
+        service
+            .endpoints
             .iter()
+            .filter(|endpoint| !endpoint.is_internal())
             .map(|endpoint| {
                 format!(
                     include_str!("docs/endpoint.html"),
@@ -278,18 +374,140 @@ impl Context {
                         .as_ref()
                         .map(|q| { format!("?{}", Self::type_ident_to_html(q)) })
                         .unwrap_or_default(),
+                    endpointAuth = if service.endpoint_requires_auth(endpoint) {
+                        r#"<div class="endpoint--auth">Requires authentication.</div>"#
+                    } else {
+                        ""
+                    },
+                    endpointHeaders = [
+                        Self::headers_to_html(endpoint.route.headers(), "header"),
+                        Self::headers_to_html(endpoint.route.response_headers(), "response header"),
+                        Self::idempotency_to_html(endpoint),
+                    ]
+                    .concat(),
+                    endpointSlo = [
+                        endpoint.slo().map(Self::slo_to_html).unwrap_or_default(),
+                        Self::limits_to_html(endpoint),
+                    ]
+                    .concat(),
+                    endpointFlag = endpoint.flag().map(Self::flag_to_html).unwrap_or_default(),
                     //endpointProperties = "",
                 )
             })
             .join("\n")
     }
 
+    fn slo_to_html(slo: ast::Slo) -> String {
+        format!(
+            r#"<div class="endpoint--slo">Latency objective: {}% of requests within {}ms.</div>"#,
+            slo.percentile, slo.target_ms
+        )
+    }
+
+    fn limits_to_html(endpoint: &ast::ServiceEndpoint) -> String {
+        let limits: Vec<_> = endpoint
+            .max_body_size()
+            .map(|size| format!("request bodies of at most {} bytes", size))
+            .into_iter()
+            .chain(
+                endpoint
+                    .timeout_ms()
+                    .map(|ms| format!("responds within {}ms", ms)),
+            )
+            .collect();
+        if limits.is_empty() {
+            return String::new();
+        }
+        format!(
+            r#"<div class="endpoint--limits">Limits: {}.</div>"#,
+            limits.join(", ")
+        )
+    }
+
+    fn idempotency_to_html(endpoint: &ast::ServiceEndpoint) -> String {
+        if !endpoint.is_idempotent() {
+            return String::new();
+        }
+        r#"<div class="endpoint--idempotent">Idempotent: requests carry a unique <code>Idempotency-Key</code> header, repeated requests with the key are answered with the response to the first one.</div>"#.to_owned()
+    }
+
+    fn flag_to_html(flag: &str) -> String {
+        format!(
+            r#"<div class="endpoint--flag">Only available if feature flag <code>{}</code> is enabled.</div>"#,
+            Escape(flag)
+        )
+    }
+
+    fn add_meta(&mut self, meta: &ast::Meta) {
+        if meta.is_empty() {
+            return;
+        }
+        let title = meta.title.as_deref().unwrap_or("API");
+        self.title = match &meta.version {
+            Some(version) => format!("{} {}", title, version),
+            None => title.to_owned(),
+        };
+
+        let mut properties = vec![];
+        if let Some(version) = &meta.version {
+            properties.push(format!("<li>Version <code>{}</code></li>", Escape(version)));
+        }
+        if let Some(base_url) = &meta.base_url {
+            properties.push(format!(
+                "<li>Base URL <code>{}</code></li>",
+                Escape(base_url)
+            ));
+        }
+        self.body.push_str(&format!(
+            include_str!("docs/meta.html"),
+            title = Escape(title),
+            properties = format!(
+                r#"<ul class="meta--properties">{}</ul>"#,
+                properties.join("")
+            ),
+        ));
+    }
+
+    fn cors_to_html(service: &ast::ServiceDef) -> String {
+        match service.cors_origins() {
+            Some(origins) => format!(
+                r#"<div class="service--cors">Accessible from browsers at origins (CORS): {}.</div>"#,
+                origins
+                    .iter()
+                    .map(|o| format!("<code>{}</code>", Escape(o)))
+                    .join(", ")
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// The `headers` of requests or responses, labeled `kind`.
+    fn headers_to_html(headers: &[ast::HeaderParam], kind: &str) -> String {
+        if headers.is_empty() {
+            return String::new();
+        }
+        format!(
+            r#"<ul class="endpoint--headers">{}</ul>"#,
+            headers
+                .iter()
+                .map(|h| format!(
+                    "<li>{} <code>{}</code>: {}</li>",
+                    kind,
+                    h.name,
+                    Self::type_ident_to_html(&h.type_ident)
+                ))
+                .join("")
+        )
+    }
+
     pub fn atom_to_html(t: ast::AtomType) -> &'static str {
         match t {
             ast::AtomType::Empty => "empty",
             ast::AtomType::Str => "string",
             ast::AtomType::I32 => "int",
             ast::AtomType::U32 => "uint",
+            ast::AtomType::I64 => "int64",
+            ast::AtomType::U64 => "uint64",
             ast::AtomType::U8 => "uint",
             ast::AtomType::F64 => "float",
             ast::AtomType::Bool => "bool",
@@ -311,20 +529,30 @@ impl Context {
         )
     }
 
+    /// The type of `field`, marked if the field is `optional`.
+    fn field_type_to_html(field: &ast::FieldNode) -> String {
+        let ty = Self::type_ident_to_html(&field.pair.type_ident);
+        if field.optional {
+            format!("<i>optional</i> {}", ty)
+        } else {
+            ty
+        }
+    }
+
     pub fn type_ident_to_html(type_ident: &ast::TypeIdent) -> String {
         match type_ident {
             ast::TypeIdent::BuiltIn(atom) => Self::atom_to_html(*atom).to_string(),
-            ast::TypeIdent::List(ty) => format!("list[{}]", Self::type_ident_to_html(&*ty)),
-            ast::TypeIdent::Option(ty) => format!("option[{}]", Self::type_ident_to_html(&*ty)),
+            ast::TypeIdent::List(ty) => format!("list[{}]", Self::type_ident_to_html(ty)),
+            ast::TypeIdent::Option(ty) => format!("option[{}]", Self::type_ident_to_html(ty)),
             ast::TypeIdent::Result(ty1, ty2) => format!(
                 "result[{},{}]",
-                Self::type_ident_to_html(&*ty1),
-                Self::type_ident_to_html(&*ty2)
+                Self::type_ident_to_html(ty1),
+                Self::type_ident_to_html(ty2)
             ),
             ast::TypeIdent::Map(ty1, ty2) => format!(
                 "map[{},{}]",
-                Self::type_ident_to_html(&*ty1),
-                Self::type_ident_to_html(&*ty2)
+                Self::type_ident_to_html(ty1),
+                Self::type_ident_to_html(ty2)
             ),
             ast::TypeIdent::Tuple(tuple) => Self::tuple_def_to_html(tuple),
             ast::TypeIdent::UserDefined(name) => format!(
@@ -344,13 +572,20 @@ impl Context {
             .iter()
             .map(|c| match c {
                 ast::ServiceRouteComponent::Literal(lit) => {
-                    format!("/<span>{}</span>", Escape(&lit))
+                    format!("/<span>{}</span>", Escape(lit))
                 }
                 ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, type_ident }) => {
                     format!(
                         "/<var><span class=\"var-bracket\">{{</span><span class=\"var-name\">{}</span><span class=\"var-ty-name-sep\">:</span><span class=\"var-ty\">{}</span><span class=\"var-bracket\">}}</span></var>",
-                        Escape(&name),
-                        Escape(&Self::type_ident_to_html(&type_ident))
+                        Escape(name),
+                        Escape(&Self::type_ident_to_html(type_ident))
+                    )
+                }
+                ast::ServiceRouteComponent::Rest(ast::FieldDefPair { name, type_ident }) => {
+                    format!(
+                        "/<var><span class=\"var-bracket\">{{</span><span class=\"var-rest\">*</span><span class=\"var-name\">{}</span><span class=\"var-ty-name-sep\">:</span><span class=\"var-ty\">{}</span><span class=\"var-bracket\">}}</span></var>",
+                        Escape(name),
+                        Escape(&Self::type_ident_to_html(type_ident))
                     )
                 }
             })
@@ -362,12 +597,19 @@ impl Context {
             .components()
             .iter()
             .map(|c| match c {
-                ast::ServiceRouteComponent::Literal(lit) => format!("/{}", Escape(&lit)),
+                ast::ServiceRouteComponent::Literal(lit) => format!("/{}", Escape(lit)),
                 ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, type_ident }) => {
                     format!(
                         "/{}:{}",
-                        Escape(&name),
-                        Escape(&Self::type_ident_to_html(&type_ident))
+                        Escape(name),
+                        Escape(&Self::type_ident_to_html(type_ident))
+                    )
+                }
+                ast::ServiceRouteComponent::Rest(ast::FieldDefPair { name, type_ident }) => {
+                    format!(
+                        "/*{}:{}",
+                        Escape(name),
+                        Escape(&Self::type_ident_to_html(type_ident))
                     )
                 }
             })
@@ -413,7 +655,7 @@ impl Context {
     }
 
     fn spec_name(&self) -> String {
-        String::new()
+        Escape(&self.title).to_string()
     }
 }
 
@@ -442,10 +684,27 @@ impl crate::CodeGenerator for Generator {
         let docs = Context::default().add_spec(spec).to_html();
 
         // TODO: support folder as output path
-        let mut outfile = File::create(&output).map_err(LibError::IoError)?;
+        let mut outfile = File::create(output).map_err(LibError::file(output))?;
         outfile
             .write_all(docs.as_bytes())
-            .map_err(LibError::IoError)?;
+            .map_err(LibError::file(output))?;
+        Ok(())
+    }
+
+    /// Writes a `{name}.html` page for every spec, including the common types it can use,
+    /// into the `output` folder.
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        if !output.is_dir() {
+            return Err(LibError::OutputMustBeFolder {
+                backend: BACKEND_NAME,
+            });
+        }
+        for ws_spec in &workspace.specs {
+            self.generate(
+                &workspace.resolved_spec(ws_spec),
+                &output.join(format!("{}.html", ws_spec.name)),
+            )?;
+        }
         Ok(())
     }
 }
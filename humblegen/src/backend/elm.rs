@@ -1,91 +1,94 @@
 //! Elm code generator.
 
-// TODO: Fix lints and remove this.
-#![allow(clippy::write_literal)]
-
-use crate::{ast, Artifact, LibError, Spec};
-use anyhow::Result;
+use crate::codegen::printer::Printer;
+use crate::strict::LossyMapping;
+use crate::{
+    ast, workspace::Workspace, Artifact, EnumRepresentation, Environment, LargeIntRepresentation,
+    LibError, MapRepresentation, Spec, WireCasing,
+};
 use inflector::cases::camelcase::to_camel_case;
-use std::io::{self, BufWriter};
+use inflector::Inflector;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::{
-    fs::{self, File},
-    io::Write,
+    fs,
     path::{Path, PathBuf},
 };
 
 const BACKEND_NAME: &str = "elm";
 
+/// Built-in types that are mapped lossily, see `crate::strict`.
+const LOSSY_MAPPINGS: &[LossyMapping] = &[
+    LossyMapping {
+        atom: ast::AtomType::U32,
+        target: "Int",
+        loss: "which is only guaranteed to hold 32-bit signed integers",
+    },
+    LossyMapping {
+        atom: ast::AtomType::U8,
+        target: "Int",
+        loss: "which does not restrict values to bytes",
+    },
+    // large integers, unless represented as strings
+    LossyMapping {
+        atom: ast::AtomType::I64,
+        target: "Int",
+        loss: "which only represents integers up to 2^53 exactly",
+    },
+    LossyMapping {
+        atom: ast::AtomType::U64,
+        target: "Int",
+        loss: "which only represents integers up to 2^53 exactly",
+    },
+];
+
+/// The lossy mappings if large integers are represented as strings, i.e., as Elm `String`s.
+const LOSSY_MAPPINGS_WITHOUT_LARGE_INTS: &[LossyMapping] = &[LOSSY_MAPPINGS[0], LOSSY_MAPPINGS[1]];
+
+/// The packages the generated code depends on, with the versions it is tested with, see
+/// `Generator::with_package`.
+const PACKAGE_DEPENDENCIES: &[(&str, &str)] = &[
+    ("danfishgold/base64-bytes", "1.0.3 <= v < 2.0.0"),
+    ("elm/bytes", "1.0.8 <= v < 2.0.0"),
+    ("elm/core", "1.0.0 <= v < 2.0.0"),
+    ("elm/http", "2.0.0 <= v < 3.0.0"),
+    ("elm/json", "1.1.3 <= v < 2.0.0"),
+    ("elm/random", "1.0.0 <= v < 2.0.0"),
+    ("elm/time", "1.0.0 <= v < 2.0.0"),
+    ("elm/url", "1.0.0 <= v < 2.0.0"),
+    ("justinmimbs/date", "3.2.1 <= v < 4.0.0"),
+    ("rtfeldman/elm-iso8601-date-strings", "1.1.3 <= v < 2.0.0"),
+];
+
+pub mod config_generation;
+pub mod conformance_generation;
 pub mod decoder_generation;
 pub mod encoder_generation;
 pub mod endpoint_generation;
+pub mod form_generation;
+pub mod route_generation;
 pub mod type_generation;
 
-pub(crate) struct IndentWriter {
-    indent: usize,
-    outstream: Box<dyn io::Write>,
-}
-
-impl IndentWriter {
-    pub(crate) fn for_file(outdir: &Path, filename: &str) -> Result<Self, LibError> {
-        let data_path = {
-            let mut p = PathBuf::from(outdir);
-            p.push(filename);
-            p
-        };
-
-        let outfile = File::create(&data_path).map_err(LibError::IoError)?;
-        let outstream = BufWriter::new(outfile);
-
-        Ok(Self {
-            outstream: Box::new(outstream),
-            indent: 0,
-        })
-    }
-
-    fn kill_indent(&mut self) {
-        self.indent = 0;
-    }
-
-    fn increase_indent(&mut self) -> String {
-        self.indent += 1;
-        self.newline()
-    }
-
-    fn decrease_indent(&mut self) -> String {
-        self.indent -= 1;
-        self.newline()
-    }
-
-    fn tabs(&self) -> String {
-        "    ".repeat(self.indent)
-    }
-
-    fn newline(&self) -> String {
-        format!("\n{}", self.tabs())
-    }
-
-    fn start_line(&mut self) -> Result<&mut dyn io::Write, LibError> {
-        write!(self.outstream, "\n{}", self.tabs())?;
-        Ok(&mut self.outstream)
-    }
-
-    fn handle(&mut self) -> &mut dyn io::Write {
-        &mut self.outstream
+/// Render `doc_comment` as documentation comment, which must precede a declaration.
+fn generate_doc_comment(doc_comment: &Option<String>) -> String {
+    match doc_comment {
+        Some(ref ds) => format!("{{-| {ds}\n-}}", ds = escape_comment(ds)),
+        None => "".to_owned(),
     }
+}
 
-    fn empty_lines(&mut self, num: usize) -> Result<(), LibError> {
-        write!(self.outstream, "{}", "\n".repeat(num + 1))?;
-        Ok(())
+/// Render `doc_comment` as regular comment, e.g. for services, which are not a declaration.
+fn generate_comment(doc_comment: &Option<String>) -> String {
+    match doc_comment {
+        Some(ref ds) => format!("{{- {ds}\n-}}", ds = escape_comment(ds)),
+        None => "".to_owned(),
     }
 }
 
-fn generate_doc_comment(_doc_comment: &Option<String>) -> String {
-    // TODO: figure out escaping rules
-    // match doc_comment {
-    //     Some(ref ds) => format!("{{-| {ds}\n-}}", ds = ds),
-    //     None => "".to_owned(),
-    // }
-    "".to_owned()
+/// `comment` without the delimiters of (nested) block comments, which would end the comment
+/// prematurely or never.
+fn escape_comment(comment: &str) -> String {
+    comment.replace("{-", "{ -").replace("-}", "- }")
 }
 
 fn to_atom(s: String) -> String {
@@ -96,12 +99,95 @@ fn to_atom(s: String) -> String {
     }
 }
 
+/// Whether map keys of type `key` are strings in Elm, the only keys supported.
+fn is_string_key(key: &ast::TypeIdent) -> bool {
+    matches!(
+        key,
+        ast::TypeIdent::BuiltIn(ast::AtomType::Str | ast::AtomType::I64 | ast::AtomType::U64)
+    )
+}
+
+/// Elm keywords, which spec names may collide with.
+const ELM_KEYWORDS: &[&str] = &[
+    "alias", "as", "case", "effect", "else", "exposing", "if", "import", "in", "infix", "let",
+    "module", "of", "port", "then", "type", "where",
+];
+
+/// The modules generated for every spec, which the modules of `mod` blocks must not be named like.
+const GENERATED_MODULES: &[&str] = &[
+    "BuiltIn",
+    "Common",
+    "Config",
+    "Data",
+    "Decode",
+    "Encode",
+    "Form",
+    "Route",
+    "Service",
+    "ServiceBuiltIn",
+];
+
+/// The record field name of spec field `ident`, in camel case and suffixed with an underscore if
+/// it is an Elm keyword, e.g. `type_`.
 fn field_name(ident: &str) -> String {
-    to_camel_case(ident)
+    let name = to_camel_case(ident);
+    if ELM_KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// An Elm package the generated code is scaffolded as, see `Generator::with_package`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Package {
+    /// `author/project`, e.g. `acme/monster-api`.
+    pub name: String,
+}
+
+impl Package {
+    /// Package `name`, or a description of why it is invalid.
+    pub fn new(name: &str) -> Result<Self, String> {
+        let is_valid_part = |part: &str| {
+            part.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+                && !part.ends_with('-')
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        };
+        match name.split_once('/') {
+            Some((author, project)) if is_valid_part(author) && is_valid_part(project) => {
+                Ok(Self {
+                    name: name.to_owned(),
+                })
+            }
+            _ => Err(format!(
+                "package name `{}` must be `author/project` in lowercase kebab-case, e.g. `acme/monster-api`",
+                name
+            )),
+        }
+    }
 }
 
 pub struct Generator {
     module_prefix: String,
+    /// Module prefix of the common package of a workspace, whose types are used instead of
+    /// redefining them.
+    common_module_prefix: Option<String>,
+    /// Whether to generate the `Route` module.
+    route_parser: bool,
+    /// The casing of field and variant names in encoders and decoders.
+    wire_casing: WireCasing,
+    /// The representation of maps whose keys are not `str`.
+    map_representation: MapRepresentation,
+    /// The representation of `i64` and `u64`.
+    large_int_representation: LargeIntRepresentation,
+    /// The representation of enums without a `@tag` or `@untagged` annotation.
+    enum_representation: EnumRepresentation,
+    /// The environments of the `Config` module.
+    environments: Vec<Environment>,
+    /// The package the output is scaffolded as, if any.
+    package: Option<Package>,
     _artifact: Artifact,
 }
 
@@ -110,177 +196,430 @@ impl Generator {
         match artifact {
             Artifact::TypesOnly | Artifact::ClientEndpoints => Ok(Self {
                 module_prefix,
+                common_module_prefix: None,
+                route_parser: false,
+                wire_casing: WireCasing::default(),
+                map_representation: MapRepresentation::default(),
+                large_int_representation: LargeIntRepresentation::default(),
+                enum_representation: EnumRepresentation::default(),
+                environments: Vec::new(),
+                package: None,
                 _artifact: artifact,
             }),
-            Artifact::ServerEndpoints => Err(LibError::UnsupportedArtifact {
+            Artifact::ServerEndpoints
+            | Artifact::ClientAndServer
+            | Artifact::Benchmarks
+            | Artifact::MockServer => Err(LibError::UnsupportedArtifact {
                 artifact,
                 backend: BACKEND_NAME,
             }),
         }
     }
 
-    fn make_file(&self, _spec: &Spec, outdir: &Path, name: &str) -> Result<IndentWriter, LibError> {
-        // TODO: populate mem filesystem or temp folder first, then make everything visible at once
-        // to avoid partial write out on error
-        let mut file = IndentWriter::for_file(outdir, &format!("{}.elm", name))?;
+    /// Generate a `Route` module parsing application URLs into the `GET` routes of the services,
+    /// e.g. for deep links of a single-page application.
+    pub fn with_route_parser(mut self, route_parser: bool) -> Self {
+        self.route_parser = route_parser;
+        self
+    }
+
+    /// Convert the names of struct fields and enum variants to `wire_casing` in encoders and
+    /// decoders, matching the Rust backend with the same casing.
+    pub fn with_wire_casing(mut self, wire_casing: WireCasing) -> Self {
+        self.wire_casing = wire_casing;
+        self
+    }
+
+    /// Represent maps whose keys are not `str` as `map_representation`. Maps represented as
+    /// entries become lists of key-value tuples, since `Dict` keys must be `comparable`.
+    pub fn with_map_representation(mut self, map_representation: MapRepresentation) -> Self {
+        self.map_representation = map_representation;
+        self
+    }
+
+    /// Represent `i64` and `u64` as `large_int_representation`, matching the Rust backend with
+    /// the same representation. Large integers represented as strings are Elm `String`s, which
+    /// unlike `Int`s hold them exactly, and are decoded from strings and numbers alike.
+    pub fn with_large_int_representation(
+        mut self,
+        large_int_representation: LargeIntRepresentation,
+    ) -> Self {
+        self.large_int_representation = large_int_representation;
+        self
+    }
+
+    /// Represent enums without a `@tag` or `@untagged` annotation as `enum_representation` in
+    /// encoders and decoders, matching the Rust backend with the same representation.
+    pub fn with_enum_representation(mut self, enum_representation: EnumRepresentation) -> Self {
+        self.enum_representation = enum_representation;
+        self
+    }
+
+    /// Generate an `Environment` type of `environments` in the `Config` module, whose
+    /// `forEnvironment` configures requests to the services deployed there.
+    pub fn with_environments(mut self, environments: Vec<Environment>) -> Self {
+        self.environments = environments;
+        self
+    }
+
+    /// Scaffold the output folder as the Elm package `package`: an `elm.json` declaring the
+    /// generated modules and the packages they depend on, and the modules below its `src`
+    /// folder, such that the output compiles with `elm make` as is.
+    pub fn with_package(mut self, package: Option<Package>) -> Self {
+        self.package = package;
+        self
+    }
+
+    /// `spec` with the maps represented as entries replaced by lists of tuples, see
+    /// `MapRepresentation::apply`, and the enums annotated as they are represented, see
+    /// `EnumRepresentation::apply`.
+    fn represent<'a>(&self, spec: &'a Spec) -> Cow<'a, Spec> {
+        match self.map_representation.apply(spec) {
+            Cow::Borrowed(spec) => self.enum_representation.apply(spec),
+            Cow::Owned(spec) => Cow::Owned(self.enum_representation.apply(&spec).into_owned()),
+        }
+    }
+
+    /// `spec` as `represent`ed, with the large integers represented as numbers replaced by `i32`,
+    /// which are `Int`s alike.
+    fn prepare<'a>(&self, spec: &'a Spec) -> Cow<'a, Spec> {
+        let spec = self.represent(spec);
+        if self.large_int_representation == LargeIntRepresentation::String {
+            return spec;
+        }
+        let mut spec = spec.into_owned();
+        spec.for_each_type_ident_mut(&mut |ty| {
+            if let ast::TypeIdent::BuiltIn(atom @ (ast::AtomType::I64 | ast::AtomType::U64)) = ty {
+                *atom = ast::AtomType::I32;
+            }
+        });
+        Cow::Owned(spec)
+    }
+
+    /// Reject the features of `spec` that cannot be represented in Elm before generating any code.
+    fn check_supported(&self, spec: &Spec) -> Result<(), LibError> {
+        // not `prepare`d, such that errors name the large integers of the spec
+        let spec = self.represent(spec);
+        let unsupported = |feature: &str, span: &str| LibError::UnsupportedFeature {
+            feature: feature.to_owned(),
+            backend: BACKEND_NAME,
+            span: span.to_owned(),
+        };
+
+        let is_supported_key = |key: &ast::TypeIdent| match key {
+            // large integers represented as numbers are `Int`s like `i32`
+            ast::TypeIdent::BuiltIn(ast::AtomType::I64 | ast::AtomType::U64) => {
+                self.large_int_representation == LargeIntRepresentation::String
+            }
+            key => is_string_key(key),
+        };
+        let mut result = Ok(());
+        spec.for_each_type_use(&mut |location, ty, _| {
+            let mut ty = ty.clone();
+            ty.for_each_mut(&mut |ty| match ty {
+                ast::TypeIdent::Map(key, _) if result.is_ok() && !is_supported_key(key) => {
+                    result = Err(unsupported(&format!("`{}` map keys", key), location));
+                }
+                _ => {}
+            });
+        });
+        result?;
+
+        for item in spec.iter() {
+            if let Some((module, _)) = item.module() {
+                if GENERATED_MODULES.contains(&module.to_pascal_case().as_str()) {
+                    return Err(unsupported(
+                        "modules named like generated modules",
+                        &format!("mod {}", module),
+                    ));
+                }
+            }
+            match item {
+                // the empty values of user defined types are defined by the form helpers
+                ast::SpecItem::StructDef(sdef) => {
+                    for field in sdef.fields.iter().filter(|f| f.is_server_set()) {
+                        let mut ty = field.pair.type_ident.clone();
+                        let mut is_user_defined = false;
+                        ty.for_each_mut(&mut |ty| is_user_defined |= ty.user_defined().is_some());
+                        if is_user_defined {
+                            return Err(unsupported(
+                                "`@server_set` fields of user defined types",
+                                &format!("{}.{}", sdef.name, field.pair.name),
+                            ));
+                        }
+                    }
+                }
+                ast::SpecItem::EnumDef(edef) if edef.variants.is_empty() => {
+                    return Err(unsupported("enums without variants", &edef.name));
+                }
+                ast::SpecItem::ServiceDef(service) => {
+                    for endpoint in &service.endpoints {
+                        let location = service.endpoint_location(endpoint);
+                        if let Some(query) = endpoint.route.query() {
+                            if query.user_defined().is_none() {
+                                return Err(unsupported("queries that are not structs", &location));
+                            }
+                        }
+                        for component in endpoint.route.components() {
+                            if let ast::ServiceRouteComponent::Variable(pair) = component {
+                                let mut ty = pair.type_ident.clone();
+                                let mut has_empty = false;
+                                ty.for_each_mut(&mut |ty| {
+                                    has_empty |=
+                                        matches!(ty, ast::TypeIdent::BuiltIn(ast::AtomType::Empty))
+                                });
+                                if has_empty {
+                                    return Err(unsupported("`()` in route segments", &location));
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        super::check_multipart_bodies(&spec, BACKEND_NAME)
+    }
 
-        // TODO: make module path prefix configurable
-        write!(
-            file.handle(),
+    /// A module named `name` below the module prefix, e.g. `Service/MonsterApi`.
+    fn make_file(&self, name: &str) -> Printer {
+        let mut file = Printer::new();
+        file.line(format!(
             "module {}.{} exposing (..)",
             self.module_prefix,
             name.replace("/", ".")
-        )?;
-        file.empty_lines(2)?;
+        ));
+        file.blank_lines(1);
 
         // TODO: write timestamp and info that this file is generated
-        Ok(file)
+        file
     }
 
-    pub fn generate_user_defined_types(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
-        {
-            let mut builtin_dir = PathBuf::from(outdir);
-            builtin_dir.push("BuiltIn");
-            fs::create_dir(builtin_dir)?;
-        }
+    /// Write module `name` into `outdir`, see `make_file`.
+    fn write_file(&self, outdir: &Path, name: &str, file: Printer) -> Result<(), LibError> {
+        // TODO: populate mem filesystem or temp folder first, then make everything visible at once
+        // to avoid partial write out on error
+        file.write_to_file(&outdir.join(format!("{}.elm", name)))
+    }
 
-        {
-            let mut file = self.make_file(spec, outdir, "BuiltIn/Bytes")?;
-            write!(
-                file.handle(),
-                "{}",
-                include_str!("./elm/builtin_type_bytes.elm"),
-            )?;
+    /// Module prefix of the `BuiltIn` modules, which are shared with the common package.
+    fn builtin_module_prefix(&self) -> &str {
+        self.common_module_prefix
+            .as_deref()
+            .unwrap_or(&self.module_prefix)
+    }
+
+    /// Import module `module` of the common package, if any.
+    fn write_common_import(&self, file: &mut Printer, module: &str, import_spec: &str) {
+        if let Some(common_module_prefix) = &self.common_module_prefix {
+            file.line(format!(
+                "import {}.{} {}",
+                common_module_prefix, module, import_spec
+            ));
         }
+    }
 
-        {
-            let mut file = self.make_file(spec, outdir, "BuiltIn/Uuid")?;
-            write!(
-                file.handle(),
-                "{}",
-                include_str!("./elm/builtin_type_uuid.elm"),
-            )?;
+    pub fn generate_user_defined_types(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
+        let spec = &self.prepare(spec);
+        if self.common_module_prefix.is_none() {
+            {
+                let mut builtin_dir = PathBuf::from(outdir);
+                builtin_dir.push("BuiltIn");
+                fs::create_dir(&builtin_dir).map_err(LibError::file(&builtin_dir))?;
+            }
+
+            let mut file = self.make_file("BuiltIn/Bytes");
+            file.line(include_str!("./elm/builtin_type_bytes.elm"));
+            self.write_file(outdir, "BuiltIn/Bytes", file)?;
+
+            let mut file = self.make_file("BuiltIn/Uuid");
+            file.line(include_str!("./elm/builtin_type_uuid.elm"));
+            self.write_file(outdir, "BuiltIn/Uuid", file)?;
         }
 
-        let mut file = self.make_file(spec, outdir, "Data")?;
-        write!(
-            file.start_line()?,
+        let mut file = self.make_file("Data");
+        self.write_common_import(&mut file, "Data", "exposing (..)");
+        file.line(format!(
             include_str!("./elm/preamble_types.elm"),
-            module_prefix = self.module_prefix
-        )?;
-        file.empty_lines(2)?;
+            builtin_prefix = self.builtin_module_prefix()
+        ));
+        file.blank_lines(2);
 
         for spec_item in spec.iter() {
             match spec_item {
                 ast::SpecItem::StructDef(sdef) => {
-                    type_generation::generate_struct_def(sdef, &mut file)?
+                    type_generation::generate_struct_def(sdef, &mut file)
                 }
-                ast::SpecItem::EnumDef(edef) => {
-                    type_generation::generate_enum_def(edef, &mut file)?
+                ast::SpecItem::TupleStructDef(tdef) => {
+                    type_generation::generate_tuple_struct_def(tdef, &mut file)
+                }
+                ast::SpecItem::EnumDef(edef) => type_generation::generate_enum_def(edef, &mut file),
+                ast::SpecItem::FlagsDef(fdef) => {
+                    type_generation::generate_flags_def(fdef, &mut file)
                 }
                 ast::SpecItem::ServiceDef(_) => {}
             };
         }
 
-        Ok(())
+        self.write_file(outdir, "Data", file)
     }
 
     pub fn generate_decoders(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
-        let mut file = self.make_file(spec, outdir, "Decode")?;
-        write!(
-            file.start_line()?,
-            "import {}.Data exposing (..)",
-            self.module_prefix
-        )?;
-        write!(
-            file.start_line()?,
+        let spec = &self.prepare(spec);
+        let mut file = self.make_file("Decode");
+        file.line(format!("import {}.Data exposing (..)", self.module_prefix));
+        self.write_common_import(&mut file, "Data", "exposing (..)");
+        self.write_common_import(&mut file, "Decode", "exposing (..)");
+        file.line(format!(
             include_str!("./elm/preamble_decoder.elm"),
-            module_prefix = self.module_prefix
-        )?;
-        file.empty_lines(2)?;
-        write!(
-            file.handle(),
-            "{}",
-            decoder_generation::generate_type_decoders(spec)
-        )?;
-        Ok(())
+            builtin_prefix = self.builtin_module_prefix()
+        ));
+        file.blank_lines(2);
+        file.line(decoder_generation::generate_type_decoders(
+            spec,
+            self.wire_casing,
+        ));
+        self.write_file(outdir, "Decode", file)
     }
 
     pub fn generate_encoders(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
-        let mut file = self.make_file(spec, outdir, "Encode")?;
-        write!(
-            file.start_line()?,
-            "import {}.Data exposing (..)",
-            self.module_prefix
-        )?;
-        write!(
-            file.start_line()?,
+        let spec = &self.prepare(spec);
+        let mut file = self.make_file("Encode");
+        file.line(format!("import {}.Data exposing (..)", self.module_prefix));
+        self.write_common_import(&mut file, "Data", "exposing (..)");
+        self.write_common_import(&mut file, "Encode", "exposing (..)");
+        file.line(format!(
             include_str!("./elm/preamble_encoder.elm"),
-            module_prefix = self.module_prefix
-        )?;
-        file.empty_lines(2)?;
-        write!(
-            file.handle(),
-            "{}",
-            encoder_generation::generate_struct_and_enum_encoders(spec)
-        )?;
+            builtin_prefix = self.builtin_module_prefix()
+        ));
+        file.blank_lines(2);
+        file.line(encoder_generation::generate_struct_and_enum_encoders(
+            spec,
+            self.wire_casing,
+        ));
+        self.write_file(outdir, "Encode", file)
+    }
+
+    pub fn generate_form_helpers(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
+        let spec = &self.prepare(spec);
+        let mut file = self.make_file("Form");
+        file.line(format!("import {}.Data exposing (..)", self.module_prefix));
+        self.write_common_import(&mut file, "Data", "exposing (..)");
+        self.write_common_import(&mut file, "Form", "exposing (..)");
+        file.line(format!(
+            include_str!("./elm/preamble_form.elm"),
+            builtin_prefix = self.builtin_module_prefix()
+        ));
+        file.blank_lines(2);
+        file.line(form_generation::generate_form_helpers(spec));
+        self.write_file(outdir, "Form", file)
+    }
+
+    /// Write a module for each `mod` block of the spec, e.g. `Billing` for `mod billing`, defining
+    /// its types, decoders and encoders by their names in the block, e.g. `Invoice` and
+    /// `decodeInvoice` for `BillingInvoice`.
+    pub fn generate_modules(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
+        let mut modules: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+        for item in spec.iter() {
+            if let Some((module, name)) = item.module() {
+                modules.entry(module).or_default().push((item.name(), name));
+            }
+        }
+
+        for (module, items) in modules {
+            let module = module.to_pascal_case();
+            let mut file = self.make_file(&module);
+            file.line(format!("import {}.Data as Data", self.module_prefix));
+            file.line(format!("import {}.Decode as Decode", self.module_prefix));
+            file.line(format!("import {}.Encode as Encode", self.module_prefix));
+            file.line("import Json.Decode as D");
+            file.line("import Json.Encode as E");
+            for (generated_name, name) in items {
+                file.blank_lines(2);
+                file.line(format!("type alias {} =", name));
+                file.with_indent(|file| file.line(format!("Data.{}", generated_name)));
+                file.blank_lines(2);
+                let decoder = decoder_generation::decoder_name(name, "");
+                file.line(format!("{} : D.Decoder {}", decoder, name));
+                file.line(format!("{} =", decoder));
+                file.with_indent(|file| {
+                    file.line(decoder_generation::decoder_name(generated_name, "Decode."))
+                });
+                file.blank_lines(2);
+                let encoder = encoder_generation::struct_or_enum_encoder_name(name, "");
+                file.line(format!("{} : {} -> E.Value", encoder, name));
+                file.line(format!("{} =", encoder));
+                file.with_indent(|file| {
+                    file.line(encoder_generation::struct_or_enum_encoder_name(
+                        generated_name,
+                        "Encode.",
+                    ))
+                });
+            }
+            self.write_file(outdir, &module, file)?;
+        }
         Ok(())
     }
 
     pub fn generate_endpoints(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
+        let spec = &self.prepare(spec);
         {
             let mut service_dir = PathBuf::from(outdir);
             service_dir.push("Service");
-            fs::create_dir(service_dir)?;
+            fs::create_dir(&service_dir).map_err(LibError::file(&service_dir))?;
         }
 
+        let mut file = self.make_file("ServiceBuiltIn");
+        file.line(include_str!("./elm/builtin_service.elm"));
+        self.write_file(outdir, "ServiceBuiltIn", file)?;
+
+        if spec
+            .iter()
+            .any(|spec_item| spec_item.service_def().is_some())
         {
-            let mut file = self.make_file(spec, outdir, "ServiceBuiltIn")?;
-            write!(
-                file.handle(),
-                "{}",
-                include_str!("./elm/builtin_service.elm"),
-            )?;
+            let mut file = self.make_file("Config");
+            file.line(format!(
+                "import {}.ServiceBuiltIn exposing (Request)",
+                self.module_prefix
+            ));
+            file.line("import Http");
+            file.blank_lines(2);
+            config_generation::generate_config(spec, &self.environments, &mut file);
+            self.write_file(outdir, "Config", file)?;
         }
 
         for spec_item in spec.iter() {
             match spec_item {
-                ast::SpecItem::StructDef(..) | ast::SpecItem::EnumDef(..) => {}
+                ast::SpecItem::StructDef(..)
+                | ast::SpecItem::TupleStructDef(..)
+                | ast::SpecItem::EnumDef(..)
+                | ast::SpecItem::FlagsDef(..) => {}
                 ast::SpecItem::ServiceDef(service) => {
-                    let mut file =
-                        self.make_file(spec, outdir, &format!("Service/{}", service.name))?;
-                    write!(
-                        file.start_line()?,
-                        "import {}.Data as Ty",
-                        self.module_prefix
-                    )?;
-                    write!(file.start_line()?, "{}", "import Json.Decode as D")?;
-                    write!(file.start_line()?, "{}", "import Json.Encode as E")?;
-                    write!(
-                        file.start_line()?,
-                        "import {}.Encode as AE",
-                        self.module_prefix
-                    )?;
-                    write!(
-                        file.start_line()?,
-                        "import {}.Decode as AD",
-                        self.module_prefix
-                    )?;
-                    write!(
-                        file.start_line()?,
+                    let name = format!("Service/{}", service.name);
+                    let mut file = self.make_file(&name);
+                    file.line(format!("import {}.Data as Ty", self.module_prefix));
+                    file.line("import Json.Decode as D");
+                    file.line("import Json.Encode as E");
+                    file.line(format!("import {}.Encode as AE", self.module_prefix));
+                    file.line(format!("import {}.Decode as AD", self.module_prefix));
+                    file.line(format!(
                         "import {}.ServiceBuiltIn exposing (..)",
                         self.module_prefix
-                    )?;
-                    write!(file.start_line()?, "import Url.Builder")?;
-                    write!(file.start_line()?, "{}", "import Http")?;
-
-                    write!(
-                        file.start_line()?,
+                    ));
+                    self.write_common_import(&mut file, "Data", "as Ty");
+                    self.write_common_import(&mut file, "Encode", "as AE");
+                    self.write_common_import(&mut file, "Decode", "as AD");
+                    file.line("import Url.Builder");
+                    file.line("import Http");
+
+                    file.line(format!(
                         include_str!("./elm/preamble_service.elm"),
-                        module_prefix = self.module_prefix
-                    )?;
-                    file.empty_lines(2)?;
-                    endpoint_generation::generate(service, &mut file)?;
+                        module_prefix = self.module_prefix,
+                        builtin_prefix = self.builtin_module_prefix()
+                    ));
+                    file.blank_lines(2);
+                    endpoint_generation::generate(spec, service, &mut file);
+                    self.write_file(outdir, &name, file)?;
                 }
             };
         }
@@ -288,6 +627,92 @@ impl Generator {
         Ok(())
     }
 
+    pub fn generate_route_parser(&self, spec: &Spec, outdir: &Path) -> Result<(), LibError> {
+        let spec = &self.prepare(spec);
+        let mut file = self.make_file("Route");
+        file.line(format!(
+            include_str!("./elm/preamble_route.elm"),
+            builtin_prefix = self.builtin_module_prefix()
+        ));
+        file.blank_lines(2);
+        file.line(route_generation::generate_routes(spec));
+        self.write_file(outdir, "Route", file)
+    }
+
+    /// The folder of the modules below `output`: `output` itself, or the folder of the module
+    /// prefix below `src` of a package, e.g. `src/Api`, which is created.
+    fn module_dir(&self, output: &Path) -> Result<PathBuf, LibError> {
+        if self.package.is_none() {
+            return Ok(output.to_owned());
+        }
+        let mut module_dir = output.join("src");
+        module_dir.extend(self.module_prefix.split('.'));
+        fs::create_dir_all(&module_dir).map_err(LibError::file(&module_dir))?;
+        Ok(module_dir)
+    }
+
+    /// Write the `elm.json` of the package, if any, into `output`, exposing all modules written
+    /// into `module_dir`. The version and summary of the package are those of `meta`, if given.
+    fn write_elm_json(
+        &self,
+        output: &Path,
+        module_dir: &Path,
+        meta: &ast::Meta,
+    ) -> Result<(), LibError> {
+        let package = match &self.package {
+            Some(package) => package,
+            None => return Ok(()),
+        };
+        let mut modules = vec![];
+        collect_modules(module_dir, &self.module_prefix, &mut modules)?;
+        modules.sort();
+
+        let summary = match &meta.title {
+            Some(title) => format!("Types and clients of {}", title),
+            None => "Generated types and clients".to_owned(),
+        };
+        // elm rejects summaries of 80 characters or more
+        let summary: String = summary.chars().take(79).collect();
+        let quote = |s: &str| serde_json::to_string(s).expect("strings are serializable");
+
+        let mut file = Printer::new();
+        file.line("{");
+        file.with_indent(|file| {
+            file.line("\"type\": \"package\",");
+            file.line(format!("\"name\": {},", quote(&package.name)));
+            file.line(format!("\"summary\": {},", quote(&summary)));
+            file.line("\"license\": \"BSD-3-Clause\",");
+            file.line(format!(
+                "\"version\": {},",
+                quote(meta.semver().unwrap_or("1.0.0"))
+            ));
+            file.line("\"exposed-modules\": [");
+            file.with_indent(|file| {
+                for (i, module) in modules.iter().enumerate() {
+                    let separator = if i + 1 < modules.len() { "," } else { "" };
+                    file.line(format!("{}{}", quote(module), separator));
+                }
+            });
+            file.line("],");
+            file.line("\"elm-version\": \"0.19.0 <= v < 0.20.0\",");
+            file.line("\"dependencies\": {");
+            file.with_indent(|file| {
+                for (i, (name, versions)) in PACKAGE_DEPENDENCIES.iter().enumerate() {
+                    let separator = if i + 1 < PACKAGE_DEPENDENCIES.len() {
+                        ","
+                    } else {
+                        ""
+                    };
+                    file.line(format!("{}: {}{}", quote(name), quote(versions), separator));
+                }
+            });
+            file.line("},");
+            file.line("\"test-dependencies\": {}");
+        });
+        file.line("}");
+        file.write_to_file(&output.join("elm.json"))
+    }
+
     pub fn validate_output_dir(path: &Path) -> Result<(), LibError> {
         if !path.is_dir() {
             return Err(LibError::OutputMustBeFolder {
@@ -295,7 +720,11 @@ impl Generator {
             });
         }
 
-        let is_empty = path.read_dir().map_err(LibError::IoError)?.next().is_none();
+        let is_empty = path
+            .read_dir()
+            .map_err(LibError::file(path))?
+            .next()
+            .is_none();
 
         if !is_empty {
             return Err(LibError::OutputFolderNotEmpty {
@@ -309,13 +738,113 @@ impl Generator {
 
 impl crate::CodeGenerator for Generator {
     fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
-        Self::validate_output_dir(&output)?;
+        Self::validate_output_dir(output)?;
+        self.check_supported(spec)?;
+        let module_dir = self.module_dir(output)?;
+
+        self.generate_user_defined_types(spec, &module_dir)?;
+        self.generate_decoders(spec, &module_dir)?;
+        self.generate_encoders(spec, &module_dir)?;
+        self.generate_form_helpers(spec, &module_dir)?;
+        self.generate_modules(spec, &module_dir)?;
+        self.generate_endpoints(spec, &module_dir)?;
+        if self.route_parser {
+            self.generate_route_parser(spec, &module_dir)?;
+        }
 
-        self.generate_user_defined_types(&spec, &output)?;
-        self.generate_decoders(&spec, &output)?;
-        self.generate_encoders(&spec, &output)?;
-        self.generate_endpoints(&spec, &output)?;
+        self.write_elm_json(output, &module_dir, spec.meta())
+    }
 
-        Ok(())
+    /// Writes the common types into a `Common` package and a package named after every spec
+    /// into the `output` folder, e.g. modules `Api.Common.Data` and `Api.Monsters.Data`.
+    /// The spec packages use the common package instead of redefining its types.
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        Self::validate_output_dir(output)?;
+        self.check_supported(&workspace.common)?;
+        for ws_spec in &workspace.specs {
+            self.check_supported(&workspace.resolved_spec(ws_spec))?;
+        }
+        let module_dir = self.module_dir(output)?;
+
+        let common_module_prefix = format!("{}.Common", self.module_prefix);
+        let common_dir = module_dir.join("Common");
+        fs::create_dir(&common_dir).map_err(LibError::file(&common_dir))?;
+        let common = Self {
+            module_prefix: common_module_prefix.clone(),
+            common_module_prefix: None,
+            route_parser: false,
+            wire_casing: self.wire_casing,
+            map_representation: self.map_representation,
+            large_int_representation: self.large_int_representation,
+            enum_representation: self.enum_representation,
+            environments: Vec::new(),
+            package: None,
+            _artifact: self._artifact,
+        };
+        common.generate_user_defined_types(&workspace.common, &common_dir)?;
+        common.generate_decoders(&workspace.common, &common_dir)?;
+        common.generate_encoders(&workspace.common, &common_dir)?;
+        common.generate_form_helpers(&workspace.common, &common_dir)?;
+        common.generate_modules(&workspace.common, &common_dir)?;
+
+        for ws_spec in &workspace.specs {
+            let package_name = ws_spec.name.to_pascal_case();
+            let package_dir = module_dir.join(&package_name);
+            fs::create_dir(&package_dir).map_err(LibError::file(&package_dir))?;
+            let package = Self {
+                module_prefix: format!("{}.{}", self.module_prefix, package_name),
+                common_module_prefix: Some(common_module_prefix.clone()),
+                route_parser: self.route_parser,
+                wire_casing: self.wire_casing,
+                map_representation: self.map_representation,
+                large_int_representation: self.large_int_representation,
+                enum_representation: self.enum_representation,
+                environments: self.environments.clone(),
+                package: None,
+                _artifact: self._artifact,
+            };
+            package.generate_user_defined_types(&ws_spec.spec, &package_dir)?;
+            package.generate_decoders(&ws_spec.spec, &package_dir)?;
+            package.generate_encoders(&ws_spec.spec, &package_dir)?;
+            package.generate_form_helpers(&ws_spec.spec, &package_dir)?;
+            package.generate_modules(&ws_spec.spec, &package_dir)?;
+            // endpoints need the common definitions, e.g. of multipart bodies
+            let resolved_spec = workspace.resolved_spec(ws_spec);
+            package.generate_endpoints(&resolved_spec, &package_dir)?;
+            if package.route_parser {
+                package.generate_route_parser(&resolved_spec, &package_dir)?;
+            }
+        }
+
+        self.write_elm_json(output, &module_dir, workspace.common.meta())
+    }
+
+    fn lossy_mappings(&self) -> &'static [LossyMapping] {
+        match self.large_int_representation {
+            LargeIntRepresentation::Number => LOSSY_MAPPINGS,
+            LargeIntRepresentation::String => LOSSY_MAPPINGS_WITHOUT_LARGE_INTS,
+        }
+    }
+}
+
+/// Add the names of the modules in `dir` and its subfolders to `modules`, where `dir` contains the
+/// modules below `module_prefix`.
+fn collect_modules(
+    dir: &Path,
+    module_prefix: &str,
+    modules: &mut Vec<String>,
+) -> Result<(), LibError> {
+    for entry in fs::read_dir(dir).map_err(LibError::file(dir))? {
+        let path = entry.map_err(LibError::file(dir))?.path();
+        let name = match path.file_stem().and_then(|name| name.to_str()) {
+            Some(name) => format!("{}.{}", module_prefix, name),
+            None => continue,
+        };
+        if path.is_dir() {
+            collect_modules(&path, &name, modules)?;
+        } else if path.extension() == Some("elm".as_ref()) {
+            modules.push(name);
+        }
     }
+    Ok(())
 }
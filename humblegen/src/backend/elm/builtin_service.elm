@@ -1,6 +1,7 @@
 import Http
 import Json.Decode as D
 import Json.Encode as E
+import Random
 import Url
 import Url.Builder
 import Task exposing (Task)
@@ -14,7 +15,7 @@ type alias Request q t =
     , query: Maybe q
     , queryEncoder: QueryEncoder q
     , body : Http.Body
-    , resolver : Http.Resolver Error t
+    , resolver : Resolver t
     , timeout : Maybe Float
     , base : String
     }
@@ -34,7 +35,7 @@ type Error
     | ServerError
 
 
-makeRequest : String -> List String -> QueryEncoder q -> Http.Resolver Error t -> Request q t
+makeRequest : String -> List String -> QueryEncoder q -> Resolver t -> Request q t
 makeRequest method urlComponents queryEncoder resolver =
     { method = method
     , headers = []
@@ -52,10 +53,40 @@ type alias NoQuery = Never
 noQueryEncoder : QueryEncoder Never
 noQueryEncoder _ = []
 
-jsonResolver : D.Decoder t -> Http.Resolver Error t
+{-| Turns the response to a request into its result, see `toTask` and `toCmdWithProgress`.
+-}
+type alias Resolver t =
+    Http.Response String -> Result Error t
+
+
+jsonResolver : D.Decoder t -> Resolver t
 jsonResolver =
+    jsonResolverWithStatuses []
+
+
+{-| Like `jsonResolver`, but also decodes responses with one of the given status codes,
+which the server uses for domain errors annotated with `@status`.
+-}
+jsonResolverWithStatuses : List Int -> D.Decoder t -> Resolver t
+jsonResolverWithStatuses domainErrorStatuses decoder =
+    resolverWithStatuses domainErrorStatuses
+        (\metadata body ->
+            D.decodeString decoder body
+                |> Result.mapError (InvalidResponse metadata (StringResponse body))
+        )
+
+
+{-| Resolver of routes returning `()`, which the server answers with 204 No Content and an empty body.
+-}
+noContentResolver : Resolver ()
+noContentResolver =
+    resolverWithStatuses [] (\_ _ -> Ok ())
+
+
+resolverWithStatuses : List Int -> (Http.Metadata -> String -> Result Error t) -> Resolver t
+resolverWithStatuses domainErrorStatuses decodeBody =
     let
-        resolve decoder response =
+        resolve response =
             case response of
                 Http.BadUrl_ badUrl ->
                     Err <| Bug <| "bad url: " ++ badUrl
@@ -67,25 +98,28 @@ jsonResolver =
                     Err <| TransportError "Http.NetworkError_"
 
                 Http.BadStatus_ metadata body ->
-                    Err <|
-                        case metadata.statusCode of
-                            401 ->
-                                AuthorizationError
+                    if List.member metadata.statusCode domainErrorStatuses then
+                        decodeBody metadata body
+
+                    else
+                        Err <|
+                            case metadata.statusCode of
+                                401 ->
+                                    AuthorizationError
 
-                            403 ->
-                                AuthenticationError
+                                403 ->
+                                    AuthenticationError
 
-                            500 ->
-                                ServerError
+                                500 ->
+                                    ServerError
 
-                            _ ->
-                                HttpBug metadata (StringResponse body)
+                                _ ->
+                                    HttpBug metadata (StringResponse body)
 
                 Http.GoodStatus_ metadata body ->
-                    D.decodeString decoder body
-                        |> Result.mapError (InvalidResponse metadata (StringResponse body))
+                    decodeBody metadata body
     in
-    Http.stringResolver << resolve
+    resolve
 
 
 withBase : String -> Request q t -> Request q t
@@ -113,11 +147,43 @@ withHeader name value req =
     { req | headers = Http.header name value :: req.headers }
 
 
+{-| Generates a new key for the requests of `@idempotent` routes, e.g. with `Random.generate`.
+Retries of a request send the key of the request, so the server answers them with its response.
+-}
+idempotencyKeyGenerator : Random.Generator String
+idempotencyKeyGenerator =
+    Random.list 32 (Random.int 0 15)
+        |> Random.map (List.map hexDigit >> String.fromList)
+
+
+hexDigit : Int -> Char
+hexDigit digit =
+    String.toList "0123456789abcdef"
+        |> List.drop digit
+        |> List.head
+        |> Maybe.withDefault '0'
+
+
+withOptionalHeader : String -> Maybe String -> Request q t -> Request q t
+withOptionalHeader name value req =
+    case value of
+        Just v ->
+            withHeader name v req
+
+        Nothing ->
+            req
+
+
 withJsonBody : (body -> E.Value) -> body -> Request q t -> Request q t
 withJsonBody encoder value req =
     { req | body = Http.stringBody "application/json" <| E.encode 2 (encoder value) }
 
 
+withMultipartBody : List Http.Part -> Request q t -> Request q t
+withMultipartBody parts req =
+    { req | body = Http.multipartBody parts }
+
+
 makeUrl : Request q t -> String
 makeUrl req =
     Url.Builder.crossOrigin
@@ -133,6 +199,94 @@ toTask req =
         , headers = req.headers
         , url = makeUrl req
         , body = req.body
-        , resolver = req.resolver
+        , resolver = Http.stringResolver req.resolver
+        , timeout = req.timeout
+        }
+
+
+{-| Like `toTask`, but sends the request as a command tracked as `tracker`,
+such that `uploadProgress tracker` reports the progress of uploading its body, e.g. for large `bytes` bodies.
+-}
+toCmdWithProgress : String -> (Result Error t -> msg) -> Request q t -> Cmd msg
+toCmdWithProgress tracker toMsg req =
+    Http.request
+        { method = req.method
+        , headers = req.headers
+        , url = makeUrl req
+        , body = req.body
+        , expect = Http.expectStringResponse toMsg req.resolver
         , timeout = req.timeout
+        , tracker = Just tracker
         }
+
+
+{-| Subscribes to the upload progress of the request sent by `toCmdWithProgress tracker`,
+as fraction of bytes sent.
+-}
+uploadProgress : String -> (Float -> msg) -> Sub msg
+uploadProgress tracker toMsg =
+    Http.track tracker
+        (\progress ->
+            case progress of
+                Http.Sending sending ->
+                    toMsg (Http.fractionSent sending)
+
+                Http.Receiving _ ->
+                    toMsg 1
+        )
+
+
+{-| The items of a paginated route loaded so far, page by page.
+
+Start with `initPageState`, load pages with the `loadMore` function of the route, e.g.
+`loadMoreGetMonsters` for `getMonsters`, and pass their results to `updatePageState`.
+-}
+type alias PageState a =
+    { items : List a
+    , nextCursor : Maybe String
+    , hasMore : Bool
+    , loading : Bool
+    , error : Maybe Error
+    }
+
+
+initPageState : PageState a
+initPageState =
+    { items = []
+    , nextCursor = Nothing
+    , hasMore = True
+    , loading = False
+    , error = Nothing
+    }
+
+
+{-| Sends the request of the page following the items of `state`, built by `request` from the
+cursor of that page, unless all pages are loaded or a page is being loaded.
+-}
+loadMore : (Maybe String -> Request q t) -> (Result Error t -> msg) -> PageState a -> ( PageState a, Cmd msg )
+loadMore request toMsg state =
+    if state.hasMore && not state.loading then
+        ( { state | loading = True, error = Nothing }
+        , Task.attempt toMsg (toTask (request state.nextCursor))
+        )
+
+    else
+        ( state, Cmd.none )
+
+
+{-| Appends the items of a loaded page to `state`, or records the error of loading it, which
+allows retrying with `loadMore`.
+-}
+updatePageState : Result Error { page | items : List a, nextCursor : Maybe String } -> PageState a -> PageState a
+updatePageState result state =
+    case result of
+        Ok page ->
+            { state
+                | items = state.items ++ page.items
+                , nextCursor = page.nextCursor
+                , hasMore = page.nextCursor /= Nothing
+                , loading = False
+            }
+
+        Err error ->
+            { state | loading = False, error = Just error }
@@ -1,10 +1,14 @@
 import Json.Decode as D
 import Json.Encode as E
 import Bytes as PkgBytes
+import Bytes.Encode as PkgBytesEncode
 import Base64 as PkgBase64
 
 type Bytes = Bytes PkgBytes.Bytes
 
+empty : Bytes
+empty = Bytes (PkgBytesEncode.encode (PkgBytesEncode.sequence []))
+
 encode : Bytes -> E.Value
 encode (Bytes bytes) = E.string <| Maybe.withDefault "" (PkgBase64.fromBytes bytes) -- base64 _en_coding never fails
 
@@ -23,3 +27,6 @@ encodeQuery (Bytes bytes) = PkgBase64.fromBytes bytes |> Maybe.withDefault "" --
 
 encodeUrlcomponent : Bytes -> String
 encodeUrlcomponent (Bytes bytes) = PkgBase64.fromBytes bytes |> Maybe.withDefault "" -- base64 _en_coding never fails
+
+toBytes : Bytes -> PkgBytes.Bytes
+toBytes (Bytes bytes) = bytes
@@ -4,6 +4,9 @@ import Url.Parser
 
 type Uuid = Uuid String
 
+nil : Uuid
+nil = Uuid "00000000-0000-0000-0000-000000000000"
+
 
 encode : Uuid -> E.Value
 encode (Uuid str) = E.string str
@@ -0,0 +1,136 @@
+//! Generation of the `Config` module, configuring the requests to the services of a spec by
+//! environment instead of by hardcoded URLs.
+
+use crate::codegen::printer::Printer;
+use crate::{ast, Environment};
+use inflector::Inflector;
+
+/// Generate the definitions of the `Config` module: a `Config` record of the base URL, timeout
+/// and headers of requests, `withConfig` applying it to a request, and an `Environment` type of
+/// `environments` with their configs, if there are any.
+pub(crate) fn generate_config(spec: &ast::Spec, environments: &[Environment], file: &mut Printer) {
+    file.line(
+        "{-| The base URL, timeout in milliseconds and headers of the requests to the services.",
+    );
+    file.line("-}");
+    file.line("type alias Config =");
+    file.with_indent(|file| {
+        file.line("{ baseUrl : String");
+        file.line(", timeout : Maybe Float");
+        file.line(", headers : List Http.Header");
+        file.line("}");
+    });
+    file.blank_lines(2);
+
+    file.line("{-| A config of requests to the services mounted at `baseUrl`, without timeout and headers.");
+    file.line("-}");
+    file.line("config : String -> Config");
+    file.line("config baseUrl =");
+    file.with_indent(|file| file.line("{ baseUrl = baseUrl, timeout = Nothing, headers = [] }"));
+    file.blank_lines(2);
+
+    if let Some(base_url) = &spec.meta().base_url {
+        file.line(
+            "{-| The config of requests to the services mounted at the `base_url` declared in the",
+        );
+        file.line("`meta` block of the spec.");
+        file.line("-}");
+        file.line("defaultConfig : Config");
+        file.line("defaultConfig =");
+        file.with_indent(|file| file.line(format!("config {}", string_literal(base_url))));
+        file.blank_lines(2);
+    }
+
+    file.line(
+        "{-| Send `request` to the base URL, with the timeout and in addition to its headers the",
+    );
+    file.line("headers of `cfg`.");
+    file.line("-}");
+    file.line("withConfig : Config -> Request q t -> Request q t");
+    file.line("withConfig cfg request =");
+    file.with_indent(|file| {
+        file.line("{ request");
+        file.line("    | base = cfg.baseUrl");
+        file.line("    , timeout = cfg.timeout");
+        file.line("    , headers = request.headers ++ cfg.headers");
+        file.line("}");
+    });
+
+    if environments.is_empty() {
+        return;
+    }
+    let variants: Vec<_> = environments
+        .iter()
+        .map(|env| env.name.to_pascal_case())
+        .collect();
+
+    file.blank_lines(2);
+    file.line("{-| The environments the services are deployed to.");
+    file.line("-}");
+    file.line("type Environment");
+    file.with_indent(|file| {
+        for (i, variant) in variants.iter().enumerate() {
+            let separator = if i == 0 { "=" } else { "|" };
+            file.line(format!("{} {}", separator, variant));
+        }
+    });
+    file.blank_lines(2);
+
+    file.line("{-| All environments, in the order they were generated in.");
+    file.line("-}");
+    file.line("environments : List Environment");
+    file.line("environments =");
+    file.with_indent(|file| file.line(format!("[ {} ]", variants.join(", "))));
+    file.blank_lines(2);
+
+    file.line("{-| The name of `environment`, e.g. `prod`.");
+    file.line("-}");
+    file.line("environmentName : Environment -> String");
+    file.line("environmentName environment =");
+    generate_environment_case(environments, &variants, file, |env| {
+        string_literal(&env.name)
+    });
+    file.blank_lines(2);
+
+    file.line("{-| The environment named `name`, e.g. passed as flag to the application.");
+    file.line("-}");
+    file.line("environmentFromName : String -> Maybe Environment");
+    file.line("environmentFromName name =");
+    file.with_indent(|file| {
+        file.line("List.head (List.filter (\\environment -> environmentName environment == name) environments)")
+    });
+    file.blank_lines(2);
+
+    file.line("{-| The config of requests to the services deployed to `environment`.");
+    file.line("-}");
+    file.line("forEnvironment : Environment -> Config");
+    file.line("forEnvironment environment =");
+    generate_environment_case(environments, &variants, file, |env| {
+        format!("config {}", string_literal(&env.base_url))
+    });
+}
+
+/// A `case` expression on `environment`, evaluating to `value` of each environment.
+fn generate_environment_case(
+    environments: &[Environment],
+    variants: &[String],
+    file: &mut Printer,
+    value: impl Fn(&Environment) -> String,
+) {
+    file.with_indent(|file| {
+        file.line("case environment of");
+        file.with_indent(|file| {
+            for (i, (env, variant)) in environments.iter().zip(variants).enumerate() {
+                if i > 0 {
+                    file.blank_lines(1);
+                }
+                file.line(format!("{} ->", variant));
+                file.with_indent(|file| file.line(value(env)));
+            }
+        });
+    });
+}
+
+fn string_literal(s: &str) -> String {
+    format!("\"{}\"", s.escape_default())
+}
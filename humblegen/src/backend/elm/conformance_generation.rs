@@ -0,0 +1,62 @@
+use super::{decoder_generation, encoder_generation};
+use crate::conformance::Corpus;
+
+use itertools::Itertools; // directly call join(.) on iterators
+
+/// Generate the elm-test module `ConformanceTest`, which round-trips the fixtures of `corpus`
+/// through the decoders and encoders generated with module prefix `module_prefix`.
+pub fn generate_conformance_tests(corpus: &Corpus, module_prefix: &str) -> String {
+    let suites = corpus
+        .fixtures
+        .iter()
+        .map(|fixture| {
+            format!(
+                "roundTrip \"{name}\" {decoder} {encoder}\n            [ {values}\n            ]",
+                name = fixture.type_name,
+                decoder = decoder_generation::decoder_name(&fixture.type_name, "AD."),
+                encoder =
+                    encoder_generation::struct_or_enum_encoder_name(&fixture.type_name, "AE."),
+                values = fixture
+                    .values
+                    .iter()
+                    .map(|value| elm_string_literal(&value.to_string()))
+                    .join("\n            , "),
+            )
+        })
+        .join("\n        , ");
+
+    format!(
+        "module ConformanceTest exposing (suite)\n\n\
+        import {module_prefix}.Decode as AD\n\
+        import {module_prefix}.Encode as AE\n\
+        import Expect\n\
+        import Json.Decode as D\n\
+        import Json.Encode as E\n\
+        import Test exposing (Test, describe, test)\n\n\n\
+        suite : Test\n\
+        suite =\n    describe \"conformance\"\n        [ {suites}\n        ]\n\n\n\
+        {helpers}",
+        module_prefix = module_prefix,
+        suites = suites,
+        helpers = include_str!("./conformance_test.elm"),
+    )
+}
+
+/// Quotes `s` as elm string literal.
+fn elm_string_literal(s: &str) -> String {
+    let mut literal = String::with_capacity(s.len() + 2);
+    literal.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{{{:04X}}}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,37 @@
+-- Decodes every fixture, re-encodes the decoded value and expects the JSON to equal the fixture.
+roundTrip : String -> D.Decoder a -> (a -> E.Value) -> List String -> Test
+roundTrip typeName decoder encoder fixtures =
+    describe typeName
+        (List.indexedMap
+            (\index fixture ->
+                test ("value #" ++ String.fromInt index) <|
+                    \_ ->
+                        case D.decodeString decoder fixture of
+                            Ok value ->
+                                Expect.equal (canonicalize fixture) (canonicalize (E.encode 0 (encoder value)))
+
+                            Err err ->
+                                Expect.fail ("cannot decode " ++ fixture ++ ": " ++ D.errorToString err)
+            )
+            fixtures
+        )
+
+
+-- JSON with the keys of all objects sorted, since their order is irrelevant.
+canonicalize : String -> String
+canonicalize json =
+    case D.decodeString canonical json of
+        Ok value ->
+            E.encode 0 value
+
+        Err err ->
+            "invalid JSON: " ++ D.errorToString err
+
+
+canonical : D.Decoder E.Value
+canonical =
+    D.oneOf
+        [ D.list (D.lazy (\_ -> canonical)) |> D.map (E.list identity)
+        , D.keyValuePairs (D.lazy (\_ -> canonical)) |> D.map (List.sortBy Tuple.first >> E.object)
+        , D.value
+        ]
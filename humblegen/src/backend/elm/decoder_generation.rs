@@ -1,21 +1,23 @@
-use super::{to_atom, type_generation};
-use crate::ast;
+use super::{form_generation, is_string_key, to_atom, type_generation};
+use crate::{ast, WireCasing};
 use inflector::Inflector;
 
 use itertools::Itertools; // directly call join(.) on iterators
 
-/// Generate elm code for decoders for a spec.
-pub fn generate_type_decoders(spec: &ast::Spec) -> String {
+/// Generate elm code for decoders for a spec, with names converted to `casing`.
+pub fn generate_type_decoders(spec: &ast::Spec, casing: WireCasing) -> String {
     spec.iter()
         .filter_map(|spec_item| match spec_item {
-            ast::SpecItem::StructDef(sdef) => Some(generate_struct_decoder(sdef)),
-            ast::SpecItem::EnumDef(edef) => Some(generate_enum_decoder(edef)),
+            ast::SpecItem::StructDef(sdef) => Some(generate_struct_decoder(sdef, casing)),
+            ast::SpecItem::TupleStructDef(tdef) => Some(generate_tuple_struct_decoder(tdef)),
+            ast::SpecItem::EnumDef(edef) => Some(generate_enum_decoder(edef, casing)),
+            ast::SpecItem::FlagsDef(fdef) => Some(generate_flags_decoder(fdef, casing)),
             ast::SpecItem::ServiceDef(_) => None,
         })
         .join("\n\n\n")
 }
 
-fn generate_struct_decoder(sdef: &ast::StructDef) -> String {
+fn generate_struct_decoder(sdef: &ast::StructDef, casing: WireCasing) -> String {
     let ns = "";
     format!(
         "{dec_name} : D.Decoder {name} \n\
@@ -25,41 +27,111 @@ fn generate_struct_decoder(sdef: &ast::StructDef) -> String {
         field_decoders = sdef
             .fields
             .iter()
-            .map(|f| generate_field_decoder(f, ns))
+            .map(|f| generate_field_decoder(f, ns, casing))
             .join("\n        ")
     )
 }
 
-fn generate_enum_decoder(edef: &ast::EnumDef) -> String {
+/// Tuple structs are decoded from lists, newtype structs from their element.
+fn generate_tuple_struct_decoder(tdef: &ast::TupleStructDef) -> String {
     let ns = "";
+    let decoder = if tdef.is_newtype() {
+        format!(
+            "D.map {} {}",
+            tdef.name,
+            to_atom(generate_type_decoder(&tdef.elements.elements()[0], ns))
+        )
+    } else {
+        format!(
+            "D.succeed {} {}",
+            tdef.name,
+            generate_components_by_index_pipeline(&tdef.elements, ns)
+        )
+    };
+    format!(
+        "{dec_name} : D.Decoder {name}
+{dec_name} =
+    {decoder}",
+        dec_name = decoder_name(&tdef.name, ns),
+        name = tdef.name,
+        decoder = decoder,
+    )
+}
 
-    let mut fields = edef.variants.iter().map(|variant| {
-        match variant.variant_type {
-            ast::VariantType::Simple => {
-                format!(
-                    "D.string |> D.andThen (\\s -> if s == \"{name}\" then D.succeed {name} else D.fail \"\")",
-                    name = variant.name
-                )
-            }
-            ast::VariantType::Tuple(ref components) => format!(
+fn generate_enum_decoder(edef: &ast::EnumDef, casing: WireCasing) -> String {
+    let ns = "";
+
+    if edef.is_numeric() {
+        return generate_numeric_enum_decoder(edef);
+    }
+
+    let tagging = edef.tagging();
+    let mut fields: Vec<_> = edef.variants.iter().map(|variant| {
+        let wire_name = variant.wire_name(casing);
+        let content = match variant.variant_type {
+            ast::VariantType::Simple => None,
+            ast::VariantType::Tuple(ref components) => Some(format!(
                 "D.succeed {name} {components}",
                 name = variant.name,
                 components = generate_components_by_index_pipeline(components, ns)
-            ),
-            ast::VariantType::Struct(ref fields) => format!(
-                "D.field \"{variantName}\" (D.succeed {name} {field_decoders} |> D.map {variantName})",
+            )),
+            ast::VariantType::Struct(ref fields) => Some(format!(
+                "D.succeed {name} {field_decoders} |> D.map {variantName}",
                 name = type_generation::enum_anonymous_struct_constructor_name(&edef.name, &variant.name),
                 variantName = variant.name,
-                field_decoders = fields.iter().map(|f| generate_field_decoder(f, ns)).join(" "),
-            ),
-            ast::VariantType::Newtype(ref ty) => format!(
-                "D.field \"{variantName}\" (D.map {name} {ty})",
+                field_decoders = fields.iter().map(|f| generate_field_decoder(f, ns, casing)).join(" "),
+            )),
+            ast::VariantType::Newtype(ref ty) => Some(format!(
+                "D.map {name} {ty}",
                 name = variant.name,
-                variantName = variant.name,
                 ty = to_atom(generate_type_decoder(ty, ns)),
+            )),
+        };
+        match (tagging, content) {
+            (ast::EnumTagging::External, None) => format!(
+                "D.string |> D.andThen (\\s -> if s == \"{wireName}\" then D.succeed {name} else D.fail \"\")",
+                name = variant.name,
+                wireName = wire_name,
+            ),
+            (ast::EnumTagging::External, Some(content)) => {
+                format!("D.field \"{}\" ({})", wire_name, content)
+            }
+            (ast::EnumTagging::Internal { tag } | ast::EnumTagging::Adjacent { tag, .. }, None) => format!(
+                "builtinDecodeTagged \"{}\" \"{}\" (D.succeed {})",
+                tag, wire_name, variant.name
             ),
+            (ast::EnumTagging::Internal { tag }, Some(content)) => format!(
+                "builtinDecodeTagged \"{}\" \"{}\" ({})",
+                tag, wire_name, content
+            ),
+            (ast::EnumTagging::Adjacent { tag, content: key }, Some(content)) => format!(
+                "builtinDecodeTagged \"{}\" \"{}\" (D.field \"{}\" ({}))",
+                tag, wire_name, key, content
+            ),
+            (ast::EnumTagging::Untagged, None) => format!("D.null {}", variant.name),
+            (ast::EnumTagging::Untagged, Some(content)) => content,
         }
-    });
+    }).collect();
+
+    if edef.is_non_exhaustive() {
+        let tag = match tagging {
+            ast::EnumTagging::Internal { tag } | ast::EnumTagging::Adjacent { tag, .. } => {
+                format!("(Just \"{}\")", tag)
+            }
+            ast::EnumTagging::External | ast::EnumTagging::Untagged => "Nothing".to_owned(),
+        };
+        let names = edef
+            .variants
+            .iter()
+            .map(|variant| format!("\"{}\"", variant.wire_name(casing)))
+            .join(", ");
+        fields.push(format!(
+            "builtinDecodeUnknownVariant {} [{}] {}",
+            tag,
+            names,
+            type_generation::enum_unknown_variant_name(&edef.name)
+        ));
+    }
 
     format!(
         "{dec_name} : D.Decoder {name}\n{dec_name} =\n    D.oneOf\n        [{fields}\n        ]",
@@ -69,14 +141,104 @@ fn generate_enum_decoder(edef: &ast::EnumDef) -> String {
     )
 }
 
-fn generate_field_decoder(field: &ast::FieldNode, ns: &str) -> String {
+/// The decoder of a flags type, failing on unknown flags.
+fn generate_flags_decoder(fdef: &ast::FlagsDef, casing: WireCasing) -> String {
+    let flags = fdef
+        .flags
+        .iter()
+        .map(|flag| {
+            format!(
+                "\"{}\" ->\n                            D.succeed {}",
+                flag.wire_name(casing),
+                type_generation::flag_constructor_name(&fdef.name, &flag.name)
+            )
+        })
+        .join("\n\n                        ");
+    format!(
+        "{dec_name} : D.Decoder {name}\n{dec_name} =\n    D.list\n        (D.string\n            |> D.andThen\n                (\\s ->\n                    case s of\n                        {flags}\n\n                        _ ->\n                            D.fail (\"unknown flag \" ++ s)\n                )\n        )",
+        dec_name = decoder_name(&fdef.name, ""),
+        name = fdef.name,
+        flags = flags,
+    )
+}
+
+/// The decoder of a `@numeric` enum, decoding the discriminants of its simple variants.
+fn generate_numeric_enum_decoder(edef: &ast::EnumDef) -> String {
+    let variants = edef
+        .variants
+        .iter()
+        .zip(edef.discriminants())
+        .map(|(variant, discriminant)| format!("( {}, {} )", discriminant, variant.name))
+        .join(", ");
+    let unknown = if edef.is_non_exhaustive() {
+        format!(
+            "(Just {})",
+            type_generation::enum_unknown_variant_name(&edef.name)
+        )
+    } else {
+        "Nothing".to_owned()
+    };
     format!(
-        "|> required \"{name}\" {decoder}",
-        name = field.pair.name,
-        decoder = to_atom(generate_type_decoder(&field.pair.type_ident, ns)),
+        "{dec_name} : D.Decoder {name}\n{dec_name} =\n    builtinDecodeDiscriminant [ {variants} ] {unknown}",
+        dec_name = decoder_name(&edef.name, ""),
+        name = edef.name,
+        variants = variants,
+        unknown = unknown,
     )
 }
 
+fn generate_field_decoder(field: &ast::FieldNode, ns: &str, casing: WireCasing) -> String {
+    let renamed_from = field.renamed_from();
+    // `optional` fields may be absent, which decodes to `Nothing`, fields with a default or set
+    // by the server to their default
+    let (presence, default) = if field.optional {
+        ("optional", String::new())
+    } else if let Some(value) = field.default_value() {
+        let default = literal(&field.pair.type_ident, value);
+        ("defaulted", format!("{} ", to_atom(default)))
+    } else if field.is_server_set() {
+        let default = form_generation::generate_empty_value(&field.pair.type_ident);
+        ("defaulted", format!("{} ", to_atom(default)))
+    } else {
+        ("required", String::new())
+    };
+    if renamed_from.is_empty() {
+        format!(
+            "|> {presence} \"{name}\" {default}{decoder}",
+            presence = presence,
+            name = field.wire_name(casing),
+            default = default,
+            decoder = to_atom(generate_type_decoder(&field.pair.type_ident, ns)),
+        )
+    } else {
+        format!(
+            "|> {presence}Renamed \"{name}\" [ {old_names} ] {default}{decoder}",
+            presence = presence,
+            default = default,
+            name = field.wire_name(casing),
+            old_names = renamed_from
+                .iter()
+                .map(|n| format!("\"{}\"", casing.field_name(n)))
+                .join(", "),
+            decoder = to_atom(generate_type_decoder(&field.pair.type_ident, ns)),
+        )
+    }
+}
+
+/// The elm value of the `@default` literal `value` of a field of type `type_ident`.
+fn literal(type_ident: &ast::TypeIdent, value: &str) -> String {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(ast::AtomType::Bool) if value == "true" => "True".to_owned(),
+        ast::TypeIdent::BuiltIn(ast::AtomType::Bool) => "False".to_owned(),
+        // large integers represented as strings, see `LargeIntRepresentation`
+        ast::TypeIdent::BuiltIn(ast::AtomType::Str | ast::AtomType::I64 | ast::AtomType::U64) => {
+            format!("\"{}\"", value.replace('\\', "\\\\"))
+        }
+        _ if value.starts_with('-') => format!("({})", value),
+        _ => value.to_owned(),
+    }
+}
+
 pub(crate) fn generate_type_decoder(type_ident: &ast::TypeIdent, ns: &str) -> String {
     match type_ident {
         ast::TypeIdent::BuiltIn(atom) => generate_atom_decoder(atom, ns),
@@ -96,10 +258,9 @@ pub(crate) fn generate_type_decoder(type_ident: &ast::TypeIdent, ns: &str) -> St
         ),
         ast::TypeIdent::Map(key, value) => {
             // TODO: elm supports more than D.string, every comparable type
-            assert_eq!(
-                generate_type_decoder(key, ns),
-                "D.string",
-                "elm only supports dict keys"
+            assert!(
+                is_string_key(key),
+                "map keys are checked by `check_supported`"
             );
             format!("D.dict {}", to_atom(generate_type_decoder(value, ns)))
         }
@@ -126,7 +287,7 @@ fn generate_components_by_index_pipeline(tuple: &ast::TupleDef, ns: &str) -> Str
         .iter()
         .enumerate()
         .map(|(index, element)| {
-            let decoder = to_atom(generate_type_decoder(&element, ns));
+            let decoder = to_atom(generate_type_decoder(element, ns));
             format!("|> requiredIdx {} {}", index, decoder)
         })
         .join(" ")
@@ -139,6 +300,8 @@ fn generate_atom_decoder(atom: &ast::AtomType, ns: &str) -> String {
         ast::AtomType::I32 => "D.int".to_string(),
         ast::AtomType::U32 => "D.int".to_string(),
         ast::AtomType::U8 => "D.int".to_string(),
+        // represented as strings, see `elm::Generator::prepare` for numbers
+        ast::AtomType::I64 | ast::AtomType::U64 => format!("{}builtinDecodeLargeInt", ns),
         ast::AtomType::F64 => "D.float".to_string(),
         ast::AtomType::Bool => "D.bool".to_string(),
         ast::AtomType::DateTime => format!("{}builtinDecodeIso8601", ns),
@@ -1,139 +1,317 @@
-use super::{field_name, to_atom};
-use crate::ast;
+use super::{field_name, is_string_key, to_atom, type_generation};
+use crate::{ast, WireCasing};
 
 use inflector::Inflector;
 use itertools::Itertools;
 
-/// Generate elm code for encoder functions for `spec`.
-pub fn generate_struct_and_enum_encoders(spec: &ast::Spec) -> String {
+/// Generate elm code for encoder functions for `spec`, with names converted to `casing`.
+pub fn generate_struct_and_enum_encoders(spec: &ast::Spec, casing: WireCasing) -> String {
     spec.iter()
         .filter_map(|spec_item| match spec_item {
             ast::SpecItem::StructDef(sdef) => {
-                let json_encoder = generate_struct_json_encoder(sdef);
-                let query_encoder = generate_struct_query_encoder(sdef);
+                let json_encoder = generate_struct_json_encoder(sdef, casing);
+                let query_encoder = generate_struct_query_encoder(sdef, casing);
                 Some(format!("{}\n\n\n{}", json_encoder, query_encoder))
             }
-            ast::SpecItem::EnumDef(edef) => Some(generate_enum_encoder(edef)),
+            ast::SpecItem::TupleStructDef(tdef) => Some(generate_tuple_struct_encoder(tdef)),
+            ast::SpecItem::EnumDef(edef) => Some(generate_enum_encoder(edef, casing)),
+            ast::SpecItem::FlagsDef(fdef) => Some(generate_flags_encoder(fdef, casing)),
             ast::SpecItem::ServiceDef(_) => None,
         })
         .join("\n\n\n")
 }
 
-fn generate_struct_json_encoder(sdef: &ast::StructDef) -> String {
+fn generate_struct_json_encoder(sdef: &ast::StructDef, casing: WireCasing) -> String {
     let ns = "";
     format!(
-        "{encoder_name} : {type_name} -> E.Value\n{encoder_name} obj =\n    E.object\n        [ {fields}\n        ]",
+        "{encoder_name} : {type_name} -> E.Value\n{encoder_name} obj =\n    {object}",
         encoder_name = struct_or_enum_encoder_name(&sdef.name, ns),
         type_name = sdef.name,
-        fields = sdef.fields.iter().map(|f| generate_field_json_encoder(f, ns)).join("\n        , "),
+        object = generate_json_object(None, &sdef.fields, ns, casing, |entries| {
+            format!("\n        [ {}\n        ]", entries.join("\n        , "))
+        }),
     )
 }
 
-fn generate_struct_query_encoder(sdef: &ast::StructDef) -> String {
+fn generate_struct_query_encoder(sdef: &ast::StructDef, casing: WireCasing) -> String {
     let ns = "";
     format!(
-        "{encoder_name} : {type_name} -> List Url.Builder.QueryParameter\n{encoder_name} obj =\n    [ {fields}\n    ]",
+        "{encoder_name} : {type_name} -> List Url.Builder.QueryParameter\n{encoder_name} obj =\n    List.filterMap identity\n        [ {fields}\n        ]",
         encoder_name = query_struct_encoder_name(&sdef.name, ns),
         type_name = sdef.name,
-        fields = sdef.fields.iter().map(|f| generate_field_query_encoder(f, ns)).join("\n    , "),
+        fields = sdef.fields.iter().map(|f| generate_field_query_encoder(f, ns, casing)).join("\n        , "),
     )
 }
 
-fn generate_enum_encoder(edef: &ast::EnumDef) -> String {
+/// Tuple structs are encoded as lists, newtype structs as their element.
+fn generate_tuple_struct_encoder(tdef: &ast::TupleStructDef) -> String {
     let ns = "";
+    let encoders: Vec<String> = tdef
+        .elements
+        .elements()
+        .iter()
+        .enumerate()
+        .map(|(idx, element)| format!("{} x{}", generate_type_json_encoder(element, ns), idx))
+        .collect();
+    format!(
+        "{encoder_name} : {type_name} -> E.Value
+{encoder_name} ({type_name} {field_names}) =
+    {value}",
+        encoder_name = struct_or_enum_encoder_name(&tdef.name, ns),
+        type_name = tdef.name,
+        field_names = (0..encoders.len()).map(|i| format!("x{}", i)).join(" "),
+        value = if tdef.is_newtype() {
+            encoders[0].clone()
+        } else {
+            format!("E.list identity [ {} ]", encoders.join(", "))
+        },
+    )
+}
+
+/// Generate the encoder of a flags type, encoding the contained flags in the order of their
+/// definition, like the Rust backend.
+fn generate_flags_encoder(fdef: &ast::FlagsDef, casing: WireCasing) -> String {
+    let flags = fdef
+        .flags
+        .iter()
+        .map(|flag| {
+            format!(
+                "( {}, \"{}\" )",
+                type_generation::flag_constructor_name(&fdef.name, &flag.name),
+                flag.wire_name(casing)
+            )
+        })
+        .join(", ");
+    format!(
+        "{encoder_name} : {type_name} -> E.Value\n{encoder_name} flags =\n    [ {flags} ]\n        |> List.filter (\\( flag, _ ) -> List.member flag flags)\n        |> E.list (\\( _, name ) -> E.string name)",
+        encoder_name = struct_or_enum_encoder_name(&fdef.name, ""),
+        type_name = fdef.name,
+        flags = flags,
+    )
+}
+
+fn generate_enum_encoder(edef: &ast::EnumDef, casing: WireCasing) -> String {
+    let ns = "";
+
+    let mut variants: Vec<_> = if edef.is_numeric() {
+        edef.variants
+            .iter()
+            .zip(edef.discriminants())
+            .map(|(v, discriminant)| {
+                // negative literals are parenthesized as arguments
+                let discriminant = if discriminant < 0 {
+                    format!("({})", discriminant)
+                } else {
+                    discriminant.to_string()
+                };
+                format!("{} -> E.int {}", v.name, discriminant)
+            })
+            .collect()
+    } else {
+        edef.variants
+            .iter()
+            .map(|v| generate_variant_encoder_branch(v, edef.tagging(), ns, casing))
+            .collect()
+    };
+    if edef.is_non_exhaustive() {
+        // unknown variants cannot be encoded, the server rejects them
+        variants.push(format!(
+            "{} -> E.null",
+            type_generation::enum_unknown_variant_name(&edef.name)
+        ));
+    }
 
     format!(
         "{encoder_name} : {type_name} -> E.Value\n{encoder_name} v =\n    case v of\n        {variants}",
         encoder_name = struct_or_enum_encoder_name(&edef.name, ns),
         type_name = edef.name,
-        variants = edef
-            .variants
-            .iter()
-            .map(|v| generate_variant_encoder_branch(v, ns))
-            .join("\n        "),
+        variants = variants.join("\n        "),
     )
 }
 
-fn generate_field_json_encoder(field: &ast::FieldNode, ns: &str) -> String {
-    format!(
-        "(\"{name}\", {value_encoder} obj.{field_name})",
-        name = field.pair.name,
-        field_name = field_name(&field.pair.name),
-        value_encoder = generate_type_json_encoder(&field.pair.type_ident, ns)
-    )
+/// Generate the `E.object` of `fields`, following the `tag` entry of internally tagged variants.
+/// The entries are a list formatted by `list`, including the whitespace before it.
+///
+/// Absent `optional` fields are omitted from the object.
+fn generate_json_object(
+    tag: Option<String>,
+    fields: &ast::StructFields,
+    ns: &str,
+    casing: WireCasing,
+    list: impl Fn(Vec<String>) -> String,
+) -> String {
+    if !fields.iter().any(|f| f.optional) {
+        let entries = tag
+            .into_iter()
+            .chain(
+                fields
+                    .iter()
+                    .map(|f| generate_field_json_encoder(f, ns, casing)),
+            )
+            .collect();
+        return format!("E.object{}", list(entries));
+    }
+
+    let entries = tag
+        .into_iter()
+        .map(|entry| format!("Just {}", entry))
+        .chain(fields.iter().map(|f| {
+            let entry = generate_field_json_encoder(f, ns, casing);
+            if f.optional {
+                entry
+            } else {
+                format!("Just {}", entry)
+            }
+        }))
+        .collect();
+    format!("E.object <| List.filterMap identity{}", list(entries))
 }
 
-fn generate_field_query_encoder(field: &ast::FieldNode, ns: &str) -> String {
-    // TODO: escape strings (but we could fix this in the whole codebase)
-    match field.pair.type_ident {
-        ast::TypeIdent::BuiltIn(ast::AtomType::Str) => format!(
-            "Url.Builder.string \"{name}\" obj.{field_name}",
-            name = field.pair.name,
-            field_name = field_name(&field.pair.name)
-        ),
-        ast::TypeIdent::BuiltIn(ast::AtomType::Uuid) => format!(
-            "Url.Builder.string \"{name}\" (BuiltinUuid.encodeQuery obj.{field_name})",
-            name = field.pair.name,
-            field_name = field_name(&field.pair.name)
-        ),
-        ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => format!(
-            "Url.Builder.string \"{name}\" (BuiltinBytes.encodeQuery obj.{field_name})",
-            name = field.pair.name,
-            field_name = field_name(&field.pair.name)
-        ),
-        ast::TypeIdent::BuiltIn(ast::AtomType::I32)
-        | ast::TypeIdent::BuiltIn(ast::AtomType::U32)
-        | ast::TypeIdent::BuiltIn(ast::AtomType::U8) => format!(
-            "Url.Builder.int \"{name}\" obj.{field_name}",
-            name = field.pair.name,
-            field_name = field_name(&field.pair.name),
+/// Generate the `(key, value)` entry of `field`, or a `Maybe` of it for an `optional` field.
+fn generate_field_json_encoder(field: &ast::FieldNode, ns: &str, casing: WireCasing) -> String {
+    let name = field.wire_name(casing);
+    let value_encoder = generate_type_json_encoder(&field.pair.type_ident, ns);
+    let field_name = field_name(&field.pair.name);
+    if field.optional {
+        format!(
+            "Maybe.map (\\v -> (\"{}\", {} v)) obj.{}",
+            name, value_encoder, field_name
+        )
+    } else {
+        format!("(\"{}\", {} obj.{})", name, value_encoder, field_name)
+    }
+}
+
+/// Generate an elm expression of type `Maybe Url.Builder.QueryParameter` for a query struct field.
+///
+/// `Nothing` values of optional fields are omitted from the query.
+fn generate_field_query_encoder(field: &ast::FieldNode, ns: &str, casing: WireCasing) -> String {
+    let name = &field.wire_name(casing);
+    let value = format!("obj.{}", field_name(&field.pair.name));
+    match &field.value_type() {
+        ast::TypeIdent::BuiltIn(atom) => {
+            format!("Just ({})", generate_atom_query_param(atom, name, &value))
+        }
+        ast::TypeIdent::Option(inner) => match inner.as_ref() {
+            ast::TypeIdent::BuiltIn(atom) => format!(
+                "Maybe.map (\\v -> {}) {}",
+                generate_atom_query_param(atom, name, "v"),
+                value
+            ),
+            other => format!(
+                "Maybe.map ({} >> E.encode 0 >> Url.Builder.string \"{}\") {}",
+                to_atom(generate_type_json_encoder(other, ns)),
+                name,
+                value
+            ),
+        },
+        // encode other types as json encoded strings
+        other => format!(
+            "Just ({} |> {} |> E.encode 0 |> Url.Builder.string \"{}\")",
+            value,
+            to_atom(generate_type_json_encoder(other, ns)),
+            name
         ),
-        _ => {
-            // encode other types as json encoded strings
-            format!(
-                "obj.{field_name} |> {value_encoder} |> E.encode 4 |> Url.Builder.string \"{name}\"",
-                name = field.pair.name,
-                field_name = field_name(&field.pair.name),
-                value_encoder = generate_complex_type_query_encoder(&field.pair.type_ident, ns)
-            )
+    }
+}
+
+/// Generate an elm expression of type `Url.Builder.QueryParameter` for an atom `value`.
+fn generate_atom_query_param(atom: &ast::AtomType, name: &str, value: &str) -> String {
+    let string_param = |s: String| format!("Url.Builder.string \"{}\" ({})", name, s);
+    match atom {
+        ast::AtomType::Empty => format!("Url.Builder.string \"{}\" \"\"", name),
+        ast::AtomType::Str => format!("Url.Builder.string \"{}\" {}", name, value),
+        ast::AtomType::I32 | ast::AtomType::U32 | ast::AtomType::U8 => {
+            format!("Url.Builder.int \"{}\" {}", name, value)
         }
+        ast::AtomType::I64 | ast::AtomType::U64 => {
+            format!("Url.Builder.string \"{}\" {}", name, value)
+        }
+        ast::AtomType::F64 => string_param(format!("String.fromFloat {}", value)),
+        ast::AtomType::Bool => string_param(format!("if {} then \"true\" else \"false\"", value)),
+        ast::AtomType::DateTime => string_param(format!("Iso8601.fromTime {}", value)),
+        ast::AtomType::Date => string_param(format!("Date.toIsoString {}", value)),
+        ast::AtomType::Uuid => string_param(format!("BuiltinUuid.encodeQuery {}", value)),
+        ast::AtomType::Bytes => string_param(format!("BuiltinBytes.encodeQuery {}", value)),
     }
 }
 
-fn generate_variant_encoder_branch(variant: &ast::VariantDef, ns: &str) -> String {
-    match variant.variant_type {
-        ast::VariantType::Simple => format!("{name} -> E.string \"{name}\"", name = variant.name),
-        ast::VariantType::Tuple(ref tdef) => format!(
-            "{name} {field_names} -> E.object [ (\"{name}\", E.list identity [{field_encoders}]) ]",
-            name = variant.name,
-            field_names = (0..tdef.elements().len())
-                .map(|i| format!("x{}", i))
-                .join(" "),
-            field_encoders = tdef
-                .elements()
-                .iter()
-                .enumerate()
-                .map(|(idx, component)| format!(
-                    "{} x{}",
-                    generate_type_json_encoder(component, ns),
-                    idx
-                ))
-                .join(", "),
+fn generate_variant_encoder_branch(
+    variant: &ast::VariantDef,
+    tagging: ast::EnumTagging,
+    ns: &str,
+    casing: WireCasing,
+) -> String {
+    let wire_name = variant.wire_name(casing);
+    let tag_field = |tag: &str| format!("(\"{}\", E.string \"{}\")", tag, wire_name);
+    // the fields of struct variants, which internally tagged enums put next to the tag
+    let mut fields = None;
+    let (pattern, content) = match variant.variant_type {
+        ast::VariantType::Simple => (variant.name.clone(), None),
+        ast::VariantType::Tuple(ref tdef) => (
+            format!(
+                "{} {}",
+                variant.name,
+                (0..tdef.elements().len())
+                    .map(|i| format!("x{}", i))
+                    .join(" ")
+            ),
+            Some(format!(
+                "E.list identity [{}]",
+                tdef.elements()
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, component)| format!(
+                        "{} x{}",
+                        generate_type_json_encoder(component, ns),
+                        idx
+                    ))
+                    .join(", ")
+            )),
         ),
-        ast::VariantType::Struct(ref fields) => format!(
-            "{name} obj -> E.object [ (\"{name}\", E.object [{fields}]) ]",
-            name = variant.name,
-            fields = fields
-                .iter()
-                .map(|f| generate_field_json_encoder(f, ns))
-                .join(", "),
+        ast::VariantType::Struct(ref struct_fields) => {
+            fields = Some(struct_fields);
+            (
+                format!("{} obj", variant.name),
+                Some(generate_json_object(
+                    None,
+                    struct_fields,
+                    ns,
+                    casing,
+                    |entries| format!(" [{}]", entries.join(", ")),
+                )),
+            )
+        }
+        ast::VariantType::Newtype(ref ty) => (
+            format!("{} obj", variant.name),
+            Some(format!("{} obj", generate_type_json_encoder(ty, ns))),
         ),
-        ast::VariantType::Newtype(ref ty) => format!(
-            "{name} obj -> E.object [ (\"{name}\", {enc} obj) ]",
-            name = variant.name,
-            enc = generate_type_json_encoder(ty, ns),
+    };
+    let value = match (tagging, content) {
+        (ast::EnumTagging::External, None) => format!("E.string \"{}\"", wire_name),
+        (ast::EnumTagging::External, Some(content)) => {
+            format!("E.object [ (\"{}\", {}) ]", wire_name, content)
+        }
+        (ast::EnumTagging::Internal { tag }, _) => generate_json_object(
+            Some(tag_field(tag)),
+            fields.unwrap_or(&ast::StructFields(vec![])),
+            ns,
+            casing,
+            |entries| format!(" [ {} ]", entries.join(", ")),
         ),
-    }
+        (ast::EnumTagging::Adjacent { tag, .. }, None) => {
+            format!("E.object [ {} ]", tag_field(tag))
+        }
+        (ast::EnumTagging::Adjacent { tag, content: key }, Some(content)) => format!(
+            "E.object [ {}, (\"{}\", {}) ]",
+            tag_field(tag),
+            key,
+            content
+        ),
+        (ast::EnumTagging::Untagged, None) => "E.null".to_owned(),
+        (ast::EnumTagging::Untagged, Some(content)) => content,
+    };
+    format!("{} -> {}", pattern, value)
 }
 
 /// Generate elm code for a type encoder.
@@ -157,10 +335,9 @@ fn generate_type_encoder(
             to_atom(generate_type_json_encoder(ok, ns))
         ),
         ast::TypeIdent::Map(key, value) => {
-            assert_eq!(
-                generate_type_json_encoder(key, ns),
-                "E.string",
-                "can only encode string keys in maps"
+            assert!(
+                is_string_key(key),
+                "map keys are checked by `check_supported`"
             );
             format!(
                 "E.dict identity {}",
@@ -176,10 +353,6 @@ pub(crate) fn generate_type_json_encoder(type_ident: &ast::TypeIdent, ns: &str)
     generate_type_encoder(&generate_atom_json_encoder, type_ident, ns)
 }
 
-fn generate_complex_type_query_encoder(type_ident: &ast::TypeIdent, ns: &str) -> String {
-    generate_type_encoder(&generate_atom_query_encoder, type_ident, ns)
-}
-
 pub(crate) fn generate_type_urlcomponent_encoder(type_ident: &ast::TypeIdent, ns: &str) -> String {
     generate_type_encoder(&generate_atom_urlcomponent_encoder, type_ident, ns)
 }
@@ -191,6 +364,7 @@ fn generate_atom_json_encoder(atom: &ast::AtomType, ns: &str) -> String {
         ast::AtomType::I32 => "E.int".to_owned(),
         ast::AtomType::U32 => "E.int".to_owned(),
         ast::AtomType::U8 => "E.int".to_owned(),
+        ast::AtomType::I64 | ast::AtomType::U64 => "E.string".to_owned(),
         ast::AtomType::F64 => "E.float".to_owned(),
         ast::AtomType::Bool => "E.bool".to_owned(),
         ast::AtomType::DateTime => format!("{}builtinEncodeIso8601", ns),
@@ -200,29 +374,17 @@ fn generate_atom_json_encoder(atom: &ast::AtomType, ns: &str) -> String {
     }
 }
 
-fn generate_atom_query_encoder(atom: &ast::AtomType, ns: &str) -> String {
+fn generate_atom_urlcomponent_encoder(atom: &ast::AtomType, _ns: &str) -> String {
     match atom {
-        ast::AtomType::Empty => "E.null".to_owned(),
-        ast::AtomType::Str => "Url.Builder.string".to_owned(),
-        ast::AtomType::Uuid => "Url.Builder.uuid".to_owned(),
-        ast::AtomType::Bytes => "Url.Builder.bytes".to_owned(),
-        ast::AtomType::I32 | ast::AtomType::U32 | ast::AtomType::U8 => "Url.Builder.int".to_owned(),
-        ast::AtomType::F64 => "E.float".to_owned(),
-        ast::AtomType::Bool => "E.bool".to_owned(),
-        ast::AtomType::DateTime => format!("{}builtinEncodeIso8601", ns),
-        ast::AtomType::Date => format!("{}builtinEncodeDate", ns),
-    }
-}
-
-fn generate_atom_urlcomponent_encoder(atom: &ast::AtomType, ns: &str) -> String {
-    match atom {
-        ast::AtomType::Empty => unimplemented!(),
-        ast::AtomType::Str => "identity".to_owned(),
+        ast::AtomType::Empty => {
+            unreachable!("`()` in route segments is rejected by `check_supported`")
+        }
+        ast::AtomType::Str | ast::AtomType::I64 | ast::AtomType::U64 => "identity".to_owned(),
         ast::AtomType::I32 | ast::AtomType::U32 | ast::AtomType::U8 => "String.fromInt".to_owned(),
         ast::AtomType::F64 => "String.fromFloat".to_owned(),
-        ast::AtomType::Bool => "String.fromBool".to_owned(),
-        ast::AtomType::DateTime => format!("{}builtinEncodeIso8601", ns),
-        ast::AtomType::Date => format!("{}builtinEncodeDate", ns),
+        ast::AtomType::Bool => "(\\v -> if v then \"true\" else \"false\")".to_owned(),
+        ast::AtomType::DateTime => "Iso8601.fromTime".to_owned(),
+        ast::AtomType::Date => "Date.toIsoString".to_owned(),
         ast::AtomType::Uuid => "BuiltinUuid.encodeUrlcomponent".to_owned(),
         ast::AtomType::Bytes => "BuiltinBytes.encodeUrlcomponent".to_owned(),
     }
@@ -257,7 +419,7 @@ pub(crate) fn query_encoder(ident: &ast::TypeIdent, ns: &str) -> String {
     if let ast::TypeIdent::UserDefined(query_ty_name) = ident {
         query_struct_encoder_name(query_ty_name, ns)
     } else {
-        panic!("query MUST be a user defined struct");
+        unreachable!("queries that are not structs are rejected by `check_supported`");
     }
 }
 
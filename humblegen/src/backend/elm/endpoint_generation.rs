@@ -1,183 +1,368 @@
-// TODO: Fix lints and remove this.
-#![allow(clippy::write_literal)]
-
 use super::{
-    decoder_generation, encoder_generation, generate_doc_comment, to_atom, type_generation,
-    IndentWriter,
+    decoder_generation, encoder_generation, field_name, generate_comment, generate_doc_comment,
+    to_atom, type_generation,
 };
-use crate::{ast, LibError};
+use crate::ast;
+use crate::codegen::printer::{Printer, Separators};
 use inflector::Inflector;
-use std::io::Write;
-
-pub(crate) fn generate(service: &ast::ServiceDef, file: &mut IndentWriter) -> Result<(), LibError> {
-    file.kill_indent();
+use itertools::Itertools;
+use std::collections::BTreeSet;
 
-    write!(
-        file.start_line()?,
-        "{}",
-        generate_doc_comment(&service.doc_comment)
-    )?;
+pub(crate) fn generate(spec: &ast::Spec, service: &ast::ServiceDef, file: &mut Printer) {
+    file.line(generate_comment(&service.doc_comment));
+    file.blank_lines(2);
 
-    file.empty_lines(2)?;
+    if let Some(base_url) = &spec.meta().base_url {
+        file.line("{-| The URL the service is mounted at by default, declared in the `meta` block of the spec.");
+        file.line("Requests are sent to it unless changed with `withBase`.");
+        file.line("-}");
+        file.line("baseUrl : String");
+        file.line("baseUrl =");
+        file.with_indent(|file| file.line(format!("\"{}\"", base_url.escape_default())));
+        file.blank_lines(2);
+    }
 
     for endpoint in &service.endpoints {
         // Note: we currently generate a single flat function for each endpoint. This is what
         // OpenApi does. A worthfile, alternative api would generate an enum of endpoints
         // enum Endpoints = GetPet | PostMonster | etc first that is consumed by a generic
         // executeRequest function.
-        write!(
-            file.start_line()?,
-            "{}",
-            generate_doc_comment(&endpoint.doc_comment)
-        )?;
-
-        {
-            let mut line_type_signature = Vec::new();
-            let mut line_arguments = Vec::new();
-
-            let endpoint_name = synthesize_endpoint_name(&endpoint.route);
-            write!(line_type_signature, "{} : ", endpoint_name)?;
-            write!(line_arguments, "{}", endpoint_name)?;
-
-            for (idx, component) in endpoint.route.components().iter().enumerate() {
-                if let ast::ServiceRouteComponent::Variable(arg) = component {
-                    write!(
-                        line_type_signature,
-                        "{} -> ",
-                        to_atom(type_generation::generate_type_ident(&arg.type_ident, "Ty."))
-                    )?;
-                    write!(
-                        line_arguments,
-                        " component{idx}_{name}",
-                        idx = idx,
-                        name = arg.name
-                    )?;
-                }
-            }
+        file.line(generate_doc_comment(&endpoint.doc_comment));
 
-            if let Some(body) = endpoint.route.request_body() {
-                write!(
-                    line_type_signature,
-                    "{} -> ",
-                    to_atom(type_generation::generate_type_ident(&body, "Ty."))
-                )?;
-                write!(line_arguments, " body")?;
+        let endpoint_name = synthesize_endpoint_name(&endpoint.route);
+        let mut arg_types = vec![];
+        let mut args = vec![];
+        for (idx, component) in endpoint.route.components().iter().enumerate() {
+            if let Some(arg) = component.param() {
+                arg_types.push(to_atom(type_generation::generate_type_ident(
+                    &arg.type_ident,
+                    "Ty.",
+                )));
+                args.push(format!("component{}_{}", idx, arg.name));
             }
-
-            // return type
-            write!(
-                line_type_signature,
-                "Request {} {}",
-                endpoint
-                    .route
-                    .query()
-                    .as_ref()
-                    .map(|q| type_generation::generate_type_ident(q, "Ty."))
-                    .unwrap_or_else(|| "NoQuery".to_owned()),
-                to_atom(type_generation::generate_type_ident(
-                    endpoint.route.return_type(),
-                    "Ty."
-                ))
-            )?;
-
-            file.start_line()?.write_all(&line_type_signature)?;
-            file.start_line()?.write_all(&line_arguments)?;
+        }
+        if let Some(body) = endpoint.route.request_body() {
+            arg_types.push(to_atom(type_generation::generate_type_ident(body, "Ty.")));
+            args.push("body".to_owned());
+        }
+        for header in endpoint.route.headers() {
+            arg_types.push(to_atom(type_generation::generate_type_ident(
+                &header.type_ident,
+                "Ty.",
+            )));
+            args.push(header_argument_name(&header.name));
+        }
+        // idempotent routes take the key of the request, see `idempotencyKeyGenerator`
+        if endpoint.is_idempotent() {
+            arg_types.push("String".to_owned());
+            args.push("idempotencyKey".to_owned());
         }
 
-        write!(file.handle(), " =")?;
-
-        file.increase_indent();
-        write!(file.start_line()?, "makeRequest")?;
-        file.increase_indent();
-
-        // method
-        write!(
-            file.start_line()?,
-            "\"{}\"",
-            endpoint.route.http_method_as_str()
-        )?;
-
-        // urlComponents
-        {
-            file.increase_indent();
-            for (idx, component) in endpoint.route.components().iter().enumerate() {
-                let is_first = idx == 0;
-                let delimiter = if is_first { "[" } else { "," };
-
-                match component {
-                    ast::ServiceRouteComponent::Literal(literal) => {
-                        // TODO: is this escape sufficient and correct for elm?
-                        write!(
-                            file.start_line()?,
-                            "{delimiter} \"{}\"",
-                            literal.escape_default(),
-                            delimiter = delimiter
-                        )?;
-                    }
-
-                    ast::ServiceRouteComponent::Variable(arg) => {
-                        write!(
-                            file.start_line()?,
-                            "{delimiter} component{idx}_{name} |> {encoder}",
-                            encoder =
-                                to_atom(encoder_generation::generate_type_urlcomponent_encoder(
-                                    &arg.type_ident,
-                                    "AE."
-                                )),
-                            name = arg.name,
-                            idx = idx,
-                            delimiter = delimiter
-                        )?;
-                    }
-                }
-            }
+        // return type
+        let query_type = endpoint
+            .route
+            .query()
+            .as_ref()
+            .map(|q| type_generation::generate_type_ident(q, "Ty."))
+            .unwrap_or_else(|| "NoQuery".to_owned());
+        let return_type = to_atom(type_generation::generate_type_ident(
+            endpoint.route.return_type(),
+            "Ty.",
+        ));
+        file.line(format!(
+            "{} : {}Request {} {}",
+            endpoint_name,
+            arg_types.iter().map(|t| format!("{} -> ", t)).join(""),
+            query_type,
+            return_type
+        ));
+        file.line(format!(
+            "{}{} =",
+            endpoint_name,
+            args.iter().map(|a| format!(" {}", a)).join("")
+        ));
 
-            write!(file.start_line()?, "]")?;
+        file.with_indent(|file| {
+            file.line("makeRequest");
+            file.with_indent(|file| generate_make_request_args(spec, endpoint, file));
+        });
+        file.blank_lines(2);
+    }
+
+    // `loadMore` functions of paginated endpoints
+    let index = spec.index();
+    for endpoint in &service.endpoints {
+        if let Some(item_type) = index.page_item_type(endpoint) {
+            file.line(generate_load_more(endpoint, item_type));
+            file.blank_lines(2);
         }
+    }
+
+    // helpers turning multipart bodies into `Http.Part`s
+    let multipart_bodies: BTreeSet<&str> = service
+        .endpoints
+        .iter()
+        .filter(|e| e.route.request_body_kind() == Some(ast::BodyKind::Multipart))
+        .filter_map(|e| match e.route.request_body() {
+            Some(ast::TypeIdent::UserDefined(name)) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    for name in multipart_bodies {
+        let sdef = spec
+            .iter()
+            .find_map(|si| match si {
+                ast::SpecItem::StructDef(sdef) if sdef.name == name => Some(sdef),
+                _ => None,
+            })
+            .expect("multipart bodies are checked by `check_multipart_bodies`");
+        file.line(generate_multipart_parts(sdef));
+        file.blank_lines(2);
+    }
+}
+
+/// Generate the arguments of the `makeRequest` call of `endpoint`, one per line, followed by
+/// the `|> with...` pipeline adding its body and headers.
+fn generate_make_request_args(
+    spec: &ast::Spec,
+    endpoint: &ast::ServiceEndpoint,
+    file: &mut Printer,
+) {
+    // method
+    file.line(format!("\"{}\"", endpoint.route.http_method_as_str()));
 
-        // queryEncoder
-        {
-            if let Some(ident) = endpoint.route.query() {
-                write!(
-                    file.start_line()?,
-                    "{}",
-                    to_atom(encoder_generation::query_encoder(ident, "AE."))
-                )?;
-            } else {
-                write!(file.start_line()?, "noQueryEncoder")?;
+    // urlComponents
+    let components: Vec<String> = endpoint
+        .route
+        .components()
+        .iter()
+        .enumerate()
+        .map(|(idx, component)| match component {
+            // TODO: is this escape sufficient and correct for elm?
+            ast::ServiceRouteComponent::Literal(literal) => {
+                format!("\"{}\"", literal.escape_default())
             }
-        }
+            // the rest of a path is a single component, whose slashes `Url.Builder` keeps
+            ast::ServiceRouteComponent::Variable(arg) | ast::ServiceRouteComponent::Rest(arg) => {
+                format!(
+                    "component{idx}_{name} |> {encoder}",
+                    encoder = to_atom(encoder_generation::generate_type_urlcomponent_encoder(
+                        &arg.type_ident,
+                        "AE."
+                    )),
+                    name = arg.name,
+                    idx = idx,
+                )
+            }
+        })
+        .collect();
+    file.newline();
+    file.delimited(("[", "]"), &components, ",", Separators::Leading);
 
-        // resolver
-        write!(
-            file.start_line()?,
-            "(jsonResolver ({}))",
-            to_atom(decoder_generation::generate_type_decoder(
-                &endpoint.route.return_type(),
-                "AD."
-            ))
-        )?;
-
-        // |> withBody if we send a body
-        if let Some(body) = endpoint.route.request_body() {
-            write!(
-                file.start_line()?,
+    // queryEncoder
+    match endpoint.route.query() {
+        Some(ident) => file.line(to_atom(encoder_generation::query_encoder(ident, "AE."))),
+        None => file.line("noQueryEncoder"),
+    }
+
+    // resolver
+    let decoder = to_atom(decoder_generation::generate_type_decoder(
+        endpoint.route.return_type(),
+        "AD.",
+    ));
+    let returns_empty = matches!(
+        endpoint.route.return_type(),
+        ast::TypeIdent::BuiltIn(ast::AtomType::Empty)
+    );
+    match domain_error_statuses(spec, endpoint.route.return_type()).as_slice() {
+        [] if returns_empty => file.line("noContentResolver"),
+        [] => file.line(format!("(jsonResolver ({}))", decoder)),
+        statuses => file.line(format!(
+            "(jsonResolverWithStatuses [ {} ] ({}))",
+            statuses.iter().join(", "),
+            decoder
+        )),
+    }
+
+    // |> withBody if we send a body
+    match (
+        endpoint.route.request_body(),
+        endpoint.route.request_body_kind(),
+    ) {
+        (Some(ast::TypeIdent::UserDefined(name)), Some(ast::BodyKind::Multipart)) => {
+            file.line(format!(
+                "|> withMultipartBody ({} body)",
+                multipart_parts_name(name)
+            ));
+        }
+        (Some(body), _) => {
+            file.line(format!(
                 "|> withJsonBody {} body",
                 to_atom(encoder_generation::generate_type_json_encoder(body, "AE."))
-            )?;
+            ));
+        }
+        (None, _) => {}
+    }
+
+    // |> withHeader for each header parameter
+    for header in endpoint.route.headers() {
+        match &header.type_ident {
+            ast::TypeIdent::Option(inner) => file.line(format!(
+                "|> withOptionalHeader \"{}\" (Maybe.map {} {})",
+                header.name,
+                to_atom(encoder_generation::generate_type_urlcomponent_encoder(
+                    inner, "AE."
+                )),
+                header_argument_name(&header.name)
+            )),
+            other => file.line(format!(
+                "|> withHeader \"{}\" ({} {})",
+                header.name,
+                to_atom(encoder_generation::generate_type_urlcomponent_encoder(
+                    other, "AE."
+                )),
+                header_argument_name(&header.name)
+            )),
         }
+    }
+
+    if endpoint.is_idempotent() {
+        file.line("|> withHeader \"Idempotency-Key\" idempotencyKey");
+    }
+
+    if spec.meta().base_url.is_some() {
+        file.line("|> withBase baseUrl");
+    }
+}
 
-        file.decrease_indent();
+/// The HTTP status codes the server uses for the domain error of a `result[T][E]` return type,
+/// i.e., the `@status` codes of enum `E`.
+fn domain_error_statuses(spec: &ast::Spec, ret: &ast::TypeIdent) -> Vec<u16> {
+    let err_name = match ret {
+        ast::TypeIdent::Result(_, err) => match err.as_ref() {
+            ast::TypeIdent::UserDefined(name) => name,
+            _ => return vec![],
+        },
+        _ => return vec![],
+    };
+    spec.iter()
+        .find_map(|si| match si {
+            ast::SpecItem::EnumDef(edef) if &edef.name == err_name => Some(edef.http_statuses()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
 
-        file.kill_indent();
+/// Generate a function loading the next page of the paginated `endpoint` into a `PageState`
+/// of its `item_type`.
+///
+/// It takes the arguments of the endpoint function and the query, whose cursor it replaces.
+fn generate_load_more(endpoint: &ast::ServiceEndpoint, item_type: &ast::TypeIdent) -> String {
+    let endpoint_name = synthesize_endpoint_name(&endpoint.route);
+    let mut arg_types = vec![];
+    let mut args = vec![];
+    for (idx, component) in endpoint.route.components().iter().enumerate() {
+        if let Some(arg) = component.param() {
+            arg_types.push(to_atom(type_generation::generate_type_ident(
+                &arg.type_ident,
+                "Ty.",
+            )));
+            args.push(format!("component{}_{}", idx, arg.name));
+        }
+    }
+    for header in endpoint.route.headers() {
+        arg_types.push(to_atom(type_generation::generate_type_ident(
+            &header.type_ident,
+            "Ty.",
+        )));
+        args.push(header_argument_name(&header.name));
     }
+    let query_type = endpoint
+        .route
+        .query()
+        .as_ref()
+        .expect("paginated endpoints have a query");
+    let page_state = format!(
+        "PageState {}",
+        to_atom(type_generation::generate_type_ident(item_type, "Ty."))
+    );
+    format!(
+        "{{-| Loads the page of `{endpoint_name}` following the items of the page state, see `loadMore`.\n\
+         The cursor of the given query is replaced by the cursor of that page.\n-}}\n\
+         {fn_name} : {arg_types}{query_type} -> (Result Error {page_type} -> msg) -> {page_state} -> ( {page_state}, Cmd msg )\n\
+         {fn_name} {args}query toMsg state =\n    \
+         loadMore (\\cursor -> {endpoint_name}{endpoint_args} |> withQuery {{ query | cursor = cursor }}) toMsg state",
+        endpoint_name = endpoint_name,
+        fn_name = format!("loadMore{}", endpoint_name.to_pascal_case()),
+        arg_types = arg_types.iter().map(|t| format!("{} -> ", t)).join(""),
+        query_type = to_atom(type_generation::generate_type_ident(query_type, "Ty.")),
+        page_type = to_atom(type_generation::generate_type_ident(
+            endpoint.route.return_type(),
+            "Ty."
+        )),
+        page_state = page_state,
+        args = args.iter().map(|a| format!("{} ", a)).join(""),
+        endpoint_args = args.iter().map(|a| format!(" {}", a)).join(""),
+    )
+}
 
-    file.empty_lines(2)?;
+fn header_argument_name(header_name: &str) -> String {
+    format!("header_{}", header_name.to_snake_case())
+}
+
+fn multipart_parts_name(ident: &str) -> String {
+    format!("multipartParts{}", ident)
+}
 
-    Ok(())
+/// Generate a function turning a struct into `multipart/form-data` parts.
+///
+/// `str` and `bytes` fields become text and file parts, all other fields JSON-encoded text parts.
+/// `Nothing` values of optional `str` and `bytes` fields are omitted.
+fn generate_multipart_parts(sdef: &ast::StructDef) -> String {
+    let parts = sdef.fields.iter().map(|field| {
+        let name = &field.pair.name;
+        let value = format!("obj.{}", field_name(name));
+        let json_part = || {
+            format!(
+                "Just (Http.stringPart \"{}\" (E.encode 0 ({} {})))",
+                name,
+                to_atom(encoder_generation::generate_type_json_encoder(
+                    &field.value_type(),
+                    "AE."
+                )),
+                value
+            )
+        };
+        match &field.value_type() {
+            ast::TypeIdent::BuiltIn(ast::AtomType::Str) => {
+                format!("Just (Http.stringPart \"{}\" {})", name, value)
+            }
+            ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => format!(
+                "Just (Http.bytesPart \"{}\" \"application/octet-stream\" (BuiltinBytes.toBytes {}))",
+                name, value
+            ),
+            ast::TypeIdent::Option(inner) => match inner.as_ref() {
+                ast::TypeIdent::BuiltIn(ast::AtomType::Str) => {
+                    format!("Maybe.map (Http.stringPart \"{}\") {}", name, value)
+                }
+                ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => format!(
+                    "Maybe.map (Http.bytesPart \"{}\" \"application/octet-stream\" << BuiltinBytes.toBytes) {}",
+                    name, value
+                ),
+                _ => json_part(),
+            },
+            _ => json_part(),
+        }
+    });
+    format!(
+        "{fn_name} : Ty.{type_name} -> List Http.Part\n{fn_name} obj =\n    List.filterMap identity\n        [ {parts}\n        ]",
+        fn_name = multipart_parts_name(&sdef.name),
+        type_name = sdef.name,
+        parts = parts.collect::<Vec<_>>().join("\n        , "),
+    )
 }
 
-fn synthesize_endpoint_name(route: &ast::ServiceRoute) -> String {
+pub(crate) fn synthesize_endpoint_name(route: &ast::ServiceRoute) -> String {
     // TODO: not guranteed to be collision free
     // TODO: let user specify names in humble spec file
     let mut out = vec![];
@@ -189,7 +374,7 @@ fn synthesize_endpoint_name(route: &ast::ServiceRoute) -> String {
             ast::ServiceRouteComponent::Literal(lit) => {
                 out.push(lit.clone().to_pascal_case());
             }
-            ast::ServiceRouteComponent::Variable(var) => {
+            ast::ServiceRouteComponent::Variable(var) | ast::ServiceRouteComponent::Rest(var) => {
                 out.push(format!("By{}Of", var.name.clone().to_pascal_case()));
             }
         }
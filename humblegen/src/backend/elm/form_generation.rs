@@ -0,0 +1,158 @@
+use super::{field_name, to_atom, type_generation};
+use crate::ast;
+use inflector::Inflector;
+
+use itertools::Itertools; // directly call join(.) on iterators
+
+/// Generate elm helpers for building editable forms from the user-defined types of a spec.
+///
+/// Every struct, enum and flags type gets an empty value, every struct field a setter and an update function.
+/// Tuple structs only get an empty value.
+pub fn generate_form_helpers(spec: &ast::Spec) -> String {
+    spec.iter()
+        .filter_map(|spec_item| match spec_item {
+            ast::SpecItem::StructDef(sdef) => Some(generate_struct_helpers(sdef)),
+            ast::SpecItem::TupleStructDef(tdef) => Some(generate_tuple_struct_empty_value(tdef)),
+            ast::SpecItem::EnumDef(edef) => Some(generate_enum_empty_value(edef)),
+            ast::SpecItem::FlagsDef(fdef) => Some(format!(
+                "{fn_name} : {name}\n{fn_name} =\n    []",
+                fn_name = empty_value_name(&fdef.name),
+                name = fdef.name,
+            )),
+            ast::SpecItem::ServiceDef(_) => None,
+        })
+        .join("\n\n\n")
+}
+
+fn generate_struct_helpers(sdef: &ast::StructDef) -> String {
+    let empty_value = format!(
+        "{fn_name} : {name}\n{fn_name} =\n    {record}",
+        fn_name = empty_value_name(&sdef.name),
+        name = sdef.name,
+        record = generate_empty_record(&sdef.fields),
+    );
+
+    let field_helpers = sdef.fields.iter().map(|field| {
+        let ty = to_atom(type_generation::generate_local_type_ident(
+            &field.value_type(),
+        ));
+        let field = field_name(&field.pair.name);
+        format!(
+            "{setter} : {ty} -> {name} -> {name}\n{setter} value obj =\n    {{ obj | {field} = value }}\n\n\n\
+            {updater} : ({ty} -> {ty}) -> {name} -> {name}\n{updater} f obj =\n    {{ obj | {field} = f obj.{field} }}",
+            setter = format!("set{}{}", sdef.name, field.to_pascal_case()),
+            updater = format!("update{}{}", sdef.name, field.to_pascal_case()),
+            ty = ty,
+            name = sdef.name,
+            field = field,
+        )
+    });
+
+    std::iter::once(empty_value)
+        .chain(field_helpers)
+        .join("\n\n\n")
+}
+
+/// The empty value of a tuple struct has empty values as elements.
+fn generate_tuple_struct_empty_value(tdef: &ast::TupleStructDef) -> String {
+    format!(
+        "{fn_name} : {name}\n{fn_name} =\n    {name} {elements}",
+        fn_name = empty_value_name(&tdef.name),
+        name = tdef.name,
+        elements = tdef
+            .elements
+            .elements()
+            .iter()
+            .map(|e| to_atom(generate_empty_value(e)))
+            .join(" "),
+    )
+}
+
+/// The empty value of an enum is its first variant, with empty values as payload.
+fn generate_enum_empty_value(edef: &ast::EnumDef) -> String {
+    let variant = edef
+        .variants
+        .first()
+        .expect("enums without variants are rejected by `check_supported`");
+    let value = match variant.variant_type {
+        ast::VariantType::Simple => variant.name.clone(),
+        ast::VariantType::Tuple(ref components) => format!(
+            "{} {}",
+            variant.name,
+            components
+                .elements()
+                .iter()
+                .map(|c| to_atom(generate_empty_value(c)))
+                .join(" ")
+        ),
+        ast::VariantType::Struct(ref fields) => {
+            format!("{} {}", variant.name, generate_empty_record(fields))
+        }
+        ast::VariantType::Newtype(ref ty) => {
+            format!("{} {}", variant.name, to_atom(generate_empty_value(ty)))
+        }
+    };
+    format!(
+        "{fn_name} : {name}\n{fn_name} =\n    {value}",
+        fn_name = empty_value_name(&edef.name),
+        name = edef.name,
+        value = value,
+    )
+}
+
+fn generate_empty_record(fields: &ast::StructFields) -> String {
+    if fields.iter().next().is_none() {
+        return "{}".to_owned();
+    }
+    format!(
+        "{{ {} }}",
+        fields
+            .iter()
+            .map(|field| format!(
+                "{} = {}",
+                field_name(&field.pair.name),
+                generate_empty_value(&field.value_type())
+            ))
+            .join(", ")
+    )
+}
+
+/// Generate the elm value an empty form field of type `type_ident` starts with.
+///
+/// Optional values start as `Nothing`, collections empty, numbers as zero.
+pub(crate) fn generate_empty_value(type_ident: &ast::TypeIdent) -> String {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => generate_atom_empty_value(atom),
+        ast::TypeIdent::List(_) => "[]".to_owned(),
+        ast::TypeIdent::Option(_) => "Nothing".to_owned(),
+        ast::TypeIdent::Result(ok, _) => format!("Ok {}", to_atom(generate_empty_value(ok))),
+        ast::TypeIdent::Map(_, _) => "Dict.empty".to_owned(),
+        ast::TypeIdent::Tuple(tdef) => format!(
+            "({})",
+            tdef.elements().iter().map(generate_empty_value).join(", ")
+        ),
+        ast::TypeIdent::UserDefined(ident) => empty_value_name(ident),
+    }
+}
+
+fn generate_atom_empty_value(atom: &ast::AtomType) -> String {
+    match atom {
+        ast::AtomType::Empty => "()",
+        ast::AtomType::Str => "\"\"",
+        ast::AtomType::I32 => "0",
+        ast::AtomType::U32 => "0",
+        ast::AtomType::U8 => "0",
+        ast::AtomType::I64 | ast::AtomType::U64 => "\"0\"",
+        ast::AtomType::F64 => "0",
+        ast::AtomType::Bool => "False",
+        ast::AtomType::DateTime => "Time.millisToPosix 0",
+        ast::AtomType::Date => "Date.fromRataDie 719163", // 1970-01-01
+        ast::AtomType::Uuid => "BuiltinUuid.nil",
+        ast::AtomType::Bytes => "BuiltinBytes.empty",
+    }
+    .to_owned()
+}
+
+fn empty_value_name(ident: &str) -> String {
+    format!("empty{}", ident)
+}
@@ -3,8 +3,8 @@ import Dict exposing (Dict)
 import Iso8601  -- rtfeldman/elm-iso8601-date-strings
 import Json.Decode as D
 import Time  -- elm/time
-import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
-import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import {builtin_prefix}.BuiltIn.Bytes as BuiltinBytes
+import {builtin_prefix}.BuiltIn.Uuid as BuiltinUuid
 
 -- TODO: move into its own module to avoid name collision
 
@@ -16,11 +16,107 @@ required : String -> D.Decoder a -> D.Decoder (a -> b) -> D.Decoder b
 required key valDecoder decoder =
     custom (D.field key valDecoder) decoder
 
+-- A field renamed from the `oldKeys`: decodes key `key`, falling back to the old keys in order.
+requiredRenamed : String -> List String -> D.Decoder a -> D.Decoder (a -> b) -> D.Decoder b
+requiredRenamed key oldKeys valDecoder decoder =
+    custom (D.oneOf (List.map (\k -> D.field k valDecoder) (key :: oldKeys))) decoder
+
+-- A field that may be absent, which decodes to `Nothing` then.
+optional : String -> D.Decoder a -> D.Decoder (Maybe a -> b) -> D.Decoder b
+optional key valDecoder decoder =
+    optionalRenamed key [] valDecoder decoder
+
+-- Like `optional`, also accepting the former keys `oldKeys` of the field.
+optionalRenamed : String -> List String -> D.Decoder a -> D.Decoder (Maybe a -> b) -> D.Decoder b
+optionalRenamed key oldKeys valDecoder decoder =
+    let
+        present k =
+            D.maybe (D.field k D.value) |> D.map (Maybe.map (\_ -> k))
+
+        firstPresent =
+            List.foldr (D.map2 (::)) (D.succeed []) (List.map present (key :: oldKeys))
+                |> D.map (List.filterMap identity >> List.head)
+    in
+    custom
+        (firstPresent
+            |> D.andThen
+                (\found ->
+                    case found of
+                        Just k ->
+                            D.map Just (D.field k valDecoder)
+
+                        Nothing ->
+                            D.succeed Nothing
+                )
+        )
+        decoder
+
+-- A field that may be absent, which decodes to `default` then.
+defaulted : String -> a -> D.Decoder a -> D.Decoder (a -> b) -> D.Decoder b
+defaulted key default valDecoder decoder =
+    defaultedRenamed key [] default valDecoder decoder
+
+-- Like `defaulted`, also accepting the former keys `oldKeys` of the field.
+defaultedRenamed : String -> List String -> a -> D.Decoder a -> D.Decoder (a -> b) -> D.Decoder b
+defaultedRenamed key oldKeys default valDecoder decoder =
+    optionalRenamed key oldKeys valDecoder (D.map (\f -> Maybe.withDefault default >> f) decoder)
+
 -- A helper function for a required index in a JSON list.
 requiredIdx : Int -> D.Decoder a -> D.Decoder (a -> b) -> D.Decoder b
 requiredIdx idx itemDecoder decoder =
     custom (D.index idx itemDecoder) decoder
 
+-- A variant of an internally or adjacently tagged enum: checks that key `tag` is `name`, then decodes the same value.
+builtinDecodeTagged : String -> String -> D.Decoder a -> D.Decoder a
+builtinDecodeTagged tag name decoder =
+    D.field tag D.string
+        |> D.andThen (\s -> if s == name then decoder else D.fail ("expected " ++ tag ++ " " ++ name))
+
+-- An unknown variant of a non-exhaustive enum, whose variants are `names`: succeeds with `unknown` if the variant name,
+-- under key `tag` or else the string or single key of an externally tagged enum, is not one of `names`.
+builtinDecodeUnknownVariant : Maybe String -> List String -> a -> D.Decoder a
+builtinDecodeUnknownVariant tag names unknown =
+    let
+        name =
+            case tag of
+                Just key ->
+                    D.field key D.string
+
+                Nothing ->
+                    D.oneOf
+                        [ D.string
+                        , D.keyValuePairs D.value
+                            |> D.andThen
+                                (\pairs ->
+                                    case pairs of
+                                        [ ( key, _ ) ] ->
+                                            D.succeed key
+
+                                        _ ->
+                                            D.fail "expected a single variant"
+                                )
+                        ]
+    in
+    name
+        |> D.andThen (\s -> if List.member s names then D.fail ("invalid value of variant " ++ s) else D.succeed unknown)
+
+-- A variant of a numeric enum by its discriminant among the `variants`, otherwise `unknown` if the enum is non-exhaustive.
+builtinDecodeDiscriminant : List ( Int, a ) -> Maybe a -> D.Decoder a
+builtinDecodeDiscriminant variants unknown =
+    D.int
+        |> D.andThen
+            (\i ->
+                case ( List.filter (\( d, _ ) -> d == i) variants, unknown ) of
+                    ( ( _, variant ) :: _, _ ) ->
+                        D.succeed variant
+
+                    ( [], Just variant ) ->
+                        D.succeed variant
+
+                    ( [], Nothing ) ->
+                        D.fail ("invalid discriminant " ++ String.fromInt i)
+            )
+
 -- Maybe-unwrapping decoder: Turns a `Maybe t` decoder into an a `t` decoder by outputting an error on `Nothing`.
 unwrapDecoder : D.Decoder (Maybe t) -> D.Decoder t
 unwrapDecoder =
@@ -52,6 +148,11 @@ builtinDecodeIso8601 : D.Decoder Time.Posix
 builtinDecodeIso8601 =
     Iso8601.decoder
 
+-- A large integer (`i64` or `u64`) as decimal string, from a string or a number. Numbers above 2^53 are rounded by JavaScript.
+builtinDecodeLargeInt : D.Decoder String
+builtinDecodeLargeInt =
+    D.oneOf [ D.string, D.map String.fromInt D.int ]
+
 
 builtinDecodeResult : D.Decoder error -> D.Decoder value -> D.Decoder (Result error value)
 builtinDecodeResult error value =
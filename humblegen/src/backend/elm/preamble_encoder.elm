@@ -4,8 +4,8 @@ import Iso8601  -- rtfeldman/elm-iso8601-date-strings
 import Json.Encode as E
 import Time  -- elm/time
 import Url.Builder
-import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
-import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import {builtin_prefix}.BuiltIn.Bytes as BuiltinBytes
+import {builtin_prefix}.BuiltIn.Uuid as BuiltinUuid
 
 
 
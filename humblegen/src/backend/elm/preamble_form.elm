@@ -0,0 +1,5 @@
+import Date -- justinmimbs/date
+import Dict exposing (Dict)
+import Time  -- elm/time
+import {builtin_prefix}.BuiltIn.Bytes as BuiltinBytes
+import {builtin_prefix}.BuiltIn.Uuid as BuiltinUuid
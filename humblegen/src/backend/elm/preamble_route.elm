@@ -0,0 +1,48 @@
+import Date -- justinmimbs/date
+import Iso8601  -- rtfeldman/elm-iso8601-date-strings
+import Time  -- elm/time
+import Url exposing (Url)  -- elm/url
+import Url.Builder
+import Url.Parser as P exposing ((</>))
+import {builtin_prefix}.BuiltIn.Uuid as BuiltinUuid
+
+
+fromUrl : Url -> Maybe Route
+fromUrl =
+    P.parse parser
+
+
+string : P.Parser (String -> a) a
+string =
+    P.custom "STRING" Url.percentDecode
+
+
+float : P.Parser (Float -> a) a
+float =
+    P.custom "FLOAT" String.toFloat
+
+
+bool : P.Parser (Bool -> a) a
+bool =
+    P.custom "BOOL"
+        (\s ->
+            case s of
+                "true" ->
+                    Just True
+
+                "false" ->
+                    Just False
+
+                _ ->
+                    Nothing
+        )
+
+
+dateTime : P.Parser (Time.Posix -> a) a
+dateTime =
+    P.custom "DATETIME" (Iso8601.toTime >> Result.toMaybe)
+
+
+date : P.Parser (Date.Date -> a) a
+date =
+    P.custom "DATE" (Date.fromIsoString >> Result.toMaybe)
@@ -1,9 +1,17 @@
-import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
-import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import Date -- justinmimbs/date
+import Iso8601  -- rtfeldman/elm-iso8601-date-strings
+import {builtin_prefix}.BuiltIn.Bytes as BuiltinBytes
+import {builtin_prefix}.BuiltIn.Uuid as BuiltinUuid
 import {module_prefix}.ServiceBuiltIn
 type alias Error = {module_prefix}.ServiceBuiltIn.Error
 type alias Request q t  = {module_prefix}.ServiceBuiltIn.Request q t
 withHeader = {module_prefix}.ServiceBuiltIn.withHeader
 withQuery = {module_prefix}.ServiceBuiltIn.withQuery
 withBase = {module_prefix}.ServiceBuiltIn.withBase
-toTask = {module_prefix}.ServiceBuiltIn.toTask
\ No newline at end of file
+toTask = {module_prefix}.ServiceBuiltIn.toTask
+toCmdWithProgress = {module_prefix}.ServiceBuiltIn.toCmdWithProgress
+uploadProgress = {module_prefix}.ServiceBuiltIn.uploadProgress
+idempotencyKeyGenerator = {module_prefix}.ServiceBuiltIn.idempotencyKeyGenerator
+type alias PageState a = {module_prefix}.ServiceBuiltIn.PageState a
+initPageState = {module_prefix}.ServiceBuiltIn.initPageState
+updatePageState = {module_prefix}.ServiceBuiltIn.updatePageState
\ No newline at end of file
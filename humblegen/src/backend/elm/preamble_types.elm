@@ -2,5 +2,5 @@ import Date -- justinmimbs/date
 import Dict exposing (Dict)
 import Iso8601  -- rtfeldman/elm-iso8601-date-strings
 import Time  -- elm/time
-import {module_prefix}.BuiltIn.Bytes as BuiltinBytes
-import {module_prefix}.BuiltIn.Uuid as BuiltinUuid
+import {builtin_prefix}.BuiltIn.Bytes as BuiltinBytes
+import {builtin_prefix}.BuiltIn.Uuid as BuiltinUuid
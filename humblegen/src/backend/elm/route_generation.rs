@@ -0,0 +1,159 @@
+//! Generation of the `Route` module, an `Url.Parser` for the paths of the `GET` routes of all
+//! services, such that the routes of a single-page application mirror the API resource paths.
+
+use super::{endpoint_generation, type_generation};
+use crate::ast;
+use inflector::Inflector;
+use itertools::Itertools;
+
+/// A `GET` route of a service, which becomes a constructor of the `Route` type.
+struct Route<'a> {
+    constructor: String,
+    components: &'a [ast::ServiceRouteComponent],
+}
+
+/// Generate the `Route` type, its `parser` and `toPath`.
+///
+/// Routes whose path parameters have no URL representation, e.g. `bytes` or user-defined types,
+/// are omitted.
+pub(crate) fn generate_routes(spec: &ast::Spec) -> String {
+    let routes = spec
+        .iter()
+        .filter_map(ast::SpecItem::service_def)
+        .flat_map(|service| {
+            service
+                .endpoints
+                .iter()
+                .filter(|endpoint| matches!(endpoint.route, ast::ServiceRoute::Get { .. }))
+                .map(move |endpoint| Route {
+                    constructor: format!(
+                        "{}{}",
+                        service.name,
+                        endpoint_generation::synthesize_endpoint_name(&endpoint.route)
+                            .to_pascal_case()
+                    ),
+                    components: endpoint.route.components(),
+                })
+        })
+        .filter(|route| {
+            route.components.iter().all(|component| match component {
+                ast::ServiceRouteComponent::Literal(_) => true,
+                ast::ServiceRouteComponent::Variable(arg) => atom_parser(&arg.type_ident).is_some(),
+                // `elm/url` cannot parse the rest of a path
+                ast::ServiceRouteComponent::Rest(_) => false,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if routes.is_empty() {
+        // a custom type needs at least one constructor
+        return "type Route\n    = NoRoutes\n\n\nparser : P.Parser (Route -> a) a\nparser =\n    P.oneOf []\n\n\ntoPath : Route -> String\ntoPath _ =\n    Url.Builder.absolute [] []\n".to_owned();
+    }
+
+    format!(
+        "type Route\n    = {constructors}\n\n\n\
+        parser : P.Parser (Route -> a) a\nparser =\n    P.oneOf\n        [ {parsers}\n        ]\n\n\n\
+        toPath : Route -> String\ntoPath route =\n    case route of\n        {paths}\n",
+        constructors = routes.iter().map(generate_constructor).join("\n    | "),
+        parsers = routes.iter().map(generate_parser).join("\n        , "),
+        paths = routes.iter().map(generate_path).join("\n\n        "),
+    )
+}
+
+/// The path parameters of `route` with their argument names, which are prefixed like those of
+/// the endpoint functions to avoid shadowing.
+fn variables<'a>(route: &'a Route) -> impl Iterator<Item = (String, &'a ast::FieldDefPair)> {
+    route
+        .components
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, component)| component.param().map(|arg| (argument_name(idx, arg), arg)))
+}
+
+fn argument_name(idx: usize, arg: &ast::FieldDefPair) -> String {
+    format!("component{}_{}", idx, arg.name)
+}
+
+fn generate_constructor(route: &Route) -> String {
+    std::iter::once(route.constructor.clone())
+        .chain(
+            variables(route)
+                .map(|(_, arg)| type_generation::generate_type_ident(&arg.type_ident, "")),
+        )
+        .join(" ")
+}
+
+fn generate_parser(route: &Route) -> String {
+    let path = route
+        .components
+        .iter()
+        .map(|component| match component {
+            ast::ServiceRouteComponent::Literal(lit) => format!("P.s \"{}\"", lit.escape_default()),
+            ast::ServiceRouteComponent::Variable(arg) => {
+                atom_parser(&arg.type_ident).unwrap().to_owned()
+            }
+            ast::ServiceRouteComponent::Rest(_) => unreachable!("routes with rest are omitted"),
+        })
+        .join(" </> ");
+    format!("P.map {} ({})", route.constructor, path)
+}
+
+fn generate_path(route: &Route) -> String {
+    let arguments = variables(route).map(|(name, _)| format!(" {}", name));
+    let segments = route
+        .components
+        .iter()
+        .enumerate()
+        .map(|(idx, component)| match component {
+            ast::ServiceRouteComponent::Literal(lit) => format!("\"{}\"", lit.escape_default()),
+            ast::ServiceRouteComponent::Variable(arg) => format!(
+                "{} {}",
+                atom_encoder(&arg.type_ident).unwrap(),
+                argument_name(idx, arg)
+            ),
+            ast::ServiceRouteComponent::Rest(_) => unreachable!("routes with rest are omitted"),
+        })
+        .join(", ");
+    format!(
+        "{}{} ->\n            Url.Builder.absolute [ {} ] []",
+        route.constructor,
+        arguments.collect::<String>(),
+        segments
+    )
+}
+
+/// The parser of a path parameter of type `ty`, if it has a URL representation.
+fn atom_parser(ty: &ast::TypeIdent) -> Option<&'static str> {
+    match ty {
+        ast::TypeIdent::BuiltIn(atom) => match atom {
+            ast::AtomType::Str | ast::AtomType::I64 | ast::AtomType::U64 => Some("string"),
+            ast::AtomType::I32 | ast::AtomType::U32 | ast::AtomType::U8 => Some("P.int"),
+            ast::AtomType::F64 => Some("float"),
+            ast::AtomType::Bool => Some("bool"),
+            ast::AtomType::DateTime => Some("dateTime"),
+            ast::AtomType::Date => Some("date"),
+            ast::AtomType::Uuid => Some("BuiltinUuid.parseUrl"),
+            ast::AtomType::Empty | ast::AtomType::Bytes => None,
+        },
+        _ => None,
+    }
+}
+
+/// The inverse of `atom_parser`.
+fn atom_encoder(ty: &ast::TypeIdent) -> Option<&'static str> {
+    match ty {
+        ast::TypeIdent::BuiltIn(atom) => match atom {
+            ast::AtomType::Str | ast::AtomType::I64 | ast::AtomType::U64 => {
+                Some("Url.percentEncode")
+            }
+            ast::AtomType::I32 | ast::AtomType::U32 | ast::AtomType::U8 => Some("String.fromInt"),
+            ast::AtomType::F64 => Some("String.fromFloat"),
+            ast::AtomType::Bool => Some("(\\v -> if v then \"true\" else \"false\")"),
+            ast::AtomType::DateTime => Some("Iso8601.fromTime"),
+            ast::AtomType::Date => Some("Date.toIsoString"),
+            ast::AtomType::Uuid => Some("BuiltinUuid.toString"),
+            ast::AtomType::Empty | ast::AtomType::Bytes => None,
+        },
+        _ => None,
+    }
+}
@@ -1,14 +1,13 @@
-use super::{field_name, generate_doc_comment, to_atom, IndentWriter};
-use crate::{ast, LibError};
+use super::{field_name, generate_doc_comment, to_atom};
+use crate::ast;
+use crate::codegen::printer::{Printer, Separators};
+use inflector::Inflector;
 use itertools::Itertools;
 
 // TODO: Elm does not allow documentation on members, so the docs need to be converted to markdown
 //       lists instead. This is true for `type alias` struct fields as well as enum variants.
 
-pub(crate) fn generate_struct_def(
-    def: &ast::StructDef,
-    file: &mut IndentWriter,
-) -> Result<(), LibError> {
+pub(crate) fn generate_struct_def(def: &ast::StructDef, file: &mut Printer) {
     generate_struct_def_from_parts(&def.name, &def.doc_comment, &def.fields, file)
 }
 
@@ -16,152 +15,156 @@ pub(crate) fn generate_struct_def_from_parts(
     def_name: &str,
     def_doc_comment: &Option<String>,
     def_fields: &ast::StructFields,
-    file: &mut IndentWriter,
-) -> Result<(), LibError> {
-    file.kill_indent();
-
-    write!(
-        file.start_line()?,
-        "{doc_comment}\ntype alias {name} =",
-        doc_comment = generate_doc_comment(def_doc_comment),
-        name = def_name
-    )?;
-
-    generate_struct_fields(def_fields, file)?;
-
-    file.empty_lines(2)?;
-
-    Ok(())
+    file: &mut Printer,
+) {
+    file.line(generate_doc_comment(def_doc_comment));
+    file.line(format!("type alias {} =", def_name));
+    file.with_indent(|file| generate_struct_fields(def_fields, file));
+    file.blank_lines(2);
 }
 
-pub(crate) fn generate_struct_fields(
-    fields: &ast::StructFields,
-    file: &mut IndentWriter,
-) -> Result<(), LibError> {
-    file.increase_indent();
-
-    for (idx, field) in fields.iter().enumerate() {
-        let first = idx == 0;
-        generate_struct_field(field, first, file)?;
-    }
-
-    write!(file.start_line()?, "}}")?;
-
-    file.decrease_indent();
-
-    Ok(())
+/// Generate the record type of `fields`, one field per line.
+pub(crate) fn generate_struct_fields(fields: &ast::StructFields, file: &mut Printer) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            format!(
+                "{}: {}",
+                field_name(&field.pair.name),
+                generate_local_type_ident(&field.value_type())
+            )
+        })
+        .collect();
+    file.delimited_lines(("{", "}"), &fields, ",", Separators::Leading);
 }
 
-fn generate_struct_field(
-    field: &ast::FieldNode,
-    first: bool,
-    file: &mut IndentWriter,
-) -> Result<(), LibError> {
-    write!(
-        file.start_line()?,
-        "{delimiter}{name}: {ty}",
-        delimiter = if first { "{ " } else { ", " },
-        name = field_name(&field.pair.name),
-        ty = generate_local_type_ident(&field.pair.type_ident)
-    )?;
-
-    Ok(())
+/// Generate elm code for a tuple struct definition, a custom type with a single constructor.
+pub(crate) fn generate_tuple_struct_def(def: &ast::TupleStructDef, file: &mut Printer) {
+    file.line(generate_doc_comment(&def.doc_comment));
+    file.line(format!("type {}", def.name));
+    file.with_indent(|file| {
+        file.line(format!(
+            "= {name} {elements}",
+            name = def.name,
+            elements = def
+                .elements
+                .elements()
+                .iter()
+                .map(generate_local_type_ident)
+                .map(to_atom)
+                .join(" ")
+        ))
+    });
+    file.blank_lines(2);
 }
 
-/// Generate elm code for an enum definition.
-pub(crate) fn generate_enum_def(
-    def: &ast::EnumDef,
-    file: &mut IndentWriter,
-) -> Result<(), LibError> {
-    file.kill_indent();
-
-    generate_enum_variant_anonymous_constructors(def, file)?;
-
-    write!(
-        file.start_line()?,
-        "{doc_comment}\ntype {name}",
-        doc_comment = generate_doc_comment(&def.doc_comment),
+/// Generate elm code for a flags definition: a `{Name}Flag` custom type with a `{Name}{Flag}`
+/// constructor per flag, the flags type as list of them, and helpers testing, inserting and
+/// removing flags.
+pub(crate) fn generate_flags_def(def: &ast::FlagsDef, file: &mut Printer) {
+    let flag_type = flag_type_name(&def.name);
+    file.line(format!("type {}", flag_type));
+    file.with_indent(|file| {
+        for (idx, flag) in def.flags.iter().enumerate() {
+            let delimiter = if idx == 0 { "= " } else { "| " };
+            file.line(format!(
+                "{}{}",
+                delimiter,
+                flag_constructor_name(&def.name, &flag.name)
+            ));
+        }
+    });
+    file.blank_lines(2);
+
+    file.line(generate_doc_comment(&def.doc_comment));
+    file.line(format!("type alias {} =", def.name));
+    file.with_indent(|file| file.line(format!("List {}", flag_type)));
+    file.blank_lines(2);
+
+    let helper = def.name.to_camel_case();
+    file.line(format!(
+        "{{-| Whether `flags` contain `flag`.\n-}}\n\
+        {helper}Has : {flag} -> {name} -> Bool\n\
+        {helper}Has flag flags =\n    List.member flag flags\n\n\n\
+        {{-| Add `flag` to `flags`, unless they contain it.\n-}}\n\
+        {helper}Insert : {flag} -> {name} -> {name}\n\
+        {helper}Insert flag flags =\n    if List.member flag flags then\n        flags\n\n    else\n        flags ++ [ flag ]\n\n\n\
+        {{-| Remove `flag` from `flags`.\n-}}\n\
+        {helper}Remove : {flag} -> {name} -> {name}\n\
+        {helper}Remove flag flags =\n    List.filter ((/=) flag) flags",
+        helper = helper,
+        flag = flag_type,
         name = def.name,
-    )?;
-
-    file.increase_indent();
+    ));
+    file.blank_lines(2);
+}
 
-    for (idx, field) in def.variants.iter().enumerate() {
-        let first = idx == 0;
-        generate_enum_variant_def(def, field, first, file)?;
-    }
+/// The name of the custom type of the flags of a flags type.
+pub(crate) fn flag_type_name(flags_name: &str) -> String {
+    format!("{}Flag", flags_name)
+}
 
-    file.empty_lines(2)?;
+/// The name of the constructor of a flag, prefixed with the flags type to be unique.
+pub(crate) fn flag_constructor_name(flags_name: &str, flag_name: &str) -> String {
+    format!("{}{}", flags_name, flag_name)
+}
 
-    Ok(())
+/// Generate elm code for an enum definition.
+pub(crate) fn generate_enum_def(def: &ast::EnumDef, file: &mut Printer) {
+    generate_enum_variant_anonymous_constructors(def, file);
+
+    file.line(generate_doc_comment(&def.doc_comment));
+    file.line(format!("type {}", def.name));
+    file.with_indent(|file| {
+        for (idx, variant) in def.variants.iter().enumerate() {
+            let delimiter = if idx == 0 { "= " } else { "| " };
+            file.line(format!(
+                "{}{}",
+                delimiter,
+                generate_enum_variant_def(def, variant)
+            ));
+        }
+        if def.is_non_exhaustive() {
+            file.line(format!("| {}", enum_unknown_variant_name(&def.name)));
+        }
+    });
+    file.blank_lines(2);
 }
 
-fn generate_enum_variant_anonymous_constructors(
-    def: &ast::EnumDef,
-    file: &mut IndentWriter,
-) -> Result<(), LibError> {
+fn generate_enum_variant_anonymous_constructors(def: &ast::EnumDef, file: &mut Printer) {
     for variant in def.variants.iter() {
         if let ast::VariantType::Struct(ref fields) = variant.variant_type {
             let def_name = enum_anonymous_struct_constructor_name(&def.name, &variant.name);
-            generate_struct_def_from_parts(&def_name, &None, fields, file)?;
+            generate_struct_def_from_parts(&def_name, &None, fields, file);
         }
     }
-
-    Ok(())
 }
 
-/// Generate elm code for a variant definition.
-fn generate_enum_variant_def(
-    edef: &ast::EnumDef,
-    variant: &ast::VariantDef,
-    first: bool,
-    file: &mut IndentWriter,
-) -> Result<(), LibError> {
-    let delimiter = if first { "= " } else { "| " };
+/// Generate elm code for a variant definition, i.e., its constructor and arguments.
+fn generate_enum_variant_def(edef: &ast::EnumDef, variant: &ast::VariantDef) -> String {
     match variant.variant_type {
-        ast::VariantType::Simple => {
-            write!(
-                file.start_line()?,
-                "{delimiter}{name}",
-                delimiter = delimiter,
-                name = variant.name,
-            )?;
-        }
-        ast::VariantType::Tuple(ref fields) => {
-            write!(
-                file.start_line()?,
-                "{delimiter}{name} {fields}",
-                delimiter = delimiter,
-                name = variant.name,
-                fields = fields
-                    .elements()
-                    .iter()
-                    .map(generate_local_type_ident)
-                    .map(to_atom)
-                    .join(" ")
-            )?;
-        }
-        ast::VariantType::Struct(ref _fields) => {
-            write!(
-                file.start_line()?,
-                "{delimiter}{name} {anonymousStruct}",
-                delimiter = delimiter,
-                name = variant.name,
-                anonymousStruct = enum_anonymous_struct_constructor_name(&edef.name, &variant.name)
-            )?;
-        }
-        ast::VariantType::Newtype(ref ty) => {
-            write!(
-                file.start_line()?,
-                "{delimiter}{name} {field}",
-                delimiter = delimiter,
-                name = variant.name,
-                field = to_atom(generate_local_type_ident(ty))
-            )?;
-        }
+        ast::VariantType::Simple => variant.name.clone(),
+        ast::VariantType::Tuple(ref fields) => format!(
+            "{name} {fields}",
+            name = variant.name,
+            fields = fields
+                .elements()
+                .iter()
+                .map(generate_local_type_ident)
+                .map(to_atom)
+                .join(" ")
+        ),
+        ast::VariantType::Struct(ref _fields) => format!(
+            "{name} {anonymousStruct}",
+            name = variant.name,
+            anonymousStruct = enum_anonymous_struct_constructor_name(&edef.name, &variant.name)
+        ),
+        ast::VariantType::Newtype(ref ty) => format!(
+            "{name} {field}",
+            name = variant.name,
+            field = to_atom(generate_local_type_ident(ty))
+        ),
     }
-
-    Ok(())
 }
 
 pub(crate) fn enum_anonymous_struct_constructor_name(
@@ -171,6 +174,11 @@ pub(crate) fn enum_anonymous_struct_constructor_name(
     format!("{}__{}__Internal__", enum_name, variant_name)
 }
 
+/// The constructor of the variants of a non-exhaustive enum unknown to the spec.
+pub(crate) fn enum_unknown_variant_name(enum_name: &str) -> String {
+    format!("{}Unknown", enum_name)
+}
+
 /// Generate elm code for a type identifier.
 pub(crate) fn generate_type_ident(type_ident: &ast::TypeIdent, ns: &str) -> String {
     match type_ident {
@@ -217,6 +225,7 @@ fn generate_atom(atom: &ast::AtomType) -> String {
         ast::AtomType::I32 => "Int",
         ast::AtomType::U32 => "Int",
         ast::AtomType::U8 => "Int",
+        ast::AtomType::I64 | ast::AtomType::U64 => "String",
         ast::AtomType::F64 => "Float",
         ast::AtomType::Bool => "Bool",
         ast::AtomType::DateTime => "Time.Posix",
@@ -0,0 +1,604 @@
+//! Generates a GraphQL schema (SDL) for a humble specification file, e.g. to front the
+//! services with a GraphQL gateway.
+//!
+//! Structs become object types, enums become enums (if all variants are simple) or unions, and
+//! the routes of services annotated with `@graphql` become fields of the `Query` (`GET` routes)
+//! and `Mutation` (all other routes) types.
+//! Types used as arguments additionally get an input type, e.g. `input MonsterInput`.
+//! See `docs/humblespec/graphql.md` for the complete mapping.
+
+use crate::backend::protobuf::{helper_name, rpc_name};
+use crate::strict::LossyMapping;
+use crate::{ast, workspace::Workspace, Artifact, LibError, Spec};
+
+use inflector::cases::camelcase::to_camel_case;
+use inflector::cases::snakecase::to_snake_case;
+use itertools::Itertools;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+const BACKEND_NAME: &str = "graphql";
+
+/// Built-in types that are mapped lossily, see `crate::strict`.
+const LOSSY_MAPPINGS: &[LossyMapping] = &[
+    LossyMapping {
+        atom: ast::AtomType::Empty,
+        target: "Boolean",
+        loss: "a placeholder without meaning",
+    },
+    LossyMapping {
+        atom: ast::AtomType::U8,
+        target: "Int",
+        loss: "which does not restrict values to bytes",
+    },
+];
+
+/// Field of object types that would otherwise have no fields, which GraphQL does not allow.
+const EMPTY_FIELD: &str = "_empty: Boolean";
+
+pub struct Generator {
+    services: bool,
+}
+
+impl Generator {
+    /// Services are only rendered for the client or server artifacts.
+    pub fn new(artifact: Artifact) -> Result<Self, LibError> {
+        match artifact {
+            Artifact::TypesOnly => Ok(Self { services: false }),
+            Artifact::ClientEndpoints | Artifact::ServerEndpoints | Artifact::ClientAndServer => {
+                Ok(Self { services: true })
+            }
+            Artifact::Benchmarks | Artifact::MockServer => Err(LibError::UnsupportedArtifact {
+                artifact,
+                backend: BACKEND_NAME,
+            }),
+        }
+    }
+
+    fn render_spec(&self, spec: &Spec) -> String {
+        let mut ctx = Context {
+            index: spec.index(),
+            helpers: BTreeMap::new(),
+            scalars: BTreeSet::new(),
+        };
+
+        let mut definitions: Vec<String> = spec
+            .iter()
+            .filter_map(|spec_item| match spec_item {
+                ast::SpecItem::StructDef(sdef) => Some(ctx.struct_def(sdef)),
+                ast::SpecItem::EnumDef(edef) => Some(ctx.enum_def(edef)),
+                ast::SpecItem::TupleStructDef(tdef) => ctx.tuple_struct_def(tdef),
+                ast::SpecItem::FlagsDef(fdef) => Some(flags_enum(fdef)),
+                ast::SpecItem::ServiceDef(_) => None,
+            })
+            .collect();
+        if self.services {
+            let (queries, mutations): (Vec<_>, Vec<_>) = spec
+                .iter()
+                .filter_map(ast::SpecItem::service_def)
+                .filter(|service| service.has_annotation("graphql"))
+                .flat_map(|service| service.endpoints.iter().map(move |e| (service, e)))
+                .partition(|(_, endpoint)| matches!(endpoint.route, ast::ServiceRoute::Get { .. }));
+            for (root, endpoints) in &[("Query", queries), ("Mutation", mutations)] {
+                if !endpoints.is_empty() {
+                    let fields = endpoints
+                        .iter()
+                        .map(|(service, endpoint)| ctx.root_field(service, endpoint))
+                        .join("");
+                    definitions.push(format!("type {} {{\n{}}}\n", root, fields));
+                }
+            }
+        }
+        // input and helper types are registered while rendering the definitions above
+        definitions.extend(ctx.helpers.values().cloned());
+
+        let mut out = "# Generated by humblegen, do not edit.\n".to_owned();
+        for scalar in &ctx.scalars {
+            out.push('\n');
+            out.push_str(&scalar_def(scalar));
+        }
+        for definition in definitions {
+            out.push('\n');
+            out.push_str(&definition);
+        }
+        out
+    }
+}
+
+/// Whether a type is rendered as an output or input type.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Direction {
+    Output,
+    Input,
+}
+
+impl Direction {
+    fn keyword(self) -> &'static str {
+        match self {
+            Direction::Output => "type",
+            Direction::Input => "input",
+        }
+    }
+
+    /// Suffix of the names of input types, which must be distinct from object types.
+    fn suffix(self) -> &'static str {
+        match self {
+            Direction::Output => "",
+            Direction::Input => "Input",
+        }
+    }
+}
+
+struct Context<'a> {
+    index: ast::SpecIndex<'a>,
+    /// Input and helper types by name.
+    helpers: BTreeMap<String, String>,
+    /// Custom scalars used by the rendered definitions.
+    scalars: BTreeSet<&'static str>,
+}
+
+impl<'a> Context<'a> {
+    fn struct_def(&mut self, sdef: &ast::StructDef) -> String {
+        self.object(
+            &sdef.doc_comment,
+            Direction::Output,
+            &sdef.name,
+            &sdef.fields,
+        )
+    }
+
+    /// An object with a field per element of tuple struct `tdef`, unless it is a newtype, which
+    /// is referred to by its inner type instead.
+    fn tuple_struct_def(&mut self, tdef: &ast::TupleStructDef) -> Option<String> {
+        if tdef.is_newtype() {
+            return None;
+        }
+        Some(format!(
+            "{}type {} {{\n{}}}\n",
+            description(&tdef.doc_comment, ""),
+            tdef.name,
+            self.tuple_fields(&tdef.elements, Direction::Output)
+        ))
+    }
+
+    /// An object or input type named `name` with `fields`.
+    fn object(
+        &mut self,
+        doc_comment: &Option<String>,
+        direction: Direction,
+        name: &str,
+        fields: &ast::StructFields,
+    ) -> String {
+        let fields = fields
+            .iter()
+            .map(|field| {
+                format!(
+                    "{}  {}: {}\n",
+                    description(&field.doc_comment, "  "),
+                    graphql_name(field.json_name(), &field.pair.name),
+                    self.type_ref(&field.flat_value_type(), direction)
+                )
+            })
+            .join("");
+        format!(
+            "{}{} {} {{\n{}}}\n",
+            description(doc_comment, ""),
+            direction.keyword(),
+            name,
+            if fields.is_empty() {
+                format!("  {}\n", EMPTY_FIELD)
+            } else {
+                fields
+            }
+        )
+    }
+
+    fn enum_def(&mut self, edef: &ast::EnumDef) -> String {
+        if edef.complex_variants().next().is_none() {
+            let values = edef
+                .variants
+                .iter()
+                .map(|variant| {
+                    format!(
+                        "{}  {}\n",
+                        description(&variant.doc_comment, "  "),
+                        graphql_name(variant.json_name(), &variant.name)
+                    )
+                })
+                .join("");
+            return format!(
+                "{}enum {} {{\n{}}}\n",
+                description(&edef.doc_comment, ""),
+                edef.name,
+                values
+            );
+        }
+
+        // a union of an object type per variant, which follow the union
+        let mut out = format!(
+            "{}union {} = {}\n",
+            description(&edef.doc_comment, ""),
+            edef.name,
+            edef.variants
+                .iter()
+                .map(|variant| format!("{}{}", edef.name, variant.name))
+                .join(" | ")
+        );
+        for variant in &edef.variants {
+            let fields = match &variant.variant_type {
+                ast::VariantType::Simple => format!("  {}\n", EMPTY_FIELD),
+                ast::VariantType::Newtype(ty) => {
+                    format!("  value: {}\n", self.type_ref(ty, Direction::Output))
+                }
+                ast::VariantType::Tuple(tdef) => self.tuple_fields(tdef, Direction::Output),
+                ast::VariantType::Struct(fields) => {
+                    out.push('\n');
+                    out.push_str(&self.object(
+                        &variant.doc_comment,
+                        Direction::Output,
+                        &format!("{}{}", edef.name, variant.name),
+                        fields,
+                    ));
+                    continue;
+                }
+            };
+            out.push_str(&format!(
+                "\n{}type {}{} {{\n{}}}\n",
+                description(&variant.doc_comment, ""),
+                edef.name,
+                variant.name,
+                fields
+            ));
+        }
+        out
+    }
+
+    /// Register the input type of complex enum `edef`, which has a nullable field per variant,
+    /// like the keys of its JSON representation. Exactly one of them must be given.
+    fn enum_input(&mut self, edef: &ast::EnumDef) -> String {
+        let fields = edef
+            .variants
+            .iter()
+            .map(|variant| {
+                let ty = match &variant.variant_type {
+                    ast::VariantType::Simple => "Boolean".to_owned(),
+                    ast::VariantType::Newtype(ty) => self.type_ref(ty, Direction::Input),
+                    ast::VariantType::Tuple(tdef) => {
+                        let name = format!("{}{}Input", edef.name, variant.name);
+                        let fields = self.tuple_fields(tdef, Direction::Input);
+                        self.helpers
+                            .insert(name.clone(), format!("input {} {{\n{}}}\n", name, fields));
+                        name
+                    }
+                    ast::VariantType::Struct(fields) => {
+                        let name = format!("{}{}Input", edef.name, variant.name);
+                        self.helpers.insert(name.clone(), String::new());
+                        let object = self.object(&None, Direction::Input, &name, fields);
+                        self.helpers.insert(name.clone(), object);
+                        name
+                    }
+                };
+                format!(
+                    "{}  {}: {}\n",
+                    description(&variant.doc_comment, "  "),
+                    variant.name,
+                    ty.trim_end_matches('!')
+                )
+            })
+            .join("");
+        format!(
+            "\"\"\"\nExactly one field must be given.\n\"\"\"\ninput {}Input {{\n{}}}\n",
+            edef.name, fields
+        )
+    }
+
+    fn tuple_fields(&mut self, tdef: &ast::TupleDef, direction: Direction) -> String {
+        tdef.elements()
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| format!("  item_{}: {}\n", i, self.type_ref(ty, direction)))
+            .join("")
+    }
+
+    /// The field of the `Query` or `Mutation` type for `endpoint`, taking the path parameters,
+    /// query, headers and body of the route as arguments.
+    fn root_field(&mut self, service: &ast::ServiceDef, endpoint: &ast::ServiceEndpoint) -> String {
+        let route = &endpoint.route;
+        let mut args = Vec::new();
+        for pair in route
+            .components()
+            .iter()
+            .filter_map(ast::ServiceRouteComponent::param)
+        {
+            args.push((pair.name.clone(), &pair.type_ident));
+        }
+        if let Some(query) = route.query() {
+            args.push(("query".to_owned(), query));
+        }
+        for header in route.headers() {
+            args.push((to_snake_case(&header.name), &header.type_ident));
+        }
+        if let Some(body) = route.request_body() {
+            args.push(("body".to_owned(), body));
+        }
+
+        let args = args
+            .into_iter()
+            .map(|(name, ty)| format!("{}: {}", name, self.type_ref(ty, Direction::Input)))
+            .join(", ");
+        format!(
+            "{}  {}{}{}: {}\n",
+            description(&endpoint.doc_comment, "  "),
+            to_camel_case(&service.name),
+            rpc_name(route),
+            if args.is_empty() {
+                String::new()
+            } else {
+                format!("({})", args)
+            },
+            self.type_ref(route.return_type(), Direction::Output)
+        )
+    }
+
+    /// A reference to the GraphQL type of `ty`, e.g. `[String!]!`, registering the input and
+    /// helper types it requires.
+    fn type_ref(&mut self, ty: &ast::TypeIdent, direction: Direction) -> String {
+        let named = match ty {
+            ast::TypeIdent::BuiltIn(atom) => self.scalar(*atom).to_owned(),
+            ast::TypeIdent::UserDefined(name) => match self.index.get(name) {
+                // newtypes are transparent on the wire
+                Some(ast::SpecItem::TupleStructDef(tdef)) if tdef.is_newtype() => {
+                    return self.type_ref(&tdef.elements.elements()[0], direction);
+                }
+                // flags are lists of their flags, whose enum is an input type as well
+                Some(ast::SpecItem::FlagsDef(_)) => format!("[{}!]", flag_enum_name(name)),
+                _ => match direction {
+                    Direction::Output => name.to_string(),
+                    Direction::Input => self.input_name(name),
+                },
+            },
+            ast::TypeIdent::Option(inner) => {
+                // GraphQL has no nested nullability, `option[option[T]]` is a nullable `T`
+                return self
+                    .type_ref(inner, direction)
+                    .trim_end_matches('!')
+                    .to_owned();
+            }
+            ast::TypeIdent::List(inner) => format!("[{}]", self.type_ref(inner, direction)),
+            ast::TypeIdent::Map(key, value) => {
+                let name = format!("{}Entry{}", helper_name(ty), direction.suffix());
+                let fields = format!(
+                    "  key: {}\n  value: {}\n",
+                    self.type_ref(key, direction),
+                    self.type_ref(value, direction)
+                );
+                self.helper(direction, &name, fields);
+                format!("[{}!]", name)
+            }
+            ast::TypeIdent::Result(ok, err) => {
+                let name = format!("{}{}", helper_name(ty), direction.suffix());
+                let fields = format!(
+                    "  ok: {}\n  err: {}\n",
+                    self.type_ref(ok, direction).trim_end_matches('!'),
+                    self.type_ref(err, direction).trim_end_matches('!')
+                );
+                self.helper(direction, &name, fields);
+                name
+            }
+            ast::TypeIdent::Tuple(tdef) => {
+                let name = format!("{}{}", helper_name(ty), direction.suffix());
+                let fields = self.tuple_fields(tdef, direction);
+                self.helper(direction, &name, fields);
+                name
+            }
+        };
+        format!("{}!", named)
+    }
+
+    fn helper(&mut self, direction: Direction, name: &str, fields: String) {
+        self.helpers.insert(
+            name.to_owned(),
+            format!("{} {} {{\n{}}}\n", direction.keyword(), name, fields),
+        );
+    }
+
+    /// The name of the input type of user-defined type `name`, registering it if required.
+    ///
+    /// Simple enums are valid input types themselves.
+    fn input_name(&mut self, name: &str) -> String {
+        let spec_item = self
+            .index
+            .get(name)
+            .expect("references are checked by `check_references`");
+        match spec_item {
+            ast::SpecItem::EnumDef(edef) if edef.complex_variants().next().is_none() => {
+                return name.to_owned()
+            }
+            ast::SpecItem::ServiceDef(_) => unreachable!("checked by `check_references`"),
+            _ => {}
+        }
+        let input_name = format!("{}Input", name);
+        if self.helpers.contains_key(&input_name) {
+            return input_name;
+        }
+        // registered before rendering the fields, which may refer to it recursively
+        self.helpers.insert(input_name.clone(), String::new());
+        let input = match spec_item {
+            ast::SpecItem::StructDef(sdef) => self.object(
+                &sdef.doc_comment,
+                Direction::Input,
+                &input_name,
+                &sdef.fields,
+            ),
+            ast::SpecItem::EnumDef(edef) => self.enum_input(edef),
+            ast::SpecItem::TupleStructDef(tdef) => format!(
+                "{}input {} {{\n{}}}\n",
+                description(&tdef.doc_comment, ""),
+                input_name,
+                self.tuple_fields(&tdef.elements, Direction::Input)
+            ),
+            ast::SpecItem::FlagsDef(_) => unreachable!("lists of an enum, see `type_ref`"),
+            ast::SpecItem::ServiceDef(_) => unreachable!("rejected above"),
+        };
+        self.helpers.insert(input_name.clone(), input);
+        input_name
+    }
+
+    fn scalar(&mut self, atom: ast::AtomType) -> &'static str {
+        let scalar = match atom {
+            ast::AtomType::Empty | ast::AtomType::Bool => "Boolean",
+            ast::AtomType::Str => "String",
+            ast::AtomType::I32 | ast::AtomType::U8 => "Int",
+            ast::AtomType::F64 => "Float",
+            ast::AtomType::U32 => "U32",
+            ast::AtomType::I64 => "I64",
+            ast::AtomType::U64 => "U64",
+            ast::AtomType::DateTime => "DateTime",
+            ast::AtomType::Date => "Date",
+            ast::AtomType::Uuid => "UUID",
+            ast::AtomType::Bytes => "Bytes",
+        };
+        if !matches!(scalar, "Boolean" | "String" | "Int" | "Float") {
+            self.scalars.insert(scalar);
+        }
+        scalar
+    }
+}
+
+/// The definition of custom scalar `name`, describing its JSON representation.
+/// The GraphQL name of a field or enum value named `name` in the spec: its `json_name`, unless
+/// that is not a valid GraphQL name.
+/// The enum of the flags of a flags type, which is a list of them.
+fn flags_enum(fdef: &ast::FlagsDef) -> String {
+    let values = fdef
+        .flags
+        .iter()
+        .map(|flag| {
+            format!(
+                "{}  {}\n",
+                description(&flag.doc_comment, "  "),
+                graphql_name(flag.json_name(), &flag.name)
+            )
+        })
+        .join("");
+    format!(
+        "{}enum {} {{\n{}}}\n",
+        description(&fdef.doc_comment, ""),
+        flag_enum_name(&fdef.name),
+        values
+    )
+}
+
+fn flag_enum_name(flags_name: &str) -> String {
+    format!("{}Flag", flags_name)
+}
+
+fn graphql_name<'a>(json_name: Option<&'a str>, name: &'a str) -> &'a str {
+    match json_name {
+        Some(json_name)
+            if json_name.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic())
+                && json_name
+                    .chars()
+                    .all(|c| c == '_' || c.is_ascii_alphanumeric()) =>
+        {
+            json_name
+        }
+        _ => name,
+    }
+}
+
+/// Check that all types referenced in `spec` are defined by it, which is required to derive
+/// their input types.
+fn check_references(spec: &Spec) -> Result<(), LibError> {
+    let index = spec.index();
+    let mut result = Ok(());
+    spec.for_each_type_use(&mut |location, ty, _| {
+        let mut ty = ty.clone();
+        ty.for_each_mut(&mut |ty| {
+            let name = match ty.user_defined() {
+                Some(name) if result.is_ok() => name,
+                _ => return,
+            };
+            let message = match index.get(name) {
+                None => format!("references unknown type `{}`", name),
+                Some(ast::SpecItem::ServiceDef(_)) => format!("uses service `{}` as a type", name),
+                Some(_) => return,
+            };
+            result = Err(LibError::InvalidDefinition {
+                definition: location.to_owned(),
+                message,
+            });
+        });
+    });
+    result
+}
+
+fn scalar_def(name: &str) -> String {
+    let doc = match name {
+        "U32" => "Unsigned 32-bit integer, which exceeds the range of `Int`.",
+        "I64" => "Signed 64-bit integer, which exceeds the range of `Int`.",
+        "U64" => "Unsigned 64-bit integer, which exceeds the range of `Int`.",
+        "DateTime" => "Timestamp in UTC time, as RFC 3339 string.",
+        "Date" => "Date, as ISO 8601 string (`YYYY-MM-DD`).",
+        "UUID" => "UUID, as hyphenated string.",
+        "Bytes" => "Raw bytes, as base64 string.",
+        _ => unreachable!("unknown scalar {}", name),
+    };
+    format!("\"\"\"\n{}\n\"\"\"\nscalar {}\n", doc, name)
+}
+
+/// Render `doc_comment` as a block string description indented by `indent`.
+fn description(doc_comment: &Option<String>, indent: &str) -> String {
+    doc_comment
+        .as_ref()
+        .map(|doc| {
+            let lines = doc
+                .replace("\"\"\"", "\\\"\"\"")
+                .lines()
+                .map(|line| format!("{}{}", indent, line).trim_end().to_owned() + "\n")
+                .join("");
+            format!("{0}\"\"\"\n{1}{0}\"\"\"\n", indent, lines)
+        })
+        .unwrap_or_default()
+}
+
+fn write_file(contents: &str, output: &Path) -> Result<(), LibError> {
+    let mut outfile = File::create(output).map_err(LibError::file(output))?;
+    outfile
+        .write_all(contents.as_bytes())
+        .map_err(LibError::file(output))?;
+    Ok(())
+}
+
+impl crate::CodeGenerator for Generator {
+    fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        check_references(spec)?;
+        write_file(&self.render_spec(spec), output)
+    }
+
+    /// Writes a self-contained `{name}.graphql` schema for every spec, including the common types
+    /// it can use, into the `output` folder.
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        if !output.is_dir() {
+            return Err(LibError::OutputMustBeFolder {
+                backend: BACKEND_NAME,
+            });
+        }
+        for ws_spec in &workspace.specs {
+            let spec = workspace.resolved_spec(ws_spec);
+            check_references(&spec)?;
+            write_file(
+                &self.render_spec(&spec),
+                &output.join(format!("{}.graphql", ws_spec.name)),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn lossy_mappings(&self) -> &'static [LossyMapping] {
+        LOSSY_MAPPINGS
+    }
+}
@@ -0,0 +1,986 @@
+//! Generates Kotlin code for a humble specification file, e.g. for Android clients.
+//!
+//! Structs become data classes and enums become enum classes (if all variants are simple) or
+//! sealed classes, serializable with kotlinx.serialization using the JSON representation of
+//! `docs/humblespec/data_types_json_representation.md`. For the client artifact, every service
+//! becomes a Retrofit interface with a suspending function per route.
+//! Types without a Kotlin equivalent, like tuples, are generated as helper classes named after the
+//! type, e.g. `TupleOfF64AndStr`. See `docs/humblespec/kotlin.md` for the complete mapping.
+
+use crate::backend::protobuf::{helper_name, rpc_name};
+use crate::strict::LossyMapping;
+use crate::{
+    ast, workspace::Workspace, Artifact, EnumRepresentation, LibError, MapRepresentation, Spec,
+    WireCasing,
+};
+
+use inflector::cases::camelcase::to_camel_case;
+use itertools::Itertools;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+const BACKEND_NAME: &str = "kotlin";
+
+/// Built-in types that are mapped lossily, see `crate::strict`.
+const LOSSY_MAPPINGS: &[LossyMapping] = &[
+    LossyMapping {
+        atom: ast::AtomType::U32,
+        target: "Long",
+        loss: "which does not restrict values to unsigned 32-bit integers",
+    },
+    LossyMapping {
+        atom: ast::AtomType::U8,
+        target: "Int",
+        loss: "which does not restrict values to bytes",
+    },
+];
+
+const IMPORTS: &[&str] = &[
+    "kotlinx.datetime.Instant",
+    "kotlinx.datetime.LocalDate",
+    "kotlinx.serialization.ExperimentalSerializationApi",
+    "kotlinx.serialization.KSerializer",
+    "kotlinx.serialization.SerialName",
+    "kotlinx.serialization.Serializable",
+    "kotlinx.serialization.SerializationException",
+    "kotlinx.serialization.descriptors.PrimitiveKind",
+    "kotlinx.serialization.descriptors.PrimitiveSerialDescriptor",
+    "kotlinx.serialization.descriptors.SerialDescriptor",
+    "kotlinx.serialization.descriptors.buildClassSerialDescriptor",
+    "kotlinx.serialization.encoding.Decoder",
+    "kotlinx.serialization.encoding.Encoder",
+    "kotlinx.serialization.json.Json",
+    "kotlinx.serialization.json.JsonArray",
+    "kotlinx.serialization.json.JsonDecoder",
+    "kotlinx.serialization.json.JsonElement",
+    "kotlinx.serialization.json.JsonEncoder",
+    "kotlinx.serialization.json.JsonNames",
+    "kotlinx.serialization.json.JsonNull",
+    "kotlinx.serialization.json.JsonObject",
+    "kotlinx.serialization.json.JsonPrimitive",
+    "kotlinx.serialization.json.decodeFromJsonElement",
+    "kotlinx.serialization.json.encodeToJsonElement",
+    "kotlinx.serialization.json.jsonArray",
+    "kotlinx.serialization.json.jsonObject",
+];
+
+/// Imports of the client, which needs Retrofit and OkHttp in addition.
+const CLIENT_IMPORTS: &[&str] = &[
+    "kotlinx.serialization.encodeToString",
+    "okhttp3.MediaType.Companion.toMediaType",
+    "okhttp3.MultipartBody",
+    "okhttp3.RequestBody.Companion.toRequestBody",
+    "retrofit2.http.Body",
+    "retrofit2.http.DELETE",
+    "retrofit2.http.GET",
+    "retrofit2.http.Header",
+    "retrofit2.http.PATCH",
+    "retrofit2.http.POST",
+    "retrofit2.http.PUT",
+    "retrofit2.http.Path",
+    "retrofit2.http.QueryMap",
+];
+
+/// Hard keywords of Kotlin, which must be escaped to be used as names.
+const KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+pub struct Generator {
+    package: String,
+    client: bool,
+    map_representation: MapRepresentation,
+    enum_representation: EnumRepresentation,
+}
+
+impl Generator {
+    /// Create a generator declaring `package` in the generated file.
+    ///
+    /// Retrofit interfaces of the services are only rendered for the client artifact.
+    pub fn new(artifact: Artifact, package: String) -> Result<Self, LibError> {
+        match artifact {
+            Artifact::TypesOnly => Ok(Self {
+                package,
+                client: false,
+                map_representation: MapRepresentation::default(),
+                enum_representation: EnumRepresentation::default(),
+            }),
+            Artifact::ClientEndpoints => Ok(Self {
+                package,
+                client: true,
+                map_representation: MapRepresentation::default(),
+                enum_representation: EnumRepresentation::default(),
+            }),
+            Artifact::ServerEndpoints
+            | Artifact::ClientAndServer
+            | Artifact::Benchmarks
+            | Artifact::MockServer => Err(LibError::UnsupportedArtifact {
+                artifact,
+                backend: BACKEND_NAME,
+            }),
+        }
+    }
+
+    /// Represent maps whose keys are not `str` as `map_representation`. Maps represented as
+    /// entries become lists of key-value tuples.
+    pub fn with_map_representation(mut self, map_representation: MapRepresentation) -> Self {
+        self.map_representation = map_representation;
+        self
+    }
+
+    /// Represent enums without a `@tag` or `@untagged` annotation as `enum_representation`.
+    pub fn with_enum_representation(mut self, enum_representation: EnumRepresentation) -> Self {
+        self.enum_representation = enum_representation;
+        self
+    }
+
+    /// Render the Kotlin file of `spec` declaring `package`.
+    fn render_spec(&self, spec: &Spec, package: &str) -> String {
+        let spec = &self.map_representation.apply(spec);
+        let spec = &self.enum_representation.apply(spec);
+        let mut ctx = Context::default();
+
+        let mut definitions: Vec<String> = spec
+            .iter()
+            .filter_map(|spec_item| match spec_item {
+                ast::SpecItem::StructDef(sdef) => Some(ctx.struct_def(sdef)),
+                ast::SpecItem::TupleStructDef(tdef) => Some(ctx.tuple_struct_def(tdef)),
+                ast::SpecItem::EnumDef(edef) => Some(ctx.enum_def(edef)),
+                ast::SpecItem::FlagsDef(fdef) => Some(flags_def(fdef)),
+                ast::SpecItem::ServiceDef(_) => None,
+            })
+            .collect();
+        if self.client {
+            let services: Vec<&ast::ServiceDef> =
+                spec.iter().filter_map(ast::SpecItem::service_def).collect();
+            let query_structs: BTreeSet<&str> = services
+                .iter()
+                .flat_map(|service| &service.endpoints)
+                .filter_map(|endpoint| endpoint.route.query().as_ref()?.user_defined())
+                .map(ast::Ident::as_str)
+                .collect();
+            let multipart_structs: BTreeSet<&str> = services
+                .iter()
+                .flat_map(|service| &service.endpoints)
+                .filter(|e| e.route.request_body_kind() == Some(ast::BodyKind::Multipart))
+                .filter_map(|endpoint| endpoint.route.request_body()?.user_defined())
+                .map(ast::Ident::as_str)
+                .collect();
+            for sdef in spec.iter().filter_map(|spec_item| match spec_item {
+                ast::SpecItem::StructDef(sdef) => Some(sdef),
+                _ => None,
+            }) {
+                if query_structs.contains(sdef.name.as_str()) {
+                    definitions.push(query_map(sdef));
+                }
+                if multipart_structs.contains(sdef.name.as_str()) {
+                    definitions.push(multipart_body(sdef));
+                }
+            }
+            for service in services {
+                definitions.push(ctx.service_def(service, spec.meta().base_url.as_deref()));
+            }
+        }
+        // helper classes are registered while rendering the definitions above
+        definitions.extend(ctx.helpers.values().cloned());
+
+        let mut imports = IMPORTS.to_vec();
+        if self.client {
+            imports.extend(CLIENT_IMPORTS);
+        }
+        imports.sort_unstable();
+
+        let mut out = format!(
+            "// Generated by humblegen, do not edit.\n\
+            @file:OptIn(ExperimentalSerializationApi::class)\n\npackage {}\n\n{}\n",
+            package,
+            imports
+                .iter()
+                .map(|import| format!("import {}\n", import))
+                .join("")
+        );
+        out.push_str(include_str!("./kotlin/preamble.kt"));
+        for definition in definitions {
+            out.push('\n');
+            out.push_str(&definition);
+        }
+        out
+    }
+}
+
+/// Helper classes required by the rendered definitions.
+#[derive(Default)]
+struct Context {
+    /// Helper classes by name.
+    helpers: BTreeMap<String, String>,
+}
+
+impl Context {
+    fn struct_def(&mut self, sdef: &ast::StructDef) -> String {
+        format!(
+            "{}@Serializable\n{}\n",
+            comment(&sdef.doc_comment, ""),
+            self.data_class(&sdef.name, &sdef.fields, "", "")
+        )
+    }
+
+    /// A value class for newtypes, encoded as their inner value, otherwise a tuple class.
+    fn tuple_struct_def(&mut self, tdef: &ast::TupleStructDef) -> String {
+        let types: Vec<String> = tdef
+            .elements
+            .elements()
+            .iter()
+            .map(|ty| self.kotlin_type(ty))
+            .collect();
+        if tdef.is_newtype() {
+            format!(
+                "{}@Serializable\n@JvmInline\nvalue class {}(val value: {})\n",
+                comment(&tdef.doc_comment, ""),
+                tdef.name,
+                types[0]
+            )
+        } else {
+            format!(
+                "{}{}",
+                comment(&tdef.doc_comment, ""),
+                tuple_class(&tdef.name, &types)
+            )
+        }
+    }
+
+    /// A data class named `name` with a property per field, `extends` its supertype if not
+    /// empty.
+    fn data_class(
+        &mut self,
+        name: &str,
+        fields: &ast::StructFields,
+        extends: &str,
+        indent: &str,
+    ) -> String {
+        if fields.0.is_empty() {
+            // data classes must have at least one property
+            return format!("class {}{}", name, extends);
+        }
+        let properties = fields
+            .iter()
+            .map(|field| {
+                let renamed_from = field.renamed_from();
+                let json_names = if renamed_from.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "@JsonNames({}) ",
+                        renamed_from.iter().map(|n| format!("\"{}\"", n)).join(", ")
+                    )
+                };
+                // absent `optional` fields are `null`, and omitted when encoding default values
+                format!(
+                    "{}{}    @SerialName(\"{}\") {}val {}: {}{},\n",
+                    comment(&field.doc_comment, &format!("{}    ", indent)),
+                    indent,
+                    field.wire_name(WireCasing::Spec),
+                    json_names,
+                    property_name(&field.pair.name),
+                    self.kotlin_type(&field.flat_value_type()),
+                    if field.optional { " = null" } else { "" }
+                )
+            })
+            .join("");
+        format!("data class {}(\n{}{}){}", name, properties, indent, extends)
+    }
+
+    fn enum_def(&mut self, edef: &ast::EnumDef) -> String {
+        if edef.is_numeric() {
+            return numeric_enum_class(edef);
+        }
+        let tagging = edef.tagging();
+        if edef.complex_variants().next().is_none() && tagging == ast::EnumTagging::External {
+            let variants = edef
+                .variants
+                .iter()
+                .map(|variant| {
+                    let serial_name = variant
+                        .json_name()
+                        .map(|json_name| format!("@SerialName(\"{}\") ", json_name))
+                        .unwrap_or_default();
+                    format!(
+                        "{}    {}{},\n",
+                        comment(&variant.doc_comment, "    "),
+                        serial_name,
+                        variant.name
+                    )
+                })
+                .join("");
+            return format!(
+                "{}@Serializable\nenum class {} {{\n{}}}\n",
+                comment(&edef.doc_comment, ""),
+                edef.name,
+                variants
+            );
+        }
+
+        let name = &edef.name;
+        let extends = format!(" : {}()", name);
+        let mut classes = Vec::new();
+        let mut encoders = Vec::new();
+        let mut decoders = Vec::new();
+        for variant in &edef.variants {
+            let variant_class = format!("{}.{}", name, variant.name);
+            let wire_name = variant.wire_name(WireCasing::Spec);
+            let doc = comment(&variant.doc_comment, "    ");
+            // the encoder of the content of `value` and the decoder of the variant from `content`
+            let (encoder, decoder) = match &variant.variant_type {
+                ast::VariantType::Simple => {
+                    classes.push(format!("{}    object {}{}\n", doc, variant.name, extends));
+                    (None, variant_class.clone())
+                }
+                ast::VariantType::Newtype(ty) => {
+                    let ty = self.kotlin_type(ty);
+                    classes.push(format!(
+                        "{}    data class {}(val value: {}){}\n",
+                        doc, variant.name, ty, extends
+                    ));
+                    (
+                        Some(format!("json.encodeToJsonElement<{}>(value.value)", ty)),
+                        format!(
+                            "{}(json.decodeFromJsonElement<{}>(content))",
+                            variant_class, ty
+                        ),
+                    )
+                }
+                ast::VariantType::Tuple(tdef) => {
+                    let types: Vec<String> = tdef
+                        .elements()
+                        .iter()
+                        .map(|ty| self.kotlin_type(ty))
+                        .collect();
+                    classes.push(format!(
+                        "{}    data class {}({}){}\n",
+                        doc,
+                        variant.name,
+                        item_properties(&types),
+                        extends
+                    ));
+                    (
+                        Some(encode_items(&types)),
+                        format!(
+                            "content.jsonArray.let {{ items -> {}({}) }}",
+                            variant_class,
+                            decode_items(&types)
+                        ),
+                    )
+                }
+                ast::VariantType::Struct(fields) => {
+                    classes.push(format!(
+                        "{}    @Serializable\n    {}\n",
+                        doc,
+                        self.data_class(&variant.name, fields, &extends, "    ")
+                    ));
+                    (
+                        Some(format!(
+                            "json.encodeToJsonElement({}.serializer(), value)",
+                            variant_class
+                        )),
+                        format!(
+                            "json.decodeFromJsonElement({}.serializer(), content)",
+                            variant_class
+                        ),
+                    )
+                }
+            };
+            let pattern = match encoder {
+                None => variant_class.clone(),
+                Some(_) => format!("is {}", variant_class),
+            };
+            let tag_entry = |tag: &str| format!("\"{}\" to JsonPrimitive(\"{}\")", tag, wire_name);
+            let encoded = match (tagging, encoder) {
+                (ast::EnumTagging::External, None) => format!("JsonPrimitive(\"{}\")", wire_name),
+                (ast::EnumTagging::External, Some(encoder)) => {
+                    format!("JsonObject(mapOf(\"{}\" to {}))", wire_name, encoder)
+                }
+                (
+                    ast::EnumTagging::Internal { tag } | ast::EnumTagging::Adjacent { tag, .. },
+                    None,
+                ) => format!("JsonObject(mapOf({}))", tag_entry(tag)),
+                (ast::EnumTagging::Internal { tag }, Some(encoder)) => format!(
+                    "JsonObject(mapOf({}) + {}.jsonObject)",
+                    tag_entry(tag),
+                    encoder
+                ),
+                (ast::EnumTagging::Adjacent { tag, content }, Some(encoder)) => format!(
+                    "JsonObject(mapOf({}, \"{}\" to {}))",
+                    tag_entry(tag),
+                    content,
+                    encoder
+                ),
+                (ast::EnumTagging::Untagged, None) => "JsonNull".to_owned(),
+                (ast::EnumTagging::Untagged, Some(encoder)) => encoder,
+            };
+            encoders.push(format!("{} -> {}", pattern, encoded));
+            decoders.push(match tagging {
+                ast::EnumTagging::Untagged if variant.is_simple() => {
+                    format!("if (element is JsonNull) return {}", decoder)
+                }
+                ast::EnumTagging::Untagged => format!("runCatching {{ return {} }}", decoder),
+                _ => format!("\"{}\" -> {}", wire_name, decoder),
+            });
+        }
+
+        let decode = match tagging {
+            ast::EnumTagging::External => {
+                let (simple_decoders, decoders): (Vec<_>, Vec<_>) = edef
+                    .variants
+                    .iter()
+                    .zip(decoders)
+                    .partition(|(variant, _)| variant.is_simple());
+                let decode_simple = if simple_decoders.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "        if (element is JsonPrimitive) {{\n            return when (element.content) {{\n{}                else -> throw SerializationException(\"unknown variant ${{element.content}} of {}\")\n            }}\n        }}\n",
+                        simple_decoders
+                            .iter()
+                            .map(|(_, d)| format!("                {}\n", d))
+                            .join(""),
+                        name
+                    )
+                };
+                format!(
+                    "{}        val (tag, content) = variantEntry(element, \"{}\")\n{}",
+                    decode_simple,
+                    name,
+                    decode_when(name, decoders.into_iter().map(|(_, d)| d))
+                )
+            }
+            ast::EnumTagging::Internal { tag } => format!(
+                "        val tag = variantTag(element, \"{tag}\", \"{name}\")\n{content}{when}",
+                tag = tag,
+                name = name,
+                content = if edef.complex_variants().next().is_some() {
+                    format!(
+                        "        val content = JsonObject(element.jsonObject - \"{}\")\n",
+                        tag
+                    )
+                } else {
+                    String::new()
+                },
+                when = decode_when(name, decoders),
+            ),
+            ast::EnumTagging::Adjacent { tag, content } => format!(
+                "        val tag = variantTag(element, \"{tag}\", \"{name}\")\n{content}{when}",
+                tag = tag,
+                name = name,
+                content = if edef.complex_variants().next().is_some() {
+                    format!(
+                        "        val content = element.jsonObject[\"{}\"] ?: JsonNull\n",
+                        content
+                    )
+                } else {
+                    String::new()
+                },
+                when = decode_when(name, decoders),
+            ),
+            ast::EnumTagging::Untagged => format!(
+                "        val content = element\n{}        throw SerializationException(\"no variant of {} matches\")\n",
+                decoders
+                    .iter()
+                    .map(|d| format!("        {}\n", d))
+                    .join(""),
+                name
+            ),
+        };
+        format!(
+            "{doc}@Serializable(with = {name}Serializer::class)\n\
+            sealed class {name} {{\n{classes}}}\n\n\
+            object {name}Serializer : KSerializer<{name}> {{\n\
+            \x20   override val descriptor: SerialDescriptor = buildClassSerialDescriptor(\"{name}\")\n\n\
+            \x20   override fun serialize(encoder: Encoder, value: {name}) {{\n\
+            \x20       val json = (encoder as JsonEncoder).json\n\
+            \x20       encoder.encodeJsonElement(\n\
+            \x20           when (value) {{\n{encoders}\
+            \x20           }}\n\
+            \x20       )\n\
+            \x20   }}\n\n\
+            \x20   override fun deserialize(decoder: Decoder): {name} {{\n\
+            \x20       val json = (decoder as JsonDecoder).json\n\
+            \x20       val element = decoder.decodeJsonElement()\n\
+            {decode}\
+            \x20   }}\n\
+            }}\n",
+            doc = comment(&edef.doc_comment, ""),
+            name = name,
+            classes = classes.join("\n"),
+            encoders = encoders
+                .iter()
+                .map(|e| format!("                {}\n", e))
+                .join(""),
+            decode = decode,
+        )
+    }
+
+    fn service_def(&mut self, service: &ast::ServiceDef, base_url: Option<&str>) -> String {
+        let functions = service
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                format!(
+                    "{}    {}\n",
+                    comment(&endpoint.doc_comment, "    "),
+                    self.function(endpoint)
+                )
+            })
+            .join("\n");
+        let companion = match base_url {
+            Some(base_url) => format!(
+                "    companion object {{\n\
+                \x20       /** The URL the service is mounted at by default, declared in the `meta` block of the spec. */\n\
+                \x20       const val BASE_URL = \"{}\"\n\
+                \x20   }}\n\n",
+                base_url
+            ),
+            None => String::new(),
+        };
+        format!(
+            "{}interface {} {{\n{}{}}}\n",
+            comment(&service.doc_comment, ""),
+            service.name,
+            companion,
+            functions
+        )
+    }
+
+    /// The annotated Retrofit function of `route`, e.g.
+    /// `@GET("monsters/{id}") suspend fun getMonstersId(@Path("id") id: Int): Monster`.
+    ///
+    /// Functions of `@idempotent` routes send a new `Idempotency-Key` per call, unless they are
+    /// passed the key of the request to retry.
+    fn function(&mut self, endpoint: &ast::ServiceEndpoint) -> String {
+        let route = &endpoint.route;
+        let mut parameters = Vec::new();
+        let path = route
+            .components()
+            .iter()
+            .map(|component| match component {
+                ast::ServiceRouteComponent::Literal(lit) => lit.clone(),
+                ast::ServiceRouteComponent::Variable(pair) => {
+                    parameters.push(format!(
+                        "@Path(\"{}\") {}: {}",
+                        pair.name,
+                        property_name(&pair.name),
+                        self.kotlin_type(&pair.type_ident)
+                    ));
+                    format!("{{{}}}", pair.name)
+                }
+                // Retrofit would encode the slashes of the rest of the path
+                ast::ServiceRouteComponent::Rest(pair) => {
+                    parameters.push(format!(
+                        "@Path(\"{}\", encoded = true) {}: {}",
+                        pair.name,
+                        property_name(&pair.name),
+                        self.kotlin_type(&pair.type_ident)
+                    ));
+                    format!("{{{}}}", pair.name)
+                }
+            })
+            .join("/");
+        for header in route.headers() {
+            parameters.push(format!(
+                "@Header(\"{}\") {}: {}",
+                header.name,
+                to_camel_case(&format!("header_{}", header.name)),
+                self.kotlin_type(&header.type_ident)
+            ));
+        }
+        if route.query().is_some() {
+            parameters.push("@QueryMap query: Map<String, String>".to_owned());
+        }
+        match (route.request_body(), route.request_body_kind()) {
+            (Some(_), Some(ast::BodyKind::Multipart)) => {
+                parameters.push("@Body body: MultipartBody".to_owned())
+            }
+            (Some(body), _) => {
+                parameters.push(format!("@Body body: {}", self.kotlin_type(body)));
+            }
+            (None, _) => {}
+        }
+        if endpoint.is_idempotent() {
+            parameters.push(
+                "@Header(\"Idempotency-Key\") idempotencyKey: String = java.util.UUID.randomUUID().toString()"
+                    .to_owned(),
+            );
+        }
+        let mut ret = match route.return_type() {
+            ast::TypeIdent::BuiltIn(ast::AtomType::Empty) => "Unit".to_owned(),
+            ty => self.kotlin_type(ty),
+        };
+        // the declared response headers are read from the `Response`
+        if !route.response_headers().is_empty() {
+            ret = format!("retrofit2.Response<{}>", ret);
+        }
+        format!(
+            "@{}(\"{}\")\n    suspend fun {}({}): {}",
+            route.http_method_as_str(),
+            path,
+            to_camel_case(&rpc_name(route)),
+            parameters.join(", "),
+            ret
+        )
+    }
+
+    /// The Kotlin type of `ty`, registering a helper class for types without a Kotlin
+    /// equivalent.
+    fn kotlin_type(&mut self, ty: &ast::TypeIdent) -> String {
+        match ty {
+            ast::TypeIdent::BuiltIn(atom) => atom_type(*atom).to_owned(),
+            ast::TypeIdent::UserDefined(name) => name.to_string(),
+            ast::TypeIdent::List(inner) => format!("List<{}>", self.kotlin_type(inner)),
+            ast::TypeIdent::Option(inner) => {
+                let inner = self.kotlin_type(inner);
+                if inner.ends_with('?') {
+                    // nested options are indistinguishable in JSON
+                    inner
+                } else {
+                    format!("{}?", inner)
+                }
+            }
+            ast::TypeIdent::Map(key, value) => format!(
+                "Map<{}, {}>",
+                self.kotlin_type(key),
+                self.kotlin_type(value)
+            ),
+            ast::TypeIdent::Result(ok, err) => format!(
+                "HumbleResult<{}, {}>",
+                self.kotlin_type(ok),
+                self.kotlin_type(err)
+            ),
+            ast::TypeIdent::Tuple(tdef) => {
+                let name = helper_name(ty);
+                if !self.helpers.contains_key(&name) {
+                    let types: Vec<String> = tdef
+                        .elements()
+                        .iter()
+                        .map(|ty| self.kotlin_type(ty))
+                        .collect();
+                    self.helpers
+                        .insert(name.clone(), tuple_class(&name, &types));
+                }
+                name
+            }
+        }
+    }
+}
+
+/// Extension function turning a query struct into the query parameters of a request.
+///
+/// Optional fields are omitted if `null`.
+fn query_map(sdef: &ast::StructDef) -> String {
+    let entries = sdef
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.wire_name(WireCasing::Spec);
+            let property = property_name(&field.pair.name);
+            match &field.flat_value_type() {
+                ast::TypeIdent::BuiltIn(_) => {
+                    format!("    put(\"{}\", {}.toString())\n", name, property)
+                }
+                ast::TypeIdent::Option(inner) => match inner.as_ref() {
+                    ast::TypeIdent::BuiltIn(_) => format!(
+                        "    {}?.let {{ put(\"{}\", it.toString()) }}\n",
+                        property, name
+                    ),
+                    _ => format!(
+                        "    {}?.let {{ put(\"{}\", HumbleJson.encodeToString(it)) }}\n",
+                        property, name
+                    ),
+                },
+                _ => format!(
+                    "    put(\"{}\", HumbleJson.encodeToString({}))\n",
+                    name, property
+                ),
+            }
+        })
+        .join("");
+    format!(
+        "fun {}.toQueryMap(): Map<String, String> = buildMap {{\n{}}}\n",
+        sdef.name, entries
+    )
+}
+
+/// Extension function turning a struct into a `multipart/form-data` body.
+///
+/// `str` and `bytes` fields become text and file parts, all other fields JSON-encoded text
+/// parts. Optional fields are omitted if `null`.
+fn multipart_body(sdef: &ast::StructDef) -> String {
+    let part = |name: &str, ty: &ast::TypeIdent, value: &str| {
+        match ty {
+        ast::TypeIdent::BuiltIn(ast::AtomType::Str) => {
+            format!("addFormDataPart(\"{}\", {})", name, value)
+        }
+        ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => format!(
+            "addFormDataPart(\"{0}\", \"{0}\", {1}.bytes.toRequestBody(\"application/octet-stream\".toMediaType()))",
+            name, value
+        ),
+        _ => format!(
+            "addFormDataPart(\"{}\", HumbleJson.encodeToString({}))",
+            name, value
+        ),
+    }
+    };
+    let parts = sdef
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.pair.name;
+            let property = property_name(name);
+            match &field.flat_value_type() {
+                ast::TypeIdent::Option(inner) => {
+                    format!("    {}?.let {{ {} }}\n", property, part(name, inner, "it"))
+                }
+                ty => format!("    {}\n", part(name, ty, &property)),
+            }
+        })
+        .join("");
+    format!(
+        "fun {}.toMultipartBody(): MultipartBody = MultipartBody.Builder().setType(MultipartBody.FORM).apply {{\n{}}}.build()\n",
+        sdef.name, parts
+    )
+}
+
+/// A data class `name` for tuples of `types`, encoded as JSON array.
+/// A set of the enum class of the flags of a flags type, which kotlinx.serialization encodes as
+/// list of their names.
+fn flags_def(fdef: &ast::FlagsDef) -> String {
+    let flags = fdef
+        .flags
+        .iter()
+        .map(|flag| {
+            let serial_name = flag
+                .json_name()
+                .map(|json_name| format!("@SerialName(\"{}\") ", json_name))
+                .unwrap_or_default();
+            format!(
+                "{}    {}{},\n",
+                comment(&flag.doc_comment, "    "),
+                serial_name,
+                flag.name
+            )
+        })
+        .join("");
+    format!(
+        "@Serializable\nenum class {name}Flag {{\n{flags}}}\n\n{doc}typealias {name} = Set<{name}Flag>\n",
+        name = fdef.name,
+        flags = flags,
+        doc = comment(&fdef.doc_comment, ""),
+    )
+}
+
+/// An enum class of a `@numeric` enum, serialized as the discriminants of its variants.
+fn numeric_enum_class(edef: &ast::EnumDef) -> String {
+    let variants = edef
+        .variants
+        .iter()
+        .zip(edef.discriminants())
+        .map(|(variant, discriminant)| {
+            format!(
+                "{}    {}({}),\n",
+                comment(&variant.doc_comment, "    "),
+                variant.name,
+                discriminant
+            )
+        })
+        .join("");
+    format!(
+        "{doc}@Serializable(with = {name}Serializer::class)\n\
+        enum class {name}(val discriminant: Long) {{\n{variants}}}\n\n\
+        object {name}Serializer : KSerializer<{name}> {{\n\
+        \x20   override val descriptor: SerialDescriptor = PrimitiveSerialDescriptor(\"{name}\", PrimitiveKind.LONG)\n\n\
+        \x20   override fun serialize(encoder: Encoder, value: {name}) =\n\
+        \x20       encoder.encodeLong(value.discriminant)\n\n\
+        \x20   override fun deserialize(decoder: Decoder): {name} {{\n\
+        \x20       val discriminant = decoder.decodeLong()\n\
+        \x20       return {name}.values().firstOrNull {{ it.discriminant == discriminant }}\n\
+        \x20           ?: throw SerializationException(\"invalid discriminant $discriminant of {name}\")\n\
+        \x20   }}\n\
+        }}\n",
+        doc = comment(&edef.doc_comment, ""),
+        name = edef.name,
+        variants = variants,
+    )
+}
+
+fn tuple_class(name: &str, types: &[String]) -> String {
+    format!(
+        "@Serializable(with = {name}Serializer::class)\n\
+        data class {name}({properties})\n\n\
+        object {name}Serializer : KSerializer<{name}> {{\n\
+        \x20   override val descriptor: SerialDescriptor = buildClassSerialDescriptor(\"{name}\")\n\n\
+        \x20   override fun serialize(encoder: Encoder, value: {name}) {{\n\
+        \x20       val json = (encoder as JsonEncoder).json\n\
+        \x20       encoder.encodeJsonElement({encode})\n\
+        \x20   }}\n\n\
+        \x20   override fun deserialize(decoder: Decoder): {name} {{\n\
+        \x20       val json = (decoder as JsonDecoder).json\n\
+        \x20       val items = decoder.decodeJsonElement().jsonArray\n\
+        \x20       return {name}({decode})\n\
+        \x20   }}\n\
+        }}\n",
+        name = name,
+        properties = item_properties(types),
+        encode = encode_items(types),
+        decode = decode_items(types),
+    )
+}
+
+/// Statement returning the variant of enum `name` whose `decoders` matches `tag`.
+fn decode_when(name: &str, decoders: impl IntoIterator<Item = String>) -> String {
+    format!(
+        "        return when (tag) {{\n{}            else -> throw SerializationException(\"unknown variant $tag of {}\")\n        }}\n",
+        decoders
+            .into_iter()
+            .map(|d| format!("            {}\n", d))
+            .join(""),
+        name
+    )
+}
+
+/// Properties `item0`, `item1`, ... of `types`.
+fn item_properties(types: &[String]) -> String {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| format!("val item{}: {}", i, ty))
+        .join(", ")
+}
+
+/// Expression encoding the properties `item0`, `item1`, ... of `value` as JSON array.
+fn encode_items(types: &[String]) -> String {
+    format!(
+        "JsonArray(listOf({}))",
+        types
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| format!("json.encodeToJsonElement<{}>(value.item{})", ty, i))
+            .join(", ")
+    )
+}
+
+/// Arguments decoding the elements of JSON array `items`.
+fn decode_items(types: &[String]) -> String {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| format!("json.decodeFromJsonElement<{}>(items[{}])", ty, i))
+        .join(", ")
+}
+
+fn atom_type(atom: ast::AtomType) -> &'static str {
+    match atom {
+        ast::AtomType::Empty => "JsonNull",
+        ast::AtomType::Str => "String",
+        ast::AtomType::I32 | ast::AtomType::U8 => "Int",
+        ast::AtomType::U32 | ast::AtomType::I64 => "Long",
+        ast::AtomType::U64 => "ULong",
+        ast::AtomType::F64 => "Double",
+        ast::AtomType::Bool => "Boolean",
+        ast::AtomType::DateTime => "Instant",
+        ast::AtomType::Date => "LocalDate",
+        ast::AtomType::Uuid => "Uuid",
+        ast::AtomType::Bytes => "Bytes",
+    }
+}
+
+/// The name of the property of field `name`, e.g. `bornAt` for `born_at`.
+fn property_name(name: &str) -> String {
+    escape(&to_camel_case(name))
+}
+
+fn escape(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("`{}`", name)
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Render `doc_comment` as KDoc comment indented by `indent`.
+fn comment(doc_comment: &Option<String>, indent: &str) -> String {
+    match doc_comment {
+        None => String::new(),
+        Some(doc) => format!(
+            "{0}/**\n{1}{0} */\n",
+            indent,
+            doc.lines()
+                .map(|line| format!("{} * {}", indent, line).trim_end().to_owned() + "\n")
+                .join("")
+        ),
+    }
+}
+
+fn write_file(contents: &str, output: &Path) -> Result<(), LibError> {
+    let mut outfile = File::create(output).map_err(LibError::file(output))?;
+    outfile
+        .write_all(contents.as_bytes())
+        .map_err(LibError::file(output))?;
+    Ok(())
+}
+
+impl crate::CodeGenerator for Generator {
+    fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        write_file(&self.render_spec(spec, &self.package), output)
+    }
+
+    /// Writes a self-contained `{name}.kt` file for every spec, including the common types it
+    /// can use, into the `output` folder. Every file declares package `{package}.{name}`.
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        if !output.is_dir() {
+            return Err(LibError::OutputMustBeFolder {
+                backend: BACKEND_NAME,
+            });
+        }
+        for ws_spec in &workspace.specs {
+            write_file(
+                &self.render_spec(
+                    &workspace.resolved_spec(ws_spec),
+                    &format!("{}.{}", self.package, ws_spec.name),
+                ),
+                &output.join(format!("{}.kt", ws_spec.name)),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn lossy_mappings(&self) -> &'static [LossyMapping] {
+        LOSSY_MAPPINGS
+    }
+}
@@ -0,0 +1,70 @@
+/** JSON configuration matching the humblespec JSON representation, e.g. for a Retrofit converter. */
+val HumbleJson = Json { ignoreUnknownKeys = true }
+
+/** The `result[T][E]` type, encoded as `{"Ok": value}` or `{"Err": error}`. */
+@Serializable(with = HumbleResultSerializer::class)
+sealed class HumbleResult<out T, out E> {
+    data class Ok<out T>(val value: T) : HumbleResult<T, Nothing>()
+    data class Err<out E>(val error: E) : HumbleResult<Nothing, E>()
+}
+
+class HumbleResultSerializer<T, E>(
+    private val okSerializer: KSerializer<T>,
+    private val errSerializer: KSerializer<E>,
+) : KSerializer<HumbleResult<T, E>> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("HumbleResult") {
+        element("Ok", okSerializer.descriptor, isOptional = true)
+        element("Err", errSerializer.descriptor, isOptional = true)
+    }
+
+    override fun serialize(encoder: Encoder, value: HumbleResult<T, E>) {
+        val output = encoder as JsonEncoder
+        output.encodeJsonElement(
+            when (value) {
+                is HumbleResult.Ok -> JsonObject(mapOf("Ok" to output.json.encodeToJsonElement(okSerializer, value.value)))
+                is HumbleResult.Err -> JsonObject(mapOf("Err" to output.json.encodeToJsonElement(errSerializer, value.error)))
+            }
+        )
+    }
+
+    override fun deserialize(decoder: Decoder): HumbleResult<T, E> {
+        val input = decoder as JsonDecoder
+        val (tag, content) = input.decodeJsonElement().jsonObject.entries.single()
+        return when (tag) {
+            "Ok" -> HumbleResult.Ok(input.json.decodeFromJsonElement(okSerializer, content))
+            "Err" -> HumbleResult.Err(input.json.decodeFromJsonElement(errSerializer, content))
+            else -> throw SerializationException("unknown variant $tag of result")
+        }
+    }
+}
+
+/** The `uuid` type, in its hyphenated form. */
+@Serializable
+@JvmInline
+value class Uuid(val value: String) {
+    override fun toString(): String = value
+}
+
+/** The `bytes` type, encoded as base64. */
+@Serializable(with = BytesSerializer::class)
+class Bytes(val bytes: ByteArray)
+
+object BytesSerializer : KSerializer<Bytes> {
+    override val descriptor: SerialDescriptor = PrimitiveSerialDescriptor("Bytes", PrimitiveKind.STRING)
+
+    override fun serialize(encoder: Encoder, value: Bytes) =
+        encoder.encodeString(java.util.Base64.getEncoder().encodeToString(value.bytes))
+
+    override fun deserialize(decoder: Decoder): Bytes =
+        Bytes(java.util.Base64.getDecoder().decode(decoder.decodeString()))
+}
+
+/** Decode the single `tag: content` entry of an externally tagged enum variant. */
+private fun variantEntry(element: JsonElement, name: String): Pair<String, JsonElement> =
+    (element as? JsonObject)?.entries?.singleOrNull()?.toPair()
+        ?: throw SerializationException("expected a variant of $name")
+
+/** Decode the tag at `key` of an internally or adjacently tagged enum variant. */
+private fun variantTag(element: JsonElement, key: String, name: String): String =
+    ((element as? JsonObject)?.get(key) as? JsonPrimitive)?.takeIf { it.isString }?.content
+        ?: throw SerializationException("expected a variant of $name")
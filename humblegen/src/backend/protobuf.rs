@@ -0,0 +1,547 @@
+//! Generates a protobuf schema (a proto3 `.proto` file) for a humble specification file,
+//! e.g. to interoperate with gRPC services.
+//!
+//! Structs become messages, enums become enums (if all variants are simple) or messages with a
+//! `oneof`, and services become gRPC services with one rpc per route.
+//! Protobuf has no equivalent of nested composite types like `list[option[T]]`, these are
+//! wrapped in generated helper messages named after the type, e.g. `OptionOfI32`.
+//! See `docs/humblespec/protobuf.md` for the complete mapping.
+
+use crate::strict::LossyMapping;
+use crate::{ast, workspace::Workspace, Artifact, LibError, Spec};
+
+use inflector::cases::{
+    pascalcase::to_pascal_case, screamingsnakecase::to_screaming_snake_case,
+    snakecase::to_snake_case,
+};
+use itertools::Itertools;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+const BACKEND_NAME: &str = "protobuf";
+
+/// Built-in types that are mapped lossily, see `crate::strict`.
+const LOSSY_MAPPINGS: &[LossyMapping] = &[
+    LossyMapping {
+        atom: ast::AtomType::U8,
+        target: "uint32",
+        loss: "which does not restrict values to bytes",
+    },
+    LossyMapping {
+        atom: ast::AtomType::Date,
+        target: "string",
+        loss: "which does not validate dates",
+    },
+    LossyMapping {
+        atom: ast::AtomType::Uuid,
+        target: "string",
+        loss: "which does not validate UUIDs",
+    },
+];
+
+const EMPTY: &str = "google.protobuf.Empty";
+const TIMESTAMP: &str = "google.protobuf.Timestamp";
+
+pub struct Generator {
+    package: String,
+    services: bool,
+}
+
+impl Generator {
+    /// Create a generator declaring `package` in the generated file.
+    ///
+    /// Services are only rendered for the client or server artifacts, gRPC stubs of both sides
+    /// are generated from the same service definition.
+    pub fn new(artifact: Artifact, package: String) -> Result<Self, LibError> {
+        match artifact {
+            Artifact::TypesOnly => Ok(Self {
+                package,
+                services: false,
+            }),
+            Artifact::ClientEndpoints | Artifact::ServerEndpoints | Artifact::ClientAndServer => {
+                Ok(Self {
+                    package,
+                    services: true,
+                })
+            }
+            Artifact::Benchmarks | Artifact::MockServer => Err(LibError::UnsupportedArtifact {
+                artifact,
+                backend: BACKEND_NAME,
+            }),
+        }
+    }
+
+    /// Render the `.proto` file of `spec` declaring `package`.
+    fn render_spec(&self, spec: &Spec, package: &str) -> String {
+        let mut ctx = Context::default();
+
+        let mut definitions: Vec<String> = spec
+            .iter()
+            .filter_map(|spec_item| match spec_item {
+                ast::SpecItem::StructDef(sdef) => Some(ctx.struct_def(sdef)),
+                ast::SpecItem::TupleStructDef(tdef) => Some(ctx.tuple_struct_def(tdef)),
+                ast::SpecItem::EnumDef(edef) => Some(ctx.enum_def(edef)),
+                ast::SpecItem::FlagsDef(fdef) => Some(flags_def(fdef)),
+                ast::SpecItem::ServiceDef(_) => None,
+            })
+            .collect();
+        if self.services {
+            for service in spec.iter().filter_map(ast::SpecItem::service_def) {
+                definitions.push(ctx.service_def(service));
+            }
+        }
+        // helper messages are registered while rendering the definitions above
+        definitions.extend(ctx.helpers.values().cloned());
+
+        let mut out = format!(
+            "// Generated by humblegen, do not edit.\n\nsyntax = \"proto3\";\n\npackage {};\n",
+            package
+        );
+        if !ctx.imports.is_empty() {
+            out.push('\n');
+            for import in &ctx.imports {
+                out.push_str(&format!("import \"{}\";\n", import));
+            }
+        }
+        for definition in definitions {
+            out.push('\n');
+            out.push_str(&definition);
+        }
+        out
+    }
+}
+
+/// Helper messages and imports required by the rendered definitions.
+#[derive(Default)]
+struct Context {
+    /// Helper messages by name.
+    helpers: BTreeMap<String, String>,
+    imports: BTreeSet<&'static str>,
+}
+
+impl Context {
+    fn struct_def(&mut self, sdef: &ast::StructDef) -> String {
+        format!(
+            "{}message {} {{\n{}}}\n",
+            comment(&sdef.doc_comment, ""),
+            sdef.name,
+            self.fields(&sdef.fields, "  ")
+        )
+    }
+
+    fn tuple_struct_def(&mut self, tdef: &ast::TupleStructDef) -> String {
+        format!(
+            "{}message {} {{\n{}}}\n",
+            comment(&tdef.doc_comment, ""),
+            tdef.name,
+            self.tuple_fields(&tdef.elements, "  ")
+        )
+    }
+
+    fn fields(&mut self, fields: &ast::StructFields, indent: &str) -> String {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let mut def = self.field(&field.pair.name, i + 1, &field.flat_value_type());
+                if let Some(json_name) = field.json_name() {
+                    // the JSON mapping of protobuf uses the `json_name` as key
+                    def = format!(
+                        "{} [json_name = \"{}\"];",
+                        def.trim_end_matches(';'),
+                        json_name
+                    );
+                }
+                format!("{}{}{}\n", comment(&field.doc_comment, indent), indent, def)
+            })
+            .join("")
+    }
+
+    fn enum_def(&mut self, edef: &ast::EnumDef) -> String {
+        if edef.complex_variants().next().is_none() {
+            // proto3 enums must start with a zero value, which is the default of absent fields
+            let prefix = to_screaming_snake_case(&edef.name);
+            let values = edef
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| {
+                    format!(
+                        "{}  {}_{} = {};\n",
+                        comment(&variant.doc_comment, "  "),
+                        prefix,
+                        to_screaming_snake_case(&variant.name),
+                        i + 1
+                    )
+                })
+                .join("");
+            return format!(
+                "{}enum {} {{\n  {}_UNSPECIFIED = 0;\n{}}}\n",
+                comment(&edef.doc_comment, ""),
+                edef.name,
+                prefix,
+                values
+            );
+        }
+
+        let mut nested = String::new();
+        let mut members = String::new();
+        for (i, variant) in edef.variants.iter().enumerate() {
+            let member_type = match &variant.variant_type {
+                ast::VariantType::Simple => self.import(EMPTY),
+                ast::VariantType::Newtype(ty) => self.value_type(ty),
+                ast::VariantType::Tuple(tdef) => {
+                    nested.push_str(&format!(
+                        "  message {} {{\n{}  }}\n",
+                        variant.name,
+                        self.tuple_fields(tdef, "    ")
+                    ));
+                    variant.name.clone()
+                }
+                ast::VariantType::Struct(fields) => {
+                    nested.push_str(&format!(
+                        "  message {} {{\n{}  }}\n",
+                        variant.name,
+                        self.fields(fields, "    ")
+                    ));
+                    variant.name.clone()
+                }
+            };
+            members.push_str(&format!(
+                "{}    {} {} = {};\n",
+                comment(&variant.doc_comment, "    "),
+                member_type,
+                to_snake_case(&variant.name),
+                i + 1
+            ));
+        }
+        format!(
+            "{}message {} {{\n{}  oneof variant {{\n{}  }}\n}}\n",
+            comment(&edef.doc_comment, ""),
+            edef.name,
+            nested,
+            members
+        )
+    }
+
+    fn service_def(&mut self, service: &ast::ServiceDef) -> String {
+        let rpcs = service
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                let rpc = rpc_name(&endpoint.route);
+                let request = self.request_message(&service.name, &rpc, &endpoint.route);
+                let response = self.response_message(&service.name, &rpc, &endpoint.route);
+                format!(
+                    "{}  rpc {}({}) returns ({});\n",
+                    comment(&endpoint.doc_comment, "  "),
+                    rpc,
+                    request,
+                    response
+                )
+            })
+            .join("");
+        format!(
+            "{}service {} {{\n{}}}\n",
+            comment(&service.doc_comment, ""),
+            service.name,
+            rpcs
+        )
+    }
+
+    /// Register the request message of a route, containing its path parameters, query, headers
+    /// and body in this order, and return its name.
+    fn request_message(&mut self, service: &str, rpc: &str, route: &ast::ServiceRoute) -> String {
+        let mut fields = Vec::new();
+        for pair in route
+            .components()
+            .iter()
+            .filter_map(ast::ServiceRouteComponent::param)
+        {
+            fields.push((pair.name.clone(), &pair.type_ident));
+        }
+        if let Some(query) = route.query() {
+            fields.push(("query".to_owned(), query));
+        }
+        for header in route.headers() {
+            fields.push((to_snake_case(&header.name), &header.type_ident));
+        }
+        if let Some(body) = route.request_body() {
+            fields.push(("body".to_owned(), body));
+        }
+
+        let name = format!("{}{}Request", service, rpc);
+        let fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(i, (field_name, ty))| format!("  {}\n", self.field(&field_name, i + 1, ty)))
+            .join("");
+        self.helpers
+            .insert(name.clone(), format!("message {} {{\n{}}}\n", name, fields));
+        name
+    }
+
+    /// Register the response message of a route, unless it returns `()`, and return its name.
+    fn response_message(&mut self, service: &str, rpc: &str, route: &ast::ServiceRoute) -> String {
+        let name = format!("{}{}Response", service, rpc);
+        let body = match route.return_type() {
+            ast::TypeIdent::BuiltIn(ast::AtomType::Empty) => return self.import(EMPTY),
+            ast::TypeIdent::Result(ok, err) => self.result_oneof(ok, err),
+            ty => format!("  {}\n", self.field("value", 1, ty)),
+        };
+        self.helpers
+            .insert(name.clone(), format!("message {} {{\n{}}}\n", name, body));
+        name
+    }
+
+    fn result_oneof(&mut self, ok: &ast::TypeIdent, err: &ast::TypeIdent) -> String {
+        format!(
+            "  oneof result {{\n    {} ok = 1;\n    {} err = 2;\n  }}\n",
+            self.value_type(ok),
+            self.value_type(err)
+        )
+    }
+
+    fn tuple_fields(&mut self, tdef: &ast::TupleDef, indent: &str) -> String {
+        tdef.elements()
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                format!(
+                    "{}{}\n",
+                    indent,
+                    self.field(&format!("item_{}", i), i + 1, ty)
+                )
+            })
+            .join("")
+    }
+
+    /// The declaration of field `name` with number `number` of type `ty`, e.g. `repeated string
+    /// names = 1;`.
+    fn field(&mut self, name: &str, number: usize, ty: &ast::TypeIdent) -> String {
+        let ty = match ty {
+            ast::TypeIdent::Option(inner) => format!("optional {}", self.value_type(inner)),
+            ast::TypeIdent::List(inner) => format!("repeated {}", self.value_type(inner)),
+            ast::TypeIdent::Map(key, value) => match map_key_type(key) {
+                Some(key) => format!("map<{}, {}>", key, self.value_type(value)),
+                None => format!("repeated {}", self.map_entry(key, value)),
+            },
+            ty => self.value_type(ty),
+        };
+        format!("{} {} = {};", ty, name, number)
+    }
+
+    /// The name of the protobuf type of a singular field of type `ty`, registering a helper
+    /// message for types without a protobuf equivalent.
+    fn value_type(&mut self, ty: &ast::TypeIdent) -> String {
+        let body = match ty {
+            ast::TypeIdent::BuiltIn(atom) => return self.atom_type(*atom),
+            ast::TypeIdent::UserDefined(name) => return name.to_string(),
+            ast::TypeIdent::Result(ok, err) => self.result_oneof(ok, err),
+            ast::TypeIdent::Tuple(tdef) => self.tuple_fields(tdef, "  "),
+            ast::TypeIdent::Option(_) | ast::TypeIdent::List(_) | ast::TypeIdent::Map(..) => {
+                format!("  {}\n", self.field("value", 1, ty))
+            }
+        };
+        let name = helper_name(ty);
+        self.helpers
+            .insert(name.clone(), format!("message {} {{\n{}}}\n", name, body));
+        name
+    }
+
+    /// Register the entry message of a map whose keys are not allowed as protobuf map keys and
+    /// return its name.
+    fn map_entry(&mut self, key: &ast::TypeIdent, value: &ast::TypeIdent) -> String {
+        let name = format!(
+            "{}Entry",
+            helper_name(&ast::TypeIdent::Map(
+                Box::new(key.clone()),
+                Box::new(value.clone())
+            ))
+        );
+        let body = format!(
+            "  {} key = 1;\n  {} value = 2;\n",
+            self.value_type(key),
+            self.value_type(value)
+        );
+        self.helpers
+            .insert(name.clone(), format!("message {} {{\n{}}}\n", name, body));
+        name
+    }
+
+    fn atom_type(&mut self, atom: ast::AtomType) -> String {
+        match atom {
+            ast::AtomType::Empty => self.import(EMPTY),
+            ast::AtomType::Str | ast::AtomType::Date | ast::AtomType::Uuid => "string".to_owned(),
+            ast::AtomType::I32 => "int32".to_owned(),
+            ast::AtomType::I64 => "int64".to_owned(),
+            ast::AtomType::U64 => "uint64".to_owned(),
+            ast::AtomType::U32 | ast::AtomType::U8 => "uint32".to_owned(),
+            ast::AtomType::F64 => "double".to_owned(),
+            ast::AtomType::Bool => "bool".to_owned(),
+            ast::AtomType::DateTime => self.import(TIMESTAMP),
+            ast::AtomType::Bytes => "bytes".to_owned(),
+        }
+    }
+
+    /// Import the well-known type `ty` and return its name.
+    fn import(&mut self, ty: &'static str) -> String {
+        self.imports.insert(match ty {
+            EMPTY => "google/protobuf/empty.proto",
+            TIMESTAMP => "google/protobuf/timestamp.proto",
+            _ => unreachable!("unknown well-known type {}", ty),
+        });
+        ty.to_owned()
+    }
+}
+
+/// The protobuf type of map keys of type `key`, if it is allowed as a protobuf map key.
+fn map_key_type(key: &ast::TypeIdent) -> Option<&'static str> {
+    match key {
+        ast::TypeIdent::BuiltIn(atom) => match atom {
+            ast::AtomType::Str | ast::AtomType::Date | ast::AtomType::Uuid => Some("string"),
+            ast::AtomType::I32 => Some("int32"),
+            ast::AtomType::I64 => Some("int64"),
+            ast::AtomType::U64 => Some("uint64"),
+            ast::AtomType::U32 | ast::AtomType::U8 => Some("uint32"),
+            ast::AtomType::Bool => Some("bool"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The name of the helper message of `ty`, e.g. `MapOfStrToListOfMonster` for
+/// `map[str][list[Monster]]`.
+pub(crate) fn helper_name(ty: &ast::TypeIdent) -> String {
+    match ty {
+        ast::TypeIdent::BuiltIn(atom) => match atom {
+            ast::AtomType::Empty => "Empty",
+            ast::AtomType::Str => "Str",
+            ast::AtomType::I32 => "I32",
+            ast::AtomType::U32 => "U32",
+            ast::AtomType::I64 => "I64",
+            ast::AtomType::U64 => "U64",
+            ast::AtomType::U8 => "U8",
+            ast::AtomType::F64 => "F64",
+            ast::AtomType::Bool => "Bool",
+            ast::AtomType::DateTime => "DateTime",
+            ast::AtomType::Date => "Date",
+            ast::AtomType::Uuid => "Uuid",
+            ast::AtomType::Bytes => "Bytes",
+        }
+        .to_owned(),
+        ast::TypeIdent::UserDefined(name) => name.to_string(),
+        ast::TypeIdent::List(inner) => format!("ListOf{}", helper_name(inner)),
+        ast::TypeIdent::Option(inner) => format!("OptionOf{}", helper_name(inner)),
+        ast::TypeIdent::Map(key, value) => {
+            format!("MapOf{}To{}", helper_name(key), helper_name(value))
+        }
+        ast::TypeIdent::Result(ok, err) => {
+            format!("ResultOf{}Or{}", helper_name(ok), helper_name(err))
+        }
+        ast::TypeIdent::Tuple(tdef) => format!(
+            "TupleOf{}",
+            tdef.elements().iter().map(helper_name).join("And")
+        ),
+    }
+}
+
+/// The name of the rpc of `route`, e.g. `GetMonstersId` for `GET /monsters/{id}`, like the
+/// handler trait method of the Rust backend.
+pub(crate) fn rpc_name(route: &ast::ServiceRoute) -> String {
+    let verb = match route {
+        ast::ServiceRoute::Get { .. } => "get",
+        ast::ServiceRoute::Delete { .. } => "delete",
+        ast::ServiceRoute::Post { .. } => "post",
+        ast::ServiceRoute::Put { .. } => "put",
+        ast::ServiceRoute::Patch { .. } => "patch",
+    };
+    let components = route.components().iter().map(|component| match component {
+        ast::ServiceRouteComponent::Literal(lit) => lit.as_str(),
+        ast::ServiceRouteComponent::Variable(pair) | ast::ServiceRouteComponent::Rest(pair) => {
+            pair.name.as_str()
+        }
+    });
+    to_pascal_case(&std::iter::once(verb).chain(components).join("_"))
+}
+
+/// Render `doc_comment` as `//` comment lines indented by `indent`.
+/// A message holding the repeated flags of a flags type, which are an enum.
+fn flags_def(fdef: &ast::FlagsDef) -> String {
+    let flag = format!("{}Flag", fdef.name);
+    // proto3 enums must start with a zero value, which is the default of absent fields
+    let prefix = to_screaming_snake_case(&flag);
+    let values = fdef
+        .flags
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            format!(
+                "{}  {}_{} = {};\n",
+                comment(&f.doc_comment, "  "),
+                prefix,
+                to_screaming_snake_case(&f.name),
+                i + 1
+            )
+        })
+        .join("");
+    format!(
+        "{}message {} {{\n  repeated {} flags = 1;\n}}\n\nenum {} {{\n  {}_UNSPECIFIED = 0;\n{}}}\n",
+        comment(&fdef.doc_comment, ""),
+        fdef.name,
+        flag,
+        flag,
+        prefix,
+        values
+    )
+}
+
+fn comment(doc_comment: &Option<String>, indent: &str) -> String {
+    doc_comment
+        .iter()
+        .flat_map(|doc| doc.lines())
+        .map(|line| format!("{}// {}", indent, line).trim_end().to_owned() + "\n")
+        .join("")
+}
+
+fn write_file(contents: &str, output: &Path) -> Result<(), LibError> {
+    let mut outfile = File::create(output).map_err(LibError::file(output))?;
+    outfile
+        .write_all(contents.as_bytes())
+        .map_err(LibError::file(output))?;
+    Ok(())
+}
+
+impl crate::CodeGenerator for Generator {
+    fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        write_file(&self.render_spec(spec, &self.package), output)
+    }
+
+    /// Writes a self-contained `{name}.proto` file for every spec, including the common types it
+    /// can use, into the `output` folder. Every file declares package `{package}.{name}`.
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        if !output.is_dir() {
+            return Err(LibError::OutputMustBeFolder {
+                backend: BACKEND_NAME,
+            });
+        }
+        for ws_spec in &workspace.specs {
+            write_file(
+                &self.render_spec(
+                    &workspace.resolved_spec(ws_spec),
+                    &format!("{}.{}", self.package, ws_spec.name),
+                ),
+                &output.join(format!("{}.proto", ws_spec.name)),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn lossy_mappings(&self) -> &'static [LossyMapping] {
+        LOSSY_MAPPINGS
+    }
+}
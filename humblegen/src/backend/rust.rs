@@ -1,22 +1,119 @@
 //! Rust code generator.
 
+mod arbitrary;
+mod benchmarks;
+mod crate_scaffold;
+mod derives;
+mod flags;
+mod mock_server;
+mod response_headers;
 pub(crate) mod rustfmt;
+mod service_client;
 mod service_server;
+mod spec_endpoint;
 
-use crate::{ast, Artifact, LibError, Spec};
-use anyhow::Result;
+use crate::{
+    ast, wire_hash, workspace::Workspace, Artifact, EnumRepresentation, Environment,
+    LargeIntRepresentation, LibError, MapRepresentation, Spec, WireCasing,
+};
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use std::{fs::File, io::Write};
 
+pub use crate_scaffold::Crate;
+
 const BACKEND_NAME: &str = "rust";
 
+/// Rust keywords, strict and reserved, which spec names may collide with.
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that cannot be raw identifiers.
+const RUST_NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
 /// Helper function to format an ident.
 ///
-/// Turns a string into an ident, eases the use inside `quote!`.
+/// Turns a string into an ident, eases the use inside `quote!`. Rust keywords become raw
+/// identifiers, e.g. `r#type`, except for those that cannot be raw, which are suffixed with an
+/// underscore, e.g. `self_`.
 fn fmt_ident(ident: &str) -> proc_macro2::Ident {
-    quote::format_ident!("{}", ident)
+    if is_suffixed_ident(ident) {
+        quote::format_ident!("{}_", ident)
+    } else if RUST_KEYWORDS.contains(&ident) {
+        proc_macro2::Ident::new_raw(ident, proc_macro2::Span::call_site())
+    } else {
+        quote::format_ident!("{}", ident)
+    }
+}
+
+/// Generate a module for each `mod` block of the spec, re-exporting its definitions by their names
+/// in the block, e.g. `billing::Invoice` for `BillingInvoice`.
+fn generate_modules(spec: &ast::Spec) -> TokenStream {
+    let mut modules: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for item in spec.iter() {
+        if let Some((module, name)) = item.module() {
+            modules.entry(module).or_default().push((item.name(), name));
+        }
+    }
+    modules
+        .into_iter()
+        .map(|(module, items)| {
+            let doc_comment = format!("The definitions of `mod {}`.", module);
+            let module = fmt_ident(module);
+            let generated_names = items
+                .iter()
+                .map(|(generated_name, _)| fmt_ident(generated_name));
+            let names = items.iter().map(|(_, name)| fmt_ident(name));
+            quote! {
+                #[doc = #doc_comment]
+                pub mod #module {
+                    #(pub use super::#generated_names as #names;)*
+                }
+            }
+        })
+        .collect()
+}
+
+/// Implement `humblegen_rt::wire_hash::WireHash` for the types of `spec`, except for those named in
+/// `external_types`, and generate the `WIRE_MANIFEST` of all types of `spec`.
+fn generate_wire_hashes(
+    spec: &ast::Spec,
+    external_types: &BTreeSet<String>,
+    representation: wire_hash::Representation,
+) -> TokenStream {
+    let hashes = wire_hash::wire_hashes(spec, representation);
+    let manifest = wire_hash::manifest(spec, representation);
+    let impls = hashes
+        .iter()
+        .filter(|(name, _)| !external_types.contains(*name))
+        .map(|(name, hash)| {
+            let ident = fmt_ident(name);
+            quote! {
+                impl ::humblegen_rt::wire_hash::WireHash for #ident {
+                    const WIRE_HASH: &'static str = #hash;
+                }
+            }
+        });
+    quote! {
+        #(#impls)*
+
+        /// The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`.
+        pub const WIRE_MANIFEST: &str = #manifest;
+    }
+}
+
+/// Whether `fmt_ident` suffixes `ident`, which unlike raw identifiers changes its name on the
+/// wire.
+fn is_suffixed_ident(ident: &str) -> bool {
+    RUST_NON_RAW_KEYWORDS.contains(&ident)
 }
 
 /// Helper function to format an optional string as a string.
@@ -24,40 +121,578 @@ fn fmt_opt_string(s: &Option<String>) -> &str {
     s.as_ref().map(|s| s.as_str()).unwrap_or("")
 }
 
-/// Generate rust code for a struct definition.
-pub(crate) fn generate_struct_def(sdef: &ast::StructDef) -> TokenStream {
+/// Helper function to generate the `rename_all` attribute converting names to `casing`, if any.
+fn generate_rename_all(casing: WireCasing) -> Option<TokenStream> {
+    casing
+        .serde_rename_all()
+        .map(|rename_all| quote! { #[serde(rename_all = #rename_all)] })
+}
+
+/// Helper function to generate the `rename` attribute of a field or variant annotated with
+/// `@json_name(json_name)`, if any.
+///
+/// `rename` takes precedence over the `rename_all` of the containing type.
+fn generate_rename(json_name: Option<&str>) -> Option<TokenStream> {
+    json_name.map(|json_name| quote! { #[serde(rename = #json_name)] })
+}
+
+/// Helper function to generate the `rename` attribute of a field, which also keeps the wire
+/// name of fields whose ident is suffixed by `fmt_ident`.
+fn generate_field_rename(field: &ast::FieldNode, casing: WireCasing) -> Option<TokenStream> {
+    if is_suffixed_ident(&field.pair.name) {
+        return generate_rename(Some(&field.wire_name(casing)));
+    }
+    generate_rename(field.json_name())
+}
+
+/// Helper function to generate the `rename` attribute of a variant, see `generate_field_rename`.
+fn generate_variant_rename(variant: &ast::VariantDef, casing: WireCasing) -> Option<TokenStream> {
+    if is_suffixed_ident(&variant.name) {
+        return generate_rename(Some(&variant.wire_name(casing)));
+    }
+    generate_rename(variant.json_name())
+}
+
+/// Helper function to generate the attributes annotated with `@rust_attr`, verbatim.
+fn generate_rust_attrs(attrs: Vec<&str>) -> TokenStream {
+    attrs
+        .into_iter()
+        .map(|attr| attr.parse::<TokenStream>().expect("validated by parser"))
+        .collect()
+}
+
+/// Helper function to generate the serde attribute selecting the enum representation
+/// `tagging`, if it is not the default.
+fn generate_tagging(tagging: ast::EnumTagging) -> Option<TokenStream> {
+    match tagging {
+        ast::EnumTagging::External => None,
+        ast::EnumTagging::Internal { tag } => Some(quote! { #[serde(tag = #tag)] }),
+        ast::EnumTagging::Adjacent { tag, content } => {
+            Some(quote! { #[serde(tag = #tag, content = #content)] })
+        }
+        ast::EnumTagging::Untagged => Some(quote! { #[serde(untagged)] }),
+    }
+}
+
+/// Generate rust code for a struct definition with field names converted to `casing` on the wire.
+pub(crate) fn generate_struct_def(
+    sdef: &ast::StructDef,
+    derives: &BTreeSet<ast::Derive>,
+    casing: WireCasing,
+    large_ints: LargeIntRepresentation,
+) -> TokenStream {
     let ident = fmt_ident(&sdef.name);
+    let derive = derives::generate_derive_attribute(derives);
+    let rust_attrs = generate_rust_attrs(sdef.rust_attrs());
     let doc_comment = fmt_opt_string(&sdef.doc_comment);
-    let fields: Vec<_> = sdef.fields.iter().map(generate_pub_field_node).collect();
+    let db_row = sdef.is_db_row();
+    let db_row_derive = if db_row {
+        Some(quote! { #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))] })
+    } else {
+        None
+    };
+    let fields: Vec<_> = sdef
+        .fields
+        .iter()
+        .map(|field| {
+            let column = if db_row {
+                generate_column_attribute(field)
+            } else {
+                None
+            };
+            let field = generate_pub_field_node(field, casing, large_ints);
+            quote! { #column #field }
+        })
+        .collect();
+    let rename_all = generate_rename_all(casing);
+    let domain_conversions: Vec<_> = sdef
+        .rust_from()
+        .into_iter()
+        .map(|domain| generate_domain_conversions(sdef, domain))
+        .collect();
+    let db_row_impl = if db_row {
+        Some(generate_db_row_impl(sdef))
+    } else {
+        None
+    };
+
+    if sdef.fields.iter().all(|f| f.renamed_from().is_empty()) {
+        return quote!(
+            #derive
+            #db_row_derive
+            #rename_all
+            #rust_attrs
+            #[doc = #doc_comment]
+            pub struct #ident {
+                #(#fields),*
+            }
 
+            #db_row_impl
+
+            #(#domain_conversions)*
+        );
+    }
+
+    let (wire_ident, wire_def) = generate_renamed_fields_wire_struct(sdef, casing, large_ints);
+    let wire_name = wire_ident.to_string();
     quote!(
-        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+        #derive
+        #db_row_derive
+        #[serde(try_from = #wire_name)]
+        #rename_all
+        #rust_attrs
         #[doc = #doc_comment]
         pub struct #ident {
             #(#fields),*
         }
+
+        #wire_def
+
+        #db_row_impl
+
+        #(#domain_conversions)*
+    )
+}
+
+/// Generate rust code for a tuple struct definition.
+///
+/// Like struct fields, `bytes` elements are base64-encoded. A tuple struct with a single element
+/// is a newtype struct, which serde represents as its element.
+pub(crate) fn generate_tuple_struct_def(
+    tdef: &ast::TupleStructDef,
+    derives: &BTreeSet<ast::Derive>,
+    large_ints: LargeIntRepresentation,
+) -> TokenStream {
+    let ident = fmt_ident(&tdef.name);
+    let derive = derives::generate_derive_attribute(derives);
+    let rust_attrs = generate_rust_attrs(tdef.rust_attrs());
+    let doc_comment = fmt_opt_string(&tdef.doc_comment);
+    let elements: Vec<_> = tdef
+        .elements
+        .elements()
+        .iter()
+        .map(|ty| {
+            let attributes = generate_field_attributes(ty, large_ints);
+            let ty = generate_type_ident(ty);
+            quote! { #(#[#attributes])* pub #ty }
+        })
+        .collect();
+
+    quote!(
+        #derive
+        #rust_attrs
+        #[doc = #doc_comment]
+        pub struct #ident(#(#elements),*);
+    )
+}
+
+/// Generate the `sqlx` attribute of a field of a struct annotated with `@db_row` loading it from
+/// its column, if any.
+///
+/// Fields are loaded from the column named like them unless annotated with `@column(name)`.
+/// Fields of types that are neither built-in nor options thereof are JSON columns.
+fn generate_column_attribute(field: &ast::FieldNode) -> Option<TokenStream> {
+    let rename = field.column().map(|column| quote! { rename = #column });
+    let json = match &field.pair.type_ident {
+        ast::TypeIdent::BuiltIn(_) => None,
+        ast::TypeIdent::Option(inner) if matches!(**inner, ast::TypeIdent::BuiltIn(_)) => None,
+        _ => Some(quote! { json }),
+    };
+    let args: Vec<_> = rename.into_iter().chain(json).collect();
+    if args.is_empty() {
+        return None;
+    }
+    Some(quote! { #[cfg_attr(feature = "sqlx", sqlx(#(#args),*))] })
+}
+
+/// Generate the column names of a struct annotated with `@db_row`, e.g. for the select lists of
+/// queries loading it.
+fn generate_db_row_impl(sdef: &ast::StructDef) -> TokenStream {
+    let ident = fmt_ident(&sdef.name);
+    let columns = sdef.fields.iter().map(ast::FieldNode::column_name);
+    quote! {
+        impl #ident {
+            /// The names of the database columns the fields are loaded from, in field order.
+            pub const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+        }
+    }
+}
+
+/// Generate the conversions of a struct annotated with `@rust_from(domain)` from and into the
+/// domain type at path `domain`.
+///
+/// Fields are matched by name and converted using `Into` and `TryInto` respectively, elementwise
+/// for lists and options. The conversions are plain struct expressions, such that the compiler
+/// reports every field missing on either side and every field without a conversion.
+fn generate_domain_conversions(sdef: &ast::StructDef, domain: &str) -> TokenStream {
+    let ident = fmt_ident(&sdef.name);
+    let domain: syn::Path = syn::parse_str(domain).expect("validated by parser");
+
+    let mut from_domain = vec![];
+    let mut into_domain = vec![];
+    for field in sdef.fields.iter() {
+        let field_ident = fmt_ident(&field.pair.name);
+        let (from, into) = match field.value_type() {
+            ast::TypeIdent::List(_) => (
+                quote! {
+                    domain.#field_ident.into_iter().map(::std::convert::Into::into).collect()
+                },
+                quote! {
+                    wire.#field_ident
+                        .into_iter()
+                        .map(::std::convert::TryInto::try_into)
+                        .collect::<Result<_, _>>()?
+                },
+            ),
+            ast::TypeIdent::Option(_) => (
+                quote! { domain.#field_ident.map(::std::convert::Into::into) },
+                quote! {
+                    wire.#field_ident.map(::std::convert::TryInto::try_into).transpose()?
+                },
+            ),
+            _ => (
+                quote! { ::std::convert::Into::into(domain.#field_ident) },
+                quote! { ::std::convert::TryInto::try_into(wire.#field_ident)? },
+            ),
+        };
+        from_domain.push(quote! { #field_ident: #from });
+        into_domain.push(quote! { #field_ident: #into });
+    }
+
+    quote! {
+        impl ::std::convert::From<#domain> for #ident {
+            fn from(domain: #domain) -> Self {
+                Self {
+                    #(#from_domain),*
+                }
+            }
+        }
+
+        impl ::std::convert::TryFrom<#ident> for #domain {
+            type Error = Box<dyn ::std::error::Error + Send + Sync>;
+
+            fn try_from(wire: #ident) -> Result<Self, Self::Error> {
+                Ok(Self {
+                    #(#into_domain),*
+                })
+            }
+        }
+    }
+}
+
+/// Generate the struct that a struct with fields annotated with `@renamed_from` is deserialized
+/// from, and its conversion into the struct.
+///
+/// The wire struct accepts the current and all former keys of renamed fields, preferring the
+/// current key if several are present. Serialization is derived as usual, i.e., only uses
+/// the current key.
+fn generate_renamed_fields_wire_struct(
+    sdef: &ast::StructDef,
+    casing: WireCasing,
+    large_ints: LargeIntRepresentation,
+) -> (proc_macro2::Ident, TokenStream) {
+    let ident = fmt_ident(&sdef.name);
+    let wire_ident = quote::format_ident!("__{}Deserialize", sdef.name.as_str());
+
+    let mut wire_fields = vec![];
+    let mut conversions = vec![];
+    for field in sdef.fields.iter() {
+        let field_ident = fmt_ident(&field.pair.name);
+        let renamed_from = field.renamed_from();
+        if renamed_from.is_empty() {
+            wire_fields.push(generate_field_node(field, casing, large_ints, quote! {}));
+            conversions.push(quote! { #field_ident: wire.#field_ident });
+            continue;
+        }
+
+        let ty = generate_type_ident(&field.pair.type_ident);
+        let deser_fn = deser_present_fn(&field.pair.type_ident, large_ints);
+        let old_idents: Vec<_> = (0..renamed_from.len())
+            .map(|i| quote::format_ident!("__{}_renamed_from_{}", field.pair.name, i))
+            .collect();
+        let rename = generate_field_rename(field, casing);
+        wire_fields.push(quote! {
+            #[serde(default, deserialize_with = #deser_fn)]
+            #rename
+            #field_ident: Option<#ty>
+        });
+        for (old_ident, old_name) in old_idents.iter().zip(renamed_from) {
+            // `rename` takes precedence over `rename_all`
+            let old_name = casing.field_name(old_name);
+            wire_fields.push(quote! {
+                #[serde(rename = #old_name, default, deserialize_with = #deser_fn)]
+                #old_ident: Option<#ty>
+            });
+        }
+
+        // absent optional fields are `None`, like without renames
+        let missing = match field.pair.type_ident {
+            _ if field.optional => quote! {},
+            ast::TypeIdent::Option(_) => quote! { .unwrap_or(None) },
+            _ => {
+                let missing_field = format!("missing field `{}`", field.pair.name);
+                quote! { .ok_or_else(|| #missing_field.to_owned())? }
+            }
+        };
+        conversions.push(quote! {
+            #field_ident: wire.#field_ident #(.or(wire.#old_idents))* #missing
+        });
+    }
+
+    let rename_all = generate_rename_all(casing);
+    let wire_def = quote! {
+        #[doc(hidden)]
+        #[derive(serde::Deserialize)]
+        #rename_all
+        pub struct #wire_ident {
+            #(#wire_fields),*
+        }
+
+        impl ::std::convert::TryFrom<#wire_ident> for #ident {
+            type Error = String;
+
+            fn try_from(wire: #wire_ident) -> Result<Self, Self::Error> {
+                Ok(Self {
+                    #(#conversions),*
+                })
+            }
+        }
+    };
+    (wire_ident, wire_def)
+}
+
+/// Generate rust code for an enum definition with variant names, and field names of struct
+/// variants, converted to `casing` on the wire.
+///
+/// Enums deriving `Default` default to their first variant.
+pub(crate) fn generate_enum_def(
+    edef: &ast::EnumDef,
+    derives: &BTreeSet<ast::Derive>,
+    casing: WireCasing,
+    large_ints: LargeIntRepresentation,
+) -> TokenStream {
+    let ident = fmt_ident(&edef.name);
+    let doc_comment = fmt_opt_string(&edef.doc_comment);
+    let derive = derives::generate_derive_attribute(derives);
+    let rust_attrs = generate_rust_attrs(edef.rust_attrs());
+
+    let mut variants: Vec<_> = edef
+        .variants
+        .iter()
+        .map(|variant| generate_variant(variant, casing, large_ints))
+        .collect();
+    if let (Some(first), true) = (
+        variants.first_mut(),
+        derives.contains(&ast::Derive::Default),
+    ) {
+        *first = quote!(#[default] #first);
+    }
+    if edef.is_numeric() {
+        return generate_numeric_enum_def(edef, derives, variants);
+    }
+
+    let rename_all = generate_rename_all(casing);
+    let tagging = generate_tagging(edef.tagging());
+
+    if !edef.is_non_exhaustive() {
+        return quote!(
+            #derive
+            #rename_all
+            #tagging
+            #rust_attrs
+            #[doc = #doc_comment]
+            pub enum #ident {
+                #(#variants),*
+        });
+    }
+
+    let (wire_ident, wire_def) = generate_unknown_variant_wire_enum(edef, casing, large_ints);
+    let wire_name = wire_ident.to_string();
+    quote!(
+        #derive
+        #[serde(from = #wire_name)]
+        #rename_all
+        #tagging
+        #rust_attrs
+        #[non_exhaustive]
+        #[doc = #doc_comment]
+        pub enum #ident {
+            #(#variants,)*
+            /// A variant unknown to this version of the spec, e.g. added by a newer server.
+            /// It cannot be serialized.
+            #[serde(skip_serializing)]
+            Unknown
+        }
+
+        #wire_def
     )
 }
 
-/// Generate rust code for an enum definition.
-pub(crate) fn generate_enum_def(edef: &ast::EnumDef) -> TokenStream {
+/// Generate rust code for an enum annotated with `@numeric`, whose simple `variants` are
+/// serialized as their discriminants.
+///
+/// Unknown discriminants are deserialized as an `Unknown` variant if the enum is also
+/// `@non_exhaustive`, otherwise they are rejected.
+fn generate_numeric_enum_def(
+    edef: &ast::EnumDef,
+    derives: &BTreeSet<ast::Derive>,
+    variants: Vec<TokenStream>,
+) -> TokenStream {
     let ident = fmt_ident(&edef.name);
     let doc_comment = fmt_opt_string(&edef.doc_comment);
+    let derive = derives::generate_numeric_derive_attribute(derives);
+    let rust_attrs = generate_rust_attrs(edef.rust_attrs());
+    let discriminants = edef.discriminants();
+    let variant_idents: Vec<_> = edef.variants.iter().map(|v| fmt_ident(&v.name)).collect();
 
-    let variants: Vec<_> = edef.variants.iter().map(generate_variant).collect();
+    let (unknown, serialize_unknown, deserialize_unknown) = if edef.is_non_exhaustive() {
+        let message = format!("unknown variant of `{}` cannot be serialized", edef.name);
+        // an explicit discriminant that no other variant has
+        let discriminant = discriminants
+            .iter()
+            .max()
+            .map(|max| max.wrapping_add(1))
+            .filter(|_| edef.variants.iter().any(|v| v.discriminant.is_some()))
+            .map(proc_macro2::Literal::i64_unsuffixed)
+            .map(|d| quote! { = #d });
+        (
+            Some(quote! {
+                /// A variant unknown to this version of the spec, e.g. added by a newer server.
+                /// It cannot be serialized.
+                Unknown #discriminant,
+            }),
+            Some(quote! { #ident::Unknown => return Err(serde::ser::Error::custom(#message)), }),
+            quote! { _ => Ok(#ident::Unknown), },
+        )
+    } else {
+        let message = format!("invalid discriminant `{{}}` of `{}`", edef.name);
+        (
+            None,
+            None,
+            quote! { discriminant => Err(serde::de::Error::custom(format!(#message, discriminant))), },
+        )
+    };
+    let non_exhaustive = unknown.as_ref().map(|_| quote! { #[non_exhaustive] });
 
     quote!(
-        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+        #derive
+        #rust_attrs
+        #non_exhaustive
         #[doc = #doc_comment]
         pub enum #ident {
+            #(#variants,)*
+            #unknown
+        }
+
+        impl serde::Serialize for #ident {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let discriminant: i64 = match self {
+                    #(#ident::#variant_idents => #discriminants,)*
+                    #serialize_unknown
+                };
+                serializer.serialize_i64(discriminant)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #ident {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                match <i64 as serde::Deserialize>::deserialize(deserializer)? {
+                    #(#discriminants => Ok(#ident::#variant_idents),)*
+                    #deserialize_unknown
+                }
+            }
+        }
+    )
+}
+
+/// Generate the enum that an enum annotated with `@non_exhaustive` is deserialized from, and its
+/// conversion into the enum.
+///
+/// The wire enum is untagged, either one of the known variants or an unknown one, see
+/// `humblegen_rt::serialization_helpers::UnknownVariant`. The known variants are deserialized by
+/// a copy of the enum without the `Unknown` variant.
+fn generate_unknown_variant_wire_enum(
+    edef: &ast::EnumDef,
+    casing: WireCasing,
+    large_ints: LargeIntRepresentation,
+) -> (proc_macro2::Ident, TokenStream) {
+    let ident = fmt_ident(&edef.name);
+    let wire_ident = quote::format_ident!("__{}Deserialize", edef.name.as_str());
+    let known_ident = quote::format_ident!("__{}Known", edef.name.as_str());
+
+    let variants = edef
+        .variants
+        .iter()
+        .map(|variant| generate_variant(variant, casing, large_ints));
+    let conversions = edef.variants.iter().map(|variant| {
+        let variant_ident = fmt_ident(&variant.name);
+        let pattern = match &variant.variant_type {
+            ast::VariantType::Simple => quote! {},
+            ast::VariantType::Tuple(tdef) => {
+                let elements = (0..tdef.elements().len()).map(|i| quote::format_ident!("e{}", i));
+                quote! { (#(#elements),*) }
+            }
+            ast::VariantType::Struct(fields) => {
+                let fields = fields.iter().map(|field| fmt_ident(&field.pair.name));
+                quote! { { #(#fields),* } }
+            }
+            ast::VariantType::Newtype(_) => quote! { (e0) },
+        };
+        quote! { #known_ident::#variant_ident #pattern => #ident::#variant_ident #pattern }
+    });
+    let tag = match edef.tagging() {
+        ast::EnumTagging::Internal { tag } | ast::EnumTagging::Adjacent { tag, .. } => {
+            quote! { Some(#tag) }
+        }
+        ast::EnumTagging::External | ast::EnumTagging::Untagged => quote! { None },
+    };
+    let names = edef
+        .variants
+        .iter()
+        .map(|variant| variant.wire_name(casing));
+    let rename_all = generate_rename_all(casing);
+    let tagging = generate_tagging(edef.tagging());
+
+    let wire_def = quote! {
+        #[doc(hidden)]
+        #[derive(serde::Deserialize)]
+        #rename_all
+        #tagging
+        pub enum #known_ident {
             #(#variants),*
-    })
+        }
+
+        impl ::humblegen_rt::serialization_helpers::KnownVariants for #known_ident {
+            const TAG: Option<&'static str> = #tag;
+            const NAMES: &'static [&'static str] = &[#(#names),*];
+        }
+
+        #[doc(hidden)]
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        pub enum #wire_ident {
+            Known(#known_ident),
+            Unknown(::humblegen_rt::serialization_helpers::UnknownVariant<#known_ident>),
+        }
+
+        impl ::std::convert::From<#wire_ident> for #ident {
+            fn from(wire: #wire_ident) -> Self {
+                match wire {
+                    #wire_ident::Known(known) => match known {
+                        #(#conversions,)*
+                    },
+                    #wire_ident::Unknown(_) => #ident::Unknown,
+                }
+            }
+        }
+    };
+    (wire_ident, wire_def)
 }
 
-/// Generate rust code for a field node.
-fn generate_field_def_pair(pair: &ast::FieldDefPair) -> TokenStream {
-    let ident = fmt_ident(&pair.name);
-    let ty = generate_type_ident(&pair.type_ident);
+/// Generate rust code for the name and type of a field node.
+fn generate_field_def(field: &ast::FieldNode) -> TokenStream {
+    let ident = fmt_ident(&field.pair.name);
+    let ty = generate_type_ident(&field.value_type());
     quote!(#ident: #ty)
 }
 
@@ -65,44 +700,104 @@ fn generate_field_def_pair(pair: &ast::FieldDefPair) -> TokenStream {
 ///
 /// Even though all fields are pub in generated code, fields in a `pub enum` cannot carry an
 /// additional `pub` qualifier.
-fn generate_pub_field_node(field: &ast::FieldNode) -> TokenStream {
+fn generate_pub_field_node(
+    field: &ast::FieldNode,
+    casing: WireCasing,
+    large_ints: LargeIntRepresentation,
+) -> TokenStream {
+    let rust_attrs = generate_rust_attrs(field.rust_attrs());
+    let field = generate_field_node(field, casing, large_ints, quote! { pub });
+    quote! { #rust_attrs #field }
+}
+
+/// Generate rust code for a field node named in `casing` on the wire with visibility qualifier
+/// `vis`.
+fn generate_field_node(
+    field: &ast::FieldNode,
+    casing: WireCasing,
+    large_ints: LargeIntRepresentation,
+    vis: TokenStream,
+) -> TokenStream {
     let doc_comment = fmt_opt_string(&field.doc_comment);
-    let attributes = generate_field_attributes(&field.pair.type_ident);
-    let field = generate_field_def_pair(&field.pair);
+    let attributes = if field.optional {
+        generate_optional_field_attributes(&field.pair.type_ident, true, large_ints)
+    } else {
+        generate_field_attributes(&field.pair.type_ident, large_ints)
+    };
+    let rename = generate_field_rename(field, casing);
+    let field = generate_field_def(field);
     quote! {
         #[doc = #doc_comment]
         #(#[#attributes])*
-        pub #field
+        #rename
+        #vis #field
     }
 }
 
 /// Generate rust code for an enum variant.
-fn generate_variant(variant: &ast::VariantDef) -> TokenStream {
+fn generate_variant(
+    variant: &ast::VariantDef,
+    casing: WireCasing,
+    large_ints: LargeIntRepresentation,
+) -> TokenStream {
     let doc_comment = fmt_opt_string(&variant.doc_comment);
     let ident = fmt_ident(&variant.name);
+    let rename = generate_variant_rename(variant, casing);
+    let rust_attrs = generate_rust_attrs(variant.rust_attrs());
 
     match variant.variant_type {
-        ast::VariantType::Simple => quote!(#[doc = #doc_comment] #ident),
+        ast::VariantType::Simple => {
+            let discriminant = variant
+                .discriminant
+                .map(proc_macro2::Literal::i64_unsuffixed)
+                .map(|d| quote! { = #d });
+            quote!(#[doc = #doc_comment] #rename #rust_attrs #ident #discriminant)
+        }
         ast::VariantType::Tuple(ref inner) => {
-            let tuple = generate_tuple_def(inner);
-            quote!(#[doc = #doc_comment] #ident #tuple)
+            let elements: Vec<_> = inner
+                .elements()
+                .iter()
+                .map(|ty| {
+                    let attribute =
+                        generate_large_int_attribute(ty, large_ints).map(|a| quote!(#[#a]));
+                    let ty = generate_type_ident(ty);
+                    quote!(#attribute #ty)
+                })
+                .collect();
+            quote!(#[doc = #doc_comment] #rename #rust_attrs #ident(#(#elements),*))
         }
         ast::VariantType::Struct(ref fields) => {
             let fields: Vec<_> = fields
                 .iter()
                 .map(|field| {
                     let doc_comment = fmt_opt_string(&field.doc_comment);
-                    let fld = generate_field_def_pair(&field.pair);
-                    quote!(#[doc = #doc_comment] #fld)
+                    let attributes = if field.optional {
+                        generate_optional_field_attributes(
+                            &field.pair.type_ident,
+                            false,
+                            large_ints,
+                        )
+                    } else {
+                        generate_large_int_attribute(&field.pair.type_ident, large_ints)
+                            .into_iter()
+                            .collect()
+                    };
+                    let rename = generate_field_rename(field, casing);
+                    let rust_attrs = generate_rust_attrs(field.rust_attrs());
+                    let fld = generate_field_def(field);
+                    quote!(#[doc = #doc_comment] #(#[#attributes])* #rename #rust_attrs #fld)
                 })
                 .collect();
 
-            quote!(#[doc = #doc_comment] #ident { #(#fields),*})
+            // the `rename_all` of the enum only applies to the variant names
+            let rename_all = generate_rename_all(casing);
+            quote!(#[doc = #doc_comment] #rename #rename_all #rust_attrs #ident { #(#fields),*})
         }
         ast::VariantType::Newtype(ref ty) => {
+            let attribute = generate_large_int_attribute(ty, large_ints).map(|a| quote!(#[#a]));
             let inner = generate_type_ident(ty);
 
-            quote!(#[doc = #doc_comment] #ident(#inner))
+            quote!(#[doc = #doc_comment] #rename #rust_attrs #ident(#attribute #inner))
         }
     }
 }
@@ -131,7 +826,7 @@ fn generate_type_ident(type_ident: &ast::TypeIdent) -> TokenStream {
         }
         ast::TypeIdent::Tuple(tdef) => generate_tuple_def(tdef),
         ast::TypeIdent::UserDefined(ident) => {
-            let id = fmt_ident(&ident);
+            let id = fmt_ident(ident);
             quote!(#id)
         }
     }
@@ -142,13 +837,21 @@ fn generate_type_ident(type_ident: &ast::TypeIdent) -> TokenStream {
 type FieldAttributes = Vec<TokenStream>;
 
 /// Render the list of field attributes for the given type_ident
-fn generate_field_attributes(type_ident: &ast::TypeIdent) -> FieldAttributes {
+fn generate_field_attributes(
+    type_ident: &ast::TypeIdent,
+    large_ints: LargeIntRepresentation,
+) -> FieldAttributes {
+    if let Some(attribute) = generate_large_int_attribute(type_ident, large_ints) {
+        return vec![attribute];
+    }
     match type_ident {
         ast::TypeIdent::BuiltIn(atom) => match atom {
             ast::AtomType::Empty => vec![],
             ast::AtomType::Str => vec![],
             ast::AtomType::I32 => vec![],
             ast::AtomType::U32 => vec![],
+            ast::AtomType::I64 => vec![],
+            ast::AtomType::U64 => vec![],
             ast::AtomType::U8 => vec![],
             ast::AtomType::F64 => vec![],
             ast::AtomType::Bool => vec![],
@@ -169,6 +872,117 @@ fn generate_field_attributes(type_ident: &ast::TypeIdent) -> FieldAttributes {
     }
 }
 
+/// Render the attributes of an `optional` field of type `type_ident`, which is omitted if `None`
+/// and `None` if absent. As opposed to `option[T]` fields, `null` is a value, e.g. `Some(None)`.
+///
+/// Like other fields, `bytes` fields are base64-encoded only if `base64_bytes`, i.e., in structs.
+fn generate_optional_field_attributes(
+    type_ident: &ast::TypeIdent,
+    base64_bytes: bool,
+    large_ints: LargeIntRepresentation,
+) -> FieldAttributes {
+    if let Some(codec) = large_int_codec(type_ident, large_ints) {
+        let with = path_string(
+            quote!(::humblegen_rt::large_int::As::<::humblegen_rt::large_int::Present<#codec>>),
+        );
+        return vec![
+            quote! { serde(default, with = #with) },
+            quote! { serde(skip_serializing_if = "Option::is_none") },
+        ];
+    }
+    let deser_fn = if base64_bytes {
+        deser_present_fn(type_ident, large_ints)
+    } else {
+        "::humblegen_rt::serialization_helpers::deser_present".to_owned()
+    };
+    let mut attributes = vec![
+        quote! { serde(default, deserialize_with = #deser_fn) },
+        quote! { serde(skip_serializing_if = "Option::is_none") },
+    ];
+    if let (ast::TypeIdent::BuiltIn(ast::AtomType::Bytes), true) = (type_ident, base64_bytes) {
+        attributes.push(
+            quote! { serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_present_bytes") },
+        );
+    }
+    attributes
+}
+
+/// The helper deserializing a present field of type `type_ident` into `Some`.
+fn deser_present_fn(type_ident: &ast::TypeIdent, large_ints: LargeIntRepresentation) -> String {
+    if let Some(codec) = large_int_codec(type_ident, large_ints) {
+        return path_string(quote!(
+            ::humblegen_rt::large_int::As::<::humblegen_rt::large_int::Present<#codec>>::deserialize
+        ));
+    }
+    match type_ident {
+        ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => {
+            "::humblegen_rt::serialization_helpers::deser_present_bytes".to_owned()
+        }
+        _ => "::humblegen_rt::serialization_helpers::deser_present".to_owned(),
+    }
+}
+
+/// Render the attribute serializing the large integers within a field, tuple element or variant
+/// of type `type_ident` as strings, if they are represented as strings.
+fn generate_large_int_attribute(
+    type_ident: &ast::TypeIdent,
+    large_ints: LargeIntRepresentation,
+) -> Option<TokenStream> {
+    let codec = large_int_codec(type_ident, large_ints)?;
+    let with = path_string(quote!(::humblegen_rt::large_int::As::<#codec>));
+    Some(quote! { serde(with = #with) })
+}
+
+/// The string of `path` as referenced in serde attributes, e.g. `"::std::vec::Vec<u8>"`.
+fn path_string(path: TokenStream) -> String {
+    path.to_string().replace(' ', "")
+}
+
+/// The `humblegen_rt::large_int::Codec` of `type_ident` if it contains large integers
+/// represented as strings, e.g. `Vec<AsString>` for `list[i64]`.
+fn large_int_codec(
+    type_ident: &ast::TypeIdent,
+    large_ints: LargeIntRepresentation,
+) -> Option<TokenStream> {
+    if large_ints == LargeIntRepresentation::Number {
+        return None;
+    }
+    let codec = |ty: &ast::TypeIdent| {
+        large_int_codec(ty, large_ints).unwrap_or_else(|| quote!(::humblegen_rt::large_int::Same))
+    };
+    match type_ident {
+        ast::TypeIdent::BuiltIn(ast::AtomType::I64 | ast::AtomType::U64) => {
+            Some(quote!(::humblegen_rt::large_int::AsString))
+        }
+        ast::TypeIdent::BuiltIn(_) | ast::TypeIdent::UserDefined(_) => None,
+        ast::TypeIdent::List(inner) => {
+            let inner = large_int_codec(inner, large_ints)?;
+            Some(quote!(Vec<#inner>))
+        }
+        ast::TypeIdent::Option(inner) => {
+            let inner = large_int_codec(inner, large_ints)?;
+            Some(quote!(Option<#inner>))
+        }
+        ast::TypeIdent::Result(ok, err) => {
+            large_int_codec(ok, large_ints).or_else(|| large_int_codec(err, large_ints))?;
+            let (ok, err) = (codec(ok), codec(err));
+            Some(quote!(Result<#ok, #err>))
+        }
+        ast::TypeIdent::Map(key, value) => {
+            large_int_codec(key, large_ints).or_else(|| large_int_codec(value, large_ints))?;
+            let (key, value) = (codec(key), codec(value));
+            Some(quote!(::std::collections::HashMap<#key, #value>))
+        }
+        ast::TypeIdent::Tuple(tdef) => {
+            tdef.elements()
+                .iter()
+                .find_map(|ty| large_int_codec(ty, large_ints))?;
+            let elements = tdef.elements().iter().map(codec);
+            Some(quote!((#(#elements,)*)))
+        }
+    }
+}
+
 /// Generate rust code for a tuple definition.
 fn generate_tuple_def(tdef: &ast::TupleDef) -> TokenStream {
     let components: Vec<_> = tdef.elements().iter().map(generate_type_ident).collect();
@@ -187,6 +1001,8 @@ fn generate_atom(atom: &ast::AtomType) -> TokenStream {
         ast::AtomType::Str => quote!(String),
         ast::AtomType::I32 => quote!(i32),
         ast::AtomType::U32 => quote!(u32),
+        ast::AtomType::I64 => quote!(i64),
+        ast::AtomType::U64 => quote!(u64),
         ast::AtomType::U8 => quote!(u8),
         ast::AtomType::F64 => quote!(f64),
         ast::AtomType::Bool => quote!(bool),
@@ -201,54 +1017,499 @@ fn generate_atom(atom: &ast::AtomType) -> TokenStream {
     }
 }
 
-/// Generate rust code for a spec definition.
-pub fn render_spec(spec: &ast::Spec) -> TokenStream {
-    let mut out = TokenStream::new();
-
-    out.extend(spec.iter().flat_map(|spec_item| match spec_item {
-        ast::SpecItem::StructDef(sdef) => generate_struct_def(sdef),
-        ast::SpecItem::EnumDef(edef) => generate_enum_def(edef),
-        ast::SpecItem::ServiceDef(_) => quote! {}, // done below
-    }));
-
-    out.extend(service_server::generate_services(
-        spec.iter().filter_map(|si| si.service_def()),
-    ));
-
-    out
+/// How the methods of generated handler traits are declared.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum HandlerTraitStyle {
+    /// `async fn` methods, made object-safe by the `async_trait` proc macro.
+    ///
+    /// Implementors annotate their `impl` block with `#[humblegen_rt::async_trait(Sync)]`.
+    #[default]
+    AsyncTrait,
+    /// Plain `fn` methods returning a `humblegen_rt::handler::HandlerFuture`.
+    ///
+    /// Implementors construct the returned future themselves, which avoids the proc macro and
+    /// allows returning e.g. an already completed future without entering an `async` block.
+    /// Native `async fn` in traits is not an option because the generated `Handler` enum
+    /// stores handlers as trait objects.
+    BoxedFuture,
 }
 
 pub struct Generator {
-    _artifact: Artifact,
+    artifact: Artifact,
+    handler_trait_style: HandlerTraitStyle,
+    tracing_spans: bool,
+    arbitrary_impls: bool,
+    wire_casing: WireCasing,
+    map_representation: MapRepresentation,
+    large_int_representation: LargeIntRepresentation,
+    enum_representation: EnumRepresentation,
+    derives: Vec<ast::Derive>,
+    environments: Vec<Environment>,
+    krate: Option<Crate>,
 }
 
 impl Generator {
     pub fn new(artifact: Artifact) -> Result<Self, LibError> {
         match artifact {
-            Artifact::TypesOnly | Artifact::ServerEndpoints => Ok(Self {
-                _artifact: artifact,
-            }),
-            Artifact::ClientEndpoints => Err(LibError::UnsupportedArtifact {
+            Artifact::TypesOnly
+            | Artifact::ClientEndpoints
+            | Artifact::ServerEndpoints
+            | Artifact::ClientAndServer
+            | Artifact::Benchmarks
+            | Artifact::MockServer => Ok(Self {
                 artifact,
-                backend: BACKEND_NAME,
+                handler_trait_style: HandlerTraitStyle::default(),
+                tracing_spans: true,
+                arbitrary_impls: false,
+                wire_casing: WireCasing::default(),
+                map_representation: MapRepresentation::default(),
+                large_int_representation: LargeIntRepresentation::default(),
+                enum_representation: EnumRepresentation::default(),
+                derives: Vec::new(),
+                environments: Vec::new(),
+                krate: None,
             }),
         }
     }
+
+    /// Select how the methods of generated handler traits are declared.
+    pub fn with_handler_trait_style(mut self, handler_trait_style: HandlerTraitStyle) -> Self {
+        self.handler_trait_style = handler_trait_style;
+        self
+    }
+
+    /// Whether the requests to each route of generated servers run within a `tracing` span named
+    /// after the service and the route, e.g. `MonsterApi.get_monsters_id`, with the path params
+    /// and the status code of the response as fields. Enabled by default.
+    pub fn with_tracing_spans(mut self, tracing_spans: bool) -> Self {
+        self.tracing_spans = tracing_spans;
+        self
+    }
+
+    /// Additionally generate `proptest::arbitrary::Arbitrary` impls for all user-defined types,
+    /// which require the `arbitrary` feature of `humblegen-rt`.
+    pub fn with_arbitrary_impls(mut self, arbitrary_impls: bool) -> Self {
+        self.arbitrary_impls = arbitrary_impls;
+        self
+    }
+
+    /// Convert the names of struct fields and enum variants to `wire_casing` when serializing.
+    pub fn with_wire_casing(mut self, wire_casing: WireCasing) -> Self {
+        self.wire_casing = wire_casing;
+        self
+    }
+
+    /// Represent maps whose keys are not `str` as `map_representation`. Maps represented as
+    /// entries become `Vec`s of key-value tuples, which need no `Hash` impl of the key.
+    pub fn with_map_representation(mut self, map_representation: MapRepresentation) -> Self {
+        self.map_representation = map_representation;
+        self
+    }
+
+    /// Represent `i64` and `u64` as `large_int_representation` in JSON. Fields of the Rust types
+    /// are still integers, whose values are converted when serializing.
+    pub fn with_large_int_representation(
+        mut self,
+        large_int_representation: LargeIntRepresentation,
+    ) -> Self {
+        self.large_int_representation = large_int_representation;
+        self
+    }
+
+    /// Represent enums without a `@tag` or `@untagged` annotation as `enum_representation`.
+    pub fn with_enum_representation(mut self, enum_representation: EnumRepresentation) -> Self {
+        self.enum_representation = enum_representation;
+        self
+    }
+
+    /// Additionally derive `derives` for all user-defined types, besides the traits annotated
+    /// with `@derive`. Generation fails if a type cannot derive them, e.g. `Eq` with an `f64` field.
+    pub fn with_derives(mut self, derives: Vec<ast::Derive>) -> Self {
+        self.derives = derives;
+        self
+    }
+
+    /// Generate a `config::Environment` for each of `environments`, whose `config::Config`
+    /// creates clients of the services deployed there.
+    pub fn with_environments(mut self, environments: Vec<Environment>) -> Self {
+        self.environments = environments;
+        self
+    }
+
+    /// Scaffold the output folder as the crate `krate`: a `Cargo.toml` depending on the
+    /// `humblegen-rt` of this version, and the code in `src/lib.rs`, with the client code behind
+    /// a `client` and the server code behind a `server` cargo feature of the crate.
+    pub fn with_crate(mut self, krate: Option<Crate>) -> Self {
+        self.krate = krate;
+        self
+    }
+
+    /// `spec` with the maps and enums annotated or replaced as they are represented, see
+    /// `MapRepresentation::apply` and `EnumRepresentation::apply`.
+    fn prepare<'a>(&self, spec: &'a Spec) -> Cow<'a, Spec> {
+        match self.map_representation.apply(spec) {
+            Cow::Borrowed(spec) => self.enum_representation.apply(spec),
+            Cow::Owned(spec) => Cow::Owned(self.enum_representation.apply(&spec).into_owned()),
+        }
+    }
+
+    /// Generate rust code for a spec definition.
+    pub fn render_spec(&self, spec: &ast::Spec) -> TokenStream {
+        self.render_spec_with_external_types(spec, &BTreeSet::new())
+    }
+
+    /// Generate rust code for a spec definition, except for the types named in `external_types`,
+    /// which are defined elsewhere (e.g., in the common module of a workspace).
+    fn render_spec_with_external_types(
+        &self,
+        spec: &ast::Spec,
+        external_types: &BTreeSet<String>,
+    ) -> TokenStream {
+        let spec = &self.prepare(spec);
+        let mut out = self.render_types(spec, external_types);
+
+        if self.artifact == Artifact::Benchmarks {
+            // benchmarks are a standalone bench target, services are of no use there
+            out.extend(benchmarks::generate_benchmarks(spec));
+            return out;
+        }
+
+        // shared by client and server
+        out.extend(response_headers::generate_response_headers(spec));
+        out.extend(self.render_endpoints(
+            || self.render_client(spec, external_types),
+            || self.render_server(spec, external_types),
+        ));
+        out
+    }
+
+    /// Generate the user-defined types of `spec`, except for those named in `external_types`.
+    fn render_types(&self, spec: &ast::Spec, external_types: &BTreeSet<String>) -> TokenStream {
+        let mut out = TokenStream::new();
+
+        out.extend(
+            spec.iter()
+                .filter(|spec_item| !external_types.contains(spec_item.name()))
+                .flat_map(|spec_item| {
+                    let derives = &derives::derives(spec_item, &self.derives);
+                    match spec_item {
+                        ast::SpecItem::StructDef(sdef) => generate_struct_def(
+                            sdef,
+                            derives,
+                            self.wire_casing,
+                            self.large_int_representation,
+                        ),
+                        ast::SpecItem::TupleStructDef(tdef) => {
+                            generate_tuple_struct_def(tdef, derives, self.large_int_representation)
+                        }
+                        ast::SpecItem::EnumDef(edef) => generate_enum_def(
+                            edef,
+                            derives,
+                            self.wire_casing,
+                            self.large_int_representation,
+                        ),
+                        ast::SpecItem::FlagsDef(fdef) => {
+                            flags::generate_flags_def(fdef, self.wire_casing)
+                        }
+                        ast::SpecItem::ServiceDef(_) => quote! {}, // see `render_endpoints`
+                    }
+                }),
+        );
+
+        out.extend(generate_modules(spec));
+        out.extend(generate_wire_hashes(
+            spec,
+            external_types,
+            wire_hash::Representation {
+                wire_casing: self.wire_casing,
+                map_representation: self.map_representation,
+                enum_representation: self.enum_representation,
+                large_int_representation: self.large_int_representation,
+            },
+        ));
+
+        if self.arbitrary_impls {
+            out.extend(arbitrary::generate_arbitrary_impls(spec, external_types));
+        }
+
+        out
+    }
+
+    /// Select the client or server code of the artifact, or gate both behind cargo features
+    /// for `Artifact::ClientAndServer`. The endpoints of crates are always feature gated.
+    fn render_endpoints(
+        &self,
+        client: impl FnOnce() -> TokenStream,
+        server: impl FnOnce() -> TokenStream,
+    ) -> TokenStream {
+        match self.artifact {
+            Artifact::ClientEndpoints if self.krate.is_some() => feature_gated("client", client()),
+            Artifact::ClientEndpoints => client(),
+            Artifact::ClientAndServer => {
+                let mut out = feature_gated("client", client());
+                out.extend(feature_gated("server", server()));
+                out
+            }
+            _ if self.krate.is_some() => feature_gated("server", server()),
+            _ => server(),
+        }
+    }
+
+    /// The cargo features of the crate of the code generated for `specs`, see `with_crate`.
+    fn crate_features<'a>(
+        &self,
+        specs: impl IntoIterator<Item = &'a Spec>,
+    ) -> crate_scaffold::Features {
+        let mut features = crate_scaffold::Features {
+            client: matches!(
+                self.artifact,
+                Artifact::ClientEndpoints | Artifact::ClientAndServer
+            ),
+            server: self.artifact != Artifact::ClientEndpoints,
+            runtime: vec![],
+        };
+        if self.arbitrary_impls {
+            features.runtime.push("arbitrary");
+        }
+        let formats: Vec<_> = specs
+            .into_iter()
+            .flat_map(|spec| spec.iter().filter_map(ast::SpecItem::service_def))
+            .flat_map(ast::ServiceDef::wire_formats)
+            .collect();
+        if formats.contains(&ast::WireFormat::Cbor) {
+            features.runtime.push("cbor");
+        }
+        if formats.contains(&ast::WireFormat::Msgpack) {
+            features.runtime.push("msgpack");
+        }
+        features
+    }
+
+    /// Generate the clients of the services of `spec`.
+    fn render_client(&self, spec: &ast::Spec, external_types: &BTreeSet<String>) -> TokenStream {
+        let mut out = service_client::generate_config(spec, &self.environments);
+        out.extend(service_client::generate_clients(spec));
+        out.extend(service_client::generate_multipart_impls(
+            spec,
+            external_types,
+        ));
+        out
+    }
+
+    /// Generate the handler traits, server builder and, if requested, mocks of the services of
+    /// `spec`.
+    fn render_server(&self, spec: &ast::Spec, external_types: &BTreeSet<String>) -> TokenStream {
+        let mut out =
+            service_server::generate_services(spec, self.handler_trait_style, self.tracing_spans);
+        if self.artifact == Artifact::MockServer {
+            out.extend(mock_server::generate_mocks(spec, self.handler_trait_style));
+        }
+        out.extend(service_server::generate_multipart_impls(
+            spec,
+            external_types,
+        ));
+        out.extend(service_server::generate_error_status_impls(
+            spec,
+            external_types,
+        ));
+        out
+    }
+
+    /// Generate the rust code for the common module of a workspace.
+    fn render_workspace_common(&self, workspace: &Workspace) -> TokenStream {
+        let common = &self.prepare(&workspace.common);
+        let mut out = self.render_types(common, &BTreeSet::new());
+        out.extend(response_headers::generate_response_headers(common));
+
+        // common structs can be multipart bodies of the services of any spec
+        let spec_types: BTreeSet<String> = workspace
+            .specs
+            .iter()
+            .flat_map(|s| s.spec.iter())
+            .map(|spec_item| spec_item.name().to_owned())
+            .collect();
+        let services = workspace
+            .specs
+            .iter()
+            .flat_map(|s| s.spec.iter())
+            .filter(|spec_item| spec_item.service_def().is_some());
+        let usages = self
+            .prepare(&Spec::new(common.iter().chain(services).cloned().collect()))
+            .into_owned();
+        out.extend(self.render_endpoints(
+            || {
+                let mut client = self.render_client(common, &BTreeSet::new());
+                client.extend(service_client::generate_multipart_impls(
+                    &usages,
+                    &spec_types,
+                ));
+                client
+            },
+            || {
+                let mut server = self.render_server(common, &BTreeSet::new());
+                server.extend(service_server::generate_multipart_impls(
+                    &usages,
+                    &spec_types,
+                ));
+                server
+            },
+        ));
+
+        out
+    }
+
+    /// Reject the features of `spec` that the endpoints of the artifact cannot be generated for,
+    /// and types that cannot derive their traits.
+    fn check_supported(&self, spec: &Spec) -> Result<(), LibError> {
+        if self.krate.is_some() && self.artifact == Artifact::Benchmarks {
+            // benchmarks are a bench target, not a library
+            return Err(LibError::UnsupportedArtifact {
+                artifact: self.artifact,
+                backend: "rust crate",
+            });
+        }
+        derives::check_derives(&self.prepare(spec), &self.derives)?;
+        if matches!(
+            self.artifact,
+            Artifact::ClientEndpoints | Artifact::ClientAndServer
+        ) && spec
+            .iter()
+            .any(|item| matches!(item.module(), Some(("config", _))))
+        {
+            return Err(LibError::UnsupportedFeature {
+                feature: "modules named like the generated `config` module of clients".to_owned(),
+                backend: BACKEND_NAME,
+                span: "mod config".to_owned(),
+            });
+        }
+        match self.artifact {
+            Artifact::TypesOnly | Artifact::Benchmarks => Ok(()),
+            _ => super::check_multipart_bodies(spec, BACKEND_NAME),
+        }
+    }
+}
+
+/// Wrap `code` in a module named after `feature` that is only compiled if the cargo feature of
+/// the same name is enabled, and re-export its items.
+///
+/// Used for `Artifact::ClientAndServer`, whose client and server code live in one crate.
+fn feature_gated(feature: &str, code: TokenStream) -> TokenStream {
+    if code.is_empty() {
+        return code;
+    }
+    let module = fmt_ident(feature);
+    quote! {
+        #[cfg(feature = #feature)]
+        pub mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+            #code
+        }
+        #[cfg(feature = #feature)]
+        pub use #module::*;
+    }
+}
+
+/// Format `code` with rustfmt, if available, and write it to `output`.
+fn write_code(code: TokenStream, output: &Path) -> Result<(), LibError> {
+    let generated_code_unformatted = code.to_string();
+    let generated_code = rustfmt::rustfmt_2018_generated_string(&generated_code_unformatted)
+        .map(std::borrow::Cow::into_owned)
+        .unwrap_or(generated_code_unformatted);
+
+    let mut outfile = File::create(output).map_err(LibError::file(output))?;
+    outfile
+        .write_all(generated_code.as_bytes())
+        .map_err(LibError::file(output))?;
+    Ok(())
 }
 
 impl crate::CodeGenerator for Generator {
     fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
         // TODO: honor artifact field
-        let generated_code_unformatted = render_spec(spec).to_string();
-        let generated_code = rustfmt::rustfmt_2018_generated_string(&generated_code_unformatted)
-            .map(std::borrow::Cow::into_owned)
-            .unwrap_or(generated_code_unformatted);
-
-        // TODO: support folder as output path
-        let mut outfile = File::create(&output).map_err(LibError::IoError)?;
-        outfile
-            .write_all(generated_code.as_bytes())
-            .map_err(LibError::IoError)?;
-        Ok(())
+        self.check_supported(spec)?;
+        let krate = match &self.krate {
+            Some(krate) => krate,
+            None => return write_code(self.render_spec(spec), output),
+        };
+        let src = crate_scaffold::create_src_dir(output, BACKEND_NAME)?;
+        write_code(self.render_spec(spec), &src.join("lib.rs"))?;
+        crate_scaffold::write_cargo_toml(
+            krate,
+            spec.meta(),
+            &self.crate_features(Some(spec)),
+            output,
+        )
+    }
+
+    /// Writes `common.rs` with the common types, a `{name}.rs` module for every spec and
+    /// a `mod.rs` declaring them into the `output` folder, or into its `src` folder with a
+    /// `lib.rs` declaring them instead of `mod.rs` for crates.
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        if !output.is_dir() {
+            return Err(LibError::OutputMustBeFolder {
+                backend: BACKEND_NAME,
+            });
+        }
+        if self.artifact == Artifact::Benchmarks {
+            return Err(LibError::WorkspaceError(
+                "benchmarks cannot be generated for workspaces".to_owned(),
+            ));
+        }
+        let (src, root_module) = match &self.krate {
+            Some(_) => (
+                crate_scaffold::create_src_dir(output, BACKEND_NAME)?,
+                "lib.rs",
+            ),
+            None => (output.to_owned(), "mod.rs"),
+        };
+
+        write_code(
+            self.render_workspace_common(workspace),
+            &src.join("common.rs"),
+        )?;
+
+        for ws_spec in &workspace.specs {
+            self.check_supported(&workspace.resolved_spec(ws_spec))?;
+        }
+
+        let common_types = workspace.common_type_names();
+        for ws_spec in &workspace.specs {
+            let mut code = quote! {
+                #[allow(unused_imports)]
+                use super::common::*;
+            };
+            code.extend(
+                self.render_spec_with_external_types(
+                    &workspace.resolved_spec(ws_spec),
+                    &common_types,
+                ),
+            );
+            write_code(code, &src.join(format!("{}.rs", ws_spec.name)))?;
+        }
+
+        let modules = workspace.specs.iter().map(|s| fmt_ident(&s.name));
+        write_code(
+            quote! {
+                pub mod common;
+                #(pub mod #modules;)*
+            },
+            &src.join(root_module),
+        )?;
+
+        match &self.krate {
+            Some(krate) => {
+                let specs = Some(&workspace.common)
+                    .into_iter()
+                    .chain(workspace.specs.iter().map(|s| &s.spec));
+                crate_scaffold::write_cargo_toml(
+                    krate,
+                    workspace.common.meta(),
+                    &self.crate_features(specs),
+                    output,
+                )
+            }
+            None => Ok(()),
+        }
     }
 }
@@ -0,0 +1,177 @@
+//! Code generation for [`proptest`](https://docs.rs/proptest) `Arbitrary` impls of user-defined types,
+//! e.g. for round-trip serialization property tests.
+//!
+//! The entrypoint to this module is the `generate_arbitrary_impls` function.
+//! The impls take a `humblegen_rt::arbitrary::Depth` as parameters, which bounds the nesting of
+//! user-defined types within lists, maps and options, and thus the size of recursive types.
+//!
+//! The generated code requires the `arbitrary` feature of `humblegen-rt`.
+
+use crate::ast;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::BTreeSet;
+
+use super::fmt_ident;
+
+/// Generate `Arbitrary` impls for all user-defined types of a spec, except for the types
+/// named in `external_types`, whose impls are generated along with them.
+pub fn generate_arbitrary_impls(
+    spec: &ast::Spec,
+    external_types: &BTreeSet<String>,
+) -> TokenStream {
+    spec.iter()
+        .filter(|spec_item| !external_types.contains(spec_item.name()))
+        .map(|spec_item| match spec_item {
+            ast::SpecItem::StructDef(sdef) => generate_arbitrary_impl(
+                &sdef.name,
+                generate_fields_strategy(&sdef.fields, quote! { Self }),
+            ),
+            ast::SpecItem::TupleStructDef(tdef) => generate_arbitrary_impl(
+                &tdef.name,
+                generate_tuple_strategy(&tdef.elements, quote! { Self }),
+            ),
+            ast::SpecItem::EnumDef(edef) => {
+                generate_arbitrary_impl(&edef.name, generate_enum_strategy(edef))
+            }
+            ast::SpecItem::FlagsDef(fdef) => generate_arbitrary_impl(
+                &fdef.name,
+                quote! { any::<u64>().prop_map(Self::from_bits_truncate).boxed() },
+            ),
+            ast::SpecItem::ServiceDef(_) => quote! {},
+        })
+        .collect()
+}
+
+fn generate_arbitrary_impl(name: &str, strategy: TokenStream) -> TokenStream {
+    let ident = fmt_ident(name);
+    quote! {
+        impl ::humblegen_rt::proptest::arbitrary::Arbitrary for #ident {
+            type Parameters = ::humblegen_rt::arbitrary::Depth;
+            type Strategy = ::humblegen_rt::proptest::strategy::BoxedStrategy<Self>;
+
+            #[allow(unused_variables)]
+            fn arbitrary_with(depth: Self::Parameters) -> Self::Strategy {
+                #[allow(unused_imports)]
+                use ::humblegen_rt::proptest::prelude::*;
+                #strategy
+            }
+        }
+    }
+}
+
+/// Strategy of a struct or struct variant `path { fields }`.
+///
+/// The field strategies are nested pairs, since tuple strategies are limited to 12 elements.
+fn generate_fields_strategy(fields: &ast::StructFields, path: TokenStream) -> TokenStream {
+    let idents: Vec<_> = fields.iter().map(|f| fmt_ident(&f.pair.name)).collect();
+    let strategies = fields.iter().map(|f| generate_strategy(&f.value_type()));
+    let (strategy, pattern) = nest_pairs(
+        strategies.collect(),
+        idents.iter().map(|i| quote! { #i }).collect(),
+    );
+    quote! {
+        #strategy.prop_map(|#pattern| #path { #(#idents),* }).boxed()
+    }
+}
+
+/// Strategy of a tuple struct or tuple variant `path(elements)`.
+fn generate_tuple_strategy(tdef: &ast::TupleDef, path: TokenStream) -> TokenStream {
+    let idents: Vec<_> = (0..tdef.elements().len())
+        .map(|i| format_ident!("e{}", i))
+        .collect();
+    let (strategy, pattern) = nest_pairs(
+        tdef.elements().iter().map(generate_strategy).collect(),
+        idents.iter().map(|i| quote! { #i }).collect(),
+    );
+    quote! {
+        #strategy.prop_map(|#pattern| #path(#(#idents),*)).boxed()
+    }
+}
+
+/// Strategy of an enum, choosing its variants uniformly.
+fn generate_enum_strategy(edef: &ast::EnumDef) -> TokenStream {
+    let variants = edef.variants.iter().map(|variant| {
+        let variant_ident = fmt_ident(&variant.name);
+        match &variant.variant_type {
+            ast::VariantType::Simple => quote! { Just(Self::#variant_ident).boxed() },
+            ast::VariantType::Newtype(ty) => {
+                let strategy = generate_strategy(ty);
+                quote! { #strategy.prop_map(Self::#variant_ident).boxed() }
+            }
+            ast::VariantType::Tuple(tdef) => {
+                generate_tuple_strategy(tdef, quote! { Self::#variant_ident })
+            }
+            ast::VariantType::Struct(fields) => {
+                generate_fields_strategy(fields, quote! { Self::#variant_ident })
+            }
+        }
+    });
+    quote! {
+        ::humblegen_rt::proptest::strategy::Union::new(vec![#(#variants),*]).boxed()
+    }
+}
+
+/// Nests `strategies` into pairs `(s0, (s1, ... Just(())))`, returning the nested strategy and
+/// the pattern binding its values to `bindings`.
+fn nest_pairs(
+    strategies: Vec<TokenStream>,
+    bindings: Vec<TokenStream>,
+) -> (TokenStream, TokenStream) {
+    strategies.into_iter().zip(bindings).rev().fold(
+        (quote! { Just(()) }, quote! { () }),
+        |(strategy, pattern), (s, b)| (quote! { (#s, #strategy) }, quote! { (#b, #pattern) }),
+    )
+}
+
+/// Generate an expression of type `BoxedStrategy<T>` for the rust type `T` of `type_ident`,
+/// nested in a value at `depth`.
+fn generate_strategy(type_ident: &ast::TypeIdent) -> TokenStream {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => generate_atom_strategy(atom),
+        ast::TypeIdent::List(inner) => {
+            let inner = generate_strategy(inner);
+            quote! { ::humblegen_rt::arbitrary::list(depth, || #inner) }
+        }
+        ast::TypeIdent::Option(inner) => {
+            let inner = generate_strategy(inner);
+            quote! { ::humblegen_rt::arbitrary::option(depth, || #inner) }
+        }
+        ast::TypeIdent::Result(ok, err) => {
+            let ok = generate_strategy(ok);
+            let err = generate_strategy(err);
+            quote! { ::humblegen_rt::arbitrary::result(#ok, #err) }
+        }
+        ast::TypeIdent::Map(key, value) => {
+            let key = generate_strategy(key);
+            let value = generate_strategy(value);
+            quote! { ::humblegen_rt::arbitrary::map(depth, || #key, || #value) }
+        }
+        ast::TypeIdent::Tuple(tdef) => {
+            let elements = tdef.elements().iter().map(generate_strategy);
+            quote! { (#(#elements,)*).boxed() }
+        }
+        ast::TypeIdent::UserDefined(ident) => {
+            let ident = fmt_ident(ident);
+            quote! { ::humblegen_rt::arbitrary::user_defined::<#ident>(depth) }
+        }
+    }
+}
+
+fn generate_atom_strategy(atom: &ast::AtomType) -> TokenStream {
+    match atom {
+        ast::AtomType::Empty => quote! { any::<()>().boxed() },
+        ast::AtomType::Str => quote! { any::<String>().boxed() },
+        ast::AtomType::I32 => quote! { any::<i32>().boxed() },
+        ast::AtomType::U32 => quote! { any::<u32>().boxed() },
+        ast::AtomType::I64 => quote! { any::<i64>().boxed() },
+        ast::AtomType::U64 => quote! { any::<u64>().boxed() },
+        ast::AtomType::U8 => quote! { any::<u8>().boxed() },
+        ast::AtomType::F64 => quote! { ::humblegen_rt::arbitrary::f64() },
+        ast::AtomType::Bool => quote! { any::<bool>().boxed() },
+        ast::AtomType::DateTime => quote! { ::humblegen_rt::arbitrary::datetime() },
+        ast::AtomType::Date => quote! { ::humblegen_rt::arbitrary::date() },
+        ast::AtomType::Uuid => quote! { ::humblegen_rt::arbitrary::uuid() },
+        ast::AtomType::Bytes => quote! { ::humblegen_rt::arbitrary::bytes() },
+    }
+}
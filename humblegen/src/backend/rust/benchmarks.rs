@@ -0,0 +1,262 @@
+//! Code generation for [`criterion`](https://docs.rs/criterion) benchmarks of the JSON serialization
+//! of all user-defined types of a humblespec.
+//!
+//! The entrypoint to this module is the `generate_benchmarks` function.
+//! It generates:
+//!
+//! - a `fn sample_$TypeName(size: usize) -> $TypeName` for each user-defined type that deterministically
+//!   constructs a sample value whose payload grows with `size`,
+//! - a `fn bench_$TypeName` that measures serialization and deserialization of samples of all
+//!   `SAMPLE_SIZES`, and
+//! - the `criterion_group!` and `criterion_main!` invocations, i.e., the generated file is a complete
+//!   benchmark target that depends on `criterion` and `serde_json`.
+//!
+//! Collections contain `size` elements, whose own size is reduced to `size / 4` so that nested
+//! collections stay reasonably small.
+
+use crate::ast;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::fmt_ident;
+
+/// Entrypoint for generating the benchmarks of all types of a humblespec.
+pub fn generate_benchmarks(spec: &ast::Spec) -> TokenStream {
+    let mut out = TokenStream::new();
+
+    out.extend(quote! {
+        /// The sizes of the sample values each type is benchmarked with.
+        const SAMPLE_SIZES: [usize; 3] = [1, 10, 100];
+    });
+
+    let mut bench_fns = vec![];
+    for spec_item in spec.iter() {
+        match spec_item {
+            ast::SpecItem::StructDef(sdef) => {
+                out.extend(generate_struct_sample_fn(sdef));
+                out.extend(generate_bench_fn(&sdef.name));
+                bench_fns.push(bench_fn_ident(&sdef.name));
+            }
+            ast::SpecItem::TupleStructDef(tdef) => {
+                out.extend(generate_tuple_struct_sample_fn(tdef));
+                out.extend(generate_bench_fn(&tdef.name));
+                bench_fns.push(bench_fn_ident(&tdef.name));
+            }
+            ast::SpecItem::EnumDef(edef) => {
+                out.extend(generate_enum_sample_fn(edef));
+                // a variant-less enum has no values that could be benchmarked
+                if !edef.variants.is_empty() {
+                    out.extend(generate_bench_fn(&edef.name));
+                    bench_fns.push(bench_fn_ident(&edef.name));
+                }
+            }
+            ast::SpecItem::FlagsDef(fdef) => {
+                out.extend(generate_flags_sample_fn(fdef));
+                out.extend(generate_bench_fn(&fdef.name));
+                bench_fns.push(bench_fn_ident(&fdef.name));
+            }
+            ast::SpecItem::ServiceDef(_) => {}
+        }
+    }
+
+    out.extend(quote! {
+        ::criterion::criterion_group!(benches, #(#bench_fns),*);
+        ::criterion::criterion_main!(benches);
+    });
+
+    out
+}
+
+fn sample_fn_ident(type_name: &str) -> proc_macro2::Ident {
+    format_ident!("sample_{}", type_name)
+}
+
+fn bench_fn_ident(type_name: &str) -> proc_macro2::Ident {
+    format_ident!("bench_{}", type_name)
+}
+
+/// Generate the function benchmarking serialization and deserialization of type `type_name`.
+fn generate_bench_fn(type_name: &str) -> TokenStream {
+    let ident = fmt_ident(type_name);
+    let sample_fn = sample_fn_ident(type_name);
+    let bench_fn = bench_fn_ident(type_name);
+    quote! {
+        #[allow(non_snake_case)]
+        fn #bench_fn(c: &mut ::criterion::Criterion) {
+            let mut group = c.benchmark_group(#type_name);
+            for size in SAMPLE_SIZES.iter().copied() {
+                let value = #sample_fn(size);
+                let json = ::serde_json::to_vec(&value).expect("sample value must be serializable");
+                group.throughput(::criterion::Throughput::Bytes(json.len() as u64));
+                group.bench_with_input(
+                    ::criterion::BenchmarkId::new("serialize", size),
+                    &value,
+                    |b, value| b.iter(|| ::serde_json::to_vec(value).unwrap()),
+                );
+                group.bench_with_input(
+                    ::criterion::BenchmarkId::new("deserialize", size),
+                    &json,
+                    |b, json| b.iter(|| ::serde_json::from_slice::<#ident>(json).unwrap()),
+                );
+            }
+            group.finish();
+        }
+    }
+}
+
+/// Generate the sample function of a struct.
+fn generate_struct_sample_fn(sdef: &ast::StructDef) -> TokenStream {
+    let ident = fmt_ident(&sdef.name);
+    let sample_fn = sample_fn_ident(&sdef.name);
+    let fields = generate_fields_sample(&sdef.fields);
+    quote! {
+        #[allow(non_snake_case, unused_variables)]
+        fn #sample_fn(size: usize) -> #ident {
+            #ident { #fields }
+        }
+    }
+}
+
+/// Generate the sample function of a tuple struct.
+fn generate_tuple_struct_sample_fn(tdef: &ast::TupleStructDef) -> TokenStream {
+    let ident = fmt_ident(&tdef.name);
+    let sample_fn = sample_fn_ident(&tdef.name);
+    let elements = tdef.elements.elements().iter().map(generate_sample_expr);
+    quote! {
+        #[allow(non_snake_case, unused_variables)]
+        fn #sample_fn(size: usize) -> #ident {
+            #ident(#(#elements),*)
+        }
+    }
+}
+
+/// Generate the sample function of an enum, choosing the variant based on `size`.
+fn generate_enum_sample_fn(edef: &ast::EnumDef) -> TokenStream {
+    let ident = fmt_ident(&edef.name);
+    let sample_fn = sample_fn_ident(&edef.name);
+    let arms = edef.variants.iter().enumerate().map(|(idx, variant)| {
+        let variant_ident = fmt_ident(&variant.name);
+        let value = match &variant.variant_type {
+            ast::VariantType::Simple => quote! { #ident::#variant_ident },
+            ast::VariantType::Tuple(tdef) => {
+                let elements = tdef.elements().iter().map(generate_sample_expr);
+                quote! { #ident::#variant_ident(#(#elements),*) }
+            }
+            ast::VariantType::Struct(fields) => {
+                let fields = generate_fields_sample(fields);
+                quote! { #ident::#variant_ident { #fields } }
+            }
+            ast::VariantType::Newtype(ty) => {
+                let inner = generate_sample_expr(ty);
+                quote! { #ident::#variant_ident(#inner) }
+            }
+        };
+        quote! { #idx => #value }
+    });
+    let num_variants = edef.variants.len();
+    let body = if num_variants == 0 {
+        let msg = format!("enum {} has no variants", edef.name);
+        quote! { unreachable!(#msg) }
+    } else {
+        quote! {
+            match size % #num_variants {
+                #(#arms,)*
+                _ => unreachable!(),
+            }
+        }
+    };
+    quote! {
+        #[allow(non_snake_case, unused_variables)]
+        fn #sample_fn(size: usize) -> #ident {
+            #body
+        }
+    }
+}
+
+/// Generate the sample function of a flags type, containing the flags of the bits of `size`.
+fn generate_flags_sample_fn(fdef: &ast::FlagsDef) -> TokenStream {
+    let ident = fmt_ident(&fdef.name);
+    let sample_fn = sample_fn_ident(&fdef.name);
+    quote! {
+        #[allow(non_snake_case)]
+        fn #sample_fn(size: usize) -> #ident {
+            #ident::from_bits_truncate(size as u64)
+        }
+    }
+}
+
+/// Generate the field initializers of a struct or struct variant.
+fn generate_fields_sample(fields: &ast::StructFields) -> TokenStream {
+    let fields = fields.iter().map(|field| {
+        let field_ident = fmt_ident(&field.pair.name);
+        let value = generate_sample_expr(&field.value_type());
+        quote! { #field_ident: #value }
+    });
+    quote! { #(#fields),* }
+}
+
+/// Generate an expression constructing a sample value of `type_ident` from the `size: usize` in scope.
+fn generate_sample_expr(type_ident: &ast::TypeIdent) -> TokenStream {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => generate_atom_sample_expr(atom),
+        ast::TypeIdent::List(inner) => {
+            let inner = generate_sample_expr(inner);
+            quote! {
+                (0..size).map(|_| { let size = size / 4; #inner }).collect::<Vec<_>>()
+            }
+        }
+        ast::TypeIdent::Option(inner) => {
+            let inner = generate_sample_expr(inner);
+            quote! { Some(#inner) }
+        }
+        ast::TypeIdent::Result(ok, _) => {
+            let ok = generate_sample_expr(ok);
+            quote! { Ok(#ok) }
+        }
+        ast::TypeIdent::Map(key, value) => {
+            // derive the keys from the element index so that they are distinct
+            let key = generate_sample_expr(key);
+            let value = generate_sample_expr(value);
+            quote! {
+                (0..size)
+                    .map(|i| ({ let size = i; #key }, { let size = size / 4; #value }))
+                    .collect::<::std::collections::HashMap<_, _>>()
+            }
+        }
+        ast::TypeIdent::Tuple(tdef) => {
+            let elements = tdef.elements().iter().map(generate_sample_expr);
+            quote! { (#(#elements,)*) }
+        }
+        ast::TypeIdent::UserDefined(name) => {
+            let sample_fn = sample_fn_ident(name);
+            quote! { #sample_fn(size) }
+        }
+    }
+}
+
+/// Generate an expression constructing a sample value of an atomic type from the `size: usize` in scope.
+fn generate_atom_sample_expr(atom: &ast::AtomType) -> TokenStream {
+    match atom {
+        ast::AtomType::Empty => quote! { () },
+        ast::AtomType::Str => quote! { "x".repeat(size) },
+        ast::AtomType::I32 => quote! { size as i32 },
+        ast::AtomType::U32 => quote! { size as u32 },
+        ast::AtomType::I64 => quote! { size as i64 },
+        ast::AtomType::U64 => quote! { size as u64 },
+        ast::AtomType::U8 => quote! { size as u8 },
+        ast::AtomType::F64 => quote! { size as f64 / 3.0 },
+        ast::AtomType::Bool => quote! { size % 2 == 0 },
+        ast::AtomType::DateTime => quote! {
+            ::humblegen_rt::chrono::TimeZone::timestamp(
+                &::humblegen_rt::chrono::Utc,
+                size as i64,
+                0,
+            )
+        },
+        ast::AtomType::Date => quote! {
+            ::humblegen_rt::chrono::NaiveDate::from_num_days_from_ce(size as i32 + 1)
+        },
+        ast::AtomType::Uuid => quote! { ::humblegen_rt::uuid::Uuid::from_u128(size as u128) },
+        ast::AtomType::Bytes => quote! { vec![0xAB; size] },
+    }
+}
@@ -0,0 +1,115 @@
+//! Scaffolding of the generated code as a crate of its own, see `Generator::with_crate`.
+//!
+//! The crate consists of a `Cargo.toml` and the generated code in `src/lib.rs`, or in modules
+//! declared by `src/lib.rs` for workspaces. Its `humblegen-rt` dependency is pinned to the version
+//! of humblegen that generated the code, since generated code and runtime are released together.
+
+use crate::{ast, LibError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A crate the generated code is scaffolded as, see `Generator::with_crate`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Crate {
+    /// Package name, e.g. `monster-api-types`.
+    pub name: String,
+}
+
+impl Crate {
+    /// Crate `name`, or a description of why it is invalid.
+    pub fn new(name: &str) -> Result<Self, String> {
+        let is_valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_valid {
+            return Err(format!(
+                "crate name `{}` must start with a letter and consist of letters, digits, `-` and `_`",
+                name
+            ));
+        }
+        Ok(Self {
+            name: name.to_owned(),
+        })
+    }
+}
+
+/// The cargo features of a crate and the features of `humblegen-rt` its code requires.
+#[derive(Debug, Default)]
+pub(super) struct Features {
+    /// Whether the crate has client code, compiled with its `client` feature.
+    pub client: bool,
+    /// Whether the crate has server code, compiled with its `server` feature.
+    pub server: bool,
+    /// The features of `humblegen-rt` required regardless of the features of the crate.
+    pub runtime: Vec<&'static str>,
+}
+
+/// Create the `src` folder of a crate in the `output` folder, which must be empty, and return it.
+pub(super) fn create_src_dir(output: &Path, backend: &'static str) -> Result<PathBuf, LibError> {
+    if !output.is_dir() {
+        return Err(LibError::OutputMustBeFolder { backend });
+    }
+    let is_empty = output
+        .read_dir()
+        .map_err(LibError::file(output))?
+        .next()
+        .is_none();
+    if !is_empty {
+        return Err(LibError::OutputFolderNotEmpty { backend });
+    }
+    let src = output.join("src");
+    fs::create_dir(&src).map_err(LibError::file(&src))?;
+    Ok(src)
+}
+
+/// Write the `Cargo.toml` of `krate` with `features` into `output`. The version and description
+/// of the crate are those of `meta`, if given.
+pub(super) fn write_cargo_toml(
+    krate: &Crate,
+    meta: &ast::Meta,
+    features: &Features,
+    output: &Path,
+) -> Result<(), LibError> {
+    let version = meta.semver().unwrap_or("0.1.0");
+    let description = match &meta.title {
+        Some(title) => format!("Types and endpoints of {}", title),
+        None => "Generated types and endpoints".to_owned(),
+    };
+    // basic strings of TOML escape like JSON strings
+    let quote = |s: &str| serde_json::to_string(s).expect("strings are serializable");
+
+    let mut toml = String::new();
+    toml.push_str("# Generated by humblegen, regenerate instead of editing.\n");
+    toml.push_str("[package]\n");
+    toml.push_str(&format!("name = {}\n", quote(&krate.name)));
+    toml.push_str(&format!("version = {}\n", quote(version)));
+    toml.push_str("edition = \"2018\"\n");
+    toml.push_str(&format!("description = {}\n", quote(&description)));
+
+    toml.push_str("\n[dependencies]\n");
+    let runtime_version = format!("={}", env!("CARGO_PKG_VERSION"));
+    if features.runtime.is_empty() {
+        toml.push_str(&format!("humblegen-rt = {}\n", quote(&runtime_version)));
+    } else {
+        let runtime_features: Vec<_> = features.runtime.iter().map(|f| quote(f)).collect();
+        toml.push_str(&format!(
+            "humblegen-rt = {{ version = {}, features = [{}] }}\n",
+            quote(&runtime_version),
+            runtime_features.join(", ")
+        ));
+    }
+    toml.push_str("serde = { version = \"1.0.110\", features = [\"derive\"] }\n");
+
+    toml.push_str("\n[features]\n");
+    if features.client {
+        toml.push_str("client = [\"humblegen-rt/client\"]\n");
+    }
+    if features.server {
+        toml.push_str("server = []\n");
+        toml.push_str("tls = [\"humblegen-rt/tls\"]\n");
+    }
+
+    let path = output.join("Cargo.toml");
+    fs::write(&path, toml).map_err(LibError::file(&path))
+}
@@ -0,0 +1,155 @@
+//! Additional derives of generated types, e.g. `PartialEq` for assertions in tests.
+//!
+//! Types derive the traits passed to `Generator::with_derives` and those annotated with
+//! `@derive(Trait, ..)`, along with the traits these require, e.g. `PartialEq` for `Eq`.
+//! Deriving a trait requires the types of all fields to implement it, which is checked before
+//! generating code, such that violations are reported in terms of the spec:
+//!
+//! - `f64` implements neither `Eq`, `Hash` nor `Ord`.
+//! - Maps implement neither `Hash`, `PartialOrd` nor `Ord`, unless represented as entries.
+//! - `datetime`, `date` and `result[T, E]` have no `Default`.
+//! - Enums default to their first variant, which must be simple.
+//! - User-defined types implement the traits they derive themselves, flags types all traits.
+
+use crate::ast::{self, Derive};
+use crate::LibError;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::BTreeSet;
+
+/// The traits that `item` derives, given the `global` derives of all types, including the
+/// traits these require.
+pub(crate) fn derives(item: &ast::SpecItem, global: &[Derive]) -> BTreeSet<Derive> {
+    let mut derives: BTreeSet<Derive> = global.iter().copied().chain(item.derives()).collect();
+    let required: Vec<Derive> = derives
+        .iter()
+        .flat_map(|derive| derive.required())
+        .copied()
+        .collect();
+    derives.extend(required);
+    derives
+}
+
+/// Generate the derive attribute of a type deriving `derives`.
+pub(crate) fn generate_derive_attribute(derives: &BTreeSet<Derive>) -> TokenStream {
+    let derives = derives
+        .iter()
+        .map(|derive| format_ident!("{}", derive.as_str()));
+    quote! { #[derive(Debug, Clone, #(#derives,)* serde::Deserialize, serde::Serialize)] }
+}
+
+/// Generate the derive attribute of a `@numeric` enum deriving `derives`, which implements the
+/// serde traits itself.
+pub(crate) fn generate_numeric_derive_attribute(derives: &BTreeSet<Derive>) -> TokenStream {
+    let derives = derives
+        .iter()
+        .map(|derive| format_ident!("{}", derive.as_str()));
+    quote! { #[derive(Debug, Clone, #(#derives),*)] }
+}
+
+/// Check that the types of `spec` can derive their traits, given the `global` derives of all
+/// types.
+pub(crate) fn check_derives(spec: &ast::Spec, global: &[Derive]) -> Result<(), LibError> {
+    let index = spec.index();
+    let supports = |type_ident: &ast::TypeIdent, derive| {
+        supports(type_ident, derive, &|name: &str| {
+            // flags types implement all traits
+            index.get(name).is_some_and(|item| {
+                matches!(item, ast::SpecItem::FlagsDef(_))
+                    || derives(item, global).contains(&derive)
+            })
+        })
+    };
+
+    for item in spec.iter() {
+        for derive in derives(item, global) {
+            let violation = match item {
+                ast::SpecItem::StructDef(sdef) => sdef
+                    .fields
+                    .iter()
+                    .find(|field| !supports(&field.pair.type_ident, derive))
+                    .map(|field| {
+                        format!(
+                            "field `{}` of type `{}` does not implement it",
+                            field.pair.name, field.pair.type_ident
+                        )
+                    }),
+                ast::SpecItem::TupleStructDef(tdef) => tdef
+                    .elements
+                    .elements()
+                    .iter()
+                    .enumerate()
+                    .find(|(_, ty)| !supports(ty, derive))
+                    .map(|(i, ty)| format!("element {} of type `{}` does not implement it", i, ty)),
+                ast::SpecItem::EnumDef(edef) => match edef.variants.first() {
+                    Some(variant) if derive == Derive::Default && !variant.is_simple() => Some(
+                        format!("the first variant `{}` is not simple", variant.name),
+                    ),
+                    _ => edef.variants.iter().find_map(|variant| {
+                        unsupported_variant_type(&variant.variant_type, &|ty| supports(ty, derive))
+                            .map(|ty| {
+                                format!(
+                                    "variant `{}` with type `{}` does not implement it",
+                                    variant.name, ty
+                                )
+                            })
+                    }),
+                },
+                ast::SpecItem::FlagsDef(_) | ast::SpecItem::ServiceDef(_) => None,
+            };
+            if let Some(violation) = violation {
+                return Err(LibError::InvalidDefinition {
+                    definition: item.name().to_owned(),
+                    message: format!("cannot derive `{}`: {}", derive, violation),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The type within a variant of `variant_type` that does not implement a trait, if any.
+fn unsupported_variant_type<'a>(
+    variant_type: &'a ast::VariantType,
+    supports: &dyn Fn(&ast::TypeIdent) -> bool,
+) -> Option<&'a ast::TypeIdent> {
+    match variant_type {
+        ast::VariantType::Simple => None,
+        ast::VariantType::Tuple(tdef) => tdef.elements().iter().find(|ty| !supports(ty)),
+        ast::VariantType::Struct(fields) => fields
+            .iter()
+            .map(|field| &field.pair.type_ident)
+            .find(|ty| !supports(ty)),
+        ast::VariantType::Newtype(ty) => Some(ty).filter(|ty| !supports(ty)),
+    }
+}
+
+/// Whether the Rust type of `type_ident` implements `derive`, where `derives` tells whether a
+/// user-defined type derives it.
+fn supports(type_ident: &ast::TypeIdent, derive: Derive, derives: &dyn Fn(&str) -> bool) -> bool {
+    match type_ident {
+        ast::TypeIdent::BuiltIn(atom) => match atom {
+            ast::AtomType::F64 => !matches!(derive, Derive::Eq | Derive::Hash | Derive::Ord),
+            ast::AtomType::DateTime | ast::AtomType::Date => derive != Derive::Default,
+            _ => true,
+        },
+        ast::TypeIdent::List(inner) | ast::TypeIdent::Option(inner) => {
+            supports(inner, derive, derives)
+        }
+        ast::TypeIdent::Result(ok, err) => {
+            derive != Derive::Default
+                && supports(ok, derive, derives)
+                && supports(err, derive, derives)
+        }
+        ast::TypeIdent::Map(key, value) => {
+            !matches!(derive, Derive::Hash | Derive::PartialOrd | Derive::Ord)
+                && supports(key, derive, derives)
+                && supports(value, derive, derives)
+        }
+        ast::TypeIdent::Tuple(tdef) => tdef
+            .elements()
+            .iter()
+            .all(|ty| supports(ty, derive, derives)),
+        ast::TypeIdent::UserDefined(name) => derives(name),
+    }
+}
@@ -0,0 +1,86 @@
+//! Code generation for `flags` definitions, using the [`bitflags`](https://docs.rs/bitflags/1)
+//! macro re-exported by `humblegen_rt`.
+//!
+//! The entrypoint to this module is the `generate_flags_def` function.
+//! Flags are serialized as the list of the wire names of the contained flags, in the order of
+//! their definition, e.g. `["Read", "Write"]`. Deserialization rejects unknown names.
+
+use crate::{ast, WireCasing};
+use inflector::Inflector;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::{fmt_ident, fmt_opt_string, generate_rust_attrs};
+
+/// Generate rust code for a flags definition with flag names converted to `casing` on the wire.
+///
+/// The `i`-th flag is bit `i` of a `u64`, and each flag is an associated const named in
+/// `SCREAMING_SNAKE_CASE`, e.g. `Permissions::READ`.
+pub(crate) fn generate_flags_def(fdef: &ast::FlagsDef, casing: WireCasing) -> TokenStream {
+    let ident = fmt_ident(&fdef.name);
+    let doc_comment = fmt_opt_string(&fdef.doc_comment);
+    let rust_attrs = generate_rust_attrs(fdef.rust_attrs());
+    let consts: Vec<_> = fdef
+        .flags
+        .iter()
+        .map(|flag| format_ident!("{}", flag.name.to_screaming_snake_case()))
+        .collect();
+    let bits = (0..fdef.flags.len()).map(proc_macro2::Literal::usize_unsuffixed);
+    let flag_docs = fdef
+        .flags
+        .iter()
+        .map(|flag| fmt_opt_string(&flag.doc_comment));
+    let wire_names: Vec<_> = fdef
+        .flags
+        .iter()
+        .map(|flag| flag.wire_name(casing))
+        .collect();
+
+    quote! {
+        ::humblegen_rt::bitflags::bitflags! {
+            #[doc = #doc_comment]
+            #rust_attrs
+            pub struct #ident: u64 {
+                #(
+                    #[doc = #flag_docs]
+                    const #consts = 1 << #bits;
+                )*
+            }
+        }
+
+        impl ::std::default::Default for #ident {
+            fn default() -> Self {
+                Self::empty()
+            }
+        }
+
+        impl serde::Serialize for #ident {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::SerializeSeq;
+                let mut seq = serializer.serialize_seq(Some(self.bits().count_ones() as usize))?;
+                #(
+                    if self.contains(Self::#consts) {
+                        seq.serialize_element(#wire_names)?;
+                    }
+                )*
+                seq.end()
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #ident {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let names: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+                let mut flags = Self::empty();
+                for name in names {
+                    flags |= match name.as_str() {
+                        #(#wire_names => Self::#consts,)*
+                        name => {
+                            return Err(serde::de::Error::unknown_variant(name, &[#(#wire_names),*]))
+                        }
+                    };
+                }
+                Ok(flags)
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Code generation for mock implementations of the handler traits generated by `service_server`.
+//!
+//! The entrypoint to this module is the `generate_mocks` function.
+//! It generates a `pub struct Mock${ServiceName}` for each humblespec service, which implements
+//! the service's handler trait by answering requests with canned responses. Canned responses are
+//! either set in code or loaded from recorded JSON response bodies, which makes the mocks suitable
+//! for serving frontends in integration tests without a backend implementation.
+//!
+//! Every route is a public `humblegen_rt::mock::MockRoute` field, named like the route's handler
+//! trait method, e.g.:
+//! ```text
+//! let mock = MockMonsters::new();
+//! mock.get_monsters_id.respond_with(Ok(monster));
+//! Handler::Monsters(Arc::new(mock))
+//! ```
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::service_server::{handler_trait_decls, HandlerTraitDecl};
+use super::HandlerTraitStyle;
+
+/// Entrypoint for generating the mocks of *all* services of a humblespec.
+pub fn generate_mocks(
+    spec: &crate::ast::Spec,
+    handler_trait_style: HandlerTraitStyle,
+) -> TokenStream {
+    handler_trait_decls(spec, handler_trait_style)
+        .iter()
+        .map(|decl| generate_mock(decl, handler_trait_style))
+        .collect()
+}
+
+/// Generates the mock struct of a single service and its handler trait impl.
+fn generate_mock(decl: &HandlerTraitDecl, handler_trait_style: HandlerTraitStyle) -> TokenStream {
+    let trait_name = &decl.trait_name;
+    let mock_ident = format_ident!("Mock{}", trait_name);
+    let mock_comment = format!(
+        "Mock implementation of handler trait `{}`, answering requests with canned responses.",
+        trait_name
+    );
+
+    let fields = decl.fns.iter().map(|f| {
+        let ident = &f.ident;
+        let ret_type = &f.ret_type;
+        let field_comment = format!("Responses of route `{}`.", f.route_str);
+        quote! {
+            #[doc = #field_comment]
+            pub #ident: ::humblegen_rt::mock::MockRoute<#ret_type>
+        }
+    });
+    let field_inits = decl.fns.iter().map(|f| {
+        let ident = &f.ident;
+        let route_str = &f.route_str;
+        quote! { #ident: ::humblegen_rt::mock::MockRoute::new(#route_str) }
+    });
+    let recordings = decl.fns.iter().map(|f| {
+        let ident = &f.ident;
+        let name = ident.to_string();
+        quote! { recordings.apply(#name, &self.#ident)?; }
+    });
+
+    let trait_fns = decl.fns.iter().map(|f| {
+        let ident = &f.ident;
+        let fn_decl = &f.decl;
+        match handler_trait_style {
+            HandlerTraitStyle::AsyncTrait => quote! {
+                #fn_decl {
+                    self.#ident.next_response()
+                }
+            },
+            HandlerTraitStyle::BoxedFuture => quote! {
+                #fn_decl {
+                    let response = self.#ident.next_response();
+                    Box::pin(async move { response })
+                }
+            },
+        }
+    });
+    let authenticator_fn = if decl.uses_auth {
+        quote! {
+            type Principal = ();
+            fn authenticator(&self) -> &dyn ::humblegen_rt::auth::Authenticator<Principal = Self::Principal> {
+                &::humblegen_rt::mock::AllowAll
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let trait_attr = match handler_trait_style {
+        HandlerTraitStyle::AsyncTrait => quote! { #[humblegen_rt::async_trait(Sync)] },
+        HandlerTraitStyle::BoxedFuture => quote! {},
+    };
+
+    quote! {
+        #[doc = #mock_comment]
+        pub struct #mock_ident {
+            #(#fields,)*
+        }
+
+        impl Default for #mock_ident {
+            fn default() -> Self {
+                Self {
+                    #(#field_inits,)*
+                }
+            }
+        }
+
+        impl #mock_ident {
+            /// Creates a mock without canned responses.
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Sets the canned responses of routes to recorded JSON response bodies, keyed by
+            /// handler trait method, e.g. `{"get_monsters_id": {"Ok": {"name": "Godzilla"}}}`.
+            pub fn load_recordings(&self, recordings: &str) -> Result<(), ::humblegen_rt::mock::MockError> {
+                #[allow(unused_mut)]
+                let mut recordings = ::humblegen_rt::mock::Recordings::parse(recordings)?;
+                #(#recordings)*
+                recordings.finish()
+            }
+        }
+
+        #trait_attr
+        #[allow(unused_variables)]
+        impl #trait_name for #mock_ident {
+            type Context = ();
+            #authenticator_fn
+            #(#trait_fns)*
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Code generation for the response headers declared by routes,
+//! e.g. `GET /files/{id: str} -> FileMeta with header "ETag": str`.
+//!
+//! The entrypoint to this module is the `generate_response_headers` function.
+//! It generates a `pub struct ${ServiceName}${RouteName}ResponseHeaders` for each route declaring
+//! response headers, with one field per header, implementing
+//! `humblegen_rt::response_headers::ResponseHeaders`.
+//! Handlers return the struct along with the return value of the route in a
+//! `humblegen_rt::handler::WithHeaders`, which clients return as well.
+//!
+//! The structs are shared by client and server code and thus generated once per spec.
+
+use crate::ast;
+use inflector::cases::pascalcase::to_pascal_case;
+use inflector::cases::snakecase::to_snake_case;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::fmt_ident;
+use super::generate_type_ident;
+use super::service_server::handler_fn_ident;
+
+/// Entrypoint for generating the response headers of *all* routes of a humblespec.
+pub fn generate_response_headers(spec: &ast::Spec) -> TokenStream {
+    spec.iter()
+        .filter_map(ast::SpecItem::service_def)
+        .flat_map(|sdef| sdef.endpoints.iter().map(move |e| (sdef, &e.route)))
+        .filter(|(_, route)| !route.response_headers().is_empty())
+        .map(|(sdef, route)| generate_response_headers_struct(sdef, route))
+        .collect()
+}
+
+/// The struct of the response headers of `route` of service `sdef`.
+pub(crate) fn response_headers_ident(
+    sdef: &ast::ServiceDef,
+    route: &ast::ServiceRoute,
+) -> proc_macro2::Ident {
+    format_ident!(
+        "{}{}ResponseHeaders",
+        sdef.name.as_str(),
+        to_pascal_case(&handler_fn_ident(route).to_string())
+    )
+}
+
+/// The return type of `route` of service `sdef`, i.e., its return value wrapped in a
+/// `WithHeaders` if it declares response headers.
+pub(crate) fn generate_ret_type(sdef: &ast::ServiceDef, route: &ast::ServiceRoute) -> TokenStream {
+    let ret_type = generate_type_ident(route.return_type());
+    if route.response_headers().is_empty() {
+        ret_type
+    } else {
+        let headers_ident = response_headers_ident(sdef, route);
+        quote! { ::humblegen_rt::handler::WithHeaders<#ret_type, #headers_ident> }
+    }
+}
+
+fn generate_response_headers_struct(
+    sdef: &ast::ServiceDef,
+    route: &ast::ServiceRoute,
+) -> TokenStream {
+    let ident = response_headers_ident(sdef, route);
+    let doc_comment = format!(
+        "The response headers of `{} {}` of service `{}`.",
+        route.http_method_as_str(),
+        route.path(),
+        sdef.name
+    );
+    let headers = route.response_headers();
+    let field_idents: Vec<_> = headers
+        .iter()
+        .map(|h| fmt_ident(&to_snake_case(&h.name)))
+        .collect();
+    let field_docs = headers.iter().map(|h| format!("Header `{}`.", h.name));
+    let field_types = headers.iter().map(|h| generate_type_ident(&h.type_ident));
+    let names: Vec<_> = headers.iter().map(|h| &h.name).collect();
+    let (write_fns, read_fns): (Vec<_>, Vec<_>) = headers
+        .iter()
+        .map(|h| match h.type_ident {
+            ast::TypeIdent::Option(_) => (quote! { write_opt_header }, quote! { read_opt_header }),
+            _ => (quote! { write_header }, quote! { read_header }),
+        })
+        .unzip();
+
+    quote! {
+        #[doc = #doc_comment]
+        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+        pub struct #ident {
+            #(
+                #[doc = #field_docs]
+                pub #field_idents: #field_types,
+            )*
+        }
+
+        impl ::humblegen_rt::response_headers::ResponseHeaders for #ident {
+            fn write(
+                &self,
+                headers: &mut ::humblegen_rt::hyper::HeaderMap,
+            ) -> Result<(), ::humblegen_rt::response_headers::InvalidHeader> {
+                #(::humblegen_rt::response_headers::#write_fns(headers, #names, &self.#field_idents)?;)*
+                Ok(())
+            }
+
+            fn read(
+                headers: &::humblegen_rt::hyper::HeaderMap,
+            ) -> Result<Self, ::humblegen_rt::response_headers::InvalidHeader> {
+                Ok(Self {
+                    #(#field_idents: ::humblegen_rt::response_headers::#read_fns(headers, #names)?,)*
+                })
+            }
+        }
+    }
+}
@@ -16,7 +16,7 @@ fn rustfmt_path() -> io::Result<PathBuf> {
     #[cfg(feature = "which-rustfmt")]
     match which::which("rustfmt") {
         Ok(p) => Ok(p),
-        Err(e) => Err(io::Error::new(io::ErrorKind::Other, format!("{}", e))),
+        Err(e) => Err(io::Error::other(format!("{}", e))),
     }
     #[cfg(not(feature = "which-rustfmt"))]
     Err(io::Error::new(
@@ -32,7 +32,8 @@ pub(crate) fn rustfmt_2018_generated_string<'a>(source: &'a str) -> io::Result<C
 
     cmd.stdin(Stdio::piped()).stdout(Stdio::piped());
 
-    cmd.args(&["--edition", "2018"]);
+    // regardless of the platform's line endings, for identical output everywhere
+    cmd.args(["--edition", "2018", "--config", "newline_style=Unix"]);
 
     let mut child = cmd.spawn()?;
     let mut child_stdin = child.stdin.take().unwrap();
@@ -60,18 +61,12 @@ pub(crate) fn rustfmt_2018_generated_string<'a>(source: &'a str) -> io::Result<C
     match String::from_utf8(output) {
         Ok(bindings) => match status.code() {
             Some(0) => Ok(Cow::Owned(bindings)),
-            Some(2) => Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Rustfmt parsing errors.".to_string(),
-            )),
+            Some(2) => Err(io::Error::other("Rustfmt parsing errors.".to_string())),
             Some(3) => {
                 log::warn!("Rustfmt could not format some lines.");
                 Ok(Cow::Owned(bindings))
             }
-            _ => Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Internal rustfmt error".to_string(),
-            )),
+            _ => Err(io::Error::other("Internal rustfmt error".to_string())),
         },
         _ => Ok(Cow::Owned(source)),
     }
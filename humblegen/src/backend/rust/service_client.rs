@@ -0,0 +1,625 @@
+//! Code generation for [`reqwest`](https://docs.rs/reqwest)-based clients of humblespec `service`s.
+//!
+//! The entrypoint to this module is the `generate_clients` function.
+//! It generates a `pub struct ${ServiceName}Client` for each humblespec service, with one async
+//! method per route. The methods are named and take their parameters like the methods of the
+//! handler trait generated by `service_server`, so client and server code read alike.
+//! Routes with multipart bodies or bodies containing `bytes` additionally get a
+//! `*_with_progress` method reporting the progress of the upload, and paginated routes a `*_pages`
+//! method iterating their pages lazily. Routes annotated with `@idempotent` send a new
+//! `Idempotency-Key` per request, and get a `*_with_idempotency_key` method to retry a request.
+//!
+//! Clients send a `User-Agent` naming the crate they are compiled into and the hash of the spec
+//! they were generated from, for traffic attribution, and accept compressed responses. Further
+//! default headers are set once with a `ClientConfig`. Clients of specs declaring a `base_url` in
+//! their `meta` block implement `Default`, sending requests to it.
+//!
+//! A `config` module, generated by `generate_config`, holds the base URL, timeout and default
+//! headers of the clients of all services in one `Config`, which is created for one of the
+//! environments passed to the generator, e.g. from an environment variable, instead of
+//! hardcoding URLs into every consumer.
+//!
+//! The generated code requires the `client` feature of `humblegen-rt`.
+
+use crate::{ast, Environment};
+use inflector::cases::pascalcase::to_pascal_case;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::BTreeSet;
+
+use super::fmt_ident;
+use super::fmt_opt_string;
+use super::generate_type_ident;
+use super::service_server::{
+    handler_fn_ident, multipart_body_structs, multipart_part_kind, rt_wire_format,
+};
+
+/// Entrypoint for generating the clients of *all* services of a humblespec.
+pub fn generate_clients(spec: &ast::Spec) -> TokenStream {
+    let spec_hash = spec_hash(spec);
+    spec.iter()
+        .filter_map(|si| si.service_def())
+        .map(|sdef| generate_client(spec, sdef, &spec_hash))
+        .collect()
+}
+
+/// Generates the `config` module shared by the clients of the services of `spec`, with an
+/// `Environment` enum of `environments` if there are any.
+pub fn generate_config(spec: &ast::Spec, environments: &[Environment]) -> TokenStream {
+    if spec.iter().all(|si| si.service_def().is_none()) {
+        return quote! {};
+    }
+
+    let environment = if environments.is_empty() {
+        quote! {}
+    } else {
+        let variants: Vec<_> = environments
+            .iter()
+            .map(|env| format_ident!("{}", to_pascal_case(&env.name)))
+            .collect();
+        let names = environments.iter().map(|env| &env.name);
+        let base_urls = environments.iter().map(|env| &env.base_url);
+        let variant_comments = environments.iter().map(|env| {
+            format!(
+                "`{}`, with the services mounted at {}.",
+                env.name, env.base_url
+            )
+        });
+        quote! {
+            /// The environments the services are deployed to.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum Environment {
+                #(
+                    #[doc = #variant_comments]
+                    #variants,
+                )*
+            }
+
+            impl Environment {
+                /// All environments, in the order they were generated in.
+                pub const ALL: &'static [Environment] = &[#(Environment::#variants),*];
+
+                /// The name of the environment, e.g. `prod`.
+                pub fn name(self) -> &'static str {
+                    match self {
+                        #(Environment::#variants => #names,)*
+                    }
+                }
+
+                /// The environment named `name`, e.g. read from an environment variable.
+                pub fn from_name(name: &str) -> Option<Environment> {
+                    Self::ALL.iter().copied().find(|environment| environment.name() == name)
+                }
+
+                /// The URL the services are mounted at in the environment.
+                pub fn base_url(self) -> &'static str {
+                    match self {
+                        #(Environment::#variants => #base_urls,)*
+                    }
+                }
+            }
+
+            impl Config {
+                /// A config of clients of the services deployed to `environment`.
+                pub fn for_environment(environment: Environment) -> Self {
+                    let base_url = ::humblegen_rt::client::Url::parse(environment.base_url())
+                        .expect("the base URLs of environments are valid");
+                    Self::new(base_url)
+                }
+            }
+        }
+    };
+
+    let default_impl = match &spec.meta().base_url {
+        Some(base_url) => quote! {
+            impl Default for Config {
+                /// A config of clients of the services mounted at the `base_url` declared in the
+                /// `meta` block of the spec.
+                fn default() -> Self {
+                    let base_url = ::humblegen_rt::client::Url::parse(#base_url)
+                        .expect("the default base URL is valid");
+                    Self::new(base_url)
+                }
+            }
+        },
+        None => quote! {},
+    };
+
+    quote! {
+        /// The configuration of the clients of the services, see `Config`.
+        pub mod config {
+            /// The base URL, timeout and default headers of the clients of the services, which
+            /// create clients with `from_config`.
+            #[derive(Debug, Clone)]
+            pub struct Config {
+                /// The URL the services are mounted at.
+                pub base_url: ::humblegen_rt::client::Url,
+                /// The time after which requests fail, unlimited if `None`.
+                pub timeout: Option<::std::time::Duration>,
+                /// The headers sent with every request, e.g. an API key.
+                pub default_headers: Vec<(::humblegen_rt::client::HeaderName, ::humblegen_rt::client::HeaderValue)>,
+            }
+
+            impl Config {
+                /// A config of clients of the services mounted at `base_url`, without timeout
+                /// and default headers.
+                pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+                    Self {
+                        base_url,
+                        timeout: None,
+                        default_headers: Vec::new(),
+                    }
+                }
+
+                /// Fail requests that are not completed within `timeout`.
+                pub fn with_timeout(mut self, timeout: ::std::time::Duration) -> Self {
+                    self.timeout = Some(timeout);
+                    self
+                }
+
+                /// Send header `name` with `value` in every request.
+                pub fn with_default_header(
+                    mut self,
+                    name: ::humblegen_rt::client::HeaderName,
+                    value: ::humblegen_rt::client::HeaderValue,
+                ) -> Self {
+                    self.default_headers.push((name, value));
+                    self
+                }
+            }
+
+            #environment
+
+            #default_impl
+        }
+    }
+}
+
+/// A hash of `spec`, which identifies the spec a client was generated from.
+fn spec_hash(spec: &ast::Spec) -> String {
+    let json = serde_json::to_string(spec).expect("specs are serializable");
+    // FNV-1a, which unlike `DefaultHasher` is stable across Rust versions
+    let hash = json.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Generates the client struct of a single service.
+fn generate_client(spec: &ast::Spec, sdef: &ast::ServiceDef, spec_hash: &str) -> TokenStream {
+    let client_ident = format_ident!("{}Client", sdef.name.as_str());
+    let user_agent_suffix = format!(" ({}; humblespec {})", sdef.name, spec_hash);
+    let client_comment = format!("Client of service `{}`.", sdef.name);
+    let service_comment = fmt_opt_string(&sdef.doc_comment);
+    let methods = sdef
+        .endpoints
+        .iter()
+        .map(|e| generate_client_method(spec, sdef, e));
+    let (default_base_url, default_impl) = match &spec.meta().base_url {
+        Some(base_url) => (
+            quote! {
+                /// The URL the service is mounted at by default, declared in the `meta` block of the spec.
+                pub const DEFAULT_BASE_URL: &'static str = #base_url;
+            },
+            quote! {
+                impl Default for #client_ident {
+                    /// Creates a client of the service mounted at `DEFAULT_BASE_URL`.
+                    fn default() -> Self {
+                        let base_url = ::humblegen_rt::client::Url::parse(Self::DEFAULT_BASE_URL)
+                            .expect("the default base URL is valid");
+                        Self::new(base_url)
+                    }
+                }
+            },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
+    quote! {
+        #[doc = #client_comment]
+        #[doc = ""]
+        #[doc = #service_comment]
+        #[derive(Debug, Clone)]
+        pub struct #client_ident {
+            base_url: ::humblegen_rt::client::Url,
+            client: ::humblegen_rt::client::Client,
+        }
+
+        impl #client_ident {
+            /// The `User-Agent` of the client: the name and version of the crate it is compiled into,
+            /// the service and the hash of the spec the client was generated from.
+            pub const USER_AGENT: &'static str = concat!(
+                env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"), #user_agent_suffix
+            );
+
+            #default_base_url
+
+            /// Creates a client of the service mounted at `base_url`, e.g. `http://localhost:3000/api`.
+            pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+                Self::with_config(base_url, &Self::default_config())
+                    .expect("the default client config is valid")
+            }
+
+            /// The config of clients created with `new`, sending the `USER_AGENT`, to add default headers to.
+            pub fn default_config() -> ::humblegen_rt::client::ClientConfig {
+                ::humblegen_rt::client::ClientConfig::new(Self::USER_AGENT)
+            }
+
+            /// Like `new`, but sends the `User-Agent` and the default headers of `config` with every request.
+            pub fn with_config(
+                base_url: ::humblegen_rt::client::Url,
+                config: &::humblegen_rt::client::ClientConfig,
+            ) -> Result<Self, ::humblegen_rt::client::ClientError> {
+                Ok(Self::with_client(base_url, config.build_client()?))
+            }
+
+            /// Like `with_config`, but mounted at the base URL and with the timeout and default headers
+            /// of `config`, which is shared by the clients of all services.
+            pub fn from_config(config: &config::Config) -> Result<Self, ::humblegen_rt::client::ClientError> {
+                let mut client_config = Self::default_config();
+                if let Some(timeout) = config.timeout {
+                    client_config = client_config.with_timeout(timeout);
+                }
+                for (name, value) in &config.default_headers {
+                    client_config = client_config.with_default_header(name.clone(), value.clone());
+                }
+                Self::with_config(config.base_url.clone(), &client_config)
+            }
+
+            /// Like `new`, but sends requests using `client`, e.g., to set timeouts.
+            pub fn with_client(base_url: ::humblegen_rt::client::Url, client: ::humblegen_rt::client::Client) -> Self {
+                Self { base_url, client }
+            }
+
+            #(#methods)*
+        }
+
+        #default_impl
+    }
+}
+
+/// Generates the client method sending requests to the route of `endpoint`.
+fn generate_client_method(
+    spec: &ast::Spec,
+    sdef: &ast::ServiceDef,
+    endpoint: &ast::ServiceEndpoint,
+) -> TokenStream {
+    let route = &endpoint.route;
+    let wire_format = sdef.wire_format();
+    let fn_ident = handler_fn_ident(route);
+    let doc_comment = fmt_opt_string(&endpoint.doc_comment);
+
+    // parameters in the order of the handler trait's methods
+    let mut params = vec![];
+    if let Some(body) = route.request_body() {
+        let body_type = generate_type_ident(body);
+        params.push(quote! { post_body: #body_type });
+    }
+    if let Some(query) = route.query() {
+        let query_type = generate_type_ident(query);
+        params.push(quote! { query: Option<#query_type> });
+    }
+    // the path parameters and headers, passed on by the `*_pages` method
+    let mut forwarded = vec![];
+    let mut rest = None;
+    let segments = route.components().iter().filter_map(|c| match c {
+        ast::ServiceRouteComponent::Literal(literal) => Some(quote! { #literal.to_owned() }),
+        ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, type_ident }) => {
+            let ident = fmt_ident(name);
+            let ty = generate_type_ident(type_ident);
+            params.push(quote! { #ident: #ty });
+            forwarded.push((ident.clone(), ty));
+            Some(quote! { ::humblegen_rt::client::ToParam::to_param(&#ident) })
+        }
+        ast::ServiceRouteComponent::Rest(ast::FieldDefPair { name, type_ident }) => {
+            let ident = fmt_ident(name);
+            let ty = generate_type_ident(type_ident);
+            params.push(quote! { #ident: #ty });
+            forwarded.push((ident.clone(), ty));
+            rest = Some(ident);
+            None
+        }
+    });
+    let segments: Vec<_> = segments.collect();
+    // the rest of the path is split into segments, such that its slashes are not encoded
+    let segments = match rest {
+        Some(rest) => quote! {
+            &[vec![#(#segments),*], ::humblegen_rt::client::rest_segments(&#rest)].concat()
+        },
+        None => quote! { &[#(#segments),*] },
+    };
+    let headers = route.headers().iter().map(|h| {
+        let ident = fmt_ident(&inflector::cases::snakecase::to_snake_case(&h.name));
+        let ty = generate_type_ident(&h.type_ident);
+        params.push(quote! { #ident: #ty });
+        forwarded.push((ident.clone(), ty));
+        let name = &h.name;
+        match h.type_ident {
+            ast::TypeIdent::Option(_) => quote! {
+                let req = match &#ident {
+                    Some(value) => req.header(#name, ::humblegen_rt::client::ToParam::to_param(value)),
+                    None => req,
+                };
+            },
+            _ => quote! { let req = req.header(#name, ::humblegen_rt::client::ToParam::to_param(&#ident)); },
+        }
+    });
+    let mut headers: Vec<_> = headers.collect();
+    if endpoint.is_idempotent() {
+        headers.push(quote! {
+            let req = req.header(::humblegen_rt::idempotency::IDEMPOTENCY_KEY, idempotency_key.as_str());
+        });
+    }
+
+    let method = match route {
+        ast::ServiceRoute::Get { .. } => quote! { GET },
+        ast::ServiceRoute::Delete { .. } => quote! { DELETE },
+        ast::ServiceRoute::Post { .. } => quote! { POST },
+        ast::ServiceRoute::Put { .. } => quote! { PUT },
+        ast::ServiceRoute::Patch { .. } => quote! { PATCH },
+    };
+
+    // structs are sent `application/x-www-form-urlencoded`, primitives as the query itself
+    let (primitive_query, struct_query) = match route.query() {
+        Some(ast::TypeIdent::UserDefined(_)) => (
+            quote! {},
+            quote! {
+                let req = match &query {
+                    Some(query) => req.query(query),
+                    None => req,
+                };
+            },
+        ),
+        Some(_) => (
+            quote! {
+                if let Some(query) = &query {
+                    url.set_query(Some(&::humblegen_rt::client::ToParam::to_param(query)));
+                }
+            },
+            quote! {},
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
+    let wire_format = rt_wire_format(wire_format);
+    let body = match (
+        route.request_body(),
+        route.request_body_kind(),
+        &wire_format,
+    ) {
+        (Some(_), Some(ast::BodyKind::Multipart), _) => quote! {
+            let req = req.multipart(::humblegen_rt::client::ToMultipart::to_multipart(post_body)?.into_form());
+        },
+        (Some(_), _, None) => quote! { let req = req.json(&post_body); },
+        (Some(_), _, Some(format)) => {
+            quote! { let req = ::humblegen_rt::client::wire_format_body(req, #format, &post_body)?; }
+        }
+        (None, _, _) => quote! {},
+    };
+    let accept = wire_format.map(|format| {
+        quote! { let req = ::humblegen_rt::client::accept_wire_format(req, #format); }
+    });
+
+    let ret_type = super::response_headers::generate_ret_type(sdef, route);
+    let domain_error_statuses = domain_error_statuses(spec, route.return_type());
+    let (send, send_with_progress) = if route.response_headers().is_empty() {
+        (
+            quote! { ::humblegen_rt::client::send(req, &[#(#domain_error_statuses),*]).await },
+            quote! { ::humblegen_rt::client::send_with_progress(&self.client, req, &progress, &[#(#domain_error_statuses),*]).await },
+        )
+    } else {
+        (
+            quote! { ::humblegen_rt::client::send_with_headers(req, &[#(#domain_error_statuses),*]).await },
+            quote! {
+                let resp = ::humblegen_rt::client::execute_with_progress(&self.client, req, &progress).await?;
+                ::humblegen_rt::client::receive_with_headers(resp, &[#(#domain_error_statuses),*]).await
+            },
+        )
+    };
+
+    let request = quote! {
+        let mut url = ::humblegen_rt::client::route_url(&self.base_url, #segments)?;
+        #primitive_query
+        let req = self.client.request(::humblegen_rt::client::Method::#method, url);
+        #struct_query
+        #body
+        #accept
+        #(#headers)*
+    };
+    let new_idempotency_key = if endpoint.is_idempotent() {
+        quote! { let idempotency_key = ::humblegen_rt::client::new_idempotency_key(); }
+    } else {
+        quote! {}
+    };
+
+    let method_with_progress = if reports_upload_progress(spec, route) {
+        let fn_ident_with_progress = format_ident!("{}_with_progress", fn_ident);
+        let doc_comment_with_progress = format!(
+            "Like `{}`, but reports the progress of the upload of the body to `progress`.",
+            fn_ident
+        );
+        let body_with_progress = match route.request_body_kind() {
+            Some(ast::BodyKind::Multipart) => quote! {
+                let req = req.multipart(::humblegen_rt::client::ToMultipart::to_multipart(post_body)?.into_form_with_progress(&progress));
+            },
+            _ => body.clone(),
+        };
+        quote! {
+            #[doc = #doc_comment_with_progress]
+            #[allow(unused_mut)]
+            pub async fn #fn_ident_with_progress(
+                &self,
+                #(#params,)*
+                progress: impl Fn(::humblegen_rt::client::UploadProgress) + Send + Sync + 'static,
+            ) -> Result<#ret_type, ::humblegen_rt::client::ClientError> {
+                #new_idempotency_key
+                let progress = ::humblegen_rt::client::UploadProgressReporter::new(progress);
+                let mut url = ::humblegen_rt::client::route_url(&self.base_url, #segments)?;
+                #primitive_query
+                let req = self.client.request(::humblegen_rt::client::Method::#method, url);
+                #struct_query
+                #body_with_progress
+                #accept
+                #(#headers)*
+                #send_with_progress
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let method_pages = match route.query() {
+        Some(ast::TypeIdent::UserDefined(query)) if endpoint.is_paginated() => {
+            let fn_ident_pages = format_ident!("{}_pages", fn_ident);
+            let doc_comment_pages = format!(
+                "Like `{}`, but lazily fetches the pages starting at the cursor of `query`, \
+                 following their `next_cursor` until the last page.",
+                fn_ident
+            );
+            let query_type = generate_type_ident(&ast::TypeIdent::UserDefined(query.clone()));
+            let (idents, types): (Vec<_>, Vec<_>) = forwarded.iter().cloned().unzip();
+            let next_cursor = if route.response_headers().is_empty() {
+                quote! { |page: &#ret_type| page.next_cursor.clone() }
+            } else {
+                quote! { |page: &#ret_type| page.value.next_cursor.clone() }
+            };
+            quote! {
+                #[doc = #doc_comment_pages]
+                pub fn #fn_ident_pages(
+                    &self,
+                    query: #query_type,
+                    #(#idents: #types),*
+                ) -> impl ::humblegen_rt::client::Stream<Item = Result<#ret_type, ::humblegen_rt::client::ClientError>> + '_ {
+                    ::humblegen_rt::client::pages(
+                        query.cursor.clone(),
+                        move |cursor| {
+                            let query = #query_type { cursor, ..query.clone() };
+                            self.#fn_ident(Some(query), #(#idents.clone()),*)
+                        },
+                        #next_cursor,
+                    )
+                }
+            }
+        }
+        _ => quote! {},
+    };
+
+    // idempotent routes send a new key, or the key of the request to retry
+    let (method_sending, method_with_idempotency_key) = if endpoint.is_idempotent() {
+        let fn_ident_with_key = format_ident!("{}_with_idempotency_key", fn_ident);
+        let doc_comment_with_key = format!(
+            "Like `{}`, but sends `idempotency_key` instead of a new key, e.g. the key of a \
+             request to retry, which the server answers like the request with the key before.",
+            fn_ident
+        );
+        let args = route
+            .request_body()
+            .map(|_| quote! { post_body })
+            .into_iter()
+            .chain(route.query().as_ref().map(|_| quote! { query }))
+            .chain(forwarded.iter().map(|(ident, _)| quote! { #ident }));
+        (
+            quote! {
+                #[doc = #doc_comment]
+                pub async fn #fn_ident(&self, #(#params),*) -> Result<#ret_type, ::humblegen_rt::client::ClientError> {
+                    self.#fn_ident_with_key(::humblegen_rt::client::new_idempotency_key(), #(#args),*).await
+                }
+            },
+            quote! {
+                #[doc = #doc_comment_with_key]
+                #[allow(unused_mut)]
+                pub async fn #fn_ident_with_key(&self, idempotency_key: String, #(#params),*) -> Result<#ret_type, ::humblegen_rt::client::ClientError> {
+                    #request
+                    #send
+                }
+            },
+        )
+    } else {
+        (
+            quote! {
+                #[doc = #doc_comment]
+                #[allow(unused_mut)]
+                pub async fn #fn_ident(&self, #(#params),*) -> Result<#ret_type, ::humblegen_rt::client::ClientError> {
+                    #request
+                    #send
+                }
+            },
+            quote! {},
+        )
+    };
+
+    quote! {
+        #method_sending
+
+        #method_with_idempotency_key
+
+        #method_with_progress
+
+        #method_pages
+    }
+}
+
+/// Whether the client of `route` gets a `*_with_progress` method, i.e., the route has a
+/// multipart body or a body that can contain `bytes`, which are typically large.
+fn reports_upload_progress(spec: &ast::Spec, route: &ast::ServiceRoute) -> bool {
+    match route.request_body() {
+        Some(body) => {
+            route.request_body_kind() == Some(ast::BodyKind::Multipart) || spec.contains_bytes(body)
+        }
+        None => false,
+    }
+}
+
+/// The HTTP status codes the server uses for the domain error of a `result[T][E]` return type,
+/// i.e., the `@status` codes of enum `E`.
+fn domain_error_statuses(spec: &ast::Spec, ret: &ast::TypeIdent) -> Vec<u16> {
+    let err_name = match ret {
+        ast::TypeIdent::Result(_, err) => match err.as_ref() {
+            ast::TypeIdent::UserDefined(name) => name,
+            _ => return vec![],
+        },
+        _ => return vec![],
+    };
+    spec.iter()
+        .find_map(|si| match si {
+            ast::SpecItem::EnumDef(edef) if &edef.name == err_name => Some(edef.http_statuses()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Generate `ToMultipart` impls for all structs used as `multipart[...]` request bodies,
+/// the client-side counterpart of `service_server::generate_multipart_impls`.
+///
+/// Structs named in `external_types` are skipped, their impls are generated along with them.
+pub fn generate_multipart_impls(
+    spec: &ast::Spec,
+    external_types: &BTreeSet<String>,
+) -> TokenStream {
+    let mut out = TokenStream::new();
+    for sdef in multipart_body_structs(spec, external_types) {
+        let ident = fmt_ident(&sdef.name);
+        let parts = sdef.fields.iter().map(|field| {
+            let field_ident = fmt_ident(&field.pair.name);
+            let part_name = &field.pair.name;
+            let kind = multipart_part_kind(&field.value_type());
+            if kind == "json" {
+                quote! { let form = form.json(#part_name, &self.#field_ident)?; }
+            } else {
+                quote! { let form = form.#kind(#part_name, self.#field_ident); }
+            }
+        });
+        out.extend(quote! {
+            impl ::humblegen_rt::client::ToMultipart for #ident {
+                fn to_multipart(
+                    self,
+                ) -> Result<::humblegen_rt::client::MultipartForm, ::humblegen_rt::client::ClientError> {
+                    let form = ::humblegen_rt::client::MultipartForm::new();
+                    #(#parts)*
+                    Ok(form)
+                }
+            }
+        });
+    }
+    out
+}
@@ -28,30 +28,79 @@
 //!
 
 use crate::ast;
+use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
+use std::collections::BTreeSet;
 
+use super::fmt_ident;
 use super::fmt_opt_string;
 use super::generate_type_ident;
+use super::HandlerTraitStyle;
 
 /// Lowered representation of an `ast::ServiceDef`.
 struct Service {
     trait_name: proc_macro2::Ident,
     trait_comment: String,
     routes_factory_name: proc_macro2::Ident,
+    /// The literal segments shared by all routes, see `humblegen_rt::server::Service`.
+    route_prefix: String,
     service_routes: Vec<ServiceRoute>,
+    /// Whether any route requires auth, which adds a `Principal` to the handler trait.
+    uses_auth: bool,
+    /// The `humblegen_rt::cors::Cors` policy of a service annotated with `@cors`.
+    cors: Option<TokenStream>,
+    /// The `humblegen_rt::wire_format::WireFormat`s besides JSON of a service annotated with
+    /// `@format(...)`, among which the formats of its requests are negotiated.
+    wire_formats: Option<TokenStream>,
 }
 
 /// Lowered representation of an `ast::ServiceRoute`.
 struct ServiceRoute {
     doc_comment: TokenStream,
+    requires_auth: bool,
     traitfn_ident: proc_macro2::Ident,
+    route_str: String,
+    /// The path of the route, e.g. `/monsters/{id}`.
+    route_path: String,
     hyper_method: TokenStream,
     components: Vec<ServiceRouteComponent>,
     query_type: Option<TokenStream>,
     query_deser_fn: TokenStream,
     post_body_type: Option<TokenStream>,
+    post_body_deser: TokenStream,
+    headers: Vec<HeaderParam>,
     ret_type: TokenStream,
+    /// Statements determining the `request_format` and `response_format` of `@format(...)` services.
+    wire_format_def: TokenStream,
+    /// Function converting the handler response into a hyper response.
+    response_conversion_fn: TokenStream,
+    /// Leading arguments of `response_conversion_fn`.
+    response_conversion_args: TokenStream,
+    /// The `humblegen_rt::metrics::Slo` of the route as `Option`, from its `@slo` annotation.
+    slo: TokenStream,
+    /// The feature flag gating the route, from its `@flag` annotation.
+    flag: Option<String>,
+    /// The maximum body size as `Option<u64>`, from its `@max_body_size` annotation or else the
+    /// default of the `limits` passed to the routes factory.
+    max_body_size: TokenStream,
+    /// The timeout as `Option<Duration>`, from its `@timeout` annotation or else the default of
+    /// the `limits` passed to the routes factory.
+    timeout: TokenStream,
+    /// Whether requests are deduplicated by their idempotency key, from its `@idempotent`
+    /// annotation.
+    idempotent: bool,
+    /// Whether responses are compressed as accepted by the client, unless the route is annotated
+    /// with `@uncompressed`.
+    compressed: bool,
+}
+
+/// Lowered representation of an `ast::HeaderParam`.
+struct HeaderParam {
+    spec_name: String,
+    rust_var_ident: proc_macro2::Ident,
+    rust_var_type: TokenStream,
+    deser_fn: TokenStream,
 }
 
 /// Lowered representation of an `ast::ServiceRouteComponent`.
@@ -63,36 +112,64 @@ enum ServiceRouteComponent {
         spec_arg_name: String,
         rust_var_ident: proc_macro2::Ident,
         rust_var_type: TokenStream,
-        url_regex_str: String,
+        /// Whether the param captures the rest of the path, see `ast::ServiceRouteComponent::Rest`.
+        rest: bool,
     },
 }
 
 /// Entrypoint for generate *all* services of a humblespec.
-pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
-    all_services: I,
+pub fn generate_services(
+    spec: &ast::Spec,
+    handler_trait_style: HandlerTraitStyle,
+    tracing_spans: bool,
 ) -> TokenStream {
-    let all_services = lower_all_services(all_services);
+    let all_services = lower_all_services(
+        spec.iter().filter_map(|si| si.service_def()),
+        &error_status_enums(spec),
+    );
 
     if all_services.is_empty() {
         return quote! {};
     }
 
-    let mut out = TokenStream::new();
+    // `Principal` is a generic of `Handler` iff at least one service requires auth,
+    // since unused generic parameters are an error.
+    let (principal_generic_decl, principal_generic) = if all_services.iter().any(|s| s.uses_auth) {
+        (
+            quote! { , Principal: Send + Sync + 'static },
+            quote! { , Principal },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let mut out = super::spec_endpoint::generate_spec_consts(spec);
+
+    // responses carry the version of the API, if declared in the `meta` block
+    let middleware = match &spec.meta().version {
+        Some(version) => {
+            out.extend(quote! {
+                /// The version of the API, sent in the `Api-Version` header of all responses.
+                pub const API_VERSION: &str = #version;
+            });
+            quote! { vec![Arc::new(::humblegen_rt::middleware::ApiVersion(API_VERSION))] }
+        }
+        None => quote! { vec![] },
+    };
 
     // generate imports and server builder
     out.extend(quote! {
         #[allow(unused_imports)]
         use ::humblegen_rt::deser_helpers::{
-            deser_post_data, deser_query_primitive, deser_query_serde_urlencoded, deser_param,
+            deser_header, deser_opt_header, deser_post_data, deser_query_primitive,
+            deser_query_serde_urlencoded, deser_param,
         };
         #[allow(unused_imports)]
         use ::humblegen_rt::service_protocol::ErrorResponse;
         #[allow(unused_imports)]
-        pub use ::humblegen_rt::handler::{self, HandlerResponse as Response, ServiceError};
+        pub use ::humblegen_rt::handler::{self, HandlerFuture, HandlerResponse as Response, ServiceError, WithHeaders};
         #[allow(unused_imports)]
-        use ::humblegen_rt::regexset_map::RegexSetMap;
-        #[allow(unused_imports)]
-        use ::humblegen_rt::server::{self, handler_response_to_hyper_response, Route, Service};
+        use ::humblegen_rt::server::{self, handler_response_to_hyper_response, Router, Service};
         #[allow(unused_imports)]
         use ::std::sync::Arc;
         use std::net::SocketAddr;
@@ -104,11 +181,77 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
         #[derive(Debug)]
         pub struct Builder {
             services: Vec<Service>,
+            metrics: Option<Arc<::humblegen_rt::metrics::Metrics>>,
+            feature_flags: Option<Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+            pretty_json: bool,
+            recorder: Option<Arc<::humblegen_rt::recording::Recorder>>,
+            middleware: Vec<Arc<dyn ::humblegen_rt::middleware::Middleware>>,
+            shutdown: ::humblegen_rt::shutdown::ShutdownHandle,
+            drain_delay: std::time::Duration,
+            limits: ::humblegen_rt::limits::Limits,
+            idempotency_store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
         }
 
         impl Builder {
             pub fn new() -> Self {
-                Self { services: vec![] }
+                Self { services: vec![], metrics: None, feature_flags: None, pretty_json: false, recorder: None, middleware: #middleware, shutdown: Default::default(), drain_delay: std::time::Duration::from_secs(0), limits: Default::default(), idempotency_store: Arc::new(::humblegen_rt::idempotency::MemoryStore::default()) }
+            }
+
+            /// Records the latencies of requests to the routes of handlers `add`ed afterwards
+            /// in `metrics`.
+            pub fn with_metrics(mut self, metrics: Arc<::humblegen_rt::metrics::Metrics>) -> Self {
+                self.metrics = Some(metrics);
+                self
+            }
+
+            /// Consults `feature_flags` whether the routes annotated with `@flag("name")` of
+            /// handlers `add`ed afterwards are enabled. Without, these routes are hidden.
+            pub fn with_feature_flags(mut self, feature_flags: Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>) -> Self {
+                self.feature_flags = Some(feature_flags);
+                self
+            }
+
+            /// Runs `middleware` around the requests to the routes of handlers `add`ed afterwards,
+            /// inside the middleware passed before.
+            pub fn with_middleware(mut self, middleware: Arc<dyn ::humblegen_rt::middleware::Middleware>) -> Self {
+                self.middleware.push(middleware);
+                self
+            }
+
+            /// Answers requests to the routes of handlers `add`ed afterwards with a body larger than
+            /// `max_body_size` bytes with 413 Payload Too Large, unless annotated with `@max_body_size`.
+            pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+                self.limits.max_body_size = Some(max_body_size);
+                self
+            }
+
+            /// Answers requests to the routes of handlers `add`ed afterwards with 504 Gateway Timeout
+            /// if not handled within `timeout`, unless annotated with `@timeout`.
+            pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+                self.limits.timeout = Some(timeout);
+                self
+            }
+
+            /// Deduplicates the requests to the routes annotated with `@idempotent` of handlers `add`ed
+            /// afterwards using `store`, e.g. one shared by all instances of the server. Defaults to a
+            /// `MemoryStore`.
+            pub fn with_idempotency_store(mut self, store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>) -> Self {
+                self.idempotency_store = store;
+                self
+            }
+
+            /// Whether responses of handlers `add`ed afterwards are pretty-printed JSON, e.g. for
+            /// debugging. Defaults to compact JSON, unless a request has query parameter `pretty=1`.
+            pub fn with_pretty_json(mut self, pretty_json: bool) -> Self {
+                self.pretty_json = pretty_json;
+                self
+            }
+
+            /// Records all requests served by `listen_and_run_forever` and their responses in
+            /// `recorder`, to be replayed using `humblegen_rt::recording::replay`.
+            pub fn with_recorder(mut self, recorder: Arc<::humblegen_rt::recording::Recorder>) -> Self {
+                self.recorder = Some(recorder);
+                self
             }
 
             /// Mounts `handler` at URL path prefix `root`.
@@ -122,29 +265,85 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
             /// and `root="/api"` will expose
             /// * handler method `fn bar() -> i32` at `/api/bar` and
             /// * handler method `fn baz() -> String` at `/api/baz`
-            pub fn add<Context: Default + Sized + Send + Sync>(mut self, root: &str, handler: Handler<Context>) -> Self {
+            pub fn add<Context: Default + Sized + Send + Sync #principal_generic_decl>(mut self, root: &str, handler: Handler<Context #principal_generic>) -> Self {
                 if !root.starts_with('/') {
                     panic!("root must start with \"/\"")
                 } else  if root.ends_with('/') {
                     panic!("root must not end with \"/\"")
                 }
 
-                let routes: Vec<Route> = handler.into_routes();
-                let routes = RegexSetMap::new(routes).unwrap();
-                self.services.push(Service((
-                    humblegen_rt::regex::Regex::new(&format!(r"^(?P<root>{})(?P<suffix>/.*)", root))
-                        .unwrap(),
-                    routes,
-                )));
+                self.services.push(Service {
+                    root: root.to_owned(),
+                    route_prefix: handler.route_prefix().to_owned(),
+                    router: handler.into_router(root, self.metrics.as_deref(), self.feature_flags.as_ref(), &self.middleware, self.limits, &self.idempotency_store),
+                    pretty_json: self.pretty_json,
+                });
+                self
+            }
+
+            /// Serves the metrics passed to `with_metrics` in the Prometheus text format at `/metrics`.
+            pub fn with_metrics_endpoint(mut self) -> Self {
+                let metrics = self.metrics.clone().expect("with_metrics must be called before with_metrics_endpoint");
+                self.services.push(::humblegen_rt::metrics::service(metrics));
                 self
             }
 
+            /// Serves liveness and readiness probes at `/health/live` and `/health/ready`, the latter
+            /// responding with 503 Service Unavailable once the server is shutting down.
+            pub fn with_health_endpoints(mut self) -> Self {
+                self.services.push(::humblegen_rt::shutdown::health_service(self.shutdown.clone()));
+                self
+            }
+
+            /// Returns the handle to shut down the server gracefully: `listen_and_run_forever`
+            /// stops accepting connections and returns once the requests in flight completed.
+            pub fn shutdown_handle(&self) -> ::humblegen_rt::shutdown::ShutdownHandle {
+                self.shutdown.clone()
+            }
+
+            /// Shuts down the server gracefully on SIGTERM or Ctrl-C.
+            /// Must be called within a tokio runtime.
+            pub fn with_shutdown_signal(self) -> Self {
+                self.shutdown.shutdown_on_signal();
+                self
+            }
+
+            /// Keeps accepting connections for `drain_delay` after the shutdown started, while
+            /// the readiness probe already fails, such that load balancers stop routing requests
+            /// to the server before it stops accepting connections. Defaults to zero.
+            pub fn with_drain_delay(mut self, drain_delay: std::time::Duration) -> Self {
+                self.drain_delay = drain_delay;
+                self
+            }
+
+            /// Serves the resolved humblespec of the services as JSON document at `/__spec`,
+            /// and an HTML index of their routes at `/__spec/index.html`.
+            pub fn with_spec_endpoint(mut self) -> Self {
+                self.services.push(::humblegen_rt::spec_endpoint::service(SPEC_JSON, SPEC_INDEX_HTML));
+                self
+            }
+
+            /// Returns the previously `add`ed handlers as services, to be served using
+            /// `humblegen_rt::server::handle_request`, e.g. in a custom hyper server or in tests.
+            pub fn into_services(self) -> Vec<Service> {
+                self.services
+            }
+
             /// Starts an HTTP server bound to address `addr` and serves incoming requests using
-            /// the previously `add`ed handlers.
+            /// the previously `add`ed handlers, until shut down using `shutdown_handle`.
             pub async fn listen_and_run_forever(self, addr: &SocketAddr) -> humblegen_rt::anyhow::Result<()> {
-                use humblegen_rt::anyhow::Context;
-                let services = RegexSetMap::new(self.services).context("invalid service configuration")?;
-                server::listen_and_run_forever(services, addr).await
+                server::listen_and_run_forever(self.services, addr, self.recorder, self.shutdown, self.drain_delay).await
+            }
+
+            /// Like `listen_and_run_forever`, but serves the connections accepted by `incoming`,
+            /// e.g. to terminate TLS using `humblegen_rt::tls::TlsAcceptor::bind`.
+            pub async fn serve<I>(self, incoming: I) -> humblegen_rt::anyhow::Result<()>
+            where
+                I: ::humblegen_rt::hyper::server::accept::Accept,
+                I::Conn: ::humblegen_rt::tokio::io::AsyncRead + ::humblegen_rt::tokio::io::AsyncWrite + Unpin + Send + 'static,
+                I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+            {
+                server::serve(incoming, self.services, self.recorder, self.shutdown, self.drain_delay).await
             }
         }
 
@@ -154,13 +353,23 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
     let handler_enum_variants: Vec<_> = all_services
         .iter()
         .map(|s| {
-            let Service { trait_name, .. } = s;
-            quote! {
-                #trait_name(Arc<dyn #trait_name<Context=Context> + Send + Sync>)
+            let Service {
+                trait_name,
+                uses_auth,
+                ..
+            } = s;
+            if *uses_auth {
+                quote! {
+                    #trait_name(Arc<dyn #trait_name<Context=Context, Principal=Principal> + Send + Sync>)
+                }
+            } else {
+                quote! {
+                    #trait_name(Arc<dyn #trait_name<Context=Context> + Send + Sync>)
+                }
             }
         })
         .collect();
-    let handler_into_routes_match_arms: Vec<_> = all_services
+    let handler_into_router_match_arms: Vec<_> = all_services
         .iter()
         .map(|s| {
             let Service {
@@ -169,7 +378,21 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
                 ..
             } = s;
             quote! {
-                Handler::#trait_name(h) => #routes_factory_name(h)
+                Handler::#trait_name(h) => #routes_factory_name(h, root, metrics, feature_flags, middleware, limits, idempotency_store)
+            }
+        })
+        .collect();
+
+    let handler_route_prefix_arms: Vec<_> = all_services
+        .iter()
+        .map(|s| {
+            let Service {
+                trait_name,
+                route_prefix,
+                ..
+            } = s;
+            quote! {
+                Handler::#trait_name(_) => #route_prefix
             }
         })
         .collect();
@@ -189,19 +412,25 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
         /// Wrapper enum with one variant for each service defined in the humble spec.
         /// Used to pass instantiated handler trait objects to `Builder::add`.
         #[allow(dead_code)]
-        pub enum Handler<Context: Default + Sized + Send + Sync + 'static> {
+        pub enum Handler<Context: Default + Sized + Send + Sync + 'static #principal_generic_decl> {
             #(#handler_enum_variants,)*
         }
 
-        impl<Context: Default + Sized + Send + Sync + 'static> Handler<Context> {
-            fn into_routes(self) -> Vec<Route> {
+        impl<Context: Default + Sized + Send + Sync + 'static #principal_generic_decl> Handler<Context #principal_generic> {
+            fn into_router(self, root: &str, metrics: Option<&::humblegen_rt::metrics::Metrics>, feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>, middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>], limits: ::humblegen_rt::limits::Limits, idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>) -> Box<Router> {
                 match self {
-                    #(#handler_into_routes_match_arms,)*
+                    #(#handler_into_router_match_arms,)*
+                }
+            }
+
+            fn route_prefix(&self) -> &'static str {
+                match self {
+                    #(#handler_route_prefix_arms,)*
                 }
             }
         }
 
-        impl<Context: Default + Sized + Send + Sync + 'static> std::fmt::Debug for Handler<Context> {
+        impl<Context: Default + Sized + Send + Sync + 'static #principal_generic_decl> std::fmt::Debug for Handler<Context #principal_generic> {
             fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 match self {
                     #(#handler_debug_arms,)*
@@ -213,7 +442,11 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
     });
 
     // generate code for the service definitions
-    out.extend(all_services.iter().map(generate_service).flatten());
+    out.extend(
+        all_services
+            .iter()
+            .flat_map(|s| generate_service(s, handler_trait_style, tracing_spans)),
+    );
 
     out
 }
@@ -221,41 +454,23 @@ pub fn generate_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
 /// generates rust code for a single a single service, which includes:
 ///
 /// - a handler trait definition
-/// - a routes factory function (called by Handler::into_routes)
-fn generate_service(service: &Service) -> TokenStream {
+/// - a routes factory function (called by Handler::into_router)
+///
+/// With `tracing_spans`, the requests to each route run within a span named
+/// `${ServiceName}.${traitfn}`, with the path params and the status code of the response as fields.
+fn generate_service(
+    service: &Service,
+    handler_trait_style: HandlerTraitStyle,
+    tracing_spans: bool,
+) -> TokenStream {
     let service_routes = &service.service_routes;
     let trait_comment = &service.trait_comment;
 
     let (trait_fns_with_comment, trait_fns_without_comment): (Vec<_>, Vec<_>) = service_routes
         .iter()
         .map(|r| {
-            let ServiceRoute {
-                traitfn_ident,
-                post_body_type,
-                query_type,
-                components,
-                ret_type,
-                doc_comment,
-                ..
-            } = r;
-            let mut param_list = vec![];
-            param_list.push(quote! {&self});
-            param_list.push(quote! {ctx: Self::Context});
-            param_list.extend(post_body_type.iter().map(|t| quote! { post_body: #t }));
-            param_list.extend(query_type.iter().map(|t| quote! { query: Option<#t> }));
-            param_list.extend(components.iter().filter_map(|c| match c {
-                ServiceRouteComponent::Literal { .. } => None,
-                ServiceRouteComponent::Param {
-                    rust_var_ident,
-                    rust_var_type,
-                    ..
-                } => Some(quote! { #rust_var_ident : #rust_var_type }),
-            }));
-            let param_list = quote! { #(#param_list),* };
-
-            let decl_without_comment = quote! {
-                async fn #traitfn_ident (#param_list) -> Response<#ret_type>
-            };
+            let ServiceRoute { doc_comment, .. } = r;
+            let decl_without_comment = generate_trait_fn_decl(r, handler_trait_style);
             let decl_as_doc_comment =
                 // render with a trailing `{}` so that rustfmt 1.4.12 doesn't crash with
                 // thread 'main' panicked at 'internal error: entered unreachable code', src/tools/rustfmt/src/visitor.rs:372:18
@@ -269,19 +484,44 @@ fn generate_service(service: &Service) -> TokenStream {
         })
         .unzip();
     let trait_name = &service.trait_name;
-    let trait_def_interceptor_fn = quote! {
-        type Context: Default + Sized + Send + Sync;
-        async fn intercept_handler_pre(&self,
-            _req: &hyper::Request<hyper::Body>,
-        ) -> Result<Self::Context, ServiceError> {
-            Ok(Self::Context::default())
+    let trait_def_authenticator_fn = if service.uses_auth {
+        quote! {
+            type Principal: Send + Sync;
+            fn authenticator(&self) -> &dyn ::humblegen_rt::auth::Authenticator<Principal = Self::Principal>;
         }
+    } else {
+        quote! {}
+    };
+    let (trait_def_interceptor_fn, trait_attr) = match handler_trait_style {
+        HandlerTraitStyle::AsyncTrait => (
+            quote! {
+                type Context: Default + Sized + Send + Sync;
+                async fn intercept_handler_pre(&self,
+                    _req: &hyper::Request<hyper::Body>,
+                ) -> Result<Self::Context, ServiceError> {
+                    Ok(Self::Context::default())
+                }
+            },
+            quote! { #[humblegen_rt::async_trait(Sync)] },
+        ),
+        HandlerTraitStyle::BoxedFuture => (
+            quote! {
+                type Context: Default + Sized + Send + Sync;
+                fn intercept_handler_pre<'a>(&'a self,
+                    _req: &'a hyper::Request<hyper::Body>,
+                ) -> HandlerFuture<'a, Result<Self::Context, ServiceError>> {
+                    Box::pin(async { Ok(Self::Context::default()) })
+                }
+            },
+            quote! {},
+        ),
     };
     let trait_def_as_doc_comment = {
         let d = quote! {
-            #[humblegen_rt::async_trait(Sync)]
+            #trait_attr
             pub trait #trait_name {
                 #trait_def_interceptor_fn
+                #trait_def_authenticator_fn
                 #(#trait_fns_without_comment ;)*
             }
         };
@@ -290,42 +530,106 @@ fn generate_service(service: &Service) -> TokenStream {
     let trait_def = quote! {
         #[doc = #trait_comment]
         #[doc = #trait_def_as_doc_comment ]
-        #[humblegen_rt::async_trait(Sync)]
+        #trait_attr
         pub trait #trait_name {
             #trait_def_interceptor_fn
+            #trait_def_authenticator_fn
             #(#trait_fns_with_comment ;)*
         }
     };
 
-    let routes = service_routes.iter().map(|r| {
+    // Routes whose literal segments coincide with another route's parameters must take precedence,
+    // so order literals before params at each position, and params before the rest of the path.
+    // The sort is stable, so otherwise equivalent routes keep their definition order.
+    let mut sorted_routes: Vec<(usize, &ServiceRoute)> =
+        service_routes.iter().enumerate().collect();
+    sorted_routes.sort_by_key(|(_, r)| {
+        r.components
+            .iter()
+            .map(|c| match c {
+                ServiceRouteComponent::Literal { .. } => 0,
+                ServiceRouteComponent::Param { rest: false, .. } => 1,
+                ServiceRouteComponent::Param { rest: true, .. } => 2,
+            })
+            .collect::<Vec<_>>()
+    });
+    let max_segments = service_routes
+        .iter()
+        .map(|r| r.components.len())
+        .max()
+        .unwrap_or(0);
+    // Paths with more segments than any route are truncated to one more segment than that, which
+    // only routes capturing the rest of the path match.
+    let has_rest = service_routes.iter().any(|r| {
+        r.components
+            .iter()
+            .any(|c| matches!(c, ServiceRouteComponent::Param { rest: true, .. }))
+    });
+    let (num_segment_slots, split_path_segments) = if has_rest {
+        (max_segments + 1, quote! { split_path_segments_with_rest })
+    } else {
+        (max_segments, quote! { split_path_segments })
+    };
+
+    // histograms in definition order, the route arms index them in sorted order
+    let route_histograms = service_routes.iter().map(|r| {
+        let hyper_method = &r.hyper_method;
+        let route_path = &r.route_path;
+        let slo = &r.slo;
+        quote! {
+            metrics.map(|m| m.route_histogram(#hyper_method.as_str(), &format!("{}{}", root, #route_path), #slo))
+        }
+    });
+
+    let route_arms = sorted_routes.into_iter().map(|(route_idx, r)| {
         let ServiceRoute {
             traitfn_ident,
             hyper_method,
+            route_str,
+            response_conversion_fn,
+            timeout,
+            idempotent,
+            compressed,
             ..
         } = r;
 
-        let regex_str = r
-            .components
-            .iter()
-            .map(|c| match c {
-                ServiceRouteComponent::Literal { spec } => format!("/{}", spec),
-                ServiceRouteComponent::Param {
-                    spec_arg_name,
-                    url_regex_str,
-                    ..
-                } => format!("/(?P<{}>{})", spec_arg_name, url_regex_str,),
-            })
-            .collect::<Vec<_>>()
-            .join("");
-        let regex_str = format!("^{}$", regex_str);
+        // disabled routes are hidden or unavailable before anything else happens
+        let flag_check = r.flag.as_ref().map(|flag| quote! {
+            match ::humblegen_rt::feature_flags::flag_state(feature_flags.as_deref(), #flag, &req) {
+                ::humblegen_rt::feature_flags::FlagState::Enabled => {}
+                ::humblegen_rt::feature_flags::FlagState::Hidden => return None,
+                ::humblegen_rt::feature_flags::FlagState::Unavailable => {
+                    return Some((#route_str, ::humblegen_rt::feature_flags::unavailable(#flag)))
+                }
+            }
+        });
 
+        // slice pattern matching the path segments, binding params by their spec name
+        let segment_patterns = r.components.iter().map(|c| match c {
+            ServiceRouteComponent::Literal { spec } => quote! { #spec },
+            ServiceRouteComponent::Param { rust_var_ident, rest: false, .. } => quote! { #rust_var_ident },
+            ServiceRouteComponent::Param { rust_var_ident, rest: true, .. } => quote! { #rust_var_ident @ .. },
+        });
+        // the rest of the path has at least one segment
+        let rest_guard = r.components.iter().filter_map(|c| match c {
+            ServiceRouteComponent::Param { rust_var_ident, rest: true, .. } => Some(quote! { !#rust_var_ident.is_empty() && }),
+            _ => None,
+        });
         // post body
         let post_body_var = r.post_body_type.iter().map(|_| {
                 quote! { post_body }
         }).collect::<Vec<_>>();
+        let post_body_deser = &r.post_body_deser;
+        let wire_format_def = &r.wire_format_def;
+        let response_conversion_args = &r.response_conversion_args;
+        let max_body_size = &r.max_body_size;
         let post_body_def = r.post_body_type.as_ref().map(|pbt| quote!{
+            ::humblegen_rt::compression::decompress_request(&mut req)?;
+            if let Some(max_body_size) = #max_body_size {
+                ::humblegen_rt::limits::limit_body(&mut req, max_body_size).await?;
+            }
             let post_body: #pbt =
-            deser_post_data(req.body_mut()).await?;
+            #post_body_deser.await?;
         });
 
         // query
@@ -341,114 +645,453 @@ fn generate_service(service: &Service) -> TokenStream {
         });
 
         // route params
-        let (route_param_vars, route_param_parse_stmts): (Vec<TokenStream>, Vec<TokenStream>) = r.components.iter().filter_map(|c| match c {
+        let (route_param_vars, route_param_parse_stmts): (Vec<TokenStream>, Vec<TokenStream>) = r.components.iter().enumerate().filter_map(|(idx, c)| match c {
             ServiceRouteComponent::Literal { .. } => None,
             ServiceRouteComponent::Param {
                 spec_arg_name,
                 rust_var_ident,
                 rust_var_type,
-                ..
+                rest: false,
             } => Some((
                 quote! { #rust_var_ident },
-                quote! { let #rust_var_ident: Result<#rust_var_type, ErrorResponse> = deser_param( #spec_arg_name,  &captures[ #spec_arg_name ]); },
+                quote! { let #rust_var_ident: Result<#rust_var_type, ErrorResponse> = deser_param( #spec_arg_name, #rust_var_ident); },
+            )),
+            ServiceRouteComponent::Param {
+                spec_arg_name,
+                rust_var_ident,
+                rust_var_type,
+                rest: true,
+            } => Some((
+                quote! { #rust_var_ident },
+                quote! { let #rust_var_ident: Result<#rust_var_type, ErrorResponse> = deser_param( #spec_arg_name, server::path_rest(&req.uri().path()[suffix_start..], #idx)); },
             )),
         }).unzip();
 
+        // headers
+        let (header_vars, header_defs): (Vec<TokenStream>, Vec<TokenStream>) = r.headers.iter().map(|h| {
+            let HeaderParam {
+                spec_name,
+                rust_var_ident,
+                rust_var_type,
+                deser_fn,
+            } = h;
+            (
+                quote! { #rust_var_ident },
+                quote! { let #rust_var_ident: #rust_var_type = #deser_fn(req.headers(), #spec_name)?; },
+            )
+        }).unzip();
+
+        // principal
+        let (principal_var, principal_def) = if r.requires_auth {
+            (
+                Some(quote! { principal }),
+                quote! {
+                    let principal = {
+                        let span = tracing::error_span!("authenticator");
+                        handler.authenticator().authenticate(req.headers()).instrument(span).await
+                            .map_err(::humblegen_rt::service_protocol::ServiceError::from)
+                            .map_err(|e| {
+                                tracing::debug!(service_error = ?format!("{:?}", e), "authenticator rejected request");
+                                e
+                            })
+                            .map_err(|e| e.to_error_response())?
+                    };
+                },
+            )
+        } else {
+            (None, quote! {})
+        };
+
         let mut arg_list = Vec::new();
+        arg_list.extend(&principal_var);
         arg_list.extend(&post_body_var);
         arg_list.extend(&query_var);
         arg_list.extend(&route_param_vars);
+        arg_list.extend(&header_vars);
+
+
+        let service_name = trait_name.to_string();
+        let handler_name = traitfn_ident.to_string();
+
+        let negotiate = service.wire_formats.as_ref().map(|formats| quote! {
+            let negotiated = ::humblegen_rt::wire_format::Negotiated::negotiate(#formats, req.headers());
+            req.extensions_mut().insert(negotiated);
+        });
 
+        // the key is read before `req` moves into the middleware, repeated requests are answered
+        // before running the middleware
+        let (idempotency_key, deduplicate) = if *idempotent {
+            (
+                quote! { let idempotency_key = ::humblegen_rt::idempotency::key(&ROUTE, &req); },
+                quote! {
+                    let dispatch = ::humblegen_rt::idempotency::deduplicate(Arc::clone(&idempotency_store), idempotency_key, dispatch);
+                },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        // the encoding is negotiated before `req` moves into the middleware, responses are
+        // compressed after deduplication, which stores them uncompressed
+        let (accept_encoding, compress) = if *compressed {
+            (
+                quote! { let accept_encoding = ::humblegen_rt::compression::Encoding::negotiate(req.headers()); },
+                quote! { let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch); },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        // the span of the route, with the path params as they appear in the path
+        let (span_def, trace_route) = if tracing_spans {
+            let span_name = format!("{}.{}", service_name, handler_name);
+            let param_fields = r.components.iter().enumerate().filter_map(|(idx, c)| match c {
+                ServiceRouteComponent::Literal { .. } => None,
+                ServiceRouteComponent::Param { rust_var_ident, rest: false, .. } => Some(quote! { #rust_var_ident = #rust_var_ident }),
+                ServiceRouteComponent::Param { rust_var_ident, rest: true, .. } => Some(quote! {
+                    #rust_var_ident = server::path_rest(&req.uri().path()[suffix_start..], #idx)
+                }),
+            });
+            (
+                quote! {
+                    let span = tracing::error_span!(#span_name, #(#param_fields,)* status = tracing::field::Empty);
+                },
+                quote! { server::trace_route(span, dispatch) },
+            )
+        } else {
+            (quote! {}, quote! { dispatch })
+        };
 
         let route_param_parse_stmts = route_param_parse_stmts.into_iter();
         let route_param_vars2 = route_param_vars.iter();
         let route_param_vars = route_param_vars.iter();
         let arg_list = arg_list.into_iter();
         quote! {
-            {
+            [#(#segment_patterns),*] if #(#rest_guard)* *req.method() == #hyper_method => {
+                tracing::debug!(route = #route_str, "route matched");
+                #flag_check
+                // The segments borrow from `req`, thus parse the route params in place
+                // before moving `req` into the middleware and the async block.
+                // Inside the block, `?` the results and return the param deserialization error.
+                #span_def
+                #(#route_param_parse_stmts);*
                 let handler = Arc::clone(&handler);
-                Route{
-                    method: #hyper_method,
-                    regex: ::humblegen_rt::regex::Regex::new(#regex_str).unwrap(),
-                    dispatcher: Box::new(
-                        move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                        captures| {
-                            let handler = Arc::clone(&handler);
-                            // We cannot move the regex captures into the async closure, thus do the parsing
-                            // of route params outside of the closure and move the parsing results into it.
-                            // Inside the closure, `?` the results and return the param deserialization error.
-                            #(#route_param_parse_stmts);*
-                            Box::pin(async move {
-                                // Invoke the interceptor
-                                use ::humblegen_rt::service_protocol::ToErrorResponse;
-                                let ctx = {
-                                    let span = tracing::error_span!("interceptor");
-                                    handler.intercept_handler_pre(&req).instrument(span).await
-                                        .map_err(::humblegen_rt::service_protocol::ServiceError::from)
-                                        .map_err(|e| {
-                                            tracing::debug!(service_error = ?format!("{:?}", e), "interceptor rejected request");
-                                            e
-                                        })
-                                        .map_err(|e| e.to_error_response())?
-                                };
-
-                                // deserialize only after we have invoked the interceptor
-                                // => interceptor can implement some DoS protection
-                                #(let #route_param_vars = #route_param_vars2?;)*
-                                #query_def
-                                #post_body_def
-
-                                drop(req); // free some memory
-
-                                // Invoke handler if interceptor doesn't return a ServiceError
-                                {
-                                    let span = tracing::error_span!("handler");
-                                    Ok(handler_response_to_hyper_response(handler.#traitfn_ident( ctx, #(#arg_list),* ).instrument(span).await))
-                                }
+                let histogram = route_histograms[#route_idx].clone();
+                static ROUTE: ::humblegen_rt::middleware::RouteInfo = ::humblegen_rt::middleware::RouteInfo {
+                    service: #service_name,
+                    route: #route_str,
+                    handler: #handler_name,
+                };
+                #idempotency_key
+                #accept_encoding
+                #negotiate
+                let dispatch = ::humblegen_rt::middleware::run(&middleware, &ROUTE, req, move |mut req| Box::pin(async move {
+                    // Invoke the interceptor
+                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                    let ctx = {
+                        let span = tracing::error_span!("interceptor");
+                        handler.intercept_handler_pre(&req).instrument(span).await
+                            .map_err(::humblegen_rt::service_protocol::ServiceError::from)
+                            .map_err(|e| {
+                                tracing::debug!(service_error = ?format!("{:?}", e), "interceptor rejected request");
+                                e
                             })
-                        }
-                    ),
-                }
+                            .map_err(|e| e.to_error_response())?
+                    };
+
+                    // Invoke the authenticator if the route requires auth
+                    #principal_def
+
+                    // deserialize only after we have invoked the interceptor
+                    // => interceptor can implement some DoS protection
+                    #(let #route_param_vars = #route_param_vars2?;)*
+                    #query_def
+                    #(#header_defs)*
+                    #wire_format_def
+                    #post_body_def
+
+                    drop(req); // free some memory
+
+                    // Invoke handler if interceptor doesn't return a ServiceError
+                    {
+                        let span = tracing::error_span!("handler");
+                        Ok(#response_conversion_fn(#response_conversion_args handler.#traitfn_ident( ctx, #(#arg_list),* ).instrument(span).await))
+                    }
+                }));
+                let dispatch = ::humblegen_rt::limits::with_timeout(#timeout, dispatch);
+                #deduplicate
+                #compress
+                Some((#route_str, ::humblegen_rt::metrics::observe_latency(histogram, #trace_route)))
             }
         }
     });
 
+    // CORS preflight requests are answered for any path, and the responses of the routes carry
+    // the CORS headers of their origin
+    let (cors_preflight, cors_headers) = match &service.cors {
+        Some(cors) => (
+            quote! {
+                static CORS: ::humblegen_rt::cors::Cors = #cors;
+                if let Some(preflight) = ::humblegen_rt::cors::preflight(&CORS, &req) {
+                    return Some(("OPTIONS", preflight));
+                }
+                let origin = req.headers().get(::humblegen_rt::hyper::header::ORIGIN).cloned();
+            },
+            quote! {
+                routed.map(|(route, dispatch)| (route, ::humblegen_rt::cors::with_headers(&CORS, origin, dispatch)))
+            },
+        ),
+        None => (quote! {}, quote! { routed }),
+    };
+
     let routes_factory_name = &service.routes_factory_name;
+    let (routes_factory_generics, handler_trait_bound) = if service.uses_auth {
+        (
+            quote! { <Context: Default + Sized + Send + Sync + 'static, Principal: Send + Sync + 'static> },
+            quote! { #trait_name<Context=Context, Principal=Principal> },
+        )
+    } else {
+        (
+            quote! { <Context: Default + Sized + Send + Sync + 'static> },
+            quote! { #trait_name<Context=Context> },
+        )
+    };
     quote! {
         #trait_def
 
         #[allow(unused_variables)]
         #[allow(unused_mut)]
         #[allow(non_snake_case)]
-        #[allow(clippy::trivial_regex)]
-        #[allow(clippy::single_char_pattern)]
-        fn #routes_factory_name<Context: Default + Sized + Send + Sync + 'static>(handler: Arc<dyn #trait_name<Context=Context> + Send + Sync>) -> Vec<Route> {
-            vec![#(#routes),*]
+        #[allow(unreachable_patterns)]
+        fn #routes_factory_name #routes_factory_generics(handler: Arc<dyn #handler_trait_bound + Send + Sync>, root: &str, metrics: Option<&::humblegen_rt::metrics::Metrics>, feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>, middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>], limits: ::humblegen_rt::limits::Limits, idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>) -> Box<Router> {
+            let idempotency_store = Arc::clone(idempotency_store);
+            let route_histograms: Vec<Option<Arc<::humblegen_rt::metrics::LatencyHistogram>>> = vec![#(#route_histograms),*];
+            let feature_flags = feature_flags.cloned();
+            let middleware: Arc<[Arc<dyn ::humblegen_rt::middleware::Middleware>]> = middleware.into();
+            Box::new(move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>, suffix_start: usize| {
+                #cors_preflight
+                let mut segments = [""; #num_segment_slots];
+                let num_segments = server::#split_path_segments(&req.uri().path()[suffix_start..], &mut segments)?;
+                let routed = match &segments[..num_segments] {
+                    #(#route_arms)*
+                    _ => None,
+                };
+                #cors_headers
+            })
         }
 
     }
 }
 
+/// Generates the declaration of the handler trait method of route `r`, without trailing `;` or body.
+fn generate_trait_fn_decl(r: &ServiceRoute, handler_trait_style: HandlerTraitStyle) -> TokenStream {
+    let ServiceRoute {
+        requires_auth,
+        traitfn_ident,
+        post_body_type,
+        query_type,
+        components,
+        headers,
+        ret_type,
+        ..
+    } = r;
+    let mut param_list = vec![];
+    param_list.push(match handler_trait_style {
+        HandlerTraitStyle::AsyncTrait => quote! {&self},
+        HandlerTraitStyle::BoxedFuture => quote! {&'a self},
+    });
+    param_list.push(quote! {ctx: Self::Context});
+    if *requires_auth {
+        param_list.push(quote! {principal: Self::Principal});
+    }
+    param_list.extend(post_body_type.iter().map(|t| quote! { post_body: #t }));
+    param_list.extend(query_type.iter().map(|t| quote! { query: Option<#t> }));
+    param_list.extend(components.iter().filter_map(|c| match c {
+        ServiceRouteComponent::Literal { .. } => None,
+        ServiceRouteComponent::Param {
+            rust_var_ident,
+            rust_var_type,
+            ..
+        } => Some(quote! { #rust_var_ident : #rust_var_type }),
+    }));
+    param_list.extend(headers.iter().map(|h| {
+        let HeaderParam {
+            rust_var_ident,
+            rust_var_type,
+            ..
+        } = h;
+        quote! { #rust_var_ident : #rust_var_type }
+    }));
+    let param_list = quote! { #(#param_list),* };
+
+    match handler_trait_style {
+        HandlerTraitStyle::AsyncTrait => quote! {
+            async fn #traitfn_ident (#param_list) -> Response<#ret_type>
+        },
+        HandlerTraitStyle::BoxedFuture => quote! {
+            fn #traitfn_ident<'a> (#param_list) -> HandlerFuture<'a, Response<#ret_type>>
+        },
+    }
+}
+
+/// The handler trait of a service, as seen by code implementing it (see `mock_server`).
+pub(crate) struct HandlerTraitDecl {
+    pub(crate) trait_name: proc_macro2::Ident,
+    /// Whether the trait has a `Principal` type and an `authenticator` method.
+    pub(crate) uses_auth: bool,
+    pub(crate) fns: Vec<HandlerTraitFnDecl>,
+}
+
+/// A handler trait method, see `HandlerTraitDecl`.
+pub(crate) struct HandlerTraitFnDecl {
+    pub(crate) ident: proc_macro2::Ident,
+    /// The route, e.g. `GET /monsters/{id}`.
+    pub(crate) route_str: String,
+    pub(crate) ret_type: TokenStream,
+    /// The method declaration, without trailing `;` or body.
+    pub(crate) decl: TokenStream,
+}
+
+/// The handler traits of all services of a humblespec.
+pub(crate) fn handler_trait_decls(
+    spec: &ast::Spec,
+    handler_trait_style: HandlerTraitStyle,
+) -> Vec<HandlerTraitDecl> {
+    lower_all_services(
+        spec.iter().filter_map(|si| si.service_def()),
+        &error_status_enums(spec),
+    )
+    .into_iter()
+    .map(|service| HandlerTraitDecl {
+        fns: service
+            .service_routes
+            .iter()
+            .map(|r| HandlerTraitFnDecl {
+                ident: r.traitfn_ident.clone(),
+                route_str: r.route_str.clone(),
+                ret_type: r.ret_type.clone(),
+                decl: generate_trait_fn_decl(r, handler_trait_style),
+            })
+            .collect(),
+        trait_name: service.trait_name,
+        uses_auth: service.uses_auth,
+    })
+    .collect()
+}
+
 /// lower the `ast::ServiceDefs` into `struct Service`
 fn lower_all_services<'a, I: Iterator<Item = &'a ast::ServiceDef>>(
     all_services: I,
+    error_status_enums: &BTreeSet<&str>,
 ) -> Vec<Service> {
     all_services
-        .map(|sdef| Service {
-            trait_name: format_ident!("{}", sdef.name),
-            trait_comment: fmt_opt_string(&sdef.doc_comment).to_string(),
-            routes_factory_name: format_ident!("routes_{}", sdef.name),
-            service_routes: sdef
+        .map(|sdef| {
+            let service_routes: Vec<_> = sdef
                 .endpoints
                 .iter()
-                .map(|e| lower_service_route(&e))
-                .collect(),
+                .map(|e| {
+                    lower_service_route(
+                        sdef,
+                        e,
+                        sdef.endpoint_requires_auth(e),
+                        !sdef.wire_formats().is_empty(),
+                        error_status_enums,
+                    )
+                })
+                .collect();
+            Service {
+                trait_name: fmt_ident(&sdef.name),
+                trait_comment: fmt_opt_string(&sdef.doc_comment).to_string(),
+                routes_factory_name: format_ident!("routes_{}", sdef.name.as_str()),
+                route_prefix: route_prefix(sdef),
+                uses_auth: service_routes.iter().any(|r| r.requires_auth),
+                cors: lower_cors(sdef),
+                wire_formats: lower_wire_formats(sdef),
+                service_routes,
+            }
         })
         .collect()
 }
 
+/// The CORS policy of `sdef`, if annotated with `@cors`, allowing the methods of its routes and
+/// exposing the response headers they declare.
+fn lower_cors(sdef: &ast::ServiceDef) -> Option<TokenStream> {
+    let origins = sdef.cors_origins()?;
+    let methods = sdef
+        .endpoints
+        .iter()
+        .map(|e| e.route.http_method_as_str())
+        .unique();
+    let expose_headers = sdef
+        .endpoints
+        .iter()
+        .flat_map(|e| e.route.response_headers())
+        .map(|h| h.name.as_str())
+        .unique();
+    Some(quote! {
+        ::humblegen_rt::cors::Cors {
+            origins: &[#(#origins),*],
+            methods: &[#(#methods),*],
+            expose_headers: &[#(#expose_headers),*],
+        }
+    })
+}
+
+fn lower_wire_formats(sdef: &ast::ServiceDef) -> Option<TokenStream> {
+    let formats: Vec<_> = sdef
+        .wire_formats()
+        .into_iter()
+        .filter_map(rt_wire_format)
+        .collect();
+    if formats.is_empty() {
+        return None;
+    }
+    Some(quote! { &[#(#formats),*] })
+}
+
+/// The literal segments that all routes of `sdef` start with, e.g. `/v2` for the services of
+/// `version` blocks.
+fn route_prefix(sdef: &ast::ServiceDef) -> String {
+    let literals = |endpoint: &ast::ServiceEndpoint| -> Vec<String> {
+        endpoint
+            .route
+            .components()
+            .iter()
+            .map_while(|c| match c {
+                ast::ServiceRouteComponent::Literal(literal) => Some(literal.clone()),
+                ast::ServiceRouteComponent::Variable(_) | ast::ServiceRouteComponent::Rest(_) => {
+                    None
+                }
+            })
+            .collect()
+    };
+    let mut endpoints = sdef.endpoints.iter();
+    let mut prefix = match endpoints.next() {
+        Some(endpoint) => literals(endpoint),
+        None => return String::new(),
+    };
+    for endpoint in endpoints {
+        let shared = prefix
+            .iter()
+            .zip(literals(endpoint))
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        prefix.truncate(shared);
+    }
+    prefix
+        .iter()
+        .map(|literal| format!("/{}", literal))
+        .collect()
+}
+
 /// Helper function for lowering an `ast::ServiceEndpoint` into a `ServiceRoute`.
-fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
+fn lower_service_route(
+    sdef: &ast::ServiceDef,
+    endpoint: &ast::ServiceEndpoint,
+    requires_auth: bool,
+    negotiates_wire_format: bool,
+    error_status_enums: &BTreeSet<&str>,
+) -> ServiceRoute {
     let components = endpoint
         .route
         .components()
@@ -458,14 +1101,21 @@ fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
                 ServiceRouteComponent::Literal { spec: spec.clone() }
             }
             ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, type_ident }) => {
-                let rust_var_ident = format_ident!("{}", name);
+                let rust_var_ident = fmt_ident(name);
                 let rust_var_type = generate_type_ident(type_ident);
-                let url_regex_str = r"[^/]+".to_owned();
                 ServiceRouteComponent::Param {
                     spec_arg_name: name.clone(),
-                    url_regex_str,
                     rust_var_ident,
                     rust_var_type,
+                    rest: false,
+                }
+            }
+            ast::ServiceRouteComponent::Rest(ast::FieldDefPair { name, type_ident }) => {
+                ServiceRouteComponent::Param {
+                    spec_arg_name: name.clone(),
+                    rust_var_ident: fmt_ident(name),
+                    rust_var_type: generate_type_ident(type_ident),
+                    rest: true,
                 }
             }
         })
@@ -479,7 +1129,82 @@ fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
         ast::ServiceRoute::Patch { body, .. } => Some(generate_type_ident(body)),
     };
 
-    let ret_type = generate_type_ident(endpoint.route.return_type());
+    let post_body_deser = match (endpoint.route.request_body_kind(), negotiates_wire_format) {
+        (Some(ast::BodyKind::Multipart), _) => {
+            quote! { ::humblegen_rt::multipart::deser_multipart(&mut req) }
+        }
+        (_, false) => quote! { deser_post_data(req.body_mut()) },
+        (_, true) => {
+            quote! { ::humblegen_rt::wire_format::deser_post_data(request_format, req.body_mut()) }
+        }
+    };
+
+    let headers = endpoint
+        .route
+        .headers()
+        .iter()
+        .map(|h| HeaderParam {
+            spec_name: h.name.clone(),
+            rust_var_ident: fmt_ident(&inflector::cases::snakecase::to_snake_case(&h.name)),
+            rust_var_type: generate_type_ident(&h.type_ident),
+            deser_fn: match h.type_ident {
+                ast::TypeIdent::Option(_) => quote! { deser_opt_header },
+                _ => quote! { deser_header },
+            },
+        })
+        .collect();
+
+    let ret_type = super::response_headers::generate_ret_type(sdef, &endpoint.route);
+
+    let has_error_status = match endpoint.route.return_type() {
+        ast::TypeIdent::Result(_, err) => match err.as_ref() {
+            ast::TypeIdent::UserDefined(name) => error_status_enums.contains(name.as_str()),
+            _ => false,
+        },
+        _ => false,
+    };
+    let returns_empty = matches!(
+        endpoint.route.return_type(),
+        ast::TypeIdent::BuiltIn(ast::AtomType::Empty)
+    );
+    // negotiated before running the middleware, which may replace the negotiation
+    let wire_format_def = if negotiates_wire_format {
+        quote! {
+            let ::humblegen_rt::wire_format::Negotiated { request: request_format, response: response_format } =
+                ::humblegen_rt::wire_format::Negotiated::of(&req);
+        }
+    } else {
+        quote! {}
+    };
+    let (response_conversion_fn, response_conversion_args) =
+        match (negotiates_wire_format, has_error_status) {
+            _ if returns_empty => (
+                quote! { server::handler_no_content_response_to_hyper_response },
+                quote! {},
+            ),
+            (false, true) => (
+                quote! { server::handler_result_response_to_hyper_response },
+                quote! {},
+            ),
+            (false, false) => (quote! { handler_response_to_hyper_response }, quote! {}),
+            (true, true) => (
+                quote! { ::humblegen_rt::wire_format::handler_result_response_to_hyper_response },
+                quote! { response_format, },
+            ),
+            (true, false) => (
+                quote! { ::humblegen_rt::wire_format::handler_response_to_hyper_response },
+                quote! { response_format, },
+            ),
+        };
+    let (response_conversion_fn, response_conversion_args) =
+        if endpoint.route.response_headers().is_empty() {
+            (response_conversion_fn, response_conversion_args)
+        } else {
+            (
+                quote! { server::with_response_headers },
+                quote! { |r| #response_conversion_fn(#response_conversion_args r), },
+            )
+        };
 
     let (query_type, query_deser_fn) = endpoint
         .route
@@ -494,31 +1219,52 @@ fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
         })
         .unwrap_or((None, quote! {}));
 
-    let traitfn_name_stem = &endpoint
+    let hyper_method = match &endpoint.route {
+        ast::ServiceRoute::Get { .. } => quote!(::humblegen_rt::hyper::Method::GET),
+        ast::ServiceRoute::Delete { .. } => quote!(::humblegen_rt::hyper::Method::DELETE),
+        ast::ServiceRoute::Post { .. } => quote!(::humblegen_rt::hyper::Method::POST),
+        ast::ServiceRoute::Put { .. } => quote!(::humblegen_rt::hyper::Method::PUT),
+        ast::ServiceRoute::Patch { .. } => quote!(::humblegen_rt::hyper::Method::PATCH),
+    };
+    let traitfn_ident = handler_fn_ident(&endpoint.route);
+
+    let route_path = endpoint
         .route
         .components()
         .iter()
         .map(|c| match c {
-            ast::ServiceRouteComponent::Literal(l) => l.clone(),
-            ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, .. }) => name.clone(),
+            ast::ServiceRouteComponent::Literal(spec) => format!("/{}", spec),
+            ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, .. }) => {
+                format!("/{{{}}}", name)
+            }
+            ast::ServiceRouteComponent::Rest(ast::FieldDefPair { name, .. }) => {
+                format!("/{{*{}}}", name)
+            }
         })
-        .collect::<Vec<_>>()
-        .join("_");
+        .join("");
+    let route_str = format!("{} {}", endpoint.route.http_method_as_str(), route_path);
 
-    let (traitfn_name_prefix, hyper_method) = match &endpoint.route {
-        ast::ServiceRoute::Get { .. } => ("get", quote!(::humblegen_rt::hyper::Method::GET)),
-        ast::ServiceRoute::Delete { .. } => {
-            ("delete", quote!(::humblegen_rt::hyper::Method::DELETE))
-        }
-        ast::ServiceRoute::Post { .. } => ("post", quote!(::humblegen_rt::hyper::Method::POST)),
-        ast::ServiceRoute::Put { .. } => ("put", quote!(::humblegen_rt::hyper::Method::PUT)),
-        ast::ServiceRoute::Patch { .. } => ("patch", quote!(::humblegen_rt::hyper::Method::PATCH)),
+    let slo = match endpoint.slo() {
+        Some(ast::Slo {
+            percentile,
+            target_ms,
+        }) => quote! {
+            Some(::humblegen_rt::metrics::Slo {
+                percentile: #percentile,
+                target: ::std::time::Duration::from_millis(#target_ms),
+            })
+        },
+        None => quote! { None },
+    };
+
+    let max_body_size = match endpoint.max_body_size() {
+        Some(max_body_size) => quote! { Some(#max_body_size) },
+        None => quote! { limits.max_body_size },
+    };
+    let timeout = match endpoint.timeout_ms() {
+        Some(timeout_ms) => quote! { Some(::std::time::Duration::from_millis(#timeout_ms)) },
+        None => quote! { limits.timeout },
     };
-    let traitfn_ident = format_ident!(
-        "{}_{}",
-        traitfn_name_prefix,
-        inflector::cases::snakecase::to_snake_case(&traitfn_name_stem)
-    );
 
     let doc_comment = {
         let doc_comment = fmt_opt_string(&endpoint.doc_comment);
@@ -527,16 +1273,213 @@ fn lower_service_route(endpoint: &ast::ServiceEndpoint) -> ServiceRoute {
 
     ServiceRoute {
         doc_comment,
+        requires_auth,
         traitfn_ident,
+        route_str,
+        route_path,
         hyper_method,
         components,
         query_type,
         query_deser_fn,
         post_body_type,
+        post_body_deser,
+        headers,
         ret_type,
+        wire_format_def,
+        response_conversion_fn,
+        response_conversion_args,
+        slo,
+        flag: endpoint.flag().map(str::to_owned),
+        max_body_size,
+        timeout,
+        idempotent: endpoint.is_idempotent(),
+        compressed: !endpoint.is_uncompressed(),
     }
 }
 
+/// The `humblegen_rt::wire_format::WireFormat` of services annotated with `@format(...)`,
+/// besides JSON, or `None` for JSON only.
+pub(crate) fn rt_wire_format(wire_format: ast::WireFormat) -> Option<TokenStream> {
+    match wire_format {
+        ast::WireFormat::Json => None,
+        ast::WireFormat::Cbor => Some(quote! { ::humblegen_rt::wire_format::WireFormat::Cbor }),
+        ast::WireFormat::Msgpack => {
+            Some(quote! { ::humblegen_rt::wire_format::WireFormat::Msgpack })
+        }
+    }
+}
+
+/// The name of the handler trait method of `route`, e.g. `get_monsters_id` for `GET /monsters/{id}`.
+pub(crate) fn handler_fn_ident(route: &ast::ServiceRoute) -> proc_macro2::Ident {
+    let name_stem = route
+        .components()
+        .iter()
+        .map(|c| match c {
+            ast::ServiceRouteComponent::Literal(l) => l.clone(),
+            ast::ServiceRouteComponent::Variable(ast::FieldDefPair { name, .. })
+            | ast::ServiceRouteComponent::Rest(ast::FieldDefPair { name, .. }) => name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("_");
+
+    let name_prefix = match route {
+        ast::ServiceRoute::Get { .. } => "get",
+        ast::ServiceRoute::Delete { .. } => "delete",
+        ast::ServiceRoute::Post { .. } => "post",
+        ast::ServiceRoute::Put { .. } => "put",
+        ast::ServiceRoute::Patch { .. } => "patch",
+    };
+    format_ident!(
+        "{}_{}",
+        name_prefix,
+        inflector::cases::snakecase::to_snake_case(&name_stem)
+    )
+}
+
+/// Names of all enums with variants annotated with `@status(code)`.
+fn error_status_enums(spec: &ast::Spec) -> BTreeSet<&str> {
+    spec.iter()
+        .filter_map(|si| match si {
+            ast::SpecItem::EnumDef(edef) if !edef.http_statuses().is_empty() => {
+                Some(edef.name.as_str())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generate `ErrorStatus` impls for all enums with variants annotated with `@status(code)`.
+///
+/// Variants without annotation keep the regular status code 200.
+/// Enums named in `external_types` are skipped, their impls are generated along with them.
+pub fn generate_error_status_impls(
+    spec: &ast::Spec,
+    external_types: &BTreeSet<String>,
+) -> TokenStream {
+    let mut out = TokenStream::new();
+    for si in spec.iter() {
+        let edef = match si {
+            ast::SpecItem::EnumDef(edef)
+                if !edef.http_statuses().is_empty()
+                    && !external_types.contains(edef.name.as_str()) =>
+            {
+                edef
+            }
+            _ => continue,
+        };
+        let ident = fmt_ident(&edef.name);
+        let arms = edef.variants.iter().map(|variant| {
+            let variant_ident = fmt_ident(&variant.name);
+            let status = variant.http_status().unwrap_or(200);
+            let pattern = match variant.variant_type {
+                ast::VariantType::Simple => quote! { #ident::#variant_ident },
+                ast::VariantType::Tuple(_) | ast::VariantType::Newtype(_) => {
+                    quote! { #ident::#variant_ident(..) }
+                }
+                ast::VariantType::Struct(_) => quote! { #ident::#variant_ident { .. } },
+            };
+            quote! { #pattern => #status }
+        });
+        // servers do not respond with unknown variants
+        let unknown_arm = if edef.is_non_exhaustive() {
+            Some(quote! { #ident::Unknown => 200, })
+        } else {
+            None
+        };
+        out.extend(quote! {
+            impl ::humblegen_rt::server::ErrorStatus for #ident {
+                fn status_code(&self) -> u16 {
+                    match self {
+                        #(#arms,)*
+                        #unknown_arm
+                    }
+                }
+            }
+        });
+    }
+    out
+}
+
+/// Generate `FromMultipart` impls for all structs used as `multipart[...]` request bodies.
+///
+/// `str` and `bytes` fields are read from plain text and file parts, all other fields
+/// from JSON-encoded parts.
+/// Structs named in `external_types` are skipped, their impls are generated along with them.
+pub fn generate_multipart_impls(
+    spec: &ast::Spec,
+    external_types: &BTreeSet<String>,
+) -> TokenStream {
+    let mut out = TokenStream::new();
+    for sdef in multipart_body_structs(spec, external_types) {
+        let ident = fmt_ident(&sdef.name);
+        let fields = sdef.fields.iter().map(|field| {
+            let field_ident = fmt_ident(&field.pair.name);
+            let part_name = &field.pair.name;
+            let extract = multipart_part_kind(&field.value_type());
+            quote! { #field_ident: form.#extract(#part_name)? }
+        });
+        out.extend(quote! {
+            impl ::humblegen_rt::multipart::FromMultipart for #ident {
+                fn from_multipart(
+                    mut form: ::humblegen_rt::multipart::MultipartForm,
+                ) -> Result<Self, ::humblegen_rt::service_protocol::ErrorResponse> {
+                    Ok(Self {
+                        #(#fields),*
+                    })
+                }
+            }
+        });
+    }
+    out
+}
+
+/// All structs used as `multipart[...]` request bodies, except for those named in `external_types`.
+pub(crate) fn multipart_body_structs<'a>(
+    spec: &'a ast::Spec,
+    external_types: &BTreeSet<String>,
+) -> Vec<&'a ast::StructDef> {
+    let multipart_bodies: BTreeSet<&str> = spec
+        .iter()
+        .filter_map(|si| si.service_def())
+        .flat_map(|sdef| sdef.endpoints.iter())
+        .filter(|e| e.route.request_body_kind() == Some(ast::BodyKind::Multipart))
+        .filter_map(|e| match e.route.request_body() {
+            Some(ast::TypeIdent::UserDefined(name)) if !external_types.contains(name.as_str()) => {
+                Some(name.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+
+    multipart_bodies
+        .into_iter()
+        .map(|name| {
+            spec.iter()
+                .find_map(|si| match si {
+                    ast::SpecItem::StructDef(sdef) if sdef.name == name => Some(sdef),
+                    _ => None,
+                })
+                .expect("multipart bodies are checked by `check_multipart_bodies`")
+        })
+        .collect()
+}
+
+/// The kind of multipart part a struct field is transferred as, named like the methods
+/// of the runtime's `MultipartForm`s.
+pub(crate) fn multipart_part_kind(type_ident: &ast::TypeIdent) -> proc_macro2::Ident {
+    let kind = match type_ident {
+        ast::TypeIdent::BuiltIn(ast::AtomType::Str) => "text",
+        ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => "file",
+        ast::TypeIdent::Option(inner) => match inner.as_ref() {
+            ast::TypeIdent::BuiltIn(ast::AtomType::Str) => "opt_text",
+            ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => "opt_file",
+            _ => "json",
+        },
+        _ => "json",
+    };
+    format_ident!("{}", kind)
+}
+
 fn generate_as_rustdoc_comment_try_rustfmt(s: &TokenStream) -> String {
     format!(
         "```\n{}\n```",
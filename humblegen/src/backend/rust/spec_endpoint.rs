@@ -0,0 +1,270 @@
+//! Code generation for the `/__spec` endpoint, which serves the contract of the generated server.
+//!
+//! The entrypoint to this module is the `generate_spec_consts` function.
+//! It renders the resolved humblespec into a JSON document `SPEC_JSON` and an HTML index of all
+//! routes `SPEC_INDEX_HTML`, which `Builder::with_spec_endpoint` serves using
+//! `humblegen_rt::spec_endpoint`. Like in the docs, routes annotated with `@internal` are omitted.
+
+use crate::ast;
+use crate::backend::docs::Escape;
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde_json::{json, Value};
+
+/// Generate the `SPEC_JSON` and `SPEC_INDEX_HTML` constants of `spec`.
+pub fn generate_spec_consts(spec: &ast::Spec) -> TokenStream {
+    let spec_json =
+        serde_json::to_string_pretty(&spec_to_json(spec)).expect("JSON values are serializable");
+    let index_html = spec_to_index_html(spec);
+    quote! {
+        /// The resolved humblespec of the services, served at `/__spec` by `Builder::with_spec_endpoint`.
+        pub const SPEC_JSON: &str = #spec_json;
+
+        /// HTML index of the routes of the services, served at `/__spec/index.html` by `Builder::with_spec_endpoint`.
+        pub const SPEC_INDEX_HTML: &str = #index_html;
+    }
+}
+
+fn spec_to_json(spec: &ast::Spec) -> Value {
+    let types: Vec<_> = spec
+        .iter()
+        .filter_map(|spec_item| match spec_item {
+            ast::SpecItem::StructDef(sdef) => Some(json!({
+                "struct": sdef.name,
+                "doc": sdef.doc_comment,
+                "fields": fields_to_json(&sdef.fields),
+            })),
+            ast::SpecItem::TupleStructDef(tdef) => Some(json!({
+                "struct": tdef.name,
+                "doc": tdef.doc_comment,
+                "type": tdef.elements.to_string(),
+            })),
+            ast::SpecItem::EnumDef(edef) => Some(enum_to_json(edef)),
+            ast::SpecItem::FlagsDef(fdef) => Some(json!({
+                "flags": fdef.name,
+                "doc": fdef.doc_comment,
+                "values": fdef.flags.iter().map(|flag| json!({
+                    "name": flag.name,
+                    "doc": flag.doc_comment,
+                    "json_name": flag.json_name(),
+                })).collect::<Vec<_>>(),
+            })),
+            ast::SpecItem::ServiceDef(_) => None,
+        })
+        .collect();
+    let services: Vec<_> = spec
+        .iter()
+        .filter_map(ast::SpecItem::service_def)
+        .map(|service| {
+            let mut value = json!({
+                "service": service.name,
+                "doc": service.doc_comment,
+                "format": service.wire_format().as_str(),
+                "routes": service
+                    .endpoints
+                    .iter()
+                    .filter(|endpoint| !endpoint.is_internal())
+                    .map(|endpoint| endpoint_to_json(service, endpoint))
+                    .collect::<Vec<_>>(),
+            });
+            let formats = service.wire_formats();
+            if formats.len() > 1 {
+                value["formats"] = json!(formats.iter().map(|f| f.as_str()).collect::<Vec<_>>());
+            }
+            if let Some(origins) = service.cors_origins() {
+                value["cors_origins"] = json!(origins);
+            }
+            value
+        })
+        .collect();
+    let mut value = json!({ "types": types, "services": services });
+    if !spec.meta().is_empty() {
+        value["meta"] = json!(spec.meta());
+    }
+    value
+}
+
+fn enum_to_json(edef: &ast::EnumDef) -> Value {
+    let mut value = json!({
+        "enum": edef.name,
+        "doc": edef.doc_comment,
+        "variants": edef.variants.iter().map(variant_to_json).collect::<Vec<_>>(),
+    });
+    match edef.tagging() {
+        ast::EnumTagging::External => {}
+        ast::EnumTagging::Internal { tag } => value["tag"] = json!(tag),
+        ast::EnumTagging::Adjacent { tag, content } => {
+            value["tag"] = json!(tag);
+            value["content"] = json!(content);
+        }
+        ast::EnumTagging::Untagged => value["untagged"] = json!(true),
+    }
+    if edef.is_numeric() {
+        value["numeric"] = json!(true);
+    }
+    value
+}
+
+fn fields_to_json(fields: &ast::StructFields) -> Vec<Value> {
+    fields
+        .iter()
+        .map(|field| {
+            let mut value = json!({
+                "name": field.pair.name,
+                "type": field.pair.type_ident.to_string(),
+                "doc": field.doc_comment,
+                "renamed_from": field.renamed_from(),
+            });
+            if let Some(json_name) = field.json_name() {
+                value["json_name"] = json!(json_name);
+            }
+            if field.optional {
+                value["optional"] = json!(true);
+            }
+            value
+        })
+        .collect()
+}
+
+fn variant_to_json(variant: &ast::VariantDef) -> Value {
+    let mut value = json!({
+        "name": variant.name,
+        "doc": variant.doc_comment,
+    });
+    match &variant.variant_type {
+        ast::VariantType::Simple => {}
+        ast::VariantType::Newtype(ty) => value["type"] = json!(ty.to_string()),
+        ast::VariantType::Tuple(tdef) => value["type"] = json!(tdef.to_string()),
+        ast::VariantType::Struct(fields) => value["fields"] = json!(fields_to_json(fields)),
+    }
+    if let Some(json_name) = variant.json_name() {
+        value["json_name"] = json!(json_name);
+    }
+    if let Some(status) = variant.http_status() {
+        value["status"] = json!(status);
+    }
+    if let Some(discriminant) = variant.discriminant {
+        value["discriminant"] = json!(discriminant);
+    }
+    value
+}
+
+fn endpoint_to_json(service: &ast::ServiceDef, endpoint: &ast::ServiceEndpoint) -> Value {
+    let route = &endpoint.route;
+    let mut value = json!({
+        "method": route.http_method_as_str(),
+        "path": route_path(route),
+        "query": route.query().as_ref().map(ToString::to_string),
+        "headers": route.headers().iter().map(|h| json!({
+            "name": h.name,
+            "type": h.type_ident.to_string(),
+        })).collect::<Vec<_>>(),
+        "body": route.request_body().map(ToString::to_string),
+        "multipart": route.request_body_kind() == Some(ast::BodyKind::Multipart),
+        "returns": route.return_type().to_string(),
+        "auth": service.endpoint_requires_auth(endpoint),
+        "flag": endpoint.flag(),
+        "doc": endpoint.doc_comment,
+    });
+    if let Some(max_body_size) = endpoint.max_body_size() {
+        value["max_body_size"] = json!(max_body_size);
+    }
+    if let Some(timeout_ms) = endpoint.timeout_ms() {
+        value["timeout_ms"] = json!(timeout_ms);
+    }
+    if endpoint.is_idempotent() {
+        value["idempotent"] = json!(true);
+    }
+    if endpoint.is_uncompressed() {
+        value["uncompressed"] = json!(true);
+    }
+    if !route.response_headers().is_empty() {
+        value["response_headers"] = route
+            .response_headers()
+            .iter()
+            .map(|h| json!({ "name": h.name, "type": h.type_ident.to_string() }))
+            .collect();
+    }
+    value
+}
+
+/// The path of `route` in humblespec syntax, e.g. `/monsters/{id: i32}`.
+fn route_path(route: &ast::ServiceRoute) -> String {
+    route
+        .components()
+        .iter()
+        .map(|component| match component {
+            ast::ServiceRouteComponent::Literal(lit) => format!("/{}", lit),
+            ast::ServiceRouteComponent::Variable(pair) => {
+                format!("/{{{}: {}}}", pair.name, pair.type_ident)
+            }
+            ast::ServiceRouteComponent::Rest(pair) => {
+                format!("/{{*{}: {}}}", pair.name, pair.type_ident)
+            }
+        })
+        .join("")
+}
+
+/// The return type of `route` with its response headers in humblespec syntax,
+/// e.g. `FileMeta with header "ETag": str`.
+fn returns(route: &ast::ServiceRoute) -> String {
+    let mut returns = route.return_type().to_string();
+    if !route.response_headers().is_empty() {
+        returns.push_str(" with");
+        for h in route.response_headers() {
+            returns.push_str(&format!(" header {:?}: {}", h.name, h.type_ident));
+        }
+    }
+    returns
+}
+
+fn spec_to_index_html(spec: &ast::Spec) -> String {
+    let services = spec
+        .iter()
+        .filter_map(ast::SpecItem::service_def)
+        .map(|service| {
+            let rows = service
+                .endpoints
+                .iter()
+                .filter(|endpoint| !endpoint.is_internal())
+                .map(|endpoint| {
+                    let route = &endpoint.route;
+                    let query = route
+                        .query()
+                        .as_ref()
+                        .map(|q| format!("?{{{}}}", q))
+                        .unwrap_or_default();
+                    format!(
+                        "<tr><td><code>{}</code></td><td><code>{}{}</code></td><td><code>{}</code></td><td><code>{}</code></td><td>{}</td></tr>",
+                        route.http_method_as_str(),
+                        Escape(&route_path(route)),
+                        Escape(&query),
+                        Escape(&route.request_body().map(ToString::to_string).unwrap_or_default()),
+                        Escape(&returns(route)),
+                        Escape(endpoint.doc_comment.as_deref().unwrap_or_default()),
+                    )
+                })
+                .join("\n");
+            format!(
+                "<h2>{}</h2>\n<p>{}</p>\n<table>\n<tr><th>Method</th><th>Path</th><th>Body</th><th>Returns</th><th>Description</th></tr>\n{}\n</table>",
+                Escape(&service.name),
+                Escape(service.doc_comment.as_deref().unwrap_or_default()),
+                rows
+            )
+        })
+        .join("\n");
+    let title = match &spec.meta().title {
+        Some(title) => format!("{} Routes", title),
+        None => "API Routes".to_owned(),
+    };
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+        <style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}</style>\n\
+        </head>\n<body>\n<h1>{title}</h1>\n\
+        <p>Paths are relative to the root a service is mounted at. The full spec is available as <a href=\"../__spec\">JSON</a>.</p>\n\
+        {services}\n</body>\n</html>\n",
+        title = Escape(&title),
+        services = services
+    )
+}
@@ -0,0 +1,1236 @@
+//! Generates Swift code for a humble specification file, e.g. for iOS clients.
+//!
+//! Structs become structs and enums become enums with raw `String` values (if all variants are
+//! simple) or with associated values, all `Codable` in the JSON representation of
+//! `docs/humblespec/data_types_json_representation.md`. For the client artifact, every service
+//! becomes a struct with an `async` method per route, sending requests with `URLSession`.
+//! Types without a Swift equivalent, like tuples, are generated as helper structs named after the
+//! type, e.g. `TupleOfF64AndStr`. See `docs/humblespec/swift.md` for the complete mapping.
+
+use crate::backend::protobuf::{helper_name, rpc_name};
+use crate::{
+    ast, workspace::Workspace, Artifact, EnumRepresentation, LibError, MapRepresentation, Spec,
+    WireCasing,
+};
+
+use inflector::cases::camelcase::to_camel_case;
+use itertools::Itertools;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+const BACKEND_NAME: &str = "swift";
+
+/// Keywords of Swift, which must be escaped to be used as names.
+const KEYWORDS: &[&str] = &[
+    "Any",
+    "Self",
+    "as",
+    "associatedtype",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "continue",
+    "default",
+    "defer",
+    "deinit",
+    "do",
+    "else",
+    "enum",
+    "extension",
+    "fallthrough",
+    "false",
+    "fileprivate",
+    "for",
+    "func",
+    "guard",
+    "if",
+    "import",
+    "in",
+    "init",
+    "inout",
+    "internal",
+    "is",
+    "let",
+    "nil",
+    "open",
+    "operator",
+    "private",
+    "protocol",
+    "public",
+    "repeat",
+    "rethrows",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "subscript",
+    "super",
+    "switch",
+    "throw",
+    "throws",
+    "true",
+    "try",
+    "typealias",
+    "var",
+    "where",
+    "while",
+];
+
+pub struct Generator {
+    client: bool,
+    map_representation: MapRepresentation,
+    enum_representation: EnumRepresentation,
+}
+
+impl Generator {
+    /// Create a generator, which renders clients of the services only for the client artifact.
+    pub fn new(artifact: Artifact) -> Result<Self, LibError> {
+        match artifact {
+            Artifact::TypesOnly => Ok(Self {
+                client: false,
+                map_representation: MapRepresentation::default(),
+                enum_representation: EnumRepresentation::default(),
+            }),
+            Artifact::ClientEndpoints => Ok(Self {
+                client: true,
+                map_representation: MapRepresentation::default(),
+                enum_representation: EnumRepresentation::default(),
+            }),
+            Artifact::ServerEndpoints
+            | Artifact::ClientAndServer
+            | Artifact::Benchmarks
+            | Artifact::MockServer => Err(LibError::UnsupportedArtifact {
+                artifact,
+                backend: BACKEND_NAME,
+            }),
+        }
+    }
+
+    /// Represent maps whose keys are not `str` as `map_representation`. Maps represented as
+    /// entries become arrays of key-value tuples.
+    pub fn with_map_representation(mut self, map_representation: MapRepresentation) -> Self {
+        self.map_representation = map_representation;
+        self
+    }
+
+    /// Represent enums without a `@tag` or `@untagged` annotation as `enum_representation`.
+    pub fn with_enum_representation(mut self, enum_representation: EnumRepresentation) -> Self {
+        self.enum_representation = enum_representation;
+        self
+    }
+
+    /// Render the Swift file of `spec`.
+    fn render_spec(&self, spec: &Spec) -> String {
+        let spec = &self.map_representation.apply(spec);
+        let spec = &self.enum_representation.apply(spec);
+        let mut ctx = Context::default();
+
+        let mut definitions: Vec<String> = spec
+            .iter()
+            .filter_map(|spec_item| match spec_item {
+                ast::SpecItem::StructDef(sdef) => Some(ctx.struct_def(sdef)),
+                ast::SpecItem::TupleStructDef(tdef) => Some(ctx.tuple_struct_def(tdef)),
+                ast::SpecItem::EnumDef(edef) => Some(ctx.enum_def(edef)),
+                ast::SpecItem::FlagsDef(fdef) => Some(flags_def(fdef)),
+                ast::SpecItem::ServiceDef(_) => None,
+            })
+            .collect();
+        if self.client {
+            let services: Vec<&ast::ServiceDef> =
+                spec.iter().filter_map(ast::SpecItem::service_def).collect();
+            let query_structs: BTreeSet<&str> = services
+                .iter()
+                .flat_map(|service| &service.endpoints)
+                .filter_map(|endpoint| endpoint.route.query().as_ref()?.user_defined())
+                .map(ast::Ident::as_str)
+                .collect();
+            let multipart_structs: BTreeSet<&str> = services
+                .iter()
+                .flat_map(|service| &service.endpoints)
+                .filter(|e| e.route.request_body_kind() == Some(ast::BodyKind::Multipart))
+                .filter_map(|endpoint| endpoint.route.request_body()?.user_defined())
+                .map(ast::Ident::as_str)
+                .collect();
+            for sdef in spec.iter().filter_map(|spec_item| match spec_item {
+                ast::SpecItem::StructDef(sdef) => Some(sdef),
+                _ => None,
+            }) {
+                if query_structs.contains(sdef.name.as_str()) {
+                    definitions.push(query_items(sdef));
+                }
+                if multipart_structs.contains(sdef.name.as_str()) {
+                    definitions.push(multipart(sdef));
+                }
+            }
+            let index = spec.index();
+            for service in services {
+                definitions.push(ctx.service_def(service, spec.meta().base_url.as_deref(), &index));
+            }
+        }
+        // helper structs are registered while rendering the definitions above
+        definitions.extend(ctx.helpers.values().cloned());
+
+        let mut out = "// Generated by humblegen, do not edit.\n\nimport Foundation\n\n".to_owned();
+        out.push_str(include_str!("./swift/preamble_types.swift"));
+        if self.client {
+            out.push('\n');
+            out.push_str(include_str!("./swift/preamble_client.swift"));
+        }
+        for definition in definitions {
+            out.push('\n');
+            out.push_str(&definition);
+        }
+        out
+    }
+}
+
+/// Helper structs required by the rendered definitions.
+#[derive(Default)]
+struct Context {
+    /// Helper structs by name.
+    helpers: BTreeMap<String, String>,
+}
+
+impl Context {
+    fn struct_def(&mut self, sdef: &ast::StructDef) -> String {
+        let name = &sdef.name;
+        if sdef.fields.0.is_empty() {
+            return format!(
+                "{}public struct {}: Codable {{\n\
+                \x20   public init() {{}}\n\n\
+                \x20   public init(from decoder: Decoder) throws {{\n\
+                \x20       _ = try decoder.container(keyedBy: HumbleCodingKey.self)\n\
+                \x20   }}\n\n\
+                \x20   public func encode(to encoder: Encoder) throws {{\n\
+                \x20       _ = encoder.container(keyedBy: HumbleCodingKey.self)\n\
+                \x20   }}\n\
+                }}\n",
+                comment(&sdef.doc_comment, ""),
+                name
+            );
+        }
+
+        let mut properties = Vec::new();
+        let mut parameters = Vec::new();
+        let mut assignments = Vec::new();
+        let mut decoders = Vec::new();
+        let mut encoders = Vec::new();
+        for field in sdef.fields.iter() {
+            let property = property_name(&field.pair.name);
+            let ty = self.swift_type(&field.flat_value_type());
+            properties.push(format!(
+                "{}    public var {}: {}\n",
+                comment(&field.doc_comment, "    "),
+                property,
+                ty
+            ));
+            parameters.push(if field.optional {
+                format!("{}: {} = nil", property, ty)
+            } else {
+                format!("{}: {}", property, ty)
+            });
+            assignments.push(format!("        self.{0} = {0}\n", property));
+            decoders.push(format!(
+                "        self.{} = try {}\n",
+                property,
+                self.decode_field(field, "container")
+            ));
+            encoders.push(format!(
+                "        try container.{}({}, forKey: HumbleCodingKey(\"{}\"))\n",
+                encode_fn(field),
+                property,
+                field.wire_name(WireCasing::Spec)
+            ));
+        }
+        format!(
+            "{doc}public struct {name}: Codable {{\n{properties}\n\
+            \x20   public init({parameters}) {{\n{assignments}\x20   }}\n\n\
+            \x20   public init(from decoder: Decoder) throws {{\n\
+            \x20       let container = try decoder.container(keyedBy: HumbleCodingKey.self)\n\
+            {decoders}\
+            \x20   }}\n\n\
+            \x20   public func encode(to encoder: Encoder) throws {{\n\
+            \x20       var container = encoder.container(keyedBy: HumbleCodingKey.self)\n\
+            {encoders}\
+            \x20   }}\n\
+            }}\n",
+            doc = comment(&sdef.doc_comment, ""),
+            name = name,
+            properties = properties.join(""),
+            parameters = parameters.join(", "),
+            assignments = assignments.join(""),
+            decoders = decoders.join(""),
+            encoders = encoders.join(""),
+        )
+    }
+
+    /// Expression decoding `field` from keyed decoding container `container`.
+    ///
+    /// Optional fields and `optional` fields may be missing, fields with former names are also
+    /// decoded from those.
+    fn decode_field(&mut self, field: &ast::FieldNode, container: &str) -> String {
+        let renamed_from = field.renamed_from();
+        let key = if renamed_from.is_empty() {
+            format!("HumbleCodingKey(\"{}\")", field.wire_name(WireCasing::Spec))
+        } else {
+            format!(
+                "humbleKey({}, \"{}\", formerly: [{}])",
+                container,
+                field.wire_name(WireCasing::Spec),
+                renamed_from.iter().map(|n| format!("\"{}\"", n)).join(", ")
+            )
+        };
+        match &field.flat_value_type() {
+            ast::TypeIdent::Option(inner) => {
+                let ty = self.swift_type(inner);
+                format!(
+                    "{}.decodeIfPresent({}.self, forKey: {})",
+                    container,
+                    ty.trim_end_matches('?'),
+                    key
+                )
+            }
+            ty => format!(
+                "{}.decode({}.self, forKey: {})",
+                container,
+                self.swift_type(ty),
+                key
+            ),
+        }
+    }
+
+    fn enum_def(&mut self, edef: &ast::EnumDef) -> String {
+        let name = &edef.name;
+        let tagging = edef.tagging();
+        if edef.is_numeric() {
+            let cases = edef
+                .variants
+                .iter()
+                .zip(edef.discriminants())
+                .map(|(variant, discriminant)| {
+                    format!(
+                        "{}    case {} = {}\n",
+                        comment(&variant.doc_comment, "    "),
+                        case_name(&variant.name),
+                        discriminant
+                    )
+                })
+                .join("");
+            return format!(
+                "{}public enum {}: Int64, Codable {{\n{}}}\n",
+                comment(&edef.doc_comment, ""),
+                name,
+                cases
+            );
+        }
+        if edef.complex_variants().next().is_none() && tagging == ast::EnumTagging::External {
+            let cases = edef
+                .variants
+                .iter()
+                .map(|variant| {
+                    format!(
+                        "{}    case {} = \"{}\"\n",
+                        comment(&variant.doc_comment, "    "),
+                        case_name(&variant.name),
+                        variant.wire_name(WireCasing::Spec)
+                    )
+                })
+                .join("");
+            return format!(
+                "{}public enum {}: String, Codable {{\n{}}}\n",
+                comment(&edef.doc_comment, ""),
+                name,
+                cases
+            );
+        }
+
+        let mut cases = Vec::new();
+        let mut simple_decoders = Vec::new();
+        let mut decoders = Vec::new();
+        let mut encoders = Vec::new();
+        for variant in &edef.variants {
+            let case = case_name(&variant.name);
+            let wire_name = variant.wire_name(WireCasing::Spec);
+            let doc = comment(&variant.doc_comment, "    ");
+            let content = match tagging {
+                ast::EnumTagging::External => Content::Key(&wire_name),
+                ast::EnumTagging::Internal { .. } => Content::Inline,
+                ast::EnumTagging::Adjacent { content, .. } => Content::Key(content),
+                ast::EnumTagging::Untagged => Content::Root,
+            };
+            // the statements decoding the content into `self`, and the pattern and statements
+            // encoding it
+            let (decode, pattern, encode) = match &variant.variant_type {
+                ast::VariantType::Simple => {
+                    cases.push(format!("{}    case {}\n", doc, case));
+                    (
+                        vec![format!("self = .{}", case)],
+                        format!(".{}", case),
+                        vec![],
+                    )
+                }
+                ast::VariantType::Newtype(ty) => {
+                    let ty = self.swift_type(ty);
+                    cases.push(format!("{}    case {}({})\n", doc, case, ty));
+                    let (value, encode) = match content {
+                        Content::Key(key) => (
+                            format!("container.decode({}.self, forKey: key)", ty),
+                            vec![format!(
+                                "try container.encode(value, forKey: HumbleCodingKey(\"{}\"))",
+                                key
+                            )],
+                        ),
+                        Content::Inline | Content::Root => (
+                            format!("decoder.singleValueContainer().decode({}.self)", ty),
+                            vec![
+                                "var container = encoder.singleValueContainer()".to_owned(),
+                                "try container.encode(value)".to_owned(),
+                            ],
+                        ),
+                    };
+                    (
+                        vec![format!("self = try .{}({})", case, value)],
+                        format!(".{}(let value)", case),
+                        encode,
+                    )
+                }
+                ast::VariantType::Tuple(tdef) => {
+                    let types: Vec<String> = tdef
+                        .elements()
+                        .iter()
+                        .map(|ty| self.swift_type(ty))
+                        .collect();
+                    let items = (0..types.len()).map(|i| format!("item{}", i)).collect_vec();
+                    cases.push(format!("{}    case {}({})\n", doc, case, types.join(", ")));
+                    let (items_decoder, items_encoder) = match content {
+                        Content::Key(key) => (
+                            "container.nestedUnkeyedContainer(forKey: key)".to_owned(),
+                            format!(
+                                "container.nestedUnkeyedContainer(forKey: HumbleCodingKey(\"{}\"))",
+                                key
+                            ),
+                        ),
+                        Content::Inline | Content::Root => (
+                            "decoder.unkeyedContainer()".to_owned(),
+                            "encoder.unkeyedContainer()".to_owned(),
+                        ),
+                    };
+                    (
+                        vec![
+                            format!("var items = try {}", items_decoder),
+                            format!(
+                                "self = try .{}({})",
+                                case,
+                                types
+                                    .iter()
+                                    .map(|ty| format!("items.decode({}.self)", ty))
+                                    .join(", ")
+                            ),
+                        ],
+                        format!(
+                            ".{}({})",
+                            case,
+                            items.iter().map(|item| format!("let {}", item)).join(", ")
+                        ),
+                        std::iter::once(format!("var items = {}", items_encoder))
+                            .chain(
+                                items
+                                    .iter()
+                                    .map(|item| format!("try items.encode({})", item)),
+                            )
+                            .collect(),
+                    )
+                }
+                ast::VariantType::Struct(fields) if fields.0.is_empty() => {
+                    cases.push(format!("{}    case {}\n", doc, case));
+                    let (decode, encode) = match content {
+                        Content::Key(key) => (
+                            vec!["_ = try container.nestedContainer(keyedBy: HumbleCodingKey.self, forKey: key)".to_owned()],
+                            vec![format!(
+                                "_ = container.nestedContainer(keyedBy: HumbleCodingKey.self, forKey: HumbleCodingKey(\"{}\"))",
+                                key
+                            )],
+                        ),
+                        Content::Inline => (vec![], vec![]),
+                        Content::Root => (
+                            vec!["_ = try decoder.container(keyedBy: HumbleCodingKey.self)".to_owned()],
+                            vec!["_ = encoder.container(keyedBy: HumbleCodingKey.self)".to_owned()],
+                        ),
+                    };
+                    (
+                        decode
+                            .into_iter()
+                            .chain(std::iter::once(format!("self = .{}", case)))
+                            .collect(),
+                        format!(".{}", case),
+                        encode,
+                    )
+                }
+                ast::VariantType::Struct(fields) => {
+                    let properties: Vec<String> = fields
+                        .iter()
+                        .map(|field| property_name(&field.pair.name))
+                        .collect();
+                    let associated_values = fields
+                        .iter()
+                        .zip(&properties)
+                        .map(|(field, property)| {
+                            format!(
+                                "{}: {}",
+                                property,
+                                self.swift_type(&field.flat_value_type())
+                            )
+                        })
+                        .join(", ");
+                    cases.push(format!("{}    case {}({})\n", doc, case, associated_values));
+                    // internally tagged fields are next to the tag in `container`
+                    let (container, fields_decoder, fields_encoder) = match content {
+                        Content::Key(key) => (
+                            "fields",
+                            Some("try container.nestedContainer(keyedBy: HumbleCodingKey.self, forKey: key)".to_owned()),
+                            Some(format!(
+                                "container.nestedContainer(keyedBy: HumbleCodingKey.self, forKey: HumbleCodingKey(\"{}\"))",
+                                key
+                            )),
+                        ),
+                        Content::Inline => ("container", None, None),
+                        Content::Root => (
+                            "fields",
+                            Some("try decoder.container(keyedBy: HumbleCodingKey.self)".to_owned()),
+                            Some("encoder.container(keyedBy: HumbleCodingKey.self)".to_owned()),
+                        ),
+                    };
+                    let decode = fields_decoder
+                        .map(|decoder| format!("let fields = {}", decoder))
+                        .into_iter()
+                        .chain(std::iter::once(format!(
+                            "self = try .{}({})",
+                            case,
+                            fields
+                                .iter()
+                                .zip(&properties)
+                                .map(|(field, property)| format!(
+                                    "{}: {}",
+                                    property,
+                                    self.decode_field(field, container)
+                                ))
+                                .join(", ")
+                        )))
+                        .collect();
+                    let encode = fields_encoder
+                        .map(|encoder| format!("var fields = {}", encoder))
+                        .into_iter()
+                        .chain(fields.iter().zip(&properties).map(|(field, property)| {
+                            format!(
+                                "try {}.{}({}, forKey: HumbleCodingKey(\"{}\"))",
+                                container,
+                                encode_fn(field),
+                                property,
+                                field.wire_name(WireCasing::Spec)
+                            )
+                        }))
+                        .collect();
+                    (
+                        decode,
+                        format!(
+                            ".{}({})",
+                            case,
+                            properties.iter().map(|p| format!("let {}", p)).join(", ")
+                        ),
+                        encode,
+                    )
+                }
+            };
+
+            let tag_encoder = |tag: &str| {
+                format!(
+                    "try container.encode(\"{}\", forKey: HumbleCodingKey(\"{}\"))",
+                    wire_name, tag
+                )
+            };
+            let keyed_container =
+                "var container = encoder.container(keyedBy: HumbleCodingKey.self)";
+            let encode: Vec<String> = match tagging {
+                ast::EnumTagging::External if variant.is_simple() => vec![
+                    "var container = encoder.singleValueContainer()".to_owned(),
+                    format!("try container.encode(\"{}\")", wire_name),
+                ],
+                ast::EnumTagging::External => std::iter::once(keyed_container.to_owned())
+                    .chain(encode)
+                    .collect(),
+                ast::EnumTagging::Internal { tag } | ast::EnumTagging::Adjacent { tag, .. } => {
+                    vec![keyed_container.to_owned(), tag_encoder(tag)]
+                        .into_iter()
+                        .chain(encode)
+                        .collect()
+                }
+                ast::EnumTagging::Untagged if variant.is_simple() => vec![
+                    "var container = encoder.singleValueContainer()".to_owned(),
+                    "try container.encodeNil()".to_owned(),
+                ],
+                ast::EnumTagging::Untagged => encode,
+            };
+            encoders.push(format!(
+                "        case {}:\n{}",
+                pattern,
+                encode
+                    .iter()
+                    .map(|statement| format!("            {}\n", statement))
+                    .join("")
+            ));
+
+            match tagging {
+                ast::EnumTagging::External if variant.is_simple() => simple_decoders.push(format!(
+                    "            case \"{}\":\n                self = .{}\n",
+                    wire_name, case
+                )),
+                ast::EnumTagging::Untagged if variant.is_simple() => decoders.push(format!(
+                    "        if (try? decoder.singleValueContainer().decodeNil()) == true {{\n\
+                    \x20           self = .{}\n\
+                    \x20           return\n\
+                    \x20       }}\n",
+                    case
+                )),
+                ast::EnumTagging::Untagged => decoders.push(format!(
+                    "        do {{\n{}\x20           return\n\x20       }} catch {{}}\n",
+                    decode
+                        .iter()
+                        .map(|statement| format!("            {}\n", statement))
+                        .join("")
+                )),
+                _ => decoders.push(format!(
+                    "        case \"{}\":\n{}",
+                    wire_name,
+                    decode
+                        .iter()
+                        .map(|statement| format!("            {}\n", statement))
+                        .join("")
+                )),
+            }
+        }
+
+        let decode = match tagging {
+            ast::EnumTagging::External => {
+                let decode_simple = if simple_decoders.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "        if let tag = try? decoder.singleValueContainer().decode(String.self) {{\n\
+                        \x20           switch tag {{\n{}\
+                        \x20           default:\n\
+                        \x20               throw humbleUnknownVariant(decoder, tag, of: \"{}\")\n\
+                        \x20           }}\n\
+                        \x20           return\n\
+                        \x20       }}\n",
+                        simple_decoders.join(""),
+                        name
+                    )
+                };
+                format!(
+                    "{decode_simple}\
+                    \x20       let (container, key) = try humbleVariant(decoder, of: \"{name}\")\n\
+                    \x20       switch key.stringValue {{\n{decoders}\
+                    \x20       default:\n\
+                    \x20           throw humbleUnknownVariant(decoder, key.stringValue, of: \"{name}\")\n\
+                    \x20       }}\n",
+                    decode_simple = decode_simple,
+                    name = name,
+                    decoders = decoders.join(""),
+                )
+            }
+            ast::EnumTagging::Internal { tag } | ast::EnumTagging::Adjacent { tag, .. } => {
+                let key = match tagging {
+                    ast::EnumTagging::Adjacent { content, .. }
+                        if edef.complex_variants().next().is_some() =>
+                    {
+                        format!("        let key = HumbleCodingKey(\"{}\")\n", content)
+                    }
+                    _ => String::new(),
+                };
+                format!(
+                    "        let container = try decoder.container(keyedBy: HumbleCodingKey.self)\n\
+                    \x20       let tag = try container.decode(String.self, forKey: HumbleCodingKey(\"{tag}\"))\n\
+                    {key}\
+                    \x20       switch tag {{\n{decoders}\
+                    \x20       default:\n\
+                    \x20           throw humbleUnknownVariant(decoder, tag, of: \"{name}\")\n\
+                    \x20       }}\n",
+                    tag = tag,
+                    key = key,
+                    name = name,
+                    decoders = decoders.join(""),
+                )
+            }
+            ast::EnumTagging::Untagged => format!(
+                "{}        throw DecodingError.dataCorrupted(.init(codingPath: decoder.codingPath, debugDescription: \"no variant of {} matches\"))\n",
+                decoders.join(""),
+                name
+            ),
+        };
+        format!(
+            "{doc}public enum {name}: Codable {{\n{cases}\n\
+            \x20   public init(from decoder: Decoder) throws {{\n\
+            {decode}\
+            \x20   }}\n\n\
+            \x20   public func encode(to encoder: Encoder) throws {{\n\
+            \x20       switch self {{\n{encoders}\
+            \x20       }}\n\
+            \x20   }}\n\
+            }}\n",
+            doc = comment(&edef.doc_comment, ""),
+            name = name,
+            cases = cases.join(""),
+            decode = decode,
+            encoders = encoders.join(""),
+        )
+    }
+
+    fn service_def(
+        &mut self,
+        service: &ast::ServiceDef,
+        base_url: Option<&str>,
+        index: &ast::SpecIndex,
+    ) -> String {
+        let methods = service
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                format!(
+                    "{}{}",
+                    comment(&endpoint.doc_comment, "    "),
+                    self.method(endpoint, index)
+                )
+            })
+            .join("\n");
+        // the base URL declared in the `meta` block is the default
+        let default_base_url = base_url
+            .map(|base_url| format!(" = URL(string: \"{}\")!", base_url))
+            .unwrap_or_default();
+        format!(
+            "{}public struct {} {{\n\
+            \x20   public var baseURL: URL\n\
+            \x20   public var session: URLSession\n\n\
+            \x20   public init(baseURL: URL{}, session: URLSession = .shared) {{\n\
+            \x20       self.baseURL = baseURL\n\
+            \x20       self.session = session\n\
+            \x20   }}\n\n{}}}\n",
+            comment(&service.doc_comment, ""),
+            service.name,
+            default_base_url,
+            methods
+        )
+    }
+
+    /// The method of the client sending a request to `route`, e.g.
+    /// `public func getMonstersId(id: Int32) async throws -> Monster`.
+    ///
+    /// Methods of `@idempotent` routes send a new `Idempotency-Key` per call, unless they are
+    /// passed the key of the request to retry.
+    fn method(&mut self, endpoint: &ast::ServiceEndpoint, index: &ast::SpecIndex) -> String {
+        let route = &endpoint.route;
+        let mut parameters = Vec::new();
+        // whether building the request throws, e.g. when JSON-encoding parameters
+        let mut throws = false;
+        // the segments of the rest of the path, appended to the other segments
+        let mut rest_segments = String::new();
+        let path = route
+            .components()
+            .iter()
+            .filter_map(|component| match component {
+                ast::ServiceRouteComponent::Literal(lit) => Some(format!("\"{}\"", lit)),
+                ast::ServiceRouteComponent::Variable(pair) => {
+                    let property = property_name(&pair.name);
+                    parameters.push(format!(
+                        "{}: {}",
+                        property,
+                        self.swift_type(&pair.type_ident)
+                    ));
+                    throws |= !is_plain(&pair.type_ident);
+                    Some(parameter(&pair.type_ident, &property))
+                }
+                ast::ServiceRouteComponent::Rest(pair) => {
+                    let property = property_name(&pair.name);
+                    parameters.push(format!(
+                        "{}: {}",
+                        property,
+                        self.swift_type(&pair.type_ident)
+                    ));
+                    rest_segments =
+                        format!(" + {}.split(separator: \"/\").map(String.init)", property);
+                    None
+                }
+            })
+            .join(", ");
+        let headers = route
+            .headers()
+            .iter()
+            .map(|header| {
+                let argument = escape(&to_camel_case(&format!("header_{}", header.name)));
+                parameters.push(format!(
+                    "{}: {}",
+                    argument,
+                    self.swift_type(&header.type_ident)
+                ));
+                let value = match &header.type_ident {
+                    ast::TypeIdent::Option(inner) if is_plain(inner) => {
+                        format!("{}.map {{ $0.humbleParameter }}", argument)
+                    }
+                    ast::TypeIdent::Option(_) => {
+                        throws = true;
+                        format!("{}.map(humbleJSONParameter)", argument)
+                    }
+                    ty => {
+                        throws |= !is_plain(ty);
+                        parameter(ty, &argument)
+                    }
+                };
+                format!("(\"{}\", {})", header.name, value)
+            })
+            .chain(
+                endpoint
+                    .is_idempotent()
+                    .then(|| "(\"Idempotency-Key\", idempotencyKey)".to_owned()),
+            )
+            .join(", ");
+        let query = match route.query() {
+            Some(ty) => {
+                parameters.push(format!("query: {}", self.swift_type(ty)));
+                throws = true;
+                "query.humbleQueryItems()"
+            }
+            None => "nil",
+        };
+        let body = match (route.request_body(), route.request_body_kind()) {
+            (Some(ty), kind) => {
+                parameters.push(format!("body: {}", self.swift_type(ty)));
+                if kind == Some(ast::BodyKind::Multipart) {
+                    "        try body.humbleMultipart().apply(to: &request)\n"
+                } else {
+                    "        try humbleJSONBody(&request, body)\n"
+                }
+            }
+            (None, _) => "",
+        };
+        if endpoint.is_idempotent() {
+            parameters.push("idempotencyKey: String = UUID().uuidString".to_owned());
+        }
+
+        let ret = route.return_type();
+        let (ret, send) = match ret {
+            ast::TypeIdent::BuiltIn(ast::AtomType::Empty) => (
+                String::new(),
+                "try await humbleSendNoContent(session, request)".to_owned(),
+            ),
+            _ => {
+                let statuses = domain_error_statuses(index, ret);
+                (
+                    format!(" -> {}", self.swift_type(ret)),
+                    if statuses.is_empty() {
+                        "return try await humbleSend(session, request)".to_owned()
+                    } else {
+                        format!(
+                            "return try await humbleSend(session, request, domainErrorStatuses: [{}])",
+                            statuses.iter().join(", ")
+                        )
+                    },
+                )
+            }
+        };
+        format!(
+            "    public func {name}({parameters}) async throws{ret} {{\n\
+            \x20       {binding} request = {throwing}humbleRequest(baseURL, \"{method}\", path: [{path}]{rest_segments}, query: {query}, headers: [{headers}])\n\
+            {body}\
+            \x20       {send}\n\
+            \x20   }}\n",
+            name = escape(&to_camel_case(&rpc_name(route))),
+            parameters = parameters.join(", "),
+            ret = ret,
+            binding = if body.is_empty() { "let" } else { "var" },
+            throwing = if throws { "try " } else { "" },
+            method = route.http_method_as_str(),
+            path = path,
+            rest_segments = rest_segments,
+            query = query,
+            headers = headers,
+            body = body,
+            send = send,
+        )
+    }
+
+    /// A struct wrapping a single value for newtypes, encoded as that value, otherwise a tuple
+    /// struct.
+    fn tuple_struct_def(&mut self, tdef: &ast::TupleStructDef) -> String {
+        let types: Vec<String> = tdef
+            .elements
+            .elements()
+            .iter()
+            .map(|ty| self.swift_type(ty))
+            .collect();
+        let definition = if tdef.is_newtype() {
+            format!(
+                "public struct {name}: Codable {{\n\
+                \x20   public var value: {ty}\n\n\
+                \x20   public init(_ value: {ty}) {{\n\
+                \x20       self.value = value\n\
+                \x20   }}\n\n\
+                \x20   public init(from decoder: Decoder) throws {{\n\
+                \x20       self.value = try decoder.singleValueContainer().decode({ty}.self)\n\
+                \x20   }}\n\n\
+                \x20   public func encode(to encoder: Encoder) throws {{\n\
+                \x20       var container = encoder.singleValueContainer()\n\
+                \x20       try container.encode(value)\n\
+                \x20   }}\n\
+                }}\n",
+                name = tdef.name,
+                ty = types[0],
+            )
+        } else {
+            tuple_struct(&tdef.name, &types)
+        };
+        format!("{}{}", comment(&tdef.doc_comment, ""), definition)
+    }
+
+    /// The Swift type of `ty`, registering a helper struct for types without a Swift
+    /// equivalent.
+    fn swift_type(&mut self, ty: &ast::TypeIdent) -> String {
+        match ty {
+            ast::TypeIdent::BuiltIn(atom) => atom_type(*atom).to_owned(),
+            ast::TypeIdent::UserDefined(name) => name.to_string(),
+            ast::TypeIdent::List(inner) => format!("[{}]", self.swift_type(inner)),
+            ast::TypeIdent::Option(inner) => {
+                let inner = self.swift_type(inner);
+                if inner.ends_with('?') {
+                    // nested options are indistinguishable in JSON
+                    inner
+                } else {
+                    format!("{}?", inner)
+                }
+            }
+            ast::TypeIdent::Map(key, value) => {
+                // JSONEncoder only encodes dictionaries with `String` and `Int` keys as objects
+                let key = match key.as_ref() {
+                    ast::TypeIdent::BuiltIn(
+                        ast::AtomType::I32
+                        | ast::AtomType::U32
+                        | ast::AtomType::I64
+                        | ast::AtomType::U8,
+                    ) => "Int",
+                    _ => "String",
+                };
+                format!("[{}: {}]", key, self.swift_type(value))
+            }
+            ast::TypeIdent::Result(ok, err) => format!(
+                "HumbleResult<{}, {}>",
+                self.swift_type(ok),
+                self.swift_type(err)
+            ),
+            ast::TypeIdent::Tuple(tdef) => {
+                let name = helper_name(ty);
+                if !self.helpers.contains_key(&name) {
+                    let types: Vec<String> = tdef
+                        .elements()
+                        .iter()
+                        .map(|ty| self.swift_type(ty))
+                        .collect();
+                    self.helpers
+                        .insert(name.clone(), tuple_struct(&name, &types));
+                }
+                name
+            }
+        }
+    }
+}
+
+/// Where the content of the complex variants of an enum is, depending on its tagging.
+#[derive(Clone, Copy)]
+enum Content<'a> {
+    /// At key `key` of the keyed `container`. When decoding, the key is the variable `key`.
+    Key(&'a str),
+    /// Fields next to the tag in the keyed `container`.
+    Inline,
+    /// The whole value.
+    Root,
+}
+
+/// The HTTP status codes the server uses for the domain error of a `result[T][E]` return type,
+/// i.e., the `@status` codes of enum `E`.
+fn domain_error_statuses(index: &ast::SpecIndex, ret: &ast::TypeIdent) -> Vec<u16> {
+    match ret {
+        ast::TypeIdent::Result(_, err) => err
+            .user_defined()
+            .and_then(|name| index.enum_def(name))
+            .map(ast::EnumDef::http_statuses)
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Whether values of type `ty` have a plain text representation in a path, query or header.
+fn is_plain(ty: &ast::TypeIdent) -> bool {
+    !matches!(
+        ty,
+        ast::TypeIdent::BuiltIn(ast::AtomType::Empty | ast::AtomType::Bytes)
+            | ast::TypeIdent::List(_)
+            | ast::TypeIdent::Option(_)
+            | ast::TypeIdent::Map(..)
+            | ast::TypeIdent::Result(..)
+            | ast::TypeIdent::Tuple(_)
+            | ast::TypeIdent::UserDefined(_)
+    )
+}
+
+/// Expression of the plain text representation of `value` of type `ty`, which is JSON for types
+/// that are not `is_plain` and then must be covered by `try`.
+fn parameter(ty: &ast::TypeIdent, value: &str) -> String {
+    if is_plain(ty) {
+        format!("{}.humbleParameter", value)
+    } else {
+        format!("humbleJSONParameter({})", value)
+    }
+}
+
+fn try_unless_plain(ty: &ast::TypeIdent) -> &'static str {
+    if is_plain(ty) {
+        ""
+    } else {
+        "try "
+    }
+}
+
+/// The method of a keyed encoding container encoding `field`, which omits absent `optional`
+/// fields instead of encoding `null`.
+fn encode_fn(field: &ast::FieldNode) -> &'static str {
+    if field.optional {
+        "encodeIfPresent"
+    } else {
+        "encode"
+    }
+}
+
+/// Extension turning a query struct into the query items of a request.
+///
+/// Optional fields are omitted if `nil`.
+fn query_items(sdef: &ast::StructDef) -> String {
+    let items = sdef
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.wire_name(WireCasing::Spec);
+            let property = property_name(&field.pair.name);
+            match &field.flat_value_type() {
+                ast::TypeIdent::Option(inner) => format!(
+                    "        if let value = {} {{\n            {}items.append(URLQueryItem(name: \"{}\", value: {}))\n        }}\n",
+                    property,
+                    try_unless_plain(inner),
+                    name,
+                    parameter(inner, "value")
+                ),
+                ty => format!(
+                    "        {}items.append(URLQueryItem(name: \"{}\", value: {}))\n",
+                    try_unless_plain(ty),
+                    name,
+                    parameter(ty, &property)
+                ),
+            }
+        })
+        .join("");
+    format!(
+        "extension {} {{\n\
+        \x20   func humbleQueryItems() throws -> [URLQueryItem] {{\n\
+        \x20       var items: [URLQueryItem] = []\n{}\
+        \x20       return items\n\
+        \x20   }}\n\
+        }}\n",
+        sdef.name, items
+    )
+}
+
+/// Extension turning a struct into a `multipart/form-data` body.
+///
+/// `str` and `bytes` fields become text and file parts, all other fields JSON-encoded text
+/// parts. Optional fields are omitted if `nil`.
+fn multipart(sdef: &ast::StructDef) -> String {
+    let part = |name: &str, ty: &ast::TypeIdent, value: &str| match ty {
+        ast::TypeIdent::BuiltIn(ast::AtomType::Str) => {
+            format!("multipart.addText(\"{}\", {})", name, value)
+        }
+        ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => {
+            format!("multipart.addFile(\"{}\", {})", name, value)
+        }
+        _ => format!(
+            "try multipart.addText(\"{}\", humbleJSONParameter({}))",
+            name, value
+        ),
+    };
+    let parts = sdef
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.pair.name;
+            let property = property_name(name);
+            match &field.flat_value_type() {
+                ast::TypeIdent::Option(inner) => format!(
+                    "        if let value = {} {{\n            {}\n        }}\n",
+                    property,
+                    part(name, inner, "value")
+                ),
+                ty => format!("        {}\n", part(name, ty, &property)),
+            }
+        })
+        .join("");
+    format!(
+        "extension {} {{\n\
+        \x20   func humbleMultipart() throws -> HumbleMultipart {{\n\
+        \x20       var multipart = HumbleMultipart()\n{}\
+        \x20       return multipart\n\
+        \x20   }}\n\
+        }}\n",
+        sdef.name, parts
+    )
+}
+
+/// A struct `name` for tuples of `types`, encoded as JSON array.
+/// A set of the enum of the flags of a flags type, which is encoded as list of their names.
+fn flags_def(fdef: &ast::FlagsDef) -> String {
+    let cases = fdef
+        .flags
+        .iter()
+        .map(|flag| {
+            format!(
+                "{}    case {} = \"{}\"\n",
+                comment(&flag.doc_comment, "    "),
+                case_name(&flag.name),
+                flag.wire_name(WireCasing::Spec)
+            )
+        })
+        .join("");
+    format!(
+        "public enum {name}Flag: String, Codable {{\n{cases}}}\n\n{doc}public typealias {name} = Set<{name}Flag>\n",
+        name = fdef.name,
+        cases = cases,
+        doc = comment(&fdef.doc_comment, ""),
+    )
+}
+
+fn tuple_struct(name: &str, types: &[String]) -> String {
+    let items = (0..types.len()).map(|i| format!("item{}", i)).collect_vec();
+    format!(
+        "public struct {name}: Codable {{\n{properties}\n\
+        \x20   public init({parameters}) {{\n{assignments}\x20   }}\n\n\
+        \x20   public init(from decoder: Decoder) throws {{\n\
+        \x20       var items = try decoder.unkeyedContainer()\n\
+        \x20       try self.init({decoders})\n\
+        \x20   }}\n\n\
+        \x20   public func encode(to encoder: Encoder) throws {{\n\
+        \x20       var items = encoder.unkeyedContainer()\n\
+        {encoders}\
+        \x20   }}\n\
+        }}\n",
+        name = name,
+        properties = items
+            .iter()
+            .zip(types)
+            .map(|(item, ty)| format!("    public var {}: {}\n", item, ty))
+            .join(""),
+        parameters = items
+            .iter()
+            .zip(types)
+            .map(|(item, ty)| format!("_ {}: {}", item, ty))
+            .join(", "),
+        assignments = items
+            .iter()
+            .map(|item| format!("        self.{0} = {0}\n", item))
+            .join(""),
+        decoders = types
+            .iter()
+            .map(|ty| format!("items.decode({}.self)", ty))
+            .join(", "),
+        encoders = items
+            .iter()
+            .map(|item| format!("        try items.encode({})\n", item))
+            .join(""),
+    )
+}
+
+fn atom_type(atom: ast::AtomType) -> &'static str {
+    match atom {
+        ast::AtomType::Empty => "HumbleEmpty",
+        ast::AtomType::Str => "String",
+        ast::AtomType::I32 => "Int32",
+        ast::AtomType::U32 => "UInt32",
+        ast::AtomType::I64 => "Int64",
+        ast::AtomType::U64 => "UInt64",
+        ast::AtomType::U8 => "UInt8",
+        ast::AtomType::F64 => "Double",
+        ast::AtomType::Bool => "Bool",
+        ast::AtomType::DateTime => "Date",
+        ast::AtomType::Date => "HumbleDate",
+        ast::AtomType::Uuid => "UUID",
+        ast::AtomType::Bytes => "Data",
+    }
+}
+
+/// The name of the property of field `name`, e.g. `bornAt` for `born_at`.
+fn property_name(name: &str) -> String {
+    escape(&to_camel_case(name))
+}
+
+/// The name of the case of variant `name`, e.g. `lightBlue` for `LightBlue`.
+fn case_name(name: &str) -> String {
+    escape(&to_camel_case(name))
+}
+
+fn escape(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("`{}`", name)
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Render `doc_comment` as documentation comment indented by `indent`.
+fn comment(doc_comment: &Option<String>, indent: &str) -> String {
+    match doc_comment {
+        None => String::new(),
+        Some(doc) => doc
+            .lines()
+            .map(|line| format!("{}/// {}", indent, line).trim_end().to_owned() + "\n")
+            .join(""),
+    }
+}
+
+fn write_file(contents: &str, output: &Path) -> Result<(), LibError> {
+    let mut outfile = File::create(output).map_err(LibError::file(output))?;
+    outfile
+        .write_all(contents.as_bytes())
+        .map_err(LibError::file(output))?;
+    Ok(())
+}
+
+impl crate::CodeGenerator for Generator {
+    fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        write_file(&self.render_spec(spec), output)
+    }
+
+    /// Writes a self-contained `{name}.swift` file for every spec, including the common types it
+    /// can use, into the `output` folder. As Swift has no namespaces within a module, every file
+    /// belongs into a module of its own.
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        if !output.is_dir() {
+            return Err(LibError::OutputMustBeFolder {
+                backend: BACKEND_NAME,
+            });
+        }
+        for ws_spec in &workspace.specs {
+            write_file(
+                &self.render_spec(&workspace.resolved_spec(ws_spec)),
+                &output.join(format!("{}.swift", ws_spec.name)),
+            )?;
+        }
+        Ok(())
+    }
+}
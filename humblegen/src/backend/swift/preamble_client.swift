@@ -0,0 +1,118 @@
+/// Error of a response with a status code other than 2XX, except for domain errors.
+public struct HumbleError: Error {
+    public let statusCode: Int
+    public let body: Data
+}
+
+/// Plain text representation of path, query and header parameters.
+public protocol HumbleParameter {
+    var humbleParameter: String { get }
+}
+
+extension String: HumbleParameter {
+    public var humbleParameter: String { self }
+}
+
+extension Int32: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension UInt32: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension UInt8: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension Double: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension Bool: HumbleParameter {
+    public var humbleParameter: String { self ? "true" : "false" }
+}
+
+extension Date: HumbleParameter {
+    public var humbleParameter: String { HumbleJSON.fractionalSecondsFormatter().string(from: self) }
+}
+
+extension HumbleDate: HumbleParameter {
+    public var humbleParameter: String { description }
+}
+
+extension UUID: HumbleParameter {
+    public var humbleParameter: String { uuidString.lowercased() }
+}
+
+/// JSON encoding of query parameters without a plain text representation.
+func humbleJSONParameter<T: Encodable>(_ value: T) throws -> String {
+    String(decoding: try HumbleJSON.encoder().encode(value), as: UTF8.self)
+}
+
+/// A request to `path` below `baseURL`.
+func humbleRequest(_ baseURL: URL, _ method: String, path: [String], query: [URLQueryItem]?, headers: [(String, String?)]) -> URLRequest {
+    var allowed = CharacterSet.urlPathAllowed
+    allowed.remove("/")
+    var url = baseURL
+    for segment in path {
+        url.appendPathComponent(segment.addingPercentEncoding(withAllowedCharacters: allowed) ?? segment, isDirectory: false)
+    }
+    if let query = query, var components = URLComponents(url: url, resolvingAgainstBaseURL: false) {
+        components.queryItems = query
+        url = components.url ?? url
+    }
+    var request = URLRequest(url: url)
+    request.httpMethod = method
+    for (name, value) in headers {
+        if let value = value {
+            request.setValue(value, forHTTPHeaderField: name)
+        }
+    }
+    return request
+}
+
+func humbleJSONBody<T: Encodable>(_ request: inout URLRequest, _ body: T) throws {
+    request.setValue("application/json", forHTTPHeaderField: "Content-Type")
+    request.httpBody = try HumbleJSON.encoder().encode(body)
+}
+
+/// Send `request` and decode the response, also for the `domainErrorStatuses`.
+func humbleSend<T: Decodable>(_ session: URLSession, _ request: URLRequest, domainErrorStatuses: [Int] = []) async throws -> T {
+    let (data, response) = try await session.data(for: request)
+    let status = (response as? HTTPURLResponse)?.statusCode ?? 0
+    guard (200..<300).contains(status) || domainErrorStatuses.contains(status) else {
+        throw HumbleError(statusCode: status, body: data)
+    }
+    return try HumbleJSON.decoder().decode(T.self, from: data)
+}
+
+/// Send `request` to a route returning `()`, whose response has no body.
+func humbleSendNoContent(_ session: URLSession, _ request: URLRequest) async throws {
+    let (data, response) = try await session.data(for: request)
+    let status = (response as? HTTPURLResponse)?.statusCode ?? 0
+    guard (200..<300).contains(status) else {
+        throw HumbleError(statusCode: status, body: data)
+    }
+}
+
+/// Builder of `multipart/form-data` bodies.
+struct HumbleMultipart {
+    let boundary = "humblegen-\(UUID().uuidString)"
+    var body = Data()
+
+    mutating func addText(_ name: String, _ value: String) {
+        body.append(Data("--\(boundary)\r\nContent-Disposition: form-data; name=\"\(name)\"\r\n\r\n\(value)\r\n".utf8))
+    }
+
+    mutating func addFile(_ name: String, _ value: Data) {
+        body.append(Data("--\(boundary)\r\nContent-Disposition: form-data; name=\"\(name)\"; filename=\"\(name)\"\r\nContent-Type: application/octet-stream\r\n\r\n".utf8))
+        body.append(value)
+        body.append(Data("\r\n".utf8))
+    }
+
+    func apply(to request: inout URLRequest) {
+        request.setValue("multipart/form-data; boundary=\(boundary)", forHTTPHeaderField: "Content-Type")
+        request.httpBody = body + Data("--\(boundary)--\r\n".utf8)
+    }
+}
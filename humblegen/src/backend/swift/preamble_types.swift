@@ -0,0 +1,139 @@
+/// Coding key of any name, used by the coders of enums and tuples.
+public struct HumbleCodingKey: CodingKey {
+    public var stringValue: String
+    public var intValue: Int? { nil }
+
+    public init(_ stringValue: String) {
+        self.stringValue = stringValue
+    }
+
+    public init?(stringValue: String) {
+        self.stringValue = stringValue
+    }
+
+    public init?(intValue: Int) {
+        return nil
+    }
+}
+
+/// Encoder and decoder of the humblespec JSON representation.
+public enum HumbleJSON {
+    public static func encoder() -> JSONEncoder {
+        let encoder = JSONEncoder()
+        encoder.dateEncodingStrategy = .custom { date, encoder in
+            var container = encoder.singleValueContainer()
+            try container.encode(fractionalSecondsFormatter().string(from: date))
+        }
+        return encoder
+    }
+
+    public static func decoder() -> JSONDecoder {
+        let decoder = JSONDecoder()
+        decoder.dateDecodingStrategy = .custom { decoder in
+            let container = try decoder.singleValueContainer()
+            let string = try container.decode(String.self)
+            guard let date = fractionalSecondsFormatter().date(from: string) ?? ISO8601DateFormatter().date(from: string) else {
+                throw DecodingError.dataCorruptedError(in: container, debugDescription: "invalid datetime \(string)")
+            }
+            return date
+        }
+        return decoder
+    }
+
+    static func fractionalSecondsFormatter() -> ISO8601DateFormatter {
+        let formatter = ISO8601DateFormatter()
+        formatter.formatOptions = [.withInternetDateTime, .withFractionalSeconds]
+        return formatter
+    }
+}
+
+/// The `()` type, encoded as `null`.
+public struct HumbleEmpty: Codable, Hashable {
+    public init() {}
+
+    public init(from decoder: Decoder) throws {}
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        try container.encodeNil()
+    }
+}
+
+/// The `date` type, encoded in ISO 8601 format (`YYYY-MM-DD`).
+public struct HumbleDate: Codable, Hashable, CustomStringConvertible {
+    public var year: Int
+    public var month: Int
+    public var day: Int
+
+    public init(year: Int, month: Int, day: Int) {
+        self.year = year
+        self.month = month
+        self.day = day
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        let string = try container.decode(String.self)
+        let parts = string.split(separator: "-").compactMap { Int($0) }
+        guard parts.count == 3 else {
+            throw DecodingError.dataCorruptedError(in: container, debugDescription: "invalid date \(string)")
+        }
+        self.init(year: parts[0], month: parts[1], day: parts[2])
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        try container.encode(description)
+    }
+
+    public var description: String {
+        String(format: "%04d-%02d-%02d", year, month, day)
+    }
+}
+
+/// The `result[T][E]` type, encoded as `{"Ok": value}` or `{"Err": error}`.
+public enum HumbleResult<T: Codable, E: Codable>: Codable {
+    case ok(T)
+    case err(E)
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        if container.contains(HumbleCodingKey("Ok")) {
+            self = .ok(try container.decode(T.self, forKey: HumbleCodingKey("Ok")))
+        } else {
+            self = .err(try container.decode(E.self, forKey: HumbleCodingKey("Err")))
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: HumbleCodingKey.self)
+        switch self {
+        case .ok(let value):
+            try container.encode(value, forKey: HumbleCodingKey("Ok"))
+        case .err(let error):
+            try container.encode(error, forKey: HumbleCodingKey("Err"))
+        }
+    }
+}
+
+/// The single variant of an externally tagged enum, `{"Variant": content}`.
+func humbleVariant(_ decoder: Decoder, of name: String) throws -> (KeyedDecodingContainer<HumbleCodingKey>, HumbleCodingKey) {
+    let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+    guard container.allKeys.count == 1, let key = container.allKeys.first else {
+        throw DecodingError.dataCorrupted(.init(codingPath: decoder.codingPath, debugDescription: "expected a variant of \(name)"))
+    }
+    return (container, key)
+}
+
+func humbleUnknownVariant(_ decoder: Decoder, _ tag: String, of name: String) -> Error {
+    DecodingError.dataCorrupted(.init(codingPath: decoder.codingPath, debugDescription: "unknown variant \(tag) of \(name)"))
+}
+
+/// The key of field `name`, which is also decoded from its `formerly` used names.
+func humbleKey(_ container: KeyedDecodingContainer<HumbleCodingKey>, _ name: String, formerly: [String]) -> HumbleCodingKey {
+    let key = HumbleCodingKey(name)
+    if container.contains(key) {
+        return key
+    }
+    return formerly.map { HumbleCodingKey($0) }.first { container.contains($0) } ?? key
+}
@@ -0,0 +1,102 @@
+//! Checking whether generated code is up to date, e.g. in CI before committed code is diffed.
+//!
+//! Code generation is deterministic: identical specs and backend configurations yield
+//! byte-for-byte identical files across runs and platforms, with `\n` line endings. So
+//! regenerating code into a temporary directory and comparing it with the output reveals
+//! every file that a regeneration would change:
+//! ```no_run
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! let spec = humblegen::parse_file("protocol.humble")?;
+//! let generator = humblegen::backend::docs::Generator::default();
+//! for file in humblegen::check::check(&generator, &spec, "docs.html".as_ref())? {
+//!     eprintln!("{}", file);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Files of the output that the backend does not generate are ignored.
+
+use crate::testing::{self, GeneratedFiles};
+use crate::{workspace::Workspace, CodeGenerator, LibError, Spec};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file of the output that regenerating it would change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutdatedFile {
+    /// The file does not exist.
+    Missing(PathBuf),
+    /// The file exists with different contents.
+    Changed(PathBuf),
+}
+
+impl OutdatedFile {
+    /// The path of the file.
+    pub fn path(&self) -> &Path {
+        match self {
+            OutdatedFile::Missing(path) | OutdatedFile::Changed(path) => path,
+        }
+    }
+}
+
+impl fmt::Display for OutdatedFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutdatedFile::Missing(path) => write!(f, "missing: {}", path.display()),
+            OutdatedFile::Changed(path) => write!(f, "changed: {}", path.display()),
+        }
+    }
+}
+
+/// Check whether the code generated by `generator` for `spec` at `output` is up to date,
+/// returning the outdated files.
+pub fn check(
+    generator: &dyn CodeGenerator,
+    spec: &Spec,
+    output: &Path,
+) -> Result<Vec<OutdatedFile>, LibError> {
+    let files = testing::generate_files(generator, spec)?;
+    if files.len() == 1 && files.contains_key(testing::SINGLE_FILE) {
+        let contents = &files[testing::SINGLE_FILE];
+        return Ok(outdated_file(output, contents)?.into_iter().collect());
+    }
+    outdated_files(&files, output)
+}
+
+/// Like `check`, for the code generated for `workspace` into the `output` folder.
+pub fn check_workspace(
+    generator: &dyn CodeGenerator,
+    workspace: &Workspace,
+    output: &Path,
+) -> Result<Vec<OutdatedFile>, LibError> {
+    let files = testing::generate_workspace_files(generator, workspace)?;
+    outdated_files(&files, output)
+}
+
+fn outdated_files(files: &GeneratedFiles, output: &Path) -> Result<Vec<OutdatedFile>, LibError> {
+    let mut outdated = vec![];
+    for (relative, contents) in files {
+        outdated.extend(outdated_file(&output_path(output, relative), contents)?);
+    }
+    Ok(outdated)
+}
+
+/// The path of the generated file at the `/`-separated `relative` path below `output`.
+pub(crate) fn output_path(output: &Path, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .fold(output.to_owned(), |path, component| path.join(component))
+}
+
+pub(crate) fn outdated_file(path: &Path, contents: &str) -> Result<Option<OutdatedFile>, LibError> {
+    match fs::read(path) {
+        Ok(existing) if existing == contents.as_bytes() => Ok(None),
+        Ok(_) => Ok(Some(OutdatedFile::Changed(path.to_owned()))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Ok(Some(OutdatedFile::Missing(path.to_owned())))
+        }
+        Err(e) => Err(LibError::file(path)(e)),
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::{self, Result};
-use std::{ops::Deref, path, str};
-use structopt::StructOpt;
+use std::{fmt, ops::Deref, path, str};
+use structopt::{clap::AppSettings, StructOpt};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +9,28 @@ pub enum CliError {
     UnknownBackend(String),
     #[error("unknown output artifact '{0}'")]
     UnknownArtifact(String),
+    #[error("unknown handler trait style '{0}'")]
+    UnknownHandlerTraitStyle(String),
+    #[error("unknown derive '{0}', expected `PartialEq`, `Eq`, `Hash`, `PartialOrd`, `Ord` or `Default`")]
+    UnknownDerive(String),
+    #[error("unknown wire casing '{0}', expected `spec`, `snake_case`, `camelCase` or `SCREAMING_SNAKE_CASE`")]
+    UnknownWireCasing(String),
+    #[error("unknown map representation '{0}', expected `object` or `entries`")]
+    UnknownMapRepresentation(String),
+    #[error("unknown large integer representation '{0}', expected `number` or `string`")]
+    UnknownLargeIntRepresentation(String),
+    #[error("unknown enum representation '{0}', expected `external` or `adjacent`")]
+    UnknownEnumRepresentation(String),
+    #[error("unknown highlight format '{0}', expected `ansi` or `html`")]
+    UnknownHighlightFormat(String),
+    #[error("invalid environment '{0}': {1}")]
+    InvalidEnvironment(String, String),
+    #[error("environment `{0}` is given twice")]
+    DuplicateEnvironment(String),
+    #[error("invalid elm package '{0}': {1}")]
+    InvalidElmPackage(String, String),
+    #[error("invalid rust crate '{0}': {1}")]
+    InvalidRustCrate(String, String),
     #[error(transparent)]
     LibraryError(#[from] humblegen::LibError),
 }
@@ -18,6 +40,12 @@ pub enum Backend {
     Rust,
     Elm,
     Docs,
+    Protobuf,
+    Graphql,
+    Kotlin,
+    Swift,
+    /// An external command, see `--plugin`.
+    Plugin,
 }
 
 impl str::FromStr for Backend {
@@ -28,12 +56,32 @@ impl str::FromStr for Backend {
             "RUST" => Ok(Backend::Rust),
             "ELM" => Ok(Backend::Elm),
             "DOCS" | "DOC" | "DOCUMENTATION" => Ok(Backend::Docs),
+            "PROTOBUF" | "PROTO" => Ok(Backend::Protobuf),
+            "GRAPHQL" => Ok(Backend::Graphql),
+            "KOTLIN" | "KT" => Ok(Backend::Kotlin),
+            "SWIFT" => Ok(Backend::Swift),
+            "PLUGIN" => Ok(Backend::Plugin),
             _ => Err(CliError::UnknownBackend(s.to_string())),
         }
     }
 }
 
-#[derive(Default)]
+impl Backend {
+    /// The name of the backend in progress reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Rust => "rust",
+            Backend::Elm => "elm",
+            Backend::Docs => "docs",
+            Backend::Protobuf => "protobuf",
+            Backend::Graphql => "graphql",
+            Backend::Kotlin => "kotlin",
+            Backend::Swift => "swift",
+            Backend::Plugin => "plugin",
+        }
+    }
+}
+
 pub(crate) struct Artifact(humblegen::Artifact);
 
 impl str::FromStr for Artifact {
@@ -44,25 +92,289 @@ impl str::FromStr for Artifact {
             "TYPES" => Ok(Artifact(humblegen::Artifact::TypesOnly)),
             "CLIENT" => Ok(Artifact(humblegen::Artifact::ClientEndpoints)),
             "SERVER" => Ok(Artifact(humblegen::Artifact::ServerEndpoints)),
+            "CLIENT-AND-SERVER" => Ok(Artifact(humblegen::Artifact::ClientAndServer)),
+            "BENCHMARKS" => Ok(Artifact(humblegen::Artifact::Benchmarks)),
+            "MOCK-SERVER" => Ok(Artifact(humblegen::Artifact::MockServer)),
             _ => Err(CliError::UnknownArtifact(s.to_string())),
         }
     }
 }
 
-// This impl is necessary allow the usage of the structopt default_value attribute
-impl ToString for Artifact {
-    fn to_string(&self) -> String {
-        match self.0 {
-            // These strings have to match the ones in str::FromString
-            humblegen::Artifact::TypesOnly => "TYPES".to_string(),
-            humblegen::Artifact::ClientEndpoints => "CLIENT".to_string(),
-            humblegen::Artifact::ServerEndpoints => "SERVER".to_string(),
+impl Deref for Artifact {
+    type Target = humblegen::Artifact;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct HandlerTraitStyle(humblegen::backend::rust::HandlerTraitStyle);
+
+impl str::FromStr for HandlerTraitStyle {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use humblegen::backend::rust::HandlerTraitStyle as Style;
+        match s.to_uppercase().as_str() {
+            "ASYNC-TRAIT" => Ok(HandlerTraitStyle(Style::AsyncTrait)),
+            "BOXED-FUTURE" => Ok(HandlerTraitStyle(Style::BoxedFuture)),
+            _ => Err(CliError::UnknownHandlerTraitStyle(s.to_string())),
         }
     }
 }
 
-impl Deref for Artifact {
-    type Target = humblegen::Artifact;
+// `Display` is necessary to allow the usage of the structopt `default_value` attribute, which
+// prints the default in `--help` and parses it with `FromStr`, so both use the same tokens.
+impl fmt::Display for HandlerTraitStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use humblegen::backend::rust::HandlerTraitStyle as Style;
+        let printable = match self.0 {
+            Style::AsyncTrait => "async-trait",
+            Style::BoxedFuture => "boxed-future",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+impl Deref for HandlerTraitStyle {
+    type Target = humblegen::backend::rust::HandlerTraitStyle;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+pub(crate) struct Derive(humblegen::ast::Derive);
+
+impl str::FromStr for Derive {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        humblegen::ast::Derive::from_annotation_arg(s)
+            .map(Derive)
+            .ok_or_else(|| CliError::UnknownDerive(s.to_string()))
+    }
+}
+
+impl Deref for Derive {
+    type Target = humblegen::ast::Derive;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct WireCasing(humblegen::WireCasing);
+
+impl str::FromStr for WireCasing {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the casings are only distinguishable case-sensitively
+        match s {
+            "spec" => Ok(WireCasing(humblegen::WireCasing::Spec)),
+            "snake_case" => Ok(WireCasing(humblegen::WireCasing::SnakeCase)),
+            "camelCase" => Ok(WireCasing(humblegen::WireCasing::CamelCase)),
+            "SCREAMING_SNAKE_CASE" => Ok(WireCasing(humblegen::WireCasing::ScreamingSnakeCase)),
+            _ => Err(CliError::UnknownWireCasing(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for WireCasing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for WireCasing {
+    type Target = humblegen::WireCasing;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+pub(crate) struct Environment(humblegen::Environment);
+
+impl str::FromStr for Environment {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| CliError::InvalidEnvironment(s.to_string(), reason);
+        let (name, base_url) = s
+            .split_once('=')
+            .ok_or_else(|| invalid("expected `name=base_url`".to_owned()))?;
+        humblegen::Environment::new(name, base_url)
+            .map(Environment)
+            .map_err(invalid)
+    }
+}
+
+impl Deref for Environment {
+    type Target = humblegen::Environment;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+pub(crate) struct ElmPackage(humblegen::backend::elm::Package);
+
+impl str::FromStr for ElmPackage {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        humblegen::backend::elm::Package::new(s)
+            .map(ElmPackage)
+            .map_err(|reason| CliError::InvalidElmPackage(s.to_string(), reason))
+    }
+}
+
+impl Deref for ElmPackage {
+    type Target = humblegen::backend::elm::Package;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+pub(crate) struct RustCrate(humblegen::backend::rust::Crate);
+
+impl str::FromStr for RustCrate {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        humblegen::backend::rust::Crate::new(s)
+            .map(RustCrate)
+            .map_err(|reason| CliError::InvalidRustCrate(s.to_string(), reason))
+    }
+}
+
+impl Deref for RustCrate {
+    type Target = humblegen::backend::rust::Crate;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MapRepresentation(humblegen::MapRepresentation);
+
+impl str::FromStr for MapRepresentation {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "object" => Ok(MapRepresentation(humblegen::MapRepresentation::Object)),
+            "entries" => Ok(MapRepresentation(humblegen::MapRepresentation::Entries)),
+            _ => Err(CliError::UnknownMapRepresentation(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for MapRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for MapRepresentation {
+    type Target = humblegen::MapRepresentation;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct LargeIntRepresentation(humblegen::LargeIntRepresentation);
+
+impl str::FromStr for LargeIntRepresentation {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "number" => Ok(LargeIntRepresentation(
+                humblegen::LargeIntRepresentation::Number,
+            )),
+            "string" => Ok(LargeIntRepresentation(
+                humblegen::LargeIntRepresentation::String,
+            )),
+            _ => Err(CliError::UnknownLargeIntRepresentation(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for LargeIntRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for LargeIntRepresentation {
+    type Target = humblegen::LargeIntRepresentation;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct EnumRepresentation(humblegen::EnumRepresentation);
+
+impl str::FromStr for EnumRepresentation {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "external" => Ok(EnumRepresentation(humblegen::EnumRepresentation::External)),
+            "adjacent" => Ok(EnumRepresentation(humblegen::EnumRepresentation::Adjacent)),
+            _ => Err(CliError::UnknownEnumRepresentation(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for EnumRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for EnumRepresentation {
+    type Target = humblegen::EnumRepresentation;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct HighlightFormat(humblegen::highlight::Format);
+
+impl str::FromStr for HighlightFormat {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ansi" => Ok(HighlightFormat(humblegen::highlight::Format::Ansi)),
+            "html" => Ok(HighlightFormat(humblegen::highlight::Format::Html)),
+            _ => Err(CliError::UnknownHighlightFormat(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for HighlightFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for HighlightFormat {
+    type Target = humblegen::highlight::Format;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -72,45 +384,362 @@ impl Deref for Artifact {
 /// Command-line arguments
 // TODO: turn into enum separating language backends from docs backend, docs backend does not need a gen_server and gen_client field
 #[derive(StructOpt)]
-#[structopt(about = "generate code from humble protocol spec")]
+#[structopt(
+    about = "generate code from humble protocol spec",
+    usage = "humblegen [OPTIONS] <input> (--language <backend> --output <output>)...\n    humblegen <SUBCOMMAND>",
+    setting = AppSettings::SubcommandsNegateReqs,
+    setting = AppSettings::ArgsNegateSubcommands,
+    setting = AppSettings::VersionlessSubcommands
+)]
 pub(crate) struct CliArgs {
-    /// language to generate code for
-    #[structopt(short = "l", long = "language")]
-    pub(crate) backend: Backend,
-    /// generate REST endpoints for a server
-    #[structopt(short = "a", long = "artifacts", default_value)]
-    pub(crate) artifacts: Artifact,
-    /// input path to humble file
-    pub(crate) input: path::PathBuf,
-    /// input path to humble file
-    #[structopt(short = "o", long = "output")]
-    pub(crate) output: path::PathBuf,
+    /// language to generate code for (repeatable, paired with the outputs in order, backends run in parallel)
+    #[structopt(short = "l", long = "language", number_of_values = 1)]
+    pub(crate) backends: Vec<Backend>,
+    /// artifacts to generate, `TYPES` by default (once for all backends, or once per backend)
+    #[structopt(short = "a", long = "artifacts", number_of_values = 1)]
+    pub(crate) artifacts: Vec<Artifact>,
+    /// input path to humble file, or to workspace config with `--workspace`
+    pub(crate) input: Option<path::PathBuf>,
+    /// treat input as AST JSON written by `dump-ast` instead of a humble file, e.g. to cache parsing
+    #[structopt(long)]
+    pub(crate) ast: bool,
+    /// treat input as workspace config listing several specs, output must be a folder
+    #[structopt(short = "w", long)]
+    pub(crate) workspace: bool,
+    /// output path of the code of the corresponding `--language` (repeatable)
+    #[structopt(short = "o", long = "output", number_of_values = 1)]
+    pub(crate) outputs: Vec<path::PathBuf>,
     /// prefix to be used in elm module declarations
     #[structopt(long, default_value = "\"Api\"")]
     pub(crate) elm_module_root: String,
+    /// generate an elm `Route` module parsing URLs into the GET routes of the services
+    #[structopt(long)]
+    pub(crate) elm_route_parser: bool,
+    /// scaffold the elm output as package `author/project`, with an `elm.json` declaring the packages the generated code depends on and the modules in its `src` folder
+    #[structopt(long)]
+    pub(crate) elm_package: Option<ElmPackage>,
+    /// package declared in generated protobuf files
+    #[structopt(long, default_value = "api")]
+    pub(crate) protobuf_package: String,
+    /// package declared in generated kotlin files
+    #[structopt(long, default_value = "api")]
+    pub(crate) kotlin_package: String,
+    /// how rust handler trait methods are declared (`async-trait` or `boxed-future`)
+    #[structopt(long, default_value)]
+    pub(crate) rust_handler_trait_style: HandlerTraitStyle,
+    /// do not run the requests to each route of rust servers within a `tracing` span named after the service and the route
+    #[structopt(long)]
+    pub(crate) rust_no_tracing_spans: bool,
+    /// derive `proptest::arbitrary::Arbitrary` for all rust types, requires the `arbitrary` feature of `humblegen-rt`
+    #[structopt(long)]
+    pub(crate) rust_arbitrary: bool,
+    /// trait to derive for all rust types besides those annotated with `@derive`, e.g. `PartialEq` (repeatable, one of `PartialEq`, `Eq`, `Hash`, `PartialOrd`, `Ord` or `Default`)
+    #[structopt(long = "rust-derive", number_of_values = 1)]
+    pub(crate) rust_derives: Vec<Derive>,
+    /// scaffold the rust output as crate `name`, with a `Cargo.toml` depending on `humblegen-rt` and the code in its `src` folder, the client code behind its `client` and the server code behind its `server` feature
+    #[structopt(long)]
+    pub(crate) rust_crate: Option<RustCrate>,
+    /// casing of field and variant names on the wire in rust and elm code (`spec`, `snake_case`, `camelCase` or `SCREAMING_SNAKE_CASE`)
+    #[structopt(long, default_value)]
+    pub(crate) wire_casing: WireCasing,
+    /// representation of maps whose keys are not `str` in rust, elm, kotlin and swift code (`object` or `entries`, i.e., arrays of `[key, value]` pairs)
+    #[structopt(long, default_value)]
+    pub(crate) map_representation: MapRepresentation,
+    /// representation of `i64` and `u64` in rust and elm code (`number`, or `string` protecting large values from being rounded by JavaScript consumers)
+    #[structopt(long, default_value)]
+    pub(crate) large_int_representation: LargeIntRepresentation,
+    /// representation of enums without `@tag` or `@untagged` in rust, elm, kotlin and swift code (`external`, or `adjacent`, i.e., `{"tag": ..., "contents": ...}` for enums with complex variants)
+    #[structopt(long, default_value)]
+    pub(crate) enum_representation: EnumRepresentation,
+    /// environment of rust and elm clients with the base URL of the services deployed there, e.g. `prod=https://api.example.com` (repeatable)
+    #[structopt(long = "env", number_of_values = 1)]
+    pub(crate) environments: Vec<Environment>,
+    /// command transforming the spec before code generation, reading and writing it as AST JSON on stdin and stdout, e.g. `python3 add_audit_fields.py` (repeatable, run in order)
+    #[structopt(long = "transform", number_of_values = 1)]
+    pub(crate) transforms: Vec<String>,
+    /// remove the definitions not reachable from the services and the types annotated with `@export` before code generation, for all backends (see the `unused` subcommand)
+    #[structopt(long)]
+    pub(crate) prune_unused: bool,
+    /// command of a custom backend, run for the corresponding `--language plugin`, reading the spec as AST JSON on stdin and writing the generated files as JSON to stdout, e.g. `python3 csharp.py` (repeatable)
+    #[structopt(long = "plugin", number_of_values = 1)]
+    pub(crate) plugins: Vec<String>,
+    /// report parsed specs and written files on stderr, as progress bar if stderr is a terminal
+    #[structopt(long)]
+    pub(crate) progress: bool,
+    /// do not write the output, but fail if regenerating it would change any file, e.g. to check committed code in CI
+    #[structopt(long)]
+    pub(crate) check: bool,
+    /// only write the generated files whose contents changed, keeping the modification times of the others for incremental compilers downstream, and print the written files on stderr
+    #[structopt(long)]
+    pub(crate) incremental: bool,
+    /// fail if a backend maps a type lossily, e.g. `u32` to elm's `Int`, unless accepted with `@lossy(type)` where it is used
+    #[structopt(long)]
+    pub(crate) strict: bool,
+    /// print the size of the generated code by file and definition, with hints on reducing it, e.g. for bundle budgets (not with `--workspace`)
+    #[structopt(long)]
+    pub(crate) size_report: bool,
+    #[structopt(subcommand)]
+    pub(crate) command: Option<Command>,
+}
+
+// Subcommands exchanging humble files with a schema registry, testing generated code, and tooling
+#[derive(StructOpt)]
+#[cfg_attr(not(feature = "registry"), allow(dead_code))]
+pub(crate) enum Command {
+    /// generate random values of all types of a humble file as fixtures, and elm tests decoding and re-encoding them
+    TestConformance {
+        /// input path to humble file
+        input: path::PathBuf,
+        /// output folder of the fixtures and tests
+        #[structopt(short = "o", long = "output")]
+        output: path::PathBuf,
+        /// seed of the random values, which are reproducible from it
+        #[structopt(long, default_value = "0")]
+        seed: u64,
+        /// number of values per type
+        #[structopt(long, default_value = "20")]
+        count: usize,
+        /// prefix of the elm modules under test
+        #[structopt(long, default_value = "Api")]
+        elm_module_root: String,
+        /// representation of `i64` and `u64` in the elm code under test (`number` or `string`)
+        #[structopt(long, default_value)]
+        large_int_representation: LargeIntRepresentation,
+        /// representation of enums without `@tag` or `@untagged` in the elm code under test (`external` or `adjacent`)
+        #[structopt(long, default_value)]
+        enum_representation: EnumRepresentation,
+    },
+    /// print the AST of a humble file, with includes and embeds resolved, as versioned JSON
+    DumpAst {
+        /// input path to humble file
+        input: path::PathBuf,
+        /// output path of the JSON, stdout by default
+        #[structopt(short = "o", long = "output")]
+        output: Option<path::PathBuf>,
+    },
+    /// print the wire hash of each type of a humble file as JSON manifest, see the `WIRE_MANIFEST` of rust code
+    WireManifest {
+        /// input path to humble file
+        input: path::PathBuf,
+        /// output path of the JSON, stdout by default
+        #[structopt(short = "o", long = "output")]
+        output: Option<path::PathBuf>,
+        /// casing of field and variant names on the wire (`spec`, `snake_case`, `camelCase` or `SCREAMING_SNAKE_CASE`)
+        #[structopt(long, default_value)]
+        wire_casing: WireCasing,
+        /// representation of maps whose keys are not `str` (`object` or `entries`)
+        #[structopt(long, default_value)]
+        map_representation: MapRepresentation,
+        /// representation of `i64` and `u64` (`number` or `string`)
+        #[structopt(long, default_value)]
+        large_int_representation: LargeIntRepresentation,
+        /// representation of enums without `@tag` or `@untagged` (`external` or `adjacent`)
+        #[structopt(long, default_value)]
+        enum_representation: EnumRepresentation,
+    },
+    /// print the definitions of a humble file not reachable from its services and the types annotated with `@export`, one per line
+    Unused {
+        /// input path to humble file
+        input: path::PathBuf,
+    },
+    /// print a humble file with syntax highlighting, as colored text or HTML
+    Highlight {
+        /// input path to humble file, or snippet thereof
+        input: path::PathBuf,
+        /// output format (`ansi` or `html`)
+        #[structopt(long, default_value)]
+        format: HighlightFormat,
+        /// output path, stdout by default
+        #[structopt(short = "o", long = "output")]
+        output: Option<path::PathBuf>,
+    },
+    /// run a language server for humble files on stdin and stdout, e.g. for editors
+    Lsp,
+    /// validate a humble file and upload it with its includes as AST JSON to a schema registry
+    Publish {
+        /// input path to humble file
+        input: path::PathBuf,
+        /// base URL of the schema registry
+        #[structopt(long)]
+        registry: String,
+        /// name to publish the spec under
+        #[structopt(long)]
+        name: String,
+        /// version to publish the spec as
+        #[structopt(long)]
+        version: String,
+    },
+    /// download a spec as AST JSON from a schema registry, e.g. for `--ast`
+    Pull {
+        /// base URL of the schema registry
+        #[structopt(long)]
+        registry: String,
+        /// name of the spec
+        #[structopt(long)]
+        name: String,
+        /// version of the spec
+        #[structopt(long)]
+        version: String,
+        /// output path of the AST JSON file
+        #[structopt(short = "o", long = "output")]
+        output: path::PathBuf,
+    },
+}
+
+/// A backend to run, with its artifacts and output.
+pub(crate) struct Target<'a> {
+    pub(crate) backend: Backend,
+    pub(crate) artifacts: humblegen::Artifact,
+    pub(crate) output: &'a path::Path,
+    /// The command of `Backend::Plugin`.
+    pub(crate) plugin: Option<&'a str>,
 }
 
 impl CliArgs {
+    /// The `input` argument and the backends to run, which are required unless a subcommand is
+    /// given.
+    ///
+    /// Exits with a usage error if one of them is missing, or if the numbers of `--language`,
+    /// `--output` and `--artifacts` arguments, or of `--language plugin` and `--plugin`
+    /// arguments, do not match.
+    pub fn generate_args(&self) -> (&path::Path, Vec<Target<'_>>) {
+        fn usage_error(description: &str, kind: structopt::clap::ErrorKind) -> ! {
+            structopt::clap::Error::with_description(description, kind).exit()
+        }
+        fn require<T>(value: Option<T>, arg: &str) -> T {
+            value.unwrap_or_else(|| {
+                usage_error(
+                    &format!("The following required argument was not provided: {}", arg),
+                    structopt::clap::ErrorKind::MissingRequiredArgument,
+                )
+            })
+        }
+
+        let input = require(self.input.as_deref(), "<input>");
+        require(self.backends.first(), "--language <backend>");
+        require(self.outputs.first(), "--output <output>");
+        if self.backends.len() != self.outputs.len() {
+            usage_error(
+                "Every --language <backend> requires an --output <output>",
+                structopt::clap::ErrorKind::WrongNumberOfValues,
+            );
+        }
+        let artifacts: Vec<humblegen::Artifact> = match self.artifacts.len() {
+            0 => vec![Default::default(); self.backends.len()],
+            1 => vec![*self.artifacts[0]; self.backends.len()],
+            n if n == self.backends.len() => self.artifacts.iter().map(|a| **a).collect(),
+            _ => usage_error(
+                "--artifacts must be given once for all backends, or once per --language <backend>",
+                structopt::clap::ErrorKind::WrongNumberOfValues,
+            ),
+        };
+        let num_plugins = self
+            .backends
+            .iter()
+            .filter(|&&backend| backend == Backend::Plugin)
+            .count();
+        if num_plugins != self.plugins.len() {
+            usage_error(
+                "Every --language plugin requires a --plugin <command>",
+                structopt::clap::ErrorKind::WrongNumberOfValues,
+            );
+        }
+        let mut plugins = self.plugins.iter();
+        let targets = self
+            .backends
+            .iter()
+            .zip(artifacts)
+            .zip(&self.outputs)
+            .map(|((&backend, artifacts), output)| Target {
+                backend,
+                artifacts,
+                output,
+                plugin: match backend {
+                    Backend::Plugin => plugins.next().map(String::as_str),
+                    _ => None,
+                },
+            })
+            .collect();
+        (input, targets)
+    }
+
     /// Dynamcally select and instantiate the correct backend for the given
-    /// command-line arguments.
+    /// command-line arguments and `target`.
     ///
     /// Might fail because the backend cannot fulfill the request. For example,
     /// requesting server endpoints for elm -- a client-side programming language --
     /// will result in an error.
-    pub fn code_generator(&self) -> Result<Box<dyn humblegen::CodeGenerator>, CliError> {
-        match self.backend {
+    pub fn code_generator(
+        &self,
+        target: &Target,
+    ) -> Result<Box<dyn humblegen::CodeGenerator>, CliError> {
+        let mut environments: Vec<humblegen::Environment> = Vec::new();
+        for environment in &self.environments {
+            if environments.iter().any(|env| env.name == environment.name) {
+                return Err(CliError::DuplicateEnvironment(environment.name.clone()));
+            }
+            environments.push((**environment).clone());
+        }
+        match target.backend {
             Backend::Rust => Ok(Box::new(
-                humblegen::backend::rust::Generator::new(*self.artifacts)
-                    .map_err(CliError::LibraryError)?,
+                humblegen::backend::rust::Generator::new(target.artifacts)
+                    .map_err(CliError::LibraryError)?
+                    .with_handler_trait_style(*self.rust_handler_trait_style)
+                    .with_tracing_spans(!self.rust_no_tracing_spans)
+                    .with_arbitrary_impls(self.rust_arbitrary)
+                    .with_derives(self.rust_derives.iter().map(|derive| **derive).collect())
+                    .with_wire_casing(*self.wire_casing)
+                    .with_map_representation(*self.map_representation)
+                    .with_large_int_representation(*self.large_int_representation)
+                    .with_enum_representation(*self.enum_representation)
+                    .with_environments(environments)
+                    .with_crate(self.rust_crate.as_ref().map(|krate| (**krate).clone())),
             )),
             Backend::Elm => Ok(Box::new(
                 humblegen::backend::elm::Generator::new(
-                    *self.artifacts,
+                    target.artifacts,
                     self.elm_module_root.clone(),
                 )
-                .map_err(CliError::LibraryError)?,
+                .map_err(CliError::LibraryError)?
+                .with_route_parser(self.elm_route_parser)
+                .with_package(self.elm_package.as_ref().map(|package| (**package).clone()))
+                .with_wire_casing(*self.wire_casing)
+                .with_map_representation(*self.map_representation)
+                .with_large_int_representation(*self.large_int_representation)
+                .with_enum_representation(*self.enum_representation)
+                .with_environments(environments),
             )),
             Backend::Docs => Ok(Box::new(humblegen::backend::docs::Generator::default())),
+            Backend::Protobuf => Ok(Box::new(
+                humblegen::backend::protobuf::Generator::new(
+                    target.artifacts,
+                    self.protobuf_package.clone(),
+                )
+                .map_err(CliError::LibraryError)?,
+            )),
+            Backend::Graphql => Ok(Box::new(
+                humblegen::backend::graphql::Generator::new(target.artifacts)
+                    .map_err(CliError::LibraryError)?,
+            )),
+            Backend::Kotlin => Ok(Box::new(
+                humblegen::backend::kotlin::Generator::new(
+                    target.artifacts,
+                    self.kotlin_package.clone(),
+                )
+                .map_err(CliError::LibraryError)?
+                .with_map_representation(*self.map_representation)
+                .with_enum_representation(*self.enum_representation),
+            )),
+            Backend::Swift => Ok(Box::new(
+                humblegen::backend::swift::Generator::new(target.artifacts)
+                    .map_err(CliError::LibraryError)?
+                    .with_map_representation(*self.map_representation)
+                    .with_enum_representation(*self.enum_representation),
+            )),
+            Backend::Plugin => Ok(Box::new(humblegen::plugin::Plugin::from_command_line(
+                target.plugin.expect("plugin targets have a command"),
+                target.artifacts,
+            ))),
         }
     }
 }
@@ -0,0 +1,3 @@
+//! Building blocks shared by the backends.
+
+pub mod printer;
@@ -0,0 +1,234 @@
+//! Pretty-printer for generated code, managing indentation, blank lines and line width.
+//!
+//! Line breaks are emitted lazily, i.e., only once the next line has content. Thus, requesting
+//! blank lines twice does not stack them up, lines never end in whitespace, and generated files
+//! end with exactly one newline:
+//! ```
+//! use humblegen::codegen::printer::Printer;
+//!
+//! let mut p = Printer::new();
+//! p.line("type Color");
+//! p.with_indent(|p| {
+//!     p.line("= Red");
+//!     p.line("| Blue");
+//! });
+//! p.blank_lines(2);
+//! p.blank_lines(2);
+//! p.line("default = Red");
+//! assert_eq!(
+//!     p.finish(),
+//!     "type Color\n    = Red\n    | Blue\n\n\ndefault = Red\n"
+//! );
+//! ```
+
+use crate::LibError;
+use std::fmt;
+use std::path::Path;
+
+/// The maximum width of lines, unless configured with `Printer::with_max_width`.
+pub const DEFAULT_MAX_WIDTH: usize = 100;
+
+/// Where delimited lists broken over several lines put their separators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separators {
+    /// At the start of each line but the first, e.g. in Elm lists and records.
+    Leading,
+    /// At the end of each line, e.g. in Kotlin or Swift argument lists.
+    Trailing,
+}
+
+/// Writes generated code into an in-memory buffer, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct Printer {
+    out: String,
+    indent: usize,
+    indent_width: usize,
+    max_width: usize,
+    /// Line breaks to emit before the next content.
+    pending_newlines: usize,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Self {
+            out: String::new(),
+            indent: 0,
+            indent_width: 4,
+            max_width: DEFAULT_MAX_WIDTH,
+            pending_newlines: 0,
+        }
+    }
+}
+
+impl Printer {
+    /// A printer indenting by 4 spaces and wrapping lines at `DEFAULT_MAX_WIDTH`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indent by `indent_width` spaces per level.
+    pub fn with_indent_width(mut self, indent_width: usize) -> Self {
+        self.indent_width = indent_width;
+        self
+    }
+
+    /// Wrap delimited lists exceeding `max_width` columns, see `delimited`.
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Append `text` to the current line. Line breaks in `text` start new lines at the current
+    /// indentation.
+    pub fn write(&mut self, text: impl fmt::Display) {
+        let text = text.to_string();
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.pending_newlines += 1;
+            }
+            let line = line.trim_end_matches('\r');
+            if !line.is_empty() {
+                self.flush_newlines();
+                self.out.push_str(line);
+            }
+        }
+    }
+
+    /// Start a new line at the current indentation with `text`.
+    pub fn line(&mut self, text: impl fmt::Display) {
+        self.newline();
+        self.write(text);
+    }
+
+    /// End the current line, unless it is empty.
+    pub fn newline(&mut self) {
+        if !self.out.is_empty() {
+            self.pending_newlines = self.pending_newlines.max(1);
+        }
+    }
+
+    /// Separate the next content by exactly `num` blank lines from the previous content.
+    pub fn blank_lines(&mut self, num: usize) {
+        if !self.out.is_empty() {
+            self.pending_newlines = num + 1;
+        }
+    }
+
+    /// Run `f` with the indentation increased by one level, for lines started by `f`.
+    pub fn with_indent<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.indent += 1;
+        let result = f(self);
+        self.indent -= 1;
+        result
+    }
+
+    /// The column the next content on the current line starts at.
+    pub fn column(&self) -> usize {
+        if self.pending_newlines > 0 || self.out.is_empty() {
+            return self.indent * self.indent_width;
+        }
+        let line_start = self.out.rfind('\n').map_or(0, |i| i + 1);
+        self.out[line_start..].chars().count()
+    }
+
+    /// Whether `text` fits onto the current line.
+    pub fn fits(&self, text: &str) -> bool {
+        self.column() + text.chars().count() <= self.max_width
+    }
+
+    /// Write `items` between `open` and `close`, separated by `separator`, onto the current line
+    /// if it fits, e.g. `[ a, b ]`. Otherwise, each item goes onto its own line, e.g.
+    /// ```text
+    /// [ a
+    /// , b
+    /// ]
+    /// ```
+    /// with `Separators::Leading`, indented by one level unless the current line is empty.
+    pub fn delimited(
+        &mut self,
+        (open, close): (&str, &str),
+        items: &[String],
+        separator: &str,
+        separators: Separators,
+    ) {
+        let single_line = if items.is_empty() {
+            format!("{}{}", open, close)
+        } else {
+            format!(
+                "{} {} {}",
+                open,
+                items.join(&format!("{} ", separator)),
+                close
+            )
+        };
+        if self.fits(&single_line) && !single_line.contains('\n') {
+            self.write(single_line);
+        } else if self.pending_newlines > 0 || self.out.is_empty() {
+            self.delimited_lines((open, close), items, separator, separators);
+        } else {
+            self.with_indent(|p| p.delimited_lines((open, close), items, separator, separators));
+        }
+    }
+
+    /// Write `items` between `open` and `close` like `delimited`, but always one item per line,
+    /// starting with a new line.
+    pub fn delimited_lines(
+        &mut self,
+        (open, close): (&str, &str),
+        items: &[String],
+        separator: &str,
+        separators: Separators,
+    ) {
+        if items.is_empty() {
+            self.line(format!("{}{}", open, close));
+            return;
+        }
+        for (i, item) in items.iter().enumerate() {
+            match separators {
+                Separators::Leading if i == 0 => self.line(format!("{} {}", open, item)),
+                Separators::Leading => self.line(format!("{} {}", separator, item)),
+                Separators::Trailing => {
+                    if i == 0 {
+                        self.line(open);
+                    }
+                    self.with_indent(|p| {
+                        p.line(item);
+                        if i + 1 < items.len() {
+                            p.write(separator);
+                        }
+                    });
+                }
+            }
+        }
+        self.line(close);
+    }
+
+    /// The printed code, ending with exactly one newline unless it is empty.
+    pub fn finish(self) -> String {
+        let mut out = self.out;
+        out.truncate(out.trim_end().len());
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Write the printed code to the file at `path`.
+    pub fn write_to_file(self, path: &Path) -> Result<(), LibError> {
+        std::fs::write(path, self.finish()).map_err(LibError::file(path))
+    }
+
+    fn flush_newlines(&mut self) {
+        if self.pending_newlines == 0 && !self.out.is_empty() {
+            return;
+        }
+        if !self.out.is_empty() {
+            let trimmed_len = self.out.trim_end_matches([' ', '\t', '\r']).len();
+            self.out.truncate(trimmed_len);
+            self.out.push_str(&"\n".repeat(self.pending_newlines));
+        }
+        self.pending_newlines = 0;
+        self.out
+            .push_str(&" ".repeat(self.indent * self.indent_width));
+    }
+}
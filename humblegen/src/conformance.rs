@@ -0,0 +1,423 @@
+//! Cross-language conformance tests of the generated encoders and decoders.
+//!
+//! `generate_corpus` generates random JSON values of every type of a humblespec, encoded like the
+//! Rust backend's serde implementations do, which makes the Rust encoding the reference.
+//! `write_conformance_suite` writes them to fixture files, together with an Elm test module that
+//! decodes every fixture with the generated Elm decoders, re-encodes it with the generated Elm
+//! encoders, and fails if the result differs from the fixture.
+//!
+//! The corpus is reproducible from the `seed` of its `CorpusConfig`.
+
+use crate::{
+    ast, backend::elm, EnumRepresentation, LargeIntRepresentation, LibError, Spec, WireCasing,
+};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// Configuration of a generated corpus.
+#[derive(Debug, Clone)]
+pub struct CorpusConfig {
+    pub seed: u64,
+    /// The number of values generated per type.
+    pub values_per_type: usize,
+    /// How many levels of user-defined types may be nested within lists, maps and options.
+    /// Bounds the size of values of recursive types.
+    pub max_depth: u32,
+    /// The representation of `i64` and `u64`, which must match the code under test. Large
+    /// integers represented as numbers are at most 2^53, which JavaScript represents exactly.
+    pub large_int_representation: LargeIntRepresentation,
+    /// The representation of enums without a `@tag` or `@untagged` annotation, which must match
+    /// the code under test.
+    pub enum_representation: EnumRepresentation,
+}
+
+impl Default for CorpusConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            values_per_type: 20,
+            max_depth: 3,
+            large_int_representation: LargeIntRepresentation::default(),
+            enum_representation: EnumRepresentation::default(),
+        }
+    }
+}
+
+/// Random values of the types of a humblespec.
+#[derive(Debug, Clone)]
+pub struct Corpus {
+    /// One fixture per struct and enum, in the order of the spec.
+    pub fixtures: Vec<Fixture>,
+}
+
+/// Random values of a single user-defined type.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub type_name: String,
+    /// The values, encoded like the Rust backend's serde implementations do.
+    pub values: Vec<Value>,
+}
+
+impl Corpus {
+    /// The fixture of type `type_name`, if any.
+    pub fn fixture(&self, type_name: &str) -> Option<&Fixture> {
+        self.fixtures.iter().find(|f| f.type_name == type_name)
+    }
+}
+
+/// Generate `config.values_per_type` random values of every struct and enum of `spec`.
+///
+/// Enums without variants have no values and thus no fixture.
+pub fn generate_corpus(spec: &Spec, config: &CorpusConfig) -> Corpus {
+    let spec = &config.enum_representation.apply(spec);
+    let mut generator = ValueGenerator {
+        index: spec.index(),
+        rng: SplitMix64(config.seed),
+        large_int_representation: config.large_int_representation,
+    };
+    let fixtures = spec
+        .iter()
+        .filter(|spec_item| match spec_item {
+            ast::SpecItem::StructDef(_)
+            | ast::SpecItem::TupleStructDef(_)
+            | ast::SpecItem::FlagsDef(_) => true,
+            ast::SpecItem::EnumDef(edef) => !edef.variants.is_empty(),
+            ast::SpecItem::ServiceDef(_) => false,
+        })
+        .map(|spec_item| Fixture {
+            type_name: spec_item.name().to_owned(),
+            values: (0..config.values_per_type)
+                .map(|_| generator.user_defined_value(spec_item.name(), config.max_depth))
+                .collect(),
+        })
+        .collect();
+    Corpus { fixtures }
+}
+
+/// Write the corpus of `spec` to folder `output`:
+///
+/// * `fixtures/${TypeName}.json`, a JSON array of the values of each type, and
+/// * `ConformanceTest.elm`, an [elm-test](https://package.elm-lang.org/packages/elm-explorations/test/latest/)
+///   module round-tripping all fixtures through the Elm code generated with module prefix `elm_module_prefix`.
+pub fn write_conformance_suite(
+    spec: &Spec,
+    config: &CorpusConfig,
+    elm_module_prefix: &str,
+    output: &Path,
+) -> Result<(), LibError> {
+    let corpus = generate_corpus(spec, config);
+
+    let fixtures_dir = output.join("fixtures");
+    fs::create_dir_all(&fixtures_dir).map_err(LibError::file(&fixtures_dir))?;
+    for fixture in &corpus.fixtures {
+        let values: Vec<_> = fixture.values.iter().map(Value::to_string).collect();
+        let path = fixtures_dir.join(format!("{}.json", fixture.type_name));
+        fs::write(&path, format!("[\n  {}\n]\n", values.join(",\n  ")))
+            .map_err(LibError::file(&path))?;
+    }
+
+    let path = output.join("ConformanceTest.elm");
+    fs::write(
+        &path,
+        elm::conformance_generation::generate_conformance_tests(&corpus, elm_module_prefix),
+    )
+    .map_err(LibError::file(&path))?;
+    Ok(())
+}
+
+/// Characters of generated strings, including ones that need escaping in JSON.
+const STRING_CHARS: &[char] = &[
+    'a', 'b', 'c', 'x', 'y', 'z', 'A', 'Z', '0', '9', ' ', '-', '_', '"', '\\', '/', '\n', '\t',
+    '\u{1}', 'ä', '€', '☃', '😀',
+];
+
+/// The largest number of elements of generated lists and maps.
+const MAX_COLLECTION_SIZE: u64 = 4;
+
+struct ValueGenerator<'a> {
+    index: ast::SpecIndex<'a>,
+    rng: SplitMix64,
+    large_int_representation: LargeIntRepresentation,
+}
+
+impl ValueGenerator<'_> {
+    /// A value of user-defined type `name` at `depth`, see `CorpusConfig::max_depth`.
+    fn user_defined_value(&mut self, name: &str, depth: u32) -> Value {
+        match self.index.get(name) {
+            Some(ast::SpecItem::StructDef(sdef)) => {
+                // only fields of structs are base64-encoded `bytes`, see `rust::generate_field_attributes`
+                self.fields_value(&sdef.fields, depth, true)
+            }
+            Some(ast::SpecItem::TupleStructDef(tdef)) => {
+                // like fields, the elements of tuple structs are base64-encoded `bytes`
+                let mut elements = tdef.elements.elements().iter().map(|ty| match ty {
+                    ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) => {
+                        Value::String(base64::encode(self.bytes()))
+                    }
+                    ty => self.value(ty, depth),
+                });
+                if tdef.is_newtype() {
+                    elements.next().unwrap()
+                } else {
+                    Value::Array(elements.collect())
+                }
+            }
+            Some(ast::SpecItem::EnumDef(edef)) => self.enum_value(edef, depth),
+            // the names of a random subset of the flags, in order like the Rust backend
+            Some(ast::SpecItem::FlagsDef(fdef)) => Value::Array(
+                fdef.flags
+                    .iter()
+                    .filter(|_| self.rng.below(2) == 0)
+                    .map(|flag| Value::String(flag.wire_name(WireCasing::Spec)))
+                    .collect(),
+            ),
+            _ => panic!("undefined type {}", name),
+        }
+    }
+
+    fn fields_value(
+        &mut self,
+        fields: &ast::StructFields,
+        depth: u32,
+        base64_bytes: bool,
+    ) -> Value {
+        let mut object = Map::new();
+        for field in fields.iter() {
+            // absent `optional` fields are omitted, like serde does for `None`
+            if field.optional && self.rng.below(2) == 0 {
+                continue;
+            }
+            let value = match &field.pair.type_ident {
+                ast::TypeIdent::BuiltIn(ast::AtomType::Bytes) if base64_bytes => {
+                    Value::String(base64::encode(self.bytes()))
+                }
+                ty => self.value(ty, depth),
+            };
+            object.insert(field.wire_name(WireCasing::Spec), value);
+        }
+        Value::Object(object)
+    }
+
+    /// A value of an enum, tagged like serde does.
+    fn enum_value(&mut self, edef: &ast::EnumDef, depth: u32) -> Value {
+        let index = self.rng.below(edef.variants.len() as u64) as usize;
+        if edef.is_numeric() {
+            return Value::from(edef.discriminants()[index]);
+        }
+        let variant = &edef.variants[index];
+        let wire_name = variant.wire_name(WireCasing::Spec);
+        let content = match &variant.variant_type {
+            ast::VariantType::Simple => None,
+            ast::VariantType::Newtype(ty) => Some(self.value(ty, depth)),
+            ast::VariantType::Tuple(tdef) if tdef.elements().len() == 1 => {
+                Some(self.value(&tdef.elements()[0], depth))
+            }
+            ast::VariantType::Tuple(tdef) => Some(self.tuple_value(tdef, depth)),
+            ast::VariantType::Struct(fields) => Some(self.fields_value(fields, depth, false)),
+        };
+        match (edef.tagging(), content) {
+            (ast::EnumTagging::External, None) => Value::String(wire_name),
+            (ast::EnumTagging::External, Some(content)) => {
+                let mut object = Map::new();
+                object.insert(wire_name, content);
+                Value::Object(object)
+            }
+            (ast::EnumTagging::Internal { tag }, content) => {
+                // struct variants, the only ones with content
+                let mut object = match content {
+                    Some(Value::Object(fields)) => fields,
+                    _ => Map::new(),
+                };
+                object.insert(tag.to_owned(), Value::String(wire_name));
+                Value::Object(object)
+            }
+            (ast::EnumTagging::Adjacent { tag, content: key }, content) => {
+                let mut object = Map::new();
+                object.insert(tag.to_owned(), Value::String(wire_name));
+                if let Some(content) = content {
+                    object.insert(key.to_owned(), content);
+                }
+                Value::Object(object)
+            }
+            (ast::EnumTagging::Untagged, content) => content.unwrap_or(Value::Null),
+        }
+    }
+
+    fn tuple_value(&mut self, tdef: &ast::TupleDef, depth: u32) -> Value {
+        Value::Array(
+            tdef.elements()
+                .iter()
+                .map(|ty| self.value(ty, depth))
+                .collect(),
+        )
+    }
+
+    /// A value of type `ty`, nested in a value at `depth`.
+    fn value(&mut self, ty: &ast::TypeIdent, depth: u32) -> Value {
+        match ty {
+            ast::TypeIdent::BuiltIn(atom) => self.atom_value(*atom),
+            ast::TypeIdent::List(inner) => {
+                let len = self.collection_size(depth);
+                Value::Array((0..len).map(|_| self.value(inner, depth)).collect())
+            }
+            ast::TypeIdent::Option(inner) => {
+                if depth == 0 || self.rng.below(3) == 0 {
+                    Value::Null
+                } else {
+                    self.value(inner, depth)
+                }
+            }
+            ast::TypeIdent::Result(ok, err) => {
+                let (tag, inner) = if self.rng.below(2) == 0 {
+                    ("Ok", ok)
+                } else {
+                    ("Err", err)
+                };
+                let mut object = Map::new();
+                object.insert(tag.to_owned(), self.value(inner, depth));
+                Value::Object(object)
+            }
+            ast::TypeIdent::Map(_, value) => {
+                // only string keys are supported by all backends
+                let mut object = Map::new();
+                for _ in 0..self.collection_size(depth) {
+                    let key = self.key();
+                    let value = self.value(value, depth);
+                    object.insert(key, value);
+                }
+                Value::Object(object)
+            }
+            ast::TypeIdent::Tuple(tdef) => self.tuple_value(tdef, depth),
+            ast::TypeIdent::UserDefined(name) => {
+                self.user_defined_value(name, depth.saturating_sub(1))
+            }
+        }
+    }
+
+    fn atom_value(&mut self, atom: ast::AtomType) -> Value {
+        let rng = &mut self.rng;
+        match atom {
+            ast::AtomType::Empty => Value::Null,
+            ast::AtomType::Str => Value::String(self.string()),
+            ast::AtomType::I32 => Value::from(rng.next_u64() as i32),
+            ast::AtomType::U32 => Value::from(rng.next_u64() as u32),
+            ast::AtomType::I64 => match self.large_int_representation {
+                LargeIntRepresentation::Number => Value::from(rng.next_u64() as i64 >> 11),
+                LargeIntRepresentation::String => {
+                    Value::String((rng.next_u64() as i64).to_string())
+                }
+            },
+            ast::AtomType::U64 => match self.large_int_representation {
+                LargeIntRepresentation::Number => Value::from(rng.next_u64() >> 11),
+                LargeIntRepresentation::String => Value::String(rng.next_u64().to_string()),
+            },
+            ast::AtomType::U8 => Value::from(rng.next_u64() as u8),
+            ast::AtomType::F64 => {
+                // decimals with few digits, which every language prints without rounding
+                let cents = rng.below(2_000_000) as f64 - 1_000_000.0;
+                Value::from(cents / 100.0)
+            }
+            ast::AtomType::Bool => Value::Bool(rng.below(2) == 0),
+            ast::AtomType::DateTime => {
+                // between 1970 and 2100, with the millisecond precision of Elm's `Time.Posix`;
+                // non-zero milliseconds, since chrono omits zero fractions
+                let secs = rng.below(4_102_444_800);
+                let millis = 1 + rng.below(999);
+                let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+                let secs_of_day = secs % 86_400;
+                Value::String(format!(
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+                    year,
+                    month,
+                    day,
+                    secs_of_day / 3600,
+                    secs_of_day / 60 % 60,
+                    secs_of_day % 60,
+                    millis
+                ))
+            }
+            ast::AtomType::Date => {
+                let (year, month, day) = civil_from_days(rng.below(47_482) as i64);
+                Value::String(format!("{:04}-{:02}-{:02}", year, month, day))
+            }
+            ast::AtomType::Uuid => {
+                let hex = format!("{:016x}{:016x}", rng.next_u64(), rng.next_u64());
+                Value::String(format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..32]
+                ))
+            }
+            // nested `bytes` are serialized by serde as arrays of numbers
+            ast::AtomType::Bytes => Value::from(self.bytes()),
+        }
+    }
+
+    fn collection_size(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            0
+        } else {
+            self.rng.below(MAX_COLLECTION_SIZE + 1)
+        }
+    }
+
+    fn string(&mut self) -> String {
+        let len = self.rng.below(12);
+        (0..len)
+            .map(|_| STRING_CHARS[self.rng.below(STRING_CHARS.len() as u64) as usize])
+            .collect()
+    }
+
+    /// A map key, which does not look like a number, since JavaScript engines order such
+    /// object keys before all others.
+    fn key(&mut self) -> String {
+        let len = 1 + self.rng.below(8);
+        (0..len)
+            .map(|_| (b'a' + self.rng.below(26) as u8) as char)
+            .collect()
+    }
+
+    fn bytes(&mut self) -> Vec<u8> {
+        let len = self.rng.below(16);
+        (0..len).map(|_| self.rng.next_u64() as u8).collect()
+    }
+}
+
+/// The (year, month, day) of the given number of days since 1970-01-01.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// The [SplitMix64](https://prng.di.unimi.it/splitmix64.c) pseudo-random number generator,
+/// whose output is stable across releases, unlike that of `rand`'s generators.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, for `n > 0`.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
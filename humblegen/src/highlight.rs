@@ -0,0 +1,93 @@
+//! Syntax highlighting of specs for terminals and web pages, run by `humblegen highlight`.
+//!
+//! Tokens are classified by `parser::tokens::classify`. HTML is a `<pre>` element with a
+//! `<span class="humble-{kind}">` per token, where `{kind}` is the `TokenKind::name`, e.g.
+//! `humble-keyword`, to be styled by the embedding page:
+//! ```
+//! let html = humblegen::highlight::highlight("struct Monster {}", humblegen::highlight::Format::Html)?;
+//! assert!(html.starts_with(r#"<pre class="humble"><code><span class="humble-keyword">struct</span>"#));
+//! # Ok::<(), humblegen::LibError>(())
+//! ```
+
+use crate::backend::docs::Escape;
+use crate::parser::tokens::{classify, TokenKind};
+use crate::LibError;
+use std::fmt;
+
+/// The output format of highlighted specs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Format {
+    /// Text colored with ANSI escape sequences, for terminals.
+    #[default]
+    Ansi,
+    /// An HTML fragment.
+    Html,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Ansi => write!(f, "ansi"),
+            Format::Html => write!(f, "html"),
+        }
+    }
+}
+
+/// Highlight the spec `input` in `format`.
+///
+/// Fails if `input` does not match the grammar, but does not validate it any further, so
+/// snippets referencing types that they do not define are highlighted.
+pub fn highlight(input: &str, format: Format) -> Result<String, LibError> {
+    let tokens = classify(input)?;
+    let mut out = String::new();
+    if format == Format::Html {
+        out.push_str(r#"<pre class="humble"><code>"#);
+    }
+    let mut end = 0;
+    for token in tokens {
+        let gap = &input[end..token.span.start];
+        let text = &input[token.span.clone()];
+        end = token.span.end;
+        match format {
+            Format::Ansi => {
+                out.push_str(gap);
+                match ansi_color(token.kind) {
+                    Some(color) => out.push_str(&format!("\x1b[{}m{}\x1b[0m", color, text)),
+                    None => out.push_str(text),
+                }
+            }
+            Format::Html => {
+                out.push_str(&format!(
+                    r#"{}<span class="humble-{}">{}</span>"#,
+                    Escape(gap),
+                    token.kind.name(),
+                    Escape(text)
+                ));
+            }
+        }
+    }
+    match format {
+        Format::Ansi => out.push_str(&input[end..]),
+        Format::Html => {
+            out.push_str(&Escape(input[end..].trim_end()).to_string());
+            out.push_str("</code></pre>\n");
+        }
+    }
+    Ok(out)
+}
+
+/// The SGR parameters of the ANSI color of tokens of `kind`, if they are colored.
+fn ansi_color(kind: TokenKind) -> Option<&'static str> {
+    match kind {
+        TokenKind::Keyword => Some("35"),
+        TokenKind::Type | TokenKind::Variant => Some("33"),
+        TokenKind::BuiltInType => Some("36"),
+        TokenKind::Namespace => Some("34"),
+        TokenKind::Annotation => Some("1;34"),
+        TokenKind::String | TokenKind::Route => Some("32"),
+        TokenKind::Literal => Some("36"),
+        TokenKind::HttpMethod => Some("1;35"),
+        TokenKind::DocComment | TokenKind::Comment => Some("90"),
+        TokenKind::Field | TokenKind::Punctuation => None,
+    }
+}
@@ -0,0 +1,80 @@
+//! Incremental generation, which only writes the generated files whose contents changed.
+//!
+//! Regenerating code for a large spec usually changes few files, but backends rewrite all of them,
+//! which makes incremental compilers downstream (e.g. `elm make` or cargo) recompile everything
+//! that depends on them. `Incremental` wraps a backend, generates its code into memory like
+//! `testing::generate_files` and only writes the files that are missing or differ from the output,
+//! keeping the modification times of the others:
+//! ```no_run
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! use humblegen::CodeGenerator;
+//!
+//! let spec = humblegen::parse_file("protocol.humble")?;
+//! let generator = humblegen::backend::elm::Generator::new(humblegen::Artifact::ClientEndpoints, "Api".to_owned())?;
+//! humblegen::incremental::Incremental::new(&generator).generate(&spec, "src/Api".as_ref())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The written files can be reported with `progress::generate_with_progress`, which reports the
+//! files whose modification time changed. Unlike the backends themselves, `Incremental` writes
+//! into output folders that are not empty. Files of the output that the backend no longer
+//! generates are kept.
+
+use crate::check::{self, OutdatedFile};
+use crate::testing::{self, GeneratedFiles};
+use crate::{strict, workspace::Workspace, CodeGenerator, LibError, Spec};
+use std::fs;
+use std::path::Path;
+
+/// A backend that only writes the files of `generator` whose contents changed.
+pub struct Incremental<'a> {
+    generator: &'a dyn CodeGenerator,
+}
+
+impl<'a> Incremental<'a> {
+    pub fn new(generator: &'a dyn CodeGenerator) -> Self {
+        Self { generator }
+    }
+}
+
+impl CodeGenerator for Incremental<'_> {
+    fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        let files = testing::generate_files(self.generator, spec)?;
+        if files.len() == 1 && files.contains_key(testing::SINGLE_FILE) {
+            let contents = &files[testing::SINGLE_FILE];
+            if check::outdated_file(output, contents)?.is_some() {
+                fs::write(output, contents).map_err(LibError::file(output))?;
+            }
+            return Ok(());
+        }
+        write_outdated(&files, output)
+    }
+
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        let files = testing::generate_workspace_files(self.generator, workspace)?;
+        write_outdated(&files, output)
+    }
+
+    fn lossy_mappings(&self) -> &'static [strict::LossyMapping] {
+        self.generator.lossy_mappings()
+    }
+}
+
+/// Write the `files` below the `output` folder that are missing or differ from their contents.
+fn write_outdated(files: &GeneratedFiles, output: &Path) -> Result<(), LibError> {
+    for (relative, contents) in files {
+        let path = check::output_path(output, relative);
+        match check::outdated_file(&path, contents)? {
+            None => continue,
+            Some(OutdatedFile::Missing(_)) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(LibError::file(parent))?;
+                }
+            }
+            Some(OutdatedFile::Changed(_)) => {}
+        }
+        fs::write(&path, contents).map_err(LibError::file(&path))?;
+    }
+    Ok(())
+}
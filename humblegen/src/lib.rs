@@ -1,46 +1,176 @@
 //! Humblegen compiler library
 
-use std::{fmt, io, path::Path, path::PathBuf};
+use std::{borrow::Cow, fmt, io, path::Path, path::PathBuf};
 
 pub use ast::Spec;
 
 pub mod ast;
+pub mod ast_json;
 pub mod backend;
+pub mod check;
+pub mod codegen;
+pub mod conformance;
+pub mod highlight;
+pub mod incremental;
+pub mod lsp;
 pub mod parser;
+pub mod plugin;
+pub mod progress;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod resolve;
+pub mod size_report;
+pub mod sources;
+pub mod strict;
+pub mod testing;
+pub mod transform;
+pub mod wire_hash;
+pub mod workspace;
 use thiserror::Error;
 
+/// Errors of parsing specs and generating code.
+///
+/// Variants wrapping an underlying error, e.g. of IO, expose it as their `source`, such that
+/// build scripts can match on the kind of error and still report its cause.
 #[derive(Error, Debug)]
 pub enum LibError {
+    /// The backend cannot generate the artifact.
     #[error("backend '{backend}' does not support artifact '{artifact}'")]
     UnsupportedArtifact {
         backend: &'static str,
         artifact: Artifact,
     },
+    /// The backend cannot generate code for a feature of the spec used at `span`, e.g. Elm for
+    /// maps with `i32` keys.
+    #[error("backend '{backend}' does not support {feature} at `{span}`")]
+    UnsupportedFeature {
+        feature: String,
+        backend: &'static str,
+        span: String,
+    },
+    /// The backend generates several files, but the output is not a folder.
     #[error("backend '{backend}' requires output to be a folder")]
     OutputMustBeFolder { backend: &'static str },
+    /// The backend generates several files, but the output folder is not empty.
     #[error("backend '{backend}' expects output folder to be empty")]
     OutputFolderNotEmpty { backend: &'static str },
+    /// Reading or writing the file or folder at `path` failed.
+    #[error("cannot access `{}`", .path.display())]
+    FileError {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    /// IO failed without a path, e.g. reading the spec passed to `parse`.
     #[error(transparent)]
     IoError(#[from] io::Error),
+    #[error("schema registry request failed: {0}")]
+    RegistryError(String),
+    /// The workspace config at `path` is not valid TOML or lacks fields.
+    #[error("invalid workspace config `{}`", .path.display())]
+    WorkspaceConfigError {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("invalid workspace: {0}")]
+    WorkspaceError(String),
+    #[error("invalid include: {0}")]
+    IncludeError(String),
+    /// There is no in-memory spec of the name, see `sources::SpecSources`.
+    #[error("no spec source named `{0}`")]
+    UnknownSource(String),
+    /// The definition named `definition` breaks a rule of the spec language, e.g. embeds a
+    /// type that is not a struct.
+    #[error("invalid definition `{definition}`: {message}")]
+    InvalidDefinition { definition: String, message: String },
+    /// Definitions reference types that are not defined, see `resolve::unknown_types`.
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    UnknownTypes(Vec<resolve::UnknownType>),
+    #[error("spec transformation failed: {0}")]
+    TransformError(String),
+    /// The AST JSON is invalid or of another version, see `ast_json::load_ast`.
+    #[error("invalid AST JSON: {0}")]
+    InvalidAst(String),
+    /// A plugin backend failed or answered with an invalid response, see `plugin::Plugin`.
+    #[error("plugin failed: {0}")]
+    PluginError(String),
+    #[error("lossy type mappings in strict mode:\n{}", .0.join("\n"))]
+    StrictModeViolation(Vec<String>),
+    /// The spec does not match the grammar, or a definition breaks a rule checked while parsing,
+    /// e.g. has an annotation that is not allowed, see `LibError::line_col` for where.
     #[error(transparent)]
-    ParseError(#[from] pest::error::Error<parser::Rule>),
+    ParseError(#[from] Box<pest::error::Error<parser::Rule>>),
+    /// Several definitions of the spec do not match the grammar or break rules checked while
+    /// parsing, each reported by position. Specs with a single error fail with `ParseError`.
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n\n"))]
+    ParseErrors(Vec<pest::error::Error<parser::Rule>>),
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+impl LibError {
+    /// Map an IO error accessing `path` to a `LibError::FileError`.
+    pub(crate) fn file<P: AsRef<Path>>(path: P) -> impl FnOnce(io::Error) -> Self {
+        let path = path.as_ref().to_owned();
+        move |source| LibError::FileError { path, source }
+    }
+
+    /// The line and column, starting at 1, at which the spec does not match the grammar,
+    /// for parse errors. For several parse errors, the position of the first one.
+    pub fn line_col(&self) -> Option<(usize, usize)> {
+        self.parse_errors().first().map(|e| match e.line_col {
+            pest::error::LineColLocation::Pos(pos) => pos,
+            pest::error::LineColLocation::Span(start, _) => start,
+        })
+    }
+
+    /// The syntax errors of `ParseError` and `ParseErrors`, by position.
+    pub fn parse_errors(&self) -> Vec<&pest::error::Error<parser::Rule>> {
+        match self {
+            LibError::ParseError(e) => vec![e],
+            LibError::ParseErrors(errors) => errors.iter().collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The error with the parse errors reported in the spec at `path`, e.g. of an include.
+    pub(crate) fn with_path(self, path: &str) -> Self {
+        match self {
+            LibError::ParseError(e) => LibError::ParseError(Box::new(e.with_path(path))),
+            LibError::ParseErrors(errors) => {
+                LibError::ParseErrors(errors.into_iter().map(|e| e.with_path(path)).collect())
+            }
+            e => e,
+        }
+    }
+
+    /// The path of the file the error occurred in, if known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            LibError::FileError { path, .. } | LibError::WorkspaceConfigError { path, .. } => {
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
 /// Which artifacts to produce in addition to user defined types
 pub enum Artifact {
     /// Only generate user defined type definitions
+    #[default]
     TypesOnly,
     /// Generate encoders, decoders and client-side REST API endpoints
     ClientEndpoints,
     /// Generate encoders, decoders and server-side REST API endpoints
     ServerEndpoints,
-}
-
-impl Default for Artifact {
-    fn default() -> Self {
-        Artifact::TypesOnly
-    }
+    /// Generate user defined types, and client-side and server-side REST API endpoints that are
+    /// only compiled if the `client` and `server` cargo features are enabled, respectively
+    ClientAndServer,
+    /// Generate user defined types and benchmarks measuring their serialization
+    Benchmarks,
+    /// Generate server-side REST API endpoints and mock handlers answering with canned responses
+    MockServer,
 }
 
 impl fmt::Display for Artifact {
@@ -49,20 +179,327 @@ impl fmt::Display for Artifact {
             Artifact::TypesOnly => "TypesOnly",
             Artifact::ClientEndpoints => "ClientEndpoints",
             Artifact::ServerEndpoints => "ServerEndpoints",
+            Artifact::ClientAndServer => "ClientAndServer",
+            Artifact::Benchmarks => "Benchmarks",
+            Artifact::MockServer => "MockServer",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+/// The casing of struct field and enum variant names on the wire, independent of their names in
+/// the spec, e.g. to interoperate with an existing `camelCase` JSON API.
+///
+/// Names are converted like serde's `rename_all` does, which the Rust backend emits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum WireCasing {
+    /// Names as in the spec, i.e., `snake_case` fields and `PascalCase` variants.
+    #[default]
+    Spec,
+    /// `snake_case` fields and variants.
+    SnakeCase,
+    /// `camelCase` fields and variants.
+    CamelCase,
+    /// `SCREAMING_SNAKE_CASE` fields and variants.
+    ScreamingSnakeCase,
+}
+
+impl WireCasing {
+    /// The wire name of the `snake_case` struct field `name`.
+    pub fn field_name(self, name: &str) -> String {
+        match self {
+            WireCasing::Spec | WireCasing::SnakeCase => name.to_owned(),
+            WireCasing::CamelCase => {
+                let mut camel = String::new();
+                let mut capitalize = false;
+                for ch in name.chars() {
+                    if ch == '_' {
+                        capitalize = !camel.is_empty();
+                    } else if capitalize {
+                        camel.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        camel.push(ch);
+                    }
+                }
+                camel
+            }
+            WireCasing::ScreamingSnakeCase => name.to_ascii_uppercase(),
+        }
+    }
+
+    /// The wire name of the `PascalCase` enum variant `name`.
+    pub fn variant_name(self, name: &str) -> String {
+        match self {
+            WireCasing::Spec => name.to_owned(),
+            WireCasing::SnakeCase => {
+                let mut snake = String::new();
+                for (i, ch) in name.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            WireCasing::CamelCase => name[..1].to_ascii_lowercase() + &name[1..],
+            WireCasing::ScreamingSnakeCase => WireCasing::SnakeCase
+                .variant_name(name)
+                .to_ascii_uppercase(),
+        }
+    }
+
+    /// The argument of serde's `rename_all` attribute, if names are converted.
+    pub(crate) fn serde_rename_all(self) -> Option<&'static str> {
+        match self {
+            WireCasing::Spec => None,
+            WireCasing::SnakeCase => Some("snake_case"),
+            WireCasing::CamelCase => Some("camelCase"),
+            WireCasing::ScreamingSnakeCase => Some("SCREAMING_SNAKE_CASE"),
+        }
+    }
+}
+
+impl fmt::Display for WireCasing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printable = match *self {
+            WireCasing::Spec => "spec",
+            WireCasing::SnakeCase => "snake_case",
+            WireCasing::CamelCase => "camelCase",
+            WireCasing::ScreamingSnakeCase => "SCREAMING_SNAKE_CASE",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+/// The JSON representation of maps whose keys are not `str`.
+///
+/// Maps with `str` keys are always JSON objects.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum MapRepresentation {
+    /// JSON objects with the keys as strings, which only works for integer keys and not in every
+    /// backend.
+    #[default]
+    Object,
+    /// Arrays of `[key, value]` entries, which work for keys of any type in every backend.
+    Entries,
+}
+
+impl MapRepresentation {
+    /// `spec` with the maps represented as entries replaced by lists of `(key, value)` tuples,
+    /// which have the same JSON representation.
+    pub(crate) fn apply(self, spec: &Spec) -> Cow<'_, Spec> {
+        match self {
+            MapRepresentation::Object => Cow::Borrowed(spec),
+            MapRepresentation::Entries => {
+                let mut spec = spec.clone();
+                spec.for_each_type_ident_mut(&mut |ty| {
+                    if let ast::TypeIdent::Map(key, value) = ty {
+                        if !matches!(**key, ast::TypeIdent::BuiltIn(ast::AtomType::Str)) {
+                            let entry = ast::TupleDef(vec![(**key).clone(), (**value).clone()]);
+                            *ty = ast::TypeIdent::List(Box::new(ast::TypeIdent::Tuple(entry)));
+                        }
+                    }
+                });
+                Cow::Owned(spec)
+            }
+        }
+    }
+}
+
+impl fmt::Display for MapRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printable = match *self {
+            MapRepresentation::Object => "object",
+            MapRepresentation::Entries => "entries",
         };
         write!(f, "{}", printable)
     }
 }
 
+/// The JSON representation of enums without a `@tag` or `@untagged` annotation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum EnumRepresentation {
+    /// Externally tagged, e.g. `{"Variant": content}`, see `ast::EnumTagging::External`.
+    #[default]
+    External,
+    /// Adjacently tagged, e.g. `{"tag": "Variant", "contents": content}`, as if annotated with
+    /// `@tag("tag", "contents")`. Enums with only simple variants are still strings, like
+    /// Aeson's `TaggedObject` encoding.
+    Adjacent,
+}
+
+impl EnumRepresentation {
+    /// `spec` with the enums represented adjacently tagged annotated with
+    /// `@tag("tag", "contents")`, which the backends represent alike.
+    pub(crate) fn apply(self, spec: &Spec) -> Cow<'_, Spec> {
+        match self {
+            EnumRepresentation::External => Cow::Borrowed(spec),
+            EnumRepresentation::Adjacent => {
+                let mut spec = spec.clone();
+                for spec_item in spec.iter_mut() {
+                    if let ast::SpecItem::EnumDef(edef) = spec_item {
+                        let annotated = edef
+                            .annotations
+                            .iter()
+                            .any(|a| a.name == "tag" || a.name == "untagged");
+                        if !annotated && edef.complex_variants().next().is_some() {
+                            edef.annotations.push(ast::Annotation {
+                                name: "tag".to_owned(),
+                                args: vec!["tag".to_owned(), "contents".to_owned()],
+                            });
+                        }
+                    }
+                }
+                Cow::Owned(spec)
+            }
+        }
+    }
+}
+
+impl fmt::Display for EnumRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printable = match *self {
+            EnumRepresentation::External => "external",
+            EnumRepresentation::Adjacent => "adjacent",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+/// The JSON representation of the large integers `i64` and `u64`.
+///
+/// Integers above 2^53 are not exactly representable as JavaScript numbers, which e.g. browsers
+/// silently round when parsing JSON.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum LargeIntRepresentation {
+    /// JSON numbers, which JavaScript consumers may round.
+    #[default]
+    Number,
+    /// JSON strings of the decimal number, which is exact in every consumer. Decoders accept
+    /// numbers as well.
+    String,
+}
+
+impl fmt::Display for LargeIntRepresentation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printable = match *self {
+            LargeIntRepresentation::Number => "number",
+            LargeIntRepresentation::String => "string",
+        };
+        write!(f, "{}", printable)
+    }
+}
+
+/// An environment the services of a spec are deployed to, e.g. `prod`, with the URL they are
+/// mounted at there. Generated clients are configured by environment instead of by URL.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Environment {
+    /// `snake_case` name, e.g. `prod` or `local_dev`.
+    pub name: String,
+    /// Absolute http or https URL, e.g. `https://api.example.com`.
+    pub base_url: String,
+}
+
+impl Environment {
+    /// Environment `name` at `base_url`, or a description of why either is invalid.
+    pub fn new(name: &str, base_url: &str) -> Result<Self, String> {
+        let is_snake_case = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !is_snake_case {
+            return Err(format!(
+                "environment name `{}` must be a snake_case identifier",
+                name
+            ));
+        }
+        if !is_absolute_http_url(base_url) {
+            return Err(format!(
+                "base URL of environment `{}` must be an absolute http or https URL, but is \"{}\"",
+                name, base_url
+            ));
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            base_url: base_url.to_owned(),
+        })
+    }
+}
+
+/// Whether `url` is an absolute http or https URL, as required of base URLs.
+pub(crate) fn is_absolute_http_url(url: &str) -> bool {
+    let is_absolute = ["http://", "https://"]
+        .iter()
+        .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme));
+    is_absolute && !url.contains(char::is_whitespace)
+}
+
 // Common interface of all backends
 pub trait CodeGenerator {
     fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError>;
+
+    /// Generate code for all specs of a workspace into the `output` folder.
+    ///
+    /// Types defined in the workspace's common specs are shared by all specs.
+    fn generate_workspace(
+        &self,
+        workspace: &workspace::Workspace,
+        output: &Path,
+    ) -> Result<(), LibError>;
+
+    /// The built-in types that the backend maps lossily, which strict mode rejects unless
+    /// accepted with `@lossy`, see `strict`.
+    fn lossy_mappings(&self) -> &'static [strict::LossyMapping] {
+        &[]
+    }
 }
 
+/// Parse a spec.
+///
+/// Specs with `include`s must be parsed with `parse_file`, since include paths are relative
+/// to the spec file, or held in memory as `sources::SpecSources`.
 pub fn parse<I: io::Read>(mut src: I) -> Result<ast::Spec, LibError> {
     let mut input = String::new();
-    src.read_to_string(&mut input).map_err(LibError::IoError)?;
-    Ok(parser::parse(&input).map_err(LibError::ParseError)?)
+    src.read_to_string(&mut input)?;
+    parser::parse(&input, None, &mut |path| {
+        Err(LibError::IncludeError(format!(
+            "cannot include `{}` without a spec file path, use `parse_file`",
+            path
+        )))
+    })
+}
+
+/// Parse the spec file at `path`, including the fragments of its `include`s relative to it.
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<ast::Spec, LibError> {
+    parse_file_with(path.as_ref(), &mut |_| {})
+}
+
+/// Parse the spec file at `path`, passing the paths of all included fragments to `on_include`.
+fn parse_file_with(path: &Path, on_include: &mut dyn FnMut(&Path)) -> Result<ast::Spec, LibError> {
+    let spec = parse_file_unresolved_with(path, on_include)?;
+    resolve::check_references(&spec)?;
+    Ok(spec)
+}
+
+/// Like `parse_file`, but the spec may reference types it does not define, see `resolve`.
+pub(crate) fn parse_file_unresolved(path: &Path) -> Result<ast::Spec, LibError> {
+    parse_file_unresolved_with(path, &mut |_| {})
+}
+
+fn parse_file_unresolved_with(
+    path: &Path,
+    on_include: &mut dyn FnMut(&Path),
+) -> Result<ast::Spec, LibError> {
+    let input = std::fs::read_to_string(path).map_err(LibError::file(path))?;
+    let root = path.parent().unwrap_or_else(|| Path::new(""));
+    parser::parse_unresolved(&input, Some(&path.to_string_lossy()), &mut |include| {
+        let include_path = root.join(include);
+        on_include(&include_path);
+        std::fs::read_to_string(&include_path).map_err(|e| {
+            LibError::IncludeError(format!("cannot read `{}`: {}", include_path.display(), e))
+        })
+    })
 }
 
 /// This method is intended for use form within a `build.rs` file.
@@ -78,8 +515,9 @@ pub fn build<P: AsRef<Path>>(src: P) -> Result<(), LibError> {
         .into();
     let out_path = out_dir.join("protocol.rs");
 
-    let infile = std::fs::File::open(src)?;
-    let spec = parse(infile)?;
+    let spec = parse_file_with(src.as_ref(), &mut |include| {
+        println!("cargo:rerun-if-changed={}", include.display());
+    })?;
     let generator = backend::rust::Generator::new(Artifact::ServerEndpoints)?;
     generator.generate(&spec, &out_path)?;
 
@@ -0,0 +1,389 @@
+//! Language server for humble files, run by `humblegen lsp`, e.g. for editors like VS Code.
+//!
+//! The server speaks the [Language Server Protocol](https://microsoft.github.io/language-server-protocol/)
+//! on stdin and stdout and supports
+//! * diagnostics of specs that do not parse, published whenever a document changes,
+//! * go-to-definition of the types referenced by fields, variants, embeds and endpoints,
+//! * hover with the doc comment of a type and
+//! * completion of the names of user defined and built-in types.
+//!
+//! Documents are synchronized in full on every change. Includes of documents opened from the
+//! file system are read relative to them, but definitions are only looked up in the document
+//! itself.
+
+use crate::parser::{
+    self,
+    names::{self, Name, NameKind},
+};
+use crate::LibError;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, prelude::*};
+use std::path::{Path, PathBuf};
+
+/// The built-in types and type constructors offered by completion.
+const BUILT_IN_TYPES: &[&str] = &[
+    "str", "i32", "u32", "i64", "u64", "u8", "f64", "bool", "datetime", "date", "uuid", "bytes",
+    "list", "option", "result", "map",
+];
+
+// https://microsoft.github.io/language-server-protocol/specifications/specification-3-15/#completionItemKind
+const COMPLETION_KIND_KEYWORD: u8 = 14;
+const COMPLETION_KIND_ENUM: u8 = 13;
+const COMPLETION_KIND_STRUCT: u8 = 22;
+
+/// Serve the language server protocol, reading messages from `input` and writing messages to
+/// `output` until the client sends `exit` or closes `input`.
+pub fn run(input: impl BufRead, output: impl Write) -> Result<(), LibError> {
+    let mut server = Server {
+        input,
+        output,
+        documents: HashMap::new(),
+        completed_names: HashMap::new(),
+    };
+    while let Some(message) = server.read_message()? {
+        if !server.handle(&message)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+struct Server<R, W> {
+    input: R,
+    output: W,
+    /// The texts of the open documents by URI, with `\n` line endings like parsed specs.
+    documents: HashMap<String, String>,
+    /// The names of the last version of each document that matched the grammar, so that types
+    /// are completed while the document is edited.
+    completed_names: HashMap<String, Vec<Name>>,
+}
+
+impl<R: BufRead, W: Write> Server<R, W> {
+    /// Handle `message`, returning whether to continue serving.
+    fn handle(&mut self, message: &Value) -> io::Result<bool> {
+        let id = message.get("id");
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match message["method"].as_str() {
+            Some("initialize") => self.respond(
+                id,
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": 1,
+                        "definitionProvider": true,
+                        "hoverProvider": true,
+                        "completionProvider": {},
+                    },
+                    "serverInfo": { "name": "humblegen", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )?,
+            Some("shutdown") => self.respond(id, Value::Null)?,
+            Some("exit") => return Ok(false),
+            Some("textDocument/didOpen") => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.update(uri, text)?;
+            }
+            Some("textDocument/didChange") => {
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|c| c.last()?["text"].as_str()) {
+                    self.update(uri, text)?;
+                }
+            }
+            Some("textDocument/didClose") => {
+                self.documents.remove(uri);
+                self.completed_names.remove(uri);
+                self.publish_diagnostics(uri, vec![])?;
+            }
+            Some("textDocument/definition") => {
+                let result = self.definition(uri, &params["position"]);
+                self.respond(id, result.unwrap_or(Value::Null))?;
+            }
+            Some("textDocument/hover") => {
+                let result = self.hover(uri, &params["position"]);
+                self.respond(id, result.unwrap_or(Value::Null))?;
+            }
+            Some("textDocument/completion") => {
+                let result = self.completion(uri);
+                self.respond(id, result)?;
+            }
+            Some(method) if id.is_some() => self.write_message(&json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("unsupported method `{}`", method) },
+            }))?,
+            // other notifications, and responses to requests we never send
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn update(&mut self, uri: &str, text: &str) -> io::Result<()> {
+        let text = text.replace("\r\n", "\n");
+        let diagnostics = diagnostics(&text, uri_to_path(uri).as_deref());
+        if let Ok(names) = names::names(&text) {
+            self.completed_names.insert(uri.to_owned(), names);
+        }
+        self.documents.insert(uri.to_owned(), text);
+        self.publish_diagnostics(uri, diagnostics)
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str, diagnostics: Vec<Value>) -> io::Result<()> {
+        self.write_message(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }))
+    }
+
+    /// The names of the document at `uri`, and the name at `position`.
+    fn name_at(&self, uri: &str, position: &Value) -> Option<(&str, Vec<Name>, Name)> {
+        let text = self.documents.get(uri)?;
+        let offset = offset(text, position)?;
+        let names = names::names(text).ok()?;
+        let name = names
+            .iter()
+            .find(|name| name.span.start <= offset && offset <= name.span.end)?
+            .clone();
+        Some((text, names, name))
+    }
+
+    fn definition(&self, uri: &str, position: &Value) -> Option<Value> {
+        let (text, names, name) = self.name_at(uri, position)?;
+        let definition = find_definition(&names, &name.name)?;
+        Some(json!({ "uri": uri, "range": range(text, &definition.span) }))
+    }
+
+    fn hover(&self, uri: &str, position: &Value) -> Option<Value> {
+        let (text, names, name) = self.name_at(uri, position)?;
+        let (keyword, doc_comment) = match &find_definition(&names, &name.name)?.kind {
+            NameKind::Definition {
+                keyword,
+                doc_comment,
+            } => (keyword, doc_comment),
+            NameKind::Reference => return None,
+        };
+        let mut contents = format!("```humble\n{} {}\n```", keyword, name.name);
+        if let Some(doc_comment) = doc_comment {
+            contents.push_str("\n\n");
+            contents.push_str(doc_comment);
+        }
+        Some(json!({
+            "contents": { "kind": "markdown", "value": contents },
+            "range": range(text, &name.span),
+        }))
+    }
+
+    fn completion(&self, uri: &str) -> Value {
+        let mut items: Vec<Value> = BUILT_IN_TYPES
+            .iter()
+            .map(|ty| json!({ "label": ty, "kind": COMPLETION_KIND_KEYWORD }))
+            .collect();
+        for name in self.completed_names.get(uri).into_iter().flatten() {
+            let kind = match name.kind {
+                NameKind::Definition {
+                    keyword: "struct", ..
+                } => COMPLETION_KIND_STRUCT,
+                NameKind::Definition {
+                    keyword: "enum" | "flags" | "type",
+                    ..
+                } => COMPLETION_KIND_ENUM,
+                // services are no types
+                _ => continue,
+            };
+            items.push(json!({ "label": name.name, "kind": kind }));
+        }
+        Value::Array(items)
+    }
+
+    fn respond(&mut self, id: Option<&Value>, result: Value) -> io::Result<()> {
+        self.write_message(&json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    fn read_message(&mut self) -> io::Result<Option<Value>> {
+        let mut content_length = None;
+        loop {
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("Content-Length") {
+                    content_length = value.trim().parse().ok();
+                }
+            }
+        }
+        let content_length = content_length.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+        })?;
+        let mut content = vec![0; content_length];
+        self.input.read_exact(&mut content)?;
+        serde_json::from_slice(&content)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn write_message(&mut self, message: &Value) -> io::Result<()> {
+        let content = message.to_string();
+        write!(
+            self.output,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )?;
+        self.output.flush()
+    }
+}
+
+fn find_definition<'a>(names: &'a [Name], name: &str) -> Option<&'a Name> {
+    names
+        .iter()
+        .find(|n| n.name == name && matches!(n.kind, NameKind::Definition { .. }))
+}
+
+/// The diagnostics of the spec `text` at `path`, i.e., why it does not parse.
+fn diagnostics(text: &str, path: Option<&Path>) -> Vec<Value> {
+    let root = path.and_then(Path::parent);
+    let result = parser::parse(text, None, &mut |include| match root {
+        Some(root) => std::fs::read_to_string(root.join(include))
+            .map_err(|e| LibError::IncludeError(format!("cannot read `{}`: {}", include, e))),
+        None => Err(LibError::IncludeError(format!(
+            "cannot include `{}` of a document that is not a file",
+            include
+        ))),
+    });
+    let (span, message) = match result {
+        Ok(_) => return vec![],
+        // every syntax error and invalid annotation is a diagnostic of its own
+        Err(e) if !e.parse_errors().is_empty() => {
+            return e
+                .parse_errors()
+                .into_iter()
+                .map(|e| {
+                    let span = match e.location {
+                        pest::error::InputLocation::Pos(pos) => pos..pos,
+                        pest::error::InputLocation::Span((start, end)) => start..end,
+                    };
+                    let message = match &e.variant {
+                        pest::error::ErrorVariant::ParsingError { positives, .. }
+                            if !positives.is_empty() =>
+                        {
+                            let expected: Vec<_> =
+                                positives.iter().map(|r| format!("{:?}", r)).collect();
+                            format!("expected {}", expected.join(", "))
+                        }
+                        pest::error::ErrorVariant::ParsingError { .. } => {
+                            "unexpected input".to_owned()
+                        }
+                        pest::error::ErrorVariant::CustomError { message } => message.clone(),
+                    };
+                    diagnostic(text, &span, &message)
+                })
+                .collect();
+        }
+        // every reference to an unknown type is a diagnostic of its own
+        Err(LibError::UnknownTypes(unknown)) => {
+            let names = names::names(text).unwrap_or_default();
+            let diagnostics: Vec<_> = names
+                .iter()
+                .filter(|name| name.kind == NameKind::Reference)
+                .filter_map(|name| {
+                    let unknown = unknown.iter().find(|u| u.name == name.name)?;
+                    let message = match &unknown.suggestion {
+                        Some(suggestion) => format!(
+                            "unknown type `{}`, did you mean `{}`?",
+                            unknown.name, suggestion
+                        ),
+                        None => format!("unknown type `{}`", unknown.name),
+                    };
+                    Some(diagnostic(text, &name.span, &message))
+                })
+                .collect();
+            if !diagnostics.is_empty() {
+                return diagnostics;
+            }
+            // e.g. referenced by an included fragment
+            (0..0, LibError::UnknownTypes(unknown).to_string())
+        }
+        Err(e) => {
+            // point at the name of the invalid definition, e.g. of `Monster` for `Monster.lair`
+            let span = match &e {
+                LibError::InvalidDefinition { definition, .. } => {
+                    let name = definition.split(['.', ':']).next();
+                    names::names(text)
+                        .ok()
+                        .and_then(|names| Some(find_definition(&names, name?)?.span.clone()))
+                }
+                _ => None,
+            };
+            (span.unwrap_or(0..0), e.to_string())
+        }
+    };
+    vec![diagnostic(text, &span, &message)]
+}
+
+/// An error diagnostic with `message` at the byte range `span` of `text`.
+fn diagnostic(text: &str, span: &std::ops::Range<usize>, message: &str) -> Value {
+    json!({
+        "range": range(text, span),
+        "severity": 1,
+        "source": "humblegen",
+        "message": message,
+    })
+}
+
+/// The LSP range, i.e., 0-based lines and UTF-16 columns, of the byte range `span` of `text`.
+fn range(text: &str, span: &std::ops::Range<usize>) -> Value {
+    json!({ "start": position(text, span.start), "end": position(text, span.end) })
+}
+
+fn position(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+    json!({ "line": before.matches('\n').count(), "character": character })
+}
+
+/// The byte offset of the LSP `position` in `text`.
+fn offset(text: &str, position: &Value) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+    let line_start = match line {
+        0 => 0,
+        _ => text.match_indices('\n').nth(line - 1)?.0 + 1,
+    };
+    let mut column = 0;
+    for (i, c) in text[line_start..].char_indices() {
+        if column >= character || c == '\n' {
+            return Some(line_start + i);
+        }
+        column += c.len_utf16();
+    }
+    Some(text.len())
+}
+
+/// The path of a `file://` URI.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    // percent-decode bytes, e.g. `%20` for spaces
+    let mut bytes = vec![];
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let hex = tail.get(..2).and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(decoded) if byte == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
@@ -1,23 +1,320 @@
 //! Humblegen code application
 
 mod cli;
+mod progress_bar;
 
 use anyhow::{Context, Result};
+use humblegen::check;
+use humblegen::incremental::Incremental;
+use humblegen::progress::{self, Progress};
+use humblegen::size_report;
+use humblegen::strict;
+use humblegen::transform::{Subprocess, Transform};
+use rayon::prelude::*;
+use std::sync::Mutex;
 use structopt::StructOpt;
 
 fn main() -> Result<()> {
     let args = cli::CliArgs::from_args();
 
-    let spec_file = std::fs::File::open(&args.input).context(format!(
-        "unable to open specification file {:?}",
-        &args.input
-    ))?;
-    let spec = humblegen::parse(spec_file).context(format!(
-        "failed to parse specification file {:?}",
-        &args.input
-    ))?;
+    if let Some(command) = &args.command {
+        return run_command(command);
+    }
 
-    args.code_generator()?.generate(&spec, &args.output)?;
+    let (input, targets) = args.generate_args();
+    let progress_bar = Mutex::new(if args.progress {
+        Some(progress_bar::ProgressBar::new(targets.len()))
+    } else {
+        None
+    });
+    let report = |event: Progress| {
+        if let Some(progress_bar) = &mut *progress_bar.lock().unwrap() {
+            progress_bar.report(event)
+        } else if let (true, Progress::FileWritten { .. }) = (args.incremental, event) {
+            eprintln!("{}", event);
+        }
+    };
 
+    let input = if args.workspace {
+        if args.ast {
+            anyhow::bail!("`--ast` is not supported with `--workspace`");
+        }
+        if args.size_report {
+            anyhow::bail!("`--size-report` is not supported with `--workspace`");
+        }
+        let mut workspace =
+            humblegen::workspace::Workspace::load_with_progress(input, &mut |e| report(e))
+                .context(format!("failed to load workspace config {:?}", input))?;
+        for transform in &args.transforms {
+            Subprocess::from_command_line(transform).transform_workspace(&mut workspace)?;
+        }
+        if args.prune_unused {
+            humblegen::resolve::prune_unused_workspace(&mut workspace);
+        }
+        Input::Workspace(workspace)
+    } else {
+        let mut spec = if args.ast {
+            humblegen::ast_json::load_ast_file(input)
+                .context(format!("failed to load AST file {:?}", input))?
+        } else {
+            progress::parse_file_with_progress(input, &mut |e| report(e))
+                .context(format!("failed to parse specification file {:?}", input))?
+        };
+        for transform in &args.transforms {
+            Subprocess::from_command_line(transform).transform(&mut spec)?;
+        }
+        if args.prune_unused {
+            humblegen::resolve::prune_unused(&mut spec);
+        }
+        Input::Spec(spec)
+    };
+
+    // all backends share the parsed input, and run in parallel
+    let results: Vec<_> = targets
+        .par_iter()
+        .map(|target| run_target(&args, target, &input, &report))
+        .collect();
+
+    // errors of a single backend are reported like those of parsing, errors of several
+    // backends are reported all together
+    let single = results.len() == 1;
+    let mut failed = 0;
+    for (target, result) in targets.iter().zip(results) {
+        let name = format!("{} ({})", target.backend.name(), target.output.display());
+        match result {
+            Ok(None) => {}
+            Ok(Some(size_report)) if single => print!("{}", size_report),
+            Ok(Some(size_report)) => print!("{}:\n{}", name, size_report),
+            Err(e) if single => return Err(e),
+            Err(e) => {
+                failed += 1;
+                eprintln!("Error: {}: {:?}", name, e);
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} of {} backends failed", failed, targets.len());
+    }
     Ok(())
 }
+
+/// The parsed input of the backends.
+enum Input {
+    Spec(humblegen::Spec),
+    Workspace(humblegen::workspace::Workspace),
+}
+
+/// Run the backend of `target` on `input`, returning its size report if requested.
+fn run_target(
+    args: &cli::CliArgs,
+    target: &cli::Target,
+    input: &Input,
+    report: &(dyn Fn(Progress) + Sync),
+) -> Result<Option<String>> {
+    let generator = args.code_generator(target)?;
+    let incremental;
+    let generator: &dyn humblegen::CodeGenerator = if args.incremental {
+        incremental = Incremental::new(generator.as_ref());
+        &incremental
+    } else {
+        generator.as_ref()
+    };
+    let backend = target.backend.name();
+    let output = target.output;
+    let mut report = |event: Progress| report(event);
+
+    if args.strict {
+        match input {
+            Input::Spec(spec) => strict::check_strict(generator, spec)?,
+            Input::Workspace(workspace) => {
+                strict::check_strict(generator, &workspace.common)?;
+                for ws_spec in &workspace.specs {
+                    strict::check_strict(generator, &ws_spec.spec)?;
+                }
+            }
+        }
+    }
+
+    if args.check {
+        let outdated = match input {
+            Input::Spec(spec) => check::check(generator, spec, output)?,
+            Input::Workspace(workspace) => check::check_workspace(generator, workspace, output)?,
+        };
+        report_outdated(outdated)?;
+        return Ok(None);
+    }
+
+    match input {
+        Input::Spec(spec) => {
+            progress::generate_with_progress(generator, backend, spec, output, &mut report)?;
+            if args.size_report {
+                let size_report = size_report::size_report(generator, spec)?;
+                return Ok(Some(size_report.to_string()));
+            }
+        }
+        Input::Workspace(workspace) => progress::generate_workspace_with_progress(
+            generator,
+            backend,
+            workspace,
+            output,
+            &mut report,
+        )?,
+    }
+    Ok(None)
+}
+
+/// Report the `outdated` files found by `--check` on stderr, failing if there are any.
+fn report_outdated(outdated: Vec<check::OutdatedFile>) -> Result<()> {
+    for file in &outdated {
+        eprintln!("{}", file);
+    }
+    if !outdated.is_empty() {
+        anyhow::bail!(
+            "{} generated files are out of date, regenerate them without `--check`",
+            outdated.len()
+        );
+    }
+    Ok(())
+}
+
+fn run_command(command: &cli::Command) -> Result<()> {
+    match command {
+        cli::Command::TestConformance {
+            input,
+            output,
+            seed,
+            count,
+            elm_module_root,
+            large_int_representation,
+            enum_representation,
+        } => {
+            let spec = humblegen::parse_file(input)
+                .context(format!("failed to parse specification file {:?}", input))?;
+            let config = humblegen::conformance::CorpusConfig {
+                seed: *seed,
+                values_per_type: *count,
+                large_int_representation: **large_int_representation,
+                enum_representation: **enum_representation,
+                ..Default::default()
+            };
+            humblegen::conformance::write_conformance_suite(
+                &spec,
+                &config,
+                elm_module_root,
+                output,
+            )
+            .context(format!("failed to write conformance suite to {:?}", output))?;
+            Ok(())
+        }
+        cli::Command::DumpAst { input, output } => {
+            let spec = humblegen::parse_file(input)
+                .context(format!("failed to parse specification file {:?}", input))?;
+            let json = humblegen::ast_json::dump_ast(&spec);
+            match output {
+                Some(output) => std::fs::write(output, json + "\n")
+                    .context(format!("unable to write AST file {:?}", output))?,
+                None => println!("{}", json),
+            }
+            Ok(())
+        }
+        cli::Command::WireManifest {
+            input,
+            output,
+            wire_casing,
+            map_representation,
+            large_int_representation,
+            enum_representation,
+        } => {
+            let spec = humblegen::parse_file(input)
+                .context(format!("failed to parse specification file {:?}", input))?;
+            let representation = humblegen::wire_hash::Representation {
+                wire_casing: **wire_casing,
+                map_representation: **map_representation,
+                enum_representation: **enum_representation,
+                large_int_representation: **large_int_representation,
+            };
+            let manifest = humblegen::wire_hash::manifest(&spec, representation);
+            match output {
+                Some(output) => std::fs::write(output, manifest + "\n")
+                    .context(format!("unable to write manifest file {:?}", output))?,
+                None => println!("{}", manifest),
+            }
+            Ok(())
+        }
+        cli::Command::Unused { input } => {
+            let spec = humblegen::parse_file(input)
+                .context(format!("failed to parse specification file {:?}", input))?;
+            for name in humblegen::resolve::unused_definitions(&spec) {
+                println!("{}", name);
+            }
+            Ok(())
+        }
+        cli::Command::Highlight {
+            input,
+            format,
+            output,
+        } => {
+            let spec = std::fs::read_to_string(input)
+                .context(format!("unable to read specification file {:?}", input))?;
+            let highlighted = humblegen::highlight::highlight(&spec, **format).context(format!(
+                "failed to highlight specification file {:?}",
+                input
+            ))?;
+            match output {
+                Some(output) => std::fs::write(output, highlighted)
+                    .context(format!("unable to write highlighted file {:?}", output))?,
+                None => print!("{}", highlighted),
+            }
+            Ok(())
+        }
+        cli::Command::Lsp => {
+            humblegen::lsp::run(std::io::stdin().lock(), std::io::stdout().lock())?;
+            Ok(())
+        }
+        cli::Command::Publish { .. } | cli::Command::Pull { .. } => run_registry_command(command),
+    }
+}
+
+#[cfg(feature = "registry")]
+fn run_registry_command(command: &cli::Command) -> Result<()> {
+    match command {
+        cli::Command::Publish {
+            input,
+            registry,
+            name,
+            version,
+        } => {
+            humblegen::registry::publish(registry, name, version, input).context(format!(
+                "failed to publish {}@{} to {}",
+                name, version, registry
+            ))?;
+        }
+        cli::Command::Pull {
+            registry,
+            name,
+            version,
+            output,
+        } => {
+            let spec_version = humblegen::registry::pull(registry, name, version).context(
+                format!("failed to pull {}@{} from {}", name, version, registry),
+            )?;
+            let json = humblegen::ast_json::dump_ast(&spec_version.spec()?);
+            std::fs::write(output, json + "\n")
+                .context(format!("unable to write AST file {:?}", output))?;
+        }
+        cli::Command::TestConformance { .. }
+        | cli::Command::DumpAst { .. }
+        | cli::Command::WireManifest { .. }
+        | cli::Command::Unused { .. }
+        | cli::Command::Highlight { .. }
+        | cli::Command::Lsp => {
+            unreachable!("not a registry command")
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "registry"))]
+fn run_registry_command(_command: &cli::Command) -> Result<()> {
+    anyhow::bail!("humblegen was built without the `registry` feature")
+}
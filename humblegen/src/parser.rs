@@ -1,7 +1,18 @@
 //! The humble language parser.
 
 mod embeds;
+mod headers;
+mod includes;
+mod modules;
+pub(crate) mod names;
+mod pagination;
+mod partials;
+mod path_params;
+pub(crate) mod queries;
+pub mod tokens;
+pub(crate) mod validate;
 
+use inflector::Inflector;
 use itertools::Itertools;
 use pest::Parser;
 use pest_derive::Parser;
@@ -12,21 +23,311 @@ use pest_derive::Parser;
 struct HumbleParser;
 
 use crate::ast::*;
+use crate::{LibError, WireCasing};
 
-/// Parse complete spec.
-pub(crate) fn parse(input: &str) -> Result<Spec, pest::error::Error<Rule>> {
-    let humbled = HumbleParser::parse(Rule::doc, input)?
-        .next()
-        .expect("grammar requires non-empty document");
+/// Parse complete spec, whose referenced types all must be defined, see `resolve`.
+///
+/// `path` is the path of the spec reported in parse errors, if it is a file.
+/// `load_include` returns the contents of the fragment at the path of an `include`.
+pub(crate) fn parse(
+    input: &str,
+    path: Option<&str>,
+    load_include: &mut dyn FnMut(&str) -> Result<String, LibError>,
+) -> Result<Spec, LibError> {
+    let spec = parse_unresolved(input, path, load_include)?;
+    crate::resolve::check_references(&spec)?;
+    Ok(spec)
+}
 
-    let mut ast = Spec(humbled.into_inner().map(parse_spec_item).collect());
+/// Like `parse`, but the spec may reference types it does not define, e.g. the specs of a
+/// workspace those of the common specs.
+pub(crate) fn parse_unresolved(
+    input: &str,
+    path: Option<&str>,
+    load_include: &mut dyn FnMut(&str) -> Result<String, LibError>,
+) -> Result<Spec, LibError> {
+    let (includes, mut ast) = parse_items(input).map_err(|e| match path {
+        Some(path) => e.with_path(path),
+        None => e,
+    })?;
 
     // AST transformations
-    embeds::resolve_embeds(&mut ast);
+    modules::resolve_modules(&mut ast)?;
+    let mut ast = includes::resolve_includes(includes, ast, load_include)?;
+    embeds::resolve_embeds(&mut ast)?;
+    partials::derive_partials(&mut ast)?;
+    pagination::expand_paginated_types(&mut ast)?;
+    pagination::validate_paginated_endpoints(&ast)?;
+    path_params::validate_path_params(&ast)?;
+    headers::validate_headers(&ast)?;
+    queries::validate_queries(&ast)?;
+    ast.intern_idents();
 
     Ok(ast)
 }
 
+type ParseError = Box<pest::error::Error<Rule>>;
+
+/// An `include "path" as namespace` definition.
+pub(crate) struct Include {
+    /// The path of the included fragment, relative to the including spec.
+    pub path: String,
+    /// The namespace of the included definitions.
+    pub namespace: String,
+}
+
+/// Parse the includes and definitions of a spec, without transforming them.
+///
+/// `\r\n` line endings are normalized to `\n`, such that doc comments, and hence the generated
+/// code, do not depend on the line endings of the spec.
+///
+/// If the spec does not match the grammar, all its definitions that do not match are reported,
+/// see `syntax_errors`. Otherwise, all definitions that break a rule checked while parsing are
+/// reported, e.g. that have annotations which are not allowed.
+fn parse_items(input: &str) -> Result<(Vec<Include>, Spec), LibError> {
+    let input = input.replace("\r\n", "\n");
+    let humbled = match HumbleParser::parse(Rule::doc, &input) {
+        Ok(mut pairs) => pairs.next().expect("grammar requires non-empty document"),
+        Err(e) => return Err(parse_errors(syntax_errors(&input, e))),
+    };
+
+    let mut definitions = Definitions::default();
+    // invalid definitions are reported together, like syntax errors
+    let mut errors = vec![];
+    for pair in humbled.into_inner() {
+        if let Err(e) = definitions.parse(pair) {
+            errors.push(*e);
+        }
+    }
+    for (owner, name, span) in &definitions.anonymous_names {
+        if definitions
+            .items
+            .iter()
+            .filter(|item| item.name() == name)
+            .count()
+            > 1
+        {
+            errors.push(*error(
+                span.clone(),
+                format!(
+                    "anonymous struct `{}` of `{}` is named like another definition",
+                    name, owner
+                ),
+            ));
+        }
+    }
+    errors.sort_by_key(|e| match e.location {
+        pest::error::InputLocation::Pos(pos) => pos,
+        pest::error::InputLocation::Span((start, _)) => start,
+    });
+    if !errors.is_empty() {
+        return Err(parse_errors(errors));
+    }
+    Ok((
+        definitions.includes,
+        Spec(definitions.items, definitions.meta),
+    ))
+}
+
+/// `ParseError` for a single error, and `ParseErrors` for several ones.
+fn parse_errors(mut errors: Vec<pest::error::Error<Rule>>) -> LibError {
+    if errors.len() == 1 {
+        LibError::ParseError(Box::new(errors.remove(0)))
+    } else {
+        LibError::ParseErrors(errors)
+    }
+}
+
+/// The error `message` about the input at `span`, reported like a syntax error.
+fn error(span: pest::Span, message: impl Into<String>) -> ParseError {
+    Box::new(pest::error::Error::new_from_span(
+        pest::error::ErrorVariant::CustomError {
+            message: message.into(),
+        },
+        span,
+    ))
+}
+
+/// The top-level definitions of a spec parsed so far.
+#[derive(Default)]
+struct Definitions<'i> {
+    meta: Meta,
+    includes: Vec<Include>,
+    items: Vec<SpecItem>,
+    /// The names of the anonymous structs of the items, with the name and definition of their
+    /// item.
+    anonymous_names: Vec<(String, String, pest::Span<'i>)>,
+}
+
+impl<'i> Definitions<'i> {
+    /// Parse the top-level definition `pair`.
+    fn parse(&mut self, pair: pest::iterators::Pair<'i, Rule>) -> Result<(), ParseError> {
+        let span = pair.as_span();
+        let mut anonymous = AnonymousStructs::default();
+        match pair.as_rule() {
+            Rule::meta_definition => {
+                self.meta = parse_meta_definition(pair)?;
+                return Ok(());
+            }
+            Rule::include_definition => {
+                let mut nodes = pair.into_inner();
+                let path = nodes.next().unwrap().into_inner().next().unwrap();
+                let namespace = nodes.next().unwrap();
+                self.includes.push(Include {
+                    path: path.as_str().to_owned(),
+                    namespace: namespace.as_str().to_owned(),
+                });
+                return Ok(());
+            }
+            Rule::mod_definition => {
+                let mut nodes = pair.into_inner();
+                let module = nodes.next().unwrap().as_str().to_owned();
+                let mut module_items = vec![];
+                for pair in nodes {
+                    module_items.push(parse_spec_item(pair, &mut anonymous)?);
+                    module_items.extend(anonymous.structs.drain(..).map(SpecItem::StructDef));
+                }
+                modules::annotate_module_items(&module, &mut module_items);
+                self.items.extend(module_items);
+                return Ok(());
+            }
+            Rule::service_definition => {
+                let services = parse_service_definition(pair, &mut anonymous)?;
+                self.items
+                    .extend(services.into_iter().map(SpecItem::ServiceDef));
+            }
+            _ => self.items.push(parse_spec_item(pair, &mut anonymous)?),
+        }
+        let owner = self.items.last().unwrap().name().to_owned();
+        for sdef in anonymous.structs {
+            self.anonymous_names
+                .push((owner.clone(), sdef.name.to_string(), span.clone()));
+            self.items.push(SpecItem::StructDef(sdef));
+        }
+        Ok(())
+    }
+}
+
+/// The keywords starting top-level definitions.
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "struct", "enum", "flags", "type", "service", "mod", "meta", "include",
+];
+
+/// The syntax errors of `input`, whose first one is `first`, by position.
+///
+/// The parser stops at the first error. To report all of them, each top-level definition (see
+/// `definition_chunks`) is parsed on its own, and the positions of its errors are moved to those in
+/// `input`. If the definitions match the grammar on their own, e.g. if a `meta` block is not the
+/// first definition, only `first` is reported.
+fn syntax_errors(input: &str, first: pest::error::Error<Rule>) -> Vec<pest::error::Error<Rule>> {
+    let mut errors: Vec<_> = definition_chunks(input)
+        .into_iter()
+        .filter_map(|chunk| {
+            let e = HumbleParser::parse(Rule::doc, &input[chunk.clone()]).err()?;
+            let position = |pos: usize| pest::Position::new(input, chunk.start + pos).unwrap();
+            Some(match e.location {
+                pest::error::InputLocation::Pos(pos) => {
+                    pest::error::Error::new_from_pos(e.variant, position(pos))
+                }
+                pest::error::InputLocation::Span((start, end)) => {
+                    pest::error::Error::new_from_span(
+                        e.variant,
+                        position(start).span(&position(end)),
+                    )
+                }
+            })
+        })
+        .collect();
+    if errors.is_empty() {
+        return vec![first];
+    }
+    errors.sort_by_key(|e| match e.location {
+        pest::error::InputLocation::Pos(pos) => pos,
+        pest::error::InputLocation::Span((start, _)) => start,
+    });
+    errors
+}
+
+/// The byte ranges of the top-level definitions of `input`, which start at the unindented lines
+/// beginning with a definition keyword, or with the doc comment or annotations preceding it.
+///
+/// The ranges are guessed from the layout, such that a definition lacking its closing `}` does not
+/// swallow the definitions after it.
+fn definition_chunks(input: &str) -> Vec<std::ops::Range<usize>> {
+    let mut starts = vec![0];
+    let mut offset = 0;
+    let mut after_prefix = false;
+    for line in input.split_inclusive('\n') {
+        let is_prefix = line.starts_with("///") || line.starts_with('@');
+        let is_definition = DEFINITION_KEYWORDS.iter().any(|keyword| {
+            line.starts_with(keyword)
+                && !line[keyword.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        });
+        if (is_prefix || is_definition) && !after_prefix && offset > 0 {
+            starts.push(offset);
+        }
+        if !line.trim().is_empty() {
+            after_prefix = is_prefix;
+        }
+        offset += line.len();
+    }
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).chain(Some(&input.len())))
+        .map(|(&start, &end)| start..end)
+        .collect()
+}
+
+/// Parse the `meta` block of a spec.
+fn parse_meta_definition(pair: pest::iterators::Pair<Rule>) -> Result<Meta, ParseError> {
+    let mut meta = Meta::default();
+    for field in pair.into_inner() {
+        let mut nodes = field.into_inner();
+        let key_pair = nodes.next().unwrap();
+        let key = key_pair.as_str();
+        let value_pair = nodes.next().unwrap();
+        let value = unescape(value_pair.clone().into_inner().next().unwrap().as_str());
+        let slot = match key {
+            "title" => &mut meta.title,
+            "version" => &mut meta.version,
+            "base_url" => &mut meta.base_url,
+            _ => {
+                return Err(error(
+                    key_pair.as_span(),
+                    format!(
+                        "unknown meta field `{}`, expected `title`, `version` or `base_url`",
+                        key
+                    ),
+                ))
+            }
+        };
+        check_meta_field(key, &value).map_err(|message| error(value_pair.as_span(), message))?;
+        if slot.is_some() {
+            return Err(error(
+                key_pair.as_span(),
+                format!("meta field `{}` is declared twice", key),
+            ));
+        }
+        *slot = Some(value);
+    }
+    Ok(meta)
+}
+
+/// Check the value of the meta field `key`, which is either parsed or part of a loaded spec.
+fn check_meta_field(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "version" if !value.chars().all(|c| c.is_ascii_graphic() || c == ' ') => Err(format!(
+            "meta field `version` must be printable ASCII to be sent in a header, but is \"{}\"",
+            value
+        )),
+        "base_url" if !crate::is_absolute_http_url(value) => Err(format!(
+            "meta field `base_url` must be an absolute http or https URL, but is \"{}\"",
+            value
+        )),
+        _ => Ok(()),
+    }
+}
+
 /// Parse a doc comment.
 ///
 /// Will peek at the `pairs` to see if the next item is a doc comment. If it is, remove it and
@@ -46,26 +347,441 @@ fn parse_doc_comment<'i>(pairs: &mut pest::iterators::Pairs<'i, Rule>) -> Option
     }
 }
 
+/// Annotations allowed on a service definition.
+const SERVICE_ANNOTATIONS: &[&str] = &["auth", "cors", "format", "graphql", "lossy"];
+
+/// Annotations allowed on a service endpoint.
+const ENDPOINT_ANNOTATIONS: &[&str] = &[
+    "auth",
+    "flag",
+    "idempotent",
+    "internal",
+    "lossy",
+    "max_body_size",
+    "paginated",
+    "slo",
+    "timeout",
+    "uncompressed",
+];
+
+/// Annotations allowed on an enum definition.
+const ENUM_ANNOTATIONS: &[&str] = &[
+    "derive",
+    "export",
+    "lossy",
+    "non_exhaustive",
+    "numeric",
+    "rust_attr",
+    "tag",
+    "untagged",
+];
+
+/// Annotations allowed on an enum variant.
+const VARIANT_ANNOTATIONS: &[&str] = &["json_name", "lossy", "rust_attr", "status"];
+
+/// Annotations allowed on a flags definition.
+const FLAGS_ANNOTATIONS: &[&str] = &["export", "rust_attr"];
+
+/// Annotations allowed on a flag.
+const FLAG_ANNOTATIONS: &[&str] = &["json_name"];
+
+/// Annotations allowed on a union of string literals.
+const LITERAL_UNION_ANNOTATIONS: &[&str] = &["derive", "export", "non_exhaustive", "rust_attr"];
+
+/// Annotations allowed on a struct definition.
+const STRUCT_ANNOTATIONS: &[&str] = &[
+    "db_row",
+    "derive",
+    "export",
+    "lossy",
+    "partial",
+    "rust_attr",
+    "rust_from",
+];
+
+/// Annotations allowed on a tuple struct definition.
+const TUPLE_STRUCT_ANNOTATIONS: &[&str] = &["derive", "export", "lossy", "rust_attr"];
+
+/// Annotations allowed on a struct field.
+const STRUCT_FIELD_ANNOTATIONS: &[&str] = &[
+    "column",
+    "default",
+    "json_name",
+    "lossy",
+    "renamed_from",
+    "rust_attr",
+    "server_set",
+];
+
+/// Annotations allowed on a field of an enum struct variant.
+const VARIANT_FIELD_ANNOTATIONS: &[&str] = &["json_name", "lossy", "rust_attr"];
+
+/// Annotations allowed on an embed.
+const EMBED_ANNOTATIONS: &[&str] = &["nested"];
+
+/// Parse all annotations, failing on annotations not contained in `allowed` or with invalid
+/// arguments.
+fn parse_annotations(
+    pairs: &mut pest::iterators::Pairs<Rule>,
+    allowed: &[&str],
+) -> Result<Vec<Annotation>, ParseError> {
+    let mut annotations = vec![];
+    while let Some(pair) = pairs.peek() {
+        if pair.as_rule() != Rule::annotation {
+            break;
+        }
+        pairs.next().unwrap(); // consume what we peeked
+        let span = pair.as_span();
+        let mut nodes = pair.into_inner();
+        let name_span = nodes.next().unwrap().as_span();
+        let name = name_span.as_str();
+        if !allowed.contains(&name) {
+            return Err(error(
+                name_span,
+                format!(
+                    "annotation `@{}` is not allowed here, expected one of {:?}",
+                    name, allowed
+                ),
+            ));
+        }
+        let args: Vec<String> = nodes
+            .next()
+            .map(|args| {
+                args.into_inner()
+                    .flat_map(|arg| {
+                        let arg = arg.into_inner().next().unwrap();
+                        match arg.as_rule() {
+                            Rule::annotation_string => vec![unescape(arg.as_str())],
+                            // `key = ["a", "b"]` results in the arguments `key=a` and `key=b`
+                            Rule::annotation_list => {
+                                let mut nodes = arg.into_inner();
+                                let key = nodes.next().unwrap().as_str();
+                                nodes
+                                    .map(|item| {
+                                        format!("{}={}", key, unescape(item.into_inner().as_str()))
+                                    })
+                                    .collect()
+                            }
+                            _ => vec![arg.as_str().replace(' ', "")],
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        check_annotation_args(name, &args).map_err(|message| error(span, message))?;
+        annotations.push(Annotation {
+            name: name.to_string(),
+            args,
+        });
+    }
+    Ok(annotations)
+}
+
+/// Checks the arguments of the annotation `name`, which is either parsed or part of a loaded spec.
+fn check_annotation_args(name: &str, args: &[String]) -> Result<(), String> {
+    let invalid = |message: &str| Err(message.to_string());
+    // the arguments of other annotations end up in string literals of all backends
+    if name != "rust_attr" && args.iter().any(|arg| arg.contains('"')) {
+        return invalid(&format!("arguments of `@{}` cannot contain `\"`", name));
+    }
+    match (name, args) {
+        ("auth", [])
+        | ("graphql", [])
+        | ("idempotent", [])
+        | ("internal", [])
+        | ("paginated", [])
+        | ("uncompressed", []) => {}
+        ("status", [code]) if matches!(code.parse::<u16>(), Ok(100..=599)) => {}
+        ("status", _) => return invalid("`@status` expects an HTTP status code, e.g. `@status(404)`"),
+        ("slo", [target]) if Slo::from_annotation_arg(target).is_some() => {}
+        ("slo", _) => return invalid("`@slo` expects a latency target, e.g. `@slo(p99 = 200ms)`"),
+        ("max_body_size", [size]) if matches!(parse_byte_size(size), Some(1..)) => {}
+        ("max_body_size", _) => {
+            return invalid("`@max_body_size` expects a size in `B`, `KB`, `MB`, `GB`, `KiB`, `MiB` or `GiB`, e.g. `@max_body_size(1MB)`")
+        }
+        ("timeout", [timeout]) if matches!(parse_duration_ms(timeout), Some(1..)) => {}
+        ("timeout", _) => {
+            return invalid("`@timeout` expects a duration in `ms` or `s`, e.g. `@timeout(30s)`")
+        }
+        ("format", formats)
+            if !formats.is_empty()
+                && formats
+                    .iter()
+                    .all(|format| WireFormat::from_annotation_arg(format).is_some()) => {}
+        ("format", _) => {
+            return invalid("`@format` expects wire formats, i.e., `@format(json)`, `@format(cbor)`, `@format(msgpack)` or several, e.g. `@format(cbor, msgpack)`")
+        }
+        ("rust_from", [path]) if syn::parse_str::<syn::Path>(path).is_ok() => {}
+        ("rust_from", _) => {
+            return invalid("`@rust_from` expects the path of a Rust type, e.g. `@rust_from(\"crate::domain::Customer\")`")
+        }
+        // the name ends up in string literals of all backends
+        ("json_name", [name]) if !name.contains(&['\\', '$'][..]) => {}
+        ("json_name", _) => {
+            return invalid("`@json_name` expects the name on the wire without `\\` and `$`, e.g. `@json_name(\"userID\")`")
+        }
+        ("tag", [tag]) if !tag.contains(&['\\', '$'][..]) => {}
+        ("tag", [tag, content])
+            if tag != content && !format!("{}{}", tag, content).contains(&['\\', '$'][..]) => {}
+        ("tag", _) => {
+            return invalid("`@tag` expects the key of the variant name and optionally the key of the content, without `\\` and `$`, e.g. `@tag(\"type\")` or `@tag(\"type\", \"value\")`")
+        }
+        ("untagged", []) => {}
+        ("non_exhaustive", []) => {}
+        ("numeric", []) => {}
+        ("rust_attr", [attr]) if is_rust_attributes(attr) => {}
+        ("rust_attr", _) => {
+            return invalid("`@rust_attr` expects Rust attributes, with `\\\"` for quotes, e.g. `@rust_attr(\"#[serde(with = \\\"my_codec\\\")]\")`")
+        }
+        ("db_row", []) => {}
+        ("export", []) => {}
+        ("column", [name]) if !name.contains(&['\\', '$'][..]) => {}
+        ("column", _) => {
+            return invalid("`@column` expects the name of the database column without `\\` and `$`, e.g. `@column(\"monster_name\")`")
+        }
+        ("derive", derives)
+            if !derives.is_empty()
+                && derives
+                    .iter()
+                    .all(|derive| Derive::from_annotation_arg(derive).is_some()) => {}
+        ("derive", _) => {
+            return invalid("`@derive` expects Rust traits to derive, i.e., `PartialEq`, `Eq`, `Hash`, `PartialOrd`, `Ord` or `Default`, e.g. `@derive(PartialEq, Hash)`")
+        }
+        ("nested", []) => {}
+        ("nested", [name]) if is_snake_case_ident(name) => {}
+        ("nested", _) => {
+            return invalid("`@nested` expects the snake_case name of the field nesting the embedded struct, if not named after it, e.g. `@nested` or `@nested(\"legacy_data\")`")
+        }
+        ("cors", origins)
+            if !origins.is_empty()
+                && origins
+                    .iter()
+                    .all(|o| o.strip_prefix("origins=").is_some_and(is_cors_origin)) => {}
+        ("cors", _) => {
+            return invalid("`@cors` expects the allowed origins, e.g. `@cors(origins = [\"https://app.example.com\"])` or `@cors(origins = [\"*\"])`")
+        }
+        ("flag", [flag]) if !flag.is_empty() => {}
+        ("flag", _) => {
+            return invalid("`@flag` expects the name of a feature flag, e.g. `@flag(\"new-billing\")`")
+        }
+        ("partial", [name]) if syn::parse_str::<syn::Ident>(name).is_ok() => {}
+        ("partial", _) => {
+            return invalid("`@partial` expects the name of the partial struct, e.g. `@partial(\"MonsterPatch\")`")
+        }
+        ("lossy", types)
+            if !types.is_empty() && types.iter().all(|t| atom_from_keyword(t).is_some()) => {}
+        ("lossy", _) => {
+            return invalid("`@lossy` expects the built-in types whose lossy mappings are accepted, e.g. `@lossy(u32)` or `@lossy(u8, date)`")
+        }
+        ("default", [_]) | ("server_set", []) => {}
+        ("default", _) => {
+            return invalid("`@default` expects the value of an absent field, e.g. `@default(0)` or `@default(\"unknown\")`")
+        }
+        // added to the items of modules, see `modules::annotate_module_items`
+        ("module", [_, _]) => {}
+        ("renamed_from", [_]) => {}
+        ("renamed_from", _) => {
+            return invalid(
+                "`@renamed_from` expects the former field name, e.g. `@renamed_from(\"name\")`",
+            )
+        }
+        (_, _) => {
+            return invalid(&format!(
+                "annotation `@{}` does not take arguments {:?}",
+                name, args
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Whether `name` is a field name like the grammar's `snake_case_ident`, e.g. `legacy_data`.
+fn is_snake_case_ident(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Unescape the `\"` and `\\` of an annotation string.
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&next)) if next == '"' || next == '\\' => {
+                unescaped.push(next);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Whether `attr` is one or more outer Rust attributes, e.g. `#[serde(default)]`.
+fn is_rust_attributes(attr: &str) -> bool {
+    use syn::parse::Parser;
+    syn::Attribute::parse_outer
+        .parse_str(attr)
+        .is_ok_and(|attrs| !attrs.is_empty())
+}
+
+/// Whether `origin` is an origin allowed by `@cors`, i.e., `*` or a scheme and host with an
+/// optional port, e.g. `https://app.example.com:8443`.
+fn is_cors_origin(origin: &str) -> bool {
+    if origin == "*" {
+        return true;
+    }
+    match origin.split_once("://") {
+        Some((scheme, host)) => {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+                && !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-.:[]".contains(c))
+        }
+        None => false,
+    }
+}
+
+/// The anonymous structs of the definition being parsed, e.g. `{ lat: f64, lon: f64 }` of
+/// field `position: { lat: f64, lon: f64 }`, which follow the definition in the spec.
+///
+/// They are named after the path to them, e.g. `LocationPosition` for field `position` of struct
+/// `Location`, `ShapeCircleCenter` for field `center` of variant `Circle` of enum `Shape`, and
+/// `MonsterApiPostMonstersBody` for the body of `POST /monsters` of service `MonsterApi`.
+#[derive(Default)]
+struct AnonymousStructs {
+    /// The path to the fields being parsed, e.g. `ShapeCircle`.
+    prefix: String,
+    structs: Vec<StructDef>,
+}
+
+impl AnonymousStructs {
+    /// Call `f` with `name` appended to the path to the fields being parsed.
+    fn with_prefix<T>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> T) -> T {
+        let len = self.prefix.len();
+        self.prefix.push_str(name);
+        let result = f(self);
+        self.prefix.truncate(len);
+        result
+    }
+
+    /// Parse the anonymous struct `pair` named `name` after the path to it, returning the type
+    /// referencing it.
+    fn parse(
+        &mut self,
+        name: &str,
+        pair: pest::iterators::Pair<Rule>,
+    ) -> Result<TypeIdent, ParseError> {
+        let span = pair.as_span();
+        let fields_pair = pair.into_inner().next().unwrap();
+        // the struct precedes the anonymous structs of its fields
+        let index = self.structs.len();
+        let fields = self.with_prefix(name, |anonymous| {
+            parse_struct_fields(fields_pair, STRUCT_FIELD_ANNOTATIONS, anonymous)
+        })?;
+        let name = format!("{}{}", self.prefix, name);
+        if self.structs.iter().any(|s| s.name == name) {
+            return Err(error(
+                span,
+                format!(
+                    "anonymous struct `{}` is named like another anonymous struct, define a struct instead",
+                    name
+                ),
+            ));
+        }
+        self.structs.insert(
+            index,
+            StructDef {
+                name: name.clone().into(),
+                fields,
+                doc_comment: None,
+                annotations: vec![],
+            },
+        );
+        Ok(TypeIdent::UserDefined(name.into()))
+    }
+}
+
 /// Parse a struct definition.
-fn parse_struct_definition(pair: pest::iterators::Pair<Rule>) -> StructDef {
+fn parse_struct_definition(
+    pair: pest::iterators::Pair<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<StructDef, ParseError> {
     let mut nodes = pair.into_inner();
 
     let doc_comment = parse_doc_comment(&mut nodes);
+    let annotations = parse_annotations(&mut nodes, STRUCT_ANNOTATIONS)?;
 
-    let name = nodes.next().unwrap().as_span().as_str().to_string();
-    let fields = parse_struct_fields(nodes.next().unwrap());
+    let name_span = nodes.next().unwrap().as_span();
+    let name = name_span.as_str().to_string();
+    let fields = anonymous.with_prefix(&name, |anonymous| {
+        parse_struct_fields(nodes.next().unwrap(), STRUCT_FIELD_ANNOTATIONS, anonymous)
+    })?;
 
-    StructDef {
-        name,
+    let sdef = StructDef {
+        name: name.into(),
         fields,
         doc_comment,
+        annotations,
+    };
+    check_columns(&sdef).map_err(|message| error(name_span, message))?;
+    Ok(sdef)
+}
+
+/// Fail if a field of the struct has a `@column`, but the struct is no `@db_row`.
+fn check_columns(sdef: &StructDef) -> Result<(), String> {
+    if !sdef.is_db_row() {
+        if let Some(field) = sdef.fields.iter().find(|f| f.column().is_some()) {
+            return Err(format!(
+                "`@column` of field `{}` is only allowed in structs annotated with `@db_row`, which `{}` is not",
+                field.pair.name, sdef.name
+            ));
+        }
     }
+    Ok(())
+}
+
+/// Parse a tuple struct definition.
+fn parse_tuple_struct_definition(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<TupleStructDef, ParseError> {
+    let mut nodes = pair.into_inner();
+
+    let doc_comment = parse_doc_comment(&mut nodes);
+    let annotations = parse_annotations(&mut nodes, TUPLE_STRUCT_ANNOTATIONS)?;
+
+    let name = nodes.next().unwrap().as_span().as_str().to_string();
+    let elements = nodes.next().unwrap();
+    let elements = match elements.as_rule() {
+        Rule::tuple_def => parse_tuple_def(elements),
+        Rule::newtype_def => TupleDef(vec![parse_type_ident(
+            elements.into_inner().next().unwrap(),
+        )]),
+        x => panic!("unexpected token {:?}", x),
+    };
+
+    Ok(TupleStructDef {
+        name: name.into(),
+        elements,
+        doc_comment,
+        annotations,
+    })
 }
 
 /// Parse inner struct fields of struct definition.
-fn parse_struct_fields(pair: pest::iterators::Pair<Rule>) -> StructFields {
-    let pair = pair;
-    let fields: Vec<_> = pair
+fn parse_struct_fields(
+    pair: pest::iterators::Pair<Rule>,
+    annotations: &[&str],
+    anonymous: &mut AnonymousStructs,
+) -> Result<StructFields, ParseError> {
+    let spans: Vec<_> = pair.clone().into_inner().map(|p| p.as_span()).collect();
+    let fields = pair
         .into_inner()
         .map(|p| {
             assert_eq!(p.as_rule(), Rule::struct_field_def);
@@ -77,63 +793,403 @@ fn parse_struct_fields(pair: pest::iterators::Pair<Rule>) -> StructFields {
                     // let mut nodes = struct_field_def.into_inner();
                     // let field_def_node = nodes.next().unwrap();
                     // assert_eq!(nodes.next(), None);
-                    parse_struct_field_def_node(struct_field_def)
+                    parse_struct_field_def_node(struct_field_def, annotations, anonymous)
                 }
                 Rule::struct_field_def_embed => {
-                    // the grammar guarantees that struct field names are snake_case
-                    // and that struct type names are PascalCase
-                    // => a struct type name is never a valid field name
-                    // ==> for embeds, use the struct type name as field name and do the fixup in spec_resolve_embeds
                     let mut nodes = struct_field_def.into_inner();
+                    let embed_annotations = parse_annotations(&mut nodes, EMBED_ANNOTATIONS)?;
                     let ty = nodes.next().unwrap();
                     assert_eq!(nodes.next(), None);
-                    FieldNode {
+                    let type_name = ty.as_span().as_str().to_string();
+                    // nested embeds are fields of the embedded struct, named by `@nested` or
+                    // after the struct
+                    let name = match embed_annotations.iter().find(|a| a.name == "nested") {
+                        Some(nested) => match nested.args.first() {
+                            Some(name) => name.clone(),
+                            None => inflector::cases::snakecase::to_snake_case(&type_name),
+                        },
+                        // the grammar guarantees that struct field names are snake_case
+                        // and that struct type names are PascalCase
+                        // => a struct type name is never a valid field name
+                        // ==> for embeds, use the struct type name as field name and do the fixup in spec_resolve_embeds
+                        None => type_name,
+                    };
+                    Ok(FieldNode {
                         doc_comment: None,
+                        annotations: vec![],
+                        optional: false,
                         pair: FieldDefPair {
-                            name: ty.as_span().as_str().to_string(),
+                            name,
                             type_ident: parse_type_ident(ty),
                         },
-                    }
+                    })
                 }
                 x => panic!("unexpected token {:?}", x),
             }
         })
-        .collect();
-    StructFields(fields)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    check_field_names(&fields).map_err(|(i, message)| error(spans[i].clone(), message))?;
+    Ok(StructFields(fields))
+}
+
+/// Fail with the index of the field and a message if a wire name or former name of a field is
+/// taken by another field, which would make the key ambiguous.
+fn check_field_names(fields: &[FieldNode]) -> Result<(), (usize, String)> {
+    for (i, field) in fields.iter().enumerate() {
+        let wire_name = field.wire_name(WireCasing::Spec);
+        if fields[..i]
+            .iter()
+            .any(|f| f.wire_name(WireCasing::Spec) == wire_name)
+        {
+            return Err((
+                i,
+                format!(
+                    "field `{}` cannot be named `{}` on the wire, which names another field",
+                    field.pair.name, wire_name
+                ),
+            ));
+        }
+    }
+    for (i, field) in fields.iter().enumerate() {
+        for old_name in field.renamed_from() {
+            if fields
+                .iter()
+                .any(|f| f.wire_name(WireCasing::Spec) == old_name)
+            {
+                return Err((
+                    i,
+                    format!(
+                        "field `{}` cannot be renamed from `{}`, which names another field",
+                        field.pair.name, old_name
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Parse enum definition.
-fn parse_enum_definition(pair: pest::iterators::Pair<Rule>) -> EnumDef {
+fn parse_enum_definition(
+    pair: pest::iterators::Pair<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<EnumDef, ParseError> {
     let mut outer_nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut outer_nodes);
+    let annotations = parse_annotations(&mut outer_nodes, ENUM_ANNOTATIONS)?;
     let mut nodes = outer_nodes.next().unwrap().into_inner();
-    let name = nodes.next().unwrap().as_span().as_str().to_string();
-    let variants = nodes.map(parse_enum_variant_def).collect();
+    let name_span = nodes.next().unwrap().as_span();
+    let name = name_span.as_str().to_string();
+    let spans: Vec<_> = nodes.clone().map(|node| node.as_span()).collect();
+    let variants: Vec<VariantDef> = nodes
+        .map(|node| {
+            anonymous.with_prefix(&name, |anonymous| parse_enum_variant_def(node, anonymous))
+        })
+        .collect::<Result<_, _>>()?;
+
+    check_variant_names(&variants).map_err(|(i, message)| error(spans[i].clone(), message))?;
+
+    let edef = EnumDef {
+        name: name.into(),
+        variants,
+        doc_comment,
+        annotations,
+    };
+    validate_tagging(&edef)
+        .and_then(|()| validate_non_exhaustive(&edef))
+        .and_then(|()| validate_discriminants(&edef))
+        .map_err(|message| error(name_span, message))?;
+    Ok(edef)
+}
+
+/// Fail with the index of the variant and a message if the wire name of a variant is taken by
+/// another variant.
+fn check_variant_names(variants: &[VariantDef]) -> Result<(), (usize, String)> {
+    for (i, variant) in variants.iter().enumerate() {
+        let wire_name = variant.wire_name(WireCasing::Spec);
+        if variants[..i]
+            .iter()
+            .any(|v| v.wire_name(WireCasing::Spec) == wire_name)
+        {
+            return Err((
+                i,
+                format!(
+                    "variant `{}` cannot be named `{}` on the wire, which names another variant",
+                    variant.name, wire_name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Parse flags definition.
+fn parse_flags_definition(pair: pest::iterators::Pair<Rule>) -> Result<FlagsDef, ParseError> {
+    let mut nodes = pair.into_inner();
+    let doc_comment = parse_doc_comment(&mut nodes);
+    let annotations = parse_annotations(&mut nodes, FLAGS_ANNOTATIONS)?;
+    let name_span = nodes.next().unwrap().as_span();
+    let name = name_span.as_str().to_string();
+    let flag_pairs = nodes.next().unwrap().into_inner();
+    let spans: Vec<_> = flag_pairs.clone().map(|pair| pair.as_span()).collect();
+    let flags: Vec<FlagDef> = flag_pairs
+        .map(|pair| {
+            let mut nodes = pair.into_inner();
+            let doc_comment = parse_doc_comment(&mut nodes);
+            let annotations = parse_annotations(&mut nodes, FLAG_ANNOTATIONS)?;
+            Ok(FlagDef {
+                name: nodes.next().unwrap().as_str().to_string(),
+                doc_comment,
+                annotations,
+            })
+        })
+        .collect::<Result<_, ParseError>>()?;
+
+    check_flags(&name, &flags).map_err(|(i, message)| match i {
+        Some(i) => error(spans[i].clone(), message),
+        None => error(name_span, message),
+    })?;
+
+    Ok(FlagsDef {
+        name: name.into(),
+        flags,
+        doc_comment,
+        annotations,
+    })
+}
+
+/// Fail with a message, and the index of the flag if it is to blame, if the flags are not
+/// between 1 and 64 flags with distinct names.
+fn check_flags(name: &str, flags: &[FlagDef]) -> Result<(), (Option<usize>, String)> {
+    if flags.is_empty() {
+        return Err((
+            None,
+            format!("flags `{}` must have at least one flag", name),
+        ));
+    }
+    if flags.len() > 64 {
+        return Err((
+            None,
+            format!("flags `{}` cannot have more than 64 flags", name),
+        ));
+    }
+    for (i, flag) in flags.iter().enumerate() {
+        if flags[..i].iter().any(|f| f.name == flag.name) {
+            return Err((
+                Some(i),
+                format!("flag `{}` of flags `{}` is defined twice", flag.name, name),
+            ));
+        }
+        let wire_name = flag.wire_name(WireCasing::Spec);
+        if flags[..i]
+            .iter()
+            .any(|f| f.wire_name(WireCasing::Spec) == wire_name)
+        {
+            return Err((
+                Some(i),
+                format!(
+                    "flag `{}` cannot be named `{}` on the wire, which names another flag",
+                    flag.name, wire_name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Parse a union of string literals, e.g. `type Status = "active" | "deleted";`, into an enum
+/// of simple variants named after the literals in `PascalCase`, which are their wire names.
+fn parse_literal_union_definition(
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<EnumDef, ParseError> {
+    let mut nodes = pair.into_inner();
+    let doc_comment = parse_doc_comment(&mut nodes);
+    let annotations = parse_annotations(&mut nodes, LITERAL_UNION_ANNOTATIONS)?;
+    let name_span = nodes.next().unwrap().as_span();
+    let name = name_span.as_str().to_string();
+
+    let mut variants: Vec<VariantDef> = vec![];
+    for pair in nodes {
+        let span = pair.as_span();
+        let literal = unescape(pair.into_inner().next().unwrap().as_str());
+        let variant_name = literal.to_pascal_case();
+        if !variant_name.starts_with(|c: char| c.is_ascii_uppercase())
+            || !variant_name.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(error(
+                span,
+                format!(
+                    "literal \"{}\" of type `{}` cannot be named as a variant, which must start with a letter",
+                    literal, name
+                ),
+            ));
+        }
+        // the literal is the `@json_name` of the variant
+        check_annotation_args("json_name", std::slice::from_ref(&literal)).map_err(|_| {
+            error(
+                span.clone(),
+                format!(
+                    "literal \"{}\" of type `{}` cannot contain `\"`, `\\` or `$`",
+                    literal, name
+                ),
+            )
+        })?;
+        if let Some(other) = variants.iter().find(|v| v.name == variant_name) {
+            let other = other.json_name().unwrap();
+            let message = if other == literal {
+                format!("literal \"{}\" of type `{}` is listed twice", literal, name)
+            } else {
+                format!(
+                    "literals \"{}\" and \"{}\" of type `{}` are both named `{}` as variants",
+                    other, literal, name, variant_name
+                )
+            };
+            return Err(error(span, message));
+        }
+        variants.push(VariantDef {
+            name: variant_name,
+            variant_type: VariantType::Simple,
+            doc_comment: None,
+            annotations: vec![Annotation {
+                name: "json_name".to_owned(),
+                args: vec![literal],
+            }],
+            discriminant: None,
+        });
+    }
 
-    EnumDef {
-        name,
+    let edef = EnumDef {
+        name: name.into(),
         variants,
         doc_comment,
+        annotations,
+    };
+    validate_non_exhaustive(&edef).map_err(|message| error(name_span, message))?;
+    Ok(edef)
+}
+
+/// Fail if the enum has discriminants or is annotated with `@numeric`, but has complex variants
+/// or variants sharing a discriminant.
+fn validate_discriminants(edef: &EnumDef) -> Result<(), String> {
+    if !edef.is_numeric() && edef.variants.iter().all(|v| v.discriminant.is_none()) {
+        return Ok(());
+    }
+    if let Some(variant) = edef.complex_variants().next() {
+        return Err(format!(
+            "variant `{}` of enum `{}` must be simple, as the enum is `@numeric` or has discriminants",
+            variant.name, edef.name
+        ));
+    }
+    let mut previous: Option<i64> = None;
+    for (i, variant) in edef.variants.iter().enumerate() {
+        if variant.discriminant.is_none() && previous == Some(i64::MAX) {
+            return Err(format!(
+                "the discriminant of variant `{}` overflows, give it one explicitly",
+                variant.name
+            ));
+        }
+        let discriminant = edef.discriminants()[i];
+        if edef.discriminants()[..i].contains(&discriminant) {
+            return Err(format!(
+                "variant `{}` cannot have the discriminant `{}`, which another variant has",
+                variant.name, discriminant
+            ));
+        }
+        previous = Some(discriminant);
+    }
+    Ok(())
+}
+
+/// Fail if the enum is annotated with `@non_exhaustive`, but unknown variants cannot be told
+/// apart from its variants.
+fn validate_non_exhaustive(edef: &EnumDef) -> Result<(), String> {
+    if !edef.is_non_exhaustive() {
+        return Ok(());
+    }
+    if edef.tagging() == EnumTagging::Untagged {
+        return Err(format!(
+            "`@non_exhaustive` enum `{}` cannot be `@untagged`, which has no variant names to tell unknown variants by",
+            edef.name
+        ));
+    }
+    if edef.variants.iter().any(|v| v.name == "Unknown") {
+        return Err(format!(
+            "`@non_exhaustive` enum `{}` cannot have a variant named `Unknown`, which stands for unknown variants",
+            edef.name
+        ));
+    }
+    Ok(())
+}
+
+/// Fail if the enum cannot be represented as chosen by its annotations.
+fn validate_tagging(edef: &EnumDef) -> Result<(), String> {
+    if edef
+        .annotations
+        .iter()
+        .filter(|a| a.name == "tag" || a.name == "untagged" || a.name == "numeric")
+        .count()
+        > 1
+    {
+        return Err(format!(
+            "enum `{}` can only have one representation",
+            edef.name
+        ));
+    }
+    if let EnumTagging::Internal { tag } = edef.tagging() {
+        for variant in &edef.variants {
+            match &variant.variant_type {
+                VariantType::Simple => {}
+                VariantType::Struct(fields) => {
+                    if fields
+                        .iter()
+                        .any(|field| field.wire_name(WireCasing::Spec) == tag)
+                    {
+                        return Err(format!(
+                            "a field of variant `{}` is named like the tag `{}` on the wire",
+                            variant.name, tag
+                        ));
+                    }
+                }
+                VariantType::Newtype(_) | VariantType::Tuple(_) => {
+                    return Err(format!(
+                        "variant `{}` of internally tagged enum `{}` must be a simple or struct variant",
+                        variant.name, edef.name
+                    ))
+                }
+            }
+        }
     }
+    Ok(())
 }
 
 /// Parse enum variant definitions.
-fn parse_enum_variant_def(pair: pest::iterators::Pair<Rule>) -> VariantDef {
+fn parse_enum_variant_def(
+    pair: pest::iterators::Pair<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<VariantDef, ParseError> {
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
+    let annotations = parse_annotations(&mut nodes, VARIANT_ANNOTATIONS)?;
     let name = nodes.next().unwrap().as_span().as_str().to_string();
 
-    if let Some(var) = nodes.next() {
+    let variant = if let Some(var) = nodes.next() {
         match var.as_rule() {
             Rule::struct_fields => VariantDef {
+                variant_type: VariantType::Struct(anonymous.with_prefix(&name, |anonymous| {
+                    parse_struct_fields(var, VARIANT_FIELD_ANNOTATIONS, anonymous)
+                })?),
                 name,
-                variant_type: VariantType::Struct(parse_struct_fields(var)),
                 doc_comment,
+                annotations,
+                discriminant: None,
             },
             Rule::tuple_def => VariantDef {
                 name,
                 variant_type: VariantType::Tuple(parse_tuple_def(var)),
                 doc_comment,
+                annotations,
+                discriminant: None,
             },
             Rule::newtype_def => VariantDef {
                 name,
@@ -141,20 +1197,36 @@ fn parse_enum_variant_def(pair: pest::iterators::Pair<Rule>) -> VariantDef {
                     var.into_inner().next().unwrap(),
                 )),
                 doc_comment,
+                annotations,
+                discriminant: None,
             },
-            _ => unreachable!(dbg!(var)),
+            Rule::variant_discriminant => VariantDef {
+                variant_type: VariantType::Simple,
+                doc_comment,
+                annotations,
+                discriminant: Some(var.as_str().parse().map_err(|_| {
+                    error(
+                        var.as_span(),
+                        format!("the discriminant of variant `{}` is out of range", name),
+                    )
+                })?),
+                name,
+            },
+            _ => unreachable!("{}", dbg!(var)),
         }
     } else {
         VariantDef {
             name,
             variant_type: VariantType::Simple,
             doc_comment,
+            annotations,
+            discriminant: None,
         }
-    }
+    };
+    Ok(variant)
 }
 
 fn parse_struct_field_def_pair(pair: pest::iterators::Pair<Rule>) -> FieldDefPair {
-    let pair = pair;
     let mut nodes = pair.into_inner();
     let name = nodes.next().unwrap().as_span().as_str().to_string();
     let type_ident = parse_type_ident(nodes.next().unwrap());
@@ -163,41 +1235,219 @@ fn parse_struct_field_def_pair(pair: pest::iterators::Pair<Rule>) -> FieldDefPai
 }
 
 /// Parse field definitions in struct.
-fn parse_struct_field_def_node(pair: pest::iterators::Pair<Rule>) -> FieldNode {
-    let pair = pair;
+fn parse_struct_field_def_node(
+    pair: pest::iterators::Pair<Rule>,
+    allowed_annotations: &[&str],
+    anonymous: &mut AnonymousStructs,
+) -> Result<FieldNode, ParseError> {
+    let span = pair.as_span();
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
-    let pair = parse_struct_field_def_pair(nodes.next().unwrap());
-    FieldNode { pair, doc_comment }
+    let annotations = parse_annotations(&mut nodes, allowed_annotations)?;
+    let optional = nodes
+        .peek()
+        .map(|p| p.as_rule() == Rule::optional_modifier)
+        .unwrap_or(false);
+    if optional {
+        nodes.next();
+    }
+    let name = nodes.next().unwrap().as_span().as_str().to_string();
+    let ty = nodes.next().unwrap();
+    let type_ident = match ty.as_rule() {
+        Rule::anonymous_struct => anonymous.parse(&name.to_pascal_case(), ty)?,
+        _ => parse_type_ident(ty),
+    };
+    assert_eq!(nodes.next(), None);
+    let pair = FieldDefPair { name, type_ident };
+    let field = FieldNode {
+        pair,
+        doc_comment,
+        annotations,
+        optional,
+    };
+    check_field_default(&field).map_err(|message| error(span, message))?;
+    Ok(field)
 }
 
-fn parse_service_definition(pair: pest::iterators::Pair<Rule>) -> ServiceDef {
+/// Check that fields with a default are not `optional`, which are absent instead, and the
+/// default itself, see `check_default_value`.
+fn check_field_default(field: &FieldNode) -> Result<(), String> {
+    let name = &field.pair.name;
+    if field.optional && (field.default_value().is_some() || field.is_server_set()) {
+        return Err(format!(
+            "`optional` field `{}` cannot have a default, it is absent instead",
+            name
+        ));
+    }
+    check_default_value(field)
+}
+
+/// Check that a `@default` value is a literal of the field type, and that the field is not
+/// `@server_set`.
+fn check_default_value(field: &FieldNode) -> Result<(), String> {
+    let name = &field.pair.name;
+    let value = match field.default_value() {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    if field.is_server_set() {
+        return Err(format!(
+            "field `{}` cannot have a `@default`, it is `@server_set`",
+            name
+        ));
+    }
+    let is_valid = match &field.pair.type_ident {
+        TypeIdent::BuiltIn(AtomType::Str) => true,
+        TypeIdent::BuiltIn(AtomType::Bool) => value == "true" || value == "false",
+        TypeIdent::BuiltIn(AtomType::I32) => value.parse::<i32>().is_ok(),
+        TypeIdent::BuiltIn(AtomType::U32) => value.parse::<u32>().is_ok(),
+        TypeIdent::BuiltIn(AtomType::U8) => value.parse::<u8>().is_ok(),
+        TypeIdent::BuiltIn(AtomType::I64) => value.parse::<i64>().is_ok(),
+        TypeIdent::BuiltIn(AtomType::U64) => value.parse::<u64>().is_ok(),
+        TypeIdent::BuiltIn(AtomType::F64) => value.parse::<f64>().is_ok_and(f64::is_finite),
+        _ => false,
+    };
+    if !is_valid {
+        return Err(format!(
+            "`@default({})` of field `{}` must be a literal of its type, which must be `str`, `bool` or a number",
+            value, name
+        ));
+    }
+    Ok(())
+}
+
+/// Parse a service definition.
+///
+/// A service with `version N { .. }` blocks is split into a service per version, named like
+/// `MonsterApiV2`, whose routes are prefixed with `/v2`. Each version has the endpoints of its
+/// block and the endpoints outside of blocks, which are shared by all versions.
+fn parse_service_definition(
+    pair: pest::iterators::Pair<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<Vec<ServiceDef>, ParseError> {
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
+    let annotations = parse_annotations(&mut nodes, SERVICE_ANNOTATIONS)?;
     let name = nodes.next().unwrap().as_span().as_str().to_string();
-    let endpoints = nodes
-        .next()
-        .unwrap()
-        .into_inner()
-        .map(parse_service_rule)
-        .collect();
+
+    // the endpoints in spec order, with the version of their block
+    let mut endpoints: Vec<(Option<u32>, ServiceEndpoint)> = vec![];
+    let mut versions: Vec<u32> = vec![];
+    for item in nodes.next().unwrap().into_inner() {
+        if item.as_rule() == Rule::service_rule {
+            endpoints.push((
+                None,
+                anonymous.with_prefix(&name, |anonymous| parse_service_rule(item, anonymous))?,
+            ));
+            continue;
+        }
+        let span = item.as_span();
+        let mut version_nodes = item.into_inner();
+        let version = version_nodes
+            .next()
+            .unwrap()
+            .as_str()
+            .parse()
+            .map_err(|_| error(span.clone(), "version numbers must fit into 32 bits"))?;
+        if versions.contains(&version) {
+            return Err(error(
+                span,
+                format!("service `{}` has two `version {}` blocks", name, version),
+            ));
+        }
+        versions.push(version);
+        let prefix = format!("{}V{}", name, version);
+        for rule in version_nodes {
+            let endpoint =
+                anonymous.with_prefix(&prefix, |anonymous| parse_service_rule(rule, anonymous))?;
+            endpoints.push((Some(version), endpoint));
+        }
+    }
     assert_eq!(nodes.next(), None);
-    ServiceDef {
-        doc_comment,
-        name,
-        endpoints,
+
+    if versions.is_empty() {
+        return Ok(vec![ServiceDef {
+            doc_comment,
+            name: name.into(),
+            annotations,
+            endpoints: endpoints
+                .into_iter()
+                .map(|(_, endpoint)| endpoint)
+                .collect(),
+        }]);
     }
+    let services = versions
+        .iter()
+        .map(|&version| ServiceDef {
+            doc_comment: doc_comment.clone(),
+            name: format!("{}V{}", name, version).into(),
+            annotations: annotations.clone(),
+            endpoints: endpoints
+                .iter()
+                .filter(|(v, _)| v.is_none_or(|v| v == version))
+                .map(|(_, endpoint)| {
+                    let mut endpoint = endpoint.clone();
+                    endpoint
+                        .route
+                        .components_mut()
+                        .insert(0, ServiceRouteComponent::Literal(format!("v{}", version)));
+                    endpoint
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(services)
 }
 
-fn parse_service_rule(pair: pest::iterators::Pair<Rule>) -> ServiceEndpoint {
+fn parse_service_rule(
+    pair: pest::iterators::Pair<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<ServiceEndpoint, ParseError> {
+    let span = pair.as_span();
     let mut nodes = pair.into_inner();
     let doc_comment = parse_doc_comment(&mut nodes);
-    let route = parse_service_rule_def(nodes.next().unwrap());
+    let mut annotations = parse_annotations(&mut nodes, ENDPOINT_ANNOTATIONS)?;
+    let def = nodes.next().unwrap();
+    // `paginated[T]` implies `@paginated`, its envelope is expanded by `pagination`
+    if def
+        .clone()
+        .into_inner()
+        .any(|n| n.as_rule() == Rule::paginated_type)
+        && !annotations.iter().any(|a| a.name == "paginated")
+    {
+        annotations.push(Annotation {
+            name: "paginated".to_owned(),
+            args: vec![],
+        });
+    }
+    let route = parse_service_rule_def(def, anonymous)?;
     assert_eq!(nodes.next(), None);
-    ServiceEndpoint { doc_comment, route }
+    let endpoint = ServiceEndpoint {
+        doc_comment,
+        annotations,
+        route,
+    };
+    check_idempotent(&endpoint).map_err(|message| error(span, message))?;
+    Ok(endpoint)
+}
+
+/// Fail if a `GET` endpoint is annotated with `@idempotent`.
+fn check_idempotent(endpoint: &ServiceEndpoint) -> Result<(), String> {
+    if matches!(endpoint.route, ServiceRoute::Get { .. })
+        && endpoint.annotations.iter().any(|a| a.name == "idempotent")
+    {
+        return Err(
+            "`@idempotent` is only allowed on mutating endpoints, i.e., not on `GET` endpoints"
+                .to_owned(),
+        );
+    }
+    Ok(())
 }
 
-fn parse_service_rule_def(pair: pest::iterators::Pair<Rule>) -> ServiceRoute {
+fn parse_service_rule_def(
+    pair: pest::iterators::Pair<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<ServiceRoute, ParseError> {
     let mut nodes = pair.into_inner();
     let parser = match nodes.peek().unwrap().as_rule() {
         Rule::http_get => parse_service_rule_get,
@@ -208,59 +1458,162 @@ fn parse_service_rule_def(pair: pest::iterators::Pair<Rule>) -> ServiceRoute {
         x => panic!("unexpected token {:?}", x),
     };
     nodes.next().unwrap(); // consume what we peeked
-    let route = parser(&mut nodes);
+    let route = parser(&mut nodes, anonymous)?;
     assert_eq!(nodes.next(), None);
-    route
+    Ok(route)
 }
 
-fn parse_service_rule_get(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Get {
+fn parse_service_rule_get(
+    pair: &mut pest::iterators::Pairs<Rule>,
+    _: &mut AnonymousStructs,
+) -> Result<ServiceRoute, ParseError> {
+    Ok(ServiceRoute::Get {
         components: parse_http_route(pair.next().unwrap()),
         query: parse_http_query(pair),
-        ret: parse_type_ident(pair.next().unwrap()),
+        headers: parse_http_headers(pair),
+        ret: parse_get_return_type(pair.next().unwrap()),
+        response_headers: parse_response_headers(pair),
+    })
+}
+
+/// Parse the return type of a `GET` route, where `paginated[T]` results in `list[T]`.
+fn parse_get_return_type(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
+    match pair.as_rule() {
+        Rule::paginated_type => TypeIdent::List(Box::new(parse_type_ident(
+            pair.into_inner().next().unwrap(),
+        ))),
+        _ => parse_type_ident(pair),
     }
 }
 
-fn parse_service_rule_delete(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Delete {
+fn parse_service_rule_delete(
+    pair: &mut pest::iterators::Pairs<Rule>,
+    _: &mut AnonymousStructs,
+) -> Result<ServiceRoute, ParseError> {
+    Ok(ServiceRoute::Delete {
         components: parse_http_route(pair.next().unwrap()),
         query: parse_http_query(pair),
+        headers: parse_http_headers(pair),
         ret: parse_type_ident(pair.next().unwrap()),
-    }
+        response_headers: parse_response_headers(pair),
+    })
 }
 
-fn parse_service_rule_post(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Post {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        body: parse_type_ident(pair.next().unwrap()),
+fn parse_service_rule_post(
+    pair: &mut pest::iterators::Pairs<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<ServiceRoute, ParseError> {
+    let components = parse_http_route(pair.next().unwrap());
+    let query = parse_http_query(pair);
+    let headers = parse_http_headers(pair);
+    let body_name = body_struct_name("Post", &components);
+    let (body_kind, body) = parse_request_body(pair.next().unwrap(), &body_name, anonymous)?;
+    Ok(ServiceRoute::Post {
+        components,
+        query,
+        headers,
+        body,
+        body_kind,
         ret: parse_type_ident(pair.next().unwrap()),
-    }
+        response_headers: parse_response_headers(pair),
+    })
 }
 
-fn parse_service_rule_put(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Put {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        body: parse_type_ident(pair.next().unwrap()),
+fn parse_service_rule_put(
+    pair: &mut pest::iterators::Pairs<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<ServiceRoute, ParseError> {
+    let components = parse_http_route(pair.next().unwrap());
+    let query = parse_http_query(pair);
+    let headers = parse_http_headers(pair);
+    let body_name = body_struct_name("Put", &components);
+    let (body_kind, body) = parse_request_body(pair.next().unwrap(), &body_name, anonymous)?;
+    Ok(ServiceRoute::Put {
+        components,
+        query,
+        headers,
+        body,
+        body_kind,
         ret: parse_type_ident(pair.next().unwrap()),
-    }
+        response_headers: parse_response_headers(pair),
+    })
 }
 
-fn parse_service_rule_patch(pair: &mut pest::iterators::Pairs<Rule>) -> ServiceRoute {
-    ServiceRoute::Patch {
-        components: parse_http_route(pair.next().unwrap()),
-        query: parse_http_query(pair),
-        body: parse_type_ident(pair.next().unwrap()),
+fn parse_service_rule_patch(
+    pair: &mut pest::iterators::Pairs<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<ServiceRoute, ParseError> {
+    let components = parse_http_route(pair.next().unwrap());
+    let query = parse_http_query(pair);
+    let headers = parse_http_headers(pair);
+    let body_name = body_struct_name("Patch", &components);
+    let (body_kind, body) = parse_request_body(pair.next().unwrap(), &body_name, anonymous)?;
+    Ok(ServiceRoute::Patch {
+        components,
+        query,
+        headers,
+        body,
+        body_kind,
         ret: parse_type_ident(pair.next().unwrap()),
+        response_headers: parse_response_headers(pair),
+    })
+}
+
+/// The name of an anonymous request body struct of an endpoint, e.g. `PostMonstersIdRenameBody`
+/// for `POST /monsters/{id}/rename`, prefixed by the service.
+fn body_struct_name(method: &str, components: &[ServiceRouteComponent]) -> String {
+    let mut name = method.to_owned();
+    for component in components {
+        match component {
+            ServiceRouteComponent::Literal(literal) => name.push_str(&literal.to_pascal_case()),
+            ServiceRouteComponent::Variable(pair) | ServiceRouteComponent::Rest(pair) => {
+                name.push_str(&pair.name.to_pascal_case())
+            }
+        }
     }
+    name.push_str("Body");
+    name
+}
+
+/// Parse a request body type, which may be wrapped in `multipart[...]` or be an anonymous
+/// struct named `body_name`.
+fn parse_request_body(
+    pair: pest::iterators::Pair<Rule>,
+    body_name: &str,
+    anonymous: &mut AnonymousStructs,
+) -> Result<(BodyKind, TypeIdent), ParseError> {
+    let inner = pair.into_inner().next().unwrap();
+    Ok(match inner.as_rule() {
+        Rule::multipart_body => {
+            let ident = inner.into_inner().next().unwrap();
+            (
+                BodyKind::Multipart,
+                TypeIdent::UserDefined(ident.as_str().into()),
+            )
+        }
+        Rule::anonymous_struct => (BodyKind::Json, anonymous.parse(body_name, inner)?),
+        Rule::type_ident => (BodyKind::Json, parse_type_ident(inner)),
+        _ => unreachable!("{}", dbg!(inner)),
+    })
 }
 
 fn parse_http_route(pair: pest::iterators::Pair<Rule>) -> Vec<ServiceRouteComponent> {
-    pair.into_inner().map(parse_http_route_segment).collect()
+    let mut components = vec![];
+    for segment in pair.into_inner() {
+        let component = parse_http_route_segment(segment, components.last());
+        components.push(component);
+    }
+    components
 }
 
-fn parse_http_route_segment(pair: pest::iterators::Pair<Rule>) -> ServiceRouteComponent {
+/// Parse a route segment, following the `previous` one.
+///
+/// Path parameters without a name, e.g. `{:uuid}` in `/monsters/{:uuid}`, are named after the
+/// previous literal segment in singular, i.e., `monster`, or `param` if there is none.
+fn parse_http_route_segment(
+    pair: pest::iterators::Pair<Rule>,
+    previous: Option<&ServiceRouteComponent>,
+) -> ServiceRouteComponent {
     let mut nodes = pair.into_inner();
     let comp = nodes.next().unwrap();
     match comp.as_rule() {
@@ -269,8 +1622,28 @@ fn parse_http_route_segment(pair: pest::iterators::Pair<Rule>) -> ServiceRouteCo
         }
         Rule::http_route_segment_arg => {
             let mut nodes = comp.into_inner();
-            let ret =
-                ServiceRouteComponent::Variable(parse_struct_field_def_pair(nodes.next().unwrap()));
+            let arg = nodes.next().unwrap();
+            let ret = match arg.as_rule() {
+                Rule::struct_field_def_pair => {
+                    ServiceRouteComponent::Variable(parse_struct_field_def_pair(arg))
+                }
+                Rule::http_route_segment_rest => ServiceRouteComponent::Rest(
+                    parse_struct_field_def_pair(arg.into_inner().next().unwrap()),
+                ),
+                Rule::http_route_segment_type => {
+                    let name = match previous {
+                        Some(ServiceRouteComponent::Literal(literal)) => {
+                            inflector::string::singularize::to_singular(
+                                &inflector::cases::snakecase::to_snake_case(literal),
+                            )
+                        }
+                        _ => "param".to_owned(),
+                    };
+                    let type_ident = parse_type_ident(arg.into_inner().next().unwrap());
+                    ServiceRouteComponent::Variable(FieldDefPair { name, type_ident })
+                }
+                x => panic!("unexpected token {:?}", x),
+            };
             assert_eq!(nodes.next(), None);
             ret
         }
@@ -285,9 +1658,58 @@ fn parse_http_query(pairs: &mut pest::iterators::Pairs<Rule>) -> Option<TypeIden
     }
     let next = pairs.next().unwrap(); // consume
     let mut tokens = next.into_inner();
-    let ret = Some(parse_type_ident(tokens.next().unwrap()));
+    let token = tokens.next().unwrap();
+    let ret = match token.as_rule() {
+        Rule::type_ident => parse_type_ident(token),
+        // `?query(T)` only admits user-defined structs
+        Rule::http_query_struct => {
+            let ident = token.into_inner().next().unwrap();
+            TypeIdent::UserDefined(ident.as_str().into())
+        }
+        _ => unreachable!("{}", dbg!(token)),
+    };
     assert_eq!(tokens.next(), None);
-    ret
+    Some(ret)
+}
+
+/// Parse all header parameters of a service rule.
+fn parse_response_headers(pairs: &mut pest::iterators::Pairs<Rule>) -> Vec<HeaderParam> {
+    match pairs.peek() {
+        Some(pair) if pair.as_rule() == Rule::response_headers => {
+            pairs.next().unwrap(); // consume what we peeked
+            let mut headers = pair.into_inner();
+            let response_headers = parse_http_headers(&mut headers);
+            assert_eq!(headers.next(), None);
+            response_headers
+        }
+        _ => vec![],
+    }
+}
+
+fn parse_http_headers(pairs: &mut pest::iterators::Pairs<Rule>) -> Vec<HeaderParam> {
+    let mut headers = vec![];
+    while let Some(pair) = pairs.peek() {
+        if pair.as_rule() != Rule::http_header {
+            break;
+        }
+        pairs.next().unwrap(); // consume what we peeked
+        let mut nodes = pair.into_inner();
+        let name = nodes.next().unwrap().into_inner().next().unwrap();
+        let ty = nodes.next().unwrap();
+        let type_ident = match ty.as_rule() {
+            Rule::http_header_atom => TypeIdent::BuiltIn(parse_built_in_atom(ty)),
+            Rule::http_header_optional => TypeIdent::Option(Box::new(TypeIdent::BuiltIn(
+                parse_built_in_atom(ty.into_inner().next().unwrap()),
+            ))),
+            _ => unreachable!("{}", dbg!(ty)),
+        };
+        assert_eq!(nodes.next(), None);
+        headers.push(HeaderParam {
+            name: name.as_span().as_str().to_string(),
+            type_ident,
+        });
+    }
+    headers
 }
 
 /// Parse type identifier.
@@ -300,18 +1722,27 @@ fn parse_type_ident(pair: pest::iterators::Pair<Rule>) -> TypeIdent {
         Rule::result_type => parse_result_type(inner),
         Rule::map_type => parse_map_type(inner),
         Rule::tuple_def => TypeIdent::Tuple(parse_tuple_def(inner)),
-        Rule::camel_case_ident => TypeIdent::UserDefined(inner.as_span().as_str().to_string()),
-        _ => unreachable!(dbg!(inner)),
+        Rule::camel_case_ident | Rule::qualified_ident => {
+            TypeIdent::UserDefined(inner.as_str().into())
+        }
+        _ => unreachable!("{}", dbg!(inner)),
     }
 }
 
 /// Parse a built-in atomic type.
 fn parse_built_in_atom(pair: pest::iterators::Pair<Rule>) -> AtomType {
-    match pair.as_span().as_str() {
+    atom_from_keyword(pair.as_span().as_str()).unwrap_or_else(|| unreachable!("{}", dbg!(pair)))
+}
+
+/// The built-in atomic type of `keyword`, e.g. `u32`, if any.
+pub(crate) fn atom_from_keyword(keyword: &str) -> Option<AtomType> {
+    Some(match keyword {
         "()" => AtomType::Empty,
         "str" => AtomType::Str,
         "i32" => AtomType::I32,
         "u32" => AtomType::U32,
+        "i64" => AtomType::I64,
+        "u64" => AtomType::U64,
         "u8" => AtomType::U8,
         "f64" => AtomType::F64,
         "bool" => AtomType::Bool,
@@ -319,8 +1750,8 @@ fn parse_built_in_atom(pair: pest::iterators::Pair<Rule>) -> AtomType {
         "date" => AtomType::Date,
         "uuid" => AtomType::Uuid,
         "bytes" => AtomType::Bytes,
-        _ => unreachable!(dbg!(pair)),
-    }
+        _ => return None,
+    })
 }
 
 /// Parse a list type.
@@ -366,12 +1797,19 @@ fn parse_tuple_def(pair: pest::iterators::Pair<Rule>) -> TupleDef {
     TupleDef(pair.into_inner().map(parse_type_ident).collect())
 }
 
-/// Parse a spec item (`struct` or `enum`).
-fn parse_spec_item(pair: pest::iterators::Pair<Rule>) -> SpecItem {
-    match pair.as_rule() {
-        Rule::struct_definition => SpecItem::StructDef(parse_struct_definition(pair)),
-        Rule::enum_definition => SpecItem::EnumDef(parse_enum_definition(pair)),
-        Rule::service_definition => SpecItem::ServiceDef(parse_service_definition(pair)),
-        _ => unreachable!(dbg!(pair)),
-    }
+/// Parse a spec item (`struct`, `enum`, `flags` or `type`).
+fn parse_spec_item(
+    pair: pest::iterators::Pair<Rule>,
+    anonymous: &mut AnonymousStructs,
+) -> Result<SpecItem, ParseError> {
+    Ok(match pair.as_rule() {
+        Rule::struct_definition => SpecItem::StructDef(parse_struct_definition(pair, anonymous)?),
+        Rule::tuple_struct_definition => {
+            SpecItem::TupleStructDef(parse_tuple_struct_definition(pair)?)
+        }
+        Rule::enum_definition => SpecItem::EnumDef(parse_enum_definition(pair, anonymous)?),
+        Rule::flags_definition => SpecItem::FlagsDef(parse_flags_definition(pair)?),
+        Rule::literal_union_definition => SpecItem::EnumDef(parse_literal_union_definition(pair)?),
+        _ => unreachable!("{}", dbg!(pair)),
+    })
 }
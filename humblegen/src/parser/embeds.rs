@@ -32,10 +32,23 @@
 //! }
 //! ```
 //!
+//! Embeds annotated with `@nested` are not flattened, but nested as a field of the embedded
+//! struct, named after it or by the annotation, e.g. for consumers expecting a sub-object:
+//!
+//! ```text
+//! struct LegacyMonster {
+//!     id: i32,
+//!     @nested("data") .. MonsterData,
+//! }
+//! ```
+//!
+//! is equivalent to `struct LegacyMonster { id: i32, data: MonsterData }`, which the parser
+//! produces right away, so nested embeds are plain fields to this transformation.
+//!
 //! # Rules
 //!
 //! - `MAX_EMBED_DEPTH` limits the maximum depth to which embeds are resolved.
-//!   Exceeding that limit results in a panic.
+//!   Exceeding that limit results in a `LibError::InvalidDefinition`.
 //! - No need for declare-before-use.
 //!
 //! # Limitations
@@ -44,118 +57,120 @@
 //!   We rely on the rust compiler for that.
 //!
 //! - Embed-loops are not explicitly checked for but, since they are equivalent
-//!   to infintely deep embeds, will result in a panic due to transgression of
+//!   to infintely deep embeds, will result in an error due to transgression of
 //!   the `MAX_EMBED_DEPTH` limit.
 //!
 //! # Implementation:
 //!
 //! - AST representation of an embed is a bit hacky, see `FieldDefPair::is_embed`
-//! - Fixed-point iteration that resolves embeds by one level per iteration.
-//! - AST updates are performed in two phases (collect, update) in order to paciy
-//!   the borrow checker and avoid iterator invalidation.
+//! - The fields of embedded structs are looked up by name in a `SpecIndex` and resolved
+//!   recursively, memoizing the resolved fields of every embedded struct. Only field lists that
+//!   contain embeds are copied, which keeps large specs cheap to resolve.
+//! - AST updates are performed in two phases (collect, update) in order to pacify
+//!   the borrow checker.
 
 use crate::ast::*;
+use crate::LibError;
 use std::collections::HashMap;
-use std::iter::FromIterator;
 
 const MAX_EMBED_DEPTH: usize = 10;
 
-pub(crate) fn resolve_embeds(spec: &mut Spec) {
-    let changed = std::cell::Cell::new(true);
-    for _ in (0..=MAX_EMBED_DEPTH).take_while(|_| changed.get()) {
-        changed.set(spec_resolve_embeds_one_level(spec));
+pub(crate) fn resolve_embeds(spec: &mut Spec) -> Result<(), LibError> {
+    // collect: the resolved fields of every field list containing embeds, identified by item and,
+    // for enums, variant
+    let mut replacements: Vec<(ItemId, Option<usize>, Vec<FieldNode>)> = vec![];
+    {
+        let mut resolver = Resolver {
+            index: spec.index(),
+            resolved: HashMap::new(),
+        };
+        for (id, item) in spec.iter_with_ids() {
+            let invalid = |message| LibError::InvalidDefinition {
+                definition: item.name().to_owned(),
+                message,
+            };
+            match item {
+                SpecItem::StructDef(sdef) if has_embeds(&sdef.fields) => {
+                    let fields = resolver.resolve_fields(&sdef.fields, 1).map_err(invalid)?;
+                    replacements.push((id, None, fields));
+                }
+                SpecItem::EnumDef(edef) => {
+                    for (i, variant) in edef.variants.iter().enumerate() {
+                        if let Some(fields) = variant.variant_type.struct_fields() {
+                            if has_embeds(fields) {
+                                let fields = resolver.resolve_fields(fields, 1).map_err(invalid)?;
+                                replacements.push((id, Some(i), fields));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
     }
-    if changed.get() {
-        panic!("maximum embed depth is {}", MAX_EMBED_DEPTH);
+
+    // update
+    for (id, variant, fields) in replacements {
+        let struct_fields = match (spec.get_mut(id), variant) {
+            (SpecItem::StructDef(sdef), None) => &mut sdef.fields,
+            (SpecItem::EnumDef(edef), Some(i)) => edef.variants[i]
+                .variant_type
+                .struct_fields_mut()
+                .expect("replacements are only collected for struct variants"),
+            _ => unreachable!("replacements are only collected for structs and enums"),
+        };
+        struct_fields.0 = fields;
     }
+    Ok(())
 }
 
-fn spec_resolve_embeds_one_level(spec: &mut Spec) -> bool {
-    let mut changed = false;
-
-    let all_structs_field_nodes: HashMap<String, &'_ Vec<FieldNode>> = HashMap::from_iter(
-        spec.iter()
-            .filter_map(|spec_item| match spec_item {
-                SpecItem::StructDef(def) => Some(vec![(def.name.clone(), &def.fields.0)]),
-                _ => None,
-            })
-            .flatten(),
-    );
+fn has_embeds(fields: &StructFields) -> bool {
+    fields.iter().any(|field| field.pair.is_embed())
+}
 
-    // find the Vec<FieldNodes> that require expansion ("replacement") and queue those replacement operations
-    // in hash map `replacements`
-    let mut replacements: HashMap<String, Vec<FieldNode>> = HashMap::new();
-    let struct_defs = spec
-        .iter()
-        .filter_map(|spec_item| match spec_item {
-            SpecItem::StructDef(def) => Some(vec![(def.name.clone(), &def.fields.0)]),
-            SpecItem::EnumDef(def) => Some(
-                def.variants
-                    .iter()
-                    .filter_map(|v| {
-                        v.variant_type
-                            .struct_fields()
-                            .map(|sf| (format!("{}.{}", def.name, v.name), &sf.0))
-                    })
-                    .collect::<Vec<_>>(),
-            ),
-            _ => None,
-        })
-        .flatten();
-    for (struct_name, field_nodes) in struct_defs {
-        let new_field_nodes = field_nodes
-            .iter()
-            .map(|field_node| {
-                if field_node.pair.is_embed() {
-                    changed = true;
-                    let embedded_field_nodes = all_structs_field_nodes
-                        .get(&field_node.pair.name)
-                        .unwrap_or_else(|| {
-                            panic!(
-                                "humble spec references unknown type {:?} in embed",
-                                field_node.pair.name
-                            )
-                        });
-                    (*embedded_field_nodes).clone()
-                } else {
-                    vec![field_node.clone()]
-                }
-            })
-            .flatten();
+struct Resolver<'a> {
+    index: SpecIndex<'a>,
+    /// The resolved fields of embedded structs by name.
+    resolved: HashMap<&'a str, Vec<FieldNode>>,
+}
 
-        let replacements = replacements.entry(struct_name).or_default();
-        replacements.extend(new_field_nodes);
+impl<'a> Resolver<'a> {
+    /// The fields of `fields` with all embeds replaced by the fields of the embedded struct,
+    /// where `depth` is the embed depth of `fields`.
+    fn resolve_fields(
+        &mut self,
+        fields: &StructFields,
+        depth: usize,
+    ) -> Result<Vec<FieldNode>, String> {
+        let mut resolved = Vec::with_capacity(fields.0.len());
+        for field_node in fields.iter() {
+            if field_node.pair.is_embed() {
+                resolved.extend(
+                    self.resolve_embed(&field_node.pair.name, depth)?
+                        .iter()
+                        .cloned(),
+                );
+            } else {
+                resolved.push(field_node.clone());
+            }
+        }
+        Ok(resolved)
     }
-    drop(all_structs_field_nodes);
-
-    // apply replacements
-    let struct_defs_mut = spec
-        .iter_mut()
-        .filter_map(|spec_item| match spec_item {
-            SpecItem::StructDef(def) => Some(vec![(def.name.clone(), &mut def.fields.0)]),
-            SpecItem::EnumDef(def) => Some({
-                let enum_name = def.name.clone();
-                def.variants
-                    .iter_mut()
-                    .filter_map(|v| {
-                        let anon_struct_name = format!("{}.{}", enum_name, v.name);
-                        v.variant_type
-                            .struct_fields_mut()
-                            .map(|sf| (anon_struct_name, &mut sf.0))
-                    })
-                    .collect::<Vec<_>>()
-            }),
-            _ => None,
-        })
-        .flatten();
-    for (struct_name, struct_field_nodes_ptr) in struct_defs_mut {
-        let (_, new_field_nodes) = match replacements.remove_entry(&struct_name) {
-            Some(n) => n,
-            None => continue,
-        };
 
-        *struct_field_nodes_ptr = new_field_nodes;
+    /// The resolved fields of the struct `name` embedded at `depth`.
+    fn resolve_embed(&mut self, name: &str, depth: usize) -> Result<&[FieldNode], String> {
+        if depth > MAX_EMBED_DEPTH {
+            // embed loops are infinitely deep
+            return Err(format!("maximum embed depth is {}", MAX_EMBED_DEPTH));
+        }
+        let sdef = self
+            .index
+            .struct_def(name)
+            .ok_or_else(|| format!("embeds unknown struct `{}`", name))?;
+        if !self.resolved.contains_key(sdef.name.as_str()) {
+            let fields = self.resolve_fields(&sdef.fields, depth + 1)?;
+            self.resolved.insert(&sdef.name, fields);
+        }
+        Ok(&self.resolved[sdef.name.as_str()])
     }
-
-    changed
 }
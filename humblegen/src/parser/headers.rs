@@ -0,0 +1,96 @@
+//! Validation of the request and response headers of endpoints.
+//!
+//! # Example
+//!
+//! ```text
+//! service MonsterApi {
+//!     GET /monsters/{id: i32} header "X-Request-Id": uuid -> Monster with header "ETag": str,
+//! }
+//! ```
+//!
+//! Backends name headers in snake_case, e.g. the parameter `x_request_id` of handlers and client
+//! methods, and the field `e_tag` of the response.
+//!
+//! # Rules
+//!
+//! - Header names start with an ASCII letter, followed by ASCII alphanumerics and `-`.
+//! - The request headers of an endpoint have distinct names in snake_case, which are neither
+//!   names of its path parameters nor `ctx`, `principal`, `post_body`, `query` or
+//!   `idempotency_key`, the other parameters of handlers and client methods.
+//! - The response headers of an endpoint have distinct names in snake_case.
+//! - Endpoints violating a rule result in a `LibError::InvalidDefinition` of their service.
+
+use crate::ast::*;
+use crate::LibError;
+use inflector::cases::snakecase::to_snake_case;
+
+/// The names of the parameters of handlers and client methods other than path parameters and
+/// headers.
+const RESERVED_PARAMS: &[&str] = &["ctx", "principal", "post_body", "query", "idempotency_key"];
+
+pub(crate) fn validate_headers(spec: &Spec) -> Result<(), LibError> {
+    for service in spec.iter().filter_map(SpecItem::service_def) {
+        for endpoint in &service.endpoints {
+            if let Err(violation) = check_headers(&endpoint.route) {
+                return Err(LibError::InvalidDefinition {
+                    definition: service.name.to_string(),
+                    message: format!(
+                        "endpoint `{} {}` {}",
+                        endpoint.route.http_method_as_str(),
+                        endpoint.route.path(),
+                        violation
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_headers(route: &ServiceRoute) -> Result<(), String> {
+    let mut params: Vec<String> = route
+        .components()
+        .iter()
+        .filter_map(|component| Some(component.param()?.name.clone()))
+        .collect();
+    params.extend(RESERVED_PARAMS.iter().map(|&param| param.to_owned()));
+    for header in route.headers() {
+        let param = check_header_name(header)?;
+        if params.contains(&param) {
+            return Err(format!(
+                "has header `{}` named `{}` like another parameter",
+                header.name, param
+            ));
+        }
+        params.push(param);
+    }
+
+    let mut fields: Vec<String> = vec![];
+    for header in route.response_headers() {
+        let field = check_header_name(header)?;
+        if fields.contains(&field) {
+            return Err(format!(
+                "has response header `{}` named `{}` like another response header",
+                header.name, field
+            ));
+        }
+        fields.push(field);
+    }
+    Ok(())
+}
+
+/// The name of `header` in snake_case, if it is a valid header name.
+fn check_header_name(header: &HeaderParam) -> Result<String, String> {
+    let is_valid = header.name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && header
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !is_valid {
+        return Err(format!(
+            "has header `{}`, which must start with a letter, followed by letters, digits and `-`",
+            header.name
+        ));
+    }
+    Ok(to_snake_case(&header.name))
+}
@@ -0,0 +1,297 @@
+//! Implementation of humblespec includes as an AST transformation.
+//!
+//! Includes allow consuming humblespec fragments published by third parties, e.g. vendored
+//! into the repository, without copying their definitions into the spec.
+//!
+//! # Example
+//!
+//! ```text
+//! include "vendor/stripe_types.humble" as stripe
+//!
+//! struct Order {
+//!     customer: stripe.Customer,
+//! }
+//! ```
+//!
+//! # Rules
+//!
+//! - Includes precede all definitions. Paths are relative to the including spec.
+//! - The definitions of a fragment are only accessible qualified by the namespace of the
+//!   include, e.g. `stripe.Customer`.
+//! - Included definitions are read-only: fragments may only define types (no services and no
+//!   further includes), and the including spec can neither redefine nor shadow them.
+//!
+//! # Implementation
+//!
+//! - Qualified names cannot be identifiers in generated code, so included definitions are
+//!   renamed to their namespace in PascalCase followed by their name (`StripeCustomer`), which
+//!   does not affect the wire format.
+//! - The included definitions precede the definitions of the including spec.
+
+use super::{parse_items, Include};
+use crate::ast::*;
+use crate::LibError;
+use inflector::cases::pascalcase::to_pascal_case;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Load the fragments of `includes` with `load_include` and add their definitions to `spec`,
+/// resolving all qualified names.
+pub(crate) fn resolve_includes(
+    includes: Vec<Include>,
+    spec: Spec,
+    load_include: &mut dyn FnMut(&str) -> Result<String, LibError>,
+) -> Result<Spec, LibError> {
+    // qualified name -> generated name
+    let mut qualified_names = BTreeMap::new();
+    let mut namespaces = BTreeSet::new();
+    let mut items = vec![];
+    for include in includes {
+        if !namespaces.insert(include.namespace.clone())
+            || spec
+                .iter()
+                .any(|item| item.module().map(|(module, _)| module) == Some(&include.namespace))
+        {
+            return Err(include_error(
+                &include,
+                format!("namespace `{}` is used more than once", include.namespace),
+            ));
+        }
+
+        let input = load_include(&include.path)?;
+        let (nested_includes, Spec(mut fragment, meta)) =
+            parse_items(&input).map_err(|e| e.with_path(&include.path))?;
+        if !meta.is_empty() {
+            return Err(include_error(
+                &include,
+                "fragments must not have a meta block".to_owned(),
+            ));
+        }
+        if !nested_includes.is_empty() {
+            return Err(include_error(
+                &include,
+                "fragments must not include other fragments".to_owned(),
+            ));
+        }
+        if let Some((module, _)) = fragment.iter().find_map(SpecItem::module) {
+            return Err(include_error(
+                &include,
+                format!(
+                    "fragments must not define modules, but define `mod {}`",
+                    module
+                ),
+            ));
+        }
+        if let Some(service) = fragment.iter().find_map(SpecItem::service_def) {
+            return Err(include_error(
+                &include,
+                format!(
+                    "fragments must only define types, but define service `{}`",
+                    service.name
+                ),
+            ));
+        }
+
+        // within the fragment, its definitions are referenced by their unqualified names
+        let renames: BTreeMap<String, String> = fragment
+            .iter()
+            .map(|item| {
+                (
+                    item.name().to_owned(),
+                    format!("{}{}", to_pascal_case(&include.namespace), item.name()),
+                )
+            })
+            .collect();
+        for item in &mut fragment {
+            rename_item(item, &mut |name| {
+                if name.contains('.') {
+                    return Err(format!(
+                        "fragments cannot reference qualified name `{}`",
+                        name
+                    ));
+                }
+                Ok(renames.get(name).cloned())
+            })
+            .map_err(|msg| include_error(&include, msg))?;
+        }
+        for (name, generated_name) in renames {
+            qualified_names.insert(format!("{}.{}", include.namespace, name), generated_name);
+        }
+        items.extend(fragment);
+    }
+
+    let included_names: BTreeSet<&String> = qualified_names.values().collect();
+    for item in spec.iter() {
+        if included_names.contains(&item.name().to_owned()) {
+            return Err(LibError::IncludeError(format!(
+                "`{}` conflicts with the name of an included definition, which are read-only",
+                item.name()
+            )));
+        }
+    }
+
+    let mut local_items = spec.0;
+    for item in &mut local_items {
+        rename_item(item, &mut |name| match name.split_once('.') {
+            None => Ok(None),
+            Some((namespace, _)) if !namespaces.contains(namespace) => {
+                Err(format!("unknown namespace `{}` in `{}`", namespace, name))
+            }
+            Some((namespace, _)) => qualified_names
+                .get(name)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| format!("namespace `{}` does not define `{}`", namespace, name)),
+        })
+        .map_err(LibError::IncludeError)?;
+    }
+    items.extend(local_items);
+    Ok(Spec(items, spec.1))
+}
+
+fn include_error(include: &Include, msg: String) -> LibError {
+    LibError::IncludeError(format!("{} (included as `{}`)", msg, include.namespace))
+}
+
+/// Renames a referenced type to `Ok(Some(new_name))`, keeps it with `Ok(None)` or fails.
+type Rename<'a> = dyn FnMut(&str) -> Result<Option<String>, String> + 'a;
+
+/// Rename the definition `item` and all types it references with `rename`.
+pub(super) fn rename_item(item: &mut SpecItem, rename: &mut Rename) -> Result<(), String> {
+    match item {
+        SpecItem::StructDef(sdef) => {
+            if let Some(name) = rename(&sdef.name)? {
+                sdef.name = name.into();
+            }
+            rename_fields(&mut sdef.fields, rename)
+        }
+        SpecItem::TupleStructDef(tdef) => {
+            if let Some(name) = rename(&tdef.name)? {
+                tdef.name = name.into();
+            }
+            for ty in &mut tdef.elements.0 {
+                rename_type(ty, rename)?;
+            }
+            Ok(())
+        }
+        SpecItem::EnumDef(edef) => {
+            if let Some(name) = rename(&edef.name)? {
+                edef.name = name.into();
+            }
+            for variant in &mut edef.variants {
+                match &mut variant.variant_type {
+                    VariantType::Simple => {}
+                    VariantType::Newtype(ty) => rename_type(ty, rename)?,
+                    VariantType::Tuple(tdef) => {
+                        for ty in &mut tdef.0 {
+                            rename_type(ty, rename)?;
+                        }
+                    }
+                    VariantType::Struct(fields) => rename_fields(fields, rename)?,
+                }
+            }
+            Ok(())
+        }
+        SpecItem::FlagsDef(fdef) => {
+            if let Some(name) = rename(&fdef.name)? {
+                fdef.name = name.into();
+            }
+            Ok(())
+        }
+        SpecItem::ServiceDef(service) => {
+            for endpoint in &mut service.endpoints {
+                rename_route(&mut endpoint.route, rename)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn rename_fields(fields: &mut StructFields, rename: &mut Rename) -> Result<(), String> {
+    for field in &mut fields.0 {
+        let is_embed = field.pair.is_embed();
+        rename_type(&mut field.pair.type_ident, rename)?;
+        if is_embed {
+            // embeds are named after the embedded struct, see `FieldDefPair::is_embed`
+            field.pair.name = field.pair.type_ident.user_defined().unwrap().to_string();
+        }
+    }
+    Ok(())
+}
+
+fn rename_route(route: &mut ServiceRoute, rename: &mut Rename) -> Result<(), String> {
+    let (components, query, headers, body, ret) = match route {
+        ServiceRoute::Get {
+            components,
+            query,
+            headers,
+            ret,
+            ..
+        }
+        | ServiceRoute::Delete {
+            components,
+            query,
+            headers,
+            ret,
+            ..
+        } => (components, query, headers, None, ret),
+        ServiceRoute::Post {
+            components,
+            query,
+            headers,
+            body,
+            ret,
+            ..
+        }
+        | ServiceRoute::Put {
+            components,
+            query,
+            headers,
+            body,
+            ret,
+            ..
+        }
+        | ServiceRoute::Patch {
+            components,
+            query,
+            headers,
+            body,
+            ret,
+            ..
+        } => (components, query, headers, Some(body), ret),
+    };
+    for component in components {
+        if let ServiceRouteComponent::Variable(pair) | ServiceRouteComponent::Rest(pair) = component
+        {
+            rename_type(&mut pair.type_ident, rename)?;
+        }
+    }
+    for ty in query.iter_mut().chain(body).chain(Some(ret)) {
+        rename_type(ty, rename)?;
+    }
+    for header in headers {
+        rename_type(&mut header.type_ident, rename)?;
+    }
+    Ok(())
+}
+
+fn rename_type(ty: &mut TypeIdent, rename: &mut Rename) -> Result<(), String> {
+    match ty {
+        TypeIdent::BuiltIn(_) => {}
+        TypeIdent::List(inner) | TypeIdent::Option(inner) => rename_type(inner, rename)?,
+        TypeIdent::Result(a, b) | TypeIdent::Map(a, b) => {
+            rename_type(a, rename)?;
+            rename_type(b, rename)?;
+        }
+        TypeIdent::Tuple(tdef) => {
+            for ty in &mut tdef.0 {
+                rename_type(ty, rename)?;
+            }
+        }
+        TypeIdent::UserDefined(name) => {
+            if let Some(new_name) = rename(name)? {
+                *name = new_name.into();
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Implementation of `mod` blocks as an AST transformation.
+//!
+//! Modules group the definitions of a part of the API under a namespace, without prefixing
+//! every name with it.
+//!
+//! # Example
+//!
+//! ```text
+//! mod billing {
+//!     struct Invoice {
+//!         lines: list[LineItem],
+//!     }
+//!
+//!     struct LineItem {
+//!         amount: u64,
+//!     }
+//! }
+//!
+//! struct Customer {
+//!     invoices: list[billing.Invoice],
+//! }
+//! ```
+//!
+//! # Rules
+//!
+//! - Modules may only define types (no services, includes or further modules).
+//! - Within a module, unqualified names refer to the definitions of the module, otherwise to
+//!   the definitions outside of modules. Definitions of other modules and outside of the module
+//!   reference them qualified by the module name, e.g. `billing.Invoice`.
+//! - A module name must not be used as the namespace of an include.
+//!
+//! # Implementation
+//!
+//! - Like included definitions, the definitions of modules are renamed to the module name in
+//!   PascalCase followed by their name (`BillingInvoice`), which does not affect the wire format.
+//!   The generated name must not be taken by another definition.
+//! - The definitions are annotated with `@module("billing", "Invoice")`, see
+//!   [`SpecItem::module`], so backends can generate them into modules of their own.
+
+use super::includes::rename_item;
+use crate::ast::*;
+use crate::LibError;
+use inflector::cases::pascalcase::to_pascal_case;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Annotate the `items` of `mod module { .. }` with `@module`.
+pub(crate) fn annotate_module_items(module: &str, items: &mut [SpecItem]) {
+    for item in items {
+        let name = item.name().to_owned();
+        let annotations = match item {
+            SpecItem::StructDef(s) => &mut s.annotations,
+            SpecItem::TupleStructDef(t) => &mut t.annotations,
+            SpecItem::EnumDef(e) => &mut e.annotations,
+            SpecItem::FlagsDef(f) => &mut f.annotations,
+            SpecItem::ServiceDef(_) => unreachable!("the grammar only allows types in modules"),
+        };
+        annotations.push(Annotation {
+            name: "module".to_owned(),
+            args: vec![module.to_owned(), name],
+        });
+    }
+}
+
+/// Rename the definitions of modules and resolve all names qualified by a module.
+///
+/// Names qualified by other namespaces are left to the resolution of includes.
+pub(crate) fn resolve_modules(spec: &mut Spec) -> Result<(), LibError> {
+    // module -> name in the module -> generated name
+    let mut modules: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    let mut names: BTreeSet<String> = spec
+        .iter()
+        .filter(|item| item.module().is_none())
+        .map(|item| item.name().to_owned())
+        .collect();
+    for item in spec.iter() {
+        if let Some((module, name)) = item.module() {
+            let generated_name = format!("{}{}", to_pascal_case(module), name);
+            if !names.insert(generated_name.clone()) {
+                return Err(LibError::InvalidDefinition {
+                    definition: format!("{}.{}", module, name),
+                    message: format!(
+                        "`{}` is named like another definition in the generated code",
+                        generated_name
+                    ),
+                });
+            }
+            modules
+                .entry(module.to_owned())
+                .or_default()
+                .insert(name.to_owned(), generated_name);
+        }
+    }
+    if modules.is_empty() {
+        return Ok(());
+    }
+
+    for item in spec.iter_mut() {
+        let module = item.module().map(|(module, _)| module.to_owned());
+        let definition = match item.module() {
+            Some((module, name)) => format!("{}.{}", module, name),
+            None => item.name().to_owned(),
+        };
+        rename_item(item, &mut |name| match name.split_once('.') {
+            Some((namespace, unqualified)) => {
+                match modules.get(namespace) {
+                    Some(definitions) => definitions
+                        .get(unqualified)
+                        .cloned()
+                        .map(Some)
+                        .ok_or_else(|| {
+                            format!("module `{}` does not define `{}`", namespace, unqualified)
+                        }),
+                    None => Ok(None),
+                }
+            }
+            None => Ok(module
+                .as_ref()
+                .and_then(|module| modules[module].get(name))
+                .cloned()),
+        })
+        .map_err(|message| LibError::InvalidDefinition {
+            definition,
+            message,
+        })?;
+    }
+    Ok(())
+}
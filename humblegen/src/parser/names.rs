@@ -0,0 +1,101 @@
+//! The names of user defined types in a spec, where they are defined and referenced, for
+//! editor tooling like the language server.
+//!
+//! Names are collected from the parse tree of the spec, without includes, so a spec whose
+//! definitions are invalid still has names as long as it matches the grammar.
+
+use super::{parse_doc_comment, HumbleParser, ParseError, Rule};
+use pest::Parser;
+use std::ops::Range;
+
+/// A name of a user defined type in a spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Name {
+    pub name: String,
+    /// The byte range of the name in the spec.
+    pub span: Range<usize>,
+    pub kind: NameKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NameKind {
+    /// The name of a definition, e.g. `Monster` in `struct Monster { .. }`.
+    Definition {
+        /// The keyword of the definition, i.e., `struct`, `enum`, `flags`, `type` or `service`.
+        keyword: &'static str,
+        doc_comment: Option<String>,
+    },
+    /// The name of a type used by a field, a variant, an embed or an endpoint.
+    Reference,
+}
+
+/// The names of user defined types in `input`, in order.
+pub(crate) fn names(input: &str) -> Result<Vec<Name>, ParseError> {
+    let doc = HumbleParser::parse(Rule::doc, input).map_err(Box::new)?;
+    let mut names = vec![];
+    for pair in doc {
+        collect_names(pair, None, &mut names);
+    }
+    Ok(names)
+}
+
+fn collect_names(
+    pair: pest::iterators::Pair<Rule>,
+    parent: Option<(Rule, &Option<String>)>,
+    names: &mut Vec<Name>,
+) {
+    let rule = pair.as_rule();
+    if rule == Rule::camel_case_ident {
+        let kind = match parent {
+            Some((Rule::struct_definition, doc_comment))
+            | Some((Rule::tuple_struct_definition, doc_comment)) => NameKind::Definition {
+                keyword: "struct",
+                doc_comment: doc_comment.clone(),
+            },
+            // the name of an enum is part of `enum_def`, its doc comment of `enum_definition`
+            Some((Rule::enum_def, doc_comment)) => NameKind::Definition {
+                keyword: "enum",
+                doc_comment: doc_comment.clone(),
+            },
+            Some((Rule::flags_definition, doc_comment)) => NameKind::Definition {
+                keyword: "flags",
+                doc_comment: doc_comment.clone(),
+            },
+            Some((Rule::literal_union_definition, doc_comment)) => NameKind::Definition {
+                keyword: "type",
+                doc_comment: doc_comment.clone(),
+            },
+            Some((Rule::service_definition, doc_comment)) => NameKind::Definition {
+                keyword: "service",
+                doc_comment: doc_comment.clone(),
+            },
+            Some((Rule::type_ident, _))
+            | Some((Rule::multipart_body, _))
+            | Some((Rule::http_query_struct, _)) => NameKind::Reference,
+            // e.g. variant names
+            _ => return,
+        };
+        let span = pair.as_span();
+        names.push(Name {
+            name: span.as_str().to_owned(),
+            span: span.start()..span.end(),
+            kind,
+        });
+        return;
+    }
+
+    let doc_comment = match rule {
+        Rule::struct_definition
+        | Rule::tuple_struct_definition
+        | Rule::enum_definition
+        | Rule::flags_definition
+        | Rule::literal_union_definition
+        | Rule::service_definition => parse_doc_comment(&mut pair.clone().into_inner()),
+        // passed on from `enum_definition`
+        Rule::enum_def => parent.and_then(|(_, doc_comment)| doc_comment.clone()),
+        _ => None,
+    };
+    for inner in pair.into_inner() {
+        collect_names(inner, Some((rule, &doc_comment)), names);
+    }
+}
@@ -0,0 +1,228 @@
+//! Expansion of `paginated[T]` return types and validation of the pagination convention of
+//! endpoints annotated with `@paginated`.
+//!
+//! # Example
+//!
+//! ```text
+//! struct MonsterQuery {
+//!     cursor: option[str],
+//!     species: option[str],
+//! }
+//!
+//! struct MonsterPage {
+//!     items: list[Monster],
+//!     next_cursor: option[str],
+//! }
+//!
+//! service MonsterApi {
+//!     @paginated
+//!     GET /monsters?{MonsterQuery} -> MonsterPage,
+//! }
+//! ```
+//!
+//! With the standard envelope, the page struct is generated:
+//!
+//! ```text
+//! service MonsterApi {
+//!     GET /monsters?{MonsterQuery} -> paginated[Monster],
+//! }
+//! ```
+//!
+//! is equivalent to:
+//!
+//! ```text
+//! /// A page of `Monster` items of a paginated endpoint.
+//! struct MonsterPage {
+//!     /// The items of the page.
+//!     items: list[Monster],
+//!     /// The cursor of the following page, none on the last page.
+//!     next_cursor: option[str],
+//!     /// The total number of items of all pages, if known.
+//!     total: option[u64],
+//! }
+//!
+//! service MonsterApi {
+//!     @paginated
+//!     GET /monsters?{MonsterQuery} -> MonsterPage,
+//! }
+//! ```
+//!
+//! # Rules
+//!
+//! - Paginated endpoints are `GET` endpoints.
+//! - Their query is a struct with a `cursor: option[str]` field, their return type a struct
+//!   with an `items: list[T]` and a `next_cursor: option[str]` field. Either may have further
+//!   fields, and the cursors may be `optional str` fields instead.
+//! - Endpoints violating the convention result in a `LibError::InvalidDefinition` of their
+//!   service.
+//! - `paginated[T]` implies `@paginated`, and `@paginated` endpoints returning `list[T]` are
+//!   equivalent to ones returning `paginated[T]`.
+//! - The page struct of `paginated[T]` is named `TPage`, e.g. `MonsterPage` or `StrPage` for
+//!   `paginated[str]`, so `T` is a user-defined or built-in type other than `()` and `bytes`.
+//!   The page struct must not be named like another definition.
+//!
+//! # Implementation
+//!
+//! - Page structs are generated after partial structs are derived, once per item type, and
+//!   precede the first service returning them.
+
+use crate::ast::*;
+use crate::LibError;
+use inflector::cases::pascalcase::to_pascal_case;
+use std::collections::HashSet;
+
+pub(crate) fn expand_paginated_types(spec: &mut Spec) -> Result<(), LibError> {
+    let mut names: HashSet<String> = spec.iter().map(|item| item.name().to_owned()).collect();
+    let mut expanded = HashSet::new();
+    let mut items = Vec::with_capacity(spec.0.len());
+    for mut item in spec.0.drain(..) {
+        if let SpecItem::ServiceDef(service) = &mut item {
+            let service_name = &service.name;
+            for endpoint in service.endpoints.iter_mut().filter(|e| e.is_paginated()) {
+                let item_type = match endpoint.route.return_type() {
+                    TypeIdent::List(item_type) => (**item_type).clone(),
+                    _ => continue,
+                };
+                let location = format!(
+                    "paginated endpoint `{} {}`",
+                    endpoint.route.http_method_as_str(),
+                    endpoint.route.path()
+                );
+                let page = page_name(&item_type).ok_or_else(|| LibError::InvalidDefinition {
+                    definition: service_name.to_string(),
+                    message: format!(
+                        "{} must have items of a user-defined or built-in type, e.g. `paginated[Monster]`",
+                        location
+                    ),
+                })?;
+                if expanded.insert(page.clone()) {
+                    if !names.insert(page.clone()) {
+                        return Err(LibError::InvalidDefinition {
+                            definition: service_name.to_string(),
+                            message: format!(
+                                "page struct `{}` of {} is named like another definition",
+                                page, location
+                            ),
+                        });
+                    }
+                    items.push(SpecItem::StructDef(page_struct(&page, item_type)));
+                }
+                *endpoint.route.return_type_mut() = TypeIdent::UserDefined(page.into());
+            }
+        }
+        items.push(item);
+    }
+    spec.0 = items;
+    Ok(())
+}
+
+/// The name of the page struct of `item_type`, if it has one.
+fn page_name(item_type: &TypeIdent) -> Option<String> {
+    match item_type {
+        TypeIdent::UserDefined(name) => {
+            // the namespace of qualified names is not part of the page name
+            Some(format!("{}Page", name.rsplit('.').next().unwrap()))
+        }
+        TypeIdent::BuiltIn(AtomType::Empty) | TypeIdent::BuiltIn(AtomType::Bytes) => None,
+        TypeIdent::BuiltIn(atom) => Some(format!("{}Page", to_pascal_case(&atom.to_string()))),
+        _ => None,
+    }
+}
+
+/// The page struct `name` of the standard envelope of `item_type`.
+fn page_struct(name: &str, item_type: TypeIdent) -> StructDef {
+    let field = |name: &str, type_ident, doc_comment: &str| FieldNode {
+        pair: FieldDefPair {
+            name: name.to_owned(),
+            type_ident,
+        },
+        doc_comment: Some(doc_comment.to_owned()),
+        annotations: vec![],
+        optional: false,
+    };
+    let optional = |atom| TypeIdent::Option(Box::new(TypeIdent::BuiltIn(atom)));
+    StructDef {
+        name: name.into(),
+        doc_comment: Some(format!(
+            "A page of `{}` items of a paginated endpoint.",
+            item_type
+        )),
+        fields: StructFields(vec![
+            field(
+                "items",
+                TypeIdent::List(Box::new(item_type)),
+                "The items of the page.",
+            ),
+            field(
+                "next_cursor",
+                optional(AtomType::Str),
+                "The cursor of the following page, none on the last page.",
+            ),
+            field(
+                "total",
+                optional(AtomType::U64),
+                "The total number of items of all pages, if known.",
+            ),
+        ]),
+        annotations: vec![],
+    }
+}
+
+pub(crate) fn validate_paginated_endpoints(spec: &Spec) -> Result<(), LibError> {
+    let index = spec.index();
+    for service in spec.iter().filter_map(SpecItem::service_def) {
+        for endpoint in service.endpoints.iter().filter(|e| e.is_paginated()) {
+            if let Err(violation) = check_convention(&index, &endpoint.route) {
+                return Err(LibError::InvalidDefinition {
+                    definition: service.name.to_string(),
+                    message: format!(
+                        "paginated endpoint `{} {}` {}",
+                        endpoint.route.http_method_as_str(),
+                        endpoint.route.path(),
+                        violation
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_convention(index: &SpecIndex, route: &ServiceRoute) -> Result<(), &'static str> {
+    if !matches!(route, ServiceRoute::Get { .. }) {
+        return Err("must be a `GET` endpoint");
+    }
+    let query = route
+        .query()
+        .as_ref()
+        .and_then(TypeIdent::user_defined)
+        .and_then(|name| index.struct_def(name))
+        .ok_or("must have a struct query")?;
+    if !has_cursor_field(query, "cursor") {
+        return Err("must have a query with a `cursor: option[str]` field");
+    }
+    let page = route
+        .return_type()
+        .user_defined()
+        .and_then(|name| index.struct_def(name))
+        .ok_or("must return a struct")?;
+    if !page
+        .fields
+        .iter()
+        .any(|f| f.pair.name == "items" && matches!(f.value_type(), TypeIdent::List(_)))
+    {
+        return Err("must return a struct with an `items: list[T]` field");
+    }
+    if !has_cursor_field(page, "next_cursor") {
+        return Err("must return a struct with a `next_cursor: option[str]` field");
+    }
+    Ok(())
+}
+
+/// Whether `sdef` has an `option[str]` field `name`.
+fn has_cursor_field(sdef: &StructDef, name: &str) -> bool {
+    sdef.fields.iter().any(|f| {
+        f.pair.name == name
+            && matches!(f.value_type(), TypeIdent::Option(inner) if matches!(*inner, TypeIdent::BuiltIn(AtomType::Str)))
+    })
+}
@@ -0,0 +1,98 @@
+//! Implementation of humblespec partial structs as an AST transformation.
+//!
+//! Partial structs are the bodies of partial updates (`PATCH` requests), which only contain
+//! the fields to change.
+//!
+//! # Example
+//!
+//! ```text
+//! @partial("MonsterPatch")
+//! struct Monster {
+//!     name: str,
+//!     nickname: option[str],
+//! }
+//! ```
+//!
+//! is equivalent to:
+//!
+//! ```text
+//! struct Monster {
+//!     name: str,
+//!     nickname: option[str],
+//! }
+//!
+//! /// Partial update of `Monster`, only containing the fields to change.
+//! struct MonsterPatch {
+//!     optional name: str,
+//!     optional nickname: option[str],
+//! }
+//! ```
+//!
+//! # Rules
+//!
+//! - The partial struct has all fields of the struct, including embedded ones, marked
+//!   `optional`, with their doc comments and field annotations.
+//! - The partial struct derives the Rust traits of the struct, given by `@derive`.
+//! - The partial struct must not be named like another definition. Violating that results in a
+//!   `LibError::InvalidDefinition`.
+//!
+//! # Implementation
+//!
+//! - Partial structs are derived after embeds are resolved and follow the struct they are
+//!   derived from.
+
+use crate::ast::*;
+use crate::LibError;
+use std::collections::HashSet;
+
+pub(crate) fn derive_partials(spec: &mut Spec) -> Result<(), LibError> {
+    let mut names: HashSet<String> = spec.iter().map(|item| item.name().to_owned()).collect();
+    let mut items = Vec::with_capacity(spec.0.len());
+    for item in spec.0.drain(..) {
+        let partial = match &item {
+            SpecItem::StructDef(sdef) => sdef.partial().map(|name| partial_struct(sdef, name)),
+            _ => None,
+        };
+        items.push(item);
+        if let Some(partial) = partial {
+            if !names.insert(partial.name.to_string()) {
+                return Err(LibError::InvalidDefinition {
+                    definition: items.last().unwrap().name().to_owned(),
+                    message: format!(
+                        "partial struct `{}` is named like another definition",
+                        partial.name
+                    ),
+                });
+            }
+            items.push(SpecItem::StructDef(partial));
+        }
+    }
+    spec.0 = items;
+    Ok(())
+}
+
+/// The partial struct `name` of `sdef`.
+fn partial_struct(sdef: &StructDef, name: &str) -> StructDef {
+    let fields = sdef
+        .fields
+        .iter()
+        .map(|field| FieldNode {
+            optional: true,
+            ..field.clone()
+        })
+        .collect();
+    StructDef {
+        name: name.into(),
+        fields: StructFields(fields),
+        doc_comment: Some(format!(
+            "Partial update of `{}`, only containing the fields to change.",
+            sdef.name
+        )),
+        annotations: sdef
+            .annotations
+            .iter()
+            .filter(|a| a.name == "derive")
+            .cloned()
+            .collect(),
+    }
+}
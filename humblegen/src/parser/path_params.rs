@@ -0,0 +1,79 @@
+//! Validation of the path parameters of endpoints.
+//!
+//! # Example
+//!
+//! ```text
+//! service MonsterApi {
+//!     GET /monsters/{id: i32} -> Monster,
+//!     GET /lairs/{:uuid}/monsters/{:u32} -> Monster,
+//!     GET /portraits/{*path: str} -> bytes,
+//! }
+//! ```
+//!
+//! Path parameters without a name, like `{:uuid}`, are named after the literal segment before
+//! them in singular, i.e., `lair` and `monster` above (see `parse_http_route_segment`).
+//!
+//! # Rules
+//!
+//! - Path parameters have a built-in type other than `()`, whose values have a textual
+//!   representation within a path segment.
+//! - The path parameters of a route have distinct names.
+//! - A `{*path: str}` parameter capturing the rest of the path is of type `str` and the last
+//!   segment of its route.
+//! - Endpoints violating a rule result in a `LibError::InvalidDefinition` of their service.
+
+use crate::ast::*;
+use crate::LibError;
+
+pub(crate) fn validate_path_params(spec: &Spec) -> Result<(), LibError> {
+    for service in spec.iter().filter_map(SpecItem::service_def) {
+        for endpoint in &service.endpoints {
+            if let Err(violation) = check_path_params(&endpoint.route) {
+                return Err(LibError::InvalidDefinition {
+                    definition: service.name.to_string(),
+                    message: format!(
+                        "endpoint `{} {}` {}",
+                        endpoint.route.http_method_as_str(),
+                        endpoint.route.path(),
+                        violation
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_path_params(route: &ServiceRoute) -> Result<(), String> {
+    let mut names: Vec<&str> = vec![];
+    let components = route.components();
+    for (idx, component) in components.iter().enumerate() {
+        if let ServiceRouteComponent::Rest(FieldDefPair { name, type_ident }) = component {
+            if !matches!(type_ident, TypeIdent::BuiltIn(AtomType::Str)) {
+                return Err(format!(
+                    "captures the rest of the path in `{}` of a type other than `str`",
+                    name
+                ));
+            }
+            if idx + 1 != components.len() {
+                return Err(format!(
+                    "has segments after the rest of the path `{}`",
+                    name
+                ));
+            }
+        }
+        if let Some(FieldDefPair { name, type_ident }) = component.param() {
+            if !matches!(type_ident, TypeIdent::BuiltIn(atom) if *atom != AtomType::Empty) {
+                return Err(format!(
+                    "has path parameter `{}` of a type other than a built-in type",
+                    name
+                ));
+            }
+            if names.contains(&name.as_str()) {
+                return Err(format!("has two path parameters named `{}`", name));
+            }
+            names.push(name);
+        }
+    }
+    Ok(())
+}
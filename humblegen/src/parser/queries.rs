@@ -0,0 +1,68 @@
+//! Validation of the query structs of endpoints.
+//!
+//! # Example
+//!
+//! ```text
+//! struct MonsterQuery {
+//!     species: option[str],
+//!     max_age: option[u32],
+//! }
+//!
+//! service MonsterApi {
+//!     GET /monsters?query(MonsterQuery) -> list[Monster],
+//! }
+//! ```
+//!
+//! Query structs are encoded as `application/x-www-form-urlencoded` with one parameter per
+//! field, e.g. `/monsters?species=Dragon`, which servers decode field by field.
+//!
+//! # Rules
+//!
+//! - The fields of query structs are atoms, or options of atoms, which are omitted if absent.
+//! - Endpoints violating a rule result in a `LibError::InvalidDefinition` of their service.
+
+use crate::ast::*;
+use crate::LibError;
+
+pub(crate) fn validate_queries(spec: &Spec) -> Result<(), LibError> {
+    let index = spec.index();
+    for service in spec.iter().filter_map(SpecItem::service_def) {
+        for endpoint in &service.endpoints {
+            let query = endpoint
+                .route
+                .query()
+                .as_ref()
+                .and_then(TypeIdent::user_defined)
+                .and_then(|name| index.struct_def(name));
+            if let Some(Err(violation)) = query.map(check_query) {
+                return Err(LibError::InvalidDefinition {
+                    definition: service.name.to_string(),
+                    message: format!(
+                        "endpoint `{} {}` {}",
+                        endpoint.route.http_method_as_str(),
+                        endpoint.route.path(),
+                        violation
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_query(query: &StructDef) -> Result<(), String> {
+    for field in query.fields.iter() {
+        let is_atom = match field.value_type() {
+            TypeIdent::BuiltIn(_) => true,
+            TypeIdent::Option(inner) => matches!(*inner, TypeIdent::BuiltIn(_)),
+            _ => false,
+        };
+        if !is_atom {
+            return Err(format!(
+                "has query `{}` with field `{}`, which must be an atom or an option of an atom",
+                query.name, field.pair.name
+            ));
+        }
+    }
+    Ok(())
+}
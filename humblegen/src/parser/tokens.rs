@@ -0,0 +1,191 @@
+//! Classification of the tokens of a spec, e.g. for syntax highlighting consistent with the
+//! grammar, see `highlight`.
+//!
+//! Tokens are classified by the rule of the grammar they are matched by, so `Monster` is a
+//! `TokenKind::Type` in `list[Monster]` and a `TokenKind::Variant` in `enum Lair { Monster }`:
+//! ```
+//! use humblegen::parser::tokens::{classify, TokenKind};
+//!
+//! let spec = "struct Monster { name: str }";
+//! let tokens = classify(spec)?;
+//! let kinds: Vec<_> = tokens.iter().map(|t| (&spec[t.span.clone()], t.kind)).collect();
+//! assert_eq!(kinds[..3], [
+//!     ("struct", TokenKind::Keyword),
+//!     ("Monster", TokenKind::Type),
+//!     ("{", TokenKind::Punctuation),
+//! ]);
+//! # Ok::<(), humblegen::LibError>(())
+//! ```
+
+use super::{HumbleParser, Rule};
+use crate::LibError;
+use pest::Parser;
+use std::ops::Range;
+
+/// The kind of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// E.g. `struct`, `enum`, `optional` or `list`.
+    Keyword,
+    /// The name of a user defined type, where it is defined or used.
+    Type,
+    /// A built-in type, e.g. `str` or `datetime`.
+    BuiltInType,
+    /// The name of an enum variant.
+    Variant,
+    /// The name of a struct field or of a path parameter.
+    Field,
+    /// The namespace of an include, e.g. `common` in `common.Monster`.
+    Namespace,
+    /// The name of an annotation, including its `@`.
+    Annotation,
+    /// A string, e.g. an include path, a header name or an annotation argument in quotes.
+    String,
+    /// Other annotation arguments, e.g. `404` or `p99 = 200ms`.
+    Literal,
+    /// The HTTP method of an endpoint, e.g. `GET`.
+    HttpMethod,
+    /// A literal segment of an endpoint's route, e.g. `monsters` in `/monsters/{id: i32}`.
+    Route,
+    DocComment,
+    Comment,
+    /// E.g. `{`, `:`, `->` or `..`.
+    Punctuation,
+}
+
+impl TokenKind {
+    /// The name of the kind, e.g. `built-in-type`, used as CSS class by `highlight`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TokenKind::Keyword => "keyword",
+            TokenKind::Type => "type",
+            TokenKind::BuiltInType => "built-in-type",
+            TokenKind::Variant => "variant",
+            TokenKind::Field => "field",
+            TokenKind::Namespace => "namespace",
+            TokenKind::Annotation => "annotation",
+            TokenKind::String => "string",
+            TokenKind::Literal => "literal",
+            TokenKind::HttpMethod => "http-method",
+            TokenKind::Route => "route",
+            TokenKind::DocComment => "doc-comment",
+            TokenKind::Comment => "comment",
+            TokenKind::Punctuation => "punctuation",
+        }
+    }
+}
+
+/// A token of a spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// The byte range of the token in the spec.
+    pub span: Range<usize>,
+}
+
+/// The tokens of `input`, in order and without whitespace, failing if it does not match the
+/// grammar.
+///
+/// Specs are not validated beyond the grammar, and includes are not resolved.
+pub fn classify(input: &str) -> Result<Vec<Token>, LibError> {
+    let doc = HumbleParser::parse(Rule::doc, input).map_err(Box::new)?;
+    let mut tokens = vec![];
+    for pair in doc {
+        classify_pair(pair, None, &mut tokens);
+    }
+
+    // keywords, punctuation and comments are not matched by rules of their own, but lie between
+    // the tokens classified by rules
+    let mut all_tokens = vec![];
+    let mut end = 0;
+    for token in tokens {
+        lex_gap(input, end..token.span.start, &mut all_tokens);
+        end = token.span.end;
+        all_tokens.push(token);
+    }
+    lex_gap(input, end..input.len(), &mut all_tokens);
+    Ok(all_tokens)
+}
+
+fn classify_pair(pair: pest::iterators::Pair<Rule>, parent: Option<Rule>, tokens: &mut Vec<Token>) {
+    let rule = pair.as_rule();
+    let span = pair.as_span().start()..pair.as_span().end();
+    let mut push = |kind, span| tokens.push(Token { kind, span });
+    let kind = match rule {
+        Rule::camel_case_ident if parent == Some(Rule::enum_variant_def) => TokenKind::Variant,
+        Rule::camel_case_ident => TokenKind::Type,
+        Rule::snake_case_ident
+            if parent == Some(Rule::include_definition)
+                || parent == Some(Rule::mod_definition)
+                || parent == Some(Rule::qualified_ident) =>
+        {
+            TokenKind::Namespace
+        }
+        Rule::snake_case_ident => TokenKind::Field,
+        Rule::built_in_atom | Rule::http_header_atom => TokenKind::BuiltInType,
+        Rule::optional_modifier => TokenKind::Keyword,
+        Rule::include_path | Rule::http_header_name | Rule::string_literal => TokenKind::String,
+        Rule::annotation_arg if pair.as_str().starts_with('"') => TokenKind::String,
+        Rule::annotation_value | Rule::service_version_number | Rule::variant_discriminant => {
+            TokenKind::Literal
+        }
+        Rule::http_get
+        | Rule::http_post
+        | Rule::http_delete
+        | Rule::http_put
+        | Rule::http_patch => TokenKind::HttpMethod,
+        Rule::kebab_case_ident => TokenKind::Route,
+        Rule::doc_comment_line => {
+            // without the line break
+            push(TokenKind::DocComment, span.start..span.end - 1);
+            return;
+        }
+        Rule::annotation => {
+            let mut nodes = pair.into_inner();
+            let name = nodes.next().expect("annotations have a name");
+            push(TokenKind::Annotation, span.start..name.as_span().end());
+            for args in nodes {
+                classify_pair(args, Some(rule), tokens);
+            }
+            return;
+        }
+        _ => {
+            for inner in pair.into_inner() {
+                classify_pair(inner, Some(rule), tokens);
+            }
+            return;
+        }
+    };
+    push(kind, span);
+}
+
+/// Lex the `gap` between tokens classified by rules into keywords, punctuation and comments.
+fn lex_gap(input: &str, gap: Range<usize>, tokens: &mut Vec<Token>) {
+    let text = &input[gap.clone()];
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let kind = if c.is_whitespace() {
+            continue;
+        } else if text[start..].starts_with("//") {
+            while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            TokenKind::Comment
+        } else if c.is_alphanumeric() {
+            while chars.next_if(|&(_, c)| c.is_alphanumeric()).is_some() {}
+            TokenKind::Keyword
+        } else {
+            // `->` and `..` are single tokens
+            if matches!(
+                (c, chars.peek()),
+                ('-', Some((_, '>'))) | ('.', Some((_, '.')))
+            ) {
+                chars.next();
+            }
+            TokenKind::Punctuation
+        };
+        let end = chars.peek().map_or(text.len(), |&(end, _)| end);
+        tokens.push(Token {
+            kind,
+            span: gap.start + start..gap.start + end,
+        });
+    }
+}
@@ -0,0 +1,122 @@
+//! Validation of specs that were not parsed, e.g. loaded from AST JSON.
+//!
+//! Parsing checks the rules of the language while building the AST, and code generation relies
+//! on them, e.g. on `@max_body_size(1MB)` being a size. Specs from elsewhere are checked against
+//! the same rules here.
+//!
+//! # Rules
+//!
+//! - Definitions, fields, variants, flags, services and endpoints only have the annotations
+//!   allowed on them, with valid arguments (see `check_annotation_args`). Types may have the
+//!   `@module` of their module (see `modules`). Fields of variants may have the annotations of
+//!   struct fields, which they get by embedding structs.
+//! - The meta fields, wire names, `@default` values, `@column`s, enum representations, flags
+//!   and `@idempotent` endpoints follow the rules checked while parsing them. Fields may be
+//!   `optional` and have a default, like the fields of partial structs (see `partials`).
+//! - The rules of `path_params`, `headers`, `queries` and `pagination` hold.
+//! - Definitions violating a rule result in a `LibError::InvalidDefinition` naming them, the
+//!   meta block is named `meta`.
+
+use super::*;
+
+pub(crate) fn validate_spec(spec: &Spec) -> Result<(), LibError> {
+    let meta = spec.meta();
+    for (key, value) in [("version", &meta.version), ("base_url", &meta.base_url)] {
+        if let Some(value) = value {
+            check_meta_field(key, value).map_err(|message| invalid("meta", message))?;
+        }
+    }
+    for item in spec.iter() {
+        check_item(item).map_err(|message| invalid(item.name(), message))?;
+    }
+    pagination::validate_paginated_endpoints(spec)?;
+    path_params::validate_path_params(spec)?;
+    headers::validate_headers(spec)?;
+    queries::validate_queries(spec)
+}
+
+fn invalid(definition: &str, message: String) -> LibError {
+    LibError::InvalidDefinition {
+        definition: definition.to_owned(),
+        message,
+    }
+}
+
+fn check_item(item: &SpecItem) -> Result<(), String> {
+    match item {
+        SpecItem::StructDef(sdef) => {
+            check_annotations(&sdef.annotations, STRUCT_ANNOTATIONS, true)?;
+            check_fields(&sdef.fields)?;
+            check_columns(sdef)
+        }
+        SpecItem::TupleStructDef(tdef) => {
+            check_annotations(&tdef.annotations, TUPLE_STRUCT_ANNOTATIONS, true)
+        }
+        SpecItem::EnumDef(edef) => {
+            check_annotations(&edef.annotations, ENUM_ANNOTATIONS, true)?;
+            for variant in &edef.variants {
+                check_annotations(&variant.annotations, VARIANT_ANNOTATIONS, false)?;
+                if let VariantType::Struct(fields) = &variant.variant_type {
+                    check_fields(fields)?;
+                }
+            }
+            check_variant_names(&edef.variants).map_err(|(_, message)| message)?;
+            validate_tagging(edef)?;
+            validate_non_exhaustive(edef)?;
+            validate_discriminants(edef)
+        }
+        SpecItem::FlagsDef(fdef) => {
+            check_annotations(&fdef.annotations, FLAGS_ANNOTATIONS, true)?;
+            for flag in &fdef.flags {
+                check_annotations(&flag.annotations, FLAG_ANNOTATIONS, false)?;
+            }
+            check_flags(&fdef.name, &fdef.flags).map_err(|(_, message)| message)
+        }
+        SpecItem::ServiceDef(service) => {
+            check_annotations(&service.annotations, SERVICE_ANNOTATIONS, false)?;
+            for endpoint in &service.endpoints {
+                check_annotations(&endpoint.annotations, ENDPOINT_ANNOTATIONS, false)
+                    .and_then(|()| check_idempotent(endpoint))
+                    .map_err(|message| {
+                        format!(
+                            "endpoint `{} {}`: {}",
+                            endpoint.route.http_method_as_str(),
+                            endpoint.route.path(),
+                            message
+                        )
+                    })?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn check_fields(fields: &StructFields) -> Result<(), String> {
+    for field in fields.iter() {
+        check_annotations(&field.annotations, STRUCT_FIELD_ANNOTATIONS, false)
+            .and_then(|()| check_default_value(field))
+            .map_err(|message| format!("field `{}`: {}", field.pair.name, message))?;
+    }
+    check_field_names(&fields.0).map_err(|(_, message)| message)
+}
+
+/// Check that the annotations are `allowed`, or the `@module` of a type if `is_type`, and have
+/// valid arguments.
+fn check_annotations(
+    annotations: &[Annotation],
+    allowed: &[&str],
+    is_type: bool,
+) -> Result<(), String> {
+    for annotation in annotations {
+        let name = annotation.name.as_str();
+        let is_module = is_type && name == "module";
+        if !allowed.contains(&name) && !is_module {
+            return Err(format!(
+                "annotation `@{}` is not allowed here, expected one of {:?}",
+                name, allowed
+            ));
+        }
+        check_annotation_args(name, &annotation.args)?;
+    }
+    Ok(())
+}
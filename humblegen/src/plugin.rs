@@ -0,0 +1,192 @@
+//! Backends implemented outside of humblegen, e.g. for languages that are not upstream.
+//!
+//! A plugin is an external command speaking a versioned JSON protocol, so it can be written in
+//! any language and does not depend on the Rust ABI of humblegen. The command reads a
+//! `PluginRequest` from stdin and writes a `PluginResponse` with the generated files to stdout:
+//! ```text
+//! $ humblegen -l plugin --plugin "python3 csharp.py" -o Api/ protocol.humble
+//!
+//! stdin:  {"protocol_version": 1, "artifact": "TypesOnly", "spec": [<AST JSON>]}
+//! stdout: {"files": [{"path": "Monster.cs", "contents": "..."}]}
+//! ```
+//!
+//! The AST JSON is the `serde_json` serialization of `ast::Spec`, as for transformations, see
+//! `transform::Subprocess`. Workspaces are passed as `"workspace": {"common": .., "specs":
+//! [{"name": .., "spec": ..}]}` instead of `"spec"`. Commands exiting with a non-zero status
+//! fail code generation, with their stderr passed through.
+//!
+//! File paths are `/`-separated and relative to the output folder. A plugin generating a single
+//! file may return it with an empty path instead, which writes it to the output itself.
+
+use crate::workspace::Workspace;
+use crate::{Artifact, CodeGenerator, LibError, Spec};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, prelude::*};
+use std::path::{Component, Path};
+use std::process::{Command, Stdio};
+
+/// The version of the plugin protocol, incremented on incompatible changes.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The input of a plugin, written to its stdin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginRequest {
+    /// `PROTOCOL_VERSION` of the humblegen running the plugin.
+    pub protocol_version: u32,
+    /// The artifact to generate, e.g. `ClientEndpoints`, see `Artifact`'s `Display` impl.
+    pub artifact: String,
+    #[serde(flatten)]
+    pub input: PluginInput,
+}
+
+/// What to generate code for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginInput {
+    Spec(Spec),
+    Workspace(PluginWorkspace),
+}
+
+/// A workspace, see `workspace::Workspace`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginWorkspace {
+    pub common: Spec,
+    pub specs: Vec<PluginWorkspaceSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginWorkspaceSpec {
+    pub name: String,
+    pub spec: Spec,
+}
+
+/// The output of a plugin, read from its stdout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginResponse {
+    pub files: Vec<GeneratedFile>,
+}
+
+/// A file generated by a plugin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedFile {
+    /// The `/`-separated path relative to the output, or empty for the output itself.
+    pub path: String,
+    pub contents: String,
+}
+
+/// A backend running an external command, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    program: String,
+    args: Vec<String>,
+    artifact: Artifact,
+}
+
+impl Plugin {
+    /// A plugin running `program` with `args` to generate `artifact`.
+    pub fn new(program: String, args: Vec<String>, artifact: Artifact) -> Self {
+        Self {
+            program,
+            args,
+            artifact,
+        }
+    }
+
+    /// A plugin running the whitespace separated program and arguments of `command`, e.g.
+    /// `python3 csharp.py`.
+    pub fn from_command_line(command: &str, artifact: Artifact) -> Self {
+        let mut words = command.split_whitespace().map(str::to_owned);
+        let program = words.next().unwrap_or_default();
+        Self::new(program, words.collect(), artifact)
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> LibError {
+        LibError::PluginError(format!("`{}`: {}", self.program, message))
+    }
+
+    /// Run the command on `input`, returning its response.
+    fn run(&self, input: PluginInput) -> Result<PluginResponse, LibError> {
+        let request = PluginRequest {
+            protocol_version: PROTOCOL_VERSION,
+            artifact: self.artifact.to_string(),
+            input,
+        };
+        let input = serde_json::to_vec(&request).map_err(|e| self.error(e))?;
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.error(e))?;
+        let mut child_stdin = child.stdin.take().unwrap();
+        let mut child_stdout = child.stdout.take().unwrap();
+
+        // Write to stdin in a new thread, so that the command does not block on writing to its
+        // stdout while we block on writing to its stdin.
+        let stdin_handle = std::thread::spawn(move || child_stdin.write_all(&input));
+
+        let mut output = vec![];
+        io::copy(&mut child_stdout, &mut output)?;
+        let status = child.wait()?;
+        // the command may exit without reading all of its input
+        let _ = stdin_handle
+            .join()
+            .expect("the thread writing to stdin does not panic");
+
+        if !status.success() {
+            return Err(self.error(format!("exited with {}", status)));
+        }
+        serde_json::from_slice(&output)
+            .map_err(|e| self.error(format!("invalid plugin response: {}", e)))
+    }
+
+    /// Write the files of `response` to `output`.
+    fn write_files(&self, response: PluginResponse, output: &Path) -> Result<(), LibError> {
+        if let [file] = response.files.as_slice() {
+            if file.path.is_empty() {
+                return fs::write(output, &file.contents).map_err(LibError::file(output));
+            }
+        }
+        if !output.is_dir() {
+            return Err(LibError::OutputMustBeFolder { backend: "plugin" });
+        }
+        for file in &response.files {
+            let path = Path::new(&file.path);
+            // plugins must not write outside of the output
+            if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+                return Err(self.error(format!("invalid file path `{}`", file.path)));
+            }
+            let path = output.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(LibError::file(parent))?;
+            }
+            fs::write(&path, &file.contents).map_err(LibError::file(&path))?;
+        }
+        Ok(())
+    }
+}
+
+impl CodeGenerator for Plugin {
+    fn generate(&self, spec: &Spec, output: &Path) -> Result<(), LibError> {
+        let response = self.run(PluginInput::Spec(spec.clone()))?;
+        self.write_files(response, output)
+    }
+
+    fn generate_workspace(&self, workspace: &Workspace, output: &Path) -> Result<(), LibError> {
+        let input = PluginInput::Workspace(PluginWorkspace {
+            common: workspace.common.clone(),
+            specs: workspace
+                .specs
+                .iter()
+                .map(|ws_spec| PluginWorkspaceSpec {
+                    name: ws_spec.name.clone(),
+                    spec: ws_spec.spec.clone(),
+                })
+                .collect(),
+        });
+        let response = self.run(input)?;
+        self.write_files(response, output)
+    }
+}
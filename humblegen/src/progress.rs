@@ -0,0 +1,143 @@
+//! Progress reporting of long generations, e.g. of workspaces with many specs.
+//!
+//! The `*_with_progress` functions pass a `Progress` event to a callback for every parsed spec,
+//! every backend stage and every written file:
+//! ```no_run
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! use humblegen::progress::{self, Progress};
+//!
+//! let mut report = |event: Progress| eprintln!("{}", event);
+//! let spec = progress::parse_file_with_progress("protocol.humble", &mut report)?;
+//! let generator = humblegen::backend::docs::Generator::default();
+//! progress::generate_with_progress(&generator, "docs", &spec, "docs.html".as_ref(), &mut report)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Backends do not report the files they write themselves. Instead, the files in the output that
+//! are new or were modified during the generation are reported once the backend has finished.
+
+use crate::{workspace::Workspace, CodeGenerator, LibError, Spec};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// An event of a generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress<'a> {
+    /// The spec at `path` was parsed into `items` definitions, including those of its includes.
+    Parsed { path: &'a Path, items: usize },
+    /// `backend` started generating code.
+    Generating { backend: &'a str },
+    /// `backend` wrote the file at `path`.
+    FileWritten { backend: &'a str, path: &'a Path },
+    /// `backend` finished, having written `files` files.
+    Generated { backend: &'a str, files: usize },
+}
+
+impl fmt::Display for Progress<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Progress::Parsed { path, items } => {
+                write!(f, "parsed {} ({} items)", path.display(), items)
+            }
+            Progress::Generating { backend } => write!(f, "{}: generating", backend),
+            Progress::FileWritten { backend, path } => {
+                write!(f, "{}: wrote {}", backend, path.display())
+            }
+            Progress::Generated { backend, files } => {
+                write!(f, "{}: generated {} files", backend, files)
+            }
+        }
+    }
+}
+
+/// Callback receiving the `Progress` of a generation.
+pub type ProgressFn<'a> = dyn FnMut(Progress) + 'a;
+
+/// Like `crate::parse_file`, reporting the parsed spec to `progress`.
+pub fn parse_file_with_progress<P: AsRef<Path>>(
+    path: P,
+    progress: &mut ProgressFn,
+) -> Result<Spec, LibError> {
+    let spec = crate::parse_file(path.as_ref())?;
+    progress(Progress::Parsed {
+        path: path.as_ref(),
+        items: spec.0.len(),
+    });
+    Ok(spec)
+}
+
+/// Like `CodeGenerator::generate`, reporting the stages of `generator`, named `backend` in the
+/// events, and the files it wrote to `progress`.
+pub fn generate_with_progress(
+    generator: &dyn CodeGenerator,
+    backend: &str,
+    spec: &Spec,
+    output: &Path,
+    progress: &mut ProgressFn,
+) -> Result<(), LibError> {
+    report_stage(backend, output, progress, || {
+        generator.generate(spec, output)
+    })
+}
+
+/// Like `CodeGenerator::generate_workspace`, reporting the stages of `generator`, named
+/// `backend` in the events, and the files it wrote to `progress`.
+pub fn generate_workspace_with_progress(
+    generator: &dyn CodeGenerator,
+    backend: &str,
+    workspace: &Workspace,
+    output: &Path,
+    progress: &mut ProgressFn,
+) -> Result<(), LibError> {
+    report_stage(backend, output, progress, || {
+        generator.generate_workspace(workspace, output)
+    })
+}
+
+fn report_stage(
+    backend: &str,
+    output: &Path,
+    progress: &mut ProgressFn,
+    generate: impl FnOnce() -> Result<(), LibError>,
+) -> Result<(), LibError> {
+    progress(Progress::Generating { backend });
+    let before = modification_times(output);
+    generate()?;
+    let written: Vec<PathBuf> = modification_times(output)
+        .into_iter()
+        .filter(|(path, modified)| before.get(path) != Some(modified))
+        .map(|(path, _)| path)
+        .collect();
+    for path in &written {
+        progress(Progress::FileWritten { backend, path });
+    }
+    progress(Progress::Generated {
+        backend,
+        files: written.len(),
+    });
+    Ok(())
+}
+
+/// The modification times of the file `path` or of all files below the folder `path`.
+fn modification_times(path: &Path) -> BTreeMap<PathBuf, SystemTime> {
+    let mut times = BTreeMap::new();
+    let mut pending = vec![path.to_owned()];
+    while let Some(path) = pending.pop() {
+        let metadata = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            // e.g. the output of a single file backend before its first run
+            Err(_) => continue,
+        };
+        if metadata.is_dir() {
+            if let Ok(entries) = std::fs::read_dir(&path) {
+                pending.extend(entries.filter_map(|entry| Some(entry.ok()?.path())));
+            }
+        } else if let Ok(modified) = metadata.modified() {
+            times.insert(path, modified);
+        }
+    }
+    times
+}
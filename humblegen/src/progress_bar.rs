@@ -0,0 +1,47 @@
+//! Progress bar of the CLI, rendering the `Progress` events of a generation to stderr.
+
+use humblegen::progress::Progress;
+use std::io::{self, IsTerminal};
+
+const WIDTH: usize = 30;
+
+pub(crate) struct ProgressBar {
+    /// Number of backends of the generation.
+    backends: usize,
+    /// Number of backends that finished.
+    generated: usize,
+    /// Whether to redraw a bar, rather than printing a line per event, e.g. for CI logs.
+    terminal: bool,
+}
+
+impl ProgressBar {
+    pub(crate) fn new(backends: usize) -> Self {
+        Self {
+            backends,
+            generated: 0,
+            terminal: io::stderr().is_terminal(),
+        }
+    }
+
+    pub(crate) fn report(&mut self, event: Progress) {
+        if let Progress::Generated { .. } = event {
+            self.generated += 1;
+        }
+        if !self.terminal {
+            eprintln!("{}", event);
+            return;
+        }
+        let filled = WIDTH * self.generated / self.backends.max(1);
+        eprint!(
+            "\r\x1b[K[{}{}] {}/{} {}",
+            "#".repeat(filled),
+            " ".repeat(WIDTH - filled),
+            self.generated,
+            self.backends,
+            event
+        );
+        if self.generated == self.backends {
+            eprintln!();
+        }
+    }
+}
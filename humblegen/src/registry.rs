@@ -0,0 +1,96 @@
+//! Client for publishing humble specs to and pulling them from a schema registry.
+//!
+//! The registry is addressed by its base URL and stores spec versions at
+//! `{registry}/specs/{name}/versions/{version}`:
+//!
+//! - `PUT` uploads a `SpecVersion` as JSON. Registries should reject overwriting an existing version.
+//! - `GET` returns the previously uploaded `SpecVersion` as JSON.
+//!
+//! Specs are published as AST JSON (see `ast_json`) with their includes resolved, such that
+//! consumers do not need the included files.
+//!
+//! See `docs/registry.md` for the protocol.
+
+use crate::{ast_json, LibError, Spec};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A published version of a humble spec, as stored in the registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecVersion {
+    /// The name the spec is published under. (example: `monster-api`)
+    pub name: String,
+    /// The version of the spec. (example: `1.2.0`)
+    pub version: String,
+    /// The humblegen version that validated the spec before publishing.
+    pub humblegen_version: String,
+    /// The spec as dumped by `ast_json::dump_ast`.
+    pub ast: serde_json::Value,
+}
+
+impl SpecVersion {
+    /// The published spec, validated like all loaded ASTs.
+    pub fn spec(&self) -> Result<Spec, LibError> {
+        ast_json::load_ast(&self.ast.to_string())
+    }
+}
+
+/// Parse the spec file at `path` with its includes and upload it to `registry` as version
+/// `version` of spec `name`.
+pub fn publish<P: AsRef<Path>>(
+    registry: &str,
+    name: &str,
+    version: &str,
+    path: P,
+) -> Result<(), LibError> {
+    // never publish a spec that consumers cannot load
+    let spec = crate::parse_file(path)?;
+
+    let spec_version = SpecVersion {
+        name: name.to_owned(),
+        version: version.to_owned(),
+        humblegen_version: env!("CARGO_PKG_VERSION").to_owned(),
+        ast: serde_json::from_str(&ast_json::dump_ast(&spec)).expect("dumps are JSON"),
+    };
+    ureq::put(&spec_version_url(registry, name, version)?)
+        .send_json(&spec_version)
+        .map_err(|e| LibError::RegistryError(e.to_string()))?;
+    Ok(())
+}
+
+/// Download version `version` of spec `name` from `registry`, failing if its spec is invalid.
+pub fn pull(registry: &str, name: &str, version: &str) -> Result<SpecVersion, LibError> {
+    let spec_version: SpecVersion = ureq::get(&spec_version_url(registry, name, version)?)
+        .call()
+        .map_err(|e| LibError::RegistryError(e.to_string()))?
+        .into_json()?;
+    if spec_version.name != name || spec_version.version != version {
+        return Err(LibError::RegistryError(format!(
+            "requested {}@{}, but registry returned {}@{}",
+            name, version, spec_version.name, spec_version.version
+        )));
+    }
+    spec_version.spec()?;
+    Ok(spec_version)
+}
+
+fn spec_version_url(registry: &str, name: &str, version: &str) -> Result<String, LibError> {
+    for segment in &[name, version] {
+        let is_valid = !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_');
+        if !is_valid {
+            return Err(LibError::RegistryError(format!(
+                "{:?} must be non-empty and consist of ASCII alphanumerics, '.', '-' and '_' only",
+                segment
+            )));
+        }
+    }
+    Ok(format!(
+        "{}/specs/{}/versions/{}",
+        registry.trim_end_matches('/'),
+        name,
+        version
+    ))
+}
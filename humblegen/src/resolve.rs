@@ -0,0 +1,272 @@
+//! Resolving the types referenced by a spec, which all must be defined by it.
+//!
+//! Backends generate code for references to undefined types anyway, which then fails to compile
+//! downstream, far from the typo in the spec. So specs are checked after parsing, and all
+//! references to undefined types are reported together, each with the most similar defined name:
+//! ```
+//! let err = humblegen::parse("struct Lair { monsters: list[Monstr] }\nstruct Monster {}".as_bytes())
+//!     .unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "unknown type `Monstr` in `Lair.monsters`, did you mean `Monster`?"
+//! );
+//! ```
+//!
+//! The specs of a workspace are checked together with the common specs, whose types they use.
+//!
+//! Definitions are unused if they are not reachable from the services of a spec, or from types
+//! exported with `@export` for code not calling the services, e.g. types of events sent over a
+//! message queue. Unused definitions are listed by `unused_definitions` and removed by
+//! `prune_unused`:
+//! ```
+//! let spec = "struct Monster {}\nstruct Orphan {}\nservice MonsterApi { GET /monsters -> list[Monster] }";
+//! let mut spec = humblegen::parse(spec.as_bytes()).unwrap();
+//! assert_eq!(humblegen::resolve::unused_definitions(&spec), ["Orphan"]);
+//! assert_eq!(humblegen::resolve::prune_unused(&mut spec), ["Orphan"]);
+//! ```
+
+use crate::ast::{SpecIndex, SpecItem};
+use crate::{workspace::Workspace, LibError, Spec};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A reference to a type that is not defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownType {
+    /// The name of the type.
+    pub name: String,
+    /// Where the type is referenced, e.g. `Lair.monsters` or `MonsterApi.GET /monsters`.
+    pub location: String,
+    /// The defined type with the most similar name, if any is similar enough to be a typo.
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for UnknownType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown type `{}` in `{}`", self.name, self.location)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{}`?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// The references of `spec` to types it does not define, in the order of the spec. Services are
+/// no types, so references to them are unknown types as well.
+pub fn unknown_types(spec: &Spec) -> Vec<UnknownType> {
+    let types: BTreeSet<&str> = spec
+        .iter()
+        .filter(|item| !matches!(item, SpecItem::ServiceDef(_)))
+        .map(SpecItem::name)
+        .collect();
+
+    let mut unknown = vec![];
+    spec.for_each_type_use(&mut |location, ty, _| {
+        let mut ty = ty.clone();
+        ty.for_each_mut(&mut |ty| {
+            let name = match ty.user_defined() {
+                Some(name) if !types.contains(name.as_str()) => name,
+                _ => return,
+            };
+            let unknown_type = UnknownType {
+                name: name.to_string(),
+                location: location.to_owned(),
+                suggestion: suggestion(name, &types).map(str::to_owned),
+            };
+            if !unknown.contains(&unknown_type) {
+                unknown.push(unknown_type);
+            }
+        });
+    });
+    unknown
+}
+
+/// Fail with `LibError::UnknownTypes` if `spec` references types it does not define.
+pub(crate) fn check_references(spec: &Spec) -> Result<(), LibError> {
+    let unknown = unknown_types(spec);
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(LibError::UnknownTypes(unknown))
+    }
+}
+
+/// The names of the definitions of `spec` that are not reachable from its services and exported
+/// types, in the order of the spec. In specs without services and exported types, e.g. specs of
+/// the types shared by other specs, all definitions are used.
+pub fn unused_definitions(spec: &Spec) -> Vec<&str> {
+    let used = used_definitions(spec, &[]);
+    spec.iter()
+        .map(SpecItem::name)
+        .filter(|name| !used.contains(*name))
+        .collect()
+}
+
+/// Remove the unused definitions of `spec`, see `unused_definitions`, returning their names.
+pub fn prune_unused(spec: &mut Spec) -> Vec<String> {
+    let used = used_definitions(spec, &[]);
+    prune(spec, &used)
+}
+
+/// Remove the definitions of the common specs that no spec of `workspace` uses, and the unused
+/// definitions of each spec, returning their names.
+pub fn prune_unused_workspace(workspace: &mut Workspace) -> Vec<String> {
+    let mut used_common = BTreeSet::new();
+    let mut pruned = vec![];
+    let mut used_by_spec = vec![];
+    for ws_spec in &workspace.specs {
+        let used = used_definitions(&workspace.resolved_spec(ws_spec), &[&workspace.common]);
+        used_common.extend(
+            workspace
+                .common
+                .iter()
+                .map(SpecItem::name)
+                .filter(|name| used.contains(*name))
+                .map(str::to_owned),
+        );
+        used_by_spec.push(used);
+    }
+    if workspace.specs.is_empty() {
+        used_common = used_definitions(&workspace.common, &[]);
+    }
+    pruned.extend(prune(&mut workspace.common, &used_common));
+    for (ws_spec, used) in workspace.specs.iter_mut().zip(&used_by_spec) {
+        pruned.extend(prune(&mut ws_spec.spec, used));
+    }
+    pruned
+}
+
+/// The names of the definitions of `spec` reachable from its services and exported types, or all
+/// names if there are none besides those of the definitions of `libraries` contained in `spec`.
+fn used_definitions(spec: &Spec, libraries: &[&Spec]) -> BTreeSet<String> {
+    let library_names: BTreeSet<&str> = libraries
+        .iter()
+        .flat_map(|library| library.iter().map(SpecItem::name))
+        .collect();
+    let index = spec.index();
+    let mut pending: Vec<&SpecItem> = spec
+        .iter()
+        .filter(|item| item.service_def().is_some() || item.is_exported())
+        .collect();
+    if pending
+        .iter()
+        .all(|item| library_names.contains(item.name()))
+    {
+        return spec.iter().map(|item| item.name().to_owned()).collect();
+    }
+
+    let mut used = BTreeSet::new();
+    while let Some(item) = pending.pop() {
+        if used.insert(item.name().to_owned()) {
+            pending.extend(dependencies(item, &index));
+        }
+    }
+    used
+}
+
+/// The definitions of `spec` ordered such that types precede the definitions using them, e.g. for
+/// backends of languages that require declarations before use.
+///
+/// Each definition is preceded by the definitions it uses that are not used by earlier
+/// definitions, in the order of their first use, so related types stay together. Otherwise, the
+/// order of the spec is kept. Mutually recursive types cannot all precede each other, but precede
+/// the other types using them.
+pub fn topological_order(spec: &Spec) -> Vec<&SpecItem> {
+    fn visit<'a>(
+        item: &'a SpecItem,
+        index: &SpecIndex<'a>,
+        visited: &mut BTreeSet<&'a str>,
+        order: &mut Vec<&'a SpecItem>,
+    ) {
+        if !visited.insert(item.name()) {
+            return;
+        }
+        for dependency in dependencies(item, index) {
+            visit(dependency, index, visited, order);
+        }
+        order.push(item);
+    }
+
+    let index = spec.index();
+    let mut visited = BTreeSet::new();
+    let mut order = vec![];
+    for item in spec.iter() {
+        visit(item, &index, &mut visited, &mut order);
+    }
+    order
+}
+
+/// The definitions of `index` that `item` uses directly, in the order of their first use.
+fn dependencies<'a>(item: &SpecItem, index: &SpecIndex<'a>) -> Vec<&'a SpecItem> {
+    let mut dependencies: Vec<&SpecItem> = vec![];
+    Spec::new(vec![item.clone()]).for_each_type_use(&mut |_, ty, _| {
+        let mut ty = ty.clone();
+        ty.for_each_mut(&mut |ty| {
+            if let Some(dependency) = ty.user_defined().and_then(|name| index.get(name)) {
+                if !dependencies
+                    .iter()
+                    .any(|known| known.name() == dependency.name())
+                {
+                    dependencies.push(dependency);
+                }
+            }
+        });
+    });
+    dependencies
+}
+
+/// Remove the definitions of `spec` not named in `used`, returning their names.
+fn prune(spec: &mut Spec, used: &BTreeSet<String>) -> Vec<String> {
+    let pruned = spec
+        .iter()
+        .map(SpecItem::name)
+        .filter(|name| !used.contains(*name))
+        .map(str::to_owned)
+        .collect();
+    spec.0.retain(|item| used.contains(item.name()));
+    pruned
+}
+
+/// The name of `types` most similar to `name`, if it differs by at most a third of its characters,
+/// e.g. by a typo, or only by case.
+fn suggestion<'a>(name: &str, types: &BTreeSet<&'a str>) -> Option<&'a str> {
+    let lowercase = name.to_lowercase();
+    types
+        .iter()
+        .map(|ty| {
+            let distance = if ty.to_lowercase() == lowercase {
+                0
+            } else {
+                edit_distance(name, ty)
+            };
+            (distance, *ty)
+        })
+        .filter(|(distance, _)| *distance <= (name.chars().count() / 3).max(1))
+        .min()
+        .map(|(_, ty)| ty)
+}
+
+/// The number of characters to insert, remove or replace, or of adjacent characters to swap, to
+/// turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance of the first i characters of a and j characters of b
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            distances[i][j] = if i == 0 || j == 0 {
+                i + j
+            } else {
+                let mut distance = (distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]))
+                    .min(distances[i - 1][j] + 1)
+                    .min(distances[i][j - 1] + 1);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    distance = distance.min(distances[i - 2][j - 2] + 1);
+                }
+                distance
+            };
+        }
+    }
+    distances[a.len()][b.len()]
+}
@@ -0,0 +1,261 @@
+//! Reports of the size of generated code per file, type and service, e.g. to keep generated
+//! frontend code within bundle budgets.
+//!
+//! The size of a definition is the number of bytes generating it adds to the output. Definitions
+//! are added in dependency order, so mutually recursive definitions are measured as a group.
+//! ```no_run
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! let spec = humblegen::parse_file("protocol.humble")?;
+//! let generator = humblegen::backend::elm::Generator::new(humblegen::Artifact::ClientEndpoints, "Api".to_owned())?;
+//! let report = humblegen::size_report::size_report(&generator, &spec)?;
+//! println!("{}", report);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ast::{SpecItem, TypeIdent};
+use crate::testing::{self, GeneratedFiles};
+use crate::{CodeGenerator, LibError, Spec};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Enums with at least this many variants are flagged.
+const LARGE_ENUM_VARIANTS: usize = 100;
+/// Services with at least this many endpoints are flagged.
+const LARGE_SERVICE_ENDPOINTS: usize = 30;
+/// Definitions and files with at least this percentage of the output are flagged.
+const HEAVY_PERCENTAGE: usize = 25;
+
+/// The size of generated code.
+#[derive(Debug, Clone)]
+pub struct SizeReport {
+    /// Bytes of each generated file, by its `/`-separated path relative to the output.
+    pub files: BTreeMap<String, usize>,
+    /// Bytes of each definition, or group of mutually recursive definitions, largest first.
+    pub definitions: Vec<DefinitionSize>,
+    /// Bytes generated regardless of the definitions, e.g. built-in helpers.
+    pub baseline: usize,
+    /// Suggestions to reduce the size of the heaviest generated code.
+    pub hints: Vec<String>,
+}
+
+/// The size of the code generated for a definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionSize {
+    /// Names of the definition and the definitions it is mutually recursive with.
+    pub names: Vec<String>,
+    /// Bytes added to the output.
+    pub bytes: usize,
+}
+
+impl SizeReport {
+    /// Bytes of all generated files.
+    pub fn total(&self) -> usize {
+        self.files.values().sum()
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.total();
+        writeln!(f, "{} bytes in {} files", total, self.files.len())?;
+        for (path, bytes) in &self.files {
+            writeln!(f, "  {:>9}  {}", bytes, path)?;
+        }
+        writeln!(f, "by definition:")?;
+        for definition in &self.definitions {
+            writeln!(
+                f,
+                "  {:>9}  {:>3}%  {}",
+                definition.bytes,
+                percentage(definition.bytes, total),
+                definition.names.join(", ")
+            )?;
+        }
+        writeln!(
+            f,
+            "  {:>9}  {:>3}%  (built-in)",
+            self.baseline,
+            percentage(self.baseline, total)
+        )?;
+        for hint in &self.hints {
+            writeln!(f, "hint: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `generator` on `spec` and report the size of the generated code.
+///
+/// Generates code once per definition, which takes a while for large specs.
+pub fn size_report(generator: &dyn CodeGenerator, spec: &Spec) -> Result<SizeReport, LibError> {
+    let generate =
+        |items: &[SpecItem]| testing::generate_files(generator, &Spec::new(items.to_vec()));
+
+    let baseline_files = generate(&[])?;
+    let baseline = size(&baseline_files);
+    let mut definitions = vec![];
+    let mut items = vec![];
+    let mut previous = baseline;
+    for group in dependency_groups(spec) {
+        items.extend(group.iter().map(|&item| item.clone()));
+        let current = size(&generate(&items)?);
+        definitions.push(DefinitionSize {
+            names: group.iter().map(|item| item.name().to_owned()).collect(),
+            bytes: current.saturating_sub(previous),
+        });
+        previous = current;
+    }
+    definitions.sort_by_key(|d| std::cmp::Reverse(d.bytes));
+
+    let generated = testing::generate_files(generator, spec)?;
+    let files: BTreeMap<_, _> = generated
+        .iter()
+        .map(|(path, contents)| (path.clone(), contents.len()))
+        .collect();
+    // built-in files are the same for every spec
+    let spec_files: Vec<_> = generated
+        .iter()
+        .filter(|(path, contents)| baseline_files.get(*path) != Some(contents))
+        .map(|(path, contents)| (path.as_str(), contents.len()))
+        .collect();
+    let hints = hints(spec, size(&generated), &spec_files, &definitions);
+    Ok(SizeReport {
+        files,
+        definitions,
+        baseline,
+        hints,
+    })
+}
+
+fn size(files: &GeneratedFiles) -> usize {
+    files.values().map(String::len).sum()
+}
+
+fn percentage(bytes: usize, total: usize) -> usize {
+    (bytes * 100).checked_div(total).unwrap_or(0)
+}
+
+/// The items of `spec` in groups such that every group only references items of itself and of
+/// preceding groups.
+fn dependency_groups(spec: &Spec) -> Vec<Vec<&SpecItem>> {
+    let index = spec.index();
+    let dependencies: BTreeMap<&str, BTreeSet<String>> = spec
+        .iter()
+        .map(|item| (item.name(), referenced_names(item)))
+        .collect();
+
+    // depth first post order, i.e., dependencies before the items referencing them
+    fn visit<'a>(
+        item: &'a SpecItem,
+        index: &crate::ast::SpecIndex<'a>,
+        dependencies: &BTreeMap<&str, BTreeSet<String>>,
+        visited: &mut BTreeSet<&'a str>,
+        order: &mut Vec<&'a SpecItem>,
+    ) {
+        if !visited.insert(item.name()) {
+            return;
+        }
+        for name in &dependencies[item.name()] {
+            if let Some(dependency) = index.get(name) {
+                visit(dependency, index, dependencies, visited, order);
+            }
+        }
+        order.push(item);
+    }
+    let mut visited = BTreeSet::new();
+    let mut order = vec![];
+    for item in spec.iter() {
+        visit(item, &index, &dependencies, &mut visited, &mut order);
+    }
+
+    // in post order, only cycles reference items that come later, which end the group once
+    // all are added
+    let mut groups = vec![];
+    let mut group = vec![];
+    let mut added = BTreeSet::new();
+    for item in order {
+        added.insert(item.name());
+        group.push(item);
+        let closed = group.iter().all(|item: &&SpecItem| {
+            dependencies[item.name()]
+                .iter()
+                .all(|name| added.contains(name.as_str()) || index.get(name).is_none())
+        });
+        if closed {
+            groups.push(std::mem::take(&mut group));
+        }
+    }
+    groups
+}
+
+/// Names of the user defined types referenced by `item`.
+fn referenced_names(item: &SpecItem) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    Spec::new(vec![item.clone()]).for_each_type_ident_mut(&mut |ty: &mut TypeIdent| {
+        if let Some(name) = ty.user_defined() {
+            names.insert(name.to_string());
+        }
+    });
+    names
+}
+
+fn hints(
+    spec: &Spec,
+    total: usize,
+    spec_files: &[(&str, usize)],
+    definitions: &[DefinitionSize],
+) -> Vec<String> {
+    let bytes_of = |name: &str| {
+        definitions
+            .iter()
+            .find(|d| d.names.iter().any(|n| n == name))
+            .map_or(0, |d| d.bytes)
+    };
+
+    let mut hints = vec![];
+    for item in spec.iter() {
+        match item {
+            SpecItem::EnumDef(edef) if edef.variants.len() >= LARGE_ENUM_VARIANTS => {
+                hints.push(format!(
+                    "enum `{}` has {} variants generating {} bytes, consider splitting it into \
+                     several enums",
+                    edef.name,
+                    edef.variants.len(),
+                    bytes_of(&edef.name)
+                ))
+            }
+            SpecItem::ServiceDef(service) if service.endpoints.len() >= LARGE_SERVICE_ENDPOINTS => {
+                hints.push(format!(
+                    "service `{}` has {} endpoints generating {} bytes, consider splitting it \
+                     into several services, such that clients only include those they use",
+                    service.name,
+                    service.endpoints.len(),
+                    bytes_of(&service.name)
+                ))
+            }
+            _ => {}
+        }
+    }
+    for definition in definitions {
+        if percentage(definition.bytes, total) >= HEAVY_PERCENTAGE {
+            hints.push(format!(
+                "`{}` accounts for {}% of the generated code",
+                definition.names.join("`, `"),
+                percentage(definition.bytes, total)
+            ));
+        }
+    }
+    if spec_files.len() > 1 {
+        for &(path, bytes) in spec_files {
+            if percentage(bytes, total) >= HEAVY_PERCENTAGE {
+                hints.push(format!(
+                    "`{}` accounts for {}% of the generated code",
+                    path,
+                    percentage(bytes, total)
+                ));
+            }
+        }
+    }
+    hints
+}
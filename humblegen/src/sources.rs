@@ -0,0 +1,91 @@
+//! Specs held in memory under virtual file names, e.g. by tools generating specs on the fly.
+//!
+//! `include`s resolve to other sources by their virtual file names relative to the including
+//! source, and parse errors refer to the virtual file names.
+//! ```
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! let sources = humblegen::sources::SpecSources::new()
+//!     .with_source("api/monsters.humble", r#"
+//!         include "types.humble" as types
+//!         struct Monster { stats: types.Stats }
+//!     "#)
+//!     .with_source("api/types.humble", "struct Stats { hp: i32 }");
+//! let spec = sources.parse("api/monsters.humble")?;
+//! assert_eq!(spec.iter().count(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{ast::Spec, parser, LibError};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// In-memory specs by virtual file name, with `/`-separated paths.
+#[derive(Debug, Clone, Default)]
+pub struct SpecSources {
+    sources: BTreeMap<String, String>,
+}
+
+impl SpecSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the spec `contents` under the virtual file name `name`, replacing a previous source
+    /// of the same name.
+    pub fn with_source(mut self, name: impl Into<String>, contents: impl Into<String>) -> Self {
+        self.sources.insert(name.into(), contents.into());
+        self
+    }
+
+    /// Parse the source `name`, including the fragments of its `include`s.
+    pub fn parse(&self, name: &str) -> Result<Spec, LibError> {
+        self.parse_with(name, &mut |_| {})
+    }
+
+    /// Parse every source that is not included by another source, by virtual file name.
+    pub fn parse_all(&self) -> Result<BTreeMap<String, Spec>, LibError> {
+        let mut included = BTreeSet::new();
+        let mut specs = BTreeMap::new();
+        for name in self.sources.keys() {
+            let spec = self.parse_with(name, &mut |include| {
+                included.insert(include.to_owned());
+            })?;
+            specs.insert(name.clone(), spec);
+        }
+        specs.retain(|name, _| !included.contains(name));
+        Ok(specs)
+    }
+
+    /// Parse the source `name`, passing the virtual file names of all included fragments to
+    /// `on_include`.
+    fn parse_with(&self, name: &str, on_include: &mut dyn FnMut(&str)) -> Result<Spec, LibError> {
+        let input = self
+            .sources
+            .get(name)
+            .ok_or_else(|| LibError::UnknownSource(name.to_owned()))?;
+        parser::parse(input, Some(name), &mut |include| {
+            let include_name = resolve(name, include);
+            on_include(&include_name);
+            self.sources.get(&include_name).cloned().ok_or_else(|| {
+                LibError::IncludeError(format!("cannot read `{}`: no such source", include_name))
+            })
+        })
+    }
+}
+
+/// The virtual file name of `include` relative to the source `name`, with `.` and `..`
+/// components removed.
+fn resolve(name: &str, include: &str) -> String {
+    let mut components: Vec<&str> = name.split('/').collect();
+    components.pop();
+    for component in include.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+    components.join("/")
+}
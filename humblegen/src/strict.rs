@@ -0,0 +1,110 @@
+//! Strict mode, rejecting specs whose types a backend maps lossily, e.g. `u32` to Elm's `Int`,
+//! which is only guaranteed to hold 32-bit signed integers.
+//!
+//! Every backend declares its lossy mappings, see `CodeGenerator::lossy_mappings`. In strict
+//! mode, each use of a type mapped lossily must be accepted with `@lossy(type, ..)` on the field,
+//! variant, endpoint or definition using it:
+//! ```text
+//! struct Monster {
+//!     @lossy(u32)
+//!     experience: u32,
+//! }
+//! ```
+
+use crate::ast::*;
+use crate::{CodeGenerator, LibError};
+use std::fmt;
+
+/// A lossy mapping of a built-in type to a type of a backend.
+#[derive(Debug, Clone, Copy)]
+pub struct LossyMapping {
+    /// The built-in type.
+    pub atom: AtomType,
+    /// The type of the backend it is mapped to, e.g. `Int`.
+    pub target: &'static str,
+    /// What is lost by the mapping.
+    pub loss: &'static str,
+}
+
+/// A use of a type mapped lossily that is not accepted with `@lossy`.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// Where the type is used, e.g. `Monster.experience` or `MonsterApi: GET /monsters/{id}`.
+    pub location: String,
+    /// The mapping of the type.
+    pub mapping: LossyMapping,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `()` is no annotation value, but a string
+        let arg = match self.mapping.atom {
+            AtomType::Empty => "\"()\"".to_owned(),
+            atom => atom.to_string(),
+        };
+        write!(
+            f,
+            "{}: `{}` is mapped to `{}`, {}; accept with `@lossy({})`",
+            self.location, self.mapping.atom, self.mapping.target, self.mapping.loss, arg
+        )
+    }
+}
+
+/// Check that every use of a type that `generator` maps lossily is accepted with `@lossy`.
+pub fn check_strict(generator: &dyn CodeGenerator, spec: &Spec) -> Result<(), LibError> {
+    let violations = violations(generator.lossy_mappings(), spec);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(LibError::StrictModeViolation(
+        violations.iter().map(Violation::to_string).collect(),
+    ))
+}
+
+/// The uses of types mapped by `mappings` in `spec` that are not accepted with `@lossy`.
+pub fn violations(mappings: &[LossyMapping], spec: &Spec) -> Vec<Violation> {
+    let mut checker = Checker {
+        mappings,
+        violations: vec![],
+    };
+    spec.for_each_type_use(&mut |location, ty, accepted| {
+        checker.check_type(location, ty, accepted)
+    });
+    checker.violations
+}
+
+struct Checker<'a> {
+    mappings: &'a [LossyMapping],
+    violations: Vec<Violation>,
+}
+
+impl Checker<'_> {
+    fn check_type(&mut self, location: &str, ty: &TypeIdent, accepted: &[&Vec<Annotation>]) {
+        let mut ty = ty.clone();
+        let mut atoms = vec![];
+        ty.for_each_mut(&mut |ty| {
+            if let TypeIdent::BuiltIn(atom) = ty {
+                if !atoms.contains(atom) {
+                    atoms.push(*atom);
+                }
+            }
+        });
+        for atom in atoms {
+            let mapping = match self.mappings.iter().find(|m| m.atom == atom) {
+                Some(mapping) => mapping,
+                None => continue,
+            };
+            let keyword = atom.to_string();
+            let is_accepted = accepted
+                .iter()
+                .flat_map(|annotations| annotations.iter())
+                .any(|a| a.name == "lossy" && a.args.contains(&keyword));
+            if !is_accepted {
+                self.violations.push(Violation {
+                    location: location.to_owned(),
+                    mapping: *mapping,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Helpers for snapshot tests of generated code, e.g. with [`insta`](https://docs.rs/insta).
+//!
+//! Backends write their output to the file system. The functions of this module run a backend
+//! on a temporary directory and return everything it wrote as a map of file path to contents,
+//! which is deterministic for a given spec and backend configuration (note that the Rust
+//! backend's output is only formatted if `rustfmt` is available):
+//! ```no_run
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! let spec = humblegen::parse("struct Monster { name: str }".as_bytes())?;
+//! let generator = humblegen::backend::elm::Generator::new(humblegen::Artifact::TypesOnly, "Api".to_owned())?;
+//! let files = humblegen::testing::generate_files(&generator, &spec)?;
+//! assert!(files["Data.elm"].contains("type alias Monster ="));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{workspace::Workspace, CodeGenerator, LibError, Spec};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Generated files by their `/`-separated path relative to the output, e.g. `BuiltIn/Bytes.elm`.
+pub type GeneratedFiles = BTreeMap<String, String>;
+
+/// The path of the output of backends that write a single file, e.g. the Rust and docs backends.
+pub const SINGLE_FILE: &str = "output";
+
+/// Run `generator` on `spec`, returning the generated files.
+///
+/// Backends that write a single file yield a single entry at `SINGLE_FILE`.
+pub fn generate_files(
+    generator: &dyn CodeGenerator,
+    spec: &Spec,
+) -> Result<GeneratedFiles, LibError> {
+    with_temp_dir(|dir| {
+        let output = dir.join(SINGLE_FILE);
+        match generator.generate(spec, &output) {
+            Err(LibError::OutputMustBeFolder { .. }) => {
+                fs::create_dir(&output)?;
+                generator.generate(spec, &output)?;
+            }
+            result => result?,
+        }
+        read_files(dir)
+    })
+    .map(|mut files| {
+        // the output folder of folder backends is not part of the paths
+        let prefix = format!("{}/", SINGLE_FILE);
+        if files.keys().all(|path| path.starts_with(&prefix)) {
+            files = files
+                .into_iter()
+                .map(|(path, contents)| (path[prefix.len()..].to_owned(), contents))
+                .collect();
+        }
+        files
+    })
+}
+
+/// Run `generator` on `workspace`, returning the generated files.
+pub fn generate_workspace_files(
+    generator: &dyn CodeGenerator,
+    workspace: &Workspace,
+) -> Result<GeneratedFiles, LibError> {
+    with_temp_dir(|dir| {
+        generator.generate_workspace(workspace, dir)?;
+        read_files(dir)
+    })
+}
+
+/// Invoke `f` with a fresh, empty temporary directory, which is removed afterwards.
+fn with_temp_dir<T>(f: impl FnOnce(&Path) -> Result<T, LibError>) -> Result<T, LibError> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "humblegen-testing-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir(&dir)?;
+    let result = f(&dir);
+    fs::remove_dir_all(&dir)?;
+    result
+}
+
+/// Read all files below `root`, keyed by their path relative to `root`.
+fn read_files(root: &Path) -> Result<GeneratedFiles, LibError> {
+    let mut files = GeneratedFiles::new();
+    let mut dirs: Vec<PathBuf> = vec![root.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                let relative = path
+                    .strip_prefix(root)
+                    .expect("entries are below root")
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.insert(relative, fs::read_to_string(&path)?);
+            }
+        }
+    }
+    Ok(files)
+}
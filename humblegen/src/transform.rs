@@ -0,0 +1,160 @@
+//! Spec-to-spec transformations, run on parsed specs before code generation.
+//!
+//! Transformations apply organization-specific conventions, e.g. adding audit fields to all
+//! structs, without forking humblegen. They see specs as parsed, i.e., with includes and embeds
+//! resolved and partial structs derived:
+//! ```no_run
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! use humblegen::ast::{FieldDefPair, FieldNode, SpecItem, TypeIdent};
+//! use humblegen::transform::Transform;
+//!
+//! let add_audit_fields = |spec: &mut humblegen::Spec| -> Result<(), humblegen::LibError> {
+//!     for item in spec.iter_mut() {
+//!         if let SpecItem::StructDef(sdef) = item {
+//!             sdef.fields.0.push(FieldNode {
+//!                 pair: FieldDefPair {
+//!                     name: "modified_by".to_owned(),
+//!                     type_ident: TypeIdent::BuiltIn(humblegen::ast::AtomType::Str),
+//!                 },
+//!                 doc_comment: None,
+//!                 annotations: vec![],
+//!                 optional: false,
+//!             });
+//!         }
+//!     }
+//!     Ok(())
+//! };
+//! let mut spec = humblegen::parse_file("spec.humble")?;
+//! add_audit_fields.transform(&mut spec)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Transformations written in other languages run as a `Subprocess`.
+
+use crate::workspace::{Workspace, WorkspaceSpec};
+use crate::{ast_json, resolve, LibError, Spec};
+use std::io::{self, prelude::*};
+use std::process::{Command, Stdio};
+
+/// A transformation of specs.
+///
+/// Implemented by closures taking a `&mut Spec`.
+pub trait Transform {
+    /// Transform `spec` in place.
+    fn transform(&self, spec: &mut Spec) -> Result<(), LibError>;
+
+    /// Transform the common spec and all specs of `workspace` in place.
+    fn transform_workspace(&self, workspace: &mut Workspace) -> Result<(), LibError> {
+        self.transform(&mut workspace.common)?;
+        for workspace_spec in &mut workspace.specs {
+            self.transform(&mut workspace_spec.spec)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F> Transform for F
+where
+    F: Fn(&mut Spec) -> Result<(), LibError>,
+{
+    fn transform(&self, spec: &mut Spec) -> Result<(), LibError> {
+        self(spec)
+    }
+}
+
+/// A transformation by an external command, which reads the spec as AST JSON from stdin and
+/// writes the transformed spec as AST JSON to stdout.
+///
+/// The AST JSON is that of `ast_json`, i.e., `dump_ast` and `load_ast`, so transformed specs are
+/// validated like loaded specs. Commands exiting with a non-zero status fail the transformation,
+/// e.g. to enforce conventions.
+#[derive(Debug, Clone)]
+pub struct Subprocess {
+    program: String,
+    args: Vec<String>,
+}
+
+impl Subprocess {
+    /// A transformation running `program` with `args`.
+    pub fn new(program: String, args: Vec<String>) -> Self {
+        Self { program, args }
+    }
+
+    /// A transformation running the whitespace separated program and arguments of `command`,
+    /// e.g. `python3 add_audit_fields.py`.
+    pub fn from_command_line(command: &str) -> Self {
+        let mut words = command.split_whitespace().map(str::to_owned);
+        let program = words.next().unwrap_or_default();
+        Self::new(program, words.collect())
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> LibError {
+        LibError::TransformError(format!("`{}`: {}", self.program, message))
+    }
+}
+
+impl Subprocess {
+    /// Run the command on `spec`, whose transformed spec may reference types it does not define.
+    fn run(&self, spec: &Spec) -> Result<Spec, LibError> {
+        let input = ast_json::dump_ast(spec).into_bytes();
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.error(e))?;
+        let mut child_stdin = child.stdin.take().unwrap();
+        let mut child_stdout = child.stdout.take().unwrap();
+
+        // Write to stdin in a new thread, so that the command does not block on writing to its
+        // stdout while we block on writing to its stdin.
+        let stdin_handle = std::thread::spawn(move || child_stdin.write_all(&input));
+
+        let mut output = vec![];
+        io::copy(&mut child_stdout, &mut output)?;
+        let status = child.wait()?;
+        // the command may exit without reading all of its input
+        let _ = stdin_handle
+            .join()
+            .expect("the thread writing to stdin does not panic");
+
+        if !status.success() {
+            return Err(self.error(format!("exited with {}", status)));
+        }
+        let output = String::from_utf8(output)
+            .map_err(|e| self.error(format!("invalid AST JSON: {}", e)))?;
+        ast_json::load_ast_unresolved(&output).map_err(|e| self.error(e))
+    }
+}
+
+impl Transform for Subprocess {
+    fn transform(&self, spec: &mut Spec) -> Result<(), LibError> {
+        let transformed = self.run(spec)?;
+        resolve::check_references(&transformed).map_err(|e| self.error(e))?;
+        *spec = transformed;
+        Ok(())
+    }
+
+    /// Transform the common spec and all specs of `workspace`, whose specs may reference the
+    /// transformed common types.
+    fn transform_workspace(&self, workspace: &mut Workspace) -> Result<(), LibError> {
+        let transformed = Workspace {
+            common: self.run(&workspace.common)?,
+            specs: workspace
+                .specs
+                .iter()
+                .map(|workspace_spec| {
+                    Ok(WorkspaceSpec {
+                        name: workspace_spec.name.clone(),
+                        spec: self.run(&workspace_spec.spec)?,
+                    })
+                })
+                .collect::<Result<_, LibError>>()?,
+        };
+        transformed.validate().map_err(|e| self.error(e))?;
+        *workspace = transformed;
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+//! Hashes of the wire shape of types, to verify that two parties agree on the JSON representation
+//! of the types they exchange, e.g. when a client connects to a service.
+//!
+//! The wire shape of a type is what its JSON representation depends on: the keys of the fields of
+//! structs and whether they may be absent, the types of the values, the representation of enums
+//! and the names of their variants and of flags. Names of types, doc comments and the order of
+//! the fields of structs do not change the representation and hence not the hash. Types used by a
+//! type are part of its shape, such that changing a type changes the hashes of all types using it.
+//! ```no_run
+//! # fn main() -> Result<(), humblegen::LibError> {
+//! let spec = humblegen::parse_file("protocol.humble")?;
+//! let manifest = humblegen::wire_hash::manifest(&spec, humblegen::wire_hash::Representation::default());
+//! println!("{}", manifest);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::ast::{
+    AtomType, EnumTagging, SpecIndex, SpecItem, StructFields, TupleDef, TypeIdent, VariantType,
+};
+use crate::{EnumRepresentation, LargeIntRepresentation, MapRepresentation, Spec, WireCasing};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// The options of the backends that change the JSON representation of types.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct Representation {
+    pub wire_casing: WireCasing,
+    pub map_representation: MapRepresentation,
+    pub enum_representation: EnumRepresentation,
+    pub large_int_representation: LargeIntRepresentation,
+}
+
+/// The wire hash of each type of `spec` as represented by `representation`, by the name of the
+/// type. Hashes are 16 hexadecimal digits.
+pub fn wire_hashes(spec: &Spec, representation: Representation) -> BTreeMap<String, String> {
+    let spec = match representation.map_representation.apply(spec) {
+        Cow::Borrowed(spec) => representation.enum_representation.apply(spec),
+        Cow::Owned(spec) => {
+            Cow::Owned(representation.enum_representation.apply(&spec).into_owned())
+        }
+    };
+    let shapes = Shapes {
+        index: spec.index(),
+        representation,
+    };
+    spec.iter()
+        .filter(|spec_item| spec_item.service_def().is_none())
+        .map(|spec_item| {
+            let shape = shapes.item_shape(spec_item, &mut vec![spec_item.name()]);
+            (
+                spec_item.name().to_owned(),
+                format!("{:016x}", fnv1a(&shape)),
+            )
+        })
+        .collect()
+}
+
+/// The wire hashes of the types of `spec` as a JSON object from the name of each type to its hash,
+/// which services can exchange to find the types they disagree on.
+pub fn manifest(spec: &Spec, representation: Representation) -> String {
+    serde_json::to_string_pretty(&wire_hashes(spec, representation))
+        .expect("maps of strings are serializable")
+}
+
+/// The 64 bit FNV-1a hash of `s`, which is stable across platforms and releases.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Renders the canonical description of the wire shape of types, which is hashed.
+struct Shapes<'a> {
+    index: SpecIndex<'a>,
+    representation: Representation,
+}
+
+impl<'a> Shapes<'a> {
+    /// The shape of `spec_item`, within the types named in `stack` whose shape is being rendered.
+    fn item_shape(&self, spec_item: &'a SpecItem, stack: &mut Vec<&'a str>) -> String {
+        let casing = self.representation.wire_casing;
+        match spec_item {
+            SpecItem::StructDef(sdef) => self.fields_shape(&sdef.fields, stack),
+            SpecItem::TupleStructDef(tdef) if tdef.is_newtype() => {
+                self.type_shape(&tdef.elements.0[0], stack)
+            }
+            SpecItem::TupleStructDef(tdef) => self.tuple_shape(&tdef.elements, stack),
+            SpecItem::EnumDef(edef) => {
+                let tagging = match edef.tagging() {
+                    EnumTagging::External => "external".to_owned(),
+                    EnumTagging::Internal { tag } => format!("internal({:?})", tag),
+                    EnumTagging::Adjacent { tag, content } => {
+                        format!("adjacent({:?},{:?})", tag, content)
+                    }
+                    EnumTagging::Untagged => "untagged".to_owned(),
+                };
+                let mut variants: Vec<_> = edef
+                    .variants
+                    .iter()
+                    .zip(edef.discriminants())
+                    .map(|(variant, discriminant)| {
+                        let shape = match &variant.variant_type {
+                            VariantType::Simple if edef.is_numeric() => discriminant.to_string(),
+                            VariantType::Simple => String::new(),
+                            VariantType::Tuple(tuple) => self.tuple_shape(tuple, stack),
+                            VariantType::Struct(fields) => self.fields_shape(fields, stack),
+                            VariantType::Newtype(ty) => self.type_shape(ty, stack),
+                        };
+                        format!("{:?}:{}", variant.wire_name(casing), shape)
+                    })
+                    .collect();
+                // untagged values are decoded as the first variant that matches
+                if edef.tagging() != EnumTagging::Untagged {
+                    variants.sort();
+                }
+                let open = if edef.is_non_exhaustive() { ",.." } else { "" };
+                format!("enum.{}<{}{}>", tagging, variants.join(","), open)
+            }
+            SpecItem::FlagsDef(fdef) => {
+                let mut flags: Vec<_> = fdef
+                    .flags
+                    .iter()
+                    .map(|flag| format!("{:?}", flag.wire_name(casing)))
+                    .collect();
+                flags.sort();
+                format!("flags<{}>", flags.join(","))
+            }
+            SpecItem::ServiceDef(_) => unreachable!("services are not types"),
+        }
+    }
+
+    fn fields_shape(&self, fields: &'a StructFields, stack: &mut Vec<&'a str>) -> String {
+        let mut fields: Vec<_> = fields
+            .iter()
+            .map(|field| {
+                let presence = if field.optional {
+                    "?"
+                } else if field.default_value().is_some() {
+                    "="
+                } else {
+                    ""
+                };
+                format!(
+                    "{:?}{}:{}",
+                    field.wire_name(self.representation.wire_casing),
+                    presence,
+                    self.type_shape(&field.pair.type_ident, stack)
+                )
+            })
+            .collect();
+        fields.sort();
+        format!("{{{}}}", fields.join(","))
+    }
+
+    fn tuple_shape(&self, tuple: &'a TupleDef, stack: &mut Vec<&'a str>) -> String {
+        let elements: Vec<_> = tuple
+            .elements()
+            .iter()
+            .map(|element| self.type_shape(element, stack))
+            .collect();
+        format!("({})", elements.join(","))
+    }
+
+    fn type_shape(&self, type_ident: &'a TypeIdent, stack: &mut Vec<&'a str>) -> String {
+        match type_ident {
+            TypeIdent::BuiltIn(atom @ AtomType::I64) | TypeIdent::BuiltIn(atom @ AtomType::U64)
+                if self.representation.large_int_representation
+                    == LargeIntRepresentation::String =>
+            {
+                format!("str.{}", atom)
+            }
+            TypeIdent::BuiltIn(atom) => atom.to_string(),
+            TypeIdent::List(ty) => format!("list[{}]", self.type_shape(ty, stack)),
+            TypeIdent::Option(ty) => format!("option[{}]", self.type_shape(ty, stack)),
+            TypeIdent::Result(ok, err) => format!(
+                "result[{}][{}]",
+                self.type_shape(ok, stack),
+                self.type_shape(err, stack)
+            ),
+            TypeIdent::Map(key, value) => format!(
+                "map[{}][{}]",
+                self.type_shape(key, stack),
+                self.type_shape(value, stack)
+            ),
+            TypeIdent::Tuple(tuple) => self.tuple_shape(tuple, stack),
+            TypeIdent::UserDefined(name) => {
+                // recursive types refer to the type being rendered by its depth, not its name
+                if let Some(depth) = stack.iter().position(|outer| outer == name) {
+                    return format!("#{}", depth);
+                }
+                let spec_item = match self.index.get(name) {
+                    Some(spec_item) => spec_item,
+                    // defined elsewhere, e.g. in the common specs of a workspace
+                    None => return format!("extern.{}", name),
+                };
+                stack.push(spec_item.name());
+                let shape = self.item_shape(spec_item, stack);
+                stack.pop();
+                shape
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Workspaces of several related specs that share common specs.
+//!
+//! A workspace is described by a TOML config, e.g. `humblegen-workspace.toml`:
+//! ```toml
+//! # specs whose types are shared by all specs of the workspace
+//! common = ["common.humble"]
+//!
+//! [[specs]]
+//! name = "monsters"
+//! input = "monsters.humble"
+//!
+//! [[specs]]
+//! name = "users"
+//! input = "users.humble"
+//! ```
+//! Paths are relative to the config's directory.
+//! Every spec can reference the types defined in the common specs.
+
+use crate::progress::{Progress, ProgressFn};
+use crate::{ast, resolve, LibError, Spec};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// The deserialized workspace config.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// Paths of the common specs.
+    #[serde(default)]
+    pub common: Vec<PathBuf>,
+    /// The specs of the workspace.
+    pub specs: Vec<WorkspaceSpecConfig>,
+}
+
+/// A spec entry of the workspace config.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceSpecConfig {
+    /// The name of the spec, used to name its generated module or package. (example: `monsters`)
+    pub name: String,
+    /// Path of the spec.
+    pub input: PathBuf,
+}
+
+/// A loaded workspace.
+#[derive(Debug)]
+pub struct Workspace {
+    /// The definitions of all common specs.
+    pub common: Spec,
+    /// The specs of the workspace, in config order.
+    pub specs: Vec<WorkspaceSpec>,
+}
+
+/// A spec of a workspace.
+#[derive(Debug)]
+pub struct WorkspaceSpec {
+    /// The name of the spec. (example: `monsters`)
+    pub name: String,
+    /// The definitions of the spec, without the common definitions.
+    pub spec: Spec,
+}
+
+impl Workspace {
+    /// Load the workspace config at `config_path` and parse all of its specs.
+    pub fn load<P: AsRef<Path>>(config_path: P) -> Result<Self, LibError> {
+        Self::load_with_progress(config_path, &mut |_| {})
+    }
+
+    /// Like `load`, reporting every parsed spec to `progress`.
+    pub fn load_with_progress<P: AsRef<Path>>(
+        config_path: P,
+        progress: &mut ProgressFn,
+    ) -> Result<Self, LibError> {
+        let config_path = config_path.as_ref();
+        let config = std::fs::read_to_string(config_path).map_err(LibError::file(config_path))?;
+        let config: WorkspaceConfig =
+            toml::from_str(&config).map_err(|source| LibError::WorkspaceConfigError {
+                path: config_path.to_owned(),
+                source,
+            })?;
+        let root = config_path.parent().unwrap_or_else(|| Path::new(""));
+
+        // specs reference the types of the common specs, so references are resolved by `validate`
+        let mut parse_file = |path: &Path| -> Result<Spec, LibError> {
+            let path = root.join(path);
+            let spec = crate::parse_file_unresolved(&path)?;
+            progress(Progress::Parsed {
+                path: &path,
+                items: spec.0.len(),
+            });
+            Ok(spec)
+        };
+
+        let mut common = Spec::new(vec![]);
+        for path in &config.common {
+            let spec = parse_file(path)?;
+            if !spec.meta().is_empty() {
+                return Err(LibError::WorkspaceError(format!(
+                    "common specs must not have a meta block, but `{}` has",
+                    path.display()
+                )));
+            }
+            common.0.extend(spec.0);
+        }
+        let specs = config
+            .specs
+            .iter()
+            .map(|s| {
+                Ok(WorkspaceSpec {
+                    name: s.name.clone(),
+                    spec: parse_file(&s.input)?,
+                })
+            })
+            .collect::<Result<_, LibError>>()?;
+
+        let workspace = Self { common, specs };
+        workspace.validate()?;
+        Ok(workspace)
+    }
+
+    /// Check the rules of workspaces, e.g. that specs only reference their own and common types.
+    pub(crate) fn validate(&self) -> Result<(), LibError> {
+        if let Some(service) = self.common.iter().find_map(ast::SpecItem::service_def) {
+            return Err(LibError::WorkspaceError(format!(
+                "common specs must not define services, but define `{}`",
+                service.name
+            )));
+        }
+        check_unique_names(&self.common, "common specs")?;
+        resolve::check_references(&self.common)?;
+
+        let mut spec_names = BTreeSet::new();
+        for s in &self.specs {
+            let is_valid_name = s.name.starts_with(|c: char| c.is_ascii_lowercase())
+                && s.name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !is_valid_name || s.name == "common" {
+                return Err(LibError::WorkspaceError(format!(
+                    "spec name `{}` must be a snake_case identifier other than `common`",
+                    s.name
+                )));
+            }
+            if !spec_names.insert(s.name.as_str()) {
+                return Err(LibError::WorkspaceError(format!(
+                    "spec name `{}` is used more than once",
+                    s.name
+                )));
+            }
+            let resolved = self.resolved_spec(s);
+            check_unique_names(
+                &resolved,
+                &format!("spec `{}` and the common specs", s.name),
+            )?;
+            resolve::check_references(&resolved)?;
+            crate::parser::queries::validate_queries(&resolved)?;
+        }
+        Ok(())
+    }
+
+    /// `spec` together with the common definitions it can reference.
+    pub fn resolved_spec(&self, spec: &WorkspaceSpec) -> Spec {
+        Spec(
+            self.common
+                .iter()
+                .chain(spec.spec.iter())
+                .cloned()
+                .collect(),
+            spec.spec.meta().clone(),
+        )
+    }
+
+    /// The names of all types defined in the common specs.
+    pub fn common_type_names(&self) -> BTreeSet<String> {
+        self.common.iter().map(|i| i.name().to_owned()).collect()
+    }
+}
+
+/// Check that no name is defined twice in `spec`, which would result in conflicting generated types.
+fn check_unique_names(spec: &Spec, context: &str) -> Result<(), LibError> {
+    let mut names = BTreeSet::new();
+    for item in spec.iter() {
+        if !names.insert(item.name()) {
+            return Err(LibError::WorkspaceError(format!(
+                "`{}` is defined more than once in {}",
+                item.name(),
+                context
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use humblegen::ast_json::{dump_ast, load_ast, AST_VERSION};
+use humblegen::LibError;
+
+#[test]
+fn dumps_are_versioned_and_resolve_embeds() {
+    let spec = humblegen::parse(
+        "struct Monster { name: str, .. Stats }\nstruct Stats { optional hp: i32 }".as_bytes(),
+    )
+    .unwrap();
+    let dump: serde_json::Value = serde_json::from_str(&dump_ast(&spec)).unwrap();
+
+    assert_eq!(dump["ast_version"], AST_VERSION);
+    let monster = &dump["spec"][0]["StructDef"];
+    assert_eq!(monster["name"], "Monster");
+    let fields: Vec<_> = monster["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["pair"]["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["name", "hp"]);
+}
+
+#[test]
+fn dumps_are_deterministic() {
+    let src =
+        "struct Monster { name: str }\nservice MonsterApi { GET /monsters/{id: i32} -> Monster }";
+    let dump = |src: &str| dump_ast(&humblegen::parse(src.as_bytes()).unwrap());
+    assert_eq!(dump(src), dump(src));
+}
+
+#[test]
+fn dumps_load_back_into_the_spec() {
+    let spec = humblegen::parse(
+        "struct Monster { name: str, .. Stats }\nstruct Stats { optional hp: i32 }\n\
+         service MonsterApi { GET /monsters/{id: i32} -> Monster }"
+            .as_bytes(),
+    )
+    .unwrap();
+    let loaded = load_ast(&dump_ast(&spec)).unwrap();
+    assert_eq!(dump_ast(&loaded), dump_ast(&spec));
+
+    let generator =
+        humblegen::backend::rust::Generator::new(humblegen::Artifact::ServerEndpoints).unwrap();
+    assert_eq!(
+        humblegen::testing::generate_files(&generator, &loaded).unwrap(),
+        humblegen::testing::generate_files(&generator, &spec).unwrap()
+    );
+}
+
+#[test]
+fn dumps_hold_the_meta_block() {
+    let spec = humblegen::parse(
+        r#"meta { title: "Monster API", version: "1.2.0" } struct Monster { name: str }"#
+            .as_bytes(),
+    )
+    .unwrap();
+    let dump = dump_ast(&spec);
+    let json: serde_json::Value = serde_json::from_str(&dump).unwrap();
+
+    assert_eq!(json["spec"]["meta"]["title"], "Monster API");
+    assert_eq!(json["spec"]["meta"].get("base_url"), None);
+    assert_eq!(json["spec"]["items"][0]["StructDef"]["name"], "Monster");
+    let loaded = load_ast(&dump).unwrap();
+    assert_eq!(loaded.meta(), spec.meta());
+    assert_eq!(loaded.meta().version.as_deref(), Some("1.2.0"));
+}
+
+#[test]
+fn dumps_of_other_versions_are_rejected() {
+    let err = load_ast(r#"{"ast_version": 0, "spec": []}"#).unwrap_err();
+    assert!(matches!(err, LibError::InvalidAst(_)));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "invalid AST JSON: unsupported AST version 0, expected {}",
+            AST_VERSION
+        )
+    );
+    assert!(matches!(
+        load_ast(r#"{"ast_version": 1, "spec": 42}"#),
+        Err(LibError::InvalidAst(_))
+    ));
+}
+
+#[test]
+fn loaded_specs_are_validated() {
+    let spec = humblegen::parse(
+        "struct Monster { name: str }\n\
+         service MonsterApi { @max_body_size(1MB) POST /monsters -> Monster -> () }"
+            .as_bytes(),
+    )
+    .unwrap();
+    let dump = dump_ast(&spec).replace("\"1MB\"", "\"lots\"");
+
+    let err = load_ast(&dump).unwrap_err();
+    assert!(
+        matches!(&err, LibError::InvalidDefinition { definition, .. } if definition == "MonsterApi"),
+        "{}",
+        err
+    );
+    assert!(
+        err.to_string().contains("`@max_body_size` expects a size"),
+        "{}",
+        err
+    );
+
+    let dump = dump_ast(&spec).replace("\"Monster\"\n", "\"Dragon\"\n");
+    assert!(matches!(load_ast(&dump), Err(LibError::UnknownTypes(_))));
+}
+
+#[test]
+fn parsed_specs_load_back() {
+    // the specs of the generator tests, whose dumps have modules, partials, literal unions, etc.
+    let mut dirs = vec![std::path::PathBuf::from("./tests")];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.file_name().unwrap() == "spec.humble" {
+                let spec = humblegen::parse_file(&path).unwrap();
+                if let Err(err) = load_ast(&dump_ast(&spec)) {
+                    panic!("{:?}: {}", path, err);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use humblegen::backend::{docs, elm};
+use humblegen::check::{check, OutdatedFile};
+use humblegen::testing::generate_files;
+use humblegen::{Artifact, CodeGenerator};
+
+const SPEC: &str = "/// A monster.\n/// Not a pet.\nstruct Monster { name: str }\n";
+
+fn elm_generator() -> elm::Generator {
+    elm::Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap()
+}
+
+#[test]
+fn up_to_date_output_passes_check() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let generator = elm_generator();
+    generator.generate(&spec, dir.path()).unwrap();
+
+    assert_eq!(check(&generator, &spec, dir.path()).unwrap(), []);
+}
+
+#[test]
+fn check_reports_changed_and_missing_files() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let generator = elm_generator();
+    generator.generate(&spec, dir.path()).unwrap();
+    std::fs::write(
+        dir.path().join("Data.elm"),
+        "module Api.Data exposing (..)\n",
+    )
+    .unwrap();
+    std::fs::remove_file(dir.path().join("Encode.elm")).unwrap();
+
+    assert_eq!(
+        check(&generator, &spec, dir.path()).unwrap(),
+        [
+            OutdatedFile::Changed(dir.path().join("Data.elm")),
+            OutdatedFile::Missing(dir.path().join("Encode.elm")),
+        ]
+    );
+}
+
+#[test]
+fn check_single_file_output() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("docs.html");
+    let generator = docs::Generator::default();
+
+    assert_eq!(
+        check(&generator, &spec, &output).unwrap(),
+        [OutdatedFile::Missing(output.clone())]
+    );
+    generator.generate(&spec, &output).unwrap();
+    assert_eq!(check(&generator, &spec, &output).unwrap(), []);
+}
+
+#[test]
+fn output_does_not_depend_on_line_endings() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let crlf_spec = humblegen::parse(SPEC.replace('\n', "\r\n").as_bytes()).unwrap();
+
+    assert_eq!(
+        generate_files(&elm_generator(), &crlf_spec).unwrap(),
+        generate_files(&elm_generator(), &spec).unwrap()
+    );
+}
@@ -0,0 +1,145 @@
+use humblegen::testing::{generate_files, SINGLE_FILE};
+use humblegen::Artifact;
+
+const SPEC: &str = r#"
+service MonsterApi {
+    GET /monsters -> list[str],
+    @internal
+    POST /cache/flush -> () -> (),
+}
+"#;
+
+#[test]
+fn internal_endpoints_are_omitted_from_docs() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+
+    let docs = &generate_files(&humblegen::backend::docs::Generator::default(), &spec).unwrap()
+        [SINGLE_FILE];
+    assert!(docs.contains("monsters"));
+    assert!(!docs.contains("flush"));
+
+    let generator = humblegen::backend::rust::Generator::new(Artifact::ClientEndpoints).unwrap();
+    let rust = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    assert!(rust.contains("flush"));
+}
+
+#[test]
+fn cors_origins_are_documented() {
+    let spec = humblegen::parse(
+        r#"
+        @cors(origins = ["https://app.example.com", "http://localhost:8000"])
+        service MonsterApi {
+            GET /monsters -> list[str],
+        }
+        "#
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let docs = &generate_files(&humblegen::backend::docs::Generator::default(), &spec).unwrap()
+        [SINGLE_FILE];
+    assert!(docs.contains(
+        "origins (CORS): <code>https://app.example.com</code>, <code>http://localhost:8000</code>."
+    ));
+}
+
+#[test]
+fn cors_origins_must_not_have_a_path() {
+    let err = humblegen::parse(
+        r#"@cors(origins = ["https://app.example.com/"]) service MonsterApi { GET /monsters -> str }"#
+            .as_bytes(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@cors` expects the allowed origins"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn route_limits_are_documented() {
+    let spec = humblegen::parse(
+        r#"
+        service UploadApi {
+            @max_body_size(2MiB)
+            @timeout(30s)
+            POST /uploads -> bytes -> (),
+        }
+        "#
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let docs = &generate_files(&humblegen::backend::docs::Generator::default(), &spec).unwrap()
+        [SINGLE_FILE];
+    assert!(
+        docs.contains("Limits: request bodies of at most 2097152 bytes, responds within 30000ms.")
+    );
+}
+
+#[test]
+fn max_body_sizes_have_a_unit() {
+    let err = humblegen::parse(
+        "service UploadApi { @max_body_size(1000) POST /uploads -> bytes -> () }".as_bytes(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("`@max_body_size` expects a size"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn idempotent_endpoints_are_documented() {
+    let spec = humblegen::parse(
+        "struct Payment { amount: u64 } service PaymentApi { @idempotent POST /payments -> Payment -> u64 }"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let docs = &generate_files(&humblegen::backend::docs::Generator::default(), &spec).unwrap()
+        [SINGLE_FILE];
+    assert!(docs.contains("requests carry a unique <code>Idempotency-Key</code> header"));
+}
+
+#[test]
+fn get_endpoints_are_not_idempotent() {
+    let err =
+        humblegen::parse("service PaymentApi { @idempotent GET /payments -> u64 }".as_bytes())
+            .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@idempotent` is only allowed on mutating endpoints"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn meta_is_documented() {
+    let spec = humblegen::parse(include_str!("rust/service-meta/spec.humble").as_bytes()).unwrap();
+
+    let docs = &generate_files(&humblegen::backend::docs::Generator::default(), &spec).unwrap()
+        [SINGLE_FILE];
+    assert!(docs.contains("<title>\nMonster API 1.2.0\n</title>"));
+    assert!(docs.contains(r#"<h1 class="meta--title">Monster API</h1>"#));
+    assert!(docs.contains("<li>Base URL <code>https://api.example.com/v1</code></li>"));
+}
+
+#[test]
+fn types_are_documented_before_their_users() {
+    let spec = humblegen::parse(
+        "struct Monster { lair: Lair }\nstruct Lair { rooms: list[Room] }\nstruct Room {}"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let docs = &generate_files(&humblegen::backend::docs::Generator::default(), &spec).unwrap()
+        [SINGLE_FILE];
+    let position = |name: &str| docs.find(&format!("id=\"type-{}\"", name)).unwrap();
+    assert!(position("Room") < position("Lair"));
+    assert!(position("Lair") < position("Monster"));
+}
@@ -0,0 +1,655 @@
+use humblegen::backend::elm::{Generator, Package};
+use humblegen::testing::generate_files;
+use humblegen::{
+    Artifact, EnumRepresentation, LargeIntRepresentation, MapRepresentation, WireCasing,
+};
+
+const SPEC: &str = include_str!("elm/routes.humble");
+
+/// Reference `Route` module generated for `routes.humble`.
+const ROUTE: &str = include_str!("elm/Route.elm");
+
+#[test]
+fn elm_route_parser() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned())
+        .unwrap()
+        .with_route_parser(true);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(files["Route.elm"], ROUTE);
+}
+
+#[test]
+fn elm_route_parser_is_optional() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(!files.contains_key("Route.elm"));
+}
+
+#[test]
+fn elm_wire_casing() {
+    let spec = humblegen::parse(include_str!("rust/wire-casing/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned())
+        .unwrap()
+        .with_wire_casing(WireCasing::CamelCase);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("(\"displayName\", E.string obj.displayName)"));
+    assert!(encode.contains("MonsterIsland -> E.string \"monsterIsland\""));
+    assert!(encode.contains("(\"lastSeen\", E.int obj.lastSeen)"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("requiredRenamed \"nickname\" [ \"nickName\" ]"));
+    assert!(decode.contains("D.field \"farOut\" (D.map FarOut D.string)"));
+}
+
+#[test]
+fn elm_json_name_overrides_wire_casing() {
+    let spec = humblegen::parse(include_str!("rust/json-name/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned())
+        .unwrap()
+        .with_wire_casing(WireCasing::CamelCase);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("(\"userID\", E.int obj.userId)"));
+    assert!(encode.contains("Active -> E.string \"ACTIVE\""));
+    assert!(encode.contains("(\"until\", builtinEncodeDate obj.suspendedUntil)"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("requiredRenamed \"e-mail\" [ \"mail\" ]"));
+    assert!(decode.contains(
+        "D.field \"suspended\" (D.succeed Status__Suspended__Internal__ |> required \"until\""
+    ));
+}
+
+#[test]
+fn elm_tuple_struct_codecs() {
+    let spec = humblegen::parse(include_str!("rust/tuple-struct/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(files["Data.elm"].contains("type Point\n    = Point Int Int"));
+    let encode = &files["Encode.elm"];
+    assert!(
+        encode.contains("encodePoint (Point x0 x1) =\n    E.list identity [ E.int x0, E.int x1 ]")
+    );
+    assert!(encode.contains("encodeMonsterId (MonsterId x0) =\n    E.int x0"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("D.succeed Point |> requiredIdx 0 D.int |> requiredIdx 1 D.int"));
+    assert!(decode.contains("D.map MonsterId D.int"));
+}
+
+#[test]
+fn elm_nested_embeds() {
+    let spec = humblegen::parse(include_str!("rust/embeds/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(
+        files["Data.elm"].contains("    , embeddedStruct: EmbeddedStruct\n    , legacy: MyStruct")
+    );
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("(\"embedded_struct\", encodeEmbeddedStruct obj.embeddedStruct)"));
+    assert!(encode.contains("(\"legacy\", encodeMyStruct obj.legacy)"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains(
+        "|> required \"embedded_struct\" decodeEmbeddedStruct\n        |> required \"legacy\" decodeMyStruct"
+    ));
+}
+
+#[test]
+fn elm_nested_embeds_are_named_in_snake_case() {
+    let err = humblegen::parse(
+        "struct Data { x: i32 } struct Monster { @nested(\"Data\") .. Data }".as_bytes(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@nested` expects the snake_case name of the field"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn elm_enum_tagging() {
+    let spec = humblegen::parse(include_str!("rust/enum-tagging/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("Started -> E.object [ (\"type\", E.string \"Started\") ]"));
+    assert!(encode.contains(
+        "Moved obj -> E.object [ (\"type\", E.string \"moved\"), (\"x\", E.float obj.x), (\"y\", E.float obj.y) ]"
+    ));
+    assert!(
+        encode.contains("Say obj -> E.object [ (\"t\", E.string \"Say\"), (\"c\", E.string obj) ]")
+    );
+    assert!(encode.contains("Number obj -> E.int obj"));
+    assert!(encode.contains("Missing -> E.null"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("builtinDecodeTagged \"type\" \"Started\" (D.succeed Started)"));
+    assert!(
+        decode.contains("builtinDecodeTagged \"t\" \"Say\" (D.field \"c\" (D.map Say D.string))")
+    );
+    assert!(decode.contains("D.map Number D.int"));
+    assert!(decode.contains("D.null Missing"));
+}
+
+#[test]
+fn elm_non_exhaustive_enums() {
+    let spec =
+        humblegen::parse(include_str!("rust/non-exhaustive/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(files["Data.elm"].contains("| AuditEventUnknown"));
+    assert!(files["Encode.elm"].contains("AuditEventUnknown -> E.null"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains(
+        "builtinDecodeUnknownVariant Nothing [\"LoggedIn\", \"Renamed\", \"Deleted\", \"Moved\"] AuditEventUnknown"
+    ));
+    assert!(decode.contains(
+        "builtinDecodeUnknownVariant (Just \"type\") [\"Ping\", \"Message\"] NotificationUnknown"
+    ));
+    assert!(decode
+        .contains("builtinDecodeUnknownVariant (Just \"t\") [\"Empty\", \"Circle\"] ShapeUnknown"));
+}
+
+#[test]
+fn elm_numeric_enums() {
+    let spec = humblegen::parse(include_str!("rust/numeric-enums/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("Normal -> E.int 2"));
+    assert!(encode.contains("Debug -> E.int (-1)"));
+    assert!(encode.contains("SensorStateUnknown -> E.null"));
+    assert!(encode.contains("Green -> E.string \"Green\""));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains(
+        "builtinDecodeDiscriminant [ ( 1, Low ), ( 2, Normal ), ( 10, High ), ( -1, Debug ) ] Nothing"
+    ));
+    assert!(decode
+        .contains("builtinDecodeDiscriminant [ ( 0, Off ), ( 1, On ) ] (Just SensorStateUnknown)"));
+}
+
+#[test]
+fn elm_enum_adjacent() {
+    let spec = humblegen::parse(include_str!("rust/enum-adjacent/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned())
+        .unwrap()
+        .with_enum_representation(EnumRepresentation::Adjacent);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("Empty -> E.object [ (\"tag\", E.string \"Empty\") ]"));
+    assert!(encode.contains(
+        "Circle obj -> E.object [ (\"tag\", E.string \"Circle\"), (\"contents\", E.float obj) ]"
+    ));
+    assert!(encode.contains(
+        "Line x0 x1 -> E.object [ (\"tag\", E.string \"Line\"), (\"contents\", E.list identity [encodePoint x0, encodePoint x1]) ]"
+    ));
+    assert!(encode.contains(
+        "Polygon obj -> E.object [ (\"tag\", E.string \"Polygon\"), (\"contents\", E.object [(\"points\", E.list encodePoint obj.points), (\"closed\", E.bool obj.closed)]) ]"
+    ));
+    assert!(encode.contains("Red -> E.string \"Red\""));
+    assert!(encode.contains("Started -> E.object [ (\"kind\", E.string \"Started\") ]"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("builtinDecodeTagged \"tag\" \"Empty\" (D.succeed Empty)"));
+    assert!(decode.contains(
+        "builtinDecodeTagged \"tag\" \"Circle\" (D.field \"contents\" (D.map Circle D.float))"
+    ));
+    assert!(decode.contains("builtinDecodeTagged \"kind\" \"Started\" (D.succeed Started)"));
+
+    // externally tagged by default
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(files["Encode.elm"].contains("Empty -> E.string \"Empty\""));
+}
+
+#[test]
+fn elm_map_entries() {
+    let spec = humblegen::parse(include_str!("rust/map-entries/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned())
+        .unwrap()
+        .with_map_representation(MapRepresentation::Entries);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let data = &files["Data.elm"];
+    assert!(data.contains("monsters: List (Coordinates, String)"));
+    assert!(data.contains("names: Dict String Coordinates"));
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains(
+        "(\"monsters\", E.list (\\(x0, x1) -> E.list identity [ encodeCoordinates x0, E.string x1 ]) obj.monsters)"
+    ));
+    assert!(encode.contains("(\"names\", E.dict identity encodeCoordinates obj.names)"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains(
+        "required \"population\" (D.list (D.succeed (\\x0 x1 -> (x0, x1)) |> requiredIdx 0 D.int |> requiredIdx 1 D.int))"
+    ));
+}
+
+#[test]
+fn elm_large_ints() {
+    let spec = humblegen::parse(include_str!("rust/large-ints/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned())
+        .unwrap()
+        .with_large_int_representation(LargeIntRepresentation::String);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let data = &files["Data.elm"];
+    assert!(data.contains("id: String"));
+    assert!(data.contains("level: Int"));
+    assert!(data.contains("visits: Dict String String"));
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("(\"id\", E.string obj.id)"));
+    assert!(encode.contains("(\"friends\", E.list E.string obj.friends)"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("|> required \"id\" builtinDecodeLargeInt"));
+    assert!(decode.contains("|> required \"friends\" (D.list builtinDecodeLargeInt)"));
+    assert!(decode.contains("D.oneOf [ D.string, D.map String.fromInt D.int ]"));
+
+    // numbers by default, which are `Int`s like `i32`
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(files["Data.elm"].contains("id: Int"));
+    assert!(files["Encode.elm"].contains("(\"id\", E.int obj.id)"));
+    assert!(files["Decode.elm"].contains("|> required \"id\" D.int"));
+}
+
+#[test]
+fn elm_field_defaults() {
+    let spec = humblegen::parse(
+        r#"
+        struct Monster {
+            name: str,
+            @default(unknown)
+            species: str,
+            @default("-1")
+            level: i32,
+            @default(true)
+            @renamed_from("is_wild")
+            wild: bool,
+            @server_set
+            id: u32,
+            @server_set
+            created: datetime,
+        }
+        "#
+        .as_bytes(),
+    )
+    .unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("|> required \"name\" D.string"));
+    assert!(decode.contains("|> defaulted \"species\" \"unknown\" D.string"));
+    assert!(decode.contains("|> defaulted \"level\" (-1) D.int"));
+    assert!(decode.contains("|> defaultedRenamed \"wild\" [ \"is_wild\" ] True D.bool"));
+    assert!(decode.contains("|> defaulted \"id\" 0 D.int"));
+    assert!(decode.contains("|> defaulted \"created\" (Time.millisToPosix 0) builtinDecodeIso8601"));
+    // fields keep their types
+    assert!(files["Data.elm"].contains("level: Int"));
+}
+
+#[test]
+fn elm_field_defaults_must_match_the_field_type() {
+    let err =
+        humblegen::parse("struct Monster { @default(many) level: i32 }".as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@default(many)` of field `level` must be a literal of its type"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn elm_optional_fields() {
+    let spec =
+        humblegen::parse(include_str!("rust/optional-fields/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let data = &files["Data.elm"];
+    assert!(data.contains("nickname: Maybe (Maybe String)"));
+    assert!(data.contains("lair: Maybe String"));
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("E.object <| List.filterMap identity\n"));
+    assert!(encode.contains("Maybe.map (\\v -> (\"name\", E.string v)) obj.name"));
+    assert!(encode.contains("Just (\"lair\", builtinEncodeMaybe E.string obj.lair)"));
+    assert!(encode.contains(
+        "[Just (\"id\", E.int obj.id), Maybe.map (\\v -> (\"name\", E.string v)) obj.name]"
+    ));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("|> optional \"name\" D.string"));
+    assert!(decode
+        .contains("|> optionalRenamed \"nickname\" [ \"nick\" ] (builtinDecodeOption D.string)"));
+    assert!(decode.contains("|> required \"lair\" (builtinDecodeOption D.string)"));
+}
+
+#[test]
+fn elm_partial_structs() {
+    let spec =
+        humblegen::parse(include_str!("rust/partial-structs/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let data = &files["Data.elm"];
+    assert!(data.contains("type alias MonsterPatch ="));
+    assert!(data.contains("nickname: Maybe (Maybe String)"));
+    assert!(data.contains("hp: Maybe Int"));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("|> optional \"nick\" (builtinDecodeOption D.string)"));
+}
+
+#[test]
+fn elm_doc_comments() {
+    let spec = humblegen::parse(
+        "/// A monster, {-not-} a pet.\nstruct Monster { name: str }\n\
+         /// Monster management.\nservice MonsterApi {\n\
+         /// Find a monster.\n///\n/// Unknown ids are `-}` not found.\n\
+         GET /monsters/{id: i32} -> Monster,\n}"
+            .as_bytes(),
+    )
+    .unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(files["Data.elm"].contains("{-| A monster, { -not- } a pet.\n-}\ntype alias Monster ="));
+    let service = &files["Service/MonsterApi.elm"];
+    assert!(service.contains("{- Monster management.\n-}"));
+    assert!(service.contains(
+        "{-| Find a monster.\n\nUnknown ids are `- }` not found.\n-}\ngetByIdOfMonsters : Int -> Request NoQuery Ty.Monster"
+    ));
+}
+
+#[test]
+fn elm_pagination_helpers() {
+    let spec = humblegen::parse(include_str!("elm/paginated.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(files["ServiceBuiltIn.elm"].contains("type alias PageState a ="));
+    let service = &files["Service/MonsterApi.elm"];
+    assert!(service.contains(
+        "loadMoreGetMonstersByLairOfLairs : Int -> Ty.MonsterQuery -> (Result Error Ty.MonsterPage -> msg) -> PageState Ty.Monster -> ( PageState Ty.Monster, Cmd msg )"
+    ));
+    assert!(service.contains(
+        "loadMore (\\cursor -> getMonstersByLairOfLairs component1_lair |> withQuery { query | cursor = cursor }) toMsg state"
+    ));
+    // only routes annotated with `@paginated`
+    assert!(!service.contains("loadMoreGetMonsters "));
+}
+
+#[test]
+fn elm_pagination_requires_cursor() {
+    let spec = include_str!("elm/paginated.humble")
+        .replace("cursor: option[str],\n    species", "species");
+    match humblegen::parse(spec.as_bytes()) {
+        Err(humblegen::LibError::InvalidDefinition {
+            definition,
+            message,
+        }) => {
+            assert_eq!(definition, "MonsterApi");
+            assert!(message.ends_with("must have a query with a `cursor: option[str]` field"));
+        }
+        other => panic!("expected invalid definition, got {:?}", other),
+    }
+}
+
+#[test]
+fn elm_paginated_return_types_expand_to_pages() {
+    let spec = humblegen::parse(
+        include_str!("elm/paginated.humble")
+            .replace("struct MonsterPage", "struct LairPage")
+            .replace("-> MonsterPage", "-> paginated[Monster]")
+            .as_bytes(),
+    )
+    .unwrap();
+    let index = spec.index();
+    let page = index.struct_def("MonsterPage").unwrap();
+    let fields: Vec<_> = page
+        .fields
+        .iter()
+        .map(|f| format!("{}: {}", f.pair.name, f.pair.type_ident))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            "items: list[Monster]",
+            "next_cursor: option[str]",
+            "total: option[u64]"
+        ]
+    );
+
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap();
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(files["Data.elm"].contains("type alias MonsterPage ="));
+    // `paginated[T]` implies `@paginated`
+    let service = &files["Service/MonsterApi.elm"];
+    assert!(service.contains("loadMoreGetMonstersByLairOfLairs : Int"));
+    assert!(service.contains(
+        "loadMoreGetMonsters : Ty.MonsterQuery -> (Result Error Ty.MonsterPage -> msg) -> PageState Ty.Monster"
+    ));
+}
+
+#[test]
+fn elm_page_structs_are_named_like_no_other_definition() {
+    let spec = include_str!("elm/paginated.humble").replace(
+        "GET /monsters?{MonsterQuery} -> MonsterPage",
+        "GET /monsters?{MonsterQuery} -> paginated[Monster]",
+    );
+    match humblegen::parse(spec.as_bytes()) {
+        Err(humblegen::LibError::InvalidDefinition {
+            definition,
+            message,
+        }) => {
+            assert_eq!(definition, "MonsterApi");
+            assert_eq!(
+                message,
+                "page struct `MonsterPage` of paginated endpoint `GET /monsters` is named like another definition"
+            );
+        }
+        other => panic!("expected invalid definition, got {:?}", other),
+    }
+}
+
+#[test]
+fn elm_keyword_fields() {
+    let spec =
+        humblegen::parse("struct Item { type: str, where: i32, name: str }".as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let data = &files["Data.elm"];
+    assert!(data.contains("{ type_: String"));
+    assert!(data.contains(", where_: Int"));
+    assert!(data.contains(", name: String"));
+    // the wire names are unchanged
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("(\"type\", E.string obj.type_)"));
+}
+
+#[test]
+fn elm_flags() {
+    let spec = humblegen::parse(include_str!("rust/flags/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let data = &files["Data.elm"];
+    assert!(data.contains("type PermissionsFlag\n    = PermissionsRead\n    | PermissionsWrite\n    | PermissionsAdmin"));
+    assert!(data.contains("type alias Permissions =\n    List PermissionsFlag"));
+    assert!(data.contains("permissionsHas : PermissionsFlag -> Permissions -> Bool"));
+    assert!(data.contains("permissionsInsert : PermissionsFlag -> Permissions -> Permissions"));
+    assert!(data.contains("permissionsRemove : PermissionsFlag -> Permissions -> Permissions"));
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains(
+        "[ ( PermissionsRead, \"Read\" ), ( PermissionsWrite, \"Write\" ), ( PermissionsAdmin, \"admin\" ) ]"
+    ));
+    let decode = &files["Decode.elm"];
+    assert!(decode.contains("\"admin\" ->\n                            D.succeed PermissionsAdmin"));
+    assert!(decode.contains("D.fail (\"unknown flag \" ++ s)"));
+}
+
+#[test]
+fn elm_literal_unions() {
+    let spec =
+        humblegen::parse(include_str!("rust/literal-unions/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let data = &files["Data.elm"];
+    assert!(data.contains("type Status\n    = Active\n    | OnHold\n    | DeletedByAdmin"));
+    let encode = &files["Encode.elm"];
+    assert!(encode.contains("OnHold -> E.string \"on-hold\""));
+}
+
+#[test]
+fn elm_modules() {
+    let spec = humblegen::parse(include_str!("rust/modules/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let billing = &files["Billing.elm"];
+    assert!(billing.starts_with("module Api.Billing exposing (..)\n"));
+    assert!(billing.contains("type alias Invoice =\n    Data.BillingInvoice"));
+    assert!(billing.contains(
+        "decodeLineItem : D.Decoder LineItem\ndecodeLineItem =\n    Decode.decodeBillingLineItem"
+    ));
+    assert!(billing.contains(
+        "encodeStatus : Status -> E.Value\nencodeStatus =\n    Encode.encodeBillingStatus"
+    ));
+    assert!(files["Shipping.elm"].contains("type alias Currency =\n    Data.ShippingCurrency"));
+    assert!(files["Data.elm"].contains("type alias BillingInvoice ="));
+}
+
+#[test]
+fn elm_modules_are_not_named_like_generated_modules() {
+    let spec = humblegen::parse("mod data { struct Point { x: f64 } }".as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+
+    let err = generate_files(&generator, &spec).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("modules named like generated modules"));
+}
+
+#[test]
+fn elm_base_url() {
+    let spec = humblegen::parse(include_str!("rust/service-meta/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let service = &files["Service/MonsterApi.elm"];
+    assert!(service.contains("baseUrl : String\nbaseUrl =\n    \"https://api.example.com/v1\""));
+    assert!(service.contains("|> withBase baseUrl"));
+}
+
+#[test]
+fn elm_idempotency_keys() {
+    let spec =
+        humblegen::parse(include_str!("rust/service-idempotency/spec.humble").as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(
+        files["ServiceBuiltIn.elm"].contains("idempotencyKeyGenerator : Random.Generator String")
+    );
+    let service = &files["Service/PaymentApi.elm"];
+    assert!(service.contains(
+        "createPaymentsByAccountOfAccounts : Int -> Ty.Payment -> String -> Request NoQuery Int"
+    ));
+    assert!(service.contains("|> withHeader \"Idempotency-Key\" idempotencyKey"));
+    assert!(service.contains("createTransfers : Ty.Payment -> Request NoQuery Int"));
+}
+
+#[test]
+fn elm_config_by_environment() {
+    let spec =
+        humblegen::parse(include_str!("rust/service-client-environments/spec.humble").as_bytes())
+            .unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Api".to_owned())
+        .unwrap()
+        .with_environments(vec![
+            humblegen::Environment::new("prod", "https://api.example.com/v1").unwrap(),
+            humblegen::Environment::new("local_dev", "http://localhost:3000/api").unwrap(),
+        ]);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    let config = &files["Config.elm"];
+    assert!(config.starts_with("module Api.Config exposing (..)"));
+    assert!(config.contains("type Environment\n    = Prod\n    | LocalDev\n"));
+    assert!(config.contains("        LocalDev ->\n            \"local_dev\""));
+    assert!(
+        config.contains("        LocalDev ->\n            config \"http://localhost:3000/api\"")
+    );
+    assert!(config.contains("defaultConfig =\n    config \"https://api.example.com/v1\""));
+    assert!(config.contains("withConfig : Config -> Request q t -> Request q t"));
+}
+
+#[test]
+fn elm_package_scaffold() {
+    let spec = humblegen::parse(
+        "meta { title: \"Monster API\", version: \"2.1.0\" }\n\
+         struct Monster { born: datetime }\n\
+         service MonsterApi { GET /monsters -> list[Monster] }"
+            .as_bytes(),
+    )
+    .unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "Monsters.Api".to_owned())
+        .unwrap()
+        .with_package(Some(Package::new("acme/monster-api").unwrap()));
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert!(
+        files["src/Monsters/Api/Data.elm"].starts_with("module Monsters.Api.Data exposing (..)")
+    );
+    assert!(files.contains_key("src/Monsters/Api/Service/MonsterApi.elm"));
+    assert!(!files.contains_key("Data.elm"));
+
+    let elm_json: serde_json::Value = serde_json::from_str(&files["elm.json"]).unwrap();
+    assert_eq!(elm_json["type"], "package");
+    assert_eq!(elm_json["name"], "acme/monster-api");
+    assert_eq!(elm_json["version"], "2.1.0");
+    assert_eq!(elm_json["summary"], "Types and clients of Monster API");
+    let exposed_modules = elm_json["exposed-modules"].as_array().unwrap();
+    assert!(exposed_modules.contains(&"Monsters.Api.Data".into()));
+    assert!(exposed_modules.contains(&"Monsters.Api.Service.MonsterApi".into()));
+    assert!(exposed_modules.contains(&"Monsters.Api.BuiltIn.Uuid".into()));
+    // every package the generated modules import is declared
+    for dependency in [
+        "elm/http",
+        "elm/random",
+        "elm/url",
+        "justinmimbs/date",
+        "rtfeldman/elm-iso8601-date-strings",
+        "danfishgold/base64-bytes",
+    ] {
+        assert!(
+            elm_json["dependencies"].get(dependency).is_some(),
+            "{}",
+            dependency
+        );
+    }
+}
+
+#[test]
+fn elm_package_names_are_author_and_project() {
+    assert!(Package::new("acme/monster-api").is_ok());
+    for name in [
+        "monster-api",
+        "Acme/monster-api",
+        "acme/monster_api",
+        "acme/api-",
+        "acme/",
+    ] {
+        assert!(Package::new(name).is_err(), "{}", name);
+    }
+}
@@ -0,0 +1,98 @@
+module Api.Route exposing (..)
+
+import Date -- justinmimbs/date
+import Iso8601  -- rtfeldman/elm-iso8601-date-strings
+import Time  -- elm/time
+import Url exposing (Url)  -- elm/url
+import Url.Builder
+import Url.Parser as P exposing ((</>))
+import Api.BuiltIn.Uuid as BuiltinUuid
+
+
+fromUrl : Url -> Maybe Route
+fromUrl =
+    P.parse parser
+
+
+string : P.Parser (String -> a) a
+string =
+    P.custom "STRING" Url.percentDecode
+
+
+float : P.Parser (Float -> a) a
+float =
+    P.custom "FLOAT" String.toFloat
+
+
+bool : P.Parser (Bool -> a) a
+bool =
+    P.custom "BOOL"
+        (\s ->
+            case s of
+                "true" ->
+                    Just True
+
+                "false" ->
+                    Just False
+
+                _ ->
+                    Nothing
+        )
+
+
+dateTime : P.Parser (Time.Posix -> a) a
+dateTime =
+    P.custom "DATETIME" (Iso8601.toTime >> Result.toMaybe)
+
+
+date : P.Parser (Date.Date -> a) a
+date =
+    P.custom "DATE" (Date.fromIsoString >> Result.toMaybe)
+
+
+type Route
+    = MonsterApiGetMonsters
+    | MonsterApiGetByIdOfMonsters Int
+    | MonsterApiGetNameByIdOfMonsters Int
+    | MonsterApiGetByAtOfByNameOfByNameMonsters String Time.Posix
+    | LairApiGetByOpenOfOpenByIdOfLairs BuiltinUuid.Uuid Bool
+    | LairApiGetByDayOfByDateLairs Date.Date
+    | LairApiGetByDepthOfByDepthLairs Float
+
+
+parser : P.Parser (Route -> a) a
+parser =
+    P.oneOf
+        [ P.map MonsterApiGetMonsters (P.s "monsters")
+        , P.map MonsterApiGetByIdOfMonsters (P.s "monsters" </> P.int)
+        , P.map MonsterApiGetNameByIdOfMonsters (P.s "monsters" </> P.int </> P.s "name")
+        , P.map MonsterApiGetByAtOfByNameOfByNameMonsters (P.s "monsters" </> P.s "by-name" </> string </> dateTime)
+        , P.map LairApiGetByOpenOfOpenByIdOfLairs (P.s "lairs" </> BuiltinUuid.parseUrl </> P.s "open" </> bool)
+        , P.map LairApiGetByDayOfByDateLairs (P.s "lairs" </> P.s "by-date" </> date)
+        , P.map LairApiGetByDepthOfByDepthLairs (P.s "lairs" </> P.s "by-depth" </> float)
+        ]
+
+
+toPath : Route -> String
+toPath route =
+    case route of
+        MonsterApiGetMonsters ->
+            Url.Builder.absolute [ "monsters" ] []
+
+        MonsterApiGetByIdOfMonsters component1_id ->
+            Url.Builder.absolute [ "monsters", String.fromInt component1_id ] []
+
+        MonsterApiGetNameByIdOfMonsters component1_id ->
+            Url.Builder.absolute [ "monsters", String.fromInt component1_id, "name" ] []
+
+        MonsterApiGetByAtOfByNameOfByNameMonsters component2_name component3_at ->
+            Url.Builder.absolute [ "monsters", "by-name", Url.percentEncode component2_name, Iso8601.fromTime component3_at ] []
+
+        LairApiGetByOpenOfOpenByIdOfLairs component1_id component3_open ->
+            Url.Builder.absolute [ "lairs", BuiltinUuid.toString component1_id, "open", (\v -> if v then "true" else "false") component3_open ] []
+
+        LairApiGetByDayOfByDateLairs component2_day ->
+            Url.Builder.absolute [ "lairs", "by-date", Date.toIsoString component2_day ] []
+
+        LairApiGetByDepthOfByDepthLairs component2_depth ->
+            Url.Builder.absolute [ "lairs", "by-depth", String.fromFloat component2_depth ] []
@@ -0,0 +1,698 @@
+use humblegen::{Artifact, CodeGenerator, LibError};
+use std::error::Error;
+
+#[test]
+fn missing_spec_files_carry_their_path_and_cause() {
+    let err = humblegen::parse_file("does/not/exist.humble").unwrap_err();
+    match &err {
+        LibError::FileError { path, source } => {
+            assert_eq!(path, std::path::Path::new("does/not/exist.humble"));
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        other => panic!("expected file error, got {:?}", other),
+    }
+    assert_eq!(err.to_string(), "cannot access `does/not/exist.humble`");
+    assert!(err.source().unwrap().is::<std::io::Error>());
+    assert_eq!(
+        err.path(),
+        Some(std::path::Path::new("does/not/exist.humble"))
+    );
+}
+
+#[test]
+fn parse_errors_locate_the_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("spec.humble");
+    std::fs::write(&path, "struct Monster {\n    name: str\n    hp: i32,\n}\n").unwrap();
+
+    let err = humblegen::parse_file(&path).unwrap_err();
+    assert!(matches!(err, LibError::ParseError(_)));
+    assert_eq!(err.line_col(), Some((2, 11)));
+    assert!(err.to_string().contains(&path.display().to_string()));
+}
+
+#[test]
+fn all_syntax_errors_are_reported() {
+    let spec = "struct Monster {\n    name: str\n    hp: i32,\n}\n\n/// A color.\nenum Color { Red Green }\n\nstruct Lair { monsters: list[Monster] }\n\nservice MonsterApi {\n    GET /monsters ->\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).unwrap_err();
+
+    assert!(matches!(err, LibError::ParseErrors(_)));
+    let positions: Vec<_> = err
+        .parse_errors()
+        .iter()
+        .map(|e| match e.line_col {
+            pest::error::LineColLocation::Pos(pos) => pos,
+            pest::error::LineColLocation::Span(start, _) => start,
+        })
+        .collect();
+    assert_eq!(positions, [(2, 11), (7, 18), (13, 1)]);
+    assert_eq!(err.line_col(), Some((2, 11)));
+}
+
+#[test]
+fn syntax_errors_of_misplaced_definitions_are_reported_once() {
+    let spec = "struct Monster { name: str }\n\nmeta { title: \"Monster API\" }\n";
+    let err = humblegen::parse(spec.as_bytes()).unwrap_err();
+
+    assert!(matches!(err, LibError::ParseError(_)));
+    assert_eq!(err.line_col(), Some((3, 1)));
+}
+
+#[test]
+fn invalid_annotations_of_all_definitions_are_reported() {
+    let spec = "meta { title: \"Uploads\", base_url: \"/api\" }\n\n\
+                service UploadApi {\n    @timeout(soon) POST /uploads -> bytes -> (),\n}\n\n\
+                struct Monster { @json_name(\"a$b\") name: str }\n";
+    let err = humblegen::parse(spec.as_bytes()).unwrap_err();
+
+    assert!(matches!(err, LibError::ParseErrors(_)));
+    let errors: Vec<_> = err
+        .parse_errors()
+        .iter()
+        .map(|e| match (&e.line_col, &e.variant) {
+            (
+                pest::error::LineColLocation::Span(start, _),
+                pest::error::ErrorVariant::CustomError { message },
+            ) => (*start, message.split(',').next().unwrap().to_owned()),
+            other => panic!("expected spanned custom error, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            (
+                (1, 36),
+                "meta field `base_url` must be an absolute http or https URL".to_owned()
+            ),
+            (
+                (4, 5),
+                "`@timeout` expects a duration in `ms` or `s`".to_owned()
+            ),
+            (
+                (7, 18),
+                "`@json_name` expects the name on the wire without `\\` and `$`".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn annotations_not_allowed_on_a_definition_are_errors() {
+    let err = humblegen::parse("@bogus\nservice MonsterApi { GET /monsters -> str }".as_bytes())
+        .unwrap_err();
+    assert!(matches!(err, LibError::ParseError(_)));
+    assert_eq!(err.line_col(), Some((1, 2)));
+    assert!(err
+        .to_string()
+        .contains("annotation `@bogus` is not allowed here"));
+
+    let err = humblegen::parse(
+        "service MonsterApi {\n    @status(abc) GET /monsters -> str,\n}".as_bytes(),
+    )
+    .unwrap_err();
+    assert_eq!(err.line_col(), Some((2, 6)));
+    assert!(err
+        .to_string()
+        .contains("annotation `@status` is not allowed here"));
+}
+
+#[test]
+fn all_unknown_types_are_reported_with_suggestions() {
+    let spec = "struct Monster { lair: Lari, color: option[COLOUR] }\n\
+                struct Lair { monsters: list[Monster] }\n\
+                enum Colour { Red }\n\
+                service MonsterApi { GET /monsters -> list[Monstr], GET /lairs -> Dungeon }";
+    let err = humblegen::parse(spec.as_bytes()).unwrap_err();
+    let unknown = match &err {
+        LibError::UnknownTypes(unknown) => unknown,
+        other => panic!("expected unknown types, got {:?}", other),
+    };
+
+    let names: Vec<_> = unknown
+        .iter()
+        .map(|unknown| (unknown.name.as_str(), unknown.suggestion.as_deref()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("Lari", Some("Lair")),
+            ("COLOUR", Some("Colour")),
+            ("Monstr", Some("Monster")),
+            ("Dungeon", None),
+        ]
+    );
+    assert_eq!(
+        err.to_string().lines().next(),
+        Some("unknown type `Lari` in `Monster.lair`, did you mean `Lair`?")
+    );
+}
+
+#[test]
+fn services_are_no_types() {
+    let spec = "struct Lair { api: MonsterApi }\nservice MonsterApi { GET /lairs -> list[Lair] }";
+    let err = humblegen::parse(spec.as_bytes()).unwrap_err();
+    assert_eq!(err.to_string(), "unknown type `MonsterApi` in `Lair.api`");
+}
+
+#[test]
+fn invalid_definitions_are_named() {
+    let err = humblegen::parse("struct Monster { ..Stats }".as_bytes()).unwrap_err();
+    match err {
+        LibError::InvalidDefinition {
+            definition,
+            message,
+        } => {
+            assert_eq!(definition, "Monster");
+            assert_eq!(message, "embeds unknown struct `Stats`");
+        }
+        other => panic!("expected invalid definition, got {:?}", other),
+    }
+}
+
+#[test]
+fn path_params_have_built_in_types_and_distinct_names() {
+    let invalid = |spec: &str| match humblegen::parse(spec.as_bytes()).unwrap_err() {
+        LibError::InvalidDefinition {
+            definition,
+            message,
+        } => (definition, message),
+        other => panic!("expected invalid definition, got {:?}", other),
+    };
+    assert_eq!(
+        invalid("struct Monster {}\nservice MonsterApi { GET /monsters/{monster: Monster} -> str }"),
+        (
+            "MonsterApi".to_owned(),
+            "endpoint `GET /monsters/{monster}` has path parameter `monster` of a type other than a built-in type".to_owned()
+        )
+    );
+    assert_eq!(
+        invalid("service MonsterApi { GET /{:u32}/{:u32} -> str }"),
+        (
+            "MonsterApi".to_owned(),
+            "endpoint `GET /{param}/{param}` has two path parameters named `param`".to_owned()
+        )
+    );
+    assert_eq!(
+        invalid("service FileApi { GET /files/{*path: str}/meta -> str }"),
+        (
+            "FileApi".to_owned(),
+            "endpoint `GET /files/{*path}/meta` has segments after the rest of the path `path`"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        invalid("service FileApi { GET /files/{*path: i32} -> str }"),
+        (
+            "FileApi".to_owned(),
+            "endpoint `GET /files/{*path}` captures the rest of the path in `path` of a type other than `str`".to_owned()
+        )
+    );
+}
+
+#[test]
+fn headers_have_distinct_parameter_names() {
+    let invalid = |spec: &str| match humblegen::parse(spec.as_bytes()).unwrap_err() {
+        LibError::InvalidDefinition {
+            definition,
+            message,
+        } => (definition, message),
+        other => panic!("expected invalid definition, got {:?}", other),
+    };
+    assert_eq!(
+        invalid(r#"service MonsterApi { GET /monsters/{id: i32} header "Id": str -> str }"#),
+        (
+            "MonsterApi".to_owned(),
+            "endpoint `GET /monsters/{id}` has header `Id` named `id` like another parameter"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        invalid(
+            r#"service MonsterApi { GET /monsters header "X-Id": str header "x-id": str -> str }"#
+        )
+        .1,
+        "endpoint `GET /monsters` has header `x-id` named `x_id` like another parameter"
+    );
+    assert_eq!(
+        invalid(r#"service MonsterApi { GET /monsters header "Query": str -> str }"#).1,
+        "endpoint `GET /monsters` has header `Query` named `query` like another parameter"
+    );
+    assert_eq!(
+        invalid(r#"service MonsterApi { GET /monsters -> str with header "ETag": str header "E-Tag": str }"#).1,
+        "endpoint `GET /monsters` has response header `E-Tag` named `e_tag` like another response header"
+    );
+}
+
+#[test]
+fn header_names_start_with_a_letter() {
+    for name in ["-", "1-Id", "-Id"] {
+        let spec = format!(
+            "service MonsterApi {{ GET /monsters header {:?}: str -> str }}",
+            name
+        );
+        let err = humblegen::parse(spec.as_bytes()).unwrap_err();
+        assert!(matches!(err, LibError::ParseError(_)), "{}", err);
+    }
+}
+
+#[test]
+fn query_fields_are_atoms() {
+    let query = |fields: &str| {
+        let spec = format!(
+            "struct MonsterQuery {{ {} }}\nservice MonsterApi {{ GET /monsters?query(MonsterQuery) -> str }}",
+            fields
+        );
+        humblegen::parse(spec.as_bytes())
+    };
+    assert!(query("species: option[str], max_age: u32, optional bits: bytes").is_ok());
+    for fields in [
+        "species: list[str]",
+        "species: option[list[str]]",
+        "optional species: option[str]",
+        "species: map[str][str]",
+    ] {
+        match query(fields).unwrap_err() {
+            LibError::InvalidDefinition {
+                definition,
+                message,
+            } => {
+                assert_eq!(definition, "MonsterApi");
+                assert_eq!(message, "endpoint `GET /monsters` has query `MonsterQuery` with field `species`, which must be an atom or an option of an atom");
+            }
+            other => panic!("expected invalid definition, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn query_fields_of_common_structs_are_atoms() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("common.humble"),
+        "struct MonsterQuery { species: list[str] }",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("monsters.humble"),
+        "service MonsterApi { GET /monsters?query(MonsterQuery) -> str }",
+    )
+    .unwrap();
+    let path = dir.path().join("humblegen.toml");
+    std::fs::write(
+        &path,
+        "common = [\"common.humble\"]\n\n[[specs]]\nname = \"monsters\"\ninput = \"monsters.humble\"\n",
+    )
+    .unwrap();
+
+    let err = humblegen::workspace::Workspace::load(&path).unwrap_err();
+    assert_eq!(err.to_string(), "invalid definition `MonsterApi`: endpoint `GET /monsters` has query `MonsterQuery` with field `species`, which must be an atom or an option of an atom");
+}
+
+#[test]
+fn service_versions_are_unique() {
+    let spec = "service MonsterApi {\n    version 1 { GET /health -> str },\n    version 1 { GET /status -> str },\n}\n";
+    let err = humblegen::parse(spec.as_bytes()).unwrap_err();
+    assert!(matches!(err, LibError::ParseError(_)));
+    assert_eq!(err.line_col(), Some((3, 5)));
+    assert!(err
+        .to_string()
+        .contains("service `MonsterApi` has two `version 1` blocks"));
+}
+
+#[test]
+fn invalid_workspace_configs_keep_the_toml_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("humblegen.toml");
+    std::fs::write(&path, "common = 42\n").unwrap();
+
+    let err = humblegen::workspace::Workspace::load(&path).unwrap_err();
+    assert!(matches!(err, LibError::WorkspaceConfigError { .. }));
+    assert_eq!(err.path(), Some(path.as_path()));
+    assert!(err.source().unwrap().is::<toml::de::Error>());
+}
+
+#[test]
+fn unwritable_outputs_carry_their_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("missing/protocol.rs");
+    let spec = humblegen::parse("struct Monster { name: str }".as_bytes()).unwrap();
+    let generator = humblegen::backend::rust::Generator::new(Artifact::TypesOnly).unwrap();
+
+    let err = generator.generate(&spec, &output).unwrap_err();
+    assert!(matches!(err, LibError::FileError { .. }));
+    assert_eq!(err.path(), Some(output.as_path()));
+}
+
+#[test]
+fn unsupported_features_are_errors_instead_of_panics() {
+    let elm = humblegen::backend::elm::Generator::new(Artifact::ClientEndpoints, "Api".to_owned())
+        .unwrap();
+    let unsupported = |generator: &dyn CodeGenerator, spec: &str| {
+        let spec = humblegen::parse(spec.as_bytes()).unwrap();
+        match humblegen::testing::generate_files(generator, &spec) {
+            Err(LibError::UnsupportedFeature {
+                feature,
+                backend,
+                span,
+            }) => (feature, backend, span),
+            other => panic!("expected unsupported feature, got {:?}", other),
+        }
+    };
+
+    assert_eq!(
+        unsupported(&elm, "struct Lair { rooms: map[i32][str] }"),
+        ("`i32` map keys".to_owned(), "elm", "Lair.rooms".to_owned())
+    );
+    // large integers are named as in the spec, although they are represented like `i32`
+    assert_eq!(
+        unsupported(&elm, "struct Lair { rooms: map[i64][str] }"),
+        ("`i64` map keys".to_owned(), "elm", "Lair.rooms".to_owned())
+    );
+    assert_eq!(
+        unsupported(
+            &elm,
+            "struct Monster { name: str }\nstruct Lair { @server_set owner: Monster }"
+        ),
+        (
+            "`@server_set` fields of user defined types".to_owned(),
+            "elm",
+            "Lair.owner".to_owned()
+        )
+    );
+    assert_eq!(
+        unsupported(&elm, "enum Never {}"),
+        (
+            "enums without variants".to_owned(),
+            "elm",
+            "Never".to_owned()
+        )
+    );
+    assert_eq!(
+        unsupported(&elm, "service Api { GET /monsters?{str} -> () }"),
+        (
+            "queries that are not structs".to_owned(),
+            "elm",
+            "Api: GET /monsters".to_owned()
+        )
+    );
+
+    let rust = humblegen::backend::rust::Generator::new(Artifact::ServerEndpoints).unwrap();
+    let spec =
+        "enum Upload { File(bytes) }\nservice Api { POST /uploads -> multipart[Upload] -> () }";
+    assert_eq!(
+        unsupported(&rust, spec),
+        (
+            "multipart bodies that are not structs".to_owned(),
+            "rust",
+            "Api: POST /uploads".to_owned()
+        )
+    );
+}
+
+#[test]
+fn graphql_rejects_unknown_types() {
+    // the parser already rejects unknown types, but specs may also be built or edited in code
+    let mut spec =
+        humblegen::parse("struct Monster { lair: Lair }\nstruct Lair {}".as_bytes()).unwrap();
+    spec.0.retain(|spec_item| spec_item.name() != "Lair");
+    let generator = humblegen::backend::graphql::Generator::new(Artifact::TypesOnly).unwrap();
+    let err = humblegen::testing::generate_files(&generator, &spec).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid definition `Monster.lair`: references unknown type `Lair`"
+    );
+}
+
+#[test]
+fn underivable_traits_name_the_offending_type() {
+    let underivable = |generator: humblegen::backend::rust::Generator, spec: &str| {
+        let spec = humblegen::parse(spec.as_bytes()).unwrap();
+        humblegen::testing::generate_files(&generator, &spec)
+            .unwrap_err()
+            .to_string()
+    };
+    let rust = || humblegen::backend::rust::Generator::new(Artifact::TypesOnly).unwrap();
+
+    assert_eq!(
+        underivable(rust(), "@derive(Hash) struct Monster { weight: f64 }"),
+        "invalid definition `Monster`: cannot derive `Hash`: field `weight` of type `f64` does not implement it"
+    );
+    assert_eq!(
+        underivable(rust(), "@derive(Ord) struct Monster { lair: Lair } struct Lair(str)"),
+        "invalid definition `Monster`: cannot derive `PartialEq`: field `lair` of type `Lair` does not implement it"
+    );
+    assert_eq!(
+        underivable(rust(), "@derive(Default) enum Monster { Dragon(i32), Goblin }"),
+        "invalid definition `Monster`: cannot derive `Default`: the first variant `Dragon` is not simple"
+    );
+    assert_eq!(
+        underivable(
+            rust().with_derives(vec![humblegen::ast::Derive::Default]),
+            "struct Monster { born: datetime }"
+        ),
+        "invalid definition `Monster`: cannot derive `Default`: field `born` of type `datetime` does not implement it"
+    );
+}
+
+#[test]
+fn columns_belong_to_db_rows() {
+    let err =
+        humblegen::parse(r#"struct Monster { @column("monster_name") name: str }"#.as_bytes())
+            .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "`@column` of field `name` is only allowed in structs annotated with `@db_row`"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn rust_attrs_are_attributes() {
+    let err =
+        humblegen::parse(r#"@rust_attr("serde(default)") struct Monster { name: str }"#.as_bytes())
+            .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@rust_attr` expects Rust attributes"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn only_rust_attrs_contain_quotes() {
+    let err = humblegen::parse(r#"struct Monster { @json_name("a\"b") name: str }"#.as_bytes())
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("arguments of `@json_name` cannot contain `\"`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn non_exhaustive_enums_are_tagged() {
+    let err = humblegen::parse(
+        "@non_exhaustive @untagged enum Key { Number(i32), Name(str) }".as_bytes(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@non_exhaustive` enum `Key` cannot be `@untagged`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn non_exhaustive_enums_reserve_unknown() {
+    let err =
+        humblegen::parse("@non_exhaustive enum Kind { Goblin, Unknown }".as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@non_exhaustive` enum `Kind` cannot have a variant named `Unknown`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn numeric_enums_are_simple() {
+    let err =
+        humblegen::parse("@numeric enum Shape { Point, Circle(f64) }".as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("variant `Circle` of enum `Shape` must be simple"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn discriminants_are_unique() {
+    let err = humblegen::parse("enum Color { Red = 1, Green = 1 }".as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("variant `Green` cannot have the discriminant `1`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn numeric_enums_are_untagged() {
+    let err = humblegen::parse(r#"@numeric @tag("type") enum Color { Red, Green }"#.as_bytes())
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("enum `Color` can only have one representation"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn flags_are_not_empty() {
+    let err = humblegen::parse("flags Permissions {}".as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("flags `Permissions` must have at least one flag"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn flags_are_unique() {
+    let err = humblegen::parse("flags Permissions { Read, Write, Read }".as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("flag `Read` of flags `Permissions` is defined twice"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn literals_are_unique() {
+    let err = humblegen::parse(r#"type Status = "active" | "active";"#.as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(r#"literal "active" of type `Status` is listed twice"#),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn literals_have_distinct_variant_names() {
+    let err = humblegen::parse(r#"type Status = "on-hold" | "on_hold";"#.as_bytes()).unwrap_err();
+    assert!(err.to_string().contains(r#"literals "on-hold" and "on_hold" of type `Status` are both named `OnHold` as variants"#), "{}", err);
+}
+
+#[test]
+fn literals_start_with_a_letter() {
+    let err = humblegen::parse(r#"type Login = "password" | "2fa";"#.as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(r#"literal "2fa" of type `Login` cannot be named as a variant"#),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn literals_are_json_names() {
+    let err = humblegen::parse(r#"type Currency = "usd" | "usd$";"#.as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(r#"literal "usd$" of type `Currency` cannot contain `"`, `\` or `$`"#),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn anonymous_structs_are_unique() {
+    let err = humblegen::parse(
+        r#"service MonsterApi {
+            POST /monsters -> { name: str } -> (),
+            POST /monsters?{str} -> { name: str } -> (),
+        }"#
+        .as_bytes(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "anonymous struct `MonsterApiPostMonstersBody` is named like another anonymous struct"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn anonymous_structs_are_not_named_like_definitions() {
+    let err = humblegen::parse(
+        "struct Location { position: { lat: f64 } }\nstruct LocationPosition { x: u8 }".as_bytes(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "anonymous struct `LocationPosition` of `Location` is named like another definition"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn meta_fields_are_known() {
+    let err = humblegen::parse(r#"meta { license: "MIT" }"#.as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("unknown meta field `license`, expected `title`, `version` or `base_url`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn meta_fields_are_unique() {
+    let err = humblegen::parse(r#"meta { title: "Monster API", title: "Monsters" }"#.as_bytes())
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("meta field `title` is declared twice"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn meta_base_url_is_absolute() {
+    let err = humblegen::parse(r#"meta { base_url: "/api" }"#.as_bytes()).unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "meta field `base_url` must be an absolute http or https URL, but is \"/api\""
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn environments_are_named_in_snake_case_and_have_absolute_urls() {
+    assert!(humblegen::Environment::new("local_dev", "http://localhost:3000").is_ok());
+    assert_eq!(
+        humblegen::Environment::new("Prod", "https://api.example.com").unwrap_err(),
+        "environment name `Prod` must be a snake_case identifier"
+    );
+    assert_eq!(
+        humblegen::Environment::new("prod", "api.example.com").unwrap_err(),
+        "base URL of environment `prod` must be an absolute http or https URL, but is \"api.example.com\""
+    );
+}
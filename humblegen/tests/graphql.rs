@@ -0,0 +1,28 @@
+use humblegen::backend::graphql::Generator;
+use humblegen::testing::{generate_files, SINGLE_FILE};
+use humblegen::Artifact;
+
+const SPEC: &str = include_str!("graphql/spec.humble");
+
+/// Reference output of the GraphQL backend for `spec.humble`.
+const SCHEMA: &str = include_str!("graphql/spec.graphql");
+
+#[test]
+fn graphql() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ServerEndpoints).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(files[SINGLE_FILE], SCHEMA);
+}
+
+#[test]
+fn graphql_types_only() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly).unwrap();
+
+    let schema = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    assert!(schema.contains("type Monster {"));
+    assert!(!schema.contains("type Query"));
+    assert!(!schema.contains("input "));
+}
@@ -0,0 +1,98 @@
+use humblegen::highlight::{highlight, Format};
+use humblegen::parser::tokens::{classify, TokenKind};
+
+fn kinds(spec: &str) -> Vec<(&str, TokenKind)> {
+    classify(spec)
+        .unwrap()
+        .into_iter()
+        .map(|token| (&spec[token.span], token.kind))
+        .collect()
+}
+
+#[test]
+fn tokens_are_classified_by_grammar_rule() {
+    use TokenKind::*;
+    let spec = "/// A monster.\nstruct Monster { optional hp: i32, lair: common.Lair, .. Stats }\n\
+                // not a doc comment\n\
+                enum Kind { Monster(list[Monster]) }\n\
+                service Api { @slo(p99 = 200ms) GET /monsters/{id: u32} -> () }";
+    assert_eq!(
+        kinds(spec),
+        [
+            ("/// A monster.", DocComment),
+            ("struct", Keyword),
+            ("Monster", Type),
+            ("{", Punctuation),
+            ("optional", Keyword),
+            ("hp", Field),
+            (":", Punctuation),
+            ("i32", BuiltInType),
+            (",", Punctuation),
+            ("lair", Field),
+            (":", Punctuation),
+            ("common", Namespace),
+            (".", Punctuation),
+            ("Lair", Type),
+            (",", Punctuation),
+            ("..", Punctuation),
+            ("Stats", Type),
+            ("}", Punctuation),
+            ("// not a doc comment", Comment),
+            ("enum", Keyword),
+            ("Kind", Type),
+            ("{", Punctuation),
+            ("Monster", Variant),
+            ("(", Punctuation),
+            ("list", Keyword),
+            ("[", Punctuation),
+            ("Monster", Type),
+            ("]", Punctuation),
+            (")", Punctuation),
+            ("}", Punctuation),
+            ("service", Keyword),
+            ("Api", Type),
+            ("{", Punctuation),
+            ("@slo", Annotation),
+            ("(", Punctuation),
+            ("p99 = 200ms", Literal),
+            (")", Punctuation),
+            ("GET", HttpMethod),
+            ("/", Punctuation),
+            ("monsters", Route),
+            ("/", Punctuation),
+            ("{", Punctuation),
+            ("id", Field),
+            (":", Punctuation),
+            ("u32", BuiltInType),
+            ("}", Punctuation),
+            ("->", Punctuation),
+            ("()", BuiltInType),
+            ("}", Punctuation),
+        ]
+    );
+}
+
+#[test]
+fn highlights_html_and_ansi() {
+    let spec = "@json_name(\"<hp>\")\nstruct Monster {}\n";
+    assert_eq!(
+        highlight(spec, Format::Html).unwrap(),
+        "<pre class=\"humble\"><code><span class=\"humble-annotation\">@json_name</span>\
+         <span class=\"humble-punctuation\">(</span>\
+         <span class=\"humble-string\">&quot;&lt;hp&gt;&quot;</span>\
+         <span class=\"humble-punctuation\">)</span>\n\
+         <span class=\"humble-keyword\">struct</span> <span class=\"humble-type\">Monster</span> \
+         <span class=\"humble-punctuation\">{</span><span class=\"humble-punctuation\">}</span>\
+         </code></pre>\n"
+    );
+    assert_eq!(
+        highlight("struct Monster {}\n", Format::Ansi).unwrap(),
+        "\x1b[35mstruct\x1b[0m \x1b[33mMonster\x1b[0m {}\n"
+    );
+}
+
+#[test]
+fn specs_not_matching_the_grammar_are_errors() {
+    let err = highlight("struct monster {}", Format::Html).unwrap_err();
+    assert!(matches!(err, humblegen::LibError::ParseError(_)));
+}
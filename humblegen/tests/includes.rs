@@ -0,0 +1,146 @@
+use humblegen::LibError;
+use std::path::Path;
+
+const FRAGMENT: &str = r#"
+struct Customer {
+    id: str,
+    address: Address,
+}
+
+struct Address {
+    city: str,
+}
+"#;
+
+/// Write `spec` next to the fragment `vendor/stripe_types.humble` and parse it.
+fn parse_with_fragment(spec: &str, fragment: &str) -> Result<humblegen::Spec, LibError> {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("vendor")).unwrap();
+    std::fs::write(dir.path().join("vendor/stripe_types.humble"), fragment).unwrap();
+    std::fs::write(dir.path().join("spec.humble"), spec).unwrap();
+    humblegen::parse_file(dir.path().join("spec.humble"))
+}
+
+fn include_error(result: Result<humblegen::Spec, LibError>) -> String {
+    match result {
+        Err(LibError::IncludeError(msg)) => msg,
+        other => panic!("expected include error, got {:?}", other),
+    }
+}
+
+#[test]
+fn included_definitions_are_namespaced() {
+    let spec = parse_with_fragment(
+        r#"
+        include "vendor/stripe_types.humble" as stripe
+
+        struct Order {
+            customer: stripe.Customer,
+        }
+        "#,
+        FRAGMENT,
+    )
+    .unwrap();
+
+    let names: Vec<_> = spec.iter().map(|item| item.name()).collect();
+    assert_eq!(names, vec!["StripeCustomer", "StripeAddress", "Order"]);
+    let customer = spec.iter().next().unwrap();
+    let address_field = match customer {
+        humblegen::ast::SpecItem::StructDef(sdef) => &sdef.fields.0[1],
+        _ => unreachable!(),
+    };
+    assert_eq!(address_field.pair.type_ident.to_string(), "StripeAddress");
+}
+
+#[test]
+fn unknown_qualified_names_are_rejected() {
+    let msg = include_error(parse_with_fragment(
+        r#"
+        include "vendor/stripe_types.humble" as stripe
+        struct Order { customer: paypal.Customer }
+        "#,
+        FRAGMENT,
+    ));
+    assert_eq!(msg, "unknown namespace `paypal` in `paypal.Customer`");
+
+    let msg = include_error(parse_with_fragment(
+        r#"
+        include "vendor/stripe_types.humble" as stripe
+        struct Order { customer: stripe.Invoice }
+        "#,
+        FRAGMENT,
+    ));
+    assert_eq!(msg, "namespace `stripe` does not define `stripe.Invoice`");
+}
+
+#[test]
+fn included_definitions_are_read_only() {
+    let msg = include_error(parse_with_fragment(
+        r#"
+        include "vendor/stripe_types.humble" as stripe
+        struct StripeCustomer { id: i32 }
+        "#,
+        FRAGMENT,
+    ));
+    assert!(msg.starts_with("`StripeCustomer` conflicts with the name of an included definition"));
+
+    let msg = include_error(parse_with_fragment(
+        r#"include "vendor/stripe_types.humble" as stripe"#,
+        "service Payments { GET /payments -> str }",
+    ));
+    assert_eq!(
+        msg,
+        "fragments must only define types, but define service `Payments` (included as `stripe`)"
+    );
+}
+
+#[test]
+fn includes_require_a_spec_file() {
+    let msg = include_error(humblegen::parse(
+        r#"include "vendor/stripe_types.humble" as stripe"#.as_bytes(),
+    ));
+    assert!(msg.contains("use `parse_file`"));
+
+    let msg = include_error(
+        humblegen::parse_file(Path::new("tests/rust/includes/spec.humble")).and_then(|_| {
+            parse_with_fragment(r#"include "vendor/missing.humble" as missing"#, FRAGMENT)
+        }),
+    );
+    assert!(msg.starts_with("cannot read"));
+}
+
+#[test]
+fn includes_and_modules_have_distinct_namespaces() {
+    let msg = include_error(parse_with_fragment(
+        r#"
+        include "vendor/stripe_types.humble" as stripe
+        mod stripe { struct Charge { amount: u64 } }
+        "#,
+        FRAGMENT,
+    ));
+    assert_eq!(
+        msg,
+        "namespace `stripe` is used more than once (included as `stripe`)"
+    );
+
+    let msg = include_error(parse_with_fragment(
+        r#"include "vendor/stripe_types.humble" as stripe"#,
+        "mod billing { struct Charge { amount: u64 } }",
+    ));
+    assert_eq!(
+        msg,
+        "fragments must not define modules, but define `mod billing` (included as `stripe`)"
+    );
+}
+
+#[test]
+fn fragments_have_no_meta_block() {
+    let msg = include_error(parse_with_fragment(
+        r#"include "vendor/stripe_types.humble" as stripe"#,
+        r#"meta { title: "Stripe" } struct Charge { amount: u64 }"#,
+    ));
+    assert_eq!(
+        msg,
+        "fragments must not have a meta block (included as `stripe`)"
+    );
+}
@@ -0,0 +1,85 @@
+use humblegen::backend::{docs, elm};
+use humblegen::check::check;
+use humblegen::incremental::Incremental;
+use humblegen::{Artifact, CodeGenerator};
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const SPEC: &str = "struct Monster { name: str }\nenum Color { Red, Green }\n";
+const CHANGED_SPEC: &str = "struct Monster { name: str, age: i32 }\nenum Color { Red, Green }\n";
+
+fn elm_generator() -> elm::Generator {
+    elm::Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap()
+}
+
+/// Set the modification time of the file at `path` into the past, to tell if it is rewritten.
+fn backdate(path: &Path) -> SystemTime {
+    let past = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(past)
+        .unwrap();
+    past
+}
+
+fn modified(path: &Path) -> SystemTime {
+    std::fs::metadata(path).unwrap().modified().unwrap()
+}
+
+#[test]
+fn only_changed_files_are_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let generator = elm_generator();
+    let incremental = Incremental::new(&generator);
+    incremental
+        .generate(&humblegen::parse(SPEC.as_bytes()).unwrap(), dir.path())
+        .unwrap();
+    let uuid = dir.path().join("BuiltIn").join("Uuid.elm");
+    let data = dir.path().join("Data.elm");
+    let past = backdate(&uuid);
+    backdate(&data);
+
+    let spec = humblegen::parse(CHANGED_SPEC.as_bytes()).unwrap();
+    incremental.generate(&spec, dir.path()).unwrap();
+
+    assert_eq!(modified(&uuid), past);
+    assert_ne!(modified(&data), past);
+    assert_eq!(check(&generator, &spec, dir.path()).unwrap(), []);
+}
+
+#[test]
+fn missing_files_are_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = elm_generator();
+    generator.generate(&spec, dir.path()).unwrap();
+    std::fs::remove_dir_all(dir.path().join("BuiltIn")).unwrap();
+
+    // unlike the elm backend itself, which requires an empty output folder
+    Incremental::new(&generator)
+        .generate(&spec, dir.path())
+        .unwrap();
+
+    assert_eq!(check(&generator, &spec, dir.path()).unwrap(), []);
+}
+
+#[test]
+fn unchanged_single_file_output_is_not_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("docs.html");
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = docs::Generator::default();
+    let incremental = Incremental::new(&generator);
+    incremental.generate(&spec, &output).unwrap();
+    let past = backdate(&output);
+
+    incremental.generate(&spec, &output).unwrap();
+    assert_eq!(modified(&output), past);
+
+    let changed = humblegen::parse(CHANGED_SPEC.as_bytes()).unwrap();
+    incremental.generate(&changed, &output).unwrap();
+    assert_ne!(modified(&output), past);
+}
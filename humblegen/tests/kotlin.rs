@@ -0,0 +1,28 @@
+use humblegen::backend::kotlin::Generator;
+use humblegen::testing::{generate_files, SINGLE_FILE};
+use humblegen::Artifact;
+
+const SPEC: &str = include_str!("kotlin/spec.humble");
+
+/// Reference output of the kotlin backend for `spec.humble`.
+const KOTLIN: &str = include_str!("kotlin/spec.kt");
+
+#[test]
+fn kotlin() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints, "api".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(files[SINGLE_FILE], KOTLIN);
+}
+
+#[test]
+fn kotlin_types_only() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "api".to_owned()).unwrap();
+
+    let kotlin = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    assert!(kotlin.contains("data class Monster("));
+    assert!(!kotlin.contains("interface MonsterApi"));
+    assert!(!kotlin.contains("retrofit2"));
+}
@@ -0,0 +1,499 @@
+// Generated by humblegen, do not edit.
+@file:OptIn(ExperimentalSerializationApi::class)
+
+package api
+
+import kotlinx.datetime.Instant
+import kotlinx.datetime.LocalDate
+import kotlinx.serialization.ExperimentalSerializationApi
+import kotlinx.serialization.KSerializer
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+import kotlinx.serialization.SerializationException
+import kotlinx.serialization.descriptors.PrimitiveKind
+import kotlinx.serialization.descriptors.PrimitiveSerialDescriptor
+import kotlinx.serialization.descriptors.SerialDescriptor
+import kotlinx.serialization.descriptors.buildClassSerialDescriptor
+import kotlinx.serialization.encodeToString
+import kotlinx.serialization.encoding.Decoder
+import kotlinx.serialization.encoding.Encoder
+import kotlinx.serialization.json.Json
+import kotlinx.serialization.json.JsonArray
+import kotlinx.serialization.json.JsonDecoder
+import kotlinx.serialization.json.JsonElement
+import kotlinx.serialization.json.JsonEncoder
+import kotlinx.serialization.json.JsonNames
+import kotlinx.serialization.json.JsonNull
+import kotlinx.serialization.json.JsonObject
+import kotlinx.serialization.json.JsonPrimitive
+import kotlinx.serialization.json.decodeFromJsonElement
+import kotlinx.serialization.json.encodeToJsonElement
+import kotlinx.serialization.json.jsonArray
+import kotlinx.serialization.json.jsonObject
+import okhttp3.MediaType.Companion.toMediaType
+import okhttp3.MultipartBody
+import okhttp3.RequestBody.Companion.toRequestBody
+import retrofit2.http.Body
+import retrofit2.http.DELETE
+import retrofit2.http.GET
+import retrofit2.http.Header
+import retrofit2.http.PATCH
+import retrofit2.http.POST
+import retrofit2.http.PUT
+import retrofit2.http.Path
+import retrofit2.http.QueryMap
+
+/** JSON configuration matching the humblespec JSON representation, e.g. for a Retrofit converter. */
+val HumbleJson = Json { ignoreUnknownKeys = true }
+
+/** The `result[T][E]` type, encoded as `{"Ok": value}` or `{"Err": error}`. */
+@Serializable(with = HumbleResultSerializer::class)
+sealed class HumbleResult<out T, out E> {
+    data class Ok<out T>(val value: T) : HumbleResult<T, Nothing>()
+    data class Err<out E>(val error: E) : HumbleResult<Nothing, E>()
+}
+
+class HumbleResultSerializer<T, E>(
+    private val okSerializer: KSerializer<T>,
+    private val errSerializer: KSerializer<E>,
+) : KSerializer<HumbleResult<T, E>> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("HumbleResult") {
+        element("Ok", okSerializer.descriptor, isOptional = true)
+        element("Err", errSerializer.descriptor, isOptional = true)
+    }
+
+    override fun serialize(encoder: Encoder, value: HumbleResult<T, E>) {
+        val output = encoder as JsonEncoder
+        output.encodeJsonElement(
+            when (value) {
+                is HumbleResult.Ok -> JsonObject(mapOf("Ok" to output.json.encodeToJsonElement(okSerializer, value.value)))
+                is HumbleResult.Err -> JsonObject(mapOf("Err" to output.json.encodeToJsonElement(errSerializer, value.error)))
+            }
+        )
+    }
+
+    override fun deserialize(decoder: Decoder): HumbleResult<T, E> {
+        val input = decoder as JsonDecoder
+        val (tag, content) = input.decodeJsonElement().jsonObject.entries.single()
+        return when (tag) {
+            "Ok" -> HumbleResult.Ok(input.json.decodeFromJsonElement(okSerializer, content))
+            "Err" -> HumbleResult.Err(input.json.decodeFromJsonElement(errSerializer, content))
+            else -> throw SerializationException("unknown variant $tag of result")
+        }
+    }
+}
+
+/** The `uuid` type, in its hyphenated form. */
+@Serializable
+@JvmInline
+value class Uuid(val value: String) {
+    override fun toString(): String = value
+}
+
+/** The `bytes` type, encoded as base64. */
+@Serializable(with = BytesSerializer::class)
+class Bytes(val bytes: ByteArray)
+
+object BytesSerializer : KSerializer<Bytes> {
+    override val descriptor: SerialDescriptor = PrimitiveSerialDescriptor("Bytes", PrimitiveKind.STRING)
+
+    override fun serialize(encoder: Encoder, value: Bytes) =
+        encoder.encodeString(java.util.Base64.getEncoder().encodeToString(value.bytes))
+
+    override fun deserialize(decoder: Decoder): Bytes =
+        Bytes(java.util.Base64.getDecoder().decode(decoder.decodeString()))
+}
+
+/** Decode the single `tag: content` entry of an externally tagged enum variant. */
+private fun variantEntry(element: JsonElement, name: String): Pair<String, JsonElement> =
+    (element as? JsonObject)?.entries?.singleOrNull()?.toPair()
+        ?: throw SerializationException("expected a variant of $name")
+
+/** Decode the tag at `key` of an internally or adjacently tagged enum variant. */
+private fun variantTag(element: JsonElement, key: String, name: String): String =
+    ((element as? JsonObject)?.get(key) as? JsonPrimitive)?.takeIf { it.isString }?.content
+        ?: throw SerializationException("expected a variant of $name")
+
+/**
+ * A monster.
+ */
+@Serializable
+data class Monster(
+    /**
+     * Unique name.
+     */
+    @SerialName("name") val name: String,
+    @SerialName("age") val age: Int,
+    @SerialName("born") val born: Instant,
+    @SerialName("nickname") @JsonNames("nick") val nickname: String?,
+    @SerialName("tags") val tags: List<String>,
+    @SerialName("stats") val stats: Map<String, Double>,
+    @SerialName("position") val position: TupleOfF64AndF64,
+    @SerialName("id") val id: Uuid,
+    @SerialName("object") val `object`: Boolean,
+    @SerialName("abilities") val abilities: Abilities,
+)
+
+@Serializable
+data class MonsterQuery(
+    @SerialName("name") val name: String?,
+    @SerialName("maxResults") val limit: Long,
+    @SerialName("tag") val tag: String?,
+)
+
+@Serializable
+data class Portrait(
+    @SerialName("caption") val caption: String?,
+    @SerialName("image") val image: Bytes,
+)
+
+@Serializable
+enum class Color {
+    Red,
+    /**
+     * Like the sky.
+     */
+    @SerialName("light-blue") LightBlue,
+}
+
+/**
+ * Priorities as understood by embedded devices.
+ */
+@Serializable(with = PrioritySerializer::class)
+enum class Priority(val discriminant: Long) {
+    Low(1),
+    /**
+     * The default.
+     */
+    Normal(2),
+    High(10),
+    Debug(-1),
+}
+
+object PrioritySerializer : KSerializer<Priority> {
+    override val descriptor: SerialDescriptor = PrimitiveSerialDescriptor("Priority", PrimitiveKind.LONG)
+
+    override fun serialize(encoder: Encoder, value: Priority) =
+        encoder.encodeLong(value.discriminant)
+
+    override fun deserialize(decoder: Decoder): Priority {
+        val discriminant = decoder.decodeLong()
+        return Priority.values().firstOrNull { it.discriminant == discriminant }
+            ?: throw SerializationException("invalid discriminant $discriminant of Priority")
+    }
+}
+
+@Serializable(with = ShapeSerializer::class)
+sealed class Shape {
+    object Point : Shape()
+
+    data class Circle(val value: Double) : Shape()
+
+    @Serializable
+    data class Rect(
+        @SerialName("w") val width: Double,
+        @SerialName("h") val height: Double,
+    ) : Shape()
+
+    data class Line(val item0: TupleOfF64AndF64, val item1: TupleOfF64AndF64) : Shape()
+}
+
+object ShapeSerializer : KSerializer<Shape> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("Shape")
+
+    override fun serialize(encoder: Encoder, value: Shape) {
+        val json = (encoder as JsonEncoder).json
+        encoder.encodeJsonElement(
+            when (value) {
+                Shape.Point -> JsonPrimitive("Point")
+                is Shape.Circle -> JsonObject(mapOf("circle" to json.encodeToJsonElement<Double>(value.value)))
+                is Shape.Rect -> JsonObject(mapOf("Rect" to json.encodeToJsonElement(Shape.Rect.serializer(), value)))
+                is Shape.Line -> JsonObject(mapOf("Line" to JsonArray(listOf(json.encodeToJsonElement<TupleOfF64AndF64>(value.item0), json.encodeToJsonElement<TupleOfF64AndF64>(value.item1)))))
+            }
+        )
+    }
+
+    override fun deserialize(decoder: Decoder): Shape {
+        val json = (decoder as JsonDecoder).json
+        val element = decoder.decodeJsonElement()
+        if (element is JsonPrimitive) {
+            return when (element.content) {
+                "Point" -> Shape.Point
+                else -> throw SerializationException("unknown variant ${element.content} of Shape")
+            }
+        }
+        val (tag, content) = variantEntry(element, "Shape")
+        return when (tag) {
+            "circle" -> Shape.Circle(json.decodeFromJsonElement<Double>(content))
+            "Rect" -> json.decodeFromJsonElement(Shape.Rect.serializer(), content)
+            "Line" -> content.jsonArray.let { items -> Shape.Line(json.decodeFromJsonElement<TupleOfF64AndF64>(items[0]), json.decodeFromJsonElement<TupleOfF64AndF64>(items[1])) }
+            else -> throw SerializationException("unknown variant $tag of Shape")
+        }
+    }
+}
+
+@Serializable(with = MonsterErrorSerializer::class)
+sealed class MonsterError {
+    object NotFound : MonsterError()
+
+    data class Invalid(val value: String) : MonsterError()
+}
+
+object MonsterErrorSerializer : KSerializer<MonsterError> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("MonsterError")
+
+    override fun serialize(encoder: Encoder, value: MonsterError) {
+        val json = (encoder as JsonEncoder).json
+        encoder.encodeJsonElement(
+            when (value) {
+                MonsterError.NotFound -> JsonPrimitive("NotFound")
+                is MonsterError.Invalid -> JsonObject(mapOf("Invalid" to json.encodeToJsonElement<String>(value.value)))
+            }
+        )
+    }
+
+    override fun deserialize(decoder: Decoder): MonsterError {
+        val json = (decoder as JsonDecoder).json
+        val element = decoder.decodeJsonElement()
+        if (element is JsonPrimitive) {
+            return when (element.content) {
+                "NotFound" -> MonsterError.NotFound
+                else -> throw SerializationException("unknown variant ${element.content} of MonsterError")
+            }
+        }
+        val (tag, content) = variantEntry(element, "MonsterError")
+        return when (tag) {
+            "Invalid" -> MonsterError.Invalid(json.decodeFromJsonElement<String>(content))
+            else -> throw SerializationException("unknown variant $tag of MonsterError")
+        }
+    }
+}
+
+/**
+ * Coordinates on the map.
+ */
+@Serializable(with = CoordinatesSerializer::class)
+data class Coordinates(val item0: Double, val item1: Double)
+
+object CoordinatesSerializer : KSerializer<Coordinates> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("Coordinates")
+
+    override fun serialize(encoder: Encoder, value: Coordinates) {
+        val json = (encoder as JsonEncoder).json
+        encoder.encodeJsonElement(JsonArray(listOf(json.encodeToJsonElement<Double>(value.item0), json.encodeToJsonElement<Double>(value.item1))))
+    }
+
+    override fun deserialize(decoder: Decoder): Coordinates {
+        val json = (decoder as JsonDecoder).json
+        val items = decoder.decodeJsonElement().jsonArray
+        return Coordinates(json.decodeFromJsonElement<Double>(items[0]), json.decodeFromJsonElement<Double>(items[1]))
+    }
+}
+
+@Serializable
+@JvmInline
+value class MonsterId(val value: Long)
+
+@Serializable
+data class Lair(
+    @SerialName("owner") val owner: MonsterId,
+    @SerialName("location") val location: Coordinates,
+)
+
+/**
+ * Events of the legacy API, tagged by a `type` field.
+ */
+@Serializable(with = EventSerializer::class)
+sealed class Event {
+    object Started : Event()
+
+    @Serializable
+    data class Moved(
+        @SerialName("x") val x: Double,
+        @SerialName("y") val y: Double,
+    ) : Event()
+}
+
+object EventSerializer : KSerializer<Event> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("Event")
+
+    override fun serialize(encoder: Encoder, value: Event) {
+        val json = (encoder as JsonEncoder).json
+        encoder.encodeJsonElement(
+            when (value) {
+                Event.Started -> JsonObject(mapOf("type" to JsonPrimitive("Started")))
+                is Event.Moved -> JsonObject(mapOf("type" to JsonPrimitive("Moved")) + json.encodeToJsonElement(Event.Moved.serializer(), value).jsonObject)
+            }
+        )
+    }
+
+    override fun deserialize(decoder: Decoder): Event {
+        val json = (decoder as JsonDecoder).json
+        val element = decoder.decodeJsonElement()
+        val tag = variantTag(element, "type", "Event")
+        val content = JsonObject(element.jsonObject - "type")
+        return when (tag) {
+            "Started" -> Event.Started
+            "Moved" -> json.decodeFromJsonElement(Event.Moved.serializer(), content)
+            else -> throw SerializationException("unknown variant $tag of Event")
+        }
+    }
+}
+
+@Serializable(with = CommandSerializer::class)
+sealed class Command {
+    object Stop : Command()
+
+    data class Say(val value: String) : Command()
+
+    data class Go(val item0: Double, val item1: Double) : Command()
+}
+
+object CommandSerializer : KSerializer<Command> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("Command")
+
+    override fun serialize(encoder: Encoder, value: Command) {
+        val json = (encoder as JsonEncoder).json
+        encoder.encodeJsonElement(
+            when (value) {
+                Command.Stop -> JsonObject(mapOf("t" to JsonPrimitive("Stop")))
+                is Command.Say -> JsonObject(mapOf("t" to JsonPrimitive("Say"), "c" to json.encodeToJsonElement<String>(value.value)))
+                is Command.Go -> JsonObject(mapOf("t" to JsonPrimitive("Go"), "c" to JsonArray(listOf(json.encodeToJsonElement<Double>(value.item0), json.encodeToJsonElement<Double>(value.item1)))))
+            }
+        )
+    }
+
+    override fun deserialize(decoder: Decoder): Command {
+        val json = (decoder as JsonDecoder).json
+        val element = decoder.decodeJsonElement()
+        val tag = variantTag(element, "t", "Command")
+        val content = element.jsonObject["c"] ?: JsonNull
+        return when (tag) {
+            "Stop" -> Command.Stop
+            "Say" -> Command.Say(json.decodeFromJsonElement<String>(content))
+            "Go" -> content.jsonArray.let { items -> Command.Go(json.decodeFromJsonElement<Double>(items[0]), json.decodeFromJsonElement<Double>(items[1])) }
+            else -> throw SerializationException("unknown variant $tag of Command")
+        }
+    }
+}
+
+@Serializable(with = KeySerializer::class)
+sealed class Key {
+    data class Number(val value: Int) : Key()
+
+    data class Name(val value: String) : Key()
+
+    object Missing : Key()
+}
+
+object KeySerializer : KSerializer<Key> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("Key")
+
+    override fun serialize(encoder: Encoder, value: Key) {
+        val json = (encoder as JsonEncoder).json
+        encoder.encodeJsonElement(
+            when (value) {
+                is Key.Number -> json.encodeToJsonElement<Int>(value.value)
+                is Key.Name -> json.encodeToJsonElement<String>(value.value)
+                Key.Missing -> JsonNull
+            }
+        )
+    }
+
+    override fun deserialize(decoder: Decoder): Key {
+        val json = (decoder as JsonDecoder).json
+        val element = decoder.decodeJsonElement()
+        val content = element
+        runCatching { return Key.Number(json.decodeFromJsonElement<Int>(content)) }
+        runCatching { return Key.Name(json.decodeFromJsonElement<String>(content)) }
+        if (element is JsonNull) return Key.Missing
+        throw SerializationException("no variant of Key matches")
+    }
+}
+
+/**
+ * A partial update of a monster, whose absent fields are unchanged.
+ */
+@Serializable
+data class MonsterPatch(
+    @SerialName("name") val name: String? = null,
+    @SerialName("lair") val lair: String? = null,
+)
+
+@Serializable
+enum class AbilitiesFlag {
+    Fly,
+    Swim,
+    /**
+     * Breathes fire.
+     */
+    @SerialName("fire") BreatheFire,
+}
+
+/**
+ * Things a monster can do.
+ */
+typealias Abilities = Set<AbilitiesFlag>
+
+fun MonsterQuery.toQueryMap(): Map<String, String> = buildMap {
+    name?.let { put("name", it.toString()) }
+    put("maxResults", limit.toString())
+    tag?.let { put("tag", it.toString()) }
+}
+
+fun Portrait.toMultipartBody(): MultipartBody = MultipartBody.Builder().setType(MultipartBody.FORM).apply {
+    caption?.let { addFormDataPart("caption", it) }
+    addFormDataPart("image", "image", image.bytes.toRequestBody("application/octet-stream".toMediaType()))
+}.build()
+
+/**
+ * Manages monsters.
+ */
+interface MonsterApi {
+    companion object {
+        /** The URL the service is mounted at by default, declared in the `meta` block of the spec. */
+        const val BASE_URL = "https://api.example.com/v1"
+    }
+
+    /**
+     * Lists monsters.
+     */
+    @GET("monsters")
+    suspend fun getMonsters(@QueryMap query: Map<String, String>): List<Monster>
+
+    @GET("monsters/{id}")
+    suspend fun getMonstersId(@Path("id") id: Int, @Header("X-Request-Id") headerXRequestId: String?): HumbleResult<Monster, MonsterError>
+
+    @POST("monsters")
+    suspend fun postMonsters(@Body body: Monster, @Header("Idempotency-Key") idempotencyKey: String = java.util.UUID.randomUUID().toString()): Int
+
+    @POST("monsters/{id}/portrait")
+    suspend fun postMonstersIdPortrait(@Path("id") id: Int, @Body body: MultipartBody): Unit
+
+    @DELETE("monsters/{id}")
+    suspend fun deleteMonstersId(@Path("id") id: Int): Unit
+
+    @GET("files/{path}")
+    suspend fun getFilesPath(@Path("path", encoded = true) path: String): Bytes
+
+    @GET("monsters/{id}/portrait")
+    suspend fun getMonstersIdPortrait(@Path("id") id: Int): retrofit2.Response<Bytes>
+}
+
+@Serializable(with = TupleOfF64AndF64Serializer::class)
+data class TupleOfF64AndF64(val item0: Double, val item1: Double)
+
+object TupleOfF64AndF64Serializer : KSerializer<TupleOfF64AndF64> {
+    override val descriptor: SerialDescriptor = buildClassSerialDescriptor("TupleOfF64AndF64")
+
+    override fun serialize(encoder: Encoder, value: TupleOfF64AndF64) {
+        val json = (encoder as JsonEncoder).json
+        encoder.encodeJsonElement(JsonArray(listOf(json.encodeToJsonElement<Double>(value.item0), json.encodeToJsonElement<Double>(value.item1))))
+    }
+
+    override fun deserialize(decoder: Decoder): TupleOfF64AndF64 {
+        val json = (decoder as JsonDecoder).json
+        val items = decoder.decodeJsonElement().jsonArray
+        return TupleOfF64AndF64(json.decodeFromJsonElement<Double>(items[0]), json.decodeFromJsonElement<Double>(items[1]))
+    }
+}
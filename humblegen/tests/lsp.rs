@@ -0,0 +1,237 @@
+use serde_json::{json, Value};
+
+const URI: &str = "file:///monsters/spec.humble";
+
+/// Run the language server on `requests`, returning the messages it sent.
+fn serve(requests: &[Value]) -> Vec<Value> {
+    let mut input = vec![];
+    for (id, request) in requests.iter().enumerate() {
+        let mut request = request.clone();
+        request["jsonrpc"] = json!("2.0");
+        if request.get("id").is_some() {
+            request["id"] = json!(id);
+        }
+        let content = request.to_string();
+        input.extend(format!("Content-Length: {}\r\n\r\n{}", content.len(), content).bytes());
+    }
+    let mut output = vec![];
+    humblegen::lsp::run(input.as_slice(), &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    output
+        .split("Content-Length: ")
+        .filter(|message| !message.is_empty())
+        .map(|message| serde_json::from_str(message.split_once("\r\n\r\n").unwrap().1).unwrap())
+        .collect()
+}
+
+fn open(text: &str) -> Value {
+    json!({
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": URI, "languageId": "humble", "version": 1, "text": text } },
+    })
+}
+
+fn request(method: &str, line: u32, character: u32) -> Value {
+    json!({
+        "id": null,
+        "method": method,
+        "params": {
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+        },
+    })
+}
+
+/// The response to the request with `id`.
+fn response(messages: &[Value], id: usize) -> &Value {
+    &messages.iter().find(|m| m["id"] == json!(id)).unwrap()["result"]
+}
+
+fn diagnostics(messages: &[Value]) -> Vec<&Value> {
+    messages
+        .iter()
+        .filter(|m| m["method"] == "textDocument/publishDiagnostics")
+        .map(|m| &m["params"]["diagnostics"])
+        .collect()
+}
+
+#[test]
+fn diagnostics_locate_parse_errors_and_invalid_definitions() {
+    let messages = serve(&[
+        open("struct Monster {\n    name: str\n    hp: i32,\n}\n"),
+        open("struct Monster { name: str }\n\nstruct Lair { .. Monster, ..Hoard }\n"),
+        open("struct Monster { name: str }\n"),
+    ]);
+    let diagnostics = diagnostics(&messages);
+
+    assert_eq!(
+        diagnostics[0][0]["range"]["start"],
+        json!({ "line": 1, "character": 10 })
+    );
+    assert_eq!(
+        diagnostics[1][0]["range"],
+        json!({ "start": { "line": 2, "character": 7 }, "end": { "line": 2, "character": 11 } })
+    );
+    assert_eq!(
+        diagnostics[1][0]["message"],
+        "invalid definition `Lair`: embeds unknown struct `Hoard`"
+    );
+    assert_eq!(diagnostics[2], &json!([]));
+}
+
+#[test]
+fn diagnostics_of_every_syntax_error() {
+    let messages = serve(&[open(
+        "struct Monster {\n    name: str\n    hp: i32,\n}\n\nenum Color { Red Green }\n",
+    )]);
+    let diagnostics = diagnostics(&messages);
+
+    let starts: Vec<_> = diagnostics[0]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| &d["range"]["start"])
+        .collect();
+    assert_eq!(
+        starts,
+        [
+            &json!({ "line": 1, "character": 10 }),
+            &json!({ "line": 5, "character": 17 }),
+        ]
+    );
+}
+
+#[test]
+fn diagnostics_of_invalid_annotations() {
+    let messages = serve(&[open(
+        "@bogus\nservice MonsterApi {\n    @status(abc) GET /monsters -> str,\n}\n\nflags Permissions {}\n",
+    )]);
+    let diagnostics = diagnostics(&messages);
+
+    let ranges: Vec<_> = diagnostics[0]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| (&d["range"]["start"], d["message"].as_str().unwrap()))
+        .collect();
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0].0, &json!({ "line": 0, "character": 1 }));
+    assert!(ranges[0]
+        .1
+        .starts_with("annotation `@bogus` is not allowed here"));
+    assert_eq!(ranges[1].0, &json!({ "line": 5, "character": 6 }));
+    assert_eq!(
+        ranges[1].1,
+        "flags `Permissions` must have at least one flag"
+    );
+}
+
+#[test]
+fn diagnostics_of_unknown_types() {
+    let messages = serve(&[open(
+        "struct Monster { name: str }\nstruct Lair { monsters: list[Monstr] }\n",
+    )]);
+    let diagnostics = diagnostics(&messages);
+
+    assert_eq!(
+        diagnostics[0][0]["range"]["start"],
+        json!({ "line": 1, "character": 29 })
+    );
+    assert_eq!(
+        diagnostics[0][0]["message"],
+        "unknown type `Monstr`, did you mean `Monster`?"
+    );
+}
+
+#[test]
+fn definitions_hovers_and_completions() {
+    let spec = "/// A monster.\n/// Dangerous.\nstruct Monster { name: str, .. Stats }\n\
+                struct Stats { hp: i32 }\n\
+                enum Lair { Cave, Tower }\n\
+                service MonsterApi { GET /monsters/{id: i32} -> list[Monster] }\n";
+    let messages = serve(&[
+        json!({ "id": null, "method": "initialize", "params": { "capabilities": {} } }),
+        open(spec),
+        // the embed `Stats`
+        request("textDocument/definition", 2, 33),
+        // `Monster` in `list[Monster]`
+        request("textDocument/hover", 5, 55),
+        // a built-in type
+        request("textDocument/definition", 2, 24),
+        // types are completed while the spec does not parse
+        json!({
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": URI, "version": 2 },
+                "contentChanges": [{ "text": format!("{}struct Hoard {{ gold: ", spec) }],
+            },
+        }),
+        request("textDocument/completion", 6, 21),
+        json!({ "id": null, "method": "shutdown" }),
+        json!({ "method": "exit" }),
+    ]);
+
+    assert_eq!(
+        response(&messages, 0)["capabilities"]["hoverProvider"],
+        true
+    );
+    assert_eq!(
+        response(&messages, 2),
+        &json!({
+            "uri": URI,
+            "range": { "start": { "line": 3, "character": 7 }, "end": { "line": 3, "character": 12 } },
+        })
+    );
+    assert_eq!(
+        response(&messages, 3)["contents"]["value"],
+        "```humble\nstruct Monster\n```\n\nA monster.\nDangerous."
+    );
+    assert_eq!(response(&messages, 4), &Value::Null);
+    let completions: Vec<_> = response(&messages, 6)
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap())
+        .collect();
+    assert!(completions.contains(&"str"));
+    assert!(completions.ends_with(&["Monster", "Stats", "Lair"]));
+    assert_eq!(response(&messages, 7), &Value::Null);
+}
+
+#[test]
+fn flags_and_literal_unions_are_definitions() {
+    let spec = "/// What a monster may do.\nflags Abilities { Fly, Swim }\n\
+                type Mood = \"calm\" | \"angry\";\n\
+                struct Monster { abilities: Abilities, mood: Mood }\n";
+    let messages = serve(&[
+        json!({ "id": null, "method": "initialize", "params": { "capabilities": {} } }),
+        open(spec),
+        // `Abilities` in `abilities: Abilities`
+        request("textDocument/hover", 3, 30),
+        // `Mood` in `mood: Mood`
+        request("textDocument/definition", 3, 46),
+        request("textDocument/completion", 3, 0),
+        json!({ "id": null, "method": "shutdown" }),
+        json!({ "method": "exit" }),
+    ]);
+
+    assert_eq!(
+        response(&messages, 2)["contents"]["value"],
+        "```humble\nflags Abilities\n```\n\nWhat a monster may do."
+    );
+    assert_eq!(
+        response(&messages, 3),
+        &json!({
+            "uri": URI,
+            "range": { "start": { "line": 2, "character": 5 }, "end": { "line": 2, "character": 9 } },
+        })
+    );
+    let completions: Vec<_> = response(&messages, 4)
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap())
+        .collect();
+    assert!(completions.ends_with(&["Abilities", "Mood", "Monster"]));
+}
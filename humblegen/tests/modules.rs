@@ -0,0 +1,98 @@
+use humblegen::ast::SpecItem;
+use humblegen::LibError;
+
+const SPEC: &str = r#"
+mod billing {
+    struct Invoice {
+        lines: list[LineItem],
+        customer: Customer,
+    }
+
+    struct LineItem {
+        currency: shipping.Currency,
+    }
+}
+
+mod shipping {
+    enum Currency { Eur, Usd }
+}
+
+struct Customer {
+    invoices: list[billing.Invoice],
+}
+"#;
+
+fn invalid_definition(spec: &str) -> (String, String) {
+    match humblegen::parse(spec.as_bytes()).unwrap_err() {
+        LibError::InvalidDefinition {
+            definition,
+            message,
+        } => (definition, message),
+        other => panic!("expected invalid definition, got {:?}", other),
+    }
+}
+
+#[test]
+fn module_definitions_are_prefixed_with_their_module() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+
+    let names: Vec<_> = spec
+        .iter()
+        .map(|item| (item.name(), item.module()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("BillingInvoice", Some(("billing", "Invoice"))),
+            ("BillingLineItem", Some(("billing", "LineItem"))),
+            ("ShippingCurrency", Some(("shipping", "Currency"))),
+            ("Customer", None),
+        ]
+    );
+
+    let index = spec.index();
+    let field_types = |name: &str| -> Vec<String> {
+        index
+            .struct_def(name)
+            .unwrap()
+            .fields
+            .iter()
+            .map(|field| field.pair.type_ident.to_string())
+            .collect()
+    };
+    // unqualified names within a module refer to the module, otherwise to the top level
+    assert_eq!(
+        field_types("BillingInvoice"),
+        vec!["list[BillingLineItem]", "Customer"]
+    );
+    assert_eq!(field_types("BillingLineItem"), vec!["ShippingCurrency"]);
+    assert_eq!(field_types("Customer"), vec!["list[BillingInvoice]"]);
+    assert!(matches!(
+        index.get("ShippingCurrency"),
+        Some(SpecItem::EnumDef(_))
+    ));
+}
+
+#[test]
+fn qualified_names_must_be_defined_by_the_module() {
+    assert_eq!(
+        invalid_definition(
+            "mod billing { struct Invoice {} }\nstruct Customer { invoice: billing.Bill }"
+        ),
+        (
+            "Customer".to_owned(),
+            "module `billing` does not define `Bill`".to_owned()
+        )
+    );
+}
+
+#[test]
+fn generated_names_must_be_unique() {
+    assert_eq!(
+        invalid_definition("struct BillingInvoice {}\nmod billing { struct Invoice {} }"),
+        (
+            "billing.Invoice".to_owned(),
+            "`BillingInvoice` is named like another definition in the generated code".to_owned()
+        )
+    );
+}
@@ -0,0 +1,83 @@
+use humblegen::plugin::{Plugin, PluginInput, PluginRequest, PROTOCOL_VERSION};
+use humblegen::testing::{self, SINGLE_FILE};
+use humblegen::{Artifact, LibError};
+
+/// A plugin saving its request to `request` and answering with `response`.
+fn plugin(request: &std::path::Path, response: &str) -> Plugin {
+    let script = format!("cat > '{}'; printf '%s' '{}'", request.display(), response);
+    Plugin::new(
+        "sh".to_owned(),
+        vec!["-c".to_owned(), script],
+        Artifact::ClientEndpoints,
+    )
+}
+
+#[test]
+fn plugins_receive_the_spec_and_write_their_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let request = dir.path().join("request.json");
+    let spec = humblegen::parse("struct Monster { name: str }".as_bytes()).unwrap();
+    let plugin = plugin(
+        &request,
+        r#"{"files": [{"path": "Api.cs", "contents": "namespace Api;\n"},
+                      {"path": "Models/Monster.cs", "contents": "class Monster {}\n"}]}"#,
+    );
+
+    let files = testing::generate_files(&plugin, &spec).unwrap();
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        ["Api.cs", "Models/Monster.cs"]
+    );
+    assert_eq!(files["Models/Monster.cs"], "class Monster {}\n");
+
+    let request: PluginRequest =
+        serde_json::from_str(&std::fs::read_to_string(&request).unwrap()).unwrap();
+    assert_eq!(request.protocol_version, PROTOCOL_VERSION);
+    assert_eq!(request.artifact, "ClientEndpoints");
+    match request.input {
+        PluginInput::Spec(spec) => assert_eq!(spec.0[0].name(), "Monster"),
+        other => panic!("expected spec, got {:?}", other),
+    }
+}
+
+#[test]
+fn plugins_may_generate_a_single_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = humblegen::parse("struct Monster { name: str }".as_bytes()).unwrap();
+    let plugin = plugin(
+        &dir.path().join("request.json"),
+        r#"{"files": [{"path": "", "contents": "class Monster {}\n"}]}"#,
+    );
+
+    let files = testing::generate_files(&plugin, &spec).unwrap();
+    assert_eq!(files[SINGLE_FILE], "class Monster {}\n");
+}
+
+#[test]
+fn plugins_must_not_write_outside_of_the_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = humblegen::parse("struct Monster { name: str }".as_bytes()).unwrap();
+    let plugin = plugin(
+        &dir.path().join("request.json"),
+        r#"{"files": [{"path": "../Monster.cs", "contents": ""}]}"#,
+    );
+
+    let err = testing::generate_files(&plugin, &spec).unwrap_err();
+    assert!(matches!(err, LibError::PluginError(_)));
+    assert!(err
+        .to_string()
+        .contains("invalid file path `../Monster.cs`"));
+}
+
+#[test]
+fn plugins_fail_on_non_zero_exit_and_invalid_responses() {
+    let spec = humblegen::parse("struct Monster { name: str }".as_bytes()).unwrap();
+
+    let failing = Plugin::from_command_line("false", Artifact::TypesOnly);
+    let err = testing::generate_files(&failing, &spec).unwrap_err();
+    assert!(matches!(err, LibError::PluginError(_)));
+
+    let silent = Plugin::from_command_line("true", Artifact::TypesOnly);
+    let err = testing::generate_files(&silent, &spec).unwrap_err();
+    assert!(err.to_string().contains("invalid plugin response"));
+}
@@ -0,0 +1,45 @@
+use humblegen::codegen::printer::{Printer, Separators};
+
+fn items(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[test]
+fn delimited_lists_wrap_when_exceeding_the_line_width() {
+    let mut p = Printer::new().with_max_width(30);
+    p.line("short =");
+    p.with_indent(|p| {
+        p.newline();
+        p.delimited(("[", "]"), &items(&["a", "b"]), ",", Separators::Leading);
+    });
+    p.line("long = ");
+    let long = items(&["monsters", "component1_id", "portrait"]);
+    p.delimited(("[", "]"), &long, ",", Separators::Leading);
+    assert_eq!(
+        p.finish(),
+        "short =\n    [ a, b ]\nlong =\n    [ monsters\n    , component1_id\n    , portrait\n    ]\n"
+    );
+}
+
+#[test]
+fn trailing_separators_indent_the_items() {
+    let mut p = Printer::new().with_indent_width(2).with_max_width(10);
+    p.write("call");
+    p.delimited(
+        ("(", ")"),
+        &items(&["first", "second"]),
+        ",",
+        Separators::Trailing,
+    );
+    assert_eq!(p.finish(), "call\n  (\n    first,\n    second\n  )\n");
+}
+
+#[test]
+fn line_breaks_are_normalized() {
+    let mut p = Printer::new();
+    p.blank_lines(2);
+    p.write("a  \r\nb\n\n\n\n");
+    p.blank_lines(1);
+    p.with_indent(|p| p.line("c\n\nd"));
+    assert_eq!(p.finish(), "a\nb\n\n    c\n\n    d\n");
+}
@@ -0,0 +1,83 @@
+use humblegen::progress::{self, Progress};
+use humblegen::Artifact;
+use std::path::PathBuf;
+
+/// The `Progress` events with owned paths.
+#[derive(Debug, PartialEq)]
+enum Event {
+    Parsed(PathBuf, usize),
+    Generating(String),
+    FileWritten(String, PathBuf),
+    Generated(String, usize),
+}
+
+fn record(events: &mut Vec<Event>) -> impl FnMut(Progress) + '_ {
+    move |event| {
+        events.push(match event {
+            Progress::Parsed { path, items } => Event::Parsed(path.to_owned(), items),
+            Progress::Generating { backend } => Event::Generating(backend.to_owned()),
+            Progress::FileWritten { backend, path } => {
+                Event::FileWritten(backend.to_owned(), path.to_owned())
+            }
+            Progress::Generated { backend, files } => Event::Generated(backend.to_owned(), files),
+        })
+    }
+}
+
+#[test]
+fn reports_parsed_items_and_written_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("spec.humble");
+    std::fs::write(&input, "struct Monster { name: str }\nenum Color { Red }").unwrap();
+    let output = dir.path().join("elm");
+    std::fs::create_dir(&output).unwrap();
+
+    let mut events = vec![];
+    let spec = progress::parse_file_with_progress(&input, &mut record(&mut events)).unwrap();
+    let generator =
+        humblegen::backend::elm::Generator::new(Artifact::TypesOnly, "Api".to_owned()).unwrap();
+    progress::generate_with_progress(&generator, "elm", &spec, &output, &mut record(&mut events))
+        .unwrap();
+
+    assert_eq!(events[0], Event::Parsed(input, 2));
+    assert_eq!(events[1], Event::Generating("elm".to_owned()));
+    assert!(events.contains(&Event::FileWritten(
+        "elm".to_owned(),
+        output.join("Data.elm")
+    )));
+    let written = events.len() - 3;
+    assert!(written > 0);
+    assert_eq!(
+        events.last(),
+        Some(&Event::Generated("elm".to_owned(), written))
+    );
+}
+
+#[test]
+fn reports_single_file_outputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("api.proto");
+    let spec = humblegen::parse("struct Monster { name: str }".as_bytes()).unwrap();
+    let generator =
+        humblegen::backend::protobuf::Generator::new(Artifact::TypesOnly, "api".to_owned())
+            .unwrap();
+
+    let mut events = vec![];
+    progress::generate_with_progress(
+        &generator,
+        "protobuf",
+        &spec,
+        &output,
+        &mut record(&mut events),
+    )
+    .unwrap();
+
+    assert_eq!(
+        events,
+        vec![
+            Event::Generating("protobuf".to_owned()),
+            Event::FileWritten("protobuf".to_owned(), output),
+            Event::Generated("protobuf".to_owned(), 1),
+        ]
+    );
+}
@@ -0,0 +1,28 @@
+use humblegen::backend::protobuf::Generator;
+use humblegen::testing::{generate_files, SINGLE_FILE};
+use humblegen::Artifact;
+
+const SPEC: &str = include_str!("protobuf/spec.humble");
+
+/// Reference output of the protobuf backend for `spec.humble`.
+const PROTO: &str = include_str!("protobuf/spec.proto");
+
+#[test]
+fn protobuf() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ServerEndpoints, "monsters.v1".to_owned()).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(files[SINGLE_FILE], PROTO);
+}
+
+#[test]
+fn protobuf_types_only() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly, "monsters.v1".to_owned()).unwrap();
+
+    let proto = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    assert!(proto.contains("message Monster {"));
+    assert!(!proto.contains("service MonsterApi"));
+    assert!(!proto.contains("Request"));
+}
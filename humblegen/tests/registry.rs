@@ -0,0 +1,83 @@
+#![cfg(feature = "registry")]
+
+use humblegen::registry::{publish, pull};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+
+/// A registry answering a `PUT` and then a `GET` with the uploaded spec version.
+fn registry() -> (String, std::thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let mut stored = vec![];
+        for stream in listener.incoming().take(2) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            if request_line.starts_with("PUT /specs/orders/versions/1.0.0 ") {
+                stored = body;
+                body = vec![];
+            } else {
+                assert!(request_line.starts_with("GET /specs/orders/versions/1.0.0 "));
+                body = stored.clone();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+        }
+    });
+    (url, handle)
+}
+
+#[test]
+fn published_specs_have_their_includes_resolved() {
+    let (url, handle) = registry();
+    publish(&url, "orders", "1.0.0", "./tests/rust/includes/spec.humble").unwrap();
+
+    let spec_version = pull(&url, "orders", "1.0.0").unwrap();
+    handle.join().unwrap();
+    assert_eq!(spec_version.humblegen_version, env!("CARGO_PKG_VERSION"));
+    let spec = spec_version.spec().unwrap();
+    let expected = humblegen::parse_file("./tests/rust/includes/spec.humble").unwrap();
+    assert_eq!(
+        humblegen::ast_json::dump_ast(&spec),
+        humblegen::ast_json::dump_ast(&expected)
+    );
+}
+
+#[test]
+fn invalid_specs_are_not_published() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("spec.humble");
+    std::fs::write(
+        &path,
+        "include \"missing.humble\" as missing\nstruct Order { id: i32 }",
+    )
+    .unwrap();
+    let err = publish("http://127.0.0.1:1", "orders", "1.0.0", &path).unwrap_err();
+    assert!(
+        matches!(err, humblegen::LibError::IncludeError(_)),
+        "{}",
+        err
+    );
+}
@@ -2,6 +2,7 @@ use anyhow::Context;
 
 use std::path::PathBuf;
 
+use humblegen::backend::rust::{Generator, HandlerTraitStyle};
 use humblegen::CodeGenerator;
 
 #[derive(Debug)]
@@ -14,12 +15,8 @@ struct RustTestCase {
 
 impl RustTestCase {
     fn run(&self) {
-        let spec_file = std::fs::File::open(&self.humble_spec).expect("open humble spec file");
-        let spec = humblegen::parse(spec_file).expect("parse humble spec file");
-        let codegen =
-            humblegen::backend::rust::Generator::new(humblegen::Artifact::ServerEndpoints)
-                .expect("failed to init humblegen rust backend");
-        codegen
+        let spec = humblegen::parse_file(&self.humble_spec).expect("parse humble spec file");
+        self.generator()
             .generate(&spec, &self.humble_rust_out)
             .expect("humblegen rust backend failed");
 
@@ -29,6 +26,52 @@ impl RustTestCase {
         drop(t);
     }
 
+    /// The generator used for this test case.
+    ///
+    /// Test cases exercising non-default generator options are configured here by name.
+    fn generator(&self) -> Generator {
+        let generator = Generator::new(humblegen::Artifact::ServerEndpoints)
+            .expect("failed to init humblegen rust backend");
+        match self.name.as_str() {
+            "benchmarks" => Generator::new(humblegen::Artifact::Benchmarks)
+                .expect("failed to init humblegen rust backend"),
+            "service-client"
+            | "service-client-cbor"
+            | "service-client-compression"
+            | "service-client-meta"
+            | "service-client-msgpack"
+            | "service-client-pagination"
+            | "service-client-progress" => Generator::new(humblegen::Artifact::ClientEndpoints)
+                .expect("failed to init humblegen rust backend"),
+            "service-client-environments" => Generator::new(humblegen::Artifact::ClientEndpoints)
+                .expect("failed to init humblegen rust backend")
+                .with_environments(vec![
+                    humblegen::Environment::new("prod", "https://api.example.com/v1").unwrap(),
+                    humblegen::Environment::new("local_dev", "http://localhost:3000/api").unwrap(),
+                ]),
+            "client-and-server" => Generator::new(humblegen::Artifact::ClientAndServer)
+                .expect("failed to init humblegen rust backend"),
+            "arbitrary" => generator.with_arbitrary_impls(true),
+            "wire-casing" => generator.with_wire_casing(humblegen::WireCasing::CamelCase),
+            "map-entries" => {
+                generator.with_map_representation(humblegen::MapRepresentation::Entries)
+            }
+            "derives" => generator.with_derives(vec![humblegen::ast::Derive::PartialEq]),
+            "enum-adjacent" => {
+                generator.with_enum_representation(humblegen::EnumRepresentation::Adjacent)
+            }
+            "large-ints" => {
+                generator.with_large_int_representation(humblegen::LargeIntRepresentation::String)
+            }
+            "service-mock" => Generator::new(humblegen::Artifact::MockServer)
+                .expect("failed to init humblegen rust backend"),
+            "service-boxed-future" => {
+                generator.with_handler_trait_style(HandlerTraitStyle::BoxedFuture)
+            }
+            _ => generator,
+        }
+    }
+
     fn from_test_dir(dir: &std::fs::DirEntry) -> anyhow::Result<RustTestCase> {
         let entries: Vec<std::fs::DirEntry> = std::fs::read_dir(dir.path())?
             .collect::<std::io::Result<Vec<std::fs::DirEntry>>>()
@@ -63,7 +106,7 @@ impl RustTestCase {
             "main.rs",
             "consumer of generated code (the test case)",
         );
-        let mut required_files = vec![&mut humble_spec, &mut humble_rust_out, &mut main];
+        let mut required_files = [&mut humble_spec, &mut humble_rust_out, &mut main];
 
         for entry in entries {
             let name = entry
@@ -106,3 +149,17 @@ fn rust() {
         test.run();
     }
 }
+
+#[test]
+fn rust_workspace() {
+    let workspace =
+        humblegen::workspace::Workspace::load("./tests/workspaces/rust/humblegen-workspace.toml")
+            .expect("load workspace");
+    Generator::new(humblegen::Artifact::ServerEndpoints)
+        .expect("failed to init humblegen rust backend")
+        .generate_workspace(&workspace, "./tests/workspaces/rust/protocol".as_ref())
+        .expect("humblegen rust backend failed");
+
+    let t = trybuild::TestCases::new();
+    t.pass("./tests/workspaces/rust/main.rs");
+}
@@ -0,0 +1,33 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let location = Location {
+        name: "lair".to_owned(),
+        position: LocationPosition {
+            lat: 1.5,
+            lon: 2.5,
+            altitude: Some(LocationPositionAltitude { meters: 10.0 }),
+        },
+    };
+    assert_eq!(
+        serde_json::to_value(&location).unwrap(),
+        json!({
+            "name": "lair",
+            "position": { "lat": 1.5, "lon": 2.5, "altitude": { "meters": 10.0 } },
+        })
+    );
+
+    let shape: Shape = serde_json::from_value(json!({
+        "Circle": { "center": { "x": 1.0, "y": 2.0 }, "radius": 3.0 },
+    }))
+    .unwrap();
+    match shape {
+        Shape::Circle { center, radius } => {
+            assert_eq!((center.x, center.y, radius), (1.0, 2.0, 3.0));
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Location {
+    #[doc = ""]
+    pub name: String,
+    #[doc = "Where the location is."]
+    pub position: LocationPosition,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct LocationPosition {
+    #[doc = ""]
+    pub lat: f64,
+    #[doc = ""]
+    pub lon: f64,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<LocationPositionAltitude>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct LocationPositionAltitude {
+    #[doc = ""]
+    pub meters: f64,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Shape {
+    #[doc = ""]
+    Circle {
+        #[doc = ""]
+        center: ShapeCircleCenter,
+        #[doc = ""]
+        radius: f64,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct ShapeCircleCenter {
+    #[doc = ""]
+    pub x: f64,
+    #[doc = ""]
+    pub y: f64,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Location {
+    const WIRE_HASH: &'static str = "1070f05507adbd89";
+}
+impl ::humblegen_rt::wire_hash::WireHash for LocationPosition {
+    const WIRE_HASH: &'static str = "1b419858f628feec";
+}
+impl ::humblegen_rt::wire_hash::WireHash for LocationPositionAltitude {
+    const WIRE_HASH: &'static str = "1565bf821c8d1241";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Shape {
+    const WIRE_HASH: &'static str = "0bd8d24381e77f8b";
+}
+impl ::humblegen_rt::wire_hash::WireHash for ShapeCircleCenter {
+    const WIRE_HASH: &'static str = "3660c8d85a3b4c22";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Location\": \"1070f05507adbd89\",\n  \"LocationPosition\": \"1b419858f628feec\",\n  \"LocationPositionAltitude\": \"1565bf821c8d1241\",\n  \"Shape\": \"0bd8d24381e77f8b\",\n  \"ShapeCircleCenter\": \"3660c8d85a3b4c22\"\n}" ;
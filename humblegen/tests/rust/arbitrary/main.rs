@@ -0,0 +1,50 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::arbitrary::Depth;
+use humblegen_rt::proptest::prelude::*;
+use humblegen_rt::proptest::test_runner::TestRunner;
+use protocol::*;
+
+fn depth(tree: &Tree) -> u32 {
+    1 + tree.children.iter().map(depth).max().unwrap_or(0)
+}
+
+/// Values survive a JSON round trip.
+fn round_trip<T>(runner: &mut TestRunner, strategy: BoxedStrategy<T>)
+where
+    T: std::fmt::Debug + serde::Serialize + serde::de::DeserializeOwned,
+{
+    runner
+        .run(&strategy, |value| {
+            let json = serde_json::to_value(&value).unwrap();
+            let decoded: T = serde_json::from_value(json.clone()).unwrap();
+            prop_assert_eq!(serde_json::to_value(&decoded).unwrap(), json);
+            Ok(())
+        })
+        .unwrap();
+}
+
+fn main() {
+    let mut runner = TestRunner::default();
+    round_trip(&mut runner, any::<Tree>());
+    round_trip(&mut runner, any::<Attribute>());
+    round_trip(&mut runner, any::<Wide>());
+    round_trip(&mut runner, any::<Empty>());
+    round_trip(&mut runner, any::<Point>());
+    round_trip(&mut runner, any::<Thumbnail>());
+
+    // the depth bounds the nesting of recursive types: the root and two nested levels
+    runner
+        .run(&any_with::<Tree>(Depth(2)), |tree| {
+            prop_assert!(depth(&tree) <= 3);
+            Ok(())
+        })
+        .unwrap();
+    runner
+        .run(&any_with::<Tree>(Depth(0)), |tree| {
+            prop_assert!(tree.children.is_empty() && tree.attributes.is_empty());
+            Ok(())
+        })
+        .unwrap();
+}
@@ -0,0 +1,283 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Recursive through a list."]
+pub struct Tree {
+    #[doc = ""]
+    pub label: String,
+    #[doc = ""]
+    pub weight: f64,
+    #[doc = ""]
+    pub children: Vec<Tree>,
+    #[doc = ""]
+    pub attributes: ::std::collections::HashMap<String, Attribute>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Attribute {
+    #[doc = ""]
+    Flag,
+    #[doc = ""]
+    Created(::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>),
+    #[doc = ""]
+    Born(::humblegen_rt::chrono::NaiveDate),
+    #[doc = ""]
+    Owner {
+        #[doc = ""]
+        id: ::humblegen_rt::uuid::Uuid,
+        #[doc = ""]
+        avatar: Option<Vec<u8>>,
+    },
+    #[doc = ""]
+    Range(u8, u32),
+    #[doc = ""]
+    Parent(Option<Tree>),
+    #[doc = ""]
+    Outcome(Result<i32, String>),
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "More fields than a tuple strategy can take."]
+pub struct Wide {
+    #[doc = ""]
+    pub f1: i32,
+    #[doc = ""]
+    pub f2: i32,
+    #[doc = ""]
+    pub f3: i32,
+    #[doc = ""]
+    pub f4: i32,
+    #[doc = ""]
+    pub f5: i32,
+    #[doc = ""]
+    pub f6: i32,
+    #[doc = ""]
+    pub f7: i32,
+    #[doc = ""]
+    pub f8: i32,
+    #[doc = ""]
+    pub f9: i32,
+    #[doc = ""]
+    pub f10: i32,
+    #[doc = ""]
+    pub f11: i32,
+    #[doc = ""]
+    pub f12: i32,
+    #[doc = ""]
+    pub f13: (bool, ()),
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Empty {}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Point(pub f64, pub f64);
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Thumbnail(
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub Vec<u8>,
+);
+impl ::humblegen_rt::wire_hash::WireHash for Attribute {
+    const WIRE_HASH: &'static str = "79f9ef7b76eaaf06";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Empty {
+    const WIRE_HASH: &'static str = "08f44b07b5901a25";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Point {
+    const WIRE_HASH: &'static str = "60278d8cf54800d8";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Thumbnail {
+    const WIRE_HASH: &'static str = "2f2ec0474f1c4fe4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Tree {
+    const WIRE_HASH: &'static str = "569733e3bdbeec2f";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Wide {
+    const WIRE_HASH: &'static str = "0d0ab3b76120c1a8";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Attribute\": \"79f9ef7b76eaaf06\",\n  \"Empty\": \"08f44b07b5901a25\",\n  \"Point\": \"60278d8cf54800d8\",\n  \"Thumbnail\": \"2f2ec0474f1c4fe4\",\n  \"Tree\": \"569733e3bdbeec2f\",\n  \"Wide\": \"0d0ab3b76120c1a8\"\n}" ;
+impl ::humblegen_rt::proptest::arbitrary::Arbitrary for Tree {
+    type Parameters = ::humblegen_rt::arbitrary::Depth;
+    type Strategy = ::humblegen_rt::proptest::strategy::BoxedStrategy<Self>;
+    #[allow(unused_variables)]
+    fn arbitrary_with(depth: Self::Parameters) -> Self::Strategy {
+        #[allow(unused_imports)]
+        use ::humblegen_rt::proptest::prelude::*;
+        (
+            any::<String>().boxed(),
+            (
+                ::humblegen_rt::arbitrary::f64(),
+                (
+                    ::humblegen_rt::arbitrary::list(depth, || {
+                        ::humblegen_rt::arbitrary::user_defined::<Tree>(depth)
+                    }),
+                    (
+                        ::humblegen_rt::arbitrary::map(
+                            depth,
+                            || any::<String>().boxed(),
+                            || ::humblegen_rt::arbitrary::user_defined::<Attribute>(depth),
+                        ),
+                        Just(()),
+                    ),
+                ),
+            ),
+        )
+            .prop_map(|(label, (weight, (children, (attributes, ()))))| Self {
+                label,
+                weight,
+                children,
+                attributes,
+            })
+            .boxed()
+    }
+}
+impl ::humblegen_rt::proptest::arbitrary::Arbitrary for Attribute {
+    type Parameters = ::humblegen_rt::arbitrary::Depth;
+    type Strategy = ::humblegen_rt::proptest::strategy::BoxedStrategy<Self>;
+    #[allow(unused_variables)]
+    fn arbitrary_with(depth: Self::Parameters) -> Self::Strategy {
+        #[allow(unused_imports)]
+        use ::humblegen_rt::proptest::prelude::*;
+        ::humblegen_rt::proptest::strategy::Union::new(vec![
+            Just(Self::Flag).boxed(),
+            ::humblegen_rt::arbitrary::datetime()
+                .prop_map(Self::Created)
+                .boxed(),
+            ::humblegen_rt::arbitrary::date()
+                .prop_map(Self::Born)
+                .boxed(),
+            (
+                ::humblegen_rt::arbitrary::uuid(),
+                (
+                    ::humblegen_rt::arbitrary::option(depth, || ::humblegen_rt::arbitrary::bytes()),
+                    Just(()),
+                ),
+            )
+                .prop_map(|(id, (avatar, ()))| Self::Owner { id, avatar })
+                .boxed(),
+            (any::<u8>().boxed(), (any::<u32>().boxed(), Just(())))
+                .prop_map(|(e0, (e1, ()))| Self::Range(e0, e1))
+                .boxed(),
+            ::humblegen_rt::arbitrary::option(depth, || {
+                ::humblegen_rt::arbitrary::user_defined::<Tree>(depth)
+            })
+            .prop_map(Self::Parent)
+            .boxed(),
+            ::humblegen_rt::arbitrary::result(any::<i32>().boxed(), any::<String>().boxed())
+                .prop_map(Self::Outcome)
+                .boxed(),
+        ])
+        .boxed()
+    }
+}
+impl ::humblegen_rt::proptest::arbitrary::Arbitrary for Wide {
+    type Parameters = ::humblegen_rt::arbitrary::Depth;
+    type Strategy = ::humblegen_rt::proptest::strategy::BoxedStrategy<Self>;
+    #[allow(unused_variables)]
+    fn arbitrary_with(depth: Self::Parameters) -> Self::Strategy {
+        #[allow(unused_imports)]
+        use ::humblegen_rt::proptest::prelude::*;
+        (
+            any::<i32>().boxed(),
+            (
+                any::<i32>().boxed(),
+                (
+                    any::<i32>().boxed(),
+                    (
+                        any::<i32>().boxed(),
+                        (
+                            any::<i32>().boxed(),
+                            (
+                                any::<i32>().boxed(),
+                                (
+                                    any::<i32>().boxed(),
+                                    (
+                                        any::<i32>().boxed(),
+                                        (
+                                            any::<i32>().boxed(),
+                                            (
+                                                any::<i32>().boxed(),
+                                                (
+                                                    any::<i32>().boxed(),
+                                                    (
+                                                        any::<i32>().boxed(),
+                                                        (
+                                                            (
+                                                                any::<bool>().boxed(),
+                                                                any::<()>().boxed(),
+                                                            )
+                                                                .boxed(),
+                                                            Just(()),
+                                                        ),
+                                                    ),
+                                                ),
+                                            ),
+                                        ),
+                                    ),
+                                ),
+                            ),
+                        ),
+                    ),
+                ),
+            ),
+        )
+            .prop_map(
+                |(
+                    f1,
+                    (f2, (f3, (f4, (f5, (f6, (f7, (f8, (f9, (f10, (f11, (f12, (f13, ())))))))))))),
+                )| Self {
+                    f1,
+                    f2,
+                    f3,
+                    f4,
+                    f5,
+                    f6,
+                    f7,
+                    f8,
+                    f9,
+                    f10,
+                    f11,
+                    f12,
+                    f13,
+                },
+            )
+            .boxed()
+    }
+}
+impl ::humblegen_rt::proptest::arbitrary::Arbitrary for Empty {
+    type Parameters = ::humblegen_rt::arbitrary::Depth;
+    type Strategy = ::humblegen_rt::proptest::strategy::BoxedStrategy<Self>;
+    #[allow(unused_variables)]
+    fn arbitrary_with(depth: Self::Parameters) -> Self::Strategy {
+        #[allow(unused_imports)]
+        use ::humblegen_rt::proptest::prelude::*;
+        Just(()).prop_map(|()| Self {}).boxed()
+    }
+}
+impl ::humblegen_rt::proptest::arbitrary::Arbitrary for Point {
+    type Parameters = ::humblegen_rt::arbitrary::Depth;
+    type Strategy = ::humblegen_rt::proptest::strategy::BoxedStrategy<Self>;
+    #[allow(unused_variables)]
+    fn arbitrary_with(depth: Self::Parameters) -> Self::Strategy {
+        #[allow(unused_imports)]
+        use ::humblegen_rt::proptest::prelude::*;
+        (
+            ::humblegen_rt::arbitrary::f64(),
+            (::humblegen_rt::arbitrary::f64(), Just(())),
+        )
+            .prop_map(|(e0, (e1, ()))| Self(e0, e1))
+            .boxed()
+    }
+}
+impl ::humblegen_rt::proptest::arbitrary::Arbitrary for Thumbnail {
+    type Parameters = ::humblegen_rt::arbitrary::Depth;
+    type Strategy = ::humblegen_rt::proptest::strategy::BoxedStrategy<Self>;
+    #[allow(unused_variables)]
+    fn arbitrary_with(depth: Self::Parameters) -> Self::Strategy {
+        #[allow(unused_imports)]
+        use ::humblegen_rt::proptest::prelude::*;
+        (::humblegen_rt::arbitrary::bytes(), Just(()))
+            .prop_map(|(e0, ())| Self(e0))
+            .boxed()
+    }
+}
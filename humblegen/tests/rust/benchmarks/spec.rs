@@ -0,0 +1,304 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A monster with all kinds of fields."]
+pub struct Monster {
+    #[doc = ""]
+    pub id: i32,
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub age: u32,
+    #[doc = ""]
+    pub level: u8,
+    #[doc = ""]
+    pub weight: f64,
+    #[doc = ""]
+    pub alive: bool,
+    #[doc = ""]
+    pub born: ::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>,
+    #[doc = ""]
+    pub registered: ::humblegen_rt::chrono::NaiveDate,
+    #[doc = ""]
+    pub uuid: ::humblegen_rt::uuid::Uuid,
+    #[doc = ""]
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub portrait: Vec<u8>,
+    #[doc = ""]
+    pub nothing: (),
+    #[doc = ""]
+    pub tags: Vec<String>,
+    #[doc = ""]
+    pub nickname: Option<String>,
+    #[doc = ""]
+    pub attributes: ::std::collections::HashMap<String, i32>,
+    #[doc = ""]
+    pub position: (f64, f64),
+    #[doc = ""]
+    pub friends: Vec<Friend>,
+    #[doc = ""]
+    pub last_fight: Result<Fight, FightError>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Friend {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub since: Option<::humblegen_rt::chrono::NaiveDate>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Fight {
+    #[doc = ""]
+    pub opponent: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum FightError {
+    #[doc = ""]
+    Lost,
+    #[doc = ""]
+    Draw(i32),
+    #[doc = ""]
+    Aborted(String, i32),
+    #[doc = ""]
+    Single(u8),
+    #[doc = ""]
+    Wrapped((String, i32)),
+    #[doc = ""]
+    Interrupted {
+        #[doc = ""]
+        reason: String,
+        #[doc = ""]
+        rounds: Vec<i32>,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Never {}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Coordinates(pub f64, pub f64);
+impl ::humblegen_rt::wire_hash::WireHash for Coordinates {
+    const WIRE_HASH: &'static str = "60278d8cf54800d8";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Fight {
+    const WIRE_HASH: &'static str = "3ee1da297c8c6057";
+}
+impl ::humblegen_rt::wire_hash::WireHash for FightError {
+    const WIRE_HASH: &'static str = "d11ff694f3e7d18f";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Friend {
+    const WIRE_HASH: &'static str = "147ffbe6a8828b72";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "b963032e8b2afb5d";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Never {
+    const WIRE_HASH: &'static str = "bb63ac29d3400a3b";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Coordinates\": \"60278d8cf54800d8\",\n  \"Fight\": \"3ee1da297c8c6057\",\n  \"FightError\": \"d11ff694f3e7d18f\",\n  \"Friend\": \"147ffbe6a8828b72\",\n  \"Monster\": \"b963032e8b2afb5d\",\n  \"Never\": \"bb63ac29d3400a3b\"\n}" ;
+#[doc = r" The sizes of the sample values each type is benchmarked with."]
+const SAMPLE_SIZES: [usize; 3] = [1, 10, 100];
+#[allow(non_snake_case, unused_variables)]
+fn sample_Monster(size: usize) -> Monster {
+    Monster {
+        id: size as i32,
+        name: "x".repeat(size),
+        age: size as u32,
+        level: size as u8,
+        weight: size as f64 / 3.0,
+        alive: size % 2 == 0,
+        born: ::humblegen_rt::chrono::TimeZone::timestamp(
+            &::humblegen_rt::chrono::Utc,
+            size as i64,
+            0,
+        ),
+        registered: ::humblegen_rt::chrono::NaiveDate::from_num_days_from_ce(size as i32 + 1),
+        uuid: ::humblegen_rt::uuid::Uuid::from_u128(size as u128),
+        portrait: vec![0xAB; size],
+        nothing: (),
+        tags: (0..size)
+            .map(|_| {
+                let size = size / 4;
+                "x".repeat(size)
+            })
+            .collect::<Vec<_>>(),
+        nickname: Some("x".repeat(size)),
+        attributes: (0..size)
+            .map(|i| {
+                (
+                    {
+                        let size = i;
+                        "x".repeat(size)
+                    },
+                    {
+                        let size = size / 4;
+                        size as i32
+                    },
+                )
+            })
+            .collect::<::std::collections::HashMap<_, _>>(),
+        position: (size as f64 / 3.0, size as f64 / 3.0),
+        friends: (0..size)
+            .map(|_| {
+                let size = size / 4;
+                sample_Friend(size)
+            })
+            .collect::<Vec<_>>(),
+        last_fight: Ok(sample_Fight(size)),
+    }
+}
+#[allow(non_snake_case)]
+fn bench_Monster(c: &mut ::criterion::Criterion) {
+    let mut group = c.benchmark_group("Monster");
+    for size in SAMPLE_SIZES.iter().copied() {
+        let value = sample_Monster(size);
+        let json = ::serde_json::to_vec(&value).expect("sample value must be serializable");
+        group.throughput(::criterion::Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("serialize", size),
+            &value,
+            |b, value| b.iter(|| ::serde_json::to_vec(value).unwrap()),
+        );
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("deserialize", size),
+            &json,
+            |b, json| b.iter(|| ::serde_json::from_slice::<Monster>(json).unwrap()),
+        );
+    }
+    group.finish();
+}
+#[allow(non_snake_case, unused_variables)]
+fn sample_Friend(size: usize) -> Friend {
+    Friend {
+        name: "x".repeat(size),
+        since: Some(::humblegen_rt::chrono::NaiveDate::from_num_days_from_ce(
+            size as i32 + 1,
+        )),
+    }
+}
+#[allow(non_snake_case)]
+fn bench_Friend(c: &mut ::criterion::Criterion) {
+    let mut group = c.benchmark_group("Friend");
+    for size in SAMPLE_SIZES.iter().copied() {
+        let value = sample_Friend(size);
+        let json = ::serde_json::to_vec(&value).expect("sample value must be serializable");
+        group.throughput(::criterion::Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("serialize", size),
+            &value,
+            |b, value| b.iter(|| ::serde_json::to_vec(value).unwrap()),
+        );
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("deserialize", size),
+            &json,
+            |b, json| b.iter(|| ::serde_json::from_slice::<Friend>(json).unwrap()),
+        );
+    }
+    group.finish();
+}
+#[allow(non_snake_case, unused_variables)]
+fn sample_Fight(size: usize) -> Fight {
+    Fight {
+        opponent: "x".repeat(size),
+    }
+}
+#[allow(non_snake_case)]
+fn bench_Fight(c: &mut ::criterion::Criterion) {
+    let mut group = c.benchmark_group("Fight");
+    for size in SAMPLE_SIZES.iter().copied() {
+        let value = sample_Fight(size);
+        let json = ::serde_json::to_vec(&value).expect("sample value must be serializable");
+        group.throughput(::criterion::Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("serialize", size),
+            &value,
+            |b, value| b.iter(|| ::serde_json::to_vec(value).unwrap()),
+        );
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("deserialize", size),
+            &json,
+            |b, json| b.iter(|| ::serde_json::from_slice::<Fight>(json).unwrap()),
+        );
+    }
+    group.finish();
+}
+#[allow(non_snake_case, unused_variables)]
+fn sample_FightError(size: usize) -> FightError {
+    match size % 6usize {
+        0usize => FightError::Lost,
+        1usize => FightError::Draw(size as i32),
+        2usize => FightError::Aborted("x".repeat(size), size as i32),
+        3usize => FightError::Single(size as u8),
+        4usize => FightError::Wrapped(("x".repeat(size), size as i32)),
+        5usize => FightError::Interrupted {
+            reason: "x".repeat(size),
+            rounds: (0..size)
+                .map(|_| {
+                    let size = size / 4;
+                    size as i32
+                })
+                .collect::<Vec<_>>(),
+        },
+        _ => unreachable!(),
+    }
+}
+#[allow(non_snake_case)]
+fn bench_FightError(c: &mut ::criterion::Criterion) {
+    let mut group = c.benchmark_group("FightError");
+    for size in SAMPLE_SIZES.iter().copied() {
+        let value = sample_FightError(size);
+        let json = ::serde_json::to_vec(&value).expect("sample value must be serializable");
+        group.throughput(::criterion::Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("serialize", size),
+            &value,
+            |b, value| b.iter(|| ::serde_json::to_vec(value).unwrap()),
+        );
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("deserialize", size),
+            &json,
+            |b, json| b.iter(|| ::serde_json::from_slice::<FightError>(json).unwrap()),
+        );
+    }
+    group.finish();
+}
+#[allow(non_snake_case, unused_variables)]
+fn sample_Never(size: usize) -> Never {
+    unreachable!("enum Never has no variants")
+}
+#[allow(non_snake_case, unused_variables)]
+fn sample_Coordinates(size: usize) -> Coordinates {
+    Coordinates(size as f64 / 3.0, size as f64 / 3.0)
+}
+#[allow(non_snake_case)]
+fn bench_Coordinates(c: &mut ::criterion::Criterion) {
+    let mut group = c.benchmark_group("Coordinates");
+    for size in SAMPLE_SIZES.iter().copied() {
+        let value = sample_Coordinates(size);
+        let json = ::serde_json::to_vec(&value).expect("sample value must be serializable");
+        group.throughput(::criterion::Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("serialize", size),
+            &value,
+            |b, value| b.iter(|| ::serde_json::to_vec(value).unwrap()),
+        );
+        group.bench_with_input(
+            ::criterion::BenchmarkId::new("deserialize", size),
+            &json,
+            |b, json| b.iter(|| ::serde_json::from_slice::<Coordinates>(json).unwrap()),
+        );
+    }
+    group.finish();
+}
+::criterion::criterion_group!(
+    benches,
+    bench_Monster,
+    bench_Friend,
+    bench_Fight,
+    bench_FightError,
+    bench_Coordinates
+);
+::criterion::criterion_main!(benches);
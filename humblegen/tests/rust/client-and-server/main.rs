@@ -0,0 +1,16 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+
+// The client and server are only compiled with the `client` and `server` features of the crate
+// containing the generated code, the data types are always available.
+fn main() {
+    let monster: Monster = serde_json::from_str(r#"{"name": "dragon", "age": 300}"#).unwrap();
+    assert_eq!(monster.name, "dragon");
+    assert_eq!(monster.age, 300);
+    assert!(matches!(
+        serde_json::from_str::<MonsterError>(r#""NotFound""#).unwrap(),
+        MonsterError::NotFound
+    ));
+}
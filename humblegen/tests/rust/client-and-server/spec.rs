@@ -0,0 +1,997 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub age: i32,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct MonsterQuery {
+    #[doc = ""]
+    pub name: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Portrait {
+    #[doc = ""]
+    pub caption: String,
+    #[doc = ""]
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub image: Vec<u8>,
+    #[doc = ""]
+    pub note: Option<String>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum MonsterError {
+    #[doc = ""]
+    NotFound,
+    #[doc = ""]
+    Invalid(String),
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "ed40f0b4893d6ae4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterError {
+    const WIRE_HASH: &'static str = "1abbbeb9152d150e";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterQuery {
+    const WIRE_HASH: &'static str = "1136063428524aa5";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Portrait {
+    const WIRE_HASH: &'static str = "d3743b3c3b3ff536";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Monster\": \"ed40f0b4893d6ae4\",\n  \"MonsterError\": \"1abbbeb9152d150e\",\n  \"MonsterQuery\": \"1136063428524aa5\",\n  \"Portrait\": \"d3743b3c3b3ff536\"\n}" ;
+#[cfg(feature = "client")]
+pub mod client {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = r" The configuration of the clients of the services, see `Config`."]
+    pub mod config {
+        #[doc = r" The base URL, timeout and default headers of the clients of the services, which"]
+        #[doc = r" create clients with `from_config`."]
+        #[derive(Debug, Clone)]
+        pub struct Config {
+            #[doc = r" The URL the services are mounted at."]
+            pub base_url: ::humblegen_rt::client::Url,
+            #[doc = r" The time after which requests fail, unlimited if `None`."]
+            pub timeout: Option<::std::time::Duration>,
+            #[doc = r" The headers sent with every request, e.g. an API key."]
+            pub default_headers: Vec<(
+                ::humblegen_rt::client::HeaderName,
+                ::humblegen_rt::client::HeaderValue,
+            )>,
+        }
+        impl Config {
+            #[doc = r" A config of clients of the services mounted at `base_url`, without timeout"]
+            #[doc = r" and default headers."]
+            pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+                Self {
+                    base_url,
+                    timeout: None,
+                    default_headers: Vec::new(),
+                }
+            }
+            #[doc = r" Fail requests that are not completed within `timeout`."]
+            pub fn with_timeout(mut self, timeout: ::std::time::Duration) -> Self {
+                self.timeout = Some(timeout);
+                self
+            }
+            #[doc = r" Send header `name` with `value` in every request."]
+            pub fn with_default_header(
+                mut self,
+                name: ::humblegen_rt::client::HeaderName,
+                value: ::humblegen_rt::client::HeaderValue,
+            ) -> Self {
+                self.default_headers.push((name, value));
+                self
+            }
+        }
+    }
+    #[doc = "Client of service `MonsterApi`."]
+    #[doc = ""]
+    #[doc = ""]
+    #[derive(Debug, Clone)]
+    pub struct MonsterApiClient {
+        base_url: ::humblegen_rt::client::Url,
+        client: ::humblegen_rt::client::Client,
+    }
+    impl MonsterApiClient {
+        #[doc = r" The `User-Agent` of the client: the name and version of the crate it is compiled into,"]
+        #[doc = r" the service and the hash of the spec the client was generated from."]
+        pub const USER_AGENT: &'static str = concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION"),
+            " (MonsterApi; humblespec ab7417b9606a3982)"
+        );
+        #[doc = r" Creates a client of the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+        pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+            Self::with_config(base_url, &Self::default_config())
+                .expect("the default client config is valid")
+        }
+        #[doc = r" The config of clients created with `new`, sending the `USER_AGENT`, to add default headers to."]
+        pub fn default_config() -> ::humblegen_rt::client::ClientConfig {
+            ::humblegen_rt::client::ClientConfig::new(Self::USER_AGENT)
+        }
+        #[doc = r" Like `new`, but sends the `User-Agent` and the default headers of `config` with every request."]
+        pub fn with_config(
+            base_url: ::humblegen_rt::client::Url,
+            config: &::humblegen_rt::client::ClientConfig,
+        ) -> Result<Self, ::humblegen_rt::client::ClientError> {
+            Ok(Self::with_client(base_url, config.build_client()?))
+        }
+        #[doc = r" Like `with_config`, but mounted at the base URL and with the timeout and default headers"]
+        #[doc = r" of `config`, which is shared by the clients of all services."]
+        pub fn from_config(
+            config: &config::Config,
+        ) -> Result<Self, ::humblegen_rt::client::ClientError> {
+            let mut client_config = Self::default_config();
+            if let Some(timeout) = config.timeout {
+                client_config = client_config.with_timeout(timeout);
+            }
+            for (name, value) in &config.default_headers {
+                client_config = client_config.with_default_header(name.clone(), value.clone());
+            }
+            Self::with_config(config.base_url.clone(), &client_config)
+        }
+        #[doc = r" Like `new`, but sends requests using `client`, e.g., to set timeouts."]
+        pub fn with_client(
+            base_url: ::humblegen_rt::client::Url,
+            client: ::humblegen_rt::client::Client,
+        ) -> Self {
+            Self { base_url, client }
+        }
+        #[doc = ""]
+        #[allow(unused_mut)]
+        pub async fn get_monsters(
+            &self,
+            query: Option<MonsterQuery>,
+        ) -> Result<Vec<Monster>, ::humblegen_rt::client::ClientError> {
+            let mut url =
+                ::humblegen_rt::client::route_url(&self.base_url, &["monsters".to_owned()])?;
+            let req = self
+                .client
+                .request(::humblegen_rt::client::Method::GET, url);
+            let req = match &query {
+                Some(query) => req.query(query),
+                None => req,
+            };
+            ::humblegen_rt::client::send(req, &[]).await
+        }
+        #[doc = ""]
+        #[allow(unused_mut)]
+        pub async fn get_monsters_id(
+            &self,
+            id: i32,
+            x_request_id: Option<String>,
+        ) -> Result<Result<Monster, MonsterError>, ::humblegen_rt::client::ClientError> {
+            let mut url = ::humblegen_rt::client::route_url(
+                &self.base_url,
+                &[
+                    "monsters".to_owned(),
+                    ::humblegen_rt::client::ToParam::to_param(&id),
+                ],
+            )?;
+            let req = self
+                .client
+                .request(::humblegen_rt::client::Method::GET, url);
+            let req = match &x_request_id {
+                Some(value) => req.header(
+                    "X-Request-Id",
+                    ::humblegen_rt::client::ToParam::to_param(value),
+                ),
+                None => req,
+            };
+            ::humblegen_rt::client::send(req, &[404u16]).await
+        }
+        #[doc = ""]
+        #[allow(unused_mut)]
+        pub async fn post_monsters(
+            &self,
+            post_body: Monster,
+            x_api_key: String,
+        ) -> Result<i32, ::humblegen_rt::client::ClientError> {
+            let mut url =
+                ::humblegen_rt::client::route_url(&self.base_url, &["monsters".to_owned()])?;
+            let req = self
+                .client
+                .request(::humblegen_rt::client::Method::POST, url);
+            let req = req.json(&post_body);
+            let req = req.header(
+                "X-Api-Key",
+                ::humblegen_rt::client::ToParam::to_param(&x_api_key),
+            );
+            ::humblegen_rt::client::send(req, &[]).await
+        }
+        #[doc = ""]
+        #[allow(unused_mut)]
+        pub async fn post_monsters_id_portrait(
+            &self,
+            post_body: Portrait,
+            id: i32,
+        ) -> Result<(), ::humblegen_rt::client::ClientError> {
+            let mut url = ::humblegen_rt::client::route_url(
+                &self.base_url,
+                &[
+                    "monsters".to_owned(),
+                    ::humblegen_rt::client::ToParam::to_param(&id),
+                    "portrait".to_owned(),
+                ],
+            )?;
+            let req = self
+                .client
+                .request(::humblegen_rt::client::Method::POST, url);
+            let req = req.multipart(
+                ::humblegen_rt::client::ToMultipart::to_multipart(post_body)?.into_form(),
+            );
+            ::humblegen_rt::client::send(req, &[]).await
+        }
+        #[doc = "Like `post_monsters_id_portrait`, but reports the progress of the upload of the body to `progress`."]
+        #[allow(unused_mut)]
+        pub async fn post_monsters_id_portrait_with_progress(
+            &self,
+            post_body: Portrait,
+            id: i32,
+            progress: impl Fn(::humblegen_rt::client::UploadProgress) + Send + Sync + 'static,
+        ) -> Result<(), ::humblegen_rt::client::ClientError> {
+            let progress = ::humblegen_rt::client::UploadProgressReporter::new(progress);
+            let mut url = ::humblegen_rt::client::route_url(
+                &self.base_url,
+                &[
+                    "monsters".to_owned(),
+                    ::humblegen_rt::client::ToParam::to_param(&id),
+                    "portrait".to_owned(),
+                ],
+            )?;
+            let req = self
+                .client
+                .request(::humblegen_rt::client::Method::POST, url);
+            let req = req.multipart(
+                ::humblegen_rt::client::ToMultipart::to_multipart(post_body)?
+                    .into_form_with_progress(&progress),
+            );
+            ::humblegen_rt::client::send_with_progress(&self.client, req, &progress, &[]).await
+        }
+        #[doc = ""]
+        #[allow(unused_mut)]
+        pub async fn delete_monsters_id(
+            &self,
+            id: i32,
+        ) -> Result<(), ::humblegen_rt::client::ClientError> {
+            let mut url = ::humblegen_rt::client::route_url(
+                &self.base_url,
+                &[
+                    "monsters".to_owned(),
+                    ::humblegen_rt::client::ToParam::to_param(&id),
+                ],
+            )?;
+            let req = self
+                .client
+                .request(::humblegen_rt::client::Method::DELETE, url);
+            ::humblegen_rt::client::send(req, &[]).await
+        }
+    }
+    impl ::humblegen_rt::client::ToMultipart for Portrait {
+        fn to_multipart(
+            self,
+        ) -> Result<::humblegen_rt::client::MultipartForm, ::humblegen_rt::client::ClientError>
+        {
+            let form = ::humblegen_rt::client::MultipartForm::new();
+            let form = form.text("caption", self.caption);
+            let form = form.file("image", self.image);
+            let form = form.opt_text("note", self.note);
+            Ok(form)
+        }
+    }
+}
+#[cfg(feature = "client")]
+pub use client::*;
+#[cfg(feature = "server")]
+pub mod server {
+    #[allow(unused_imports)]
+    use super::*;
+    #[doc = r" The resolved humblespec of the services, served at `/__spec` by `Builder::with_spec_endpoint`."]
+    pub const SPEC_JSON : & str = "{\n  \"services\": [\n    {\n      \"doc\": null,\n      \"format\": \"json\",\n      \"routes\": [\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters\",\n          \"query\": \"MonsterQuery\",\n          \"returns\": \"list[Monster]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [\n            {\n              \"name\": \"X-Request-Id\",\n              \"type\": \"option[str]\"\n            }\n          ],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters/{id: i32}\",\n          \"query\": null,\n          \"returns\": \"result[Monster][MonsterError]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": \"Monster\",\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [\n            {\n              \"name\": \"X-Api-Key\",\n              \"type\": \"str\"\n            }\n          ],\n          \"method\": \"POST\",\n          \"multipart\": false,\n          \"path\": \"/monsters\",\n          \"query\": null,\n          \"returns\": \"i32\"\n        },\n        {\n          \"auth\": false,\n          \"body\": \"Portrait\",\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"POST\",\n          \"multipart\": true,\n          \"path\": \"/monsters/{id: i32}/portrait\",\n          \"query\": null,\n          \"returns\": \"()\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"DELETE\",\n          \"multipart\": false,\n          \"path\": \"/monsters/{id: i32}\",\n          \"query\": null,\n          \"returns\": \"()\"\n        }\n      ],\n      \"service\": \"MonsterApi\"\n    }\n  ],\n  \"types\": [\n    {\n      \"doc\": null,\n      \"fields\": [\n        {\n          \"doc\": null,\n          \"name\": \"name\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"age\",\n          \"renamed_from\": [],\n          \"type\": \"i32\"\n        }\n      ],\n      \"struct\": \"Monster\"\n    },\n    {\n      \"doc\": null,\n      \"fields\": [\n        {\n          \"doc\": null,\n          \"name\": \"name\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        }\n      ],\n      \"struct\": \"MonsterQuery\"\n    },\n    {\n      \"doc\": null,\n      \"fields\": [\n        {\n          \"doc\": null,\n          \"name\": \"caption\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"image\",\n          \"renamed_from\": [],\n          \"type\": \"bytes\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"note\",\n          \"renamed_from\": [],\n          \"type\": \"option[str]\"\n        }\n      ],\n      \"struct\": \"Portrait\"\n    },\n    {\n      \"doc\": null,\n      \"enum\": \"MonsterError\",\n      \"variants\": [\n        {\n          \"doc\": null,\n          \"name\": \"NotFound\",\n          \"status\": 404\n        },\n        {\n          \"doc\": null,\n          \"name\": \"Invalid\",\n          \"type\": \"str\"\n        }\n      ]\n    }\n  ]\n}" ;
+    #[doc = r" HTML index of the routes of the services, served at `/__spec/index.html` by `Builder::with_spec_endpoint`."]
+    pub const SPEC_INDEX_HTML : & str = "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>API Routes</title>\n<style>body { font-family: sans-serif; } table { border-collapse: collapse; } td, th { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }</style>\n</head>\n<body>\n<h1>API Routes</h1>\n<p>Paths are relative to the root a service is mounted at. The full spec is available as <a href=\"../__spec\">JSON</a>.</p>\n<h2>MonsterApi</h2>\n<p></p>\n<table>\n<tr><th>Method</th><th>Path</th><th>Body</th><th>Returns</th><th>Description</th></tr>\n<tr><td><code>GET</code></td><td><code>/monsters?{MonsterQuery}</code></td><td><code></code></td><td><code>list[Monster]</code></td><td></td></tr>\n<tr><td><code>GET</code></td><td><code>/monsters/{id: i32}</code></td><td><code></code></td><td><code>result[Monster][MonsterError]</code></td><td></td></tr>\n<tr><td><code>POST</code></td><td><code>/monsters</code></td><td><code>Monster</code></td><td><code>i32</code></td><td></td></tr>\n<tr><td><code>POST</code></td><td><code>/monsters/{id: i32}/portrait</code></td><td><code>Portrait</code></td><td><code>()</code></td><td></td></tr>\n<tr><td><code>DELETE</code></td><td><code>/monsters/{id: i32}</code></td><td><code></code></td><td><code>()</code></td><td></td></tr>\n</table>\n</body>\n</html>\n" ;
+    #[allow(unused_imports)]
+    use ::humblegen_rt::deser_helpers::{
+        deser_header, deser_opt_header, deser_param, deser_post_data, deser_query_primitive,
+        deser_query_serde_urlencoded,
+    };
+    #[allow(unused_imports)]
+    pub use ::humblegen_rt::handler::{
+        self, HandlerFuture, HandlerResponse as Response, ServiceError, WithHeaders,
+    };
+    #[allow(unused_imports)]
+    use ::humblegen_rt::server::{self, handler_response_to_hyper_response, Router, Service};
+    #[allow(unused_imports)]
+    use ::humblegen_rt::service_protocol::ErrorResponse;
+    use ::humblegen_rt::tracing_futures::Instrument;
+    #[allow(unused_imports)]
+    use ::humblegen_rt::{hyper, tracing};
+    #[allow(unused_imports)]
+    use ::std::sync::Arc;
+    use std::net::SocketAddr;
+    #[doc = r" Builds an HTTP server that exposes services implemented by handler trait objects."]
+    #[derive(Debug)]
+    pub struct Builder {
+        services: Vec<Service>,
+        metrics: Option<Arc<::humblegen_rt::metrics::Metrics>>,
+        feature_flags: Option<Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+        pretty_json: bool,
+        recorder: Option<Arc<::humblegen_rt::recording::Recorder>>,
+        middleware: Vec<Arc<dyn ::humblegen_rt::middleware::Middleware>>,
+        shutdown: ::humblegen_rt::shutdown::ShutdownHandle,
+        drain_delay: std::time::Duration,
+        limits: ::humblegen_rt::limits::Limits,
+        idempotency_store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+    }
+    impl Builder {
+        pub fn new() -> Self {
+            Self {
+                services: vec![],
+                metrics: None,
+                feature_flags: None,
+                pretty_json: false,
+                recorder: None,
+                middleware: vec![],
+                shutdown: Default::default(),
+                drain_delay: std::time::Duration::from_secs(0),
+                limits: Default::default(),
+                idempotency_store: Arc::new(::humblegen_rt::idempotency::MemoryStore::default()),
+            }
+        }
+        #[doc = r" Records the latencies of requests to the routes of handlers `add`ed afterwards"]
+        #[doc = r" in `metrics`."]
+        pub fn with_metrics(mut self, metrics: Arc<::humblegen_rt::metrics::Metrics>) -> Self {
+            self.metrics = Some(metrics);
+            self
+        }
+        #[doc = r#" Consults `feature_flags` whether the routes annotated with `@flag("name")` of"#]
+        #[doc = r" handlers `add`ed afterwards are enabled. Without, these routes are hidden."]
+        pub fn with_feature_flags(
+            mut self,
+            feature_flags: Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>,
+        ) -> Self {
+            self.feature_flags = Some(feature_flags);
+            self
+        }
+        #[doc = r" Runs `middleware` around the requests to the routes of handlers `add`ed afterwards,"]
+        #[doc = r" inside the middleware passed before."]
+        pub fn with_middleware(
+            mut self,
+            middleware: Arc<dyn ::humblegen_rt::middleware::Middleware>,
+        ) -> Self {
+            self.middleware.push(middleware);
+            self
+        }
+        #[doc = r" Answers requests to the routes of handlers `add`ed afterwards with a body larger than"]
+        #[doc = r" `max_body_size` bytes with 413 Payload Too Large, unless annotated with `@max_body_size`."]
+        pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+            self.limits.max_body_size = Some(max_body_size);
+            self
+        }
+        #[doc = r" Answers requests to the routes of handlers `add`ed afterwards with 504 Gateway Timeout"]
+        #[doc = r" if not handled within `timeout`, unless annotated with `@timeout`."]
+        pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+            self.limits.timeout = Some(timeout);
+            self
+        }
+        #[doc = r" Deduplicates the requests to the routes annotated with `@idempotent` of handlers `add`ed"]
+        #[doc = r" afterwards using `store`, e.g. one shared by all instances of the server. Defaults to a"]
+        #[doc = r" `MemoryStore`."]
+        pub fn with_idempotency_store(
+            mut self,
+            store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+        ) -> Self {
+            self.idempotency_store = store;
+            self
+        }
+        #[doc = r" Whether responses of handlers `add`ed afterwards are pretty-printed JSON, e.g. for"]
+        #[doc = r" debugging. Defaults to compact JSON, unless a request has query parameter `pretty=1`."]
+        pub fn with_pretty_json(mut self, pretty_json: bool) -> Self {
+            self.pretty_json = pretty_json;
+            self
+        }
+        #[doc = r" Records all requests served by `listen_and_run_forever` and their responses in"]
+        #[doc = r" `recorder`, to be replayed using `humblegen_rt::recording::replay`."]
+        pub fn with_recorder(mut self, recorder: Arc<::humblegen_rt::recording::Recorder>) -> Self {
+            self.recorder = Some(recorder);
+            self
+        }
+        #[doc = r" Mounts `handler` at URL path prefix `root`."]
+        #[doc = r" This means that a `handler` implementing humble service"]
+        #[doc = r" ```"]
+        #[doc = r" service S {"]
+        #[doc = r"     GET /bar -> i32,"]
+        #[doc = r"     GET /baz -> str,"]
+        #[doc = r" }"]
+        #[doc = r" ```"]
+        #[doc = r#" and `root="/api"` will expose"#]
+        #[doc = r" * handler method `fn bar() -> i32` at `/api/bar` and"]
+        #[doc = r" * handler method `fn baz() -> String` at `/api/baz`"]
+        pub fn add<Context: Default + Sized + Send + Sync>(
+            mut self,
+            root: &str,
+            handler: Handler<Context>,
+        ) -> Self {
+            if !root.starts_with('/') {
+                panic!("root must start with \"/\"")
+            } else if root.ends_with('/') {
+                panic!("root must not end with \"/\"")
+            }
+            self.services.push(Service {
+                root: root.to_owned(),
+                route_prefix: handler.route_prefix().to_owned(),
+                router: handler.into_router(
+                    root,
+                    self.metrics.as_deref(),
+                    self.feature_flags.as_ref(),
+                    &self.middleware,
+                    self.limits,
+                    &self.idempotency_store,
+                ),
+                pretty_json: self.pretty_json,
+            });
+            self
+        }
+        #[doc = r" Serves the metrics passed to `with_metrics` in the Prometheus text format at `/metrics`."]
+        pub fn with_metrics_endpoint(mut self) -> Self {
+            let metrics = self
+                .metrics
+                .clone()
+                .expect("with_metrics must be called before with_metrics_endpoint");
+            self.services
+                .push(::humblegen_rt::metrics::service(metrics));
+            self
+        }
+        #[doc = r" Serves liveness and readiness probes at `/health/live` and `/health/ready`, the latter"]
+        #[doc = r" responding with 503 Service Unavailable once the server is shutting down."]
+        pub fn with_health_endpoints(mut self) -> Self {
+            self.services.push(::humblegen_rt::shutdown::health_service(
+                self.shutdown.clone(),
+            ));
+            self
+        }
+        #[doc = r" Returns the handle to shut down the server gracefully: `listen_and_run_forever`"]
+        #[doc = r" stops accepting connections and returns once the requests in flight completed."]
+        pub fn shutdown_handle(&self) -> ::humblegen_rt::shutdown::ShutdownHandle {
+            self.shutdown.clone()
+        }
+        #[doc = r" Shuts down the server gracefully on SIGTERM or Ctrl-C."]
+        #[doc = r" Must be called within a tokio runtime."]
+        pub fn with_shutdown_signal(self) -> Self {
+            self.shutdown.shutdown_on_signal();
+            self
+        }
+        #[doc = r" Keeps accepting connections for `drain_delay` after the shutdown started, while"]
+        #[doc = r" the readiness probe already fails, such that load balancers stop routing requests"]
+        #[doc = r" to the server before it stops accepting connections. Defaults to zero."]
+        pub fn with_drain_delay(mut self, drain_delay: std::time::Duration) -> Self {
+            self.drain_delay = drain_delay;
+            self
+        }
+        #[doc = r" Serves the resolved humblespec of the services as JSON document at `/__spec`,"]
+        #[doc = r" and an HTML index of their routes at `/__spec/index.html`."]
+        pub fn with_spec_endpoint(mut self) -> Self {
+            self.services.push(::humblegen_rt::spec_endpoint::service(
+                SPEC_JSON,
+                SPEC_INDEX_HTML,
+            ));
+            self
+        }
+        #[doc = r" Returns the previously `add`ed handlers as services, to be served using"]
+        #[doc = r" `humblegen_rt::server::handle_request`, e.g. in a custom hyper server or in tests."]
+        pub fn into_services(self) -> Vec<Service> {
+            self.services
+        }
+        #[doc = r" Starts an HTTP server bound to address `addr` and serves incoming requests using"]
+        #[doc = r" the previously `add`ed handlers, until shut down using `shutdown_handle`."]
+        pub async fn listen_and_run_forever(
+            self,
+            addr: &SocketAddr,
+        ) -> humblegen_rt::anyhow::Result<()> {
+            server::listen_and_run_forever(
+                self.services,
+                addr,
+                self.recorder,
+                self.shutdown,
+                self.drain_delay,
+            )
+            .await
+        }
+        #[doc = r" Like `listen_and_run_forever`, but serves the connections accepted by `incoming`,"]
+        #[doc = r" e.g. to terminate TLS using `humblegen_rt::tls::TlsAcceptor::bind`."]
+        pub async fn serve<I>(self, incoming: I) -> humblegen_rt::anyhow::Result<()>
+        where
+            I: ::humblegen_rt::hyper::server::accept::Accept,
+            I::Conn: ::humblegen_rt::tokio::io::AsyncRead
+                + ::humblegen_rt::tokio::io::AsyncWrite
+                + Unpin
+                + Send
+                + 'static,
+            I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        {
+            server::serve(
+                incoming,
+                self.services,
+                self.recorder,
+                self.shutdown,
+                self.drain_delay,
+            )
+            .await
+        }
+    }
+    #[doc = r" Wrapper enum with one variant for each service defined in the humble spec."]
+    #[doc = r" Used to pass instantiated handler trait objects to `Builder::add`."]
+    #[allow(dead_code)]
+    pub enum Handler<Context: Default + Sized + Send + Sync + 'static> {
+        MonsterApi(Arc<dyn MonsterApi<Context = Context> + Send + Sync>),
+    }
+    impl<Context: Default + Sized + Send + Sync + 'static> Handler<Context> {
+        fn into_router(
+            self,
+            root: &str,
+            metrics: Option<&::humblegen_rt::metrics::Metrics>,
+            feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+            middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+            limits: ::humblegen_rt::limits::Limits,
+            idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+        ) -> Box<Router> {
+            match self {
+                Handler::MonsterApi(h) => routes_MonsterApi(
+                    h,
+                    root,
+                    metrics,
+                    feature_flags,
+                    middleware,
+                    limits,
+                    idempotency_store,
+                ),
+            }
+        }
+        fn route_prefix(&self) -> &'static str {
+            match self {
+                Handler::MonsterApi(_) => "/monsters",
+            }
+        }
+    }
+    impl<Context: Default + Sized + Send + Sync + 'static> std::fmt::Debug for Handler<Context> {
+        fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Handler::MonsterApi(_) => write!(formatter, "{}", "MonsterApi")?,
+            }
+            Ok(())
+        }
+    }
+    #[doc = ""]
+    #[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait MonsterApi {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<Self::Context, ServiceError> {\n        Ok(Self::Context::default())\n    }\n    async fn get_monsters(\n        &self,\n        ctx: Self::Context,\n        query: Option<MonsterQuery>,\n    ) -> Response<Vec<Monster>>;\n    async fn get_monsters_id(\n        &self,\n        ctx: Self::Context,\n        id: i32,\n        x_request_id: Option<String>,\n    ) -> Response<Result<Monster, MonsterError>>;\n    async fn post_monsters(\n        &self,\n        ctx: Self::Context,\n        post_body: Monster,\n        x_api_key: String,\n    ) -> Response<i32>;\n    async fn post_monsters_id_portrait(\n        &self,\n        ctx: Self::Context,\n        post_body: Portrait,\n        id: i32,\n    ) -> Response<()>;\n    async fn delete_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<()>;\n}\n\n```"]
+    #[humblegen_rt::async_trait(Sync)]
+    pub trait MonsterApi {
+        type Context: Default + Sized + Send + Sync;
+        async fn intercept_handler_pre(
+            &self,
+            _req: &hyper::Request<hyper::Body>,
+        ) -> Result<Self::Context, ServiceError> {
+            Ok(Self::Context::default())
+        }
+        #[doc = "```\nasync fn get_monsters(\n    &self,\n    ctx: Self::Context,\n    query: Option<MonsterQuery>,\n) -> Response<Vec<Monster>> {\n}\n\n```"]
+        #[doc = ""]
+        async fn get_monsters(
+            &self,
+            ctx: Self::Context,
+            query: Option<MonsterQuery>,
+        ) -> Response<Vec<Monster>>;
+        #[doc = "```\nasync fn get_monsters_id(\n    &self,\n    ctx: Self::Context,\n    id: i32,\n    x_request_id: Option<String>,\n) -> Response<Result<Monster, MonsterError>> {\n}\n\n```"]
+        #[doc = ""]
+        async fn get_monsters_id(
+            &self,
+            ctx: Self::Context,
+            id: i32,
+            x_request_id: Option<String>,
+        ) -> Response<Result<Monster, MonsterError>>;
+        #[doc = "```\nasync fn post_monsters(\n    &self,\n    ctx: Self::Context,\n    post_body: Monster,\n    x_api_key: String,\n) -> Response<i32> {\n}\n\n```"]
+        #[doc = ""]
+        async fn post_monsters(
+            &self,
+            ctx: Self::Context,
+            post_body: Monster,
+            x_api_key: String,
+        ) -> Response<i32>;
+        #[doc = "```\nasync fn post_monsters_id_portrait(\n    &self,\n    ctx: Self::Context,\n    post_body: Portrait,\n    id: i32,\n) -> Response<()> {\n}\n\n```"]
+        #[doc = ""]
+        async fn post_monsters_id_portrait(
+            &self,
+            ctx: Self::Context,
+            post_body: Portrait,
+            id: i32,
+        ) -> Response<()>;
+        #[doc = "```\nasync fn delete_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<()> {}\n\n```"]
+        #[doc = ""]
+        async fn delete_monsters_id(&self, ctx: Self::Context, id: i32) -> Response<()>;
+    }
+    #[allow(unused_variables)]
+    #[allow(unused_mut)]
+    #[allow(non_snake_case)]
+    #[allow(unreachable_patterns)]
+    fn routes_MonsterApi<Context: Default + Sized + Send + Sync + 'static>(
+        handler: Arc<dyn MonsterApi<Context = Context> + Send + Sync>,
+        root: &str,
+        metrics: Option<&::humblegen_rt::metrics::Metrics>,
+        feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+        middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+        limits: ::humblegen_rt::limits::Limits,
+        idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+    ) -> Box<Router> {
+        let idempotency_store = Arc::clone(idempotency_store);
+        let route_histograms: Vec<Option<Arc<::humblegen_rt::metrics::LatencyHistogram>>> = vec![
+            metrics.map(|m| {
+                m.route_histogram(
+                    ::humblegen_rt::hyper::Method::GET.as_str(),
+                    &format!("{}{}", root, "/monsters"),
+                    None,
+                )
+            }),
+            metrics.map(|m| {
+                m.route_histogram(
+                    ::humblegen_rt::hyper::Method::GET.as_str(),
+                    &format!("{}{}", root, "/monsters/{id}"),
+                    None,
+                )
+            }),
+            metrics.map(|m| {
+                m.route_histogram(
+                    ::humblegen_rt::hyper::Method::POST.as_str(),
+                    &format!("{}{}", root, "/monsters"),
+                    None,
+                )
+            }),
+            metrics.map(|m| {
+                m.route_histogram(
+                    ::humblegen_rt::hyper::Method::POST.as_str(),
+                    &format!("{}{}", root, "/monsters/{id}/portrait"),
+                    None,
+                )
+            }),
+            metrics.map(|m| {
+                m.route_histogram(
+                    ::humblegen_rt::hyper::Method::DELETE.as_str(),
+                    &format!("{}{}", root, "/monsters/{id}"),
+                    None,
+                )
+            }),
+        ];
+        let feature_flags = feature_flags.cloned();
+        let middleware: Arc<[Arc<dyn ::humblegen_rt::middleware::Middleware>]> = middleware.into();
+        Box::new(
+            move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
+                  suffix_start: usize| {
+                let mut segments = [""; 3usize];
+                let num_segments =
+                    server::split_path_segments(&req.uri().path()[suffix_start..], &mut segments)?;
+                let routed = match &segments[..num_segments] {
+                    ["monsters"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                        tracing::debug!(route = "GET /monsters", "route matched");
+                        let span = tracing::error_span!(
+                            "MonsterApi.get_monsters",
+                            status = tracing::field::Empty
+                        );
+                        let handler = Arc::clone(&handler);
+                        let histogram = route_histograms[0usize].clone();
+                        static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                            ::humblegen_rt::middleware::RouteInfo {
+                                service: "MonsterApi",
+                                route: "GET /monsters",
+                                handler: "get_monsters",
+                            };
+                        let accept_encoding =
+                            ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                        let dispatch = ::humblegen_rt::middleware::run(
+                            &middleware,
+                            &ROUTE,
+                            req,
+                            move |mut req| {
+                                Box::pin(async move {
+                                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                    let ctx = {
+                                        let span = tracing::error_span!("interceptor");
+                                        handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                    };
+                                    let query: Option<MonsterQuery> = match req.uri().query() {
+                                        None => None,
+                                        Some(q) => Some(deser_query_serde_urlencoded(q)?),
+                                    };
+                                    drop(req);
+                                    {
+                                        let span = tracing::error_span!("handler");
+                                        Ok(handler_response_to_hyper_response(
+                                            handler.get_monsters(ctx, query).instrument(span).await,
+                                        ))
+                                    }
+                                })
+                            },
+                        );
+                        let dispatch =
+                            ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                        let dispatch =
+                            ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                        Some((
+                            "GET /monsters",
+                            ::humblegen_rt::metrics::observe_latency(
+                                histogram,
+                                server::trace_route(span, dispatch),
+                            ),
+                        ))
+                    }
+                    ["monsters"] if *req.method() == ::humblegen_rt::hyper::Method::POST => {
+                        tracing::debug!(route = "POST /monsters", "route matched");
+                        let span = tracing::error_span!(
+                            "MonsterApi.post_monsters",
+                            status = tracing::field::Empty
+                        );
+                        let handler = Arc::clone(&handler);
+                        let histogram = route_histograms[2usize].clone();
+                        static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                            ::humblegen_rt::middleware::RouteInfo {
+                                service: "MonsterApi",
+                                route: "POST /monsters",
+                                handler: "post_monsters",
+                            };
+                        let accept_encoding =
+                            ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                        let dispatch = ::humblegen_rt::middleware::run(
+                            &middleware,
+                            &ROUTE,
+                            req,
+                            move |mut req| {
+                                Box::pin(async move {
+                                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                    let ctx = {
+                                        let span = tracing::error_span!("interceptor");
+                                        handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                    };
+                                    let x_api_key: String =
+                                        deser_header(req.headers(), "X-Api-Key")?;
+                                    ::humblegen_rt::compression::decompress_request(&mut req)?;
+                                    if let Some(max_body_size) = limits.max_body_size {
+                                        ::humblegen_rt::limits::limit_body(&mut req, max_body_size)
+                                            .await?;
+                                    }
+                                    let post_body: Monster =
+                                        deser_post_data(req.body_mut()).await?;
+                                    drop(req);
+                                    {
+                                        let span = tracing::error_span!("handler");
+                                        Ok(handler_response_to_hyper_response(
+                                            handler
+                                                .post_monsters(ctx, post_body, x_api_key)
+                                                .instrument(span)
+                                                .await,
+                                        ))
+                                    }
+                                })
+                            },
+                        );
+                        let dispatch =
+                            ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                        let dispatch =
+                            ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                        Some((
+                            "POST /monsters",
+                            ::humblegen_rt::metrics::observe_latency(
+                                histogram,
+                                server::trace_route(span, dispatch),
+                            ),
+                        ))
+                    }
+                    ["monsters", id] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                        tracing::debug!(route = "GET /monsters/{id}", "route matched");
+                        let span = tracing::error_span!(
+                            "MonsterApi.get_monsters_id",
+                            id = id,
+                            status = tracing::field::Empty
+                        );
+                        let id: Result<i32, ErrorResponse> = deser_param("id", id);
+                        let handler = Arc::clone(&handler);
+                        let histogram = route_histograms[1usize].clone();
+                        static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                            ::humblegen_rt::middleware::RouteInfo {
+                                service: "MonsterApi",
+                                route: "GET /monsters/{id}",
+                                handler: "get_monsters_id",
+                            };
+                        let accept_encoding =
+                            ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                        let dispatch = ::humblegen_rt::middleware::run(
+                            &middleware,
+                            &ROUTE,
+                            req,
+                            move |mut req| {
+                                Box::pin(async move {
+                                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                    let ctx = {
+                                        let span = tracing::error_span!("interceptor");
+                                        handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                    };
+                                    let id = id?;
+                                    let x_request_id: Option<String> =
+                                        deser_opt_header(req.headers(), "X-Request-Id")?;
+                                    drop(req);
+                                    {
+                                        let span = tracing::error_span!("handler");
+                                        Ok(server::handler_result_response_to_hyper_response(
+                                            handler
+                                                .get_monsters_id(ctx, id, x_request_id)
+                                                .instrument(span)
+                                                .await,
+                                        ))
+                                    }
+                                })
+                            },
+                        );
+                        let dispatch =
+                            ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                        let dispatch =
+                            ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                        Some((
+                            "GET /monsters/{id}",
+                            ::humblegen_rt::metrics::observe_latency(
+                                histogram,
+                                server::trace_route(span, dispatch),
+                            ),
+                        ))
+                    }
+                    ["monsters", id] if *req.method() == ::humblegen_rt::hyper::Method::DELETE => {
+                        tracing::debug!(route = "DELETE /monsters/{id}", "route matched");
+                        let span = tracing::error_span!(
+                            "MonsterApi.delete_monsters_id",
+                            id = id,
+                            status = tracing::field::Empty
+                        );
+                        let id: Result<i32, ErrorResponse> = deser_param("id", id);
+                        let handler = Arc::clone(&handler);
+                        let histogram = route_histograms[4usize].clone();
+                        static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                            ::humblegen_rt::middleware::RouteInfo {
+                                service: "MonsterApi",
+                                route: "DELETE /monsters/{id}",
+                                handler: "delete_monsters_id",
+                            };
+                        let accept_encoding =
+                            ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                        let dispatch = ::humblegen_rt::middleware::run(
+                            &middleware,
+                            &ROUTE,
+                            req,
+                            move |mut req| {
+                                Box::pin(async move {
+                                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                    let ctx = {
+                                        let span = tracing::error_span!("interceptor");
+                                        handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                    };
+                                    let id = id?;
+                                    drop(req);
+                                    {
+                                        let span = tracing::error_span!("handler");
+                                        Ok(server::handler_no_content_response_to_hyper_response(
+                                            handler
+                                                .delete_monsters_id(ctx, id)
+                                                .instrument(span)
+                                                .await,
+                                        ))
+                                    }
+                                })
+                            },
+                        );
+                        let dispatch =
+                            ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                        let dispatch =
+                            ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                        Some((
+                            "DELETE /monsters/{id}",
+                            ::humblegen_rt::metrics::observe_latency(
+                                histogram,
+                                server::trace_route(span, dispatch),
+                            ),
+                        ))
+                    }
+                    ["monsters", id, "portrait"]
+                        if *req.method() == ::humblegen_rt::hyper::Method::POST =>
+                    {
+                        tracing::debug!(route = "POST /monsters/{id}/portrait", "route matched");
+                        let span = tracing::error_span!(
+                            "MonsterApi.post_monsters_id_portrait",
+                            id = id,
+                            status = tracing::field::Empty
+                        );
+                        let id: Result<i32, ErrorResponse> = deser_param("id", id);
+                        let handler = Arc::clone(&handler);
+                        let histogram = route_histograms[3usize].clone();
+                        static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                            ::humblegen_rt::middleware::RouteInfo {
+                                service: "MonsterApi",
+                                route: "POST /monsters/{id}/portrait",
+                                handler: "post_monsters_id_portrait",
+                            };
+                        let accept_encoding =
+                            ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                        let dispatch = ::humblegen_rt::middleware::run(
+                            &middleware,
+                            &ROUTE,
+                            req,
+                            move |mut req| {
+                                Box::pin(async move {
+                                    use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                    let ctx = {
+                                        let span = tracing::error_span!("interceptor");
+                                        handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                    };
+                                    let id = id?;
+                                    ::humblegen_rt::compression::decompress_request(&mut req)?;
+                                    if let Some(max_body_size) = limits.max_body_size {
+                                        ::humblegen_rt::limits::limit_body(&mut req, max_body_size)
+                                            .await?;
+                                    }
+                                    let post_body: Portrait =
+                                        ::humblegen_rt::multipart::deser_multipart(&mut req)
+                                            .await?;
+                                    drop(req);
+                                    {
+                                        let span = tracing::error_span!("handler");
+                                        Ok(server::handler_no_content_response_to_hyper_response(
+                                            handler
+                                                .post_monsters_id_portrait(ctx, post_body, id)
+                                                .instrument(span)
+                                                .await,
+                                        ))
+                                    }
+                                })
+                            },
+                        );
+                        let dispatch =
+                            ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                        let dispatch =
+                            ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                        Some((
+                            "POST /monsters/{id}/portrait",
+                            ::humblegen_rt::metrics::observe_latency(
+                                histogram,
+                                server::trace_route(span, dispatch),
+                            ),
+                        ))
+                    }
+                    _ => None,
+                };
+                routed
+            },
+        )
+    }
+    impl ::humblegen_rt::multipart::FromMultipart for Portrait {
+        fn from_multipart(
+            mut form: ::humblegen_rt::multipart::MultipartForm,
+        ) -> Result<Self, ::humblegen_rt::service_protocol::ErrorResponse> {
+            Ok(Self {
+                caption: form.text("caption")?,
+                image: form.file("image")?,
+                note: form.opt_text("note")?,
+            })
+        }
+    }
+    impl ::humblegen_rt::server::ErrorStatus for MonsterError {
+        fn status_code(&self) -> u16 {
+            match self {
+                MonsterError::NotFound => 404u16,
+                MonsterError::Invalid(..) => 200u16,
+            }
+        }
+    }
+}
+#[cfg(feature = "server")]
+pub use server::*;
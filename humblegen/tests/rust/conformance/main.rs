@@ -0,0 +1,56 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen::conformance::{generate_corpus, Corpus, CorpusConfig};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Decodes all values of the fixture of `type_name` as `T` and expects them to re-encode equally.
+fn check<T: DeserializeOwned + Serialize>(corpus: &Corpus, type_name: &str) {
+    let fixture = corpus.fixture(type_name).expect("fixture exists");
+    assert_eq!(fixture.values.len(), 50);
+    for value in &fixture.values {
+        let decoded: T = serde_json::from_value(value.clone())
+            .unwrap_or_else(|e| panic!("cannot decode {}: {}", value, e));
+        assert_eq!(&serde_json::to_value(&decoded).unwrap(), value);
+    }
+}
+
+fn main() {
+    let spec = humblegen::parse(include_str!("spec.humble").as_bytes()).unwrap();
+    let config = CorpusConfig {
+        seed: 42,
+        values_per_type: 50,
+        ..Default::default()
+    };
+    let corpus = generate_corpus(&spec, &config);
+
+    assert_eq!(
+        corpus
+            .fixtures
+            .iter()
+            .map(|f| f.type_name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Node", "Kind", "Empty"]
+    );
+    check::<protocol::Node>(&corpus, "Node");
+    check::<protocol::Kind>(&corpus, "Kind");
+    check::<protocol::Empty>(&corpus, "Empty");
+
+    // reproducible from the seed
+    let values = |corpus: &Corpus| corpus.fixtures[0].values.clone();
+    assert_eq!(values(&generate_corpus(&spec, &config)), values(&corpus));
+    let other_seed = CorpusConfig { seed: 7, ..config };
+    assert_ne!(
+        values(&generate_corpus(&spec, &other_seed)),
+        values(&corpus)
+    );
+
+    // the recursion ends at the maximum depth
+    fn depth(node: &serde_json::Value) -> usize {
+        let children = node["children"].as_array().unwrap();
+        1 + children.iter().map(depth).max().unwrap_or(0)
+    }
+    let max_depth = corpus.fixtures[0].values.iter().map(depth).max().unwrap();
+    assert!(max_depth <= 1 + config.max_depth as usize);
+    assert!(max_depth > 1);
+}
@@ -0,0 +1,102 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A recursive type with all built-in types."]
+pub struct Node {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub signed: i32,
+    #[doc = ""]
+    pub unsigned: u32,
+    #[doc = ""]
+    pub byte: u8,
+    #[doc = ""]
+    pub weight: f64,
+    #[doc = ""]
+    pub enabled: bool,
+    #[doc = ""]
+    pub created: ::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>,
+    #[doc = ""]
+    pub birthday: ::humblegen_rt::chrono::NaiveDate,
+    #[doc = ""]
+    pub id: ::humblegen_rt::uuid::Uuid,
+    #[doc = ""]
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub payload: Vec<u8>,
+    #[doc = ""]
+    pub chunks: Vec<Vec<u8>>,
+    #[doc = ""]
+    pub unit: (),
+    #[doc = ""]
+    pub parent: Option<String>,
+    #[doc = ""]
+    pub children: Vec<Node>,
+    #[doc = ""]
+    pub attributes: ::std::collections::HashMap<String, i32>,
+    #[doc = ""]
+    pub outcome: Result<Kind, String>,
+    #[doc = ""]
+    pub pair: (String, Option<f64>),
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cleared: Option<Option<i32>>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present_bytes"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_present_bytes")]
+    pub thumbnail: Option<Vec<u8>>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Kind {
+    #[doc = ""]
+    Leaf,
+    #[doc = ""]
+    Weighted(f64),
+    #[doc = ""]
+    Pair(i32, String),
+    #[doc = ""]
+    Tagged {
+        #[doc = ""]
+        tag: String,
+        #[doc = ""]
+        blob: Vec<u8>,
+        #[doc = ""]
+        kinds: Vec<Kind>,
+        #[doc = ""]
+        #[serde(
+            default,
+            deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+        )]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Empty {}
+impl ::humblegen_rt::wire_hash::WireHash for Empty {
+    const WIRE_HASH: &'static str = "08f44b07b5901a25";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Kind {
+    const WIRE_HASH: &'static str = "52f95b1675d524bf";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Node {
+    const WIRE_HASH: &'static str = "31e6aeccddfc7741";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Empty\": \"08f44b07b5901a25\",\n  \"Kind\": \"52f95b1675d524bf\",\n  \"Node\": \"31e6aeccddfc7741\"\n}" ;
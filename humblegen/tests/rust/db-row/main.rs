@@ -0,0 +1,24 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+
+fn main() {
+    assert_eq!(
+        Monster::COLUMNS,
+        &["id", "monster_name", "hp", "tags", "created_at", "updated"]
+    );
+    assert_eq!(Audit::COLUMNS, &["created_at", "updated"]);
+    // column names do not affect wire names
+    let monster = Monster {
+        id: humblegen_rt::uuid::Uuid::nil(),
+        name: "Smaug".to_owned(),
+        hp: None,
+        tags: vec![],
+        created_at: humblegen_rt::chrono::Utc::now(),
+        updated_at: None,
+    };
+    let json = serde_json::to_value(&monster).unwrap();
+    assert_eq!(json["monsterName"], "Smaug");
+    assert_eq!(json["updated_at"], serde_json::Value::Null);
+}
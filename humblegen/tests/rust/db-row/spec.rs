@@ -0,0 +1,49 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[doc = "A row of the `monsters` table."]
+pub struct Monster {
+    #[doc = ""]
+    pub id: ::humblegen_rt::uuid::Uuid,
+    #[cfg_attr(feature = "sqlx", sqlx(rename = "monster_name"))]
+    #[doc = ""]
+    #[serde(rename = "monsterName")]
+    pub name: String,
+    #[doc = ""]
+    pub hp: Option<i32>,
+    #[cfg_attr(feature = "sqlx", sqlx(json))]
+    #[doc = ""]
+    pub tags: Vec<String>,
+    #[doc = ""]
+    pub created_at: ::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>,
+    #[cfg_attr(feature = "sqlx", sqlx(rename = "updated"))]
+    #[doc = ""]
+    pub updated_at: Option<::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>>,
+}
+impl Monster {
+    #[doc = r" The names of the database columns the fields are loaded from, in field order."]
+    pub const COLUMNS: &'static [&'static str] =
+        &["id", "monster_name", "hp", "tags", "created_at", "updated"];
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+#[doc = ""]
+pub struct Audit {
+    #[doc = ""]
+    pub created_at: ::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>,
+    #[cfg_attr(feature = "sqlx", sqlx(rename = "updated"))]
+    #[doc = ""]
+    pub updated_at: Option<::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>>,
+}
+impl Audit {
+    #[doc = r" The names of the database columns the fields are loaded from, in field order."]
+    pub const COLUMNS: &'static [&'static str] = &["created_at", "updated"];
+}
+impl ::humblegen_rt::wire_hash::WireHash for Audit {
+    const WIRE_HASH: &'static str = "dcad0ec61012ba73";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "dc3a73ca2f951396";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Audit\": \"dcad0ec61012ba73\",\n  \"Monster\": \"dc3a73ca2f951396\"\n}";
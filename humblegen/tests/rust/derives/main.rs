@@ -0,0 +1,54 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use std::collections::{BTreeSet, HashSet};
+
+fn stats(hp: i32) -> Stats {
+    Stats {
+        hp,
+        ..Stats::default()
+    }
+}
+
+fn main() {
+    let default = Stats::default();
+    assert_eq!(default.hp, 0);
+    assert_eq!(default.kind, Kind::Unknown);
+    assert_eq!(default.position, Position(0, 0));
+
+    let hashed: HashSet<Stats> = vec![stats(1), stats(1), stats(2)].into_iter().collect();
+    assert_eq!(hashed.len(), 2);
+    let ordered: BTreeSet<Stats> = vec![stats(2), stats(1)].into_iter().collect();
+    assert_eq!(ordered.iter().next(), Some(&stats(1)));
+    assert!(Kind::Unknown < Kind::Dragon(0));
+
+    let patch = StatsPatch {
+        hp: Some(1),
+        tags: None,
+        id: None,
+        position: None,
+        kind: None,
+    };
+    assert_eq!(patch.clone(), patch);
+    assert_eq!(StatsPatch::default().hp, None);
+
+    let monster = Monster {
+        name: "Smaug".to_owned(),
+        weight: 1.5,
+        born: humblegen_rt::chrono::Utc::now(),
+        traits: Default::default(),
+        stats: stats(1),
+        lair: Lair {
+            rooms: vec![Position(1, 2)],
+        },
+    };
+    assert_eq!(monster.clone(), monster);
+    assert_ne!(
+        Monster {
+            weight: 2.0,
+            ..monster.clone()
+        },
+        monster
+    );
+}
@@ -0,0 +1,156 @@
+#[derive(Debug, Clone, PartialEq, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Derives `PartialEq` from the generator options only."]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub weight: f64,
+    #[doc = ""]
+    pub born: ::humblegen_rt::chrono::DateTime<::humblegen_rt::chrono::prelude::Utc>,
+    #[doc = ""]
+    pub traits: ::std::collections::HashMap<String, i32>,
+    #[doc = ""]
+    pub stats: Stats,
+    #[doc = ""]
+    pub lair: Lair,
+}
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    serde :: Deserialize,
+    serde :: Serialize,
+)]
+#[doc = "Can be used as map key."]
+pub struct Stats {
+    #[doc = ""]
+    pub hp: i32,
+    #[doc = ""]
+    pub tags: Vec<String>,
+    #[doc = ""]
+    pub id: ::humblegen_rt::uuid::Uuid,
+    #[doc = ""]
+    pub position: Position,
+    #[doc = ""]
+    pub kind: Kind,
+}
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    serde :: Deserialize,
+    serde :: Serialize,
+)]
+#[doc = "Partial update of `Stats`, only containing the fields to change."]
+pub struct StatsPatch {
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hp: Option<i32>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<::humblegen_rt::uuid::Uuid>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<Kind>,
+}
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    serde :: Deserialize,
+    serde :: Serialize,
+)]
+#[doc = ""]
+pub struct Position(pub i32, pub i32);
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    serde :: Deserialize,
+    serde :: Serialize,
+)]
+#[doc = ""]
+pub enum Kind {
+    #[default]
+    #[doc = ""]
+    Unknown,
+    #[doc = ""]
+    Dragon(i32),
+    #[doc = ""]
+    Goblin {
+        #[doc = ""]
+        clan: Option<String>,
+    },
+}
+#[derive(Debug, Clone, PartialEq, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Lair {
+    #[doc = ""]
+    pub rooms: Vec<Position>,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Kind {
+    const WIRE_HASH: &'static str = "0998b50b9a9096ff";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Lair {
+    const WIRE_HASH: &'static str = "7f6d910370776aca";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "22a7c2f786ecaa3f";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Position {
+    const WIRE_HASH: &'static str = "aa27fcd2c55f07f4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Stats {
+    const WIRE_HASH: &'static str = "e11ec08b9ab1ee5d";
+}
+impl ::humblegen_rt::wire_hash::WireHash for StatsPatch {
+    const WIRE_HASH: &'static str = "1cc9135695cbbd1e";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Kind\": \"0998b50b9a9096ff\",\n  \"Lair\": \"7f6d910370776aca\",\n  \"Monster\": \"22a7c2f786ecaa3f\",\n  \"Position\": \"aa27fcd2c55f07f4\",\n  \"Stats\": \"e11ec08b9ab1ee5d\",\n  \"StatsPatch\": \"1cc9135695cbbd1e\"\n}" ;
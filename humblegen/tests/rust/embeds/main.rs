@@ -9,4 +9,24 @@ fn main() {
         bar: 23,
         foo: "foo".to_owned(),
     };
+
+    // nested embeds are sub-objects on the wire
+    let nested = NestedStruct {
+        bar: 1,
+        embedded_struct: EmbeddedStruct {
+            foo: "foo".to_owned(),
+        },
+        legacy: MyStruct {
+            bar: 2,
+            foo: "bar".to_owned(),
+        },
+    };
+    assert_eq!(
+        serde_json::to_value(&nested).unwrap(),
+        serde_json::json!({
+            "bar": 1,
+            "embedded_struct": {"foo": "foo"},
+            "legacy": {"bar": 2, "foo": "bar"},
+        })
+    );
 }
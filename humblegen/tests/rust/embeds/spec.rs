@@ -23,3 +23,27 @@ pub enum MyEnum {
         foo: String,
     },
 }
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct NestedStruct {
+    #[doc = ""]
+    pub bar: i32,
+    #[doc = ""]
+    pub embedded_struct: EmbeddedStruct,
+    #[doc = ""]
+    pub legacy: MyStruct,
+}
+impl ::humblegen_rt::wire_hash::WireHash for EmbeddedStruct {
+    const WIRE_HASH: &'static str = "437043b44a269c0a";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MyEnum {
+    const WIRE_HASH: &'static str = "0ba05d2af37d2f1c";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MyStruct {
+    const WIRE_HASH: &'static str = "c90ad517d404b16f";
+}
+impl ::humblegen_rt::wire_hash::WireHash for NestedStruct {
+    const WIRE_HASH: &'static str = "e91ac68f256875de";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"EmbeddedStruct\": \"437043b44a269c0a\",\n  \"MyEnum\": \"0ba05d2af37d2f1c\",\n  \"MyStruct\": \"c90ad517d404b16f\",\n  \"NestedStruct\": \"e91ac68f256875de\"\n}" ;
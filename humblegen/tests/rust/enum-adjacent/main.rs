@@ -0,0 +1,55 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn point(x: f64, y: f64) -> Point {
+    Point { x, y }
+}
+
+fn main() {
+    let drawing = Drawing {
+        shape: Shape::Polygon {
+            points: vec![point(0.0, 0.0), point(1.0, 0.0), point(0.0, 1.0)],
+            closed: true,
+        },
+        color: Color::Red,
+        events: vec![Event::Started, Event::Moved { x: 1.0, y: 2.0 }],
+    };
+    let encoded = json!({
+        "shape": {
+            "tag": "Polygon",
+            "contents": {
+                "points": [{"x": 0.0, "y": 0.0}, {"x": 1.0, "y": 0.0}, {"x": 0.0, "y": 1.0}],
+                "closed": true,
+            },
+        },
+        "color": "Red",
+        "events": [{"kind": "Started"}, {"kind": "Moved", "x": 1.0, "y": 2.0}],
+    });
+    assert_eq!(serde_json::to_value(&drawing).unwrap(), encoded);
+    let decoded: Drawing = serde_json::from_value(encoded).unwrap();
+    assert!(matches!(decoded.shape, Shape::Polygon { closed: true, .. }));
+
+    assert_eq!(
+        serde_json::to_value(&Shape::Empty).unwrap(),
+        json!({"tag": "Empty"})
+    );
+    assert_eq!(
+        serde_json::to_value(&Shape::Circle(2.0)).unwrap(),
+        json!({"tag": "Circle", "contents": 2.0})
+    );
+    assert_eq!(
+        serde_json::to_value(&Shape::Line(point(0.0, 0.0), point(1.0, 1.0))).unwrap(),
+        json!({"tag": "Line", "contents": [{"x": 0.0, "y": 0.0}, {"x": 1.0, "y": 1.0}]})
+    );
+    assert!(matches!(
+        serde_json::from_value(json!({"contents": 3.0, "tag": "Circle"})).unwrap(),
+        Shape::Circle(r) if r == 3.0
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"tag": "Empty"})).unwrap(),
+        Shape::Empty
+    ));
+}
@@ -0,0 +1,75 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Point {
+    #[doc = ""]
+    pub x: f64,
+    #[doc = ""]
+    pub y: f64,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(tag = "tag", content = "contents")]
+#[doc = "Adjacently tagged like every enum with complex variants."]
+pub enum Shape {
+    #[doc = ""]
+    Empty,
+    #[doc = ""]
+    Circle(f64),
+    #[doc = ""]
+    Line(Point, Point),
+    #[doc = ""]
+    Polygon {
+        #[doc = ""]
+        points: Vec<Point>,
+        #[doc = ""]
+        closed: bool,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Only simple variants, which are still strings."]
+pub enum Color {
+    #[doc = ""]
+    Red,
+    #[doc = ""]
+    Green,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(tag = "kind")]
+#[doc = "Annotated enums keep their representation."]
+pub enum Event {
+    #[doc = ""]
+    Started,
+    #[doc = ""]
+    Moved {
+        #[doc = ""]
+        x: f64,
+        #[doc = ""]
+        y: f64,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Drawing {
+    #[doc = ""]
+    pub shape: Shape,
+    #[doc = ""]
+    pub color: Color,
+    #[doc = ""]
+    pub events: Vec<Event>,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Color {
+    const WIRE_HASH: &'static str = "6ea6cb4bdaef81ab";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Drawing {
+    const WIRE_HASH: &'static str = "8b073a48edb3eece";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Event {
+    const WIRE_HASH: &'static str = "a3e276ca5e441603";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Point {
+    const WIRE_HASH: &'static str = "3660c8d85a3b4c22";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Shape {
+    const WIRE_HASH: &'static str = "231038b7d06bb1ed";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Color\": \"6ea6cb4bdaef81ab\",\n  \"Drawing\": \"8b073a48edb3eece\",\n  \"Event\": \"a3e276ca5e441603\",\n  \"Point\": \"3660c8d85a3b4c22\",\n  \"Shape\": \"231038b7d06bb1ed\"\n}" ;
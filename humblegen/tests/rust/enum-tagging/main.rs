@@ -0,0 +1,41 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    assert_eq!(
+        serde_json::to_value(&Event::Started).unwrap(),
+        json!({"type": "Started"})
+    );
+    assert_eq!(
+        serde_json::to_value(&Event::Moved { x: 1.0, y: 2.0 }).unwrap(),
+        json!({"type": "moved", "x": 1.0, "y": 2.0})
+    );
+
+    assert_eq!(
+        serde_json::to_value(&Command::Stop).unwrap(),
+        json!({"t": "Stop"})
+    );
+    assert_eq!(
+        serde_json::to_value(&Command::Say("hi".to_owned())).unwrap(),
+        json!({"t": "Say", "c": "hi"})
+    );
+    assert_eq!(
+        serde_json::to_value(&Command::Go(1.0, 2.0)).unwrap(),
+        json!({"t": "Go", "c": [1.0, 2.0]})
+    );
+
+    assert_eq!(serde_json::to_value(&Key::Number(5)).unwrap(), json!(5));
+    assert_eq!(serde_json::to_value(&Key::Missing).unwrap(), json!(null));
+    // untagged values are decoded as the first variant that matches
+    assert!(matches!(
+        serde_json::from_value(json!("ogre")).unwrap(),
+        Key::Name(name) if name == "ogre"
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"x": 0.5, "type": "moved", "y": 1.5})).unwrap(),
+        Event::Moved { x, y } if x == 0.5 && y == 1.5
+    ));
+}
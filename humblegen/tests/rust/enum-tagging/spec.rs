@@ -0,0 +1,48 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(tag = "type")]
+#[doc = "Events of the legacy API, tagged by a `type` field."]
+pub enum Event {
+    #[doc = ""]
+    Started,
+    #[doc = ""]
+    #[serde(rename = "moved")]
+    Moved {
+        #[doc = ""]
+        x: f64,
+        #[doc = ""]
+        y: f64,
+    },
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(tag = "t", content = "c")]
+#[doc = ""]
+pub enum Command {
+    #[doc = ""]
+    Stop,
+    #[doc = ""]
+    Say(String),
+    #[doc = ""]
+    Go(f64, f64),
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(untagged)]
+#[doc = ""]
+pub enum Key {
+    #[doc = ""]
+    Number(i32),
+    #[doc = ""]
+    Name(String),
+    #[doc = ""]
+    Missing,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Command {
+    const WIRE_HASH: &'static str = "ee200fb2aa09ca97";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Event {
+    const WIRE_HASH: &'static str = "33b5906f7c8f59ad";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Key {
+    const WIRE_HASH: &'static str = "08a8fea9b861d4ac";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Command\": \"ee200fb2aa09ca97\",\n  \"Event\": \"33b5906f7c8f59ad\",\n  \"Key\": \"08a8fea9b861d4ac\"\n}" ;
@@ -0,0 +1,30 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let permissions = Permissions::READ | Permissions::ADMIN;
+    assert!(permissions.contains(Permissions::READ));
+    assert!(!permissions.contains(Permissions::WRITE));
+    assert_eq!(Permissions::default(), Permissions::empty());
+
+    // flags are the list of their names, in the order of their definition
+    assert_eq!(
+        serde_json::to_value(&permissions).unwrap(),
+        json!(["Read", "admin"])
+    );
+    assert_eq!(
+        serde_json::to_value(&Permissions::empty()).unwrap(),
+        json!([])
+    );
+    let user: User = serde_json::from_value(json!({
+        "name": "ogre",
+        "permissions": ["Write", "Read", "Write"],
+    }))
+    .unwrap();
+    assert_eq!(user.permissions, Permissions::READ | Permissions::WRITE);
+    assert!(serde_json::from_value::<Permissions>(json!(["Execute"])).is_err());
+    assert!(serde_json::from_value::<Permissions>(json!("Read")).is_err());
+}
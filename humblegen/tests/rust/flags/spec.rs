@@ -0,0 +1,59 @@
+::humblegen_rt::bitflags::bitflags! { # [doc = "Permissions of a user."] pub struct Permissions : u64 { # [doc = ""] const READ = 1 << 0 ; # [doc = ""] const WRITE = 1 << 1 ; # [doc = "May manage other users."] const ADMIN = 1 << 2 ; } }
+impl ::std::default::Default for Permissions {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+impl serde::Serialize for Permissions {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.bits().count_ones() as usize))?;
+        if self.contains(Self::READ) {
+            seq.serialize_element("Read")?;
+        }
+        if self.contains(Self::WRITE) {
+            seq.serialize_element("Write")?;
+        }
+        if self.contains(Self::ADMIN) {
+            seq.serialize_element("admin")?;
+        }
+        seq.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Permissions {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+        let mut flags = Self::empty();
+        for name in names {
+            flags |= match name.as_str() {
+                "Read" => Self::READ,
+                "Write" => Self::WRITE,
+                "admin" => Self::ADMIN,
+                name => {
+                    return Err(serde::de::Error::unknown_variant(
+                        name,
+                        &["Read", "Write", "admin"],
+                    ))
+                }
+            };
+        }
+        Ok(flags)
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct User {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub permissions: Permissions,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Permissions {
+    const WIRE_HASH: &'static str = "4e47bc7580c6b3ae";
+}
+impl ::humblegen_rt::wire_hash::WireHash for User {
+    const WIRE_HASH: &'static str = "b0f48a2b8fc8c554";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Permissions\": \"4e47bc7580c6b3ae\",\n  \"User\": \"b0f48a2b8fc8c554\"\n}";
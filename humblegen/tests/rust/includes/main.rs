@@ -0,0 +1,28 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+
+fn main() {
+    // included definitions are prefixed with their namespace
+    let order: Order = serde_json::from_str(
+        r#"{
+            "id": 1,
+            "customer": {"id": "cus_1", "email": null, "address": {"city": "Berlin", "country": "DE"}},
+            "currency": "Eur"
+        }"#,
+    )
+    .unwrap();
+    let customer: StripeCustomer = order.customer;
+    let address: StripeAddress = customer.address;
+    assert_eq!(address.city, "Berlin");
+    assert!(matches!(order.currency, StripeCurrency::Eur));
+
+    // embeds of included structs
+    let label = ShippingLabel {
+        city: address.city,
+        country: address.country,
+        recipient: "Ada".to_owned(),
+    };
+    assert_eq!(label.recipient, "Ada");
+}
@@ -0,0 +1,35 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let user = User {
+        user_id: 7,
+        email: "ken@example.com".to_owned(),
+        status: Status::Active,
+    };
+    assert_eq!(
+        serde_json::to_value(&user).unwrap(),
+        json!({"userID": 7, "e-mail": "ken@example.com", "status": "ACTIVE"})
+    );
+
+    let status = Status::Suspended {
+        suspended_until: ::humblegen_rt::chrono::NaiveDate::from_ymd(2020, 1, 1),
+    };
+    assert_eq!(
+        serde_json::to_value(&status).unwrap(),
+        json!({"suspended": {"until": "2020-01-01"}})
+    );
+
+    // former names of renamed fields are still accepted
+    let user: User = serde_json::from_value(json!({
+        "userID": 8,
+        "mail": "mothra@example.com",
+        "status": "ACTIVE",
+    }))
+    .unwrap();
+    assert_eq!(user.user_id, 8);
+    assert_eq!(user.email, "mothra@example.com");
+}
@@ -0,0 +1,70 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(try_from = "__UserDeserialize")]
+#[doc = ""]
+pub struct User {
+    #[doc = ""]
+    #[serde(rename = "userID")]
+    pub user_id: i32,
+    #[doc = ""]
+    #[serde(rename = "e-mail")]
+    pub email: String,
+    #[doc = ""]
+    pub status: Status,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+pub struct __UserDeserialize {
+    #[doc = ""]
+    #[serde(rename = "userID")]
+    user_id: i32,
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(rename = "e-mail")]
+    email: Option<String>,
+    #[serde(
+        rename = "mail",
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    __email_renamed_from_0: Option<String>,
+    #[doc = ""]
+    status: Status,
+}
+impl ::std::convert::TryFrom<__UserDeserialize> for User {
+    type Error = String;
+    fn try_from(wire: __UserDeserialize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            user_id: wire.user_id,
+            email: wire
+                .email
+                .or(wire.__email_renamed_from_0)
+                .ok_or_else(|| "missing field `email`".to_owned())?,
+            status: wire.status,
+        })
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Status {
+    #[doc = ""]
+    #[serde(rename = "ACTIVE")]
+    Active,
+    #[doc = ""]
+    #[serde(rename = "suspended")]
+    Suspended {
+        #[doc = ""]
+        #[serde(rename = "until")]
+        suspended_until: ::humblegen_rt::chrono::NaiveDate,
+    },
+}
+impl ::humblegen_rt::wire_hash::WireHash for Status {
+    const WIRE_HASH: &'static str = "4da85bd64c625468";
+}
+impl ::humblegen_rt::wire_hash::WireHash for User {
+    const WIRE_HASH: &'static str = "6d264d5848bea20a";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Status\": \"4da85bd64c625468\",\n  \"User\": \"6d264d5848bea20a\"\n}";
@@ -0,0 +1,58 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl ItemApi for S {
+    type Context = ();
+
+    async fn get_items_type(&self, _ctx: Self::Context, r#type: String) -> Response<Vec<Item>> {
+        Ok(vec![Item {
+            r#type,
+            r#fn: 1,
+            self_: true,
+            r#match: None,
+        }])
+    }
+}
+
+fn main() {
+    // raw identifiers keep their names on the wire
+    let item = Item {
+        r#type: "sword".to_owned(),
+        r#fn: 3,
+        self_: false,
+        r#match: Some("duel".to_owned()),
+    };
+    assert_eq!(
+        serde_json::to_value(&item).unwrap(),
+        json!({"type": "sword", "fn": 3, "self": false, "match": "duel"})
+    );
+
+    let item: Item = serde_json::from_value(json!({
+        "type": "shield",
+        "fn": 4,
+        "self": true,
+        "kind": "joust",
+    }))
+    .unwrap();
+    assert!(item.self_);
+    assert_eq!(item.r#match.as_deref(), Some("joust"));
+
+    // so do suffixed ones
+    assert_eq!(serde_json::to_value(&Owner::Self_).unwrap(), json!("Self"));
+    let owner = Owner::Other {
+        crate_: "humblegen".to_owned(),
+        r#where: "here".to_owned(),
+    };
+    assert_eq!(
+        serde_json::to_value(&owner).unwrap(),
+        json!({"Other": {"crate": "humblegen", "where": "here"}})
+    );
+
+    let _ = S;
+}
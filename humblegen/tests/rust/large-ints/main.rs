@@ -0,0 +1,87 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let monster = Monster {
+        id: u64::MAX,
+        balance: i64::MIN,
+        level: 7,
+        friends: vec![9_007_199_254_740_993],
+        rival: Some(1),
+        mentor: None,
+        visits: vec![("Tokyo".to_owned(), -2)].into_iter().collect(),
+        lair: ("Monster Island".to_owned(), 3),
+        tail_length: 120,
+    };
+    let value = json!({
+        "id": "18446744073709551615",
+        "balance": "-9223372036854775808",
+        "level": 7,
+        "friends": ["9007199254740993"],
+        "rival": "1",
+        "visits": {"Tokyo": "-2"},
+        "lair": ["Monster Island", "3"],
+        "tail_length": "120",
+    });
+    assert_eq!(serde_json::to_value(&monster).unwrap(), value);
+    let decoded: Monster = serde_json::from_value(value).unwrap();
+    assert_eq!(decoded.id, u64::MAX);
+    assert_eq!(decoded.friends, vec![9_007_199_254_740_993]);
+
+    // numbers are accepted as well
+    let decoded: Monster = serde_json::from_value(json!({
+        "id": 1,
+        "balance": -1,
+        "level": 7,
+        "friends": [2, "3"],
+        "rival": null,
+        "mentor": 4,
+        "visits": {},
+        "lair": ["Monster Island", 5],
+        "tail": 6,
+    }))
+    .unwrap();
+    assert_eq!(decoded.friends, vec![2, 3]);
+    assert_eq!(decoded.rival, None);
+    assert_eq!(decoded.mentor, Some(4));
+    assert_eq!(decoded.tail_length, 6);
+    assert!(serde_json::from_value::<Monster>(json!({
+        "id": "-1",
+        "balance": 0,
+        "level": 7,
+        "friends": [],
+        "rival": null,
+        "visits": {},
+        "lair": ["Monster Island", 5],
+        "tail_length": 0,
+    }))
+    .is_err());
+
+    assert_eq!(
+        serde_json::to_value(MonsterId(u64::MAX)).unwrap(),
+        json!("18446744073709551615")
+    );
+
+    let events = vec![
+        Event::Spawned(1),
+        Event::Moved(-2, 3),
+        Event::Renamed {
+            id: 4,
+            name: "Mothra".to_owned(),
+            by: Some(5),
+        },
+        Event::Outcome(Ok(6)),
+    ];
+    let value = json!([
+        {"Spawned": "1"},
+        {"Moved": ["-2", "3"]},
+        {"Renamed": {"id": "4", "name": "Mothra", "by": "5"}},
+        {"Outcome": {"Ok": "6"}},
+    ]);
+    assert_eq!(serde_json::to_value(&events).unwrap(), value);
+    let decoded: Vec<Event> = serde_json::from_value(value).unwrap();
+    assert!(matches!(decoded[1], Event::Moved(-2, 3)));
+}
@@ -0,0 +1,155 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(try_from = "__MonsterDeserialize")]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")]
+    pub id: u64,
+    #[doc = "signed large integers work alike"]
+    #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")]
+    pub balance: i64,
+    #[doc = ""]
+    pub level: i32,
+    #[doc = "large integers are strings nested, too"]
+    #[serde(with = "::humblegen_rt::large_int::As::<Vec<::humblegen_rt::large_int::AsString>>")]
+    pub friends: Vec<u64>,
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::large_int::As::<Option<::humblegen_rt::large_int::AsString>>")]
+    pub rival: Option<u64>,
+    #[doc = ""]
+    #[serde(
+        default,
+        with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::Present<::humblegen_rt::large_int::AsString>>"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mentor: Option<u64>,
+    #[doc = ""]
+    #[serde(
+        with = "::humblegen_rt::large_int::As::<::std::collections::HashMap<::humblegen_rt::large_int::Same,::humblegen_rt::large_int::AsString>>"
+    )]
+    pub visits: ::std::collections::HashMap<String, i64>,
+    #[doc = ""]
+    #[serde(
+        with = "::humblegen_rt::large_int::As::<(::humblegen_rt::large_int::Same,::humblegen_rt::large_int::AsString,)>"
+    )]
+    pub lair: (String, u64),
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")]
+    pub tail_length: i64,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+pub struct __MonsterDeserialize {
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")]
+    id: u64,
+    #[doc = "signed large integers work alike"]
+    #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")]
+    balance: i64,
+    #[doc = ""]
+    level: i32,
+    #[doc = "large integers are strings nested, too"]
+    #[serde(with = "::humblegen_rt::large_int::As::<Vec<::humblegen_rt::large_int::AsString>>")]
+    friends: Vec<u64>,
+    #[doc = ""]
+    #[serde(with = "::humblegen_rt::large_int::As::<Option<::humblegen_rt::large_int::AsString>>")]
+    rival: Option<u64>,
+    #[doc = ""]
+    #[serde(
+        default,
+        with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::Present<::humblegen_rt::large_int::AsString>>"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mentor: Option<u64>,
+    #[doc = ""]
+    #[serde(
+        with = "::humblegen_rt::large_int::As::<::std::collections::HashMap<::humblegen_rt::large_int::Same,::humblegen_rt::large_int::AsString>>"
+    )]
+    visits: ::std::collections::HashMap<String, i64>,
+    #[doc = ""]
+    #[serde(
+        with = "::humblegen_rt::large_int::As::<(::humblegen_rt::large_int::Same,::humblegen_rt::large_int::AsString,)>"
+    )]
+    lair: (String, u64),
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::Present<::humblegen_rt::large_int::AsString>>::deserialize"
+    )]
+    tail_length: Option<i64>,
+    #[serde(
+        rename = "tail",
+        default,
+        deserialize_with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::Present<::humblegen_rt::large_int::AsString>>::deserialize"
+    )]
+    __tail_length_renamed_from_0: Option<i64>,
+}
+impl ::std::convert::TryFrom<__MonsterDeserialize> for Monster {
+    type Error = String;
+    fn try_from(wire: __MonsterDeserialize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: wire.id,
+            balance: wire.balance,
+            level: wire.level,
+            friends: wire.friends,
+            rival: wire.rival,
+            mentor: wire.mentor,
+            visits: wire.visits,
+            lair: wire.lair,
+            tail_length: wire
+                .tail_length
+                .or(wire.__tail_length_renamed_from_0)
+                .ok_or_else(|| "missing field `tail_length`".to_owned())?,
+        })
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct MonsterId(
+    #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")] pub u64,
+);
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Event {
+    #[doc = ""]
+    Spawned(
+        #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")] u64,
+    ),
+    #[doc = ""]
+    Moved(
+        #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")] i64,
+        #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")] i64,
+    ),
+    #[doc = ""]
+    Renamed {
+        #[doc = ""]
+        #[serde(with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::AsString>")]
+        id: u64,
+        #[doc = ""]
+        name: String,
+        #[doc = ""]
+        #[serde(
+            default,
+            with = "::humblegen_rt::large_int::As::<::humblegen_rt::large_int::Present<::humblegen_rt::large_int::AsString>>"
+        )]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        by: Option<u64>,
+    },
+    #[doc = ""]
+    Outcome(
+        #[serde(
+            with = "::humblegen_rt::large_int::As::<Result<::humblegen_rt::large_int::AsString,::humblegen_rt::large_int::Same>>"
+        )]
+        Result<u64, String>,
+    ),
+}
+impl ::humblegen_rt::wire_hash::WireHash for Event {
+    const WIRE_HASH: &'static str = "7d15424fd9c60d96";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "cffca4c780dab316";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterId {
+    const WIRE_HASH: &'static str = "c7ead1f3902420ff";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Event\": \"7d15424fd9c60d96\",\n  \"Monster\": \"cffca4c780dab316\",\n  \"MonsterId\": \"c7ead1f3902420ff\"\n}" ;
@@ -0,0 +1,24 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    // literals are the exact wire names of the variants
+    assert_eq!(serde_json::to_value(&Status::Active).unwrap(), json!("active"));
+    assert_eq!(serde_json::to_value(&Status::OnHold).unwrap(), json!("on-hold"));
+    assert_eq!(
+        serde_json::to_value(&Status::DeletedByAdmin).unwrap(),
+        json!("deleted_by_admin")
+    );
+    assert!(serde_json::from_value::<Status>(json!("OnHold")).is_err());
+
+    let account: Account = serde_json::from_value(json!({
+        "status": "on-hold",
+        "plan": "enterprise",
+    }))
+    .unwrap();
+    assert!(matches!(account.status, Status::OnHold));
+    assert!(matches!(account.plan, Plan::Unknown));
+}
@@ -0,0 +1,80 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "The status of an account, as named by the billing provider."]
+pub enum Status {
+    #[doc = ""]
+    #[serde(rename = "active")]
+    Active,
+    #[doc = ""]
+    #[serde(rename = "on-hold")]
+    OnHold,
+    #[doc = ""]
+    #[serde(rename = "deleted_by_admin")]
+    DeletedByAdmin,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(from = "__PlanDeserialize")]
+#[non_exhaustive]
+#[doc = ""]
+pub enum Plan {
+    #[doc = ""]
+    #[serde(rename = "free")]
+    Free,
+    #[doc = ""]
+    #[serde(rename = "pro")]
+    Pro,
+    #[doc = r" A variant unknown to this version of the spec, e.g. added by a newer server."]
+    #[doc = r" It cannot be serialized."]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+pub enum __PlanKnown {
+    #[doc = ""]
+    #[serde(rename = "free")]
+    Free,
+    #[doc = ""]
+    #[serde(rename = "pro")]
+    Pro,
+}
+impl ::humblegen_rt::serialization_helpers::KnownVariants for __PlanKnown {
+    const TAG: Option<&'static str> = None;
+    const NAMES: &'static [&'static str] = &["free", "pro"];
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+#[serde(untagged)]
+pub enum __PlanDeserialize {
+    Known(__PlanKnown),
+    Unknown(::humblegen_rt::serialization_helpers::UnknownVariant<__PlanKnown>),
+}
+impl ::std::convert::From<__PlanDeserialize> for Plan {
+    fn from(wire: __PlanDeserialize) -> Self {
+        match wire {
+            __PlanDeserialize::Known(known) => match known {
+                __PlanKnown::Free => Plan::Free,
+                __PlanKnown::Pro => Plan::Pro,
+            },
+            __PlanDeserialize::Unknown(_) => Plan::Unknown,
+        }
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Account {
+    #[doc = ""]
+    pub status: Status,
+    #[doc = ""]
+    pub plan: Plan,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Account {
+    const WIRE_HASH: &'static str = "4ef9462b6cdec695";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Plan {
+    const WIRE_HASH: &'static str = "6a447fc5d0ec04d4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Status {
+    const WIRE_HASH: &'static str = "cee4d13502ea90b3";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Account\": \"4ef9462b6cdec695\",\n  \"Plan\": \"6a447fc5d0ec04d4\",\n  \"Status\": \"cee4d13502ea90b3\"\n}" ;
@@ -0,0 +1,31 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let territory = Territory {
+        size: 4,
+        monsters: vec![(Coordinates(0, 1), "Godzilla".to_owned())],
+        history: vec![vec![], vec![(Coordinates(2, 3), "Mothra".to_owned())]],
+        population: vec![(1954, 1)],
+        names: vec![("home".to_owned(), Coordinates(0, 0))]
+            .into_iter()
+            .collect(),
+    };
+    let value = json!({
+        "size": 4,
+        "monsters": [[[0, 1], "Godzilla"]],
+        "history": [[], [[[2, 3], "Mothra"]]],
+        "population": [[1954, 1]],
+        "names": {"home": [0, 0]},
+    });
+    assert_eq!(serde_json::to_value(&territory).unwrap(), value);
+    let territory: Territory = serde_json::from_value(value).unwrap();
+    assert_eq!(territory.monsters[0].1, "Godzilla");
+
+    let change: Change =
+        serde_json::from_value(json!({"Moved": [[[0, 1], [1, 1]]]})).unwrap();
+    assert!(matches!(change, Change::Moved(entries) if entries[0].1 .0 == 1));
+}
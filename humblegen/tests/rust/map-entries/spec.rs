@@ -0,0 +1,34 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Coordinates(pub i32, pub i32);
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Territory {
+    #[doc = ""]
+    pub size: u32,
+    #[doc = "monsters by their position"]
+    pub monsters: Vec<(Coordinates, String)>,
+    #[doc = "complex keys work nested, too"]
+    pub history: Vec<Vec<(Coordinates, String)>>,
+    #[doc = "integer keys are entries as well"]
+    pub population: Vec<(i32, u32)>,
+    #[doc = "string keys remain objects"]
+    pub names: ::std::collections::HashMap<String, Coordinates>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Change {
+    #[doc = ""]
+    Moved(Vec<(Coordinates, Coordinates)>),
+}
+impl ::humblegen_rt::wire_hash::WireHash for Change {
+    const WIRE_HASH: &'static str = "f320ba53e76dcab5";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Coordinates {
+    const WIRE_HASH: &'static str = "aa27fcd2c55f07f4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Territory {
+    const WIRE_HASH: &'static str = "8d6a48d11233d053";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Change\": \"f320ba53e76dcab5\",\n  \"Coordinates\": \"aa27fcd2c55f07f4\",\n  \"Territory\": \"8d6a48d11233d053\"\n}" ;
@@ -0,0 +1,32 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    // the definitions of modules are available by their names in the module
+    let invoice = billing::Invoice {
+        lines: vec![billing::LineItem {
+            amount: 42,
+            currency: shipping::Currency::Eur,
+        }],
+        status: billing::Status::Open,
+    };
+    let customer = Customer {
+        name: "ogre".to_owned(),
+        invoices: vec![invoice],
+    };
+    // and by their generated names, which do not affect the wire format
+    let _: &BillingInvoice = &customer.invoices[0];
+    assert_eq!(
+        serde_json::to_value(&customer).unwrap(),
+        json!({
+            "name": "ogre",
+            "invoices": [{
+                "lines": [{ "amount": 42, "currency": "Eur" }],
+                "status": "Open",
+            }],
+        })
+    );
+}
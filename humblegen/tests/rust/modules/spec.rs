@@ -0,0 +1,67 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "An invoice of a customer."]
+pub struct BillingInvoice {
+    #[doc = ""]
+    pub lines: Vec<BillingLineItem>,
+    #[doc = ""]
+    pub status: BillingStatus,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct BillingLineItem {
+    #[doc = ""]
+    pub amount: u64,
+    #[doc = ""]
+    pub currency: ShippingCurrency,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum BillingStatus {
+    #[doc = ""]
+    Open,
+    #[doc = ""]
+    Paid,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum ShippingCurrency {
+    #[doc = ""]
+    Eur,
+    #[doc = ""]
+    Usd,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Customer {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub invoices: Vec<BillingInvoice>,
+}
+#[doc = "The definitions of `mod billing`."]
+pub mod billing {
+    pub use super::BillingInvoice as Invoice;
+    pub use super::BillingLineItem as LineItem;
+    pub use super::BillingStatus as Status;
+}
+#[doc = "The definitions of `mod shipping`."]
+pub mod shipping {
+    pub use super::ShippingCurrency as Currency;
+}
+impl ::humblegen_rt::wire_hash::WireHash for BillingInvoice {
+    const WIRE_HASH: &'static str = "44d940754731b45e";
+}
+impl ::humblegen_rt::wire_hash::WireHash for BillingLineItem {
+    const WIRE_HASH: &'static str = "f789e742f1f4a12f";
+}
+impl ::humblegen_rt::wire_hash::WireHash for BillingStatus {
+    const WIRE_HASH: &'static str = "d466f8c7514d4ed3";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Customer {
+    const WIRE_HASH: &'static str = "20c4ee5ceb30bcb6";
+}
+impl ::humblegen_rt::wire_hash::WireHash for ShippingCurrency {
+    const WIRE_HASH: &'static str = "548b4664b4afe3fd";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"BillingInvoice\": \"44d940754731b45e\",\n  \"BillingLineItem\": \"f789e742f1f4a12f\",\n  \"BillingStatus\": \"d466f8c7514d4ed3\",\n  \"Customer\": \"20c4ee5ceb30bcb6\",\n  \"ShippingCurrency\": \"548b4664b4afe3fd\"\n}" ;
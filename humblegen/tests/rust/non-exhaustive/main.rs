@@ -0,0 +1,68 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    // known variants round-trip unchanged
+    let event = AuditEvent::Renamed {
+        from: "a".to_owned(),
+        to: "b".to_owned(),
+    };
+    let value = serde_json::to_value(&event).unwrap();
+    assert_eq!(value, json!({"Renamed": {"from": "a", "to": "b"}}));
+    assert!(matches!(
+        serde_json::from_value(value).unwrap(),
+        AuditEvent::Renamed { from, to } if from == "a" && to == "b"
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!("LoggedIn")).unwrap(),
+        AuditEvent::LoggedIn
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"Deleted": 7})).unwrap(),
+        AuditEvent::Deleted(7)
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"Moved": [1, -1]})).unwrap(),
+        AuditEvent::Moved(1, -1)
+    ));
+
+    // unknown variants, simple or with content, decode as `Unknown`
+    assert!(matches!(
+        serde_json::from_value(json!("LoggedOut")).unwrap(),
+        AuditEvent::Unknown
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"Archived": {"at": 1}})).unwrap(),
+        AuditEvent::Unknown
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"type": "Alarm", "level": 3})).unwrap(),
+        Notification::Unknown
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"type": "Message", "text": "hi"})).unwrap(),
+        Notification::Message { text } if text == "hi"
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"t": "Square", "c": 2.0})).unwrap(),
+        Shape::Unknown
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!({"t": "Circle", "c": 2.0})).unwrap(),
+        Shape::Circle(r) if r == 2.0
+    ));
+
+    // known variants with malformed content are still rejected
+    assert!(serde_json::from_value::<AuditEvent>(json!({"Deleted": "seven"})).is_err());
+    assert!(serde_json::from_value::<Notification>(json!({"type": "Message"})).is_err());
+    assert!(serde_json::from_value::<Shape>(json!({"t": "Circle", "c": "big"})).is_err());
+    // as are values that are no variant at all
+    assert!(serde_json::from_value::<AuditEvent>(json!(5)).is_err());
+    assert!(serde_json::from_value::<Notification>(json!({"text": "hi"})).is_err());
+
+    // unknown variants cannot be serialized
+    assert!(serde_json::to_value(&AuditEvent::Unknown).is_err());
+}
@@ -0,0 +1,173 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(from = "__AuditEventDeserialize")]
+#[non_exhaustive]
+#[doc = "Events of the audit log, which gains new kinds over time."]
+pub enum AuditEvent {
+    #[doc = ""]
+    LoggedIn,
+    #[doc = ""]
+    Renamed {
+        #[doc = ""]
+        from: String,
+        #[doc = ""]
+        to: String,
+    },
+    #[doc = ""]
+    Deleted(u32),
+    #[doc = ""]
+    Moved(i32, i32),
+    #[doc = r" A variant unknown to this version of the spec, e.g. added by a newer server."]
+    #[doc = r" It cannot be serialized."]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+pub enum __AuditEventKnown {
+    #[doc = ""]
+    LoggedIn,
+    #[doc = ""]
+    Renamed {
+        #[doc = ""]
+        from: String,
+        #[doc = ""]
+        to: String,
+    },
+    #[doc = ""]
+    Deleted(u32),
+    #[doc = ""]
+    Moved(i32, i32),
+}
+impl ::humblegen_rt::serialization_helpers::KnownVariants for __AuditEventKnown {
+    const TAG: Option<&'static str> = None;
+    const NAMES: &'static [&'static str] = &["LoggedIn", "Renamed", "Deleted", "Moved"];
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+#[serde(untagged)]
+pub enum __AuditEventDeserialize {
+    Known(__AuditEventKnown),
+    Unknown(::humblegen_rt::serialization_helpers::UnknownVariant<__AuditEventKnown>),
+}
+impl ::std::convert::From<__AuditEventDeserialize> for AuditEvent {
+    fn from(wire: __AuditEventDeserialize) -> Self {
+        match wire {
+            __AuditEventDeserialize::Known(known) => match known {
+                __AuditEventKnown::LoggedIn => AuditEvent::LoggedIn,
+                __AuditEventKnown::Renamed { from, to } => AuditEvent::Renamed { from, to },
+                __AuditEventKnown::Deleted(e0) => AuditEvent::Deleted(e0),
+                __AuditEventKnown::Moved(e0, e1) => AuditEvent::Moved(e0, e1),
+            },
+            __AuditEventDeserialize::Unknown(_) => AuditEvent::Unknown,
+        }
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(from = "__NotificationDeserialize")]
+#[serde(tag = "type")]
+#[non_exhaustive]
+#[doc = ""]
+pub enum Notification {
+    #[doc = ""]
+    Ping,
+    #[doc = ""]
+    Message {
+        #[doc = ""]
+        text: String,
+    },
+    #[doc = r" A variant unknown to this version of the spec, e.g. added by a newer server."]
+    #[doc = r" It cannot be serialized."]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+#[serde(tag = "type")]
+pub enum __NotificationKnown {
+    #[doc = ""]
+    Ping,
+    #[doc = ""]
+    Message {
+        #[doc = ""]
+        text: String,
+    },
+}
+impl ::humblegen_rt::serialization_helpers::KnownVariants for __NotificationKnown {
+    const TAG: Option<&'static str> = Some("type");
+    const NAMES: &'static [&'static str] = &["Ping", "Message"];
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+#[serde(untagged)]
+pub enum __NotificationDeserialize {
+    Known(__NotificationKnown),
+    Unknown(::humblegen_rt::serialization_helpers::UnknownVariant<__NotificationKnown>),
+}
+impl ::std::convert::From<__NotificationDeserialize> for Notification {
+    fn from(wire: __NotificationDeserialize) -> Self {
+        match wire {
+            __NotificationDeserialize::Known(known) => match known {
+                __NotificationKnown::Ping => Notification::Ping,
+                __NotificationKnown::Message { text } => Notification::Message { text },
+            },
+            __NotificationDeserialize::Unknown(_) => Notification::Unknown,
+        }
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(from = "__ShapeDeserialize")]
+#[serde(tag = "t", content = "c")]
+#[non_exhaustive]
+#[doc = ""]
+pub enum Shape {
+    #[doc = ""]
+    Empty,
+    #[doc = ""]
+    Circle(f64),
+    #[doc = r" A variant unknown to this version of the spec, e.g. added by a newer server."]
+    #[doc = r" It cannot be serialized."]
+    #[serde(skip_serializing)]
+    Unknown,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+#[serde(tag = "t", content = "c")]
+pub enum __ShapeKnown {
+    #[doc = ""]
+    Empty,
+    #[doc = ""]
+    Circle(f64),
+}
+impl ::humblegen_rt::serialization_helpers::KnownVariants for __ShapeKnown {
+    const TAG: Option<&'static str> = Some("t");
+    const NAMES: &'static [&'static str] = &["Empty", "Circle"];
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+#[serde(untagged)]
+pub enum __ShapeDeserialize {
+    Known(__ShapeKnown),
+    Unknown(::humblegen_rt::serialization_helpers::UnknownVariant<__ShapeKnown>),
+}
+impl ::std::convert::From<__ShapeDeserialize> for Shape {
+    fn from(wire: __ShapeDeserialize) -> Self {
+        match wire {
+            __ShapeDeserialize::Known(known) => match known {
+                __ShapeKnown::Empty => Shape::Empty,
+                __ShapeKnown::Circle(e0) => Shape::Circle(e0),
+            },
+            __ShapeDeserialize::Unknown(_) => Shape::Unknown,
+        }
+    }
+}
+impl ::humblegen_rt::wire_hash::WireHash for AuditEvent {
+    const WIRE_HASH: &'static str = "7c1ff235c13bde8a";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Notification {
+    const WIRE_HASH: &'static str = "50909d7b74ffb1c1";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Shape {
+    const WIRE_HASH: &'static str = "219e87708a10419d";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"AuditEvent\": \"7c1ff235c13bde8a\",\n  \"Notification\": \"50909d7b74ffb1c1\",\n  \"Shape\": \"219e87708a10419d\"\n}" ;
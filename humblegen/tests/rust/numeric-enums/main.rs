@@ -0,0 +1,38 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    assert_eq!(serde_json::to_value(&Priority::Low).unwrap(), json!(1));
+    assert_eq!(serde_json::to_value(&Priority::Normal).unwrap(), json!(2));
+    assert_eq!(serde_json::to_value(&Priority::High).unwrap(), json!(10));
+    assert_eq!(serde_json::to_value(&Priority::Debug).unwrap(), json!(-1));
+    assert!(matches!(
+        serde_json::from_value(json!(-1)).unwrap(),
+        Priority::Debug
+    ));
+    assert!(matches!(
+        serde_json::from_value(json!(2)).unwrap(),
+        Priority::Normal
+    ));
+    assert!(serde_json::from_value::<Priority>(json!(3)).is_err());
+    assert!(serde_json::from_value::<Priority>(json!("Low")).is_err());
+    assert_eq!(Priority::High as i64, 10);
+
+    // unknown discriminants of non-exhaustive enums decode as `Unknown`
+    assert!(matches!(
+        serde_json::from_value(json!(7)).unwrap(),
+        SensorState::Unknown
+    ));
+    assert!(serde_json::to_value(&SensorState::Unknown).is_err());
+
+    let reading: Reading = serde_json::from_value(json!({"priority": 10, "state": 1})).unwrap();
+    assert!(matches!(reading.priority, Priority::High));
+    assert!(matches!(reading.state, SensorState::On));
+
+    // enums that are not `@numeric` keep their names on the wire
+    assert_eq!(serde_json::to_value(&Color::Green).unwrap(), json!("Green"));
+    assert_eq!(Color::Green as i32, 2);
+}
@@ -0,0 +1,102 @@
+#[derive(Debug, Clone)]
+#[doc = "Priorities as understood by embedded devices."]
+pub enum Priority {
+    #[doc = ""]
+    Low = 1,
+    #[doc = ""]
+    Normal,
+    #[doc = ""]
+    High = 10,
+    #[doc = ""]
+    Debug = -1,
+}
+impl serde::Serialize for Priority {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let discriminant: i64 = match self {
+            Priority::Low => 1i64,
+            Priority::Normal => 2i64,
+            Priority::High => 10i64,
+            Priority::Debug => -1i64,
+        };
+        serializer.serialize_i64(discriminant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for Priority {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match <i64 as serde::Deserialize>::deserialize(deserializer)? {
+            1i64 => Ok(Priority::Low),
+            2i64 => Ok(Priority::Normal),
+            10i64 => Ok(Priority::High),
+            -1i64 => Ok(Priority::Debug),
+            discriminant => Err(serde::de::Error::custom(format!(
+                "invalid discriminant `{}` of `Priority`",
+                discriminant
+            ))),
+        }
+    }
+}
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+#[doc = "Sensor states, which newer firmware extends."]
+pub enum SensorState {
+    #[doc = ""]
+    Off,
+    #[doc = ""]
+    On,
+    #[doc = r" A variant unknown to this version of the spec, e.g. added by a newer server."]
+    #[doc = r" It cannot be serialized."]
+    Unknown,
+}
+impl serde::Serialize for SensorState {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let discriminant: i64 = match self {
+            SensorState::Off => 0i64,
+            SensorState::On => 1i64,
+            SensorState::Unknown => {
+                return Err(serde::ser::Error::custom(
+                    "unknown variant of `SensorState` cannot be serialized",
+                ))
+            }
+        };
+        serializer.serialize_i64(discriminant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for SensorState {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match <i64 as serde::Deserialize>::deserialize(deserializer)? {
+            0i64 => Ok(SensorState::Off),
+            1i64 => Ok(SensorState::On),
+            _ => Ok(SensorState::Unknown),
+        }
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Colors keep their names on the wire, discriminants are for casts only."]
+pub enum Color {
+    #[doc = ""]
+    Red = 1,
+    #[doc = ""]
+    Green = 2,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Reading {
+    #[doc = ""]
+    pub priority: Priority,
+    #[doc = ""]
+    pub state: SensorState,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Color {
+    const WIRE_HASH: &'static str = "6ea6cb4bdaef81ab";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Priority {
+    const WIRE_HASH: &'static str = "c89230f1fb0b7275";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Reading {
+    const WIRE_HASH: &'static str = "7a45522c85e37fd5";
+}
+impl ::humblegen_rt::wire_hash::WireHash for SensorState {
+    const WIRE_HASH: &'static str = "584baf741ea83f9c";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Color\": \"6ea6cb4bdaef81ab\",\n  \"Priority\": \"c89230f1fb0b7275\",\n  \"Reading\": \"7a45522c85e37fd5\",\n  \"SensorState\": \"584baf741ea83f9c\"\n}" ;
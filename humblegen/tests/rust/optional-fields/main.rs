@@ -0,0 +1,40 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    // absent fields are omitted, `option[T]` fields are `null`
+    let patch = MonsterPatch {
+        name: None,
+        nickname: Some(None),
+        portrait: Some(vec![1, 2, 3]),
+        lair: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&patch).unwrap(),
+        json!({"nickname": null, "portrait": "AQID", "lair": null})
+    );
+
+    // absent and `null` values are different
+    let patch: MonsterPatch = serde_json::from_value(json!({"lair": null})).unwrap();
+    assert!(patch.name.is_none());
+    assert!(patch.nickname.is_none());
+    assert!(patch.portrait.is_none());
+    let patch: MonsterPatch =
+        serde_json::from_value(json!({"name": "Mothra", "nick": null, "lair": "Infant Island"}))
+            .unwrap();
+    assert_eq!(patch.name.as_deref(), Some("Mothra"));
+    assert_eq!(patch.nickname, Some(None));
+    assert_eq!(patch.lair.as_deref(), Some("Infant Island"));
+
+    let change = Change::Patched { id: 1, name: None };
+    assert_eq!(
+        serde_json::to_value(&change).unwrap(),
+        json!({"Patched": {"id": 1}})
+    );
+    let change: Change =
+        serde_json::from_value(json!({"Patched": {"id": 1, "name": "Rodan"}})).unwrap();
+    assert!(matches!(change, Change::Patched { name: Some(name), .. } if name == "Rodan"));
+}
@@ -0,0 +1,97 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(try_from = "__MonsterPatchDeserialize")]
+#[doc = "A partial update of a monster."]
+pub struct MonsterPatch {
+    #[doc = "absent if unchanged"]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[doc = "absent if unchanged, `null` to remove the nickname"]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<Option<String>>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present_bytes"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_present_bytes")]
+    pub portrait: Option<Vec<u8>>,
+    #[doc = "always present, but may be `null`"]
+    pub lair: Option<String>,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+pub struct __MonsterPatchDeserialize {
+    #[doc = "absent if unchanged"]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    nickname: Option<Option<String>>,
+    #[serde(
+        rename = "nick",
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    __nickname_renamed_from_0: Option<Option<String>>,
+    #[doc = ""]
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present_bytes"
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_present_bytes")]
+    portrait: Option<Vec<u8>>,
+    #[doc = "always present, but may be `null`"]
+    lair: Option<String>,
+}
+impl ::std::convert::TryFrom<__MonsterPatchDeserialize> for MonsterPatch {
+    type Error = String;
+    fn try_from(wire: __MonsterPatchDeserialize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: wire.name,
+            nickname: wire.nickname.or(wire.__nickname_renamed_from_0),
+            portrait: wire.portrait,
+            lair: wire.lair,
+        })
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Change {
+    #[doc = ""]
+    Patched {
+        #[doc = ""]
+        id: i32,
+        #[doc = ""]
+        #[serde(
+            default,
+            deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+        )]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+}
+impl ::humblegen_rt::wire_hash::WireHash for Change {
+    const WIRE_HASH: &'static str = "7132ccfb76c22160";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterPatch {
+    const WIRE_HASH: &'static str = "8ec94c476c7cf826";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Change\": \"7132ccfb76c22160\",\n  \"MonsterPatch\": \"8ec94c476c7cf826\"\n}";
@@ -0,0 +1,23 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    // only the fields to change are present
+    let patch = MonsterPatch {
+        name: None,
+        nickname: Some(None),
+        hp: Some(42),
+    };
+    assert_eq!(
+        serde_json::to_value(&patch).unwrap(),
+        json!({"nick": null, "hp": 42})
+    );
+
+    let patch: MonsterPatch = serde_json::from_value(json!({"name": "Mothra"})).unwrap();
+    assert_eq!(patch.name.as_deref(), Some("Mothra"));
+    assert!(patch.nickname.is_none());
+    assert!(patch.hp.is_none());
+}
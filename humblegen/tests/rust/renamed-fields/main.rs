@@ -0,0 +1,80 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn decode(value: serde_json::Value) -> Result<Monster, serde_json::Error> {
+    serde_json::from_value(value)
+}
+
+fn main() {
+    // current keys
+    let monster = decode(json!({
+        "display_name": "Godzilla",
+        "nickname": "Goji",
+        "portrait": "AQI=",
+        "age": 70,
+    }))
+    .unwrap();
+    assert_eq!(monster.display_name, "Godzilla");
+    assert_eq!(monster.nickname.as_deref(), Some("Goji"));
+    assert_eq!(monster.portrait, vec![1, 2]);
+
+    // former keys
+    let monster = decode(json!({
+        "name": "Mothra",
+        "alias": "Queen",
+        "picture": "AQI=",
+        "age": 5,
+    }))
+    .unwrap();
+    assert_eq!(monster.display_name, "Mothra");
+    assert_eq!(monster.nickname.as_deref(), Some("Queen"));
+    assert_eq!(monster.portrait, vec![1, 2]);
+
+    // the current key takes precedence, then the newest former key
+    let monster = decode(json!({
+        "display_name": "Rodan",
+        "name": "Radon",
+        "nick": "Ro",
+        "alias": "Rad",
+        "portrait": "",
+        "age": 1,
+    }))
+    .unwrap();
+    assert_eq!(monster.display_name, "Rodan");
+    assert_eq!(monster.nickname.as_deref(), Some("Ro"));
+
+    // a `null` current key takes precedence as well, absent optional fields are `None`
+    let monster = decode(json!({
+        "display_name": "Rodan",
+        "nickname": null,
+        "nick": "Ro",
+        "portrait": "",
+        "age": 1,
+    }))
+    .unwrap();
+    assert_eq!(monster.nickname, None);
+    let monster = decode(json!({"display_name": "Rodan", "portrait": "", "age": 1})).unwrap();
+    assert_eq!(monster.nickname, None);
+
+    // required fields are required under either key
+    let err = decode(json!({"nickname": null, "portrait": "", "age": 1})).unwrap_err();
+    assert!(
+        err.to_string().contains("missing field `display_name`"),
+        "{}",
+        err
+    );
+
+    // serialization uses the current keys only
+    assert_eq!(
+        serde_json::to_value(&monster).unwrap(),
+        json!({
+            "display_name": "Rodan",
+            "nickname": null,
+            "portrait": "",
+            "age": 1,
+        })
+    );
+}
@@ -0,0 +1,86 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(try_from = "__MonsterDeserialize")]
+#[doc = ""]
+pub struct Monster {
+    #[doc = "Shown in the UI."]
+    pub display_name: String,
+    #[doc = ""]
+    pub nickname: Option<String>,
+    #[doc = ""]
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub portrait: Vec<u8>,
+    #[doc = ""]
+    pub age: i32,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+pub struct __MonsterDeserialize {
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    display_name: Option<String>,
+    #[serde(
+        rename = "name",
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    __display_name_renamed_from_0: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    nickname: Option<Option<String>>,
+    #[serde(
+        rename = "nick",
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    __nickname_renamed_from_0: Option<Option<String>>,
+    #[serde(
+        rename = "alias",
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    __nickname_renamed_from_1: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present_bytes"
+    )]
+    portrait: Option<Vec<u8>>,
+    #[serde(
+        rename = "picture",
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present_bytes"
+    )]
+    __portrait_renamed_from_0: Option<Vec<u8>>,
+    #[doc = ""]
+    age: i32,
+}
+impl ::std::convert::TryFrom<__MonsterDeserialize> for Monster {
+    type Error = String;
+    fn try_from(wire: __MonsterDeserialize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            display_name: wire
+                .display_name
+                .or(wire.__display_name_renamed_from_0)
+                .ok_or_else(|| "missing field `display_name`".to_owned())?,
+            nickname: wire
+                .nickname
+                .or(wire.__nickname_renamed_from_0)
+                .or(wire.__nickname_renamed_from_1)
+                .unwrap_or(None),
+            portrait: wire
+                .portrait
+                .or(wire.__portrait_renamed_from_0)
+                .ok_or_else(|| "missing field `portrait`".to_owned())?,
+            age: wire.age,
+        })
+    }
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "f89fa79be3d09dc5";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str = "{\n  \"Monster\": \"f89fa79be3d09dc5\"\n}";
@@ -0,0 +1,30 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let monster: Monster = serde_json::from_value(json!({
+        "name": "Smaug",
+        "color": "green",
+        "position": [1.0, 2.0],
+    }))
+    .unwrap();
+    assert_eq!(monster.nickname, "");
+    let color = monster.color;
+    assert_eq!(color, Color::Green);
+    assert_eq!(monster.position, Position(1.0, 2.0));
+    assert_eq!(
+        serde_json::to_value(&monster).unwrap(),
+        json!({"name": "Smaug", "color": "Green", "position": [1.0, 2.0]})
+    );
+
+    let unknown = json!({"name": "Smaug", "color": "Red", "position": [0.0, 0.0], "hp": 1});
+    assert!(serde_json::from_value::<Monster>(unknown).is_err());
+
+    let event: Event = serde_json::from_value(json!({"Moved": {"pos": [0.0, 1.0]}})).unwrap();
+    match event {
+        Event::Moved { position } => assert_eq!(position, Position(0.0, 1.0)),
+    }
+}
@@ -0,0 +1,50 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(deny_unknown_fields)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    #[doc = ""]
+    pub nickname: String,
+    #[doc = ""]
+    pub color: Color,
+    #[doc = ""]
+    pub position: Position,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize, Copy, PartialEq, Eq)]
+#[doc = ""]
+pub enum Color {
+    #[doc = ""]
+    Red,
+    #[doc = ""]
+    #[serde(alias = "green")]
+    Green,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize, PartialEq)]
+#[doc = ""]
+pub struct Position(pub f64, pub f64);
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Event {
+    #[doc = ""]
+    Moved {
+        #[doc = ""]
+        #[serde(alias = "pos")]
+        position: Position,
+    },
+}
+impl ::humblegen_rt::wire_hash::WireHash for Color {
+    const WIRE_HASH: &'static str = "6ea6cb4bdaef81ab";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Event {
+    const WIRE_HASH: &'static str = "8b910a15b5427944";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "023dced4859a8513";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Position {
+    const WIRE_HASH: &'static str = "60278d8cf54800d8";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Color\": \"6ea6cb4bdaef81ab\",\n  \"Event\": \"8b910a15b5427944\",\n  \"Monster\": \"023dced4859a8513\",\n  \"Position\": \"60278d8cf54800d8\"\n}" ;
@@ -0,0 +1,77 @@
+mod protocol {
+    include!("spec.rs");
+}
+
+mod domain {
+    use std::convert::TryFrom;
+
+    pub struct Customer {
+        pub name: String,
+        pub email: Email,
+        pub address: Option<Address>,
+        pub previous_addresses: Vec<Address>,
+        pub visits: u32,
+    }
+
+    pub struct Address {
+        pub street: String,
+        pub city: String,
+    }
+
+    pub struct Email(pub String);
+
+    impl TryFrom<String> for Email {
+        type Error = String;
+
+        fn try_from(email: String) -> Result<Self, Self::Error> {
+            if email.contains('@') {
+                Ok(Email(email))
+            } else {
+                Err(format!("invalid email {:?}", email))
+            }
+        }
+    }
+
+    impl From<Email> for String {
+        fn from(email: Email) -> Self {
+            email.0
+        }
+    }
+}
+
+use std::convert::TryFrom;
+
+fn main() {
+    let customer = domain::Customer {
+        name: "Ken Watanabe".to_owned(),
+        email: domain::Email("ken@example.com".to_owned()),
+        address: Some(domain::Address {
+            street: "1 Monster Road".to_owned(),
+            city: "Tokyo".to_owned(),
+        }),
+        previous_addresses: vec![domain::Address {
+            street: "2 Monster Road".to_owned(),
+            city: "Odo".to_owned(),
+        }],
+        visits: 3,
+    };
+
+    let wire = protocol::Customer::from(customer);
+    assert_eq!(wire.email, "ken@example.com");
+    assert_eq!(wire.address.as_ref().unwrap().city, "Tokyo");
+    assert_eq!(wire.previous_addresses[0].city, "Odo");
+    assert_eq!(wire.visits, 3);
+
+    let customer = domain::Customer::try_from(wire.clone()).unwrap();
+    assert_eq!(customer.email.0, "ken@example.com");
+    assert_eq!(customer.previous_addresses.len(), 1);
+    assert_eq!(customer.visits, 3);
+
+    // failing field conversions are reported
+    let invalid = protocol::Customer {
+        email: "ken".to_owned(),
+        ..wire
+    };
+    let err = domain::Customer::try_from(invalid).err().unwrap();
+    assert_eq!(err.to_string(), "invalid email \"ken\"");
+}
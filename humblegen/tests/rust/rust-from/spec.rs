@@ -0,0 +1,82 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A customer as sent to clients."]
+pub struct Customer {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub email: String,
+    #[doc = ""]
+    pub address: Option<Address>,
+    #[doc = ""]
+    pub previous_addresses: Vec<Address>,
+    #[doc = ""]
+    pub visits: u32,
+}
+impl ::std::convert::From<crate::domain::Customer> for Customer {
+    fn from(domain: crate::domain::Customer) -> Self {
+        Self {
+            name: ::std::convert::Into::into(domain.name),
+            email: ::std::convert::Into::into(domain.email),
+            address: domain.address.map(::std::convert::Into::into),
+            previous_addresses: domain
+                .previous_addresses
+                .into_iter()
+                .map(::std::convert::Into::into)
+                .collect(),
+            visits: ::std::convert::Into::into(domain.visits),
+        }
+    }
+}
+impl ::std::convert::TryFrom<Customer> for crate::domain::Customer {
+    type Error = Box<dyn ::std::error::Error + Send + Sync>;
+    fn try_from(wire: Customer) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: ::std::convert::TryInto::try_into(wire.name)?,
+            email: ::std::convert::TryInto::try_into(wire.email)?,
+            address: wire
+                .address
+                .map(::std::convert::TryInto::try_into)
+                .transpose()?,
+            previous_addresses: wire
+                .previous_addresses
+                .into_iter()
+                .map(::std::convert::TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            visits: ::std::convert::TryInto::try_into(wire.visits)?,
+        })
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Address {
+    #[doc = ""]
+    pub street: String,
+    #[doc = ""]
+    pub city: String,
+}
+impl ::std::convert::From<crate::domain::Address> for Address {
+    fn from(domain: crate::domain::Address) -> Self {
+        Self {
+            street: ::std::convert::Into::into(domain.street),
+            city: ::std::convert::Into::into(domain.city),
+        }
+    }
+}
+impl ::std::convert::TryFrom<Address> for crate::domain::Address {
+    type Error = Box<dyn ::std::error::Error + Send + Sync>;
+    fn try_from(wire: Address) -> Result<Self, Self::Error> {
+        Ok(Self {
+            street: ::std::convert::TryInto::try_into(wire.street)?,
+            city: ::std::convert::TryInto::try_into(wire.city)?,
+        })
+    }
+}
+impl ::humblegen_rt::wire_hash::WireHash for Address {
+    const WIRE_HASH: &'static str = "9e19f5b7b30191dd";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Customer {
+    const WIRE_HASH: &'static str = "1c9a859de64bc9b5";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Address\": \"9e19f5b7b30191dd\",\n  \"Customer\": \"1c9a859de64bc9b5\"\n}";
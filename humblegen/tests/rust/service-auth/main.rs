@@ -0,0 +1,121 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::auth::Authenticator;
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+struct User(String);
+
+struct TokenAuthenticator;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Authenticator for TokenAuthenticator {
+    type Principal = User;
+
+    async fn authenticate(&self, headers: &hyper::HeaderMap) -> Result<User, ServiceError> {
+        match headers.get(hyper::header::AUTHORIZATION) {
+            Some(token) if token == "Bearer alice-token" => Ok(User("alice".to_owned())),
+            _ => Err(ServiceError::Authentication),
+        }
+    }
+}
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl AccountApi for S {
+    type Context = ();
+    type Principal = User;
+
+    fn authenticator(&self) -> &dyn Authenticator<Principal = User> {
+        &TokenAuthenticator
+    }
+
+    async fn get_me(&self, _ctx: Self::Context, principal: User) -> Response<String> {
+        Ok(principal.0)
+    }
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+    type Principal = User;
+
+    fn authenticator(&self) -> &dyn Authenticator<Principal = User> {
+        &TokenAuthenticator
+    }
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(vec!["dragon".to_owned()])
+    }
+
+    async fn post_monsters(
+        &self,
+        _ctx: Self::Context,
+        principal: User,
+        post_body: String,
+    ) -> Response<String> {
+        Ok(format!("{} created by {}", post_body, principal.0))
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    method: &str,
+    path: &str,
+    authorization: Option<&str>,
+    body: &'static str,
+) -> (u16, String) {
+    let mut req = hyper::Request::builder().method(method).uri(path);
+    if let Some(authorization) = authorization {
+        req = req.header(hyper::header::AUTHORIZATION, authorization);
+    }
+    let req = req.body(hyper::Body::from(body)).unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/account", Handler::AccountApi(Arc::new(S)))
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+    let s = &services;
+    let alice = Some("Bearer alice-token");
+
+    assert_eq!(
+        request(s, "GET", "/account/me", alice, "").await,
+        (200, "\"alice\"".to_owned())
+    );
+    assert_eq!(request(s, "GET", "/account/me", None, "").await.0, 401);
+    assert_eq!(
+        request(s, "GET", "/account/me", Some("Bearer mallory"), "")
+            .await
+            .0,
+        401
+    );
+
+    assert_eq!(
+        request(s, "GET", "/api/monsters", None, "").await,
+        (200, "[\"dragon\"]".to_owned())
+    );
+    assert_eq!(
+        request(s, "POST", "/api/monsters", alice, "\"wyvern\"").await,
+        (200, "\"wyvern created by alice\"".to_owned())
+    );
+    assert_eq!(
+        request(s, "POST", "/api/monsters", None, "\"wyvern\"")
+            .await
+            .0,
+        401
+    );
+}
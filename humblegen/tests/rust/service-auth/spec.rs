@@ -0,0 +1,584 @@
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str = "{}";
+#[doc = r" The resolved humblespec of the services, served at `/__spec` by `Builder::with_spec_endpoint`."]
+pub const SPEC_JSON : & str = "{\n  \"services\": [\n    {\n      \"doc\": \"Every endpoint requires auth.\",\n      \"format\": \"json\",\n      \"routes\": [\n        {\n          \"auth\": true,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/me\",\n          \"query\": null,\n          \"returns\": \"str\"\n        }\n      ],\n      \"service\": \"AccountApi\"\n    },\n    {\n      \"doc\": null,\n      \"format\": \"json\",\n      \"routes\": [\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": \"Public, no principal.\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters\",\n          \"query\": null,\n          \"returns\": \"list[str]\"\n        },\n        {\n          \"auth\": true,\n          \"body\": \"str\",\n          \"doc\": \"Requires auth.\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"POST\",\n          \"multipart\": false,\n          \"path\": \"/monsters\",\n          \"query\": null,\n          \"returns\": \"str\"\n        }\n      ],\n      \"service\": \"MonsterApi\"\n    }\n  ],\n  \"types\": []\n}" ;
+#[doc = r" HTML index of the routes of the services, served at `/__spec/index.html` by `Builder::with_spec_endpoint`."]
+pub const SPEC_INDEX_HTML : & str = "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>API Routes</title>\n<style>body { font-family: sans-serif; } table { border-collapse: collapse; } td, th { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }</style>\n</head>\n<body>\n<h1>API Routes</h1>\n<p>Paths are relative to the root a service is mounted at. The full spec is available as <a href=\"../__spec\">JSON</a>.</p>\n<h2>AccountApi</h2>\n<p>Every endpoint requires auth.</p>\n<table>\n<tr><th>Method</th><th>Path</th><th>Body</th><th>Returns</th><th>Description</th></tr>\n<tr><td><code>GET</code></td><td><code>/me</code></td><td><code></code></td><td><code>str</code></td><td></td></tr>\n</table>\n<h2>MonsterApi</h2>\n<p></p>\n<table>\n<tr><th>Method</th><th>Path</th><th>Body</th><th>Returns</th><th>Description</th></tr>\n<tr><td><code>GET</code></td><td><code>/monsters</code></td><td><code></code></td><td><code>list[str]</code></td><td>Public, no principal.</td></tr>\n<tr><td><code>POST</code></td><td><code>/monsters</code></td><td><code>str</code></td><td><code>str</code></td><td>Requires auth.</td></tr>\n</table>\n</body>\n</html>\n" ;
+#[allow(unused_imports)]
+use ::humblegen_rt::deser_helpers::{
+    deser_header, deser_opt_header, deser_param, deser_post_data, deser_query_primitive,
+    deser_query_serde_urlencoded,
+};
+#[allow(unused_imports)]
+pub use ::humblegen_rt::handler::{
+    self, HandlerFuture, HandlerResponse as Response, ServiceError, WithHeaders,
+};
+#[allow(unused_imports)]
+use ::humblegen_rt::server::{self, handler_response_to_hyper_response, Router, Service};
+#[allow(unused_imports)]
+use ::humblegen_rt::service_protocol::ErrorResponse;
+use ::humblegen_rt::tracing_futures::Instrument;
+#[allow(unused_imports)]
+use ::humblegen_rt::{hyper, tracing};
+#[allow(unused_imports)]
+use ::std::sync::Arc;
+use std::net::SocketAddr;
+#[doc = r" Builds an HTTP server that exposes services implemented by handler trait objects."]
+#[derive(Debug)]
+pub struct Builder {
+    services: Vec<Service>,
+    metrics: Option<Arc<::humblegen_rt::metrics::Metrics>>,
+    feature_flags: Option<Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+    pretty_json: bool,
+    recorder: Option<Arc<::humblegen_rt::recording::Recorder>>,
+    middleware: Vec<Arc<dyn ::humblegen_rt::middleware::Middleware>>,
+    shutdown: ::humblegen_rt::shutdown::ShutdownHandle,
+    drain_delay: std::time::Duration,
+    limits: ::humblegen_rt::limits::Limits,
+    idempotency_store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+}
+impl Builder {
+    pub fn new() -> Self {
+        Self {
+            services: vec![],
+            metrics: None,
+            feature_flags: None,
+            pretty_json: false,
+            recorder: None,
+            middleware: vec![],
+            shutdown: Default::default(),
+            drain_delay: std::time::Duration::from_secs(0),
+            limits: Default::default(),
+            idempotency_store: Arc::new(::humblegen_rt::idempotency::MemoryStore::default()),
+        }
+    }
+    #[doc = r" Records the latencies of requests to the routes of handlers `add`ed afterwards"]
+    #[doc = r" in `metrics`."]
+    pub fn with_metrics(mut self, metrics: Arc<::humblegen_rt::metrics::Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    #[doc = r#" Consults `feature_flags` whether the routes annotated with `@flag("name")` of"#]
+    #[doc = r" handlers `add`ed afterwards are enabled. Without, these routes are hidden."]
+    pub fn with_feature_flags(
+        mut self,
+        feature_flags: Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>,
+    ) -> Self {
+        self.feature_flags = Some(feature_flags);
+        self
+    }
+    #[doc = r" Runs `middleware` around the requests to the routes of handlers `add`ed afterwards,"]
+    #[doc = r" inside the middleware passed before."]
+    pub fn with_middleware(
+        mut self,
+        middleware: Arc<dyn ::humblegen_rt::middleware::Middleware>,
+    ) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+    #[doc = r" Answers requests to the routes of handlers `add`ed afterwards with a body larger than"]
+    #[doc = r" `max_body_size` bytes with 413 Payload Too Large, unless annotated with `@max_body_size`."]
+    pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+        self.limits.max_body_size = Some(max_body_size);
+        self
+    }
+    #[doc = r" Answers requests to the routes of handlers `add`ed afterwards with 504 Gateway Timeout"]
+    #[doc = r" if not handled within `timeout`, unless annotated with `@timeout`."]
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.limits.timeout = Some(timeout);
+        self
+    }
+    #[doc = r" Deduplicates the requests to the routes annotated with `@idempotent` of handlers `add`ed"]
+    #[doc = r" afterwards using `store`, e.g. one shared by all instances of the server. Defaults to a"]
+    #[doc = r" `MemoryStore`."]
+    pub fn with_idempotency_store(
+        mut self,
+        store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+    ) -> Self {
+        self.idempotency_store = store;
+        self
+    }
+    #[doc = r" Whether responses of handlers `add`ed afterwards are pretty-printed JSON, e.g. for"]
+    #[doc = r" debugging. Defaults to compact JSON, unless a request has query parameter `pretty=1`."]
+    pub fn with_pretty_json(mut self, pretty_json: bool) -> Self {
+        self.pretty_json = pretty_json;
+        self
+    }
+    #[doc = r" Records all requests served by `listen_and_run_forever` and their responses in"]
+    #[doc = r" `recorder`, to be replayed using `humblegen_rt::recording::replay`."]
+    pub fn with_recorder(mut self, recorder: Arc<::humblegen_rt::recording::Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+    #[doc = r" Mounts `handler` at URL path prefix `root`."]
+    #[doc = r" This means that a `handler` implementing humble service"]
+    #[doc = r" ```"]
+    #[doc = r" service S {"]
+    #[doc = r"     GET /bar -> i32,"]
+    #[doc = r"     GET /baz -> str,"]
+    #[doc = r" }"]
+    #[doc = r" ```"]
+    #[doc = r#" and `root="/api"` will expose"#]
+    #[doc = r" * handler method `fn bar() -> i32` at `/api/bar` and"]
+    #[doc = r" * handler method `fn baz() -> String` at `/api/baz`"]
+    pub fn add<Context: Default + Sized + Send + Sync, Principal: Send + Sync + 'static>(
+        mut self,
+        root: &str,
+        handler: Handler<Context, Principal>,
+    ) -> Self {
+        if !root.starts_with('/') {
+            panic!("root must start with \"/\"")
+        } else if root.ends_with('/') {
+            panic!("root must not end with \"/\"")
+        }
+        self.services.push(Service {
+            root: root.to_owned(),
+            route_prefix: handler.route_prefix().to_owned(),
+            router: handler.into_router(
+                root,
+                self.metrics.as_deref(),
+                self.feature_flags.as_ref(),
+                &self.middleware,
+                self.limits,
+                &self.idempotency_store,
+            ),
+            pretty_json: self.pretty_json,
+        });
+        self
+    }
+    #[doc = r" Serves the metrics passed to `with_metrics` in the Prometheus text format at `/metrics`."]
+    pub fn with_metrics_endpoint(mut self) -> Self {
+        let metrics = self
+            .metrics
+            .clone()
+            .expect("with_metrics must be called before with_metrics_endpoint");
+        self.services
+            .push(::humblegen_rt::metrics::service(metrics));
+        self
+    }
+    #[doc = r" Serves liveness and readiness probes at `/health/live` and `/health/ready`, the latter"]
+    #[doc = r" responding with 503 Service Unavailable once the server is shutting down."]
+    pub fn with_health_endpoints(mut self) -> Self {
+        self.services.push(::humblegen_rt::shutdown::health_service(
+            self.shutdown.clone(),
+        ));
+        self
+    }
+    #[doc = r" Returns the handle to shut down the server gracefully: `listen_and_run_forever`"]
+    #[doc = r" stops accepting connections and returns once the requests in flight completed."]
+    pub fn shutdown_handle(&self) -> ::humblegen_rt::shutdown::ShutdownHandle {
+        self.shutdown.clone()
+    }
+    #[doc = r" Shuts down the server gracefully on SIGTERM or Ctrl-C."]
+    #[doc = r" Must be called within a tokio runtime."]
+    pub fn with_shutdown_signal(self) -> Self {
+        self.shutdown.shutdown_on_signal();
+        self
+    }
+    #[doc = r" Keeps accepting connections for `drain_delay` after the shutdown started, while"]
+    #[doc = r" the readiness probe already fails, such that load balancers stop routing requests"]
+    #[doc = r" to the server before it stops accepting connections. Defaults to zero."]
+    pub fn with_drain_delay(mut self, drain_delay: std::time::Duration) -> Self {
+        self.drain_delay = drain_delay;
+        self
+    }
+    #[doc = r" Serves the resolved humblespec of the services as JSON document at `/__spec`,"]
+    #[doc = r" and an HTML index of their routes at `/__spec/index.html`."]
+    pub fn with_spec_endpoint(mut self) -> Self {
+        self.services.push(::humblegen_rt::spec_endpoint::service(
+            SPEC_JSON,
+            SPEC_INDEX_HTML,
+        ));
+        self
+    }
+    #[doc = r" Returns the previously `add`ed handlers as services, to be served using"]
+    #[doc = r" `humblegen_rt::server::handle_request`, e.g. in a custom hyper server or in tests."]
+    pub fn into_services(self) -> Vec<Service> {
+        self.services
+    }
+    #[doc = r" Starts an HTTP server bound to address `addr` and serves incoming requests using"]
+    #[doc = r" the previously `add`ed handlers, until shut down using `shutdown_handle`."]
+    pub async fn listen_and_run_forever(
+        self,
+        addr: &SocketAddr,
+    ) -> humblegen_rt::anyhow::Result<()> {
+        server::listen_and_run_forever(
+            self.services,
+            addr,
+            self.recorder,
+            self.shutdown,
+            self.drain_delay,
+        )
+        .await
+    }
+    #[doc = r" Like `listen_and_run_forever`, but serves the connections accepted by `incoming`,"]
+    #[doc = r" e.g. to terminate TLS using `humblegen_rt::tls::TlsAcceptor::bind`."]
+    pub async fn serve<I>(self, incoming: I) -> humblegen_rt::anyhow::Result<()>
+    where
+        I: ::humblegen_rt::hyper::server::accept::Accept,
+        I::Conn: ::humblegen_rt::tokio::io::AsyncRead
+            + ::humblegen_rt::tokio::io::AsyncWrite
+            + Unpin
+            + Send
+            + 'static,
+        I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        server::serve(
+            incoming,
+            self.services,
+            self.recorder,
+            self.shutdown,
+            self.drain_delay,
+        )
+        .await
+    }
+}
+#[doc = r" Wrapper enum with one variant for each service defined in the humble spec."]
+#[doc = r" Used to pass instantiated handler trait objects to `Builder::add`."]
+#[allow(dead_code)]
+pub enum Handler<Context: Default + Sized + Send + Sync + 'static, Principal: Send + Sync + 'static>
+{
+    AccountApi(Arc<dyn AccountApi<Context = Context, Principal = Principal> + Send + Sync>),
+    MonsterApi(Arc<dyn MonsterApi<Context = Context, Principal = Principal> + Send + Sync>),
+}
+impl<Context: Default + Sized + Send + Sync + 'static, Principal: Send + Sync + 'static>
+    Handler<Context, Principal>
+{
+    fn into_router(
+        self,
+        root: &str,
+        metrics: Option<&::humblegen_rt::metrics::Metrics>,
+        feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+        middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+        limits: ::humblegen_rt::limits::Limits,
+        idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+    ) -> Box<Router> {
+        match self {
+            Handler::AccountApi(h) => routes_AccountApi(
+                h,
+                root,
+                metrics,
+                feature_flags,
+                middleware,
+                limits,
+                idempotency_store,
+            ),
+            Handler::MonsterApi(h) => routes_MonsterApi(
+                h,
+                root,
+                metrics,
+                feature_flags,
+                middleware,
+                limits,
+                idempotency_store,
+            ),
+        }
+    }
+    fn route_prefix(&self) -> &'static str {
+        match self {
+            Handler::AccountApi(_) => "/me",
+            Handler::MonsterApi(_) => "/monsters",
+        }
+    }
+}
+impl<Context: Default + Sized + Send + Sync + 'static, Principal: Send + Sync + 'static>
+    std::fmt::Debug for Handler<Context, Principal>
+{
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Handler::AccountApi(_) => write!(formatter, "{}", "AccountApi")?,
+            Handler::MonsterApi(_) => write!(formatter, "{}", "MonsterApi")?,
+        }
+        Ok(())
+    }
+}
+#[doc = "Every endpoint requires auth."]
+#[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait AccountApi {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<Self::Context, ServiceError> {\n        Ok(Self::Context::default())\n    }\n    type Principal: Send + Sync;\n    fn authenticator(\n        &self,\n    ) -> &dyn ::humblegen_rt::auth::Authenticator<Principal = Self::Principal>;\n    async fn get_me(&self, ctx: Self::Context, principal: Self::Principal) -> Response<String>;\n}\n\n```"]
+#[humblegen_rt::async_trait(Sync)]
+pub trait AccountApi {
+    type Context: Default + Sized + Send + Sync;
+    async fn intercept_handler_pre(
+        &self,
+        _req: &hyper::Request<hyper::Body>,
+    ) -> Result<Self::Context, ServiceError> {
+        Ok(Self::Context::default())
+    }
+    type Principal: Send + Sync;
+    fn authenticator(
+        &self,
+    ) -> &dyn ::humblegen_rt::auth::Authenticator<Principal = Self::Principal>;
+    #[doc = "```\nasync fn get_me(&self, ctx: Self::Context, principal: Self::Principal) -> Response<String> {}\n\n```"]
+    #[doc = ""]
+    async fn get_me(&self, ctx: Self::Context, principal: Self::Principal) -> Response<String>;
+}
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+#[allow(non_snake_case)]
+#[allow(unreachable_patterns)]
+fn routes_AccountApi<
+    Context: Default + Sized + Send + Sync + 'static,
+    Principal: Send + Sync + 'static,
+>(
+    handler: Arc<dyn AccountApi<Context = Context, Principal = Principal> + Send + Sync>,
+    root: &str,
+    metrics: Option<&::humblegen_rt::metrics::Metrics>,
+    feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+    middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+    limits: ::humblegen_rt::limits::Limits,
+    idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+) -> Box<Router> {
+    let idempotency_store = Arc::clone(idempotency_store);
+    let route_histograms: Vec<Option<Arc<::humblegen_rt::metrics::LatencyHistogram>>> =
+        vec![metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/me"),
+                None,
+            )
+        })];
+    let feature_flags = feature_flags.cloned();
+    let middleware: Arc<[Arc<dyn ::humblegen_rt::middleware::Middleware>]> = middleware.into();
+    Box::new(
+        move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
+              suffix_start: usize| {
+            let mut segments = [""; 1usize];
+            let num_segments =
+                server::split_path_segments(&req.uri().path()[suffix_start..], &mut segments)?;
+            let routed = match &segments[..num_segments] {
+                ["me"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /me", "route matched");
+                    let span =
+                        tracing::error_span!("AccountApi.get_me", status = tracing::field::Empty);
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[0usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "AccountApi",
+                            route: "GET /me",
+                            handler: "get_me",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let principal = {
+                                    let span = tracing::error_span!("authenticator");
+                                    handler . authenticator () . authenticate (req . headers ()) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "authenticator rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_me(ctx, principal).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /me",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                _ => None,
+            };
+            routed
+        },
+    )
+}
+#[doc = ""]
+#[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait MonsterApi {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<Self::Context, ServiceError> {\n        Ok(Self::Context::default())\n    }\n    type Principal: Send + Sync;\n    fn authenticator(\n        &self,\n    ) -> &dyn ::humblegen_rt::auth::Authenticator<Principal = Self::Principal>;\n    async fn get_monsters(&self, ctx: Self::Context) -> Response<Vec<String>>;\n    async fn post_monsters(\n        &self,\n        ctx: Self::Context,\n        principal: Self::Principal,\n        post_body: String,\n    ) -> Response<String>;\n}\n\n```"]
+#[humblegen_rt::async_trait(Sync)]
+pub trait MonsterApi {
+    type Context: Default + Sized + Send + Sync;
+    async fn intercept_handler_pre(
+        &self,
+        _req: &hyper::Request<hyper::Body>,
+    ) -> Result<Self::Context, ServiceError> {
+        Ok(Self::Context::default())
+    }
+    type Principal: Send + Sync;
+    fn authenticator(
+        &self,
+    ) -> &dyn ::humblegen_rt::auth::Authenticator<Principal = Self::Principal>;
+    #[doc = "```\nasync fn get_monsters(&self, ctx: Self::Context) -> Response<Vec<String>> {}\n\n```"]
+    #[doc = "Public, no principal."]
+    async fn get_monsters(&self, ctx: Self::Context) -> Response<Vec<String>>;
+    #[doc = "```\nasync fn post_monsters(\n    &self,\n    ctx: Self::Context,\n    principal: Self::Principal,\n    post_body: String,\n) -> Response<String> {\n}\n\n```"]
+    #[doc = "Requires auth."]
+    async fn post_monsters(
+        &self,
+        ctx: Self::Context,
+        principal: Self::Principal,
+        post_body: String,
+    ) -> Response<String>;
+}
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+#[allow(non_snake_case)]
+#[allow(unreachable_patterns)]
+fn routes_MonsterApi<
+    Context: Default + Sized + Send + Sync + 'static,
+    Principal: Send + Sync + 'static,
+>(
+    handler: Arc<dyn MonsterApi<Context = Context, Principal = Principal> + Send + Sync>,
+    root: &str,
+    metrics: Option<&::humblegen_rt::metrics::Metrics>,
+    feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+    middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+    limits: ::humblegen_rt::limits::Limits,
+    idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+) -> Box<Router> {
+    let idempotency_store = Arc::clone(idempotency_store);
+    let route_histograms: Vec<Option<Arc<::humblegen_rt::metrics::LatencyHistogram>>> = vec![
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/monsters"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::POST.as_str(),
+                &format!("{}{}", root, "/monsters"),
+                None,
+            )
+        }),
+    ];
+    let feature_flags = feature_flags.cloned();
+    let middleware: Arc<[Arc<dyn ::humblegen_rt::middleware::Middleware>]> = middleware.into();
+    Box::new(
+        move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
+              suffix_start: usize| {
+            let mut segments = [""; 1usize];
+            let num_segments =
+                server::split_path_segments(&req.uri().path()[suffix_start..], &mut segments)?;
+            let routed = match &segments[..num_segments] {
+                ["monsters"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /monsters", "route matched");
+                    let span = tracing::error_span!(
+                        "MonsterApi.get_monsters",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[0usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "MonsterApi",
+                            route: "GET /monsters",
+                            handler: "get_monsters",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_monsters(ctx).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /monsters",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters"] if *req.method() == ::humblegen_rt::hyper::Method::POST => {
+                    tracing::debug!(route = "POST /monsters", "route matched");
+                    let span = tracing::error_span!(
+                        "MonsterApi.post_monsters",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[1usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "MonsterApi",
+                            route: "POST /monsters",
+                            handler: "post_monsters",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let principal = {
+                                    let span = tracing::error_span!("authenticator");
+                                    handler . authenticator () . authenticate (req . headers ()) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "authenticator rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                ::humblegen_rt::compression::decompress_request(&mut req)?;
+                                if let Some(max_body_size) = limits.max_body_size {
+                                    ::humblegen_rt::limits::limit_body(&mut req, max_body_size)
+                                        .await?;
+                                }
+                                let post_body: String = deser_post_data(req.body_mut()).await?;
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler
+                                            .post_monsters(ctx, principal, post_body)
+                                            .instrument(span)
+                                            .await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "POST /monsters",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                _ => None,
+            };
+            routed
+        },
+    )
+}
@@ -0,0 +1,58 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[derive(Default)]
+struct Caller {
+    is_admin: bool,
+}
+
+impl MonsterApi for S {
+    type Context = Caller;
+
+    fn intercept_handler_pre<'a>(
+        &'a self,
+        req: &'a hyper::Request<hyper::Body>,
+    ) -> HandlerFuture<'a, Result<Self::Context, ServiceError>> {
+        let is_admin = req.headers().contains_key(hyper::header::AUTHORIZATION);
+        Box::pin(async move { Ok(Caller { is_admin }) })
+    }
+
+    fn get_monsters_id<'a>(
+        &'a self,
+        _ctx: Self::Context,
+        id: i32,
+    ) -> HandlerFuture<'a, Response<Option<Monster>>> {
+        Box::pin(async move {
+            Ok(Some(Monster {
+                id,
+                name: "Godzilla".to_owned(),
+            }))
+        })
+    }
+
+    fn post_monsters_id_name<'a>(
+        &'a self,
+        ctx: Self::Context,
+        post_body: String,
+        id: i32,
+    ) -> HandlerFuture<'a, Response<()>> {
+        Box::pin(async move {
+            if !ctx.is_admin {
+                return Err(ServiceError::Authorization);
+            }
+            println!("renaming monster {} to {:?}", id, post_body);
+            Ok(())
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    Builder::new().add("/api", Handler::MonsterApi(Arc::new(S)));
+}
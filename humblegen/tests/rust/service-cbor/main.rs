@@ -0,0 +1,128 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::serde_cbor;
+use humblegen_rt::server::Service;
+use humblegen_rt::wire_format::CBOR_CONTENT_TYPE;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: i32,
+    ) -> Response<Result<Monster, MonsterError>> {
+        match id {
+            1 => Ok(Ok(monster())),
+            _ => Ok(Err(MonsterError::NotFound)),
+        }
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, post_body: Monster) -> Response<Monster> {
+        Ok(Monster {
+            age: post_body.age + 1,
+            ..post_body
+        })
+    }
+}
+
+fn monster() -> Monster {
+    Monster {
+        name: "dragon".to_owned(),
+        age: 9,
+        portrait: vec![0, 1, 2],
+    }
+}
+
+fn services() -> Arc<Vec<Service>> {
+    Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    )
+}
+
+async fn request(req: hyper::Request<hyper::Body>) -> (u16, Option<String>, Vec<u8>) {
+    let resp = humblegen_rt::server::handle_request(services(), req).await;
+    let status = resp.status().as_u16();
+    let content_type = resp
+        .headers()
+        .get("Content-Type")
+        .map(|v| v.to_str().unwrap().to_owned());
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, content_type, body.to_vec())
+}
+
+#[tokio::main]
+async fn main() {
+    // CBOR responses on request
+    let (status, content_type, body) = request(
+        hyper::Request::get("/api/monsters/1")
+            .header("Accept", "application/json, application/cbor; q=0.9")
+            .body(hyper::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type.as_deref(), Some(CBOR_CONTENT_TYPE));
+    let m: Result<Monster, MonsterError> = serde_cbor::from_slice(&body).unwrap();
+    assert_eq!(m.unwrap().portrait, monster().portrait);
+
+    // domain errors are encoded in CBOR as well and keep their status codes
+    let (status, content_type, body) = request(
+        hyper::Request::get("/api/monsters/2")
+            .header("Accept", CBOR_CONTENT_TYPE)
+            .body(hyper::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 404);
+    assert_eq!(content_type.as_deref(), Some(CBOR_CONTENT_TYPE));
+    let m: Result<Monster, MonsterError> = serde_cbor::from_slice(&body).unwrap();
+    assert!(matches!(m, Err(MonsterError::NotFound)));
+
+    // CBOR request bodies
+    let (status, content_type, body) = request(
+        hyper::Request::post("/api/monsters")
+            .header("Content-Type", CBOR_CONTENT_TYPE)
+            .header("Accept", CBOR_CONTENT_TYPE)
+            .body(serde_cbor::to_vec(&monster()).unwrap().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type.as_deref(), Some(CBOR_CONTENT_TYPE));
+    let m: Monster = serde_cbor::from_slice(&body).unwrap();
+    assert_eq!(m.age, 10);
+
+    // JSON by default
+    let (status, content_type, body) = request(
+        hyper::Request::post("/api/monsters")
+            .body(serde_json::to_vec(&monster()).unwrap().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    let m: Monster = serde_json::from_slice(&body).unwrap();
+    assert_eq!(m.age, 10);
+
+    // error responses of the service protocol stay JSON
+    let (status, content_type, _) = request(
+        hyper::Request::post("/api/monsters")
+            .header("Content-Type", CBOR_CONTENT_TYPE)
+            .header("Accept", CBOR_CONTENT_TYPE)
+            .body(b"{}".to_vec().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+}
@@ -0,0 +1,63 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::client::{ClientError, Url};
+use humblegen_rt::compression::{Encoding, ACCEPT_ENCODING};
+use humblegen_rt::hyper::service::{make_service_fn, service_fn};
+use humblegen_rt::hyper::{Body, Request, Response, Server};
+use protocol::*;
+use std::convert::Infallible;
+
+fn monsters() -> Vec<Monster> {
+    (0..100)
+        .map(|age| Monster {
+            name: "dragon".to_owned(),
+            age,
+        })
+        .collect()
+}
+
+/// Stands in for a humblegen server, compressing its responses as accepted by the client.
+async fn serve(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    assert_eq!(req.headers()["Accept-Encoding"], ACCEPT_ENCODING);
+    let (encoding, body) = match req.uri().path() {
+        "/api/monsters" => (
+            Encoding::Gzip,
+            Encoding::Gzip
+                .encode(&serde_json::to_vec(&monsters()).unwrap())
+                .unwrap(),
+        ),
+        "/api/monsters/1" => (
+            Encoding::Deflate,
+            Encoding::Deflate
+                .encode(&serde_json::to_vec(&monsters()[1]).unwrap())
+                .unwrap(),
+        ),
+        _ => (Encoding::Gzip, b"not compressed".to_vec()),
+    };
+    Ok(Response::builder()
+        .header("Content-Encoding", encoding.as_str())
+        .body(body.into())
+        .unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(serve))
+    }));
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let client = MonsterApiClient::new(Url::parse(&format!("http://{}/api", addr)).unwrap());
+
+    // responses are decompressed by their `Content-Encoding`
+    let m = client.get_monsters().await.unwrap();
+    assert_eq!(m.len(), 100);
+    assert_eq!(m[99].age, 99);
+    let m = client.get_monsters_id(1).await.unwrap();
+    assert_eq!(m.age, 1);
+
+    let e = client.get_monsters_id(2).await.unwrap_err();
+    assert!(matches!(e, ClientError::InvalidCompressedResponse(_)));
+}
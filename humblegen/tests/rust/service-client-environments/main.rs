@@ -0,0 +1,30 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::config::{Config, Environment};
+use protocol::*;
+use std::time::Duration;
+
+fn main() {
+    assert_eq!(Environment::ALL, &[Environment::Prod, Environment::LocalDev]);
+    assert_eq!(Environment::from_name("local_dev"), Some(Environment::LocalDev));
+    assert_eq!(Environment::from_name("staging"), None);
+    assert_eq!(Environment::LocalDev.name(), "local_dev");
+
+    // one config, e.g. selected by an environment variable, creates the clients of all services
+    let config = Config::for_environment(Environment::LocalDev)
+        .with_timeout(Duration::from_secs(10))
+        .with_default_header(
+            humblegen_rt::client::HeaderName::from_static("x-api-key"),
+            humblegen_rt::client::HeaderValue::from_static("secret"),
+        );
+    assert_eq!(config.base_url.as_str(), "http://localhost:3000/api");
+    let _monsters = MonsterApiClient::from_config(&config).unwrap();
+    let _villains = VillainApiClient::from_config(&config).unwrap();
+
+    // specs with a base URL have a default config
+    assert_eq!(
+        Config::default().base_url.as_str(),
+        Environment::Prod.base_url()
+    );
+}
@@ -0,0 +1,13 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+
+fn main() {
+    assert_eq!(
+        MonsterApiClient::DEFAULT_BASE_URL,
+        "https://api.example.com/v1"
+    );
+    // clients of specs with a base URL send requests to it by default
+    let _client = MonsterApiClient::default();
+}
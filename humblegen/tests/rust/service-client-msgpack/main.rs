@@ -0,0 +1,70 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::client::Url;
+use humblegen_rt::hyper::service::{make_service_fn, service_fn};
+use humblegen_rt::hyper::{self, Body, Request, Response, Server};
+use humblegen_rt::msgpack;
+use humblegen_rt::wire_format::MSGPACK_CONTENT_TYPE;
+use protocol::*;
+use std::convert::Infallible;
+
+fn monster() -> Monster {
+    Monster {
+        name: "dragon".to_owned(),
+        age: 9,
+        portrait: vec![0, 1, 2],
+    }
+}
+
+/// Stands in for a humblegen server, checking that the client sends and accepts MessagePack.
+async fn serve(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    assert_eq!(req.headers()["Accept"], MSGPACK_CONTENT_TYPE);
+    let method = req.method().as_str().to_owned();
+    let path = req.uri().path().to_owned();
+    let content_type = req.headers().get("Content-Type").cloned();
+    let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+
+    let (status, body) = match (method.as_str(), path.as_str()) {
+        ("GET", "/api/monsters/1") => (
+            200,
+            msgpack::to_vec(&Ok::<_, MonsterError>(monster())).unwrap(),
+        ),
+        ("GET", "/api/monsters/2") => (
+            404,
+            msgpack::to_vec(&Err::<Monster, _>(MonsterError::NotFound)).unwrap(),
+        ),
+        ("POST", "/api/monsters") => {
+            assert_eq!(content_type.unwrap(), MSGPACK_CONTENT_TYPE);
+            let mut monster: Monster = msgpack::from_slice(&body).unwrap();
+            monster.age += 1;
+            (200, msgpack::to_vec(&monster).unwrap())
+        }
+        _ => (500, b"unexpected request".to_vec()),
+    };
+    Ok(Response::builder()
+        .status(status)
+        .header("Content-Type", MSGPACK_CONTENT_TYPE)
+        .body(body.into())
+        .unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(serve))
+    }));
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let client = MonsterApiClient::new(Url::parse(&format!("http://{}/api", addr)).unwrap());
+
+    let m = client.get_monsters_id(1).await.unwrap();
+    assert_eq!(m.unwrap().portrait, monster().portrait);
+
+    let m = client.get_monsters_id(2).await.unwrap();
+    assert!(matches!(m, Err(MonsterError::NotFound)));
+
+    let m = client.post_monsters(monster()).await.unwrap();
+    assert_eq!(m.age, 10);
+}
@@ -0,0 +1,190 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub age: i32,
+    #[doc = ""]
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub portrait: Vec<u8>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum MonsterError {
+    #[doc = ""]
+    NotFound,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "cb00ebaf188cc226";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterError {
+    const WIRE_HASH: &'static str = "011df17b9e1e2062";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Monster\": \"cb00ebaf188cc226\",\n  \"MonsterError\": \"011df17b9e1e2062\"\n}";
+#[doc = r" The configuration of the clients of the services, see `Config`."]
+pub mod config {
+    #[doc = r" The base URL, timeout and default headers of the clients of the services, which"]
+    #[doc = r" create clients with `from_config`."]
+    #[derive(Debug, Clone)]
+    pub struct Config {
+        #[doc = r" The URL the services are mounted at."]
+        pub base_url: ::humblegen_rt::client::Url,
+        #[doc = r" The time after which requests fail, unlimited if `None`."]
+        pub timeout: Option<::std::time::Duration>,
+        #[doc = r" The headers sent with every request, e.g. an API key."]
+        pub default_headers: Vec<(
+            ::humblegen_rt::client::HeaderName,
+            ::humblegen_rt::client::HeaderValue,
+        )>,
+    }
+    impl Config {
+        #[doc = r" A config of clients of the services mounted at `base_url`, without timeout"]
+        #[doc = r" and default headers."]
+        pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+            Self {
+                base_url,
+                timeout: None,
+                default_headers: Vec::new(),
+            }
+        }
+        #[doc = r" Fail requests that are not completed within `timeout`."]
+        pub fn with_timeout(mut self, timeout: ::std::time::Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+        #[doc = r" Send header `name` with `value` in every request."]
+        pub fn with_default_header(
+            mut self,
+            name: ::humblegen_rt::client::HeaderName,
+            value: ::humblegen_rt::client::HeaderValue,
+        ) -> Self {
+            self.default_headers.push((name, value));
+            self
+        }
+    }
+}
+#[doc = "Client of service `MonsterApi`."]
+#[doc = ""]
+#[doc = ""]
+#[derive(Debug, Clone)]
+pub struct MonsterApiClient {
+    base_url: ::humblegen_rt::client::Url,
+    client: ::humblegen_rt::client::Client,
+}
+impl MonsterApiClient {
+    #[doc = r" The `User-Agent` of the client: the name and version of the crate it is compiled into,"]
+    #[doc = r" the service and the hash of the spec the client was generated from."]
+    pub const USER_AGENT: &'static str = concat!(
+        env!("CARGO_PKG_NAME"),
+        "/",
+        env!("CARGO_PKG_VERSION"),
+        " (MonsterApi; humblespec c6a3fb58033855c2)"
+    );
+    #[doc = r" Creates a client of the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+        Self::with_config(base_url, &Self::default_config())
+            .expect("the default client config is valid")
+    }
+    #[doc = r" The config of clients created with `new`, sending the `USER_AGENT`, to add default headers to."]
+    pub fn default_config() -> ::humblegen_rt::client::ClientConfig {
+        ::humblegen_rt::client::ClientConfig::new(Self::USER_AGENT)
+    }
+    #[doc = r" Like `new`, but sends the `User-Agent` and the default headers of `config` with every request."]
+    pub fn with_config(
+        base_url: ::humblegen_rt::client::Url,
+        config: &::humblegen_rt::client::ClientConfig,
+    ) -> Result<Self, ::humblegen_rt::client::ClientError> {
+        Ok(Self::with_client(base_url, config.build_client()?))
+    }
+    #[doc = r" Like `with_config`, but mounted at the base URL and with the timeout and default headers"]
+    #[doc = r" of `config`, which is shared by the clients of all services."]
+    pub fn from_config(
+        config: &config::Config,
+    ) -> Result<Self, ::humblegen_rt::client::ClientError> {
+        let mut client_config = Self::default_config();
+        if let Some(timeout) = config.timeout {
+            client_config = client_config.with_timeout(timeout);
+        }
+        for (name, value) in &config.default_headers {
+            client_config = client_config.with_default_header(name.clone(), value.clone());
+        }
+        Self::with_config(config.base_url.clone(), &client_config)
+    }
+    #[doc = r" Like `new`, but sends requests using `client`, e.g., to set timeouts."]
+    pub fn with_client(
+        base_url: ::humblegen_rt::client::Url,
+        client: ::humblegen_rt::client::Client,
+    ) -> Self {
+        Self { base_url, client }
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn get_monsters_id(
+        &self,
+        id: i32,
+    ) -> Result<Result<Monster, MonsterError>, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&id),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::GET, url);
+        let req = ::humblegen_rt::client::accept_wire_format(
+            req,
+            ::humblegen_rt::wire_format::WireFormat::Msgpack,
+        );
+        ::humblegen_rt::client::send(req, &[404u16]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn post_monsters(
+        &self,
+        post_body: Monster,
+    ) -> Result<Monster, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(&self.base_url, &["monsters".to_owned()])?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::POST, url);
+        let req = ::humblegen_rt::client::wire_format_body(
+            req,
+            ::humblegen_rt::wire_format::WireFormat::Msgpack,
+            &post_body,
+        )?;
+        let req = ::humblegen_rt::client::accept_wire_format(
+            req,
+            ::humblegen_rt::wire_format::WireFormat::Msgpack,
+        );
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = "Like `post_monsters`, but reports the progress of the upload of the body to `progress`."]
+    #[allow(unused_mut)]
+    pub async fn post_monsters_with_progress(
+        &self,
+        post_body: Monster,
+        progress: impl Fn(::humblegen_rt::client::UploadProgress) + Send + Sync + 'static,
+    ) -> Result<Monster, ::humblegen_rt::client::ClientError> {
+        let progress = ::humblegen_rt::client::UploadProgressReporter::new(progress);
+        let mut url = ::humblegen_rt::client::route_url(&self.base_url, &["monsters".to_owned()])?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::POST, url);
+        let req = ::humblegen_rt::client::wire_format_body(
+            req,
+            ::humblegen_rt::wire_format::WireFormat::Msgpack,
+            &post_body,
+        )?;
+        let req = ::humblegen_rt::client::accept_wire_format(
+            req,
+            ::humblegen_rt::wire_format::WireFormat::Msgpack,
+        );
+        ::humblegen_rt::client::send_with_progress(&self.client, req, &progress, &[]).await
+    }
+}
@@ -0,0 +1,97 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::client::{ClientError, Url};
+use humblegen_rt::hyper::service::{make_service_fn, service_fn};
+use humblegen_rt::hyper::{Body, Request, Response, Server};
+use protocol::*;
+use std::convert::Infallible;
+use tokio::stream::StreamExt;
+
+/// Stands in for a humblegen server, serving the pages of three monsters.
+async fn serve(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let path = req.uri().path().to_owned();
+    let query = req.uri().query().unwrap_or("").to_owned();
+    let (status, body) = match (path.as_str(), query.as_str()) {
+        ("/api/lairs/1/monsters", "species=dragon") => {
+            assert_eq!(req.headers()["X-Tenant"], "acme");
+            (
+                200,
+                r#"{"items":[{"name":"Smaug"},{"name":"Fafnir"}],"next_cursor":"2","total":3}"#,
+            )
+        }
+        ("/api/lairs/1/monsters", "cursor=2&species=dragon") => {
+            assert_eq!(req.headers()["X-Tenant"], "acme");
+            (
+                200,
+                r#"{"items":[{"name":"Glaurung"}],"next_cursor":null,"total":3}"#,
+            )
+        }
+        ("/api/names", "cursor=7") => (200, r#"{"items":["Nessie"],"next_cursor":null,"total":null}"#),
+        _ => (500, "unexpected request"),
+    };
+    Ok(Response::builder()
+        .status(status)
+        .body(body.into())
+        .unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(serve))
+    }));
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let client = MonsterApiClient::new(Url::parse(&format!("http://{}/api", addr)).unwrap());
+    let query = MonsterQuery {
+        cursor: None,
+        species: Some("dragon".to_owned()),
+    };
+
+    // `paginated[T]` returns the standard envelope
+    let page: MonsterPage = client
+        .get_lairs_lair_monsters(Some(query.clone()), 1, "acme".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.next_cursor.as_deref(), Some("2"));
+    assert_eq!(page.total, Some(3));
+
+    // the pages are fetched lazily, following the cursors until the last page
+    let pages: Vec<MonsterPage> = client
+        .get_lairs_lair_monsters_pages(query.clone(), 1, "acme".to_owned())
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    let names: Vec<_> = pages
+        .into_iter()
+        .flat_map(|page| page.items)
+        .map(|monster| monster.name)
+        .collect();
+    assert_eq!(names, ["Smaug", "Fafnir", "Glaurung"]);
+
+    // iteration starts at the cursor of the query
+    let pages: Vec<StrPage> = client
+        .get_names_pages(MonsterQuery {
+            cursor: Some("7".to_owned()),
+            species: None,
+        })
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].items, ["Nessie"]);
+
+    // the stream ends after the first error
+    let results: Vec<_> = client
+        .get_lairs_lair_monsters_pages(query, 2, "acme".to_owned())
+        .collect()
+        .await;
+    assert_eq!(results.len(), 1);
+    assert!(matches!(
+        results[0],
+        Err(ClientError::UnexpectedStatus { status: 500, .. })
+    ));
+}
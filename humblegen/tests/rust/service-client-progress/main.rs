@@ -0,0 +1,90 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::client::{UploadProgress, Url, UPLOAD_CHUNK_SIZE};
+use humblegen_rt::hyper::service::{make_service_fn, service_fn};
+use humblegen_rt::hyper::{self, Body, Request, Response, Server};
+use protocol::*;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+/// Stands in for a humblegen server, answering with the size of the request body.
+async fn serve(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+    Ok(Response::new(body.len().to_string().into()))
+}
+
+/// A progress callback and the progress it recorded.
+fn recorder() -> (
+    impl Fn(UploadProgress) + Send + Sync + 'static,
+    Arc<Mutex<Vec<UploadProgress>>>,
+) {
+    let recorded = Arc::new(Mutex::new(vec![]));
+    let recorded2 = Arc::clone(&recorded);
+    (
+        move |progress| recorded2.lock().unwrap().push(progress),
+        recorded,
+    )
+}
+
+fn assert_complete(recorded: &[UploadProgress], total: u64) {
+    assert_eq!(recorded.last().unwrap().sent, total);
+    assert!(recorded.iter().all(|p| p.total == total));
+    assert!(recorded.windows(2).all(|w| w[0].sent < w[1].sent));
+}
+
+#[tokio::main]
+async fn main() {
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(serve))
+    }));
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let client = MonsterApiClient::new(Url::parse(&format!("http://{}/api", addr)).unwrap());
+
+    // multipart bodies report their file parts
+    let image = vec![7; 3 * UPLOAD_CHUNK_SIZE + 1];
+    let (progress, recorded) = recorder();
+    let len = client
+        .post_monsters_id_portrait_with_progress(
+            Portrait {
+                caption: "Godzilla".to_owned(),
+                image: image.clone(),
+            },
+            1,
+            progress,
+        )
+        .await
+        .unwrap();
+    assert!(len as usize > image.len());
+    let recorded = recorded.lock().unwrap();
+    assert_eq!(recorded.len(), 4);
+    assert_complete(&recorded, image.len() as u64);
+
+    // other bodies report all of their bytes
+    let attachments = vec![
+        Attachment::Text("roar".to_owned()),
+        Attachment::Image(vec![1; 2 * UPLOAD_CHUNK_SIZE]),
+    ];
+    let (progress, recorded) = recorder();
+    let len = client
+        .post_monsters_id_attachments_with_progress(attachments.clone(), 1, progress)
+        .await
+        .unwrap();
+    assert_eq!(
+        len as usize,
+        serde_json::to_vec(&attachments).unwrap().len()
+    );
+    assert_complete(&recorded.lock().unwrap(), len.into());
+
+    // the methods without progress are unaffected
+    let len = client
+        .post_monsters_id_attachments(attachments.clone(), 1)
+        .await
+        .unwrap();
+    assert_eq!(
+        len as usize,
+        serde_json::to_vec(&attachments).unwrap().len()
+    );
+}
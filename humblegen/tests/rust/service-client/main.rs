@@ -0,0 +1,210 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::client::{ClientError, HeaderName, HeaderValue, Url};
+use humblegen_rt::hyper::service::{make_service_fn, service_fn};
+use humblegen_rt::hyper::{self, Body, Request, Response, Server};
+use humblegen_rt::service_protocol::{ErrorResponseKind, ServiceError, ToErrorResponse};
+use protocol::*;
+use std::convert::Infallible;
+
+/// Stands in for a humblegen server, checking the requests sent by the client.
+async fn serve(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let method = req.method().as_str().to_owned();
+    let path = req.uri().path().to_owned();
+    let query = req.uri().query().unwrap_or("").to_owned();
+    let headers = req.headers().clone();
+    let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+
+    let (status, body) = match (method.as_str(), path.as_str()) {
+        ("GET", "/api/monsters") => (200, format!(r#"[{{"name":{:?},"age":1}}]"#, query)),
+        ("GET", "/api/monsters/1") => {
+            let request_id = headers
+                .get("X-Request-Id")
+                .map(|v| v.to_str().unwrap())
+                .unwrap_or("none");
+            (
+                200,
+                format!(r#"{{"Ok":{{"name":{:?},"age":1}}}}"#, request_id),
+            )
+        }
+        ("GET", "/api/monsters/2") => (404, r#"{"Err":"NotFound"}"#.to_owned()),
+        ("GET", "/api/monsters/3") => {
+            let e = ServiceError::Authentication.to_error_response();
+            (e.code, serde_json::to_string(&e).unwrap())
+        }
+        ("POST", "/api/monsters") => {
+            assert_eq!(headers["X-Api-Key"], "secret");
+            let monster: Monster = serde_json::from_slice(&body).unwrap();
+            (200, monster.age.to_string())
+        }
+        ("POST", "/api/monsters/7/portrait") => {
+            let content_type = headers["Content-Type"].to_str().unwrap();
+            assert!(content_type.starts_with("multipart/form-data"));
+            let body = String::from_utf8_lossy(&body);
+            assert!(body.contains("name=\"caption\"\r\n\r\nGodzilla"));
+            assert!(!body.contains("name=\"note\""));
+            (200, "null".to_owned())
+        }
+        // routes returning `()` respond 204 No Content without a body
+        ("DELETE", "/api/monsters/7") => {
+            let user_agent = headers["User-Agent"].to_str().unwrap();
+            assert_eq!(user_agent, MonsterApiClient::USER_AGENT);
+            assert!(user_agent.contains(" (MonsterApi; humblespec "));
+            assert!(!headers.contains_key("X-Tenant"));
+            (204, String::new())
+        }
+        ("DELETE", "/api/monsters/8") => {
+            assert_eq!(headers["X-Tenant"], "acme");
+            assert_eq!(headers["User-Agent"], MonsterApiClient::USER_AGENT);
+            (204, String::new())
+        }
+        // path parameters are formatted like the server parses them, and percent-encoded
+        ("GET", "/api/lairs/67e55044-10b1-426f-9247-bb680e5fe0c8/monsters/18446744073709551615")
+        | ("GET", "/api/search/red%20dragon%2Fwyrm")
+        // the slashes of the rest of the path are kept
+        | ("GET", "/api/files/portraits/red%20dragon.png") => (200, r#""found""#.to_owned()),
+        // idempotent routes answer with the idempotency key
+        ("PUT", "/api/monsters/7/owner") => (
+            200,
+            format!("{:?}", headers["Idempotency-Key"].to_str().unwrap()),
+        ),
+        // response headers declared by the route
+        ("GET", "/api/monsters/1/meta") => {
+            return Ok(Response::builder()
+                .header("ETag", "\"v1\"")
+                .body(r#"{"name":"Godzilla","age":1}"#.into())
+                .unwrap());
+        }
+        ("GET", "/api/monsters/2/meta") => (200, r#"{"name":"Godzilla","age":1}"#.to_owned()),
+        _ => (500, "unexpected request".to_owned()),
+    };
+    Ok(Response::builder()
+        .status(status)
+        .body(body.into())
+        .unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(serve))
+    }));
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    let client = MonsterApiClient::new(Url::parse(&format!("http://{}/api", addr)).unwrap());
+
+    let monsters = client
+        .get_monsters(Some(MonsterQuery {
+            name: "Godzilla".to_owned(),
+        }))
+        .await
+        .unwrap();
+    assert_eq!(monsters[0].name, "name=Godzilla");
+
+    let monster = client
+        .get_monsters_id(1, Some("r-1".to_owned()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(monster.name, "r-1");
+
+    // domain errors are part of the return value
+    match client.get_monsters_id(2, None).await {
+        Ok(Err(MonsterError::NotFound)) => {}
+        other => panic!("expected domain error, got {:?}", other),
+    }
+
+    // protocol errors are client errors
+    match client.get_monsters_id(3, None).await {
+        Err(ClientError::ErrorResponse(e)) => {
+            assert_eq!(e.code, 401);
+            assert!(matches!(
+                e.kind,
+                ErrorResponseKind::Service(ServiceError::Authentication)
+            ));
+        }
+        other => panic!("expected error response, got {:?}", other),
+    }
+
+    let age = client
+        .post_monsters(
+            Monster {
+                name: "Godzilla".to_owned(),
+                age: 42,
+            },
+            "secret".to_owned(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(age, 42);
+
+    client
+        .post_monsters_id_portrait(
+            Portrait {
+                caption: "Godzilla".to_owned(),
+                image: b"PNG".to_vec(),
+                note: None,
+            },
+            7,
+        )
+        .await
+        .unwrap();
+
+    client.delete_monsters_id(7).await.unwrap();
+
+    // idempotent routes send a new key per request, or the key of the request to retry
+    let key = client
+        .put_monsters_id_owner("Ann".to_owned(), 7)
+        .await
+        .unwrap();
+    assert_eq!(key.len(), 36);
+    let other_key = client
+        .put_monsters_id_owner("Ann".to_owned(), 7)
+        .await
+        .unwrap();
+    assert_ne!(key, other_key);
+    let retried_key = client
+        .put_monsters_id_owner_with_idempotency_key(key.clone(), "Ann".to_owned(), 7)
+        .await
+        .unwrap();
+    assert_eq!(retried_key, key);
+
+    let lair = "67e55044-10b1-426f-9247-bb680e5fe0c8".parse().unwrap();
+    let found = client
+        .get_lairs_lair_monsters_monster(lair, u64::MAX)
+        .await
+        .unwrap();
+    assert_eq!(found, "found");
+    let found = client
+        .get_search_term("red dragon/wyrm".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(found, "found");
+    let found = client
+        .get_files_path("portraits/red dragon.png".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(found, "found");
+
+    let meta = client.get_monsters_id_meta(1).await.unwrap();
+    assert_eq!(meta.value.name, "Godzilla");
+    assert_eq!(meta.headers.e_tag, "\"v1\"");
+    assert_eq!(meta.headers.x_version, None);
+    // required response headers are missing
+    match client.get_monsters_id_meta(2).await {
+        Err(ClientError::InvalidResponseHeader(e)) => assert_eq!(e.name, "ETag"),
+        other => panic!("expected invalid response header, got {:?}", other),
+    }
+
+    // default headers are configured once for all requests
+    let config = MonsterApiClient::default_config().with_default_header(
+        HeaderName::from_static("x-tenant"),
+        HeaderValue::from_static("acme"),
+    );
+    let client =
+        MonsterApiClient::with_config(Url::parse(&format!("http://{}/api", addr)).unwrap(), &config)
+            .unwrap();
+    client.delete_monsters_id(8).await.unwrap();
+}
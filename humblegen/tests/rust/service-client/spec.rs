@@ -0,0 +1,429 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub age: i32,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct MonsterQuery {
+    #[doc = ""]
+    pub name: String,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Portrait {
+    #[doc = ""]
+    pub caption: String,
+    #[doc = ""]
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub image: Vec<u8>,
+    #[doc = ""]
+    pub note: Option<String>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum MonsterError {
+    #[doc = ""]
+    NotFound,
+    #[doc = ""]
+    Invalid(String),
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "ed40f0b4893d6ae4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterError {
+    const WIRE_HASH: &'static str = "1abbbeb9152d150e";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterQuery {
+    const WIRE_HASH: &'static str = "1136063428524aa5";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Portrait {
+    const WIRE_HASH: &'static str = "d3743b3c3b3ff536";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Monster\": \"ed40f0b4893d6ae4\",\n  \"MonsterError\": \"1abbbeb9152d150e\",\n  \"MonsterQuery\": \"1136063428524aa5\",\n  \"Portrait\": \"d3743b3c3b3ff536\"\n}" ;
+#[doc = "The response headers of `GET /monsters/{id}/meta` of service `MonsterApi`."]
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+pub struct MonsterApiGetMonstersIdMetaResponseHeaders {
+    #[doc = "Header `ETag`."]
+    pub e_tag: String,
+    #[doc = "Header `X-Version`."]
+    pub x_version: Option<u32>,
+}
+impl ::humblegen_rt::response_headers::ResponseHeaders
+    for MonsterApiGetMonstersIdMetaResponseHeaders
+{
+    fn write(
+        &self,
+        headers: &mut ::humblegen_rt::hyper::HeaderMap,
+    ) -> Result<(), ::humblegen_rt::response_headers::InvalidHeader> {
+        ::humblegen_rt::response_headers::write_header(headers, "ETag", &self.e_tag)?;
+        ::humblegen_rt::response_headers::write_opt_header(headers, "X-Version", &self.x_version)?;
+        Ok(())
+    }
+    fn read(
+        headers: &::humblegen_rt::hyper::HeaderMap,
+    ) -> Result<Self, ::humblegen_rt::response_headers::InvalidHeader> {
+        Ok(Self {
+            e_tag: ::humblegen_rt::response_headers::read_header(headers, "ETag")?,
+            x_version: ::humblegen_rt::response_headers::read_opt_header(headers, "X-Version")?,
+        })
+    }
+}
+#[doc = r" The configuration of the clients of the services, see `Config`."]
+pub mod config {
+    #[doc = r" The base URL, timeout and default headers of the clients of the services, which"]
+    #[doc = r" create clients with `from_config`."]
+    #[derive(Debug, Clone)]
+    pub struct Config {
+        #[doc = r" The URL the services are mounted at."]
+        pub base_url: ::humblegen_rt::client::Url,
+        #[doc = r" The time after which requests fail, unlimited if `None`."]
+        pub timeout: Option<::std::time::Duration>,
+        #[doc = r" The headers sent with every request, e.g. an API key."]
+        pub default_headers: Vec<(
+            ::humblegen_rt::client::HeaderName,
+            ::humblegen_rt::client::HeaderValue,
+        )>,
+    }
+    impl Config {
+        #[doc = r" A config of clients of the services mounted at `base_url`, without timeout"]
+        #[doc = r" and default headers."]
+        pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+            Self {
+                base_url,
+                timeout: None,
+                default_headers: Vec::new(),
+            }
+        }
+        #[doc = r" Fail requests that are not completed within `timeout`."]
+        pub fn with_timeout(mut self, timeout: ::std::time::Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+        #[doc = r" Send header `name` with `value` in every request."]
+        pub fn with_default_header(
+            mut self,
+            name: ::humblegen_rt::client::HeaderName,
+            value: ::humblegen_rt::client::HeaderValue,
+        ) -> Self {
+            self.default_headers.push((name, value));
+            self
+        }
+    }
+}
+#[doc = "Client of service `MonsterApi`."]
+#[doc = ""]
+#[doc = ""]
+#[derive(Debug, Clone)]
+pub struct MonsterApiClient {
+    base_url: ::humblegen_rt::client::Url,
+    client: ::humblegen_rt::client::Client,
+}
+impl MonsterApiClient {
+    #[doc = r" The `User-Agent` of the client: the name and version of the crate it is compiled into,"]
+    #[doc = r" the service and the hash of the spec the client was generated from."]
+    pub const USER_AGENT: &'static str = concat!(
+        env!("CARGO_PKG_NAME"),
+        "/",
+        env!("CARGO_PKG_VERSION"),
+        " (MonsterApi; humblespec fdb8202807a95225)"
+    );
+    #[doc = r" Creates a client of the service mounted at `base_url`, e.g. `http://localhost:3000/api`."]
+    pub fn new(base_url: ::humblegen_rt::client::Url) -> Self {
+        Self::with_config(base_url, &Self::default_config())
+            .expect("the default client config is valid")
+    }
+    #[doc = r" The config of clients created with `new`, sending the `USER_AGENT`, to add default headers to."]
+    pub fn default_config() -> ::humblegen_rt::client::ClientConfig {
+        ::humblegen_rt::client::ClientConfig::new(Self::USER_AGENT)
+    }
+    #[doc = r" Like `new`, but sends the `User-Agent` and the default headers of `config` with every request."]
+    pub fn with_config(
+        base_url: ::humblegen_rt::client::Url,
+        config: &::humblegen_rt::client::ClientConfig,
+    ) -> Result<Self, ::humblegen_rt::client::ClientError> {
+        Ok(Self::with_client(base_url, config.build_client()?))
+    }
+    #[doc = r" Like `with_config`, but mounted at the base URL and with the timeout and default headers"]
+    #[doc = r" of `config`, which is shared by the clients of all services."]
+    pub fn from_config(
+        config: &config::Config,
+    ) -> Result<Self, ::humblegen_rt::client::ClientError> {
+        let mut client_config = Self::default_config();
+        if let Some(timeout) = config.timeout {
+            client_config = client_config.with_timeout(timeout);
+        }
+        for (name, value) in &config.default_headers {
+            client_config = client_config.with_default_header(name.clone(), value.clone());
+        }
+        Self::with_config(config.base_url.clone(), &client_config)
+    }
+    #[doc = r" Like `new`, but sends requests using `client`, e.g., to set timeouts."]
+    pub fn with_client(
+        base_url: ::humblegen_rt::client::Url,
+        client: ::humblegen_rt::client::Client,
+    ) -> Self {
+        Self { base_url, client }
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn get_monsters(
+        &self,
+        query: Option<MonsterQuery>,
+    ) -> Result<Vec<Monster>, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(&self.base_url, &["monsters".to_owned()])?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::GET, url);
+        let req = match &query {
+            Some(query) => req.query(query),
+            None => req,
+        };
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn get_monsters_id(
+        &self,
+        id: i32,
+        x_request_id: Option<String>,
+    ) -> Result<Result<Monster, MonsterError>, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&id),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::GET, url);
+        let req = match &x_request_id {
+            Some(value) => req.header(
+                "X-Request-Id",
+                ::humblegen_rt::client::ToParam::to_param(value),
+            ),
+            None => req,
+        };
+        ::humblegen_rt::client::send(req, &[404u16]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn post_monsters(
+        &self,
+        post_body: Monster,
+        x_api_key: String,
+    ) -> Result<i32, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(&self.base_url, &["monsters".to_owned()])?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::POST, url);
+        let req = req.json(&post_body);
+        let req = req.header(
+            "X-Api-Key",
+            ::humblegen_rt::client::ToParam::to_param(&x_api_key),
+        );
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn post_monsters_id_portrait(
+        &self,
+        post_body: Portrait,
+        id: i32,
+    ) -> Result<(), ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&id),
+                "portrait".to_owned(),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::POST, url);
+        let req = req
+            .multipart(::humblegen_rt::client::ToMultipart::to_multipart(post_body)?.into_form());
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = "Like `post_monsters_id_portrait`, but reports the progress of the upload of the body to `progress`."]
+    #[allow(unused_mut)]
+    pub async fn post_monsters_id_portrait_with_progress(
+        &self,
+        post_body: Portrait,
+        id: i32,
+        progress: impl Fn(::humblegen_rt::client::UploadProgress) + Send + Sync + 'static,
+    ) -> Result<(), ::humblegen_rt::client::ClientError> {
+        let progress = ::humblegen_rt::client::UploadProgressReporter::new(progress);
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&id),
+                "portrait".to_owned(),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::POST, url);
+        let req = req.multipart(
+            ::humblegen_rt::client::ToMultipart::to_multipart(post_body)?
+                .into_form_with_progress(&progress),
+        );
+        ::humblegen_rt::client::send_with_progress(&self.client, req, &progress, &[]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn delete_monsters_id(
+        &self,
+        id: i32,
+    ) -> Result<(), ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&id),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::DELETE, url);
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = ""]
+    pub async fn put_monsters_id_owner(
+        &self,
+        post_body: String,
+        id: i32,
+    ) -> Result<String, ::humblegen_rt::client::ClientError> {
+        self.put_monsters_id_owner_with_idempotency_key(
+            ::humblegen_rt::client::new_idempotency_key(),
+            post_body,
+            id,
+        )
+        .await
+    }
+    #[doc = "Like `put_monsters_id_owner`, but sends `idempotency_key` instead of a new key, e.g. the key of a request to retry, which the server answers like the request with the key before."]
+    #[allow(unused_mut)]
+    pub async fn put_monsters_id_owner_with_idempotency_key(
+        &self,
+        idempotency_key: String,
+        post_body: String,
+        id: i32,
+    ) -> Result<String, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&id),
+                "owner".to_owned(),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::PUT, url);
+        let req = req.json(&post_body);
+        let req = req.header(
+            ::humblegen_rt::idempotency::IDEMPOTENCY_KEY,
+            idempotency_key.as_str(),
+        );
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn get_lairs_lair_monsters_monster(
+        &self,
+        lair: ::humblegen_rt::uuid::Uuid,
+        monster: u64,
+    ) -> Result<String, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "lairs".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&lair),
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&monster),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::GET, url);
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn get_search_term(
+        &self,
+        term: String,
+    ) -> Result<String, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "search".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&term),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::GET, url);
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn get_files_path(
+        &self,
+        path: String,
+    ) -> Result<String, ::humblegen_rt::client::ClientError> {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                vec!["files".to_owned()],
+                ::humblegen_rt::client::rest_segments(&path),
+            ]
+            .concat(),
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::GET, url);
+        ::humblegen_rt::client::send(req, &[]).await
+    }
+    #[doc = ""]
+    #[allow(unused_mut)]
+    pub async fn get_monsters_id_meta(
+        &self,
+        id: i32,
+    ) -> Result<
+        ::humblegen_rt::handler::WithHeaders<Monster, MonsterApiGetMonstersIdMetaResponseHeaders>,
+        ::humblegen_rt::client::ClientError,
+    > {
+        let mut url = ::humblegen_rt::client::route_url(
+            &self.base_url,
+            &[
+                "monsters".to_owned(),
+                ::humblegen_rt::client::ToParam::to_param(&id),
+                "meta".to_owned(),
+            ],
+        )?;
+        let req = self
+            .client
+            .request(::humblegen_rt::client::Method::GET, url);
+        ::humblegen_rt::client::send_with_headers(req, &[]).await
+    }
+}
+impl ::humblegen_rt::client::ToMultipart for Portrait {
+    fn to_multipart(
+        self,
+    ) -> Result<::humblegen_rt::client::MultipartForm, ::humblegen_rt::client::ClientError> {
+        let form = ::humblegen_rt::client::MultipartForm::new();
+        let form = form.text("caption", self.caption);
+        let form = form.file("image", self.image);
+        let form = form.opt_text("note", self.note);
+        Ok(form)
+    }
+}
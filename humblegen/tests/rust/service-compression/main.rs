@@ -0,0 +1,172 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::compression::{Encoding, DECOMPRESSED_CHUNK_SIZE};
+use tokio::stream::StreamExt;
+use humblegen_rt::hyper::{self, header, Body, HeaderMap, Request};
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+fn monsters(count: u32) -> Vec<Monster> {
+    (0..count as i32)
+        .map(|age| Monster {
+            name: "dragon".to_owned(),
+            age,
+        })
+        .collect()
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_count(&self, _ctx: Self::Context, count: u32) -> Response<Vec<Monster>> {
+        Ok(monsters(count))
+    }
+
+    async fn get_archive(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("x".repeat(4096))
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, post_body: Vec<Monster>) -> Response<u64> {
+        Ok(post_body.len() as u64)
+    }
+
+    async fn post_names(&self, _ctx: Self::Context, post_body: String) -> Response<u64> {
+        Ok(post_body.len() as u64)
+    }
+}
+
+async fn request(services: &Arc<Vec<Service>>, req: Request<Body>) -> (u16, HeaderMap, Vec<u8>) {
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let headers = resp.headers().clone();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, headers, body.to_vec())
+}
+
+fn get(path: &str, accept_encoding: &str) -> Request<Body> {
+    Request::get(path)
+        .header(header::ACCEPT_ENCODING, accept_encoding)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn post(path: &str, encoding: Encoding, body: &str) -> Request<Body> {
+    Request::post(path)
+        .header(header::CONTENT_ENCODING, encoding.as_str())
+        .body(Body::from(encoding.encode(body.as_bytes()).unwrap()))
+        .unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+    let json = serde_json::to_vec(&monsters(100)).unwrap();
+
+    // large responses are compressed in the accepted encoding
+    let (status, headers, body) = request(&services, get("/api/monsters/100", "gzip")).await;
+    assert_eq!(status, 200);
+    assert_eq!(headers[header::CONTENT_ENCODING], "gzip");
+    assert_eq!(headers[header::VARY], "Accept-Encoding");
+    assert!(body.len() < json.len() / 4);
+    assert_eq!(Encoding::Gzip.decode(&body).unwrap(), json);
+
+    // ... the one with the highest quality
+    let accept_encoding = "zstd, gzip;q=0.5, deflate;q=0.8";
+    let (_, headers, body) = request(&services, get("/api/monsters/100", accept_encoding)).await;
+    assert_eq!(headers[header::CONTENT_ENCODING], "deflate");
+    assert_eq!(Encoding::Deflate.decode(&body).unwrap(), json);
+    let (_, headers, body) = request(&services, get("/api/monsters/100", "br")).await;
+    assert_eq!(headers[header::CONTENT_ENCODING], "br");
+    assert!(body.len() < json.len() / 4);
+    assert_eq!(Encoding::Brotli.decode(&body).unwrap(), json);
+
+    // `*` stands for the encodings that are not listed
+    for (accept_encoding, encoding) in &[
+        ("*", "gzip"),
+        ("gzip;q=0, *", "deflate"),
+        ("gzip;q=0, deflate;q=0, *;q=0.5", "br"),
+        ("br;q=0.5, *", "gzip"),
+        ("*;q=0.5, br", "br"),
+    ] {
+        let (_, headers, _) = request(&services, get("/api/monsters/100", accept_encoding)).await;
+        assert_eq!(headers[header::CONTENT_ENCODING], *encoding, "{}", accept_encoding);
+    }
+
+    // ... unless no encoding is accepted
+    for accept_encoding in &[
+        "identity",
+        "zstd",
+        "gzip;q=0",
+        "*;q=0",
+        "gzip;q=0, deflate;q=0, br;q=0, *",
+    ] {
+        let (_, headers, body) =
+            request(&services, get("/api/monsters/100", accept_encoding)).await;
+        assert!(!headers.contains_key(header::CONTENT_ENCODING));
+        assert_eq!(headers[header::VARY], "Accept-Encoding");
+        assert_eq!(body, json);
+    }
+
+    // small responses are sent as is
+    let (_, headers, body) = request(&services, get("/api/monsters/1", "gzip")).await;
+    assert!(!headers.contains_key(header::CONTENT_ENCODING));
+    assert_eq!(headers[header::VARY], "Accept-Encoding");
+    assert_eq!(body, serde_json::to_vec(&monsters(1)).unwrap());
+
+    // ... like the responses of routes annotated with `@uncompressed`
+    let (status, headers, body) = request(&services, get("/api/archive", "gzip")).await;
+    assert_eq!(status, 200);
+    assert!(!headers.contains_key(header::CONTENT_ENCODING));
+    assert!(!headers.contains_key(header::VARY));
+    assert_eq!(body.len(), 4096 + 2);
+
+    // compressed request bodies are decompressed
+    let json = String::from_utf8(json).unwrap();
+    for &encoding in &[Encoding::Gzip, Encoding::Deflate, Encoding::Brotli] {
+        let (status, _, body) = request(&services, post("/api/monsters", encoding, &json)).await;
+        assert_eq!(status, 200);
+        assert_eq!(body, b"100");
+    }
+
+    // ... and limited by their decompressed size
+    let names = format!("{:?}", "dragon".repeat(100));
+    let (status, _, body) = request(&services, post("/api/names", Encoding::Gzip, &names)).await;
+    assert_eq!(status, 200);
+    assert_eq!(body, b"600");
+    let names = format!("{:?}", "dragon".repeat(1000));
+    let (status, _, _) = request(&services, post("/api/names", Encoding::Gzip, &names)).await;
+    assert_eq!(status, 413);
+
+    // ... before decompressing more, e.g. of bombs decompressing to far more than their size
+    for &encoding in &[Encoding::Gzip, Encoding::Deflate, Encoding::Brotli] {
+        let bomb = encoding.encode(&vec![b' '; 64 << 20]).unwrap();
+        let mut req = post("/api/names", encoding, "");
+        *req.body_mut() = Body::from(bomb);
+        humblegen_rt::compression::decompress_request(&mut req).unwrap();
+        let chunk = req.body_mut().next().await.unwrap().unwrap();
+        assert_eq!(chunk.len(), DECOMPRESSED_CHUNK_SIZE);
+        let status = humblegen_rt::limits::limit_body(&mut req, 1 << 20)
+            .await
+            .unwrap_err()
+            .to_hyper_response()
+            .status();
+        assert_eq!(status, 413);
+    }
+
+    // bodies of other encodings are rejected
+    let req = Request::post("/api/names")
+        .header(header::CONTENT_ENCODING, "zstd")
+        .body(Body::from(r#""dragon""#))
+        .unwrap();
+    let (status, _, _) = request(&services, req).await;
+    assert_eq!(status, 415);
+}
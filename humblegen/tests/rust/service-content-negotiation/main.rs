@@ -0,0 +1,149 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper::{self, Body, Request};
+use humblegen_rt::middleware::{Middleware, Next, RouteInfo};
+use humblegen_rt::msgpack;
+use humblegen_rt::serde_cbor;
+use humblegen_rt::server::Service;
+use humblegen_rt::wire_format::{
+    Negotiated, WireFormat, CBOR_CONTENT_TYPE, JSON_CONTENT_TYPE, MSGPACK_CONTENT_TYPE,
+};
+use protocol::*;
+use std::sync::{Arc, Mutex};
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<Monster> {
+        Ok(Monster {
+            name: "dragon".to_owned(),
+            age: id,
+        })
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, post_body: Monster) -> Response<Monster> {
+        Ok(Monster {
+            age: post_body.age + 1,
+            ..post_body
+        })
+    }
+}
+
+/// Records the negotiated formats, and answers clients sending `X-Legacy` in JSON.
+#[derive(Default)]
+struct Formats(Mutex<Vec<Negotiated>>);
+
+#[humblegen_rt::async_trait(Sync)]
+impl Middleware for Formats {
+    async fn handle(
+        &self,
+        mut req: Request<Body>,
+        _route: &'static RouteInfo,
+        next: Next,
+    ) -> hyper::Response<Body> {
+        self.0.lock().unwrap().push(Negotiated::of(&req));
+        if req.headers().contains_key("X-Legacy") {
+            let negotiated = Negotiated {
+                response: WireFormat::Json,
+                ..Negotiated::of(&req)
+            };
+            req.extensions_mut().insert(negotiated);
+        }
+        next.run(req).await
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    req: hyper::http::request::Builder,
+    body: Vec<u8>,
+) -> (Option<String>, Vec<u8>) {
+    let req = req.body(Body::from(body)).unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    assert_eq!(resp.status(), 200);
+    let content_type = resp
+        .headers()
+        .get("Content-Type")
+        .map(|v| v.to_str().unwrap().to_owned());
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (content_type, body.to_vec())
+}
+
+fn get(accept: &str) -> hyper::http::request::Builder {
+    Request::get("/api/monsters/7").header("Accept", accept)
+}
+
+#[tokio::main]
+async fn main() {
+    let formats = Arc::new(Formats::default());
+    let services = Arc::new(
+        Builder::new()
+            .with_middleware(Arc::clone(&formats) as _)
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+
+    // the response format is negotiated per request among the formats of the service
+    let (content_type, body) = request(&services, get(CBOR_CONTENT_TYPE), vec![]).await;
+    assert_eq!(content_type.as_deref(), Some(CBOR_CONTENT_TYPE));
+    let monster: Monster = serde_cbor::from_slice(&body).unwrap();
+    assert_eq!(monster.age, 7);
+    let (content_type, body) = request(&services, get(MSGPACK_CONTENT_TYPE), vec![]).await;
+    assert_eq!(content_type.as_deref(), Some(MSGPACK_CONTENT_TYPE));
+    let monster: Monster = msgpack::from_slice(&body).unwrap();
+    assert_eq!(monster.age, 7);
+    let (content_type, _) = request(&services, get(JSON_CONTENT_TYPE), vec![]).await;
+    assert_eq!(content_type.as_deref(), Some(JSON_CONTENT_TYPE));
+
+    // ... by the quality of the listed formats, the first one among equals
+    let accept = "application/json, application/cbor;q=0.5, application/msgpack;q=0.8";
+    let (content_type, _) = request(&services, get(accept), vec![]).await;
+    assert_eq!(content_type.as_deref(), Some(MSGPACK_CONTENT_TYPE));
+    let accept = "application/msgpack, application/cbor";
+    let (content_type, _) = request(&services, get(accept), vec![]).await;
+    assert_eq!(content_type.as_deref(), Some(MSGPACK_CONTENT_TYPE));
+    let accept = "application/msgpack;q=0, application/cbor;q=0.1";
+    let (content_type, _) = request(&services, get(accept), vec![]).await;
+    assert_eq!(content_type.as_deref(), Some(CBOR_CONTENT_TYPE));
+
+    // request bodies are decoded independently of the response format
+    let post = Request::post("/api/monsters")
+        .header("Content-Type", CBOR_CONTENT_TYPE)
+        .header("Accept", MSGPACK_CONTENT_TYPE);
+    let monster = Monster {
+        name: "dragon".to_owned(),
+        age: 1,
+    };
+    let (content_type, body) = request(&services, post, serde_cbor::to_vec(&monster).unwrap()).await;
+    assert_eq!(content_type.as_deref(), Some(MSGPACK_CONTENT_TYPE));
+    let monster: Monster = msgpack::from_slice(&body).unwrap();
+    assert_eq!(monster.age, 2);
+
+    // middleware sees the negotiated formats and may replace them
+    let (content_type, _) = request(
+        &services,
+        get(CBOR_CONTENT_TYPE).header("X-Legacy", "1"),
+        vec![],
+    )
+    .await;
+    assert_eq!(content_type.as_deref(), Some(JSON_CONTENT_TYPE));
+    let recorded = formats.0.lock().unwrap();
+    assert_eq!(
+        recorded[recorded.len() - 2],
+        Negotiated {
+            request: WireFormat::Cbor,
+            response: WireFormat::Msgpack,
+        }
+    );
+    assert_eq!(
+        recorded[recorded.len() - 1],
+        Negotiated {
+            request: WireFormat::Json,
+            response: WireFormat::Cbor,
+        }
+    );
+}
@@ -0,0 +1,172 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::handler::WithHeaders;
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        _id: i32,
+    ) -> Response<WithHeaders<String, MonsterApiGetMonstersIdResponseHeaders>> {
+        Ok(WithHeaders::new(
+            "dragon".to_owned(),
+            MonsterApiGetMonstersIdResponseHeaders {
+                e_tag: "v1".to_owned(),
+            },
+        ))
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<()> {
+        Ok(())
+    }
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl LairApi for S {
+    type Context = ();
+
+    async fn get_lairs(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(vec![])
+    }
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl AdminApi for S {
+    type Context = ();
+
+    async fn get_admin(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("admin".to_owned())
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+) -> hyper::Response<hyper::Body> {
+    let mut req = hyper::Request::builder().method(method).uri(path);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let req = req.body(hyper::Body::empty()).unwrap();
+    humblegen_rt::server::handle_request(Arc::clone(services), req).await
+}
+
+fn header<'a>(resp: &'a hyper::Response<hyper::Body>, name: &str) -> Option<&'a str> {
+    resp.headers().get(name).map(|v| v.to_str().unwrap())
+}
+
+fn vary(resp: &hyper::Response<hyper::Body>) -> Vec<&str> {
+    resp.headers()
+        .get_all("Vary")
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect()
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .add("/lairs", Handler::LairApi(Arc::new(S)))
+            .add("/admin", Handler::AdminApi(Arc::new(S)))
+            .into_services(),
+    );
+    let preflight = [
+        ("Origin", "https://app.example.com"),
+        ("Access-Control-Request-Method", "DELETE"),
+        ("Access-Control-Request-Headers", "content-type"),
+    ];
+
+    // preflight requests from allowed origins are answered with the methods of the service
+    let resp = request(&services, "OPTIONS", "/api/monsters/1", &preflight).await;
+    assert_eq!(resp.status(), 204);
+    assert_eq!(
+        header(&resp, "Access-Control-Allow-Origin"),
+        Some("https://app.example.com")
+    );
+    assert_eq!(
+        header(&resp, "Access-Control-Allow-Methods"),
+        Some("GET, DELETE")
+    );
+    assert_eq!(
+        header(&resp, "Access-Control-Allow-Headers"),
+        Some("content-type")
+    );
+    assert_eq!(header(&resp, "Vary"), Some("Origin"));
+
+    // ... and rejected from other origins
+    let resp = request(
+        &services,
+        "OPTIONS",
+        "/api/monsters/1",
+        &[
+            ("Origin", "https://evil.example.com"),
+            ("Access-Control-Request-Method", "DELETE"),
+        ],
+    )
+    .await;
+    assert_eq!(resp.status(), 403);
+    assert_eq!(header(&resp, "Access-Control-Allow-Origin"), None);
+
+    // responses to allowed origins carry the origin and expose the declared response headers
+    let origin = [("Origin", "http://localhost:8000")];
+    let resp = request(&services, "GET", "/api/monsters/1", &origin).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        header(&resp, "Access-Control-Allow-Origin"),
+        Some("http://localhost:8000")
+    );
+    assert_eq!(header(&resp, "Access-Control-Expose-Headers"), Some("ETag"));
+    assert_eq!(header(&resp, "ETag"), Some("v1"));
+
+    let resp = request(
+        &services,
+        "GET",
+        "/api/monsters/1",
+        &[("Origin", "https://evil.example.com")],
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(header(&resp, "Access-Control-Allow-Origin"), None);
+    assert_eq!(vary(&resp), ["Accept-Encoding", "Origin"]);
+
+    // errors of the routes carry the CORS headers as well
+    let resp = request(&services, "GET", "/api/monsters/dragon", &origin).await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(
+        header(&resp, "Access-Control-Allow-Origin"),
+        Some("http://localhost:8000")
+    );
+
+    // `*` allows any origin
+    let resp = request(
+        &services,
+        "GET",
+        "/lairs/lairs",
+        &[("Origin", "https://evil.example.com")],
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(header(&resp, "Access-Control-Allow-Origin"), Some("*"));
+    assert_eq!(vary(&resp), ["Accept-Encoding"]);
+
+    // services without `@cors` neither answer preflight requests nor add CORS headers
+    let resp = request(&services, "OPTIONS", "/admin/admin", &preflight).await;
+    assert_eq!(resp.status(), 404);
+    let resp = request(&services, "GET", "/admin/admin", &origin).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(header(&resp, "Access-Control-Allow-Origin"), None);
+}
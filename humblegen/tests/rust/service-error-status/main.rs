@@ -0,0 +1,75 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: i32,
+    ) -> Response<Result<String, GetMonsterError>> {
+        Ok(match id {
+            1 => Ok("dragon".to_owned()),
+            2 => Err(GetMonsterError::NotFound),
+            3 => Err(GetMonsterError::Conflict("taken".to_owned())),
+            4 => Err(GetMonsterError::Invalid {
+                reason: "negative".to_owned(),
+            }),
+            _ => Err(GetMonsterError::Unannotated),
+        })
+    }
+}
+
+async fn request(services: &Arc<Vec<Service>>, path: &str) -> (u16, String) {
+    let req = hyper::Request::builder()
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+    let s = &services;
+
+    assert_eq!(
+        request(s, "/api/monsters/1").await,
+        (200, r#"{"Ok":"dragon"}"#.to_owned())
+    );
+    assert_eq!(
+        request(s, "/api/monsters/2").await,
+        (404, r#"{"Err":"NotFound"}"#.to_owned())
+    );
+    assert_eq!(
+        request(s, "/api/monsters/3").await,
+        (409, r#"{"Err":{"Conflict":"taken"}}"#.to_owned())
+    );
+    assert_eq!(
+        request(s, "/api/monsters/4").await,
+        (
+            422,
+            r#"{"Err":{"Invalid":{"reason":"negative"}}}"#.to_owned()
+        )
+    );
+    assert_eq!(
+        request(s, "/api/monsters/5").await,
+        (200, r#"{"Err":"Unannotated"}"#.to_owned())
+    );
+}
@@ -0,0 +1,78 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::feature_flags::{FeatureFlags, FlagState};
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl BillingApi for S {
+    type Context = ();
+
+    async fn get_customers(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(vec![])
+    }
+
+    async fn get_invoices(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(vec![])
+    }
+
+    async fn post_refunds(&self, _ctx: Self::Context, _post_body: String) -> Response<()> {
+        Ok(())
+    }
+}
+
+/// Enables `new-billing` for requests of the beta tenant, and takes down `refunds`.
+struct Flags;
+
+impl FeatureFlags for Flags {
+    fn state(&self, flag: &str, req: &hyper::Request<hyper::Body>) -> FlagState {
+        match flag {
+            "new-billing" if req.headers().contains_key("x-beta") => FlagState::Enabled,
+            "refunds" => FlagState::Unavailable,
+            _ => FlagState::Hidden,
+        }
+    }
+}
+
+async fn request(services: &Arc<Vec<Service>>, method: &str, path: &str, beta: bool) -> u16 {
+    let mut req = hyper::Request::builder().method(method).uri(path);
+    if beta {
+        req = req.header("x-beta", "1");
+    }
+    let body = if method == "POST" { "\"\"" } else { "" };
+    let resp =
+        humblegen_rt::server::handle_request(Arc::clone(services), req.body(body.into()).unwrap())
+            .await;
+    resp.status().as_u16()
+}
+
+#[tokio::main]
+async fn main() {
+    // without feature flags, flagged routes are hidden
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::BillingApi(Arc::new(S)))
+            .into_services(),
+    );
+    let s = &services;
+    assert_eq!(request(s, "GET", "/api/customers", false).await, 200);
+    assert_eq!(request(s, "GET", "/api/invoices", true).await, 404);
+    assert_eq!(request(s, "POST", "/api/refunds", false).await, 404);
+
+    let services = Arc::new(
+        Builder::new()
+            .with_feature_flags(Arc::new(Flags))
+            .add("/api", Handler::BillingApi(Arc::new(S)))
+            .into_services(),
+    );
+    let s = &services;
+    assert_eq!(request(s, "GET", "/api/customers", false).await, 200);
+    assert_eq!(request(s, "GET", "/api/invoices", false).await, 404);
+    assert_eq!(request(s, "GET", "/api/invoices", true).await, 200);
+    assert_eq!(request(s, "POST", "/api/refunds", true).await, 503);
+}
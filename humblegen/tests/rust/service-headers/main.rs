@@ -0,0 +1,99 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use humblegen_rt::uuid::Uuid;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: i32,
+        x_request_id: Uuid,
+        x_tenant: Option<String>,
+    ) -> Response<String> {
+        Ok(format!("{} {} {:?}", id, x_request_id, x_tenant))
+    }
+
+    async fn post_monsters(
+        &self,
+        _ctx: Self::Context,
+        post_body: String,
+        x_limit: u32,
+    ) -> Response<String> {
+        Ok(format!("{} {}", post_body, x_limit))
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &'static str,
+) -> (u16, String) {
+    let mut req = hyper::Request::builder().method(method).uri(path);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let req = req.body(hyper::Body::from(body)).unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+    let s = &services;
+
+    let id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    let ok = |s: &str| (200, format!("{:?}", s));
+    assert_eq!(
+        request(s, "GET", "/api/monsters/3", &[("X-Request-Id", id)], "").await,
+        ok(&format!("3 {} None", id))
+    );
+    assert_eq!(
+        request(
+            s,
+            "GET",
+            "/api/monsters/3",
+            &[("x-request-id", id), ("X-Tenant", "acme")],
+            ""
+        )
+        .await,
+        ok(&format!("3 {} Some(\"acme\")", id))
+    );
+    assert_eq!(
+        request(s, "POST", "/api/monsters", &[("X-Limit", "10")], "\"body\"").await,
+        ok("body 10")
+    );
+
+    let (status, body) = request(s, "GET", "/api/monsters/3", &[], "").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("HeaderInvalid"), "{}", body);
+    assert!(body.contains("missing"), "{}", body);
+    let (status, body) =
+        request(s, "GET", "/api/monsters/3", &[("X-Request-Id", "nope")], "").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("X-Request-Id"), "{}", body);
+    assert_eq!(
+        request(s, "POST", "/api/monsters", &[("X-Limit", "-1")], "\"body\"")
+            .await
+            .0,
+        400
+    );
+}
@@ -0,0 +1,186 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::idempotency::{Claim, IdempotencyStore, MemoryStore};
+use humblegen_rt::recording::RecordedResponse;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Counts the payments, which fail on account 0 and take a while on account 2.
+#[derive(Default)]
+struct S {
+    payments: AtomicU64,
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl PaymentApi for S {
+    type Context = ();
+
+    async fn post_accounts_account_payments(
+        &self,
+        _ctx: Self::Context,
+        post_body: Payment,
+        account: u32,
+    ) -> Response<u64> {
+        if account == 0 {
+            self.payments.fetch_add(1, Ordering::SeqCst);
+            return Err(ServiceError::Internal("payment provider unavailable".into()));
+        }
+        if account == 2 {
+            tokio::time::delay_for(Duration::from_millis(100)).await;
+        }
+        Ok(self.payments.fetch_add(1, Ordering::SeqCst) * 1000 + post_body.amount)
+    }
+
+    async fn post_transfers(&self, _ctx: Self::Context, post_body: Payment) -> Response<u64> {
+        Ok(self.payments.fetch_add(1, Ordering::SeqCst) * 1000 + post_body.amount)
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    path: &str,
+    headers: &[(&str, &str)],
+    amount: u64,
+) -> (u16, String) {
+    let mut req = hyper::Request::builder().method("POST").uri(path);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    let req = req
+        .body(hyper::Body::from(format!(r#"{{"amount":{}}}"#, amount)))
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn instance(handler: Arc<S>, store: Arc<dyn IdempotencyStore>) -> Arc<Vec<Service>> {
+    Arc::new(
+        Builder::new()
+            .with_idempotency_store(store)
+            .add("/api", Handler::PaymentApi(handler))
+            .into_services(),
+    )
+}
+
+#[tokio::main]
+async fn main() {
+    let handler = Arc::new(S::default());
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::PaymentApi(handler.clone()))
+            .into_services(),
+    );
+    let key = [("Idempotency-Key", "k-1")];
+
+    // repeated requests are answered with the response to the first one
+    let first = request(&services, "/api/accounts/1/payments", &key, 42).await;
+    assert_eq!(first, (200, "42".to_owned()));
+    assert_eq!(request(&services, "/api/accounts/1/payments", &key, 42).await, first);
+    assert_eq!(handler.payments.load(Ordering::SeqCst), 1);
+
+    // keys are scoped to the path and the `Authorization` header
+    assert_eq!(
+        request(&services, "/api/accounts/3/payments", &key, 42).await,
+        (200, "1042".to_owned())
+    );
+    let other_user = [("Idempotency-Key", "k-1"), ("Authorization", "Bearer other")];
+    assert_eq!(
+        request(&services, "/api/accounts/1/payments", &other_user, 42).await,
+        (200, "2042".to_owned())
+    );
+
+    // requests without a key are rejected, unless the route is not idempotent
+    assert_eq!(request(&services, "/api/accounts/1/payments", &[], 42).await.0, 400);
+    assert_eq!(request(&services, "/api/transfers", &[], 42).await.0, 200);
+    assert_eq!(request(&services, "/api/transfers", &[], 42).await.0, 200);
+    assert_eq!(handler.payments.load(Ordering::SeqCst), 5);
+
+    // failed requests are not stored and can be retried
+    let failing = [("Idempotency-Key", "k-2")];
+    assert_eq!(request(&services, "/api/accounts/0/payments", &failing, 1).await.0, 500);
+    assert_eq!(request(&services, "/api/accounts/0/payments", &failing, 1).await.0, 500);
+    assert_eq!(handler.payments.load(Ordering::SeqCst), 7);
+
+    // requests with the key of a request in progress conflict
+    let slow = [("Idempotency-Key", "k-3")];
+    let (first, second) = tokio::join!(
+        request(&services, "/api/accounts/2/payments", &slow, 7),
+        async {
+            tokio::time::delay_for(Duration::from_millis(20)).await;
+            request(&services, "/api/accounts/2/payments", &slow, 7).await
+        }
+    );
+    assert_eq!(first, (200, "7007".to_owned()));
+    assert_eq!(second.0, 409);
+    assert_eq!(request(&services, "/api/accounts/2/payments", &slow, 7).await, first);
+
+    // requests dropped while they are dispatched, e.g. by disconnecting clients, release their key
+    let dropped = [("Idempotency-Key", "k-4")];
+    let request_dropped = tokio::time::timeout(
+        Duration::from_millis(20),
+        request(&services, "/api/accounts/2/payments", &dropped, 8),
+    );
+    assert!(request_dropped.await.is_err());
+    tokio::time::delay_for(Duration::from_millis(10)).await;
+    assert_eq!(
+        request(&services, "/api/accounts/2/payments", &dropped, 8).await.0,
+        200
+    );
+
+    // instances sharing a store answer the requests to each other
+    let handler = Arc::new(S::default());
+    let store: Arc<dyn IdempotencyStore> = Arc::new(MemoryStore::new(Duration::from_secs(60)));
+    let instance1 = instance(Arc::clone(&handler), Arc::clone(&store));
+    let instance2 = instance(Arc::clone(&handler), store);
+    let first = request(&instance1, "/api/accounts/1/payments", &key, 5).await;
+    assert_eq!(request(&instance2, "/api/accounts/1/payments", &key, 5).await, first);
+    assert_eq!(handler.payments.load(Ordering::SeqCst), 1);
+
+    // keys are evicted after their time to live
+    let services = instance(handler, Arc::new(MemoryStore::new(Duration::from_millis(50))));
+    assert_eq!(request(&services, "/api/accounts/1/payments", &key, 5).await.1, "1005");
+    tokio::time::delay_for(Duration::from_millis(100)).await;
+    assert_eq!(request(&services, "/api/accounts/1/payments", &key, 5).await.1, "2005");
+
+    // keys claimed again after their release expire with their new claim
+    let services = instance(
+        Arc::new(S::default()),
+        Arc::new(MemoryStore::new(Duration::from_millis(100))),
+    );
+    let released = [("Idempotency-Key", "k-5")];
+    assert_eq!(request(&services, "/api/accounts/0/payments", &released, 6).await.0, 500);
+    tokio::time::delay_for(Duration::from_millis(60)).await;
+    let first = request(&services, "/api/accounts/1/payments", &released, 6).await;
+    assert_eq!(first, (200, "1006".to_owned()));
+    tokio::time::delay_for(Duration::from_millis(60)).await;
+    assert_eq!(request(&services, "/api/accounts/1/payments", &released, 6).await, first);
+
+    // stored responses with an invalid status code are answered with an error response
+    let services = instance(Arc::new(S::default()), Arc::new(InvalidStore));
+    assert_eq!(request(&services, "/api/accounts/1/payments", &key, 5).await.0, 500);
+}
+
+/// A store answering every request with a response with an invalid status code.
+struct InvalidStore;
+
+#[humblegen_rt::async_trait(Sync)]
+impl IdempotencyStore for InvalidStore {
+    async fn claim(&self, _key: &str) -> Claim {
+        Claim::Completed(RecordedResponse {
+            status: 1000,
+            headers: vec![],
+            body: vec![],
+        })
+    }
+
+    async fn complete(&self, _key: &str, _response: RecordedResponse) {}
+
+    async fn release(&self, _key: &str) {}
+}
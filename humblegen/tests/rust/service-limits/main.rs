@@ -0,0 +1,89 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl UploadApi for S {
+    type Context = ();
+
+    async fn post_small(&self, _ctx: Self::Context, post_body: String) -> Response<u64> {
+        Ok(post_body.len() as u64)
+    }
+
+    async fn post_large(&self, _ctx: Self::Context, post_body: String) -> Response<u64> {
+        Ok(post_body.len() as u64)
+    }
+
+    async fn get_slow_ms(&self, _ctx: Self::Context, ms: u64) -> Response<()> {
+        tokio::time::delay_for(Duration::from_millis(ms)).await;
+        Ok(())
+    }
+
+    async fn get_default_ms(&self, _ctx: Self::Context, ms: u64) -> Response<()> {
+        tokio::time::delay_for(Duration::from_millis(ms)).await;
+        Ok(())
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    method: &str,
+    path: &str,
+    body: &str,
+    content_length: bool,
+) -> u16 {
+    let mut req = hyper::Request::builder().method(method).uri(path);
+    if content_length {
+        req = req.header("Content-Length", body.len());
+    }
+    let req = req.body(hyper::Body::from(body.to_owned())).unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    resp.status().as_u16()
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .with_max_body_size(20)
+            .with_timeout(Duration::from_millis(200))
+            .add("/api", Handler::UploadApi(Arc::new(S)))
+            .into_services(),
+    );
+
+    // bodies larger than `@max_body_size` are rejected, by their length or by the bytes read
+    let small = r#""abcdef""#;
+    let large = r#""abcdefghijklmnop""#;
+    let huge = r#""abcdefghijklmnopqrstuvwxyz""#;
+    for &content_length in &[true, false] {
+        assert_eq!(request(&services, "POST", "/api/small", small, content_length).await, 200);
+        assert_eq!(request(&services, "POST", "/api/small", large, content_length).await, 413);
+        // ... and else than the default of the builder
+        assert_eq!(request(&services, "POST", "/api/large", large, content_length).await, 200);
+        assert_eq!(request(&services, "POST", "/api/large", huge, content_length).await, 413);
+    }
+
+    // handlers not responding within `@timeout`, or else the default, time out
+    assert_eq!(request(&services, "GET", "/api/slow/0", "", false).await, 204);
+    assert_eq!(request(&services, "GET", "/api/slow/100", "", false).await, 504);
+    assert_eq!(request(&services, "GET", "/api/default/100", "", false).await, 204);
+    assert_eq!(request(&services, "GET", "/api/default/400", "", false).await, 504);
+
+    // without defaults, only the annotated routes are limited
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::UploadApi(Arc::new(S)))
+            .into_services(),
+    );
+    assert_eq!(request(&services, "POST", "/api/small", large, true).await, 413);
+    assert_eq!(request(&services, "POST", "/api/large", huge, true).await, 200);
+    assert_eq!(request(&services, "GET", "/api/slow/100", "", false).await, 504);
+    assert_eq!(request(&services, "GET", "/api/default/400", "", false).await, 204);
+}
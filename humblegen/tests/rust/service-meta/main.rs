@@ -0,0 +1,39 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<Vec<Monster>> {
+        Ok(vec![])
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    assert_eq!(API_VERSION, "1.2.0");
+    let spec: serde_json::Value = serde_json::from_str(SPEC_JSON).unwrap();
+    assert_eq!(spec["meta"]["title"], "Monster API");
+    assert!(SPEC_INDEX_HTML.contains("<h1>Monster API Routes</h1>"));
+
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+    let req = hyper::Request::builder()
+        .method("GET")
+        .uri("/api/monsters")
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(services, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["api-version"], "1.2.0");
+}
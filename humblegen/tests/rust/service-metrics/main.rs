@@ -0,0 +1,160 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::metrics::{Metrics, Slo};
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(vec!["dragon".to_owned()])
+    }
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<String> {
+        if id == 2 {
+            tokio::time::delay_for(Duration::from_millis(40)).await;
+        }
+        Ok("dragon".to_owned())
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<()> {
+        Ok(())
+    }
+}
+
+async fn request(services: &Arc<Vec<Service>>, method: &str, path: &str) -> u16 {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    resp.status().as_u16()
+}
+
+async fn get_body(services: &Arc<Vec<Service>>, path: &str) -> String {
+    let req = hyper::Request::builder()
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["Content-Type"], "text/plain; version=0.0.4");
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let metrics = Arc::new(Metrics::with_buckets(vec![
+        Duration::from_millis(100),
+        Duration::from_millis(10),
+    ]));
+    let services = Arc::new(
+        Builder::new()
+            .with_metrics(Arc::clone(&metrics))
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .with_metrics_endpoint()
+            .into_services(),
+    );
+    let s = &services;
+
+    assert_eq!(request(s, "GET", "/api/monsters").await, 200);
+    assert_eq!(request(s, "GET", "/api/monsters/1").await, 200);
+    assert_eq!(request(s, "GET", "/api/monsters/2").await, 200);
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(
+        snapshot.keys().collect::<Vec<_>>(),
+        vec![
+            "DELETE /api/monsters/{id}",
+            "GET /api/monsters",
+            "GET /api/monsters/{id}"
+        ]
+    );
+
+    // global buckets
+    let get_monsters = &snapshot["GET /api/monsters"];
+    assert_eq!(get_monsters.slo, None);
+    assert_eq!(get_monsters.count, 1);
+    assert_eq!(
+        get_monsters.buckets,
+        vec![
+            (Duration::from_millis(10), 1),
+            (Duration::from_millis(100), 1)
+        ]
+    );
+    assert_eq!(get_monsters.slo_met(), None);
+
+    // the SLO target is an additional bucket
+    let get_monster = &snapshot["GET /api/monsters/{id}"];
+    assert_eq!(
+        get_monster.slo,
+        Some(Slo {
+            percentile: 99.0,
+            target: Duration::from_millis(30),
+        })
+    );
+    assert_eq!(get_monster.count, 2);
+    assert_eq!(
+        get_monster.buckets,
+        vec![
+            (Duration::from_millis(10), 1),
+            (Duration::from_millis(30), 1),
+            (Duration::from_millis(100), 2),
+        ]
+    );
+    assert!(get_monster.sum >= Duration::from_millis(40));
+    assert_eq!(get_monster.slo_met(), Some(false));
+
+    let delete_monster = &snapshot["DELETE /api/monsters/{id}"];
+    assert_eq!(
+        delete_monster.slo,
+        Some(Slo {
+            percentile: 99.9,
+            target: Duration::from_secs(2),
+        })
+    );
+    assert_eq!(delete_monster.slo_met(), None);
+    assert_eq!(request(s, "DELETE", "/api/monsters/1").await, 204);
+    assert_eq!(
+        metrics.snapshot()["DELETE /api/monsters/{id}"].slo_met(),
+        Some(true)
+    );
+
+    // responses are counted by status code
+    assert_eq!(request(s, "GET", "/api/monsters/x").await, 400);
+    let statuses = &metrics.snapshot()["GET /api/monsters/{id}"].statuses;
+    assert_eq!(statuses.iter().collect::<Vec<_>>(), vec![(&200, &2), (&400, &1)]);
+
+    // the Prometheus text format
+    let text = get_body(s, "/metrics").await;
+    for line in &[
+        "# TYPE humblegen_requests_total counter",
+        r#"humblegen_requests_total{method="GET",route="/api/monsters/{id}",status="200"} 2"#,
+        r#"humblegen_requests_total{method="GET",route="/api/monsters/{id}",status="400"} 1"#,
+        r#"humblegen_requests_total{method="DELETE",route="/api/monsters/{id}",status="204"} 1"#,
+        "# TYPE humblegen_request_duration_seconds histogram",
+        r#"humblegen_request_duration_seconds_bucket{method="GET",route="/api/monsters",le="0.01"} 1"#,
+        r#"humblegen_request_duration_seconds_bucket{method="GET",route="/api/monsters",le="0.1"} 1"#,
+        r#"humblegen_request_duration_seconds_bucket{method="GET",route="/api/monsters",le="+Inf"} 1"#,
+        r#"humblegen_request_duration_seconds_bucket{method="GET",route="/api/monsters/{id}",le="0.03"} 2"#,
+        r#"humblegen_request_duration_seconds_count{method="GET",route="/api/monsters/{id}"} 3"#,
+    ] {
+        assert!(
+            text.lines().any(|l| l == *line),
+            "missing {:?} in\n{}",
+            line,
+            text
+        );
+    }
+    assert_eq!(request(s, "POST", "/metrics").await, 404);
+}
@@ -0,0 +1,143 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper::{self, Body, Request};
+use humblegen_rt::middleware::{Middleware, Next, RouteInfo};
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::{Arc, Mutex};
+
+struct Monsters;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for Monsters {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<String> {
+        Ok(format!("monster {}", id))
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<()> {
+        Ok(())
+    }
+}
+
+struct Lairs;
+
+#[humblegen_rt::async_trait(Sync)]
+impl LairApi for Lairs {
+    type Context = ();
+
+    async fn get_lairs(&self, _ctx: Self::Context) -> Response<Vec<String>> {
+        Ok(vec![])
+    }
+}
+
+/// Logs the route and the status of each request.
+#[derive(Default)]
+struct Log(Mutex<Vec<String>>);
+
+#[humblegen_rt::async_trait(Sync)]
+impl Middleware for Log {
+    async fn handle(
+        &self,
+        req: Request<Body>,
+        route: &'static RouteInfo,
+        next: Next,
+    ) -> hyper::Response<Body> {
+        let resp = next.run(req).await;
+        self.0.lock().unwrap().push(format!(
+            "{} {} {} {}",
+            route.service,
+            route.route,
+            route.handler,
+            resp.status().as_u16()
+        ));
+        resp
+    }
+}
+
+/// Rejects requests with header `X-Over-Limit`, before they reach the handler.
+struct RateLimit;
+
+#[humblegen_rt::async_trait(Sync)]
+impl Middleware for RateLimit {
+    async fn handle(
+        &self,
+        req: Request<Body>,
+        _route: &'static RouteInfo,
+        next: Next,
+    ) -> hyper::Response<Body> {
+        if req.headers().contains_key("X-Over-Limit") {
+            return hyper::Response::builder()
+                .status(429)
+                .body(Body::empty())
+                .unwrap();
+        }
+        let mut resp = next.run(req).await;
+        resp.headers_mut()
+            .insert("X-Rate-Limit", hyper::header::HeaderValue::from_static("100"));
+        resp
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    method: hyper::Method,
+    path: &str,
+    over_limit: bool,
+) -> hyper::Response<Body> {
+    let mut req = Request::builder().method(method).uri(path);
+    if over_limit {
+        req = req.header("X-Over-Limit", "1");
+    }
+    humblegen_rt::server::handle_request(Arc::clone(services), req.body(Body::empty()).unwrap())
+        .await
+}
+
+#[tokio::main]
+async fn main() {
+    let log = Arc::new(Log::default());
+    let services = Arc::new(
+        Builder::new()
+            // lairs are not rate limited
+            .with_middleware(log.clone())
+            .add("/api", Handler::LairApi(Arc::new(Lairs)))
+            .with_middleware(Arc::new(RateLimit))
+            .add("/api", Handler::MonsterApi(Arc::new(Monsters)))
+            .into_services(),
+    );
+    let s = &services;
+
+    let resp = request(s, hyper::Method::GET, "/api/monsters/1", false).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["X-Rate-Limit"], "100");
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    assert_eq!(&body[..], br#""monster 1""#);
+
+    // errors of the dispatcher pass through the middleware as responses
+    let resp = request(s, hyper::Method::GET, "/api/monsters/x", false).await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(resp.headers()["X-Rate-Limit"], "100");
+
+    let resp = request(s, hyper::Method::DELETE, "/api/monsters/1", true).await;
+    assert_eq!(resp.status(), 429);
+
+    let resp = request(s, hyper::Method::GET, "/api/lairs", true).await;
+    assert_eq!(resp.status(), 200);
+    assert!(!resp.headers().contains_key("X-Rate-Limit"));
+
+    // requests without a matching route bypass the middleware
+    let resp = request(s, hyper::Method::GET, "/api/dungeons", false).await;
+    assert_eq!(resp.status(), 404);
+
+    assert_eq!(
+        *log.0.lock().unwrap(),
+        vec![
+            "MonsterApi GET /monsters/{id} get_monsters_id 200",
+            "MonsterApi GET /monsters/{id} get_monsters_id 400",
+            "MonsterApi DELETE /monsters/{id} delete_monsters_id 429",
+            "LairApi GET /lairs get_lairs 200",
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::mock::MockError;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    method: &str,
+    path: &str,
+    body: &'static str,
+) -> (u16, String) {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::from(body))
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let mock = Arc::new(MockMonsterApi::new());
+    mock.load_recordings(
+        r#"{
+            "get_monsters": [{"name": "dragon", "age": 300}],
+            "get_monsters_id": {"Err": "NotFound"}
+        }"#,
+    )
+    .unwrap();
+    mock.get_monsters_id.respond_once(Ok(Monster {
+        name: "wyvern".to_owned(),
+        age: 20,
+    }));
+
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(mock.clone()))
+            .into_services(),
+    );
+    let s = &services;
+
+    // recorded responses
+    assert_eq!(
+        request(s, "GET", "/api/monsters", "").await,
+        (200, r#"[{"name":"dragon","age":300}]"#.to_owned())
+    );
+    // queued responses take precedence over the canned one, in order
+    assert_eq!(
+        request(s, "GET", "/api/monsters/1", "").await,
+        (200, r#"{"Ok":{"name":"wyvern","age":20}}"#.to_owned())
+    );
+    assert_eq!(
+        request(s, "GET", "/api/monsters/1", "").await,
+        (404, r#"{"Err":"NotFound"}"#.to_owned())
+    );
+    assert_eq!(mock.get_monsters_id.calls(), 2);
+
+    // routes without responses fail, auth is always granted
+    assert_eq!(
+        request(s, "POST", "/api/monsters", r#"{"name":"imp","age":1}"#)
+            .await
+            .0,
+        500
+    );
+    mock.post_monsters.respond_with(42);
+    assert_eq!(
+        request(s, "POST", "/api/monsters", r#"{"name":"imp","age":1}"#).await,
+        (200, "42".to_owned())
+    );
+    assert_eq!(mock.post_monsters.calls(), 2);
+
+    // recordings must match the routes
+    assert!(matches!(
+        mock.load_recordings(r#"{"get_dragons": []}"#),
+        Err(MockError::UnknownRoute(route_fn)) if route_fn == "get_dragons"
+    ));
+    assert!(matches!(
+        mock.load_recordings(r#"{"post_monsters": "many"}"#),
+        Err(MockError::InvalidRecording { .. })
+    ));
+}
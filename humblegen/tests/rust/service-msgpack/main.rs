@@ -0,0 +1,161 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::msgpack;
+use humblegen_rt::server::Service;
+use humblegen_rt::wire_format::MSGPACK_CONTENT_TYPE;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: i32,
+    ) -> Response<Result<Monster, MonsterError>> {
+        match id {
+            1 => Ok(Ok(monster())),
+            _ => Ok(Err(MonsterError::NotFound)),
+        }
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, post_body: Monster) -> Response<Monster> {
+        Ok(Monster {
+            age: post_body.age + 1,
+            ..post_body
+        })
+    }
+}
+
+fn monster() -> Monster {
+    Monster {
+        name: "dragon".to_owned(),
+        age: 9,
+        portrait: vec![0, 1, 2],
+    }
+}
+
+fn services() -> Arc<Vec<Service>> {
+    Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    )
+}
+
+async fn request(req: hyper::Request<hyper::Body>) -> (u16, Option<String>, Vec<u8>) {
+    let resp = humblegen_rt::server::handle_request(services(), req).await;
+    let status = resp.status().as_u16();
+    let content_type = resp
+        .headers()
+        .get("Content-Type")
+        .map(|v| v.to_str().unwrap().to_owned());
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, content_type, body.to_vec())
+}
+
+#[tokio::main]
+async fn main() {
+    // MessagePack responses on request
+    let (status, content_type, body) = request(
+        hyper::Request::get("/api/monsters/1")
+            .header("Accept", "application/json, application/msgpack; q=0.9")
+            .body(hyper::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type.as_deref(), Some(MSGPACK_CONTENT_TYPE));
+    let m: Result<Monster, MonsterError> = msgpack::from_slice(&body).unwrap();
+    assert_eq!(m.unwrap().portrait, monster().portrait);
+
+    // domain errors are encoded in MessagePack as well and keep their status codes
+    let (status, content_type, body) = request(
+        hyper::Request::get("/api/monsters/2")
+            .header("Accept", MSGPACK_CONTENT_TYPE)
+            .body(hyper::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 404);
+    assert_eq!(content_type.as_deref(), Some(MSGPACK_CONTENT_TYPE));
+    let m: Result<Monster, MonsterError> = msgpack::from_slice(&body).unwrap();
+    assert!(matches!(m, Err(MonsterError::NotFound)));
+
+    // MessagePack request bodies
+    let (status, content_type, body) = request(
+        hyper::Request::post("/api/monsters")
+            .header("Content-Type", MSGPACK_CONTENT_TYPE)
+            .header("Accept", MSGPACK_CONTENT_TYPE)
+            .body(msgpack::to_vec(&monster()).unwrap().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type.as_deref(), Some(MSGPACK_CONTENT_TYPE));
+    let m: Monster = msgpack::from_slice(&body).unwrap();
+    assert_eq!(m.age, 10);
+
+    // other formats are not negotiated
+    let (_, content_type, _) = request(
+        hyper::Request::get("/api/monsters/1")
+            .header("Accept", "application/cbor")
+            .body(hyper::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+
+    // JSON by default
+    let (status, content_type, body) = request(
+        hyper::Request::post("/api/monsters")
+            .body(serde_json::to_vec(&monster()).unwrap().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    let m: Monster = serde_json::from_slice(&body).unwrap();
+    assert_eq!(m.age, 10);
+
+    // error responses of the service protocol stay JSON
+    let (status, content_type, _) = request(
+        hyper::Request::post("/api/monsters")
+            .header("Content-Type", MSGPACK_CONTENT_TYPE)
+            .header("Accept", MSGPACK_CONTENT_TYPE)
+            .body(b"{}".to_vec().into())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+
+    // interoperates with other MessagePack implementations
+    let encoded: &[u8] = &[
+        0x83, // map of 3 entries
+        0xa4, b'n', b'a', b'm', b'e', 0xa3, b'o', b'r', b'c', // "name": "orc"
+        0xa3, b'a', b'g', b'e', 0xd0, 0x9c, // "age": -100 as int 8
+        0xa8, b'p', b'o', b'r', b't', b'r', b'a', b'i', b't', 0xc4, 0x02, 0x07,
+        0x08, // "portrait": bin
+    ];
+    let m: Monster = msgpack::from_slice(encoded).unwrap();
+    assert_eq!(
+        (m.name.as_str(), m.age, m.portrait),
+        ("orc", -100, vec![7, 8])
+    );
+    assert!(msgpack::from_slice::<Monster>(&encoded[..5]).is_err());
+    assert_eq!(
+        msgpack::to_vec(&MonsterError::NotFound).unwrap(),
+        b"\xa8NotFound"
+    );
+    assert_eq!(
+        msgpack::to_vec(&vec![300, -1, -33]).unwrap(),
+        [0x93, 0xcd, 0x01, 0x2c, 0xff, 0xd0, 0xdf]
+    );
+}
@@ -0,0 +1,57 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::multipart::deser_multipart;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn post_monsters_id_portrait(
+        &self,
+        _ctx: Self::Context,
+        post_body: PortraitUpload,
+        id: i32,
+    ) -> Response<()> {
+        println!("portrait of {}: {:?}", id, post_body.caption);
+        Ok(())
+    }
+}
+
+const BODY: &str = "--X-BOUNDARY\r\n\
+Content-Disposition: form-data; name=\"caption\"\r\n\r\n\
+Godzilla\r\n\
+--X-BOUNDARY\r\n\
+Content-Disposition: form-data; name=\"image\"; filename=\"g.png\"\r\n\
+Content-Type: application/octet-stream\r\n\r\n\
+PNG\r\n\
+--X-BOUNDARY\r\n\
+Content-Disposition: form-data; name=\"meta\"\r\n\r\n\
+{\"tags\":[\"big\"]}\r\n\
+--X-BOUNDARY--\r\n";
+
+#[tokio::main]
+async fn main() {
+    Builder::new().add("/api", Handler::MonsterApi(Arc::new(S)));
+
+    let mut req = hyper::Request::builder()
+        .method("POST")
+        .header(
+            hyper::header::CONTENT_TYPE,
+            "multipart/form-data; boundary=X-BOUNDARY",
+        )
+        .body(hyper::Body::from(BODY))
+        .unwrap();
+    let upload: PortraitUpload = deser_multipart(&mut req).await.unwrap();
+    assert_eq!(upload.caption, "Godzilla");
+    assert_eq!(upload.image, b"PNG".to_vec());
+    assert_eq!(upload.alt_text, None);
+    assert_eq!(upload.thumbnail, None);
+    assert_eq!(upload.meta.tags, vec!["big".to_owned()]);
+    assert_eq!(upload.rating, None);
+}
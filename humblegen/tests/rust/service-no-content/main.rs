@@ -0,0 +1,83 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use humblegen_rt::handler::ServiceError;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters(&self, _ctx: Self::Context) -> Response<Vec<Monster>> {
+        Ok(vec![])
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<()> {
+        match id {
+            1 => Ok(()),
+            _ => Err(ServiceError::Authorization),
+        }
+    }
+}
+
+fn services(pretty_json: bool) -> Arc<Vec<Service>> {
+    Arc::new(
+        Builder::new()
+            .with_pretty_json(pretty_json)
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    )
+}
+
+async fn request(
+    services: Arc<Vec<Service>>,
+    method: &str,
+    path: &str,
+) -> (u16, Option<String>, String) {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(services, req).await;
+    let status = resp.status().as_u16();
+    let content_type = resp
+        .headers()
+        .get("Content-Type")
+        .map(|v| v.to_str().unwrap().to_owned());
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::main]
+async fn main() {
+    // `()` is answered with 204 No Content, regardless of pretty-printing
+    for pretty_json in &[false, true] {
+        let (status, content_type, body) =
+            request(services(*pretty_json), "DELETE", "/api/monsters/1").await;
+        assert_eq!(status, 204);
+        assert_eq!(content_type, None);
+        assert_eq!(body, "");
+    }
+
+    // errors are error responses as usual
+    let (status, content_type, body) = request(services(false), "DELETE", "/api/monsters/2").await;
+    assert_eq!(status, 403);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    assert!(body.contains("Authorization"));
+
+    // other return types are unaffected
+    let (status, content_type, body) = request(services(false), "GET", "/api/monsters").await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    assert_eq!(body, "[]");
+}
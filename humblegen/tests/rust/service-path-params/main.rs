@@ -0,0 +1,88 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct Handler;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for Handler {
+    type Context = ();
+
+    // anonymous path parameters are named after the literal segment before them
+    async fn get_lairs_lair_monsters_monster(
+        &self,
+        _ctx: Self::Context,
+        lair: humblegen_rt::uuid::Uuid,
+        monster: u32,
+    ) -> Response<String> {
+        Ok(format!("{}:{}", lair, monster))
+    }
+
+    async fn get_pages_page(&self, _ctx: Self::Context, page: u64) -> Response<u64> {
+        Ok(page)
+    }
+
+    async fn get_search_term(&self, _ctx: Self::Context, term: String) -> Response<String> {
+        Ok(term)
+    }
+
+    async fn get_hatched_at(
+        &self,
+        _ctx: Self::Context,
+        at: humblegen_rt::chrono::DateTime<humblegen_rt::chrono::Utc>,
+    ) -> Response<String> {
+        Ok(at.to_rfc3339())
+    }
+}
+
+async fn get(services: &Arc<Vec<Service>>, path: &str) -> (u16, String) {
+    let req = hyper::Request::builder()
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", protocol::Handler::MonsterApi(Arc::new(Handler)))
+            .into_services(),
+    );
+    let s = &services;
+
+    let lair = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    assert_eq!(
+        get(s, &format!("/api/lairs/{}/monsters/7", lair)).await,
+        (200, format!("\"{}:7\"", lair))
+    );
+    assert_eq!(
+        get(s, "/api/pages/18446744073709551615").await,
+        (200, "18446744073709551615".to_owned())
+    );
+    // path segments are percent-decoded
+    assert_eq!(
+        get(s, "/api/search/red%20dragon%2Fwyrm").await,
+        (200, "\"red dragon/wyrm\"".to_owned())
+    );
+    assert_eq!(
+        get(s, "/api/hatched/2020-01-02T03:04:05+00:00").await,
+        (200, "\"2020-01-02T03:04:05+00:00\"".to_owned())
+    );
+
+    // parameters not matching their type are bad requests
+    let (status, body) = get(s, "/api/lairs/nest/monsters/7").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("lair"), "{}", body);
+    assert_eq!(get(s, &format!("/api/lairs/{}/monsters/-1", lair)).await.0, 400);
+    assert_eq!(get(s, "/api/pages/18446744073709551616").await.0, 400);
+    assert_eq!(get(s, "/api/search/%FF").await.0, 400);
+}
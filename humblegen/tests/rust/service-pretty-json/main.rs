@@ -0,0 +1,79 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<Monster> {
+        Ok(Monster {
+            name: "dragon".to_owned(),
+            tags: vec![],
+            attributes: vec![("strength".to_owned(), 9)].into_iter().collect(),
+            escaped: "\"{[,:]}\" \\".to_owned(),
+        })
+    }
+}
+
+async fn request(services: Vec<Service>, path: &str) -> String {
+    let req = hyper::Request::builder()
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::new(services), req).await;
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+fn services(pretty_json: bool) -> Vec<Service> {
+    Builder::new()
+        .with_pretty_json(pretty_json)
+        .add("/api", Handler::MonsterApi(Arc::new(S)))
+        .into_services()
+}
+
+const COMPACT: &str =
+    r#"{"name":"dragon","tags":[],"attributes":{"strength":9},"escaped":"\"{[,:]}\" \\"}"#;
+
+const PRETTY: &str = r#"{
+  "name": "dragon",
+  "tags": [],
+  "attributes": {
+    "strength": 9
+  },
+  "escaped": "\"{[,:]}\" \\"
+}"#;
+
+#[tokio::main]
+async fn main() {
+    // compact by default
+    assert_eq!(request(services(false), "/api/monsters/1").await, COMPACT);
+    assert_eq!(
+        request(services(false), "/api/monsters/1?pretty=0").await,
+        COMPACT
+    );
+
+    // pretty on request
+    for query in &["pretty", "pretty=1", "pretty=true"] {
+        let path = format!("/api/monsters/1?{}", query);
+        assert_eq!(request(services(false), &path).await, PRETTY);
+    }
+
+    // pretty by configuration
+    assert_eq!(request(services(true), "/api/monsters/1").await, PRETTY);
+
+    // pretty-printing retains the error responses' status codes
+    let req = hyper::Request::builder()
+        .uri("/api/unknown?pretty")
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::new(services(false)), req).await;
+    assert_eq!(resp.status(), 404);
+}
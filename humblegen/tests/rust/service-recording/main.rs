@@ -0,0 +1,104 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::recording::{self, Recorder};
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S {
+    hp: i32,
+}
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<Monster> {
+        Ok(Monster {
+            name: "dragon".to_owned(),
+            hp: self.hp,
+        })
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, _post_body: Monster) -> Response<()> {
+        Ok(())
+    }
+}
+
+fn services(hp: i32) -> Arc<Vec<Service>> {
+    Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S { hp })))
+            .into_services(),
+    )
+}
+
+async fn request(
+    recorder: &Recorder,
+    services: &Arc<Vec<Service>>,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> u16 {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .header("Authorization", "Bearer secret")
+        .body(hyper::Body::from(body.to_owned()))
+        .unwrap();
+    let resp = recorder.handle_request(Arc::clone(services), req).await;
+    resp.status().as_u16()
+}
+
+#[tokio::main]
+async fn main() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("recording.jsonl");
+
+    let recorder = Recorder::create(&path).unwrap();
+    let s = &services(100);
+    assert_eq!(request(&recorder, s, "GET", "/api/monsters/1", "").await, 200);
+    let monster = r#"{"name":"goblin","hp":7}"#;
+    assert_eq!(request(&recorder, s, "POST", "/api/monsters", monster).await, 204);
+    assert_eq!(request(&recorder, s, "GET", "/api/lairs", "").await, 404);
+
+    let exchanges = recording::read_recording(&path).unwrap();
+    let routes: Vec<_> = exchanges.iter().map(|e| e.route.as_deref()).collect();
+    assert_eq!(
+        routes,
+        vec![Some("GET /monsters/{id}"), Some("POST /monsters"), None]
+    );
+    let post = &exchanges[1];
+    assert_eq!(post.request.uri, "/api/monsters");
+    assert_eq!(post.request.body, monster.as_bytes());
+    assert_eq!(
+        post.request.headers,
+        vec![("authorization".to_owned(), recording::REDACTED.to_owned())]
+    );
+    assert_eq!(exchanges[0].response.body, br#"{"name":"dragon","hp":100}"#);
+    assert!(exchanges[0].timestamp <= exchanges[1].timestamp);
+
+    // replaying against the same handler reproduces the responses
+    let mismatches = recording::replay(services(100), &exchanges).await.unwrap();
+    assert!(mismatches.is_empty(), "{:#?}", mismatches);
+
+    // regressions are reported
+    let mismatches = recording::replay(services(50), &exchanges).await.unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].index, 0);
+    assert_eq!(mismatches[0].part, "body");
+    assert_eq!(
+        mismatches[0].to_string(),
+        r#"exchange 0 (GET /api/monsters/1): body was `{"name":"dragon","hp":100}`, replayed `{"name":"dragon","hp":50}`"#
+    );
+
+    // recordings are appended to
+    let recorder = Recorder::create(&path).unwrap();
+    assert_eq!(request(&recorder, s, "GET", "/api/monsters/2", "").await, 200);
+    assert_eq!(recording::read_recording(&path).unwrap().len(), 4);
+
+    let err = recording::parse_recording("\n{}\n").unwrap_err();
+    assert!(err.to_string().starts_with("invalid exchange in line 2: "));
+}
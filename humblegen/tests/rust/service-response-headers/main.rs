@@ -0,0 +1,101 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct Files;
+
+#[humblegen_rt::async_trait(Sync)]
+impl FileApi for Files {
+    type Context = ();
+
+    async fn get_files_name(
+        &self,
+        _ctx: Self::Context,
+        name: String,
+    ) -> Response<WithHeaders<Result<FileMeta, FileError>, FileApiGetFilesNameResponseHeaders>> {
+        let value = match name.as_str() {
+            "missing" => Err(FileError::NotFound),
+            _ => Ok(FileMeta { name, size: 3 }),
+        };
+        let e_tag = match &value {
+            // header values cannot contain newlines
+            Ok(meta) if meta.name == "broken" => "\"a\nb\"".to_owned(),
+            _ => "\"v1\"".to_owned(),
+        };
+        Ok(WithHeaders::new(
+            value,
+            FileApiGetFilesNameResponseHeaders {
+                e_tag,
+                last_modified: None,
+            },
+        ))
+    }
+
+    async fn delete_files_name(
+        &self,
+        _ctx: Self::Context,
+        name: String,
+    ) -> Response<WithHeaders<(), FileApiDeleteFilesNameResponseHeaders>> {
+        if name == "locked" {
+            return Err(ServiceError::Authorization);
+        }
+        Ok(WithHeaders::new(
+            (),
+            FileApiDeleteFilesNameResponseHeaders { x_deleted_count: 1 },
+        ))
+    }
+}
+
+async fn request(
+    services: &Arc<Vec<Service>>,
+    method: hyper::Method,
+    path: &str,
+) -> hyper::Response<hyper::Body> {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    humblegen_rt::server::handle_request(Arc::clone(services), req).await
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::FileApi(Arc::new(Files)))
+            .into_services(),
+    );
+    let s = &services;
+
+    let resp = request(s, hyper::Method::GET, "/api/files/a.txt").await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["ETag"], "\"v1\"");
+    // optional headers without a value are omitted
+    assert!(!resp.headers().contains_key("Last-Modified"));
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    assert_eq!(&body[..], br#"{"Ok":{"name":"a.txt","size":3}}"#);
+
+    // domain errors carry the headers, too
+    let resp = request(s, hyper::Method::GET, "/api/files/missing").await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(resp.headers()["ETag"], "\"v1\"");
+
+    // invalid header values are an internal error
+    let resp = request(s, hyper::Method::GET, "/api/files/broken").await;
+    assert_eq!(resp.status(), 500);
+    assert!(!resp.headers().contains_key("ETag"));
+
+    let resp = request(s, hyper::Method::DELETE, "/api/files/a.txt").await;
+    assert_eq!(resp.status(), 204);
+    assert_eq!(resp.headers()["X-Deleted-Count"], "1");
+
+    // service errors have no response headers
+    let resp = request(s, hyper::Method::DELETE, "/api/files/locked").await;
+    assert_eq!(resp.status(), 403);
+    assert!(!resp.headers().contains_key("X-Deleted-Count"));
+}
@@ -0,0 +1,87 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct Files;
+
+#[humblegen_rt::async_trait(Sync)]
+impl FileApi for Files {
+    type Context = ();
+
+    async fn get_files_path(&self, _ctx: Self::Context, path: String) -> Response<String> {
+        Ok(format!("file {}", path))
+    }
+
+    async fn get_files_name_meta(&self, _ctx: Self::Context, name: String) -> Response<String> {
+        Ok(format!("meta {}", name))
+    }
+
+    async fn get_files_readme(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("readme".to_owned())
+    }
+
+    async fn get_proxy_host_path(
+        &self,
+        _ctx: Self::Context,
+        host: String,
+        path: String,
+    ) -> Response<String> {
+        Ok(format!("proxy {} {}", host, path))
+    }
+}
+
+async fn get(services: &Arc<Vec<Service>>, path: &str) -> (u16, String) {
+    let req = hyper::Request::builder()
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::FileApi(Arc::new(Files)))
+            .into_services(),
+    );
+    let s = &services;
+
+    let ok = |body: &str| (200, format!("{:?}", body));
+    assert_eq!(get(s, "/api/files/site.css").await, ok("file site.css"));
+    assert_eq!(get(s, "/api/files/css/site.css").await, ok("file css/site.css"));
+    // the rest may have more segments than any route
+    assert_eq!(
+        get(s, "/api/files/a/b/c/d/e/f.txt").await,
+        ok("file a/b/c/d/e/f.txt")
+    );
+    // the rest is percent-decoded
+    assert_eq!(
+        get(s, "/api/files/my%20docs/notes.txt").await,
+        ok("file my docs/notes.txt")
+    );
+    // literals and params take precedence over the rest
+    assert_eq!(get(s, "/api/files/readme").await, ok("readme"));
+    assert_eq!(get(s, "/api/files/site.css/meta").await, ok("meta site.css"));
+    assert_eq!(
+        get(s, "/api/files/css/site.css/meta").await,
+        ok("file css/site.css/meta")
+    );
+    assert_eq!(
+        get(s, "/api/proxy/example.com/a/b").await,
+        ok("proxy example.com a/b")
+    );
+
+    // the rest has at least one segment, and no empty ones
+    assert_eq!(get(s, "/api/files").await.0, 404);
+    assert_eq!(get(s, "/api/proxy/example.com").await.0, 404);
+    assert_eq!(get(s, "/api/files/a//b").await.0, 404);
+    assert_eq!(get(s, "/api/files/a/b/c/d/e//f").await.0, 404);
+}
@@ -0,0 +1,74 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<String> {
+        Ok(format!("get {}", id))
+    }
+
+    async fn get_monsters_new(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("new".to_owned())
+    }
+
+    async fn delete_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<String> {
+        Ok(format!("delete {}", id))
+    }
+
+    async fn get_monsters_id_friends_friend_id(
+        &self,
+        _ctx: Self::Context,
+        id: i32,
+        friend_id: i32,
+    ) -> Response<String> {
+        Ok(format!("friend {} of {}", friend_id, id))
+    }
+}
+
+async fn request(services: &Arc<Vec<Service>>, method: &str, path: &str) -> (u16, String) {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+    let s = &services;
+
+    let ok = |s: &str| (200, format!("{:?}", s));
+    assert_eq!(request(s, "GET", "/api/monsters/3").await, ok("get 3"));
+    assert_eq!(request(s, "GET", "/api/monsters/new").await, ok("new"));
+    assert_eq!(request(s, "DELETE", "/api/monsters/3").await, ok("delete 3"));
+    assert_eq!(
+        request(s, "GET", "/api/monsters/3/friends/4").await,
+        ok("friend 4 of 3")
+    );
+
+    assert_eq!(request(s, "GET", "/api/monsters/three").await.0, 400);
+    assert_eq!(request(s, "DELETE", "/api/monsters/new").await.0, 400);
+    assert_eq!(request(s, "POST", "/api/monsters/3").await.0, 404);
+    assert_eq!(request(s, "GET", "/api/monsters/3/").await.0, 404);
+    assert_eq!(request(s, "GET", "/api/monsters").await.0, 404);
+    assert_eq!(request(s, "GET", "/api/monsters/3/friends/4/x").await.0, 404);
+    assert_eq!(request(s, "GET", "/apiary/monsters/3").await.0, 404);
+}
@@ -0,0 +1,77 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<String> {
+        tokio::time::delay_for(Duration::from_millis(id as u64)).await;
+        Ok("dragon".to_owned())
+    }
+}
+
+async fn health(services: &Arc<Vec<Service>>, method: &str, path: &str) -> u16 {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    resp.status().as_u16()
+}
+
+#[tokio::main]
+async fn main() {
+    // the readiness probe fails once the server is shutting down, unlike the liveness probe
+    let builder = Builder::new()
+        .add("/api", Handler::MonsterApi(Arc::new(S)))
+        .with_health_endpoints();
+    let handle = builder.shutdown_handle();
+    let services = Arc::new(builder.into_services());
+    assert_eq!(health(&services, "GET", "/health/live").await, 200);
+    assert_eq!(health(&services, "GET", "/health/ready").await, 200);
+    assert_eq!(health(&services, "POST", "/health/ready").await, 404);
+    assert_eq!(health(&services, "GET", "/health/other").await, 404);
+    handle.shutdown();
+    assert!(handle.is_shutting_down());
+    assert_eq!(health(&services, "GET", "/health/live").await, 200);
+    assert_eq!(health(&services, "GET", "/health/ready").await, 503);
+    assert_eq!(health(&services, "GET", "/api/monsters/0").await, 200);
+
+    // a request in flight completes before the server returns
+    let addr: std::net::SocketAddr = "127.0.0.1:38133".parse().unwrap();
+    let builder = Builder::new()
+        .add("/api", Handler::MonsterApi(Arc::new(S)))
+        .with_drain_delay(Duration::from_millis(50));
+    let handle = builder.shutdown_handle();
+    let server = tokio::spawn(async move { builder.listen_and_run_forever(&addr).await });
+    tokio::time::delay_for(Duration::from_millis(50)).await;
+
+    let in_flight = tokio::spawn(async move {
+        let uri = format!("http://{}/api/monsters/300", addr).parse().unwrap();
+        let resp = hyper::Client::new().get(uri).await.unwrap();
+        let status = resp.status().as_u16();
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        (status, body)
+    });
+    tokio::time::delay_for(Duration::from_millis(100)).await;
+    handle.shutdown();
+    handle.shutting_down().await;
+    server.await.unwrap().unwrap();
+    let (status, body) = in_flight.await.unwrap();
+    assert_eq!(status, 200);
+    assert_eq!(&body[..], br#""dragon""#);
+
+    // once the server returned, it no longer accepts connections
+    let uri = format!("http://{}/api/monsters/0", addr).parse().unwrap();
+    assert!(hyper::Client::new().get(uri).await.is_err());
+}
@@ -0,0 +1,143 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters(
+        &self,
+        _ctx: Self::Context,
+        _query: Option<MonsterQuery>,
+    ) -> Response<Vec<Monster>> {
+        Ok(vec![])
+    }
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        _id: i32,
+    ) -> Response<Result<Monster, MonsterError>> {
+        Ok(Err(MonsterError::NotFound))
+    }
+
+    async fn post_monsters(&self, _ctx: Self::Context, _body: Monster) -> Response<()> {
+        Ok(())
+    }
+
+    async fn post_cache_flush(&self, _ctx: Self::Context, _body: ()) -> Response<()> {
+        Ok(())
+    }
+}
+
+async fn request(services: &Arc<Vec<Service>>, method: &str, path: &str) -> (u16, String, String) {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let content_type = resp.headers()[hyper::header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::main]
+async fn main() {
+    // opt-in
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .into_services(),
+    );
+    assert_eq!(request(&services, "GET", "/__spec").await.0, 404);
+
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(S)))
+            .with_spec_endpoint()
+            .into_services(),
+    );
+
+    let (status, content_type, body) = request(&services, "GET", "/__spec").await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type, "application/json");
+    assert_eq!(body, SPEC_JSON);
+    let spec: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        spec["types"][0],
+        serde_json::json!({
+            "struct": "Monster",
+            "doc": "A monster.",
+            "fields": [
+                { "name": "name", "type": "str", "doc": null, "renamed_from": [] },
+                { "name": "weaknesses", "type": "list[str]", "doc": "Known weaknesses.", "renamed_from": ["flaws"] },
+            ],
+        })
+    );
+    assert_eq!(
+        spec["types"][2]["variants"],
+        serde_json::json!([
+            { "name": "NotFound", "doc": null, "status": 404 },
+            { "name": "Invalid", "doc": null, "fields": [
+                { "name": "reason", "type": "str", "doc": null, "renamed_from": [] },
+            ] },
+        ])
+    );
+    let service = &spec["services"][0];
+    assert_eq!(service["service"], "MonsterApi");
+    assert_eq!(
+        service["routes"][1],
+        serde_json::json!({
+            "method": "GET",
+            "path": "/monsters/{id: i32}",
+            "query": null,
+            "headers": [],
+            "body": null,
+            "multipart": false,
+            "returns": "result[Monster][MonsterError]",
+            "auth": false,
+            "flag": null,
+            "doc": null,
+        })
+    );
+    assert_eq!(service["routes"][0]["query"], "MonsterQuery");
+    assert_eq!(service["routes"][2]["body"], "Monster");
+    // internal routes are not published
+    assert_eq!(service["routes"].as_array().unwrap().len(), 3);
+    assert!(!body.contains("/cache/flush"));
+
+    // pretty-printing does not apply to the HTML index
+    let (status, content_type, body) = request(&services, "GET", "/__spec/index.html?pretty").await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type, "text/html; charset=utf-8");
+    assert_eq!(body, SPEC_INDEX_HTML);
+    assert!(body.contains("<td><code>/monsters/{id: i32}</code></td>"));
+    assert!(body.contains("Find &lt;all&gt; monsters."));
+    assert!(!body.contains("/cache/flush"));
+
+    assert_eq!(request(&services, "POST", "/__spec").await.0, 404);
+    assert_eq!(request(&services, "GET", "/__spec/other").await.0, 404);
+
+    // the services are unaffected
+    let (status, content_type, body) = request(&services, "GET", "/api/monsters").await;
+    assert_eq!(
+        (status, content_type.as_str(), body.as_str()),
+        (200, "application/json", "[]")
+    );
+}
@@ -0,0 +1,90 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::rustls;
+use humblegen_rt::tls::TlsAcceptor;
+use protocol::*;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+struct S;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for S {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<String> {
+        Ok(format!("dragon {}", id))
+    }
+}
+
+// cert.pem is a self-signed certificate for `localhost`, key.pem its private key
+const CERT: &[u8] = include_bytes!("cert.pem");
+const KEY: &[u8] = include_bytes!("key.pem");
+
+/// Sends `GET path` via HTTPS to `port`, trusting `CERT`.
+fn get(port: u16, path: &str) -> String {
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in rustls::pki_types::pem::PemObject::pem_slice_iter(CERT) {
+        roots.add(cert.unwrap()).unwrap();
+    }
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let server_name = rustls::pki_types::ServerName::try_from("localhost").unwrap();
+    let conn = rustls::ClientConnection::new(Arc::new(config), server_name).unwrap();
+    let sock = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let mut tls = rustls::StreamOwned::new(conn, sock);
+    write!(
+        tls,
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        path
+    )
+    .unwrap();
+    let mut response = String::new();
+    tls.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[tokio::main]
+async fn main() {
+    assert!(TlsAcceptor::from_pem(CERT, b"no key").is_err());
+    assert!(TlsAcceptor::from_pem(b"no certificate", KEY).is_err());
+
+    let acceptor = TlsAcceptor::from_pem(CERT, KEY).unwrap();
+    let incoming = acceptor
+        .bind(&"127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    let port = incoming.local_addr().unwrap().port();
+    let builder = Builder::new().add("/api", Handler::MonsterApi(Arc::new(S)));
+    let handle = builder.shutdown_handle();
+    let server = tokio::spawn(builder.serve(incoming));
+
+    let response = tokio::task::spawn_blocking(move || get(port, "/api/monsters/3"))
+        .await
+        .unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.ends_with("\r\n\r\n\"dragon 3\""), "{}", response);
+
+    // plaintext HTTP is not served
+    let plaintext = tokio::task::spawn_blocking(move || {
+        let mut sock = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        sock.write_all(b"GET /api/monsters/3 HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = vec![];
+        let _ = sock.read_to_end(&mut response);
+        response
+    })
+    .await
+    .unwrap();
+    assert!(!plaintext.starts_with(b"HTTP"));
+
+    handle.shutdown();
+    server.await.unwrap().unwrap();
+}
@@ -0,0 +1,119 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use humblegen_rt::tracing::field::{Field, Visit};
+use humblegen_rt::tracing::span::{Attributes, Id, Record};
+use humblegen_rt::tracing::{Event, Metadata, Subscriber};
+use protocol::*;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+struct Monsters;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApi for Monsters {
+    type Context = ();
+
+    async fn get_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<String> {
+        Ok(format!("monster {}", id))
+    }
+
+    async fn get_lairs_name_maps_path(
+        &self,
+        _ctx: Self::Context,
+        name: String,
+        path: String,
+    ) -> Response<String> {
+        Ok(format!("map {} {}", name, path))
+    }
+}
+
+/// The name and the fields of a span, e.g. `MonsterApi.get_monsters_id id=1 status=200`.
+type Spans = Arc<Mutex<Vec<String>>>;
+
+/// Records the spans named after a route.
+struct RouteSpans(Spans);
+
+struct Fields<'a>(&'a mut String);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push_str(&format!(" {}={:?}", field.name(), value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push_str(&format!(" {}={}", field.name(), value));
+    }
+}
+
+impl Subscriber for RouteSpans {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.0.lock().unwrap();
+        let mut s = span.metadata().name().to_owned();
+        span.record(&mut Fields(&mut s));
+        spans.push(s);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.0.lock().unwrap();
+        values.record(&mut Fields(&mut spans[span.into_u64() as usize - 1]));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+async fn get(services: &Arc<Vec<Service>>, path: &str) -> u16 {
+    let req = hyper::Request::builder()
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    resp.status().as_u16()
+}
+
+#[tokio::main]
+async fn main() {
+    let spans = Spans::default();
+    humblegen_rt::tracing::subscriber::set_global_default(RouteSpans(spans.clone())).unwrap();
+
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApi(Arc::new(Monsters)))
+            .into_services(),
+    );
+    let s = &services;
+
+    assert_eq!(get(s, "/api/monsters/1").await, 200);
+    assert_eq!(get(s, "/api/monsters/x").await, 400);
+    assert_eq!(get(s, "/api/lairs/cave/maps/a/b.png").await, 200);
+    assert_eq!(get(s, "/api/dungeons").await, 404);
+
+    let route_spans: Vec<String> = spans
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|s| s.starts_with("MonsterApi."))
+        .cloned()
+        .collect();
+    assert_eq!(
+        route_spans,
+        vec![
+            "MonsterApi.get_monsters_id id=1 status=200",
+            "MonsterApi.get_monsters_id id=x status=400",
+            "MonsterApi.get_lairs_name_maps_path name=cave path=a/b.png status=200",
+        ]
+    );
+}
@@ -0,0 +1,87 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::hyper;
+use humblegen_rt::server::Service;
+use protocol::*;
+use std::sync::Arc;
+
+struct V1;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApiV1 for V1 {
+    type Context = ();
+
+    async fn get_v_1_health(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("v1".to_owned())
+    }
+
+    async fn get_v_1_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<Monster> {
+        Ok(Monster {
+            name: format!("monster {}", id),
+        })
+    }
+}
+
+struct V2;
+
+#[humblegen_rt::async_trait(Sync)]
+impl MonsterApiV2 for V2 {
+    type Context = ();
+
+    async fn get_v_2_health(&self, _ctx: Self::Context) -> Response<String> {
+        Ok("v2".to_owned())
+    }
+
+    async fn get_v_2_monsters_id(&self, _ctx: Self::Context, id: i32) -> Response<MonsterWithHp> {
+        Ok(MonsterWithHp {
+            name: format!("monster {}", id),
+            hp: 10,
+        })
+    }
+
+    async fn delete_v_2_monsters_id(&self, _ctx: Self::Context, _id: i32) -> Response<()> {
+        Ok(())
+    }
+}
+
+async fn request(services: &Arc<Vec<Service>>, method: &str, path: &str) -> (u16, String) {
+    let req = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::main]
+async fn main() {
+    // both versions are served side by side under the same root
+    let services = Arc::new(
+        Builder::new()
+            .add("/api", Handler::MonsterApiV1(Arc::new(V1)))
+            .add("/api", Handler::MonsterApiV2(Arc::new(V2)))
+            .into_services(),
+    );
+    let s = &services;
+
+    assert_eq!(request(s, "GET", "/api/v1/health").await, (200, "\"v1\"".to_owned()));
+    assert_eq!(request(s, "GET", "/api/v2/health").await, (200, "\"v2\"".to_owned()));
+    assert_eq!(
+        request(s, "GET", "/api/v1/monsters/3").await,
+        (200, r#"{"name":"monster 3"}"#.to_owned())
+    );
+    assert_eq!(
+        request(s, "GET", "/api/v2/monsters/3").await,
+        (200, r#"{"name":"monster 3","hp":10}"#.to_owned())
+    );
+    assert_eq!(request(s, "DELETE", "/api/v2/monsters/3").await.0, 204);
+
+    assert_eq!(request(s, "DELETE", "/api/v1/monsters/3").await.0, 404);
+    assert_eq!(request(s, "GET", "/api/v3/health").await.0, 404);
+    assert_eq!(request(s, "GET", "/api/v10/health").await.0, 404);
+    assert_eq!(request(s, "GET", "/api/health").await.0, 404);
+}
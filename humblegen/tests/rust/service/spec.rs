@@ -69,16 +69,50 @@ pub struct MonsterQuery {
     #[doc = ""]
     pub max_age: Option<i32>,
 }
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "384c99df32e0299d";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterData {
+    const WIRE_HASH: &'static str = "2c1b119ca8ee551d";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterData2 {
+    const WIRE_HASH: &'static str = "2e356cb86cb3ecf4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterData3 {
+    const WIRE_HASH: &'static str = "36b4aa1bdf8b6cb7";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterError {
+    const WIRE_HASH: &'static str = "0f42e21bd8e82a1c";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterPatch {
+    const WIRE_HASH: &'static str = "b8d08a4981d0de23";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterQuery {
+    const WIRE_HASH: &'static str = "c9ecb6d800598aab";
+}
+impl ::humblegen_rt::wire_hash::WireHash for PoliceCar {
+    const WIRE_HASH: &'static str = "08f44b07b5901a25";
+}
+impl ::humblegen_rt::wire_hash::WireHash for PoliceError {
+    const WIRE_HASH: &'static str = "bb63ac29d3400a3b";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Monster\": \"384c99df32e0299d\",\n  \"MonsterData\": \"2c1b119ca8ee551d\",\n  \"MonsterData2\": \"2e356cb86cb3ecf4\",\n  \"MonsterData3\": \"36b4aa1bdf8b6cb7\",\n  \"MonsterError\": \"0f42e21bd8e82a1c\",\n  \"MonsterPatch\": \"b8d08a4981d0de23\",\n  \"MonsterQuery\": \"c9ecb6d800598aab\",\n  \"PoliceCar\": \"08f44b07b5901a25\",\n  \"PoliceError\": \"bb63ac29d3400a3b\"\n}" ;
+#[doc = r" The resolved humblespec of the services, served at `/__spec` by `Builder::with_spec_endpoint`."]
+pub const SPEC_JSON : & str = "{\n  \"services\": [\n    {\n      \"doc\": \"service Godzilla provides services related to monsters.\",\n      \"format\": \"json\",\n      \"routes\": [\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": \"Get foo.\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/foo\",\n          \"query\": null,\n          \"returns\": \"u32\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": \"Get monster by id\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters/{id: i32}\",\n          \"query\": null,\n          \"returns\": \"result[Monster][MonsterError]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": \"Get monster by posting a query\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters\",\n          \"query\": \"MonsterQuery\",\n          \"returns\": \"list[Monster]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters2\",\n          \"query\": \"str\",\n          \"returns\": \"list[Monster]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters3\",\n          \"query\": \"i32\",\n          \"returns\": \"list[Monster]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters4\",\n          \"query\": null,\n          \"returns\": \"list[Monster]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/monsters5\",\n          \"query\": \"MonsterQuery\",\n          \"returns\": \"list[Monster]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": \"MonsterData\",\n          \"doc\": \"Create a new monster.\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"POST\",\n          \"multipart\": false,\n          \"path\": \"/monsters\",\n          \"query\": null,\n          \"returns\": \"result[Monster][MonsterError]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": \"Monster\",\n          \"doc\": \"Overwrite a monster.\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"PUT\",\n          \"multipart\": false,\n          \"path\": \"/monsters/{id: str}\",\n          \"query\": null,\n          \"returns\": \"result[()][MonsterError]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": \"MonsterPatch\",\n          \"doc\": \"Patch a monster.\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"PATCH\",\n          \"multipart\": false,\n          \"path\": \"/monsters/{id: str}\",\n          \"query\": null,\n          \"returns\": \"result[()][MonsterError]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": \"Delete a monster\",\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"DELETE\",\n          \"multipart\": false,\n          \"path\": \"/monster/{id: str}\",\n          \"query\": null,\n          \"returns\": \"result[()][MonsterError]\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/version\",\n          \"query\": null,\n          \"returns\": \"str\"\n        },\n        {\n          \"auth\": false,\n          \"body\": null,\n          \"doc\": null,\n          \"flag\": null,\n          \"headers\": [],\n          \"method\": \"GET\",\n          \"multipart\": false,\n          \"path\": \"/tokio-police-locations\",\n          \"query\": null,\n          \"returns\": \"result[list[PoliceCar]][PoliceError]\"\n        }\n      ],\n      \"service\": \"Godzilla\"\n    },\n    {\n      \"doc\": null,\n      \"format\": \"json\",\n      \"routes\": [],\n      \"service\": \"Movies\"\n    }\n  ],\n  \"types\": [\n    {\n      \"doc\": \"A wandering monster\",\n      \"fields\": [\n        {\n          \"doc\": \"Monster ID.\",\n          \"name\": \"id\",\n          \"renamed_from\": [],\n          \"type\": \"i32\"\n        },\n        {\n          \"doc\": \"The monster's name\",\n          \"name\": \"name\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        },\n        {\n          \"doc\": \"Max hitpoints.\",\n          \"name\": \"hp\",\n          \"renamed_from\": [],\n          \"type\": \"i32\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"foo\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"bar\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        }\n      ],\n      \"struct\": \"Monster\"\n    },\n    {\n      \"doc\": null,\n      \"fields\": [\n        {\n          \"doc\": \"The monster's name\",\n          \"name\": \"name\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        },\n        {\n          \"doc\": \"Max hitpoints.\",\n          \"name\": \"hp\",\n          \"renamed_from\": [],\n          \"type\": \"i32\"\n        }\n      ],\n      \"struct\": \"MonsterData\"\n    },\n    {\n      \"doc\": null,\n      \"fields\": [\n        {\n          \"doc\": null,\n          \"name\": \"foo\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"bar\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        }\n      ],\n      \"struct\": \"MonsterData2\"\n    },\n    {\n      \"doc\": \"patch of a monster\",\n      \"fields\": [\n        {\n          \"doc\": null,\n          \"name\": \"name\",\n          \"renamed_from\": [],\n          \"type\": \"option[str]\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"hp\",\n          \"renamed_from\": [],\n          \"type\": \"option[i32]\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"foo\",\n          \"renamed_from\": [],\n          \"type\": \"option[str]\"\n        }\n      ],\n      \"struct\": \"MonsterPatch\"\n    },\n    {\n      \"doc\": null,\n      \"fields\": [\n        {\n          \"doc\": null,\n          \"name\": \"bar\",\n          \"renamed_from\": [],\n          \"type\": \"str\"\n        }\n      ],\n      \"struct\": \"MonsterData3\"\n    },\n    {\n      \"doc\": \"Errors returned by the monster service.\",\n      \"enum\": \"MonsterError\",\n      \"variants\": [\n        {\n          \"doc\": null,\n          \"name\": \"TooWeak\"\n        },\n        {\n          \"doc\": null,\n          \"fields\": [\n            {\n              \"doc\": null,\n              \"name\": \"max_strength\",\n              \"renamed_from\": [],\n              \"type\": \"i32\"\n            }\n          ],\n          \"name\": \"TooStrong\"\n        }\n      ]\n    },\n    {\n      \"doc\": null,\n      \"fields\": [],\n      \"struct\": \"PoliceCar\"\n    },\n    {\n      \"doc\": null,\n      \"enum\": \"PoliceError\",\n      \"variants\": []\n    },\n    {\n      \"doc\": null,\n      \"fields\": [\n        {\n          \"doc\": null,\n          \"name\": \"name\",\n          \"renamed_from\": [],\n          \"type\": \"option[str]\"\n        },\n        {\n          \"doc\": null,\n          \"name\": \"max_age\",\n          \"renamed_from\": [],\n          \"type\": \"option[i32]\"\n        }\n      ],\n      \"struct\": \"MonsterQuery\"\n    }\n  ]\n}" ;
+#[doc = r" HTML index of the routes of the services, served at `/__spec/index.html` by `Builder::with_spec_endpoint`."]
+pub const SPEC_INDEX_HTML : & str = "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>API Routes</title>\n<style>body { font-family: sans-serif; } table { border-collapse: collapse; } td, th { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }</style>\n</head>\n<body>\n<h1>API Routes</h1>\n<p>Paths are relative to the root a service is mounted at. The full spec is available as <a href=\"../__spec\">JSON</a>.</p>\n<h2>Godzilla</h2>\n<p>service Godzilla provides services related to monsters.</p>\n<table>\n<tr><th>Method</th><th>Path</th><th>Body</th><th>Returns</th><th>Description</th></tr>\n<tr><td><code>GET</code></td><td><code>/foo</code></td><td><code></code></td><td><code>u32</code></td><td>Get foo.</td></tr>\n<tr><td><code>GET</code></td><td><code>/monsters/{id: i32}</code></td><td><code></code></td><td><code>result[Monster][MonsterError]</code></td><td>Get monster by id</td></tr>\n<tr><td><code>GET</code></td><td><code>/monsters?{MonsterQuery}</code></td><td><code></code></td><td><code>list[Monster]</code></td><td>Get monster by posting a query</td></tr>\n<tr><td><code>GET</code></td><td><code>/monsters2?{str}</code></td><td><code></code></td><td><code>list[Monster]</code></td><td></td></tr>\n<tr><td><code>GET</code></td><td><code>/monsters3?{i32}</code></td><td><code></code></td><td><code>list[Monster]</code></td><td></td></tr>\n<tr><td><code>GET</code></td><td><code>/monsters4</code></td><td><code></code></td><td><code>list[Monster]</code></td><td></td></tr>\n<tr><td><code>GET</code></td><td><code>/monsters5?{MonsterQuery}</code></td><td><code></code></td><td><code>list[Monster]</code></td><td></td></tr>\n<tr><td><code>POST</code></td><td><code>/monsters</code></td><td><code>MonsterData</code></td><td><code>result[Monster][MonsterError]</code></td><td>Create a new monster.</td></tr>\n<tr><td><code>PUT</code></td><td><code>/monsters/{id: str}</code></td><td><code>Monster</code></td><td><code>result[()][MonsterError]</code></td><td>Overwrite a monster.</td></tr>\n<tr><td><code>PATCH</code></td><td><code>/monsters/{id: str}</code></td><td><code>MonsterPatch</code></td><td><code>result[()][MonsterError]</code></td><td>Patch a monster.</td></tr>\n<tr><td><code>DELETE</code></td><td><code>/monster/{id: str}</code></td><td><code></code></td><td><code>result[()][MonsterError]</code></td><td>Delete a monster</td></tr>\n<tr><td><code>GET</code></td><td><code>/version</code></td><td><code></code></td><td><code>str</code></td><td></td></tr>\n<tr><td><code>GET</code></td><td><code>/tokio-police-locations</code></td><td><code></code></td><td><code>result[list[PoliceCar]][PoliceError]</code></td><td></td></tr>\n</table>\n<h2>Movies</h2>\n<p></p>\n<table>\n<tr><th>Method</th><th>Path</th><th>Body</th><th>Returns</th><th>Description</th></tr>\n\n</table>\n</body>\n</html>\n" ;
 #[allow(unused_imports)]
 use ::humblegen_rt::deser_helpers::{
-    deser_param, deser_post_data, deser_query_primitive, deser_query_serde_urlencoded,
+    deser_header, deser_opt_header, deser_param, deser_post_data, deser_query_primitive,
+    deser_query_serde_urlencoded,
 };
 #[allow(unused_imports)]
-pub use ::humblegen_rt::handler::{self, HandlerResponse as Response, ServiceError};
-#[allow(unused_imports)]
-use ::humblegen_rt::regexset_map::RegexSetMap;
+pub use ::humblegen_rt::handler::{
+    self, HandlerFuture, HandlerResponse as Response, ServiceError, WithHeaders,
+};
 #[allow(unused_imports)]
-use ::humblegen_rt::server::{self, handler_response_to_hyper_response, Route, Service};
+use ::humblegen_rt::server::{self, handler_response_to_hyper_response, Router, Service};
 #[allow(unused_imports)]
 use ::humblegen_rt::service_protocol::ErrorResponse;
 use ::humblegen_rt::tracing_futures::Instrument;
@@ -91,10 +125,88 @@ use std::net::SocketAddr;
 #[derive(Debug)]
 pub struct Builder {
     services: Vec<Service>,
+    metrics: Option<Arc<::humblegen_rt::metrics::Metrics>>,
+    feature_flags: Option<Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+    pretty_json: bool,
+    recorder: Option<Arc<::humblegen_rt::recording::Recorder>>,
+    middleware: Vec<Arc<dyn ::humblegen_rt::middleware::Middleware>>,
+    shutdown: ::humblegen_rt::shutdown::ShutdownHandle,
+    drain_delay: std::time::Duration,
+    limits: ::humblegen_rt::limits::Limits,
+    idempotency_store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
 }
 impl Builder {
     pub fn new() -> Self {
-        Self { services: vec![] }
+        Self {
+            services: vec![],
+            metrics: None,
+            feature_flags: None,
+            pretty_json: false,
+            recorder: None,
+            middleware: vec![],
+            shutdown: Default::default(),
+            drain_delay: std::time::Duration::from_secs(0),
+            limits: Default::default(),
+            idempotency_store: Arc::new(::humblegen_rt::idempotency::MemoryStore::default()),
+        }
+    }
+    #[doc = r" Records the latencies of requests to the routes of handlers `add`ed afterwards"]
+    #[doc = r" in `metrics`."]
+    pub fn with_metrics(mut self, metrics: Arc<::humblegen_rt::metrics::Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    #[doc = r#" Consults `feature_flags` whether the routes annotated with `@flag("name")` of"#]
+    #[doc = r" handlers `add`ed afterwards are enabled. Without, these routes are hidden."]
+    pub fn with_feature_flags(
+        mut self,
+        feature_flags: Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>,
+    ) -> Self {
+        self.feature_flags = Some(feature_flags);
+        self
+    }
+    #[doc = r" Runs `middleware` around the requests to the routes of handlers `add`ed afterwards,"]
+    #[doc = r" inside the middleware passed before."]
+    pub fn with_middleware(
+        mut self,
+        middleware: Arc<dyn ::humblegen_rt::middleware::Middleware>,
+    ) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+    #[doc = r" Answers requests to the routes of handlers `add`ed afterwards with a body larger than"]
+    #[doc = r" `max_body_size` bytes with 413 Payload Too Large, unless annotated with `@max_body_size`."]
+    pub fn with_max_body_size(mut self, max_body_size: u64) -> Self {
+        self.limits.max_body_size = Some(max_body_size);
+        self
+    }
+    #[doc = r" Answers requests to the routes of handlers `add`ed afterwards with 504 Gateway Timeout"]
+    #[doc = r" if not handled within `timeout`, unless annotated with `@timeout`."]
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.limits.timeout = Some(timeout);
+        self
+    }
+    #[doc = r" Deduplicates the requests to the routes annotated with `@idempotent` of handlers `add`ed"]
+    #[doc = r" afterwards using `store`, e.g. one shared by all instances of the server. Defaults to a"]
+    #[doc = r" `MemoryStore`."]
+    pub fn with_idempotency_store(
+        mut self,
+        store: Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+    ) -> Self {
+        self.idempotency_store = store;
+        self
+    }
+    #[doc = r" Whether responses of handlers `add`ed afterwards are pretty-printed JSON, e.g. for"]
+    #[doc = r" debugging. Defaults to compact JSON, unless a request has query parameter `pretty=1`."]
+    pub fn with_pretty_json(mut self, pretty_json: bool) -> Self {
+        self.pretty_json = pretty_json;
+        self
+    }
+    #[doc = r" Records all requests served by `listen_and_run_forever` and their responses in"]
+    #[doc = r" `recorder`, to be replayed using `humblegen_rt::recording::replay`."]
+    pub fn with_recorder(mut self, recorder: Arc<::humblegen_rt::recording::Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
     }
     #[doc = r" Mounts `handler` at URL path prefix `root`."]
     #[doc = r" This means that a `handler` implementing humble service"]
@@ -117,24 +229,106 @@ impl Builder {
         } else if root.ends_with('/') {
             panic!("root must not end with \"/\"")
         }
-        let routes: Vec<Route> = handler.into_routes();
-        let routes = RegexSetMap::new(routes).unwrap();
-        self.services.push(Service((
-            humblegen_rt::regex::Regex::new(&format!(r"^(?P<root>{})(?P<suffix>/.*)", root))
-                .unwrap(),
-            routes,
-        )));
+        self.services.push(Service {
+            root: root.to_owned(),
+            route_prefix: handler.route_prefix().to_owned(),
+            router: handler.into_router(
+                root,
+                self.metrics.as_deref(),
+                self.feature_flags.as_ref(),
+                &self.middleware,
+                self.limits,
+                &self.idempotency_store,
+            ),
+            pretty_json: self.pretty_json,
+        });
         self
     }
+    #[doc = r" Serves the metrics passed to `with_metrics` in the Prometheus text format at `/metrics`."]
+    pub fn with_metrics_endpoint(mut self) -> Self {
+        let metrics = self
+            .metrics
+            .clone()
+            .expect("with_metrics must be called before with_metrics_endpoint");
+        self.services
+            .push(::humblegen_rt::metrics::service(metrics));
+        self
+    }
+    #[doc = r" Serves liveness and readiness probes at `/health/live` and `/health/ready`, the latter"]
+    #[doc = r" responding with 503 Service Unavailable once the server is shutting down."]
+    pub fn with_health_endpoints(mut self) -> Self {
+        self.services.push(::humblegen_rt::shutdown::health_service(
+            self.shutdown.clone(),
+        ));
+        self
+    }
+    #[doc = r" Returns the handle to shut down the server gracefully: `listen_and_run_forever`"]
+    #[doc = r" stops accepting connections and returns once the requests in flight completed."]
+    pub fn shutdown_handle(&self) -> ::humblegen_rt::shutdown::ShutdownHandle {
+        self.shutdown.clone()
+    }
+    #[doc = r" Shuts down the server gracefully on SIGTERM or Ctrl-C."]
+    #[doc = r" Must be called within a tokio runtime."]
+    pub fn with_shutdown_signal(self) -> Self {
+        self.shutdown.shutdown_on_signal();
+        self
+    }
+    #[doc = r" Keeps accepting connections for `drain_delay` after the shutdown started, while"]
+    #[doc = r" the readiness probe already fails, such that load balancers stop routing requests"]
+    #[doc = r" to the server before it stops accepting connections. Defaults to zero."]
+    pub fn with_drain_delay(mut self, drain_delay: std::time::Duration) -> Self {
+        self.drain_delay = drain_delay;
+        self
+    }
+    #[doc = r" Serves the resolved humblespec of the services as JSON document at `/__spec`,"]
+    #[doc = r" and an HTML index of their routes at `/__spec/index.html`."]
+    pub fn with_spec_endpoint(mut self) -> Self {
+        self.services.push(::humblegen_rt::spec_endpoint::service(
+            SPEC_JSON,
+            SPEC_INDEX_HTML,
+        ));
+        self
+    }
+    #[doc = r" Returns the previously `add`ed handlers as services, to be served using"]
+    #[doc = r" `humblegen_rt::server::handle_request`, e.g. in a custom hyper server or in tests."]
+    pub fn into_services(self) -> Vec<Service> {
+        self.services
+    }
     #[doc = r" Starts an HTTP server bound to address `addr` and serves incoming requests using"]
-    #[doc = r" the previously `add`ed handlers."]
+    #[doc = r" the previously `add`ed handlers, until shut down using `shutdown_handle`."]
     pub async fn listen_and_run_forever(
         self,
         addr: &SocketAddr,
     ) -> humblegen_rt::anyhow::Result<()> {
-        use humblegen_rt::anyhow::Context;
-        let services = RegexSetMap::new(self.services).context("invalid service configuration")?;
-        server::listen_and_run_forever(services, addr).await
+        server::listen_and_run_forever(
+            self.services,
+            addr,
+            self.recorder,
+            self.shutdown,
+            self.drain_delay,
+        )
+        .await
+    }
+    #[doc = r" Like `listen_and_run_forever`, but serves the connections accepted by `incoming`,"]
+    #[doc = r" e.g. to terminate TLS using `humblegen_rt::tls::TlsAcceptor::bind`."]
+    pub async fn serve<I>(self, incoming: I) -> humblegen_rt::anyhow::Result<()>
+    where
+        I: ::humblegen_rt::hyper::server::accept::Accept,
+        I::Conn: ::humblegen_rt::tokio::io::AsyncRead
+            + ::humblegen_rt::tokio::io::AsyncWrite
+            + Unpin
+            + Send
+            + 'static,
+        I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        server::serve(
+            incoming,
+            self.services,
+            self.recorder,
+            self.shutdown,
+            self.drain_delay,
+        )
+        .await
     }
 }
 #[doc = r" Wrapper enum with one variant for each service defined in the humble spec."]
@@ -145,10 +339,40 @@ pub enum Handler<Context: Default + Sized + Send + Sync + 'static> {
     Movies(Arc<dyn Movies<Context = Context> + Send + Sync>),
 }
 impl<Context: Default + Sized + Send + Sync + 'static> Handler<Context> {
-    fn into_routes(self) -> Vec<Route> {
+    fn into_router(
+        self,
+        root: &str,
+        metrics: Option<&::humblegen_rt::metrics::Metrics>,
+        feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+        middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+        limits: ::humblegen_rt::limits::Limits,
+        idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+    ) -> Box<Router> {
+        match self {
+            Handler::Godzilla(h) => routes_Godzilla(
+                h,
+                root,
+                metrics,
+                feature_flags,
+                middleware,
+                limits,
+                idempotency_store,
+            ),
+            Handler::Movies(h) => routes_Movies(
+                h,
+                root,
+                metrics,
+                feature_flags,
+                middleware,
+                limits,
+                idempotency_store,
+            ),
+        }
+    }
+    fn route_prefix(&self) -> &'static str {
         match self {
-            Handler::Godzilla(h) => routes_Godzilla(h),
-            Handler::Movies(h) => routes_Movies(h),
+            Handler::Godzilla(_) => "",
+            Handler::Movies(_) => "",
         }
     }
 }
@@ -162,7 +386,7 @@ impl<Context: Default + Sized + Send + Sync + 'static> std::fmt::Debug for Handl
     }
 }
 #[doc = "service Godzilla provides services related to monsters."]
-#[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait Godzilla {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<Self::Context, ServiceError> {\n        Ok(Self::Context::default())\n    }\n    async fn get_foo(&self, ctx: Self::Context) -> Response<u32>;\n    async fn get_monsters_id(\n        &self,\n        ctx: Self::Context,\n        id: i32,\n    ) -> Response<Result<Monster, MonsterError>>;\n    async fn get_monsters(\n        &self,\n        ctx: Self::Context,\n        query: Option<MonsterQuery>,\n    ) -> Response<Vec<Monster>>;\n    async fn get_monsters_2(\n        &self,\n        ctx: Self::Context,\n        query: Option<String>,\n    ) -> Response<Vec<Monster>>;\n    async fn get_monsters_3(\n        &self,\n        ctx: Self::Context,\n        query: Option<i32>,\n    ) -> Response<Vec<Monster>>;\n    async fn get_monsters_4(&self, ctx: Self::Context) -> Response<Vec<Monster>>;\n    async fn post_monsters(\n        &self,\n        ctx: Self::Context,\n        post_body: MonsterData,\n    ) -> Response<Result<Monster, MonsterError>>;\n    async fn put_monsters_id(\n        &self,\n        ctx: Self::Context,\n        post_body: Monster,\n        id: String,\n    ) -> Response<Result<(), MonsterError>>;\n    async fn patch_monsters_id(\n        &self,\n        ctx: Self::Context,\n        post_body: MonsterPatch,\n        id: String,\n    ) -> Response<Result<(), MonsterError>>;\n    async fn delete_monster_id(\n        &self,\n        ctx: Self::Context,\n        id: String,\n    ) -> Response<Result<(), MonsterError>>;\n    async fn get_version(&self, ctx: Self::Context) -> Response<String>;\n    async fn get_tokio_police_locations(\n        &self,\n        ctx: Self::Context,\n    ) -> Response<Result<Vec<PoliceCar>, PoliceError>>;\n}\n\n```"]
+#[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait Godzilla {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<Self::Context, ServiceError> {\n        Ok(Self::Context::default())\n    }\n    async fn get_foo(&self, ctx: Self::Context) -> Response<u32>;\n    async fn get_monsters_id(\n        &self,\n        ctx: Self::Context,\n        id: i32,\n    ) -> Response<Result<Monster, MonsterError>>;\n    async fn get_monsters(\n        &self,\n        ctx: Self::Context,\n        query: Option<MonsterQuery>,\n    ) -> Response<Vec<Monster>>;\n    async fn get_monsters_2(\n        &self,\n        ctx: Self::Context,\n        query: Option<String>,\n    ) -> Response<Vec<Monster>>;\n    async fn get_monsters_3(\n        &self,\n        ctx: Self::Context,\n        query: Option<i32>,\n    ) -> Response<Vec<Monster>>;\n    async fn get_monsters_4(&self, ctx: Self::Context) -> Response<Vec<Monster>>;\n    async fn get_monsters_5(\n        &self,\n        ctx: Self::Context,\n        query: Option<MonsterQuery>,\n    ) -> Response<Vec<Monster>>;\n    async fn post_monsters(\n        &self,\n        ctx: Self::Context,\n        post_body: MonsterData,\n    ) -> Response<Result<Monster, MonsterError>>;\n    async fn put_monsters_id(\n        &self,\n        ctx: Self::Context,\n        post_body: Monster,\n        id: String,\n    ) -> Response<Result<(), MonsterError>>;\n    async fn patch_monsters_id(\n        &self,\n        ctx: Self::Context,\n        post_body: MonsterPatch,\n        id: String,\n    ) -> Response<Result<(), MonsterError>>;\n    async fn delete_monster_id(\n        &self,\n        ctx: Self::Context,\n        id: String,\n    ) -> Response<Result<(), MonsterError>>;\n    async fn get_version(&self, ctx: Self::Context) -> Response<String>;\n    async fn get_tokio_police_locations(\n        &self,\n        ctx: Self::Context,\n    ) -> Response<Result<Vec<PoliceCar>, PoliceError>>;\n}\n\n```"]
 #[humblegen_rt::async_trait(Sync)]
 pub trait Godzilla {
     type Context: Default + Sized + Send + Sync;
@@ -206,6 +430,13 @@ pub trait Godzilla {
     #[doc = "```\nasync fn get_monsters_4(&self, ctx: Self::Context) -> Response<Vec<Monster>> {}\n\n```"]
     #[doc = ""]
     async fn get_monsters_4(&self, ctx: Self::Context) -> Response<Vec<Monster>>;
+    #[doc = "```\nasync fn get_monsters_5(\n    &self,\n    ctx: Self::Context,\n    query: Option<MonsterQuery>,\n) -> Response<Vec<Monster>> {\n}\n\n```"]
+    #[doc = ""]
+    async fn get_monsters_5(
+        &self,
+        ctx: Self::Context,
+        query: Option<MonsterQuery>,
+    ) -> Response<Vec<Monster>>;
     #[doc = "```\nasync fn post_monsters(\n    &self,\n    ctx: Self::Context,\n    post_body: MonsterData,\n) -> Response<Result<Monster, MonsterError>> {\n}\n\n```"]
     #[doc = "Create a new monster."]
     async fn post_monsters(
@@ -249,369 +480,795 @@ pub trait Godzilla {
 #[allow(unused_variables)]
 #[allow(unused_mut)]
 #[allow(non_snake_case)]
-#[allow(clippy::trivial_regex)]
-#[allow(clippy::single_char_pattern)]
+#[allow(unreachable_patterns)]
 fn routes_Godzilla<Context: Default + Sized + Send + Sync + 'static>(
     handler: Arc<dyn Godzilla<Context = Context> + Send + Sync>,
-) -> Vec<Route> {
-    vec![
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/foo$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.get_foo(ctx).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters/(?P<id>[^/]+)$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        let id: Result<i32, ErrorResponse> = deser_param("id", &captures["id"]);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let id = id?;
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.get_monsters_id(ctx, id).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let query: Option<MonsterQuery> = match req.uri().query() {
-                                None => None,
-                                Some(q) => Some(deser_query_serde_urlencoded(q)?),
-                            };
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.get_monsters(ctx, query).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters2$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let query: Option<String> = match req.uri().query() {
-                                None => None,
-                                Some(q) => Some(deser_query_primitive(q)?),
-                            };
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.get_monsters_2(ctx, query).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters3$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let query: Option<i32> = match req.uri().query() {
-                                None => None,
-                                Some(q) => Some(deser_query_primitive(q)?),
-                            };
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.get_monsters_3(ctx, query).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
+    root: &str,
+    metrics: Option<&::humblegen_rt::metrics::Metrics>,
+    feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+    middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+    limits: ::humblegen_rt::limits::Limits,
+    idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+) -> Box<Router> {
+    let idempotency_store = Arc::clone(idempotency_store);
+    let route_histograms: Vec<Option<Arc<::humblegen_rt::metrics::LatencyHistogram>>> = vec![
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/foo"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/monsters/{id}"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/monsters"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/monsters2"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/monsters3"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/monsters4"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/monsters5"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::POST.as_str(),
+                &format!("{}{}", root, "/monsters"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::PUT.as_str(),
+                &format!("{}{}", root, "/monsters/{id}"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::PATCH.as_str(),
+                &format!("{}{}", root, "/monsters/{id}"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::DELETE.as_str(),
+                &format!("{}{}", root, "/monster/{id}"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/version"),
+                None,
+            )
+        }),
+        metrics.map(|m| {
+            m.route_histogram(
+                ::humblegen_rt::hyper::Method::GET.as_str(),
+                &format!("{}{}", root, "/tokio-police-locations"),
+                None,
+            )
+        }),
+    ];
+    let feature_flags = feature_flags.cloned();
+    let middleware: Arc<[Arc<dyn ::humblegen_rt::middleware::Middleware>]> = middleware.into();
+    Box::new(
+        move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
+              suffix_start: usize| {
+            let mut segments = [""; 2usize];
+            let num_segments =
+                server::split_path_segments(&req.uri().path()[suffix_start..], &mut segments)?;
+            let routed = match &segments[..num_segments] {
+                ["foo"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /foo", "route matched");
+                    let span =
+                        tracing::error_span!("Godzilla.get_foo", status = tracing::field::Empty);
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[0usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /foo",
+                            handler: "get_foo",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_foo(ctx).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /foo",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /monsters", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_monsters",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[2usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /monsters",
+                            handler: "get_monsters",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let query: Option<MonsterQuery> = match req.uri().query() {
+                                    None => None,
+                                    Some(q) => Some(deser_query_serde_urlencoded(q)?),
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_monsters(ctx, query).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /monsters",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters2"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /monsters2", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_monsters_2",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[3usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /monsters2",
+                            handler: "get_monsters_2",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let query: Option<String> = match req.uri().query() {
+                                    None => None,
+                                    Some(q) => Some(deser_query_primitive(q)?),
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_monsters_2(ctx, query).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /monsters2",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters3"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /monsters3", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_monsters_3",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[4usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /monsters3",
+                            handler: "get_monsters_3",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let query: Option<i32> = match req.uri().query() {
+                                    None => None,
+                                    Some(q) => Some(deser_query_primitive(q)?),
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_monsters_3(ctx, query).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /monsters3",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters4"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /monsters4", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_monsters_4",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[5usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /monsters4",
+                            handler: "get_monsters_4",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_monsters_4(ctx).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /monsters4",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters5"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /monsters5", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_monsters_5",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[6usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /monsters5",
+                            handler: "get_monsters_5",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let query: Option<MonsterQuery> = match req.uri().query() {
+                                    None => None,
+                                    Some(q) => Some(deser_query_serde_urlencoded(q)?),
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_monsters_5(ctx, query).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /monsters5",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters"] if *req.method() == ::humblegen_rt::hyper::Method::POST => {
+                    tracing::debug!(route = "POST /monsters", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.post_monsters",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[7usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "POST /monsters",
+                            handler: "post_monsters",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                ::humblegen_rt::compression::decompress_request(&mut req)?;
+                                if let Some(max_body_size) = limits.max_body_size {
+                                    ::humblegen_rt::limits::limit_body(&mut req, max_body_size)
+                                        .await?;
+                                }
+                                let post_body: MonsterData =
+                                    deser_post_data(req.body_mut()).await?;
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler
+                                            .post_monsters(ctx, post_body)
+                                            .instrument(span)
+                                            .await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "POST /monsters",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["version"] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /version", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_version",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[11usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /version",
+                            handler: "get_version",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_version(ctx).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /version",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["tokio-police-locations"]
+                    if *req.method() == ::humblegen_rt::hyper::Method::GET =>
+                {
+                    tracing::debug!(route = "GET /tokio-police-locations", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_tokio_police_locations",
+                        status = tracing::field::Empty
+                    );
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[12usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /tokio-police-locations",
+                            handler: "get_tokio_police_locations",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler
+                                            .get_tokio_police_locations(ctx)
+                                            .instrument(span)
+                                            .await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /tokio-police-locations",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters", id] if *req.method() == ::humblegen_rt::hyper::Method::GET => {
+                    tracing::debug!(route = "GET /monsters/{id}", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.get_monsters_id",
+                        id = id,
+                        status = tracing::field::Empty
+                    );
+                    let id: Result<i32, ErrorResponse> = deser_param("id", id);
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[1usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "GET /monsters/{id}",
+                            handler: "get_monsters_id",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let id = id?;
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.get_monsters_id(ctx, id).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "GET /monsters/{id}",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters", id] if *req.method() == ::humblegen_rt::hyper::Method::PUT => {
+                    tracing::debug!(route = "PUT /monsters/{id}", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.put_monsters_id",
+                        id = id,
+                        status = tracing::field::Empty
+                    );
+                    let id: Result<String, ErrorResponse> = deser_param("id", id);
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[8usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "PUT /monsters/{id}",
+                            handler: "put_monsters_id",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let id = id?;
+                                ::humblegen_rt::compression::decompress_request(&mut req)?;
+                                if let Some(max_body_size) = limits.max_body_size {
+                                    ::humblegen_rt::limits::limit_body(&mut req, max_body_size)
+                                        .await?;
+                                }
+                                let post_body: Monster = deser_post_data(req.body_mut()).await?;
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler
+                                            .put_monsters_id(ctx, post_body, id)
+                                            .instrument(span)
+                                            .await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "PUT /monsters/{id}",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monsters", id] if *req.method() == ::humblegen_rt::hyper::Method::PATCH => {
+                    tracing::debug!(route = "PATCH /monsters/{id}", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.patch_monsters_id",
+                        id = id,
+                        status = tracing::field::Empty
+                    );
+                    let id: Result<String, ErrorResponse> = deser_param("id", id);
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[9usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "PATCH /monsters/{id}",
+                            handler: "patch_monsters_id",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let id = id?;
+                                ::humblegen_rt::compression::decompress_request(&mut req)?;
+                                if let Some(max_body_size) = limits.max_body_size {
+                                    ::humblegen_rt::limits::limit_body(&mut req, max_body_size)
+                                        .await?;
+                                }
+                                let post_body: MonsterPatch =
+                                    deser_post_data(req.body_mut()).await?;
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler
+                                            .patch_monsters_id(ctx, post_body, id)
+                                            .instrument(span)
+                                            .await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "PATCH /monsters/{id}",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                ["monster", id] if *req.method() == ::humblegen_rt::hyper::Method::DELETE => {
+                    tracing::debug!(route = "DELETE /monster/{id}", "route matched");
+                    let span = tracing::error_span!(
+                        "Godzilla.delete_monster_id",
+                        id = id,
+                        status = tracing::field::Empty
+                    );
+                    let id: Result<String, ErrorResponse> = deser_param("id", id);
+                    let handler = Arc::clone(&handler);
+                    let histogram = route_histograms[10usize].clone();
+                    static ROUTE: ::humblegen_rt::middleware::RouteInfo =
+                        ::humblegen_rt::middleware::RouteInfo {
+                            service: "Godzilla",
+                            route: "DELETE /monster/{id}",
+                            handler: "delete_monster_id",
+                        };
+                    let accept_encoding =
+                        ::humblegen_rt::compression::Encoding::negotiate(req.headers());
+                    let dispatch = ::humblegen_rt::middleware::run(
+                        &middleware,
+                        &ROUTE,
+                        req,
+                        move |mut req| {
+                            Box::pin(async move {
+                                use ::humblegen_rt::service_protocol::ToErrorResponse;
+                                let ctx = {
+                                    let span = tracing::error_span!("interceptor");
+                                    handler . intercept_handler_pre (& req) . instrument (span) . await . map_err (:: humblegen_rt :: service_protocol :: ServiceError :: from) . map_err (| e | { tracing :: debug ! (service_error = ? format ! ("{:?}" , e) , "interceptor rejected request") ; e }) . map_err (| e | e . to_error_response ()) ?
+                                };
+                                let id = id?;
+                                drop(req);
+                                {
+                                    let span = tracing::error_span!("handler");
+                                    Ok(handler_response_to_hyper_response(
+                                        handler.delete_monster_id(ctx, id).instrument(span).await,
+                                    ))
+                                }
+                            })
+                        },
+                    );
+                    let dispatch = ::humblegen_rt::limits::with_timeout(limits.timeout, dispatch);
+                    let dispatch = ::humblegen_rt::compression::compress(accept_encoding, dispatch);
+                    Some((
+                        "DELETE /monster/{id}",
+                        ::humblegen_rt::metrics::observe_latency(
+                            histogram,
+                            server::trace_route(span, dispatch),
+                        ),
+                    ))
+                }
+                _ => None,
+            };
+            routed
         },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters4$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.get_monsters_4(ctx).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::POST,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let post_body: MonsterData = deser_post_data(req.body_mut()).await?;
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.post_monsters(ctx, post_body).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::PUT,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters/(?P<id>[^/]+)$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        let id: Result<String, ErrorResponse> = deser_param("id", &captures["id"]);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let id = id?;
-                            let post_body: Monster = deser_post_data(req.body_mut()).await?;
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler
-                                        .put_monsters_id(ctx, post_body, id)
-                                        .instrument(span)
-                                        .await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::PATCH,
-                regex: ::humblegen_rt::regex::Regex::new("^/monsters/(?P<id>[^/]+)$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        let id: Result<String, ErrorResponse> = deser_param("id", &captures["id"]);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let id = id?;
-                            let post_body: MonsterPatch = deser_post_data(req.body_mut()).await?;
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler
-                                        .patch_monsters_id(ctx, post_body, id)
-                                        .instrument(span)
-                                        .await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::DELETE,
-                regex: ::humblegen_rt::regex::Regex::new("^/monster/(?P<id>[^/]+)$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        let id: Result<String, ErrorResponse> = deser_param("id", &captures["id"]);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            let id = id?;
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.delete_monster_id(ctx, id).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/version$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler.get_version(ctx).instrument(span).await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-        {
-            let handler = Arc::clone(&handler);
-            Route {
-                method: ::humblegen_rt::hyper::Method::GET,
-                regex: ::humblegen_rt::regex::Regex::new("^/tokio-police-locations$").unwrap(),
-                dispatcher: Box::new(
-                    move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
-                          captures| {
-                        let handler = Arc::clone(&handler);
-                        Box::pin(async move {
-                            use ::humblegen_rt::service_protocol::ToErrorResponse;
-                            let ctx = {
-                                let span = tracing::error_span!("interceptor");
-                                handler . intercept_handler_pre ( & req ) . instrument ( span ) . await . map_err ( :: humblegen_rt :: service_protocol :: ServiceError :: from ) . map_err ( | e | { tracing :: debug ! ( service_error = ? format ! ( "{:?}" , e ) , "interceptor rejected request" ) ; e } ) . map_err ( | e | e . to_error_response ( ) ) ?
-                            };
-                            drop(req);
-                            {
-                                let span = tracing::error_span!("handler");
-                                Ok(handler_response_to_hyper_response(
-                                    handler
-                                        .get_tokio_police_locations(ctx)
-                                        .instrument(span)
-                                        .await,
-                                ))
-                            }
-                        })
-                    },
-                ),
-            }
-        },
-    ]
+    )
 }
 #[doc = ""]
 #[doc = "```\n#[humblegen_rt::async_trait(Sync)]\npub trait Movies {\n    type Context: Default + Sized + Send + Sync;\n    async fn intercept_handler_pre(\n        &self,\n        _req: &hyper::Request<hyper::Body>,\n    ) -> Result<Self::Context, ServiceError> {\n        Ok(Self::Context::default())\n    }\n}\n\n```"]
@@ -628,10 +1285,30 @@ pub trait Movies {
 #[allow(unused_variables)]
 #[allow(unused_mut)]
 #[allow(non_snake_case)]
-#[allow(clippy::trivial_regex)]
-#[allow(clippy::single_char_pattern)]
+#[allow(unreachable_patterns)]
 fn routes_Movies<Context: Default + Sized + Send + Sync + 'static>(
     handler: Arc<dyn Movies<Context = Context> + Send + Sync>,
-) -> Vec<Route> {
-    vec![]
+    root: &str,
+    metrics: Option<&::humblegen_rt::metrics::Metrics>,
+    feature_flags: Option<&Arc<dyn ::humblegen_rt::feature_flags::FeatureFlags>>,
+    middleware: &[Arc<dyn ::humblegen_rt::middleware::Middleware>],
+    limits: ::humblegen_rt::limits::Limits,
+    idempotency_store: &Arc<dyn ::humblegen_rt::idempotency::IdempotencyStore>,
+) -> Box<Router> {
+    let idempotency_store = Arc::clone(idempotency_store);
+    let route_histograms: Vec<Option<Arc<::humblegen_rt::metrics::LatencyHistogram>>> = vec![];
+    let feature_flags = feature_flags.cloned();
+    let middleware: Arc<[Arc<dyn ::humblegen_rt::middleware::Middleware>]> = middleware.into();
+    Box::new(
+        move |mut req: ::humblegen_rt::hyper::Request<::humblegen_rt::hyper::Body>,
+              suffix_start: usize| {
+            let mut segments = [""; 0usize];
+            let num_segments =
+                server::split_path_segments(&req.uri().path()[suffix_start..], &mut segments)?;
+            let routed = match &segments[..num_segments] {
+                _ => None,
+            };
+            routed
+        },
+    )
 }
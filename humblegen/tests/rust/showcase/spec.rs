@@ -55,3 +55,12 @@ pub enum Color {
         v: u8,
     },
 }
+impl ::humblegen_rt::wire_hash::WireHash for Color {
+    const WIRE_HASH: &'static str = "e61a7a10760c9cb5";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Customer {
+    const WIRE_HASH: &'static str = "dd33dd829cee1a31";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Color\": \"e61a7a10760c9cb5\",\n  \"Customer\": \"dd33dd829cee1a31\"\n}";
@@ -0,0 +1,24 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let monster = Monster {
+        id: MonsterId(7),
+        position: Point(1, -2),
+        thumbnail: Some(Thumbnail(vec![0, 255])),
+    };
+    // newtypes are encoded as their inner value, other tuple structs as arrays
+    let json = serde_json::to_value(&monster).unwrap();
+    assert_eq!(
+        json,
+        json!({"id": 7, "position": [1, -2], "thumbnail": "AP8="})
+    );
+
+    let decoded: Monster = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.id.0, 7);
+    assert_eq!((decoded.position.0, decoded.position.1), (1, -2));
+    assert_eq!(decoded.thumbnail.unwrap().0, vec![0, 255]);
+}
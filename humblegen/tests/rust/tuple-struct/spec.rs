@@ -0,0 +1,37 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A position on the map."]
+pub struct Point(pub i32, pub i32);
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "Identifies a monster."]
+pub struct MonsterId(pub u32);
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Thumbnail(
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub Vec<u8>,
+);
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub id: MonsterId,
+    #[doc = ""]
+    pub position: Point,
+    #[doc = ""]
+    pub thumbnail: Option<Thumbnail>,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "97989f298831b65c";
+}
+impl ::humblegen_rt::wire_hash::WireHash for MonsterId {
+    const WIRE_HASH: &'static str = "4d2bdf193e8526d1";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Point {
+    const WIRE_HASH: &'static str = "aa27fcd2c55f07f4";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Thumbnail {
+    const WIRE_HASH: &'static str = "2f2ec0474f1c4fe4";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Monster\": \"97989f298831b65c\",\n  \"MonsterId\": \"4d2bdf193e8526d1\",\n  \"Point\": \"aa27fcd2c55f07f4\",\n  \"Thumbnail\": \"2f2ec0474f1c4fe4\"\n}" ;
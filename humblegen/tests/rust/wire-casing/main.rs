@@ -0,0 +1,34 @@
+mod protocol {
+    include!("spec.rs");
+}
+use protocol::*;
+use serde_json::json;
+
+fn main() {
+    let monster = Monster {
+        display_name: "Godzilla".to_owned(),
+        nickname: Some("Goji".to_owned()),
+        home_island: Island::MonsterIsland,
+    };
+    assert_eq!(
+        serde_json::to_value(&monster).unwrap(),
+        json!({"displayName": "Godzilla", "nickname": "Goji", "homeIsland": "monsterIsland"})
+    );
+
+    let island = Island::Lost { last_seen: 1954 };
+    assert_eq!(
+        serde_json::to_value(&island).unwrap(),
+        json!({"lost": {"lastSeen": 1954}})
+    );
+    let island: Island = serde_json::from_value(json!({"farOut": "Odo"})).unwrap();
+    assert!(matches!(island, Island::FarOut(name) if name == "Odo"));
+
+    // former names are cased as well
+    let monster: Monster = serde_json::from_value(json!({
+        "displayName": "Mothra",
+        "nickName": "Queen",
+        "homeIsland": "monsterIsland",
+    }))
+    .unwrap();
+    assert_eq!(monster.nickname.as_deref(), Some("Queen"));
+}
@@ -0,0 +1,69 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(try_from = "__MonsterDeserialize")]
+#[serde(rename_all = "camelCase")]
+#[doc = ""]
+pub struct Monster {
+    #[doc = ""]
+    pub display_name: String,
+    #[doc = ""]
+    pub nickname: Option<String>,
+    #[doc = ""]
+    pub home_island: Island,
+}
+#[doc(hidden)]
+#[derive(serde :: Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct __MonsterDeserialize {
+    #[doc = ""]
+    display_name: String,
+    #[serde(
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    nickname: Option<Option<String>>,
+    #[serde(
+        rename = "nickName",
+        default,
+        deserialize_with = "::humblegen_rt::serialization_helpers::deser_present"
+    )]
+    __nickname_renamed_from_0: Option<Option<String>>,
+    #[doc = ""]
+    home_island: Island,
+}
+impl ::std::convert::TryFrom<__MonsterDeserialize> for Monster {
+    type Error = String;
+    fn try_from(wire: __MonsterDeserialize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            display_name: wire.display_name,
+            nickname: wire
+                .nickname
+                .or(wire.__nickname_renamed_from_0)
+                .unwrap_or(None),
+            home_island: wire.home_island,
+        })
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[serde(rename_all = "camelCase")]
+#[doc = ""]
+pub enum Island {
+    #[doc = ""]
+    MonsterIsland,
+    #[doc = ""]
+    FarOut(String),
+    #[doc = ""]
+    #[serde(rename_all = "camelCase")]
+    Lost {
+        #[doc = ""]
+        last_seen: i32,
+    },
+}
+impl ::humblegen_rt::wire_hash::WireHash for Island {
+    const WIRE_HASH: &'static str = "35a4e44361072aa1";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "431fbbb45294d3f9";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"Island\": \"35a4e44361072aa1\",\n  \"Monster\": \"431fbbb45294d3f9\"\n}";
@@ -0,0 +1,30 @@
+mod protocol {
+    include!("spec.rs");
+}
+use humblegen_rt::wire_hash::{self, WireHash};
+use protocol::*;
+use std::collections::BTreeMap;
+
+fn main() {
+    // names and the order of fields are not part of the wire shape
+    assert_eq!(Monster::WIRE_HASH, LegacyMonster::WIRE_HASH);
+    assert_ne!(Monster::WIRE_HASH, Color::WIRE_HASH);
+    assert_ne!(Permissions::WIRE_HASH, Color::WIRE_HASH);
+
+    let manifest: BTreeMap<String, String> = serde_json::from_str(WIRE_MANIFEST).unwrap();
+    assert_eq!(manifest["Monster"], Monster::WIRE_HASH);
+    assert_eq!(manifest.len(), 4);
+
+    assert!(wire_hash::incompatible_types(WIRE_MANIFEST, WIRE_MANIFEST)
+        .unwrap()
+        .is_empty());
+    let theirs = serde_json::json!({
+        "Monster": Monster::WIRE_HASH,
+        "Color": "0000000000000000",
+        "Weapon": "0000000000000000",
+    });
+    assert_eq!(
+        wire_hash::incompatible_types(WIRE_MANIFEST, &theirs.to_string()).unwrap(),
+        vec!["Color".to_owned()]
+    );
+}
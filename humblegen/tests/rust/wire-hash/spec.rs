@@ -0,0 +1,71 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A monster."]
+pub struct Monster {
+    #[doc = ""]
+    pub name: String,
+    #[doc = ""]
+    pub hit_points: u32,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "A monster of the previous release, with the same wire format."]
+pub struct LegacyMonster {
+    #[doc = ""]
+    pub hit_points: u32,
+    #[doc = ""]
+    pub name: String,
+}
+::humblegen_rt::bitflags::bitflags! { # [doc = ""] pub struct Permissions : u64 { # [doc = ""] const READ = 1 << 0 ; # [doc = ""] const WRITE = 1 << 1 ; } }
+impl ::std::default::Default for Permissions {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+impl serde::Serialize for Permissions {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.bits().count_ones() as usize))?;
+        if self.contains(Self::READ) {
+            seq.serialize_element("Read")?;
+        }
+        if self.contains(Self::WRITE) {
+            seq.serialize_element("Write")?;
+        }
+        seq.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Permissions {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+        let mut flags = Self::empty();
+        for name in names {
+            flags |= match name.as_str() {
+                "Read" => Self::READ,
+                "Write" => Self::WRITE,
+                name => return Err(serde::de::Error::unknown_variant(name, &["Read", "Write"])),
+            };
+        }
+        Ok(flags)
+    }
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum Color {
+    #[doc = ""]
+    Red,
+    #[doc = ""]
+    Green,
+}
+impl ::humblegen_rt::wire_hash::WireHash for Color {
+    const WIRE_HASH: &'static str = "6ea6cb4bdaef81ab";
+}
+impl ::humblegen_rt::wire_hash::WireHash for LegacyMonster {
+    const WIRE_HASH: &'static str = "603e963a056824aa";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Monster {
+    const WIRE_HASH: &'static str = "603e963a056824aa";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Permissions {
+    const WIRE_HASH: &'static str = "e7d443dd871a0fdd";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST : & str = "{\n  \"Color\": \"6ea6cb4bdaef81ab\",\n  \"LegacyMonster\": \"603e963a056824aa\",\n  \"Monster\": \"603e963a056824aa\",\n  \"Permissions\": \"e7d443dd871a0fdd\"\n}" ;
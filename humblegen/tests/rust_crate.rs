@@ -0,0 +1,109 @@
+use humblegen::backend::rust::{Crate, Generator};
+use humblegen::testing::{generate_files, generate_workspace_files};
+use humblegen::{Artifact, LibError};
+
+const SPEC: &str = "meta { title: \"Monster API\", version: \"2.1.0\" }\n\
+                    struct Monster { name: str }\n\
+                    @format(cbor)\n\
+                    service MonsterApi { GET /monsters -> list[Monster] }";
+
+fn generator(artifact: Artifact) -> Generator {
+    Generator::new(artifact)
+        .unwrap()
+        .with_crate(Some(Crate::new("monster-api").unwrap()))
+}
+
+#[test]
+fn crate_scaffold() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = generator(Artifact::ClientAndServer).with_arbitrary_impls(true);
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        vec!["Cargo.toml", "src/lib.rs"]
+    );
+    let cargo_toml = &files["Cargo.toml"];
+    assert!(cargo_toml.contains("name = \"monster-api\"\nversion = \"2.1.0\"\n"));
+    assert!(cargo_toml.contains("description = \"Types and endpoints of Monster API\""));
+    assert!(cargo_toml.contains(&format!(
+        "humblegen-rt = {{ version = \"={}\", features = [\"arbitrary\", \"cbor\"] }}",
+        env!("CARGO_PKG_VERSION")
+    )));
+    assert!(cargo_toml.contains("client = [\"humblegen-rt/client\"]\n"));
+    assert!(cargo_toml.contains("server = []\n"));
+
+    let lib = &files["src/lib.rs"];
+    assert!(lib.contains("pub struct Monster"));
+    assert!(lib.contains("#[cfg(feature = \"client\")]"));
+    assert!(lib.contains("#[cfg(feature = \"server\")]"));
+}
+
+#[test]
+fn endpoints_of_crates_are_feature_gated() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+
+    let files = generate_files(&generator(Artifact::ClientEndpoints), &spec).unwrap();
+    assert!(files["src/lib.rs"].contains("#[cfg(feature = \"client\")]"));
+    assert!(!files["src/lib.rs"].contains("#[cfg(feature = \"server\")]"));
+    assert!(files["Cargo.toml"].contains("client = "));
+    assert!(!files["Cargo.toml"].contains("server = "));
+
+    let files = generate_files(&generator(Artifact::ServerEndpoints), &spec).unwrap();
+    assert!(files["src/lib.rs"].contains("#[cfg(feature = \"server\")]"));
+    assert!(!files["src/lib.rs"].contains("#[cfg(feature = \"client\")]"));
+    assert!(!files["Cargo.toml"].contains("client = "));
+    assert!(files["Cargo.toml"].contains("server = []"));
+}
+
+#[test]
+fn workspace_crate_scaffold() {
+    let workspace =
+        humblegen::workspace::Workspace::load("./tests/workspaces/rust/humblegen-workspace.toml")
+            .unwrap();
+
+    let files =
+        generate_workspace_files(&generator(Artifact::ServerEndpoints), &workspace).unwrap();
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        vec![
+            "Cargo.toml",
+            "src/common.rs",
+            "src/lib.rs",
+            "src/monsters.rs",
+            "src/users.rs"
+        ]
+    );
+    let lib = &files["src/lib.rs"];
+    assert!(lib.contains("pub mod common;"));
+    assert!(lib.contains("pub mod monsters;"));
+    assert!(lib.contains("pub mod users;"));
+    assert!(files["Cargo.toml"].contains("name = \"monster-api\""));
+}
+
+#[test]
+fn crates_are_not_generated_into_non_empty_folders() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let output = tempfile::tempdir().unwrap();
+    std::fs::write(output.path().join("lib.rs"), "").unwrap();
+
+    let result =
+        humblegen::CodeGenerator::generate(&generator(Artifact::TypesOnly), &spec, output.path());
+    assert!(matches!(result, Err(LibError::OutputFolderNotEmpty { .. })));
+}
+
+#[test]
+fn benchmarks_are_no_crates() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let result = generate_files(&generator(Artifact::Benchmarks), &spec);
+    assert!(matches!(result, Err(LibError::UnsupportedArtifact { .. })));
+}
+
+#[test]
+fn crate_names() {
+    assert!(Crate::new("monster-api").is_ok());
+    assert!(Crate::new("monster_api2").is_ok());
+    for name in ["", "2monsters", "-monsters", "monster api", "monsters::api"] {
+        assert!(Crate::new(name).is_err(), "{}", name);
+    }
+}
@@ -0,0 +1,61 @@
+use humblegen::backend::elm::Generator;
+use humblegen::size_report::size_report;
+use humblegen::Artifact;
+
+fn generator() -> Generator {
+    Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap()
+}
+
+#[test]
+fn sizes_by_definition() {
+    let spec = humblegen::parse(
+        "struct Monster { name: str, lair: Lair }\n\
+         struct Lair { name: str, owner: option[Owner] }\n\
+         struct Owner { lair: Lair }\n\
+         service MonsterApi { GET /monsters -> list[Monster] }"
+            .as_bytes(),
+    )
+    .unwrap();
+    let report = size_report(&generator(), &spec).unwrap();
+
+    let mut names: Vec<_> = report
+        .definitions
+        .iter()
+        .map(|d| d.names.join(", "))
+        .collect();
+    names.sort();
+    // mutually recursive definitions are measured together
+    assert_eq!(names, ["Monster", "MonsterApi", "Owner, Lair"]);
+    assert!(report.definitions.iter().all(|d| d.bytes > 0));
+    assert!(report.files["Service/MonsterApi.elm"] > 0);
+    let definitions: usize = report.definitions.iter().map(|d| d.bytes).sum();
+    assert_eq!(definitions + report.baseline, report.total());
+}
+
+#[test]
+fn hints_at_large_enums_and_services() {
+    let variants: Vec<_> = (0..100).map(|i| format!("Species{}", i)).collect();
+    let endpoints: Vec<_> = (0..30)
+        .map(|i| format!("GET /species{} -> Species", i))
+        .collect();
+    let spec = humblegen::parse(
+        format!(
+            "enum Species {{ {} }}\nservice SpeciesApi {{ {} }}",
+            variants.join(", "),
+            endpoints.join(",\n")
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+    let report = size_report(&generator(), &spec).unwrap();
+
+    assert!(report
+        .hints
+        .iter()
+        .any(|h| h.starts_with("enum `Species` has 100 variants")));
+    assert!(report
+        .hints
+        .iter()
+        .any(|h| h.starts_with("service `SpeciesApi` has 30 endpoints")));
+    assert!(report.to_string().contains("hint: "));
+}
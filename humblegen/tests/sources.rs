@@ -0,0 +1,56 @@
+use humblegen::sources::SpecSources;
+use humblegen::LibError;
+
+fn sources() -> SpecSources {
+    SpecSources::new()
+        .with_source(
+            "api/monsters.humble",
+            r#"
+            include "../common/types.humble" as types
+            struct Monster { stats: types.Stats }
+            "#,
+        )
+        .with_source("common/types.humble", "struct Stats { hp: i32 }")
+        .with_source("api/lairs.humble", "struct Lair { name: str }")
+}
+
+#[test]
+fn includes_resolve_to_sources_by_virtual_file_name() {
+    let spec = sources().parse("api/monsters.humble").unwrap();
+    let names: Vec<_> = spec.iter().map(|item| item.name()).collect();
+    assert_eq!(names, vec!["TypesStats", "Monster"]);
+}
+
+#[test]
+fn parse_all_skips_included_fragments() {
+    let specs = sources().parse_all().unwrap();
+    let names: Vec<_> = specs.keys().map(String::as_str).collect();
+    assert_eq!(names, vec!["api/lairs.humble", "api/monsters.humble"]);
+}
+
+#[test]
+fn parse_errors_refer_to_the_virtual_file_name() {
+    let err = sources()
+        .with_source("api/broken.humble", "struct Broken {")
+        .parse("api/broken.humble")
+        .unwrap_err();
+    assert!(matches!(err, LibError::ParseError(_)));
+    assert!(err.to_string().contains("api/broken.humble:1:"));
+}
+
+#[test]
+fn unknown_sources_are_rejected() {
+    let err = sources().parse("api/missing.humble").unwrap_err();
+    assert!(matches!(err, LibError::UnknownSource(name) if name == "api/missing.humble"));
+
+    let err = SpecSources::new()
+        .with_source("spec.humble", "include \"types.humble\" as types")
+        .parse("spec.humble")
+        .unwrap_err();
+    match err {
+        LibError::IncludeError(msg) => {
+            assert_eq!(msg, "cannot read `types.humble`: no such source")
+        }
+        other => panic!("expected include error, got {:?}", other),
+    }
+}
@@ -0,0 +1,124 @@
+use humblegen::ast::SpecItem;
+
+#[test]
+fn index_looks_up_items_by_name() {
+    let spec = humblegen::parse(
+        r#"
+        struct Monster { name: str }
+        enum Color { Red }
+        service MonsterApi { GET /monsters -> list[Monster], }
+        "#
+        .as_bytes(),
+    )
+    .unwrap();
+    let index = spec.index();
+
+    assert_eq!(index.struct_def("Monster").unwrap().name, "Monster");
+    assert_eq!(index.enum_def("Color").unwrap().name, "Color");
+    assert!(index.struct_def("Color").is_none());
+    assert!(index.get("Missing").is_none());
+
+    let id = index.id("MonsterApi").unwrap();
+    assert!(matches!(spec.get(id), SpecItem::ServiceDef(_)));
+}
+
+#[test]
+fn embeds_of_large_specs_are_resolved() {
+    // every struct embeds its predecessor in groups of ten
+    let mut src = String::new();
+    for i in 0..2000 {
+        if i % 10 == 0 {
+            src.push_str(&format!("struct S{} {{ f{}: i32 }}\n", i, i));
+        } else {
+            src.push_str(&format!("struct S{} {{ f{}: i32, ..S{} }}\n", i, i, i - 1));
+        }
+    }
+    let spec = humblegen::parse(src.as_bytes()).unwrap();
+
+    let fields: Vec<_> = spec
+        .index()
+        .struct_def("S1999")
+        .unwrap()
+        .fields
+        .iter()
+        .map(|field| field.pair.name.clone())
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            "f1999", "f1998", "f1997", "f1996", "f1995", "f1994", "f1993", "f1992", "f1991",
+            "f1990"
+        ]
+    );
+}
+
+#[test]
+fn embed_loops_are_rejected() {
+    let err = humblegen::parse("struct A { ..B }\nstruct B { ..A }".as_bytes()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid definition `A`: maximum embed depth is 10"
+    );
+}
+
+#[test]
+fn anonymous_structs_are_named_after_their_path() {
+    let spec = humblegen::parse(
+        r#"
+        struct Location { position: { lat: f64, lon: f64, altitude: { meters: f64 } } }
+        enum Shape { Circle { center: { x: f64, y: f64 } } }
+        service MonsterApi { POST /monsters/{id: i32}/rename -> { name: str } -> (), }
+        "#
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let names: Vec<_> = spec.iter().map(|item| item.name()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "Location",
+            "LocationPosition",
+            "LocationPositionAltitude",
+            "Shape",
+            "ShapeCircleCenter",
+            "MonsterApi",
+            "MonsterApiPostMonstersIdRenameBody",
+        ]
+    );
+    let index = spec.index();
+    let position = &index.struct_def("Location").unwrap().fields.0[0];
+    assert_eq!(
+        position.pair.type_ident.user_defined().unwrap(),
+        "LocationPosition"
+    );
+    let altitude = &index.struct_def("LocationPosition").unwrap().fields.0[2];
+    assert_eq!(
+        altitude.pair.type_ident.user_defined().unwrap(),
+        "LocationPositionAltitude"
+    );
+}
+
+#[test]
+fn identifiers_are_interned() {
+    let source = r#"
+        struct Monster { name: str }
+        struct Lair { monsters: list[Monster], boss: option[Monster] }
+        service MonsterApi { GET /monsters -> list[Monster], }
+        "#;
+    let same_allocation = |spec: &humblegen::ast::Spec| {
+        let mut names = vec![spec.index().struct_def("Monster").unwrap().name.clone()];
+        spec.clone().for_each_type_ident_mut(&mut |ty| {
+            if let Some(name) = ty.user_defined() {
+                names.push(name.clone());
+            }
+        });
+        assert_eq!(names.len(), 4);
+        names.iter().all(|name| name.as_ptr() == names[0].as_ptr())
+    };
+
+    let spec = humblegen::parse(source.as_bytes()).unwrap();
+    assert!(same_allocation(&spec));
+    let loaded = humblegen::ast_json::load_ast(&humblegen::ast_json::dump_ast(&spec)).unwrap();
+    assert!(same_allocation(&loaded));
+}
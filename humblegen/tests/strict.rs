@@ -0,0 +1,94 @@
+use humblegen::backend::{elm, graphql, rust};
+use humblegen::strict::check_strict;
+use humblegen::{Artifact, LargeIntRepresentation, LibError};
+
+fn elm_generator() -> elm::Generator {
+    elm::Generator::new(Artifact::ClientEndpoints, "Api".to_owned()).unwrap()
+}
+
+fn violations(result: Result<(), LibError>) -> Vec<String> {
+    match result {
+        Err(LibError::StrictModeViolation(violations)) => violations,
+        other => panic!("expected strict mode violations, got {:?}", other),
+    }
+}
+
+#[test]
+fn strict_mode_rejects_unaccepted_lossy_mappings() {
+    let spec = humblegen::parse(
+        "struct Monster { name: str, xp: u32, friends: list[option[u32]] }\n\
+         enum Event { Hit(u32), Heal { amount: u8 } }\n\
+         service MonsterApi { GET /monsters/{id: u32} -> Monster }"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        violations(check_strict(&elm_generator(), &spec)),
+        [
+            "Monster.xp: `u32` is mapped to `Int`, which is only guaranteed to hold 32-bit signed integers; accept with `@lossy(u32)`",
+            "Monster.friends: `u32` is mapped to `Int`, which is only guaranteed to hold 32-bit signed integers; accept with `@lossy(u32)`",
+            "Event::Hit: `u32` is mapped to `Int`, which is only guaranteed to hold 32-bit signed integers; accept with `@lossy(u32)`",
+            "Event::Heal.amount: `u8` is mapped to `Int`, which does not restrict values to bytes; accept with `@lossy(u8)`",
+            "MonsterApi: GET /monsters/{id}: `u32` is mapped to `Int`, which is only guaranteed to hold 32-bit signed integers; accept with `@lossy(u32)`",
+        ]
+    );
+    // the rust backend maps all types losslessly
+    let rust_generator = rust::Generator::new(Artifact::ServerEndpoints).unwrap();
+    check_strict(&rust_generator, &spec).unwrap();
+}
+
+#[test]
+fn lossy_annotation_accepts_mappings() {
+    let spec = humblegen::parse(
+        "struct Monster { name: str, @lossy(u32) xp: u32 }\n\
+         @lossy(u8)\nstruct Color(u8, u8, u8);\n\
+         @lossy(u32, u8)\nenum Event { Hit(u32), Heal { amount: u8 } }\n\
+         service MonsterApi { @lossy(u32) GET /monsters/{id: u32} -> Monster }"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    check_strict(&elm_generator(), &spec).unwrap();
+}
+
+#[test]
+fn empty_type_is_accepted_as_string() {
+    let generator = graphql::Generator::new(Artifact::ServerEndpoints).unwrap();
+    let spec =
+        humblegen::parse("service MonsterApi { DELETE /monsters/{id: i32} -> () }".as_bytes())
+            .unwrap();
+    assert_eq!(
+        violations(check_strict(&generator, &spec)),
+        ["MonsterApi: DELETE /monsters/{id}: `()` is mapped to `Boolean`, a placeholder without meaning; accept with `@lossy(\"()\")`"]
+    );
+
+    let spec = humblegen::parse(
+        "service MonsterApi { @lossy(\"()\") DELETE /monsters/{id: i32} -> () }".as_bytes(),
+    )
+    .unwrap();
+    check_strict(&generator, &spec).unwrap();
+}
+
+#[test]
+fn large_ints_are_lossy_in_elm_unless_strings() {
+    let spec = humblegen::parse("struct Monster { id: u64 }".as_bytes()).unwrap();
+    assert_eq!(
+        violations(check_strict(&elm_generator(), &spec)),
+        ["Monster.id: `u64` is mapped to `Int`, which only represents integers up to 2^53 exactly; accept with `@lossy(u64)`"]
+    );
+
+    let generator = elm_generator().with_large_int_representation(LargeIntRepresentation::String);
+    check_strict(&generator, &spec).unwrap();
+}
+
+#[test]
+fn lossy_annotation_requires_built_in_types() {
+    let err = humblegen::parse("struct Monster { @lossy(u16) xp: u32 }".as_bytes()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("`@lossy` expects the built-in types"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,28 @@
+use humblegen::backend::swift::Generator;
+use humblegen::testing::{generate_files, SINGLE_FILE};
+use humblegen::Artifact;
+
+const SPEC: &str = include_str!("swift/spec.humble");
+
+/// Reference output of the swift backend for `spec.humble`.
+const SWIFT: &str = include_str!("swift/spec.swift");
+
+#[test]
+fn swift() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::ClientEndpoints).unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(files[SINGLE_FILE], SWIFT);
+}
+
+#[test]
+fn swift_types_only() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = Generator::new(Artifact::TypesOnly).unwrap();
+
+    let swift = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    assert!(swift.contains("public struct Monster: Codable {"));
+    assert!(!swift.contains("public struct MonsterApi {"));
+    assert!(!swift.contains("URLSession"));
+}
@@ -0,0 +1,762 @@
+// Generated by humblegen, do not edit.
+
+import Foundation
+
+/// Coding key of any name, used by the coders of enums and tuples.
+public struct HumbleCodingKey: CodingKey {
+    public var stringValue: String
+    public var intValue: Int? { nil }
+
+    public init(_ stringValue: String) {
+        self.stringValue = stringValue
+    }
+
+    public init?(stringValue: String) {
+        self.stringValue = stringValue
+    }
+
+    public init?(intValue: Int) {
+        return nil
+    }
+}
+
+/// Encoder and decoder of the humblespec JSON representation.
+public enum HumbleJSON {
+    public static func encoder() -> JSONEncoder {
+        let encoder = JSONEncoder()
+        encoder.dateEncodingStrategy = .custom { date, encoder in
+            var container = encoder.singleValueContainer()
+            try container.encode(fractionalSecondsFormatter().string(from: date))
+        }
+        return encoder
+    }
+
+    public static func decoder() -> JSONDecoder {
+        let decoder = JSONDecoder()
+        decoder.dateDecodingStrategy = .custom { decoder in
+            let container = try decoder.singleValueContainer()
+            let string = try container.decode(String.self)
+            guard let date = fractionalSecondsFormatter().date(from: string) ?? ISO8601DateFormatter().date(from: string) else {
+                throw DecodingError.dataCorruptedError(in: container, debugDescription: "invalid datetime \(string)")
+            }
+            return date
+        }
+        return decoder
+    }
+
+    static func fractionalSecondsFormatter() -> ISO8601DateFormatter {
+        let formatter = ISO8601DateFormatter()
+        formatter.formatOptions = [.withInternetDateTime, .withFractionalSeconds]
+        return formatter
+    }
+}
+
+/// The `()` type, encoded as `null`.
+public struct HumbleEmpty: Codable, Hashable {
+    public init() {}
+
+    public init(from decoder: Decoder) throws {}
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        try container.encodeNil()
+    }
+}
+
+/// The `date` type, encoded in ISO 8601 format (`YYYY-MM-DD`).
+public struct HumbleDate: Codable, Hashable, CustomStringConvertible {
+    public var year: Int
+    public var month: Int
+    public var day: Int
+
+    public init(year: Int, month: Int, day: Int) {
+        self.year = year
+        self.month = month
+        self.day = day
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        let string = try container.decode(String.self)
+        let parts = string.split(separator: "-").compactMap { Int($0) }
+        guard parts.count == 3 else {
+            throw DecodingError.dataCorruptedError(in: container, debugDescription: "invalid date \(string)")
+        }
+        self.init(year: parts[0], month: parts[1], day: parts[2])
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        try container.encode(description)
+    }
+
+    public var description: String {
+        String(format: "%04d-%02d-%02d", year, month, day)
+    }
+}
+
+/// The `result[T][E]` type, encoded as `{"Ok": value}` or `{"Err": error}`.
+public enum HumbleResult<T: Codable, E: Codable>: Codable {
+    case ok(T)
+    case err(E)
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        if container.contains(HumbleCodingKey("Ok")) {
+            self = .ok(try container.decode(T.self, forKey: HumbleCodingKey("Ok")))
+        } else {
+            self = .err(try container.decode(E.self, forKey: HumbleCodingKey("Err")))
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: HumbleCodingKey.self)
+        switch self {
+        case .ok(let value):
+            try container.encode(value, forKey: HumbleCodingKey("Ok"))
+        case .err(let error):
+            try container.encode(error, forKey: HumbleCodingKey("Err"))
+        }
+    }
+}
+
+/// The single variant of an externally tagged enum, `{"Variant": content}`.
+func humbleVariant(_ decoder: Decoder, of name: String) throws -> (KeyedDecodingContainer<HumbleCodingKey>, HumbleCodingKey) {
+    let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+    guard container.allKeys.count == 1, let key = container.allKeys.first else {
+        throw DecodingError.dataCorrupted(.init(codingPath: decoder.codingPath, debugDescription: "expected a variant of \(name)"))
+    }
+    return (container, key)
+}
+
+func humbleUnknownVariant(_ decoder: Decoder, _ tag: String, of name: String) -> Error {
+    DecodingError.dataCorrupted(.init(codingPath: decoder.codingPath, debugDescription: "unknown variant \(tag) of \(name)"))
+}
+
+/// The key of field `name`, which is also decoded from its `formerly` used names.
+func humbleKey(_ container: KeyedDecodingContainer<HumbleCodingKey>, _ name: String, formerly: [String]) -> HumbleCodingKey {
+    let key = HumbleCodingKey(name)
+    if container.contains(key) {
+        return key
+    }
+    return formerly.map { HumbleCodingKey($0) }.first { container.contains($0) } ?? key
+}
+
+/// Error of a response with a status code other than 2XX, except for domain errors.
+public struct HumbleError: Error {
+    public let statusCode: Int
+    public let body: Data
+}
+
+/// Plain text representation of path, query and header parameters.
+public protocol HumbleParameter {
+    var humbleParameter: String { get }
+}
+
+extension String: HumbleParameter {
+    public var humbleParameter: String { self }
+}
+
+extension Int32: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension UInt32: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension UInt8: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension Double: HumbleParameter {
+    public var humbleParameter: String { String(self) }
+}
+
+extension Bool: HumbleParameter {
+    public var humbleParameter: String { self ? "true" : "false" }
+}
+
+extension Date: HumbleParameter {
+    public var humbleParameter: String { HumbleJSON.fractionalSecondsFormatter().string(from: self) }
+}
+
+extension HumbleDate: HumbleParameter {
+    public var humbleParameter: String { description }
+}
+
+extension UUID: HumbleParameter {
+    public var humbleParameter: String { uuidString.lowercased() }
+}
+
+/// JSON encoding of query parameters without a plain text representation.
+func humbleJSONParameter<T: Encodable>(_ value: T) throws -> String {
+    String(decoding: try HumbleJSON.encoder().encode(value), as: UTF8.self)
+}
+
+/// A request to `path` below `baseURL`.
+func humbleRequest(_ baseURL: URL, _ method: String, path: [String], query: [URLQueryItem]?, headers: [(String, String?)]) -> URLRequest {
+    var allowed = CharacterSet.urlPathAllowed
+    allowed.remove("/")
+    var url = baseURL
+    for segment in path {
+        url.appendPathComponent(segment.addingPercentEncoding(withAllowedCharacters: allowed) ?? segment, isDirectory: false)
+    }
+    if let query = query, var components = URLComponents(url: url, resolvingAgainstBaseURL: false) {
+        components.queryItems = query
+        url = components.url ?? url
+    }
+    var request = URLRequest(url: url)
+    request.httpMethod = method
+    for (name, value) in headers {
+        if let value = value {
+            request.setValue(value, forHTTPHeaderField: name)
+        }
+    }
+    return request
+}
+
+func humbleJSONBody<T: Encodable>(_ request: inout URLRequest, _ body: T) throws {
+    request.setValue("application/json", forHTTPHeaderField: "Content-Type")
+    request.httpBody = try HumbleJSON.encoder().encode(body)
+}
+
+/// Send `request` and decode the response, also for the `domainErrorStatuses`.
+func humbleSend<T: Decodable>(_ session: URLSession, _ request: URLRequest, domainErrorStatuses: [Int] = []) async throws -> T {
+    let (data, response) = try await session.data(for: request)
+    let status = (response as? HTTPURLResponse)?.statusCode ?? 0
+    guard (200..<300).contains(status) || domainErrorStatuses.contains(status) else {
+        throw HumbleError(statusCode: status, body: data)
+    }
+    return try HumbleJSON.decoder().decode(T.self, from: data)
+}
+
+/// Send `request` to a route returning `()`, whose response has no body.
+func humbleSendNoContent(_ session: URLSession, _ request: URLRequest) async throws {
+    let (data, response) = try await session.data(for: request)
+    let status = (response as? HTTPURLResponse)?.statusCode ?? 0
+    guard (200..<300).contains(status) else {
+        throw HumbleError(statusCode: status, body: data)
+    }
+}
+
+/// Builder of `multipart/form-data` bodies.
+struct HumbleMultipart {
+    let boundary = "humblegen-\(UUID().uuidString)"
+    var body = Data()
+
+    mutating func addText(_ name: String, _ value: String) {
+        body.append(Data("--\(boundary)\r\nContent-Disposition: form-data; name=\"\(name)\"\r\n\r\n\(value)\r\n".utf8))
+    }
+
+    mutating func addFile(_ name: String, _ value: Data) {
+        body.append(Data("--\(boundary)\r\nContent-Disposition: form-data; name=\"\(name)\"; filename=\"\(name)\"\r\nContent-Type: application/octet-stream\r\n\r\n".utf8))
+        body.append(value)
+        body.append(Data("\r\n".utf8))
+    }
+
+    func apply(to request: inout URLRequest) {
+        request.setValue("multipart/form-data; boundary=\(boundary)", forHTTPHeaderField: "Content-Type")
+        request.httpBody = body + Data("--\(boundary)--\r\n".utf8)
+    }
+}
+
+/// A monster.
+public struct Monster: Codable {
+    /// Unique name.
+    public var name: String
+    public var age: UInt8
+    public var born: Date
+    public var nickname: String?
+    public var tags: [String]
+    public var stats: [String: Double]
+    public var position: TupleOfF64AndF64
+    public var id: UUID
+    public var object: Bool
+    public var abilities: Abilities
+
+    public init(name: String, age: UInt8, born: Date, nickname: String?, tags: [String], stats: [String: Double], position: TupleOfF64AndF64, id: UUID, object: Bool, abilities: Abilities) {
+        self.name = name
+        self.age = age
+        self.born = born
+        self.nickname = nickname
+        self.tags = tags
+        self.stats = stats
+        self.position = position
+        self.id = id
+        self.object = object
+        self.abilities = abilities
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        self.name = try container.decode(String.self, forKey: HumbleCodingKey("name"))
+        self.age = try container.decode(UInt8.self, forKey: HumbleCodingKey("age"))
+        self.born = try container.decode(Date.self, forKey: HumbleCodingKey("born"))
+        self.nickname = try container.decodeIfPresent(String.self, forKey: humbleKey(container, "nickname", formerly: ["nick"]))
+        self.tags = try container.decode([String].self, forKey: HumbleCodingKey("tags"))
+        self.stats = try container.decode([String: Double].self, forKey: HumbleCodingKey("stats"))
+        self.position = try container.decode(TupleOfF64AndF64.self, forKey: HumbleCodingKey("position"))
+        self.id = try container.decode(UUID.self, forKey: HumbleCodingKey("id"))
+        self.object = try container.decode(Bool.self, forKey: HumbleCodingKey("object"))
+        self.abilities = try container.decode(Abilities.self, forKey: HumbleCodingKey("abilities"))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: HumbleCodingKey.self)
+        try container.encode(name, forKey: HumbleCodingKey("name"))
+        try container.encode(age, forKey: HumbleCodingKey("age"))
+        try container.encode(born, forKey: HumbleCodingKey("born"))
+        try container.encode(nickname, forKey: HumbleCodingKey("nickname"))
+        try container.encode(tags, forKey: HumbleCodingKey("tags"))
+        try container.encode(stats, forKey: HumbleCodingKey("stats"))
+        try container.encode(position, forKey: HumbleCodingKey("position"))
+        try container.encode(id, forKey: HumbleCodingKey("id"))
+        try container.encode(object, forKey: HumbleCodingKey("object"))
+        try container.encode(abilities, forKey: HumbleCodingKey("abilities"))
+    }
+}
+
+public struct MonsterQuery: Codable {
+    public var name: String?
+    public var limit: UInt32
+    public var tag: String?
+
+    public init(name: String?, limit: UInt32, tag: String?) {
+        self.name = name
+        self.limit = limit
+        self.tag = tag
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        self.name = try container.decodeIfPresent(String.self, forKey: HumbleCodingKey("name"))
+        self.limit = try container.decode(UInt32.self, forKey: HumbleCodingKey("maxResults"))
+        self.tag = try container.decodeIfPresent(String.self, forKey: HumbleCodingKey("tag"))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: HumbleCodingKey.self)
+        try container.encode(name, forKey: HumbleCodingKey("name"))
+        try container.encode(limit, forKey: HumbleCodingKey("maxResults"))
+        try container.encode(tag, forKey: HumbleCodingKey("tag"))
+    }
+}
+
+public struct Portrait: Codable {
+    public var caption: String?
+    public var image: Data
+
+    public init(caption: String?, image: Data) {
+        self.caption = caption
+        self.image = image
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        self.caption = try container.decodeIfPresent(String.self, forKey: HumbleCodingKey("caption"))
+        self.image = try container.decode(Data.self, forKey: HumbleCodingKey("image"))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: HumbleCodingKey.self)
+        try container.encode(caption, forKey: HumbleCodingKey("caption"))
+        try container.encode(image, forKey: HumbleCodingKey("image"))
+    }
+}
+
+public enum Color: String, Codable {
+    case red = "Red"
+    /// Like the sky.
+    case lightBlue = "light-blue"
+}
+
+/// Priorities as understood by embedded devices.
+public enum Priority: Int64, Codable {
+    case low = 1
+    /// The default.
+    case normal = 2
+    case high = 10
+    case debug = -1
+}
+
+public enum Shape: Codable {
+    case point
+    case circle(Double)
+    case rect(width: Double, height: Double)
+    case line(TupleOfF64AndF64, TupleOfF64AndF64)
+
+    public init(from decoder: Decoder) throws {
+        if let tag = try? decoder.singleValueContainer().decode(String.self) {
+            switch tag {
+            case "Point":
+                self = .point
+            default:
+                throw humbleUnknownVariant(decoder, tag, of: "Shape")
+            }
+            return
+        }
+        let (container, key) = try humbleVariant(decoder, of: "Shape")
+        switch key.stringValue {
+        case "circle":
+            self = try .circle(container.decode(Double.self, forKey: key))
+        case "Rect":
+            let fields = try container.nestedContainer(keyedBy: HumbleCodingKey.self, forKey: key)
+            self = try .rect(width: fields.decode(Double.self, forKey: HumbleCodingKey("w")), height: fields.decode(Double.self, forKey: HumbleCodingKey("h")))
+        case "Line":
+            var items = try container.nestedUnkeyedContainer(forKey: key)
+            self = try .line(items.decode(TupleOfF64AndF64.self), items.decode(TupleOfF64AndF64.self))
+        default:
+            throw humbleUnknownVariant(decoder, key.stringValue, of: "Shape")
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        switch self {
+        case .point:
+            var container = encoder.singleValueContainer()
+            try container.encode("Point")
+        case .circle(let value):
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            try container.encode(value, forKey: HumbleCodingKey("circle"))
+        case .rect(let width, let height):
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            var fields = container.nestedContainer(keyedBy: HumbleCodingKey.self, forKey: HumbleCodingKey("Rect"))
+            try fields.encode(width, forKey: HumbleCodingKey("w"))
+            try fields.encode(height, forKey: HumbleCodingKey("h"))
+        case .line(let item0, let item1):
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            var items = container.nestedUnkeyedContainer(forKey: HumbleCodingKey("Line"))
+            try items.encode(item0)
+            try items.encode(item1)
+        }
+    }
+}
+
+public enum MonsterError: Codable {
+    case notFound
+    case invalid(String)
+
+    public init(from decoder: Decoder) throws {
+        if let tag = try? decoder.singleValueContainer().decode(String.self) {
+            switch tag {
+            case "NotFound":
+                self = .notFound
+            default:
+                throw humbleUnknownVariant(decoder, tag, of: "MonsterError")
+            }
+            return
+        }
+        let (container, key) = try humbleVariant(decoder, of: "MonsterError")
+        switch key.stringValue {
+        case "Invalid":
+            self = try .invalid(container.decode(String.self, forKey: key))
+        default:
+            throw humbleUnknownVariant(decoder, key.stringValue, of: "MonsterError")
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        switch self {
+        case .notFound:
+            var container = encoder.singleValueContainer()
+            try container.encode("NotFound")
+        case .invalid(let value):
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            try container.encode(value, forKey: HumbleCodingKey("Invalid"))
+        }
+    }
+}
+
+/// Coordinates on the map.
+public struct Coordinates: Codable {
+    public var item0: Double
+    public var item1: Double
+
+    public init(_ item0: Double, _ item1: Double) {
+        self.item0 = item0
+        self.item1 = item1
+    }
+
+    public init(from decoder: Decoder) throws {
+        var items = try decoder.unkeyedContainer()
+        try self.init(items.decode(Double.self), items.decode(Double.self))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var items = encoder.unkeyedContainer()
+        try items.encode(item0)
+        try items.encode(item1)
+    }
+}
+
+public struct MonsterId: Codable {
+    public var value: UInt32
+
+    public init(_ value: UInt32) {
+        self.value = value
+    }
+
+    public init(from decoder: Decoder) throws {
+        self.value = try decoder.singleValueContainer().decode(UInt32.self)
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        try container.encode(value)
+    }
+}
+
+public struct Lair: Codable {
+    public var owner: MonsterId
+    public var location: Coordinates
+
+    public init(owner: MonsterId, location: Coordinates) {
+        self.owner = owner
+        self.location = location
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        self.owner = try container.decode(MonsterId.self, forKey: HumbleCodingKey("owner"))
+        self.location = try container.decode(Coordinates.self, forKey: HumbleCodingKey("location"))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: HumbleCodingKey.self)
+        try container.encode(owner, forKey: HumbleCodingKey("owner"))
+        try container.encode(location, forKey: HumbleCodingKey("location"))
+    }
+}
+
+/// Events of the legacy API, tagged by a `type` field.
+public enum Event: Codable {
+    case started
+    case moved(x: Double, y: Double)
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        let tag = try container.decode(String.self, forKey: HumbleCodingKey("type"))
+        switch tag {
+        case "Started":
+            self = .started
+        case "Moved":
+            self = try .moved(x: container.decode(Double.self, forKey: HumbleCodingKey("x")), y: container.decode(Double.self, forKey: HumbleCodingKey("y")))
+        default:
+            throw humbleUnknownVariant(decoder, tag, of: "Event")
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        switch self {
+        case .started:
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            try container.encode("Started", forKey: HumbleCodingKey("type"))
+        case .moved(let x, let y):
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            try container.encode("Moved", forKey: HumbleCodingKey("type"))
+            try container.encode(x, forKey: HumbleCodingKey("x"))
+            try container.encode(y, forKey: HumbleCodingKey("y"))
+        }
+    }
+}
+
+public enum Command: Codable {
+    case stop
+    case say(String)
+    case go(Double, Double)
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        let tag = try container.decode(String.self, forKey: HumbleCodingKey("t"))
+        let key = HumbleCodingKey("c")
+        switch tag {
+        case "Stop":
+            self = .stop
+        case "Say":
+            self = try .say(container.decode(String.self, forKey: key))
+        case "Go":
+            var items = try container.nestedUnkeyedContainer(forKey: key)
+            self = try .go(items.decode(Double.self), items.decode(Double.self))
+        default:
+            throw humbleUnknownVariant(decoder, tag, of: "Command")
+        }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        switch self {
+        case .stop:
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            try container.encode("Stop", forKey: HumbleCodingKey("t"))
+        case .say(let value):
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            try container.encode("Say", forKey: HumbleCodingKey("t"))
+            try container.encode(value, forKey: HumbleCodingKey("c"))
+        case .go(let item0, let item1):
+            var container = encoder.container(keyedBy: HumbleCodingKey.self)
+            try container.encode("Go", forKey: HumbleCodingKey("t"))
+            var items = container.nestedUnkeyedContainer(forKey: HumbleCodingKey("c"))
+            try items.encode(item0)
+            try items.encode(item1)
+        }
+    }
+}
+
+public enum Key: Codable {
+    case number(Int32)
+    case name(String)
+    case missing
+
+    public init(from decoder: Decoder) throws {
+        do {
+            self = try .number(decoder.singleValueContainer().decode(Int32.self))
+            return
+        } catch {}
+        do {
+            self = try .name(decoder.singleValueContainer().decode(String.self))
+            return
+        } catch {}
+        if (try? decoder.singleValueContainer().decodeNil()) == true {
+            self = .missing
+            return
+        }
+        throw DecodingError.dataCorrupted(.init(codingPath: decoder.codingPath, debugDescription: "no variant of Key matches"))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        switch self {
+        case .number(let value):
+            var container = encoder.singleValueContainer()
+            try container.encode(value)
+        case .name(let value):
+            var container = encoder.singleValueContainer()
+            try container.encode(value)
+        case .missing:
+            var container = encoder.singleValueContainer()
+            try container.encodeNil()
+        }
+    }
+}
+
+/// A partial update of a monster, whose absent fields are unchanged.
+public struct MonsterPatch: Codable {
+    public var name: String?
+    public var lair: String?
+
+    public init(name: String? = nil, lair: String? = nil) {
+        self.name = name
+        self.lair = lair
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: HumbleCodingKey.self)
+        self.name = try container.decodeIfPresent(String.self, forKey: HumbleCodingKey("name"))
+        self.lair = try container.decodeIfPresent(String.self, forKey: HumbleCodingKey("lair"))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: HumbleCodingKey.self)
+        try container.encodeIfPresent(name, forKey: HumbleCodingKey("name"))
+        try container.encodeIfPresent(lair, forKey: HumbleCodingKey("lair"))
+    }
+}
+
+public enum AbilitiesFlag: String, Codable {
+    case fly = "Fly"
+    case swim = "Swim"
+    /// Breathes fire.
+    case breatheFire = "fire"
+}
+
+/// Things a monster can do.
+public typealias Abilities = Set<AbilitiesFlag>
+
+extension MonsterQuery {
+    func humbleQueryItems() throws -> [URLQueryItem] {
+        var items: [URLQueryItem] = []
+        if let value = name {
+            items.append(URLQueryItem(name: "name", value: value.humbleParameter))
+        }
+        items.append(URLQueryItem(name: "maxResults", value: limit.humbleParameter))
+        if let value = tag {
+            items.append(URLQueryItem(name: "tag", value: value.humbleParameter))
+        }
+        return items
+    }
+}
+
+extension Portrait {
+    func humbleMultipart() throws -> HumbleMultipart {
+        var multipart = HumbleMultipart()
+        if let value = caption {
+            multipart.addText("caption", value)
+        }
+        multipart.addFile("image", image)
+        return multipart
+    }
+}
+
+/// Manages monsters.
+public struct MonsterApi {
+    public var baseURL: URL
+    public var session: URLSession
+
+    public init(baseURL: URL = URL(string: "https://api.example.com/v1")!, session: URLSession = .shared) {
+        self.baseURL = baseURL
+        self.session = session
+    }
+
+    /// Lists monsters.
+    public func getMonsters(query: MonsterQuery) async throws -> [Monster] {
+        let request = try humbleRequest(baseURL, "GET", path: ["monsters"], query: query.humbleQueryItems(), headers: [])
+        return try await humbleSend(session, request)
+    }
+
+    public func getMonstersId(id: Int32, headerXRequestId: String?) async throws -> HumbleResult<Monster, MonsterError> {
+        let request = humbleRequest(baseURL, "GET", path: ["monsters", id.humbleParameter], query: nil, headers: [("X-Request-Id", headerXRequestId.map { $0.humbleParameter })])
+        return try await humbleSend(session, request, domainErrorStatuses: [404])
+    }
+
+    public func postMonsters(body: Monster, idempotencyKey: String = UUID().uuidString) async throws -> Int32 {
+        var request = humbleRequest(baseURL, "POST", path: ["monsters"], query: nil, headers: [("Idempotency-Key", idempotencyKey)])
+        try humbleJSONBody(&request, body)
+        return try await humbleSend(session, request)
+    }
+
+    public func postMonstersIdPortrait(id: Int32, body: Portrait) async throws {
+        var request = humbleRequest(baseURL, "POST", path: ["monsters", id.humbleParameter, "portrait"], query: nil, headers: [])
+        try body.humbleMultipart().apply(to: &request)
+        try await humbleSendNoContent(session, request)
+    }
+
+    public func deleteMonstersId(id: Int32) async throws {
+        let request = humbleRequest(baseURL, "DELETE", path: ["monsters", id.humbleParameter], query: nil, headers: [])
+        try await humbleSendNoContent(session, request)
+    }
+
+    public func getFilesPath(path: String) async throws -> Data {
+        let request = humbleRequest(baseURL, "GET", path: ["files"] + path.split(separator: "/").map(String.init), query: nil, headers: [])
+        return try await humbleSend(session, request)
+    }
+}
+
+public struct TupleOfF64AndF64: Codable {
+    public var item0: Double
+    public var item1: Double
+
+    public init(_ item0: Double, _ item1: Double) {
+        self.item0 = item0
+        self.item1 = item1
+    }
+
+    public init(from decoder: Decoder) throws {
+        var items = try decoder.unkeyedContainer()
+        try self.init(items.decode(Double.self), items.decode(Double.self))
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var items = encoder.unkeyedContainer()
+        try items.encode(item0)
+        try items.encode(item1)
+    }
+}
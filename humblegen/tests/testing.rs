@@ -0,0 +1,96 @@
+use humblegen::testing::{generate_files, generate_workspace_files, SINGLE_FILE};
+use humblegen::Artifact;
+
+const SPEC: &str = r#"
+struct Monster {
+    name: str,
+}
+
+service MonsterApi {
+    GET /monsters -> list[Monster],
+}
+"#;
+
+#[test]
+fn generate_files_of_folder_backend() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator =
+        humblegen::backend::elm::Generator::new(Artifact::ClientEndpoints, "Api".to_owned())
+            .unwrap();
+
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        vec![
+            "BuiltIn/Bytes.elm",
+            "BuiltIn/Uuid.elm",
+            "Config.elm",
+            "Data.elm",
+            "Decode.elm",
+            "Encode.elm",
+            "Form.elm",
+            "Service/MonsterApi.elm",
+            "ServiceBuiltIn.elm",
+        ]
+    );
+    assert!(files["Data.elm"].starts_with("module Api.Data exposing (..)"));
+
+    // deterministic
+    assert_eq!(generate_files(&generator, &spec).unwrap(), files);
+}
+
+#[test]
+fn generate_files_of_single_file_backends() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+
+    let generator = humblegen::backend::rust::Generator::new(Artifact::ServerEndpoints).unwrap();
+    let files = generate_files(&generator, &spec).unwrap();
+    assert_eq!(files.keys().collect::<Vec<_>>(), vec![SINGLE_FILE]);
+    assert!(files[SINGLE_FILE].contains("pub struct Monster"));
+    assert_eq!(generate_files(&generator, &spec).unwrap(), files);
+
+    let files = generate_files(&humblegen::backend::docs::Generator::default(), &spec).unwrap();
+    assert_eq!(files.keys().collect::<Vec<_>>(), vec![SINGLE_FILE]);
+    assert!(files[SINGLE_FILE].contains("MonsterApi"));
+}
+
+#[test]
+fn generate_workspace_files_of_rust_backend() {
+    let workspace =
+        humblegen::workspace::Workspace::load("./tests/workspaces/rust/humblegen-workspace.toml")
+            .unwrap();
+    let generator = humblegen::backend::rust::Generator::new(Artifact::ServerEndpoints).unwrap();
+
+    let files = generate_workspace_files(&generator, &workspace).unwrap();
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        vec!["common.rs", "mod.rs", "monsters.rs", "users.rs"]
+    );
+}
+
+#[test]
+fn client_and_server_are_feature_gated() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = humblegen::backend::rust::Generator::new(Artifact::ClientAndServer).unwrap();
+
+    let code = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    let client = code.find("#[cfg(feature = \"client\")]").unwrap();
+    let server = code.find("#[cfg(feature = \"server\")]").unwrap();
+    assert!(code.find("pub struct Monster").unwrap() < client);
+    assert!(client < code.find("pub struct MonsterApiClient").unwrap());
+    assert!(server < code.find("pub trait MonsterApi").unwrap());
+}
+
+#[test]
+fn tracing_spans_can_be_disabled() {
+    let spec = humblegen::parse(SPEC.as_bytes()).unwrap();
+    let generator = humblegen::backend::rust::Generator::new(Artifact::ServerEndpoints).unwrap();
+    let code = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    assert!(code.contains("\"MonsterApi.get_monsters\""));
+    assert!(code.contains("server::trace_route(span, dispatch)"));
+
+    let generator = generator.with_tracing_spans(false);
+    let code = &generate_files(&generator, &spec).unwrap()[SINGLE_FILE];
+    assert!(!code.contains("\"MonsterApi.get_monsters\""));
+    assert!(!code.contains("trace_route"));
+}
@@ -0,0 +1,49 @@
+use humblegen::resolve::topological_order;
+
+fn order(src: &str) -> Vec<String> {
+    let spec = humblegen::parse(src.as_bytes()).unwrap();
+    topological_order(&spec)
+        .into_iter()
+        .map(|item| item.name().to_owned())
+        .collect()
+}
+
+#[test]
+fn types_precede_their_users() {
+    assert_eq!(
+        order(
+            "service MonsterApi { GET /monsters -> list[Monster] }\n\
+             struct Monster { lair: Lair, stats: map[str][Stats] }\n\
+             enum Lair { Cave, Tower(Room) }\n\
+             struct Room(i32, i32)\n\
+             struct Stats {}"
+        ),
+        ["Room", "Lair", "Stats", "Monster", "MonsterApi"]
+    );
+}
+
+#[test]
+fn related_types_are_grouped_and_unrelated_ones_keep_the_spec_order() {
+    assert_eq!(
+        order(
+            "struct Monster { color: Color }\n\
+             struct User { address: Address }\n\
+             flags Permissions { Read }\n\
+             struct Address {}\n\
+             enum Color { Red }"
+        ),
+        ["Color", "Monster", "Address", "User", "Permissions"]
+    );
+}
+
+#[test]
+fn recursive_types_precede_the_types_using_them_from_outside() {
+    assert_eq!(
+        order(
+            "struct Tree { root: Node }\n\
+             struct Node { children: list[Node], parent: option[Parent] }\n\
+             struct Parent { node: Node }"
+        ),
+        ["Parent", "Node", "Tree"]
+    );
+}
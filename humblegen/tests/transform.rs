@@ -0,0 +1,101 @@
+use humblegen::ast::SpecItem;
+use humblegen::transform::{Subprocess, Transform};
+use humblegen::LibError;
+
+fn parse(src: &str) -> humblegen::Spec {
+    humblegen::parse(src.as_bytes()).unwrap()
+}
+
+fn struct_names(spec: &humblegen::Spec) -> Vec<&str> {
+    spec.iter()
+        .filter(|item| matches!(item, SpecItem::StructDef(_)))
+        .map(SpecItem::name)
+        .collect()
+}
+
+#[test]
+fn closure_transform() {
+    let mut spec = parse("struct Monster { name: str }");
+    let prefix = |spec: &mut humblegen::Spec| -> Result<(), LibError> {
+        for item in spec.iter_mut() {
+            if let SpecItem::StructDef(sdef) = item {
+                sdef.name = format!("Api{}", sdef.name).into();
+            }
+        }
+        Ok(())
+    };
+    prefix.transform(&mut spec).unwrap();
+    assert_eq!(struct_names(&spec), ["ApiMonster"]);
+}
+
+#[test]
+fn subprocess_transform_round_trips_ast_json() {
+    let mut spec = parse(
+        "struct Monster { name: str, .. Stats }\nstruct Stats { optional hp: i32 }\n\
+         service MonsterApi { GET /monsters/{id: i32} -> Monster }",
+    );
+    Subprocess::from_command_line("cat")
+        .transform(&mut spec)
+        .unwrap();
+    assert_eq!(struct_names(&spec), ["Monster", "Stats"]);
+    match &spec.0[0] {
+        SpecItem::StructDef(sdef) => {
+            let fields: Vec<_> = sdef.fields.iter().map(|f| f.pair.name.as_str()).collect();
+            assert_eq!(fields, ["name", "hp"]);
+            assert!(sdef.fields.0[1].optional);
+        }
+        _ => panic!("expected struct"),
+    }
+}
+
+#[test]
+fn subprocess_transform_fails_on_non_zero_exit() {
+    let mut spec = parse("struct Monster { name: str }");
+    let err = Subprocess::from_command_line("false")
+        .transform(&mut spec)
+        .unwrap_err();
+    assert!(matches!(err, LibError::TransformError(_)));
+}
+
+#[test]
+fn subprocess_transform_validates_the_transformed_spec() {
+    let mut spec = parse("service MonsterApi { @timeout(30s) DELETE /monsters/{id: i32} -> () }");
+    let err = Subprocess::new("sed".to_owned(), vec!["s/30s/forever/".to_owned()])
+        .transform(&mut spec)
+        .unwrap_err();
+    assert!(matches!(err, LibError::TransformError(_)));
+    assert!(
+        err.to_string().contains("`@timeout` expects a duration"),
+        "{}",
+        err
+    );
+
+    let err = Subprocess::new(
+        "sh".to_owned(),
+        vec!["-c".to_owned(), r#"cat >/dev/null; echo '[]'"#.to_owned()],
+    )
+    .transform(&mut spec)
+    .unwrap_err();
+    assert!(err.to_string().contains("invalid AST JSON"), "{}", err);
+}
+
+#[test]
+fn subprocess_transform_of_workspaces() {
+    let mut workspace =
+        humblegen::workspace::Workspace::load("./tests/workspaces/rust/humblegen-workspace.toml")
+            .unwrap();
+    let common = workspace.common.0.len();
+    Subprocess::from_command_line("cat")
+        .transform_workspace(&mut workspace)
+        .unwrap();
+    assert_eq!(workspace.common.0.len(), common);
+
+    // the specs reference common types, which must still be defined
+    let err = Subprocess::new(
+        "sed".to_owned(),
+        vec![r#"s/"name": "Picture"/"name": "Image"/"#.to_owned()],
+    )
+    .transform_workspace(&mut workspace)
+    .unwrap_err();
+    assert!(err.to_string().contains("unknown type"), "{}", err);
+}
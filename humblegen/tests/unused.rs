@@ -0,0 +1,101 @@
+use humblegen::resolve::{prune_unused, prune_unused_workspace, unused_definitions};
+use humblegen::workspace::{Workspace, WorkspaceSpec};
+
+fn parse(src: &str) -> humblegen::Spec {
+    humblegen::parse(src.as_bytes()).unwrap()
+}
+
+fn names(spec: &humblegen::Spec) -> Vec<&str> {
+    spec.iter().map(|item| item.name()).collect()
+}
+
+#[test]
+fn definitions_not_reachable_from_services_are_unused() {
+    let spec = parse(
+        "struct Monster { lair: option[Lair], stats: map[str][Stats] }\n\
+         struct Lair(list[Room])\n\
+         enum Room { Cave, Tower { guard: Guard } }\n\
+         struct Guard {}\n\
+         struct Stats {}\n\
+         struct OldMonster { lair: Lair }\n\
+         enum Orphan { Lonely(Stats) }\n\
+         flags Permissions { Read }\n\
+         service MonsterApi { GET /monsters/{id: i32} -> Monster }",
+    );
+    assert_eq!(
+        unused_definitions(&spec),
+        ["OldMonster", "Orphan", "Permissions"]
+    );
+}
+
+#[test]
+fn exported_types_and_the_types_they_use_are_used() {
+    let spec = parse(
+        "@export\nstruct MonsterSpawned { monster: Monster }\n\
+         struct Monster {}\n\
+         struct Orphan {}",
+    );
+    assert_eq!(unused_definitions(&spec), ["Orphan"]);
+}
+
+#[test]
+fn all_definitions_of_specs_without_services_and_exports_are_used() {
+    let spec = parse("struct Monster {}\nstruct Lair {}");
+    assert!(unused_definitions(&spec).is_empty());
+}
+
+#[test]
+fn pruning_removes_unused_definitions() {
+    let mut spec = parse(
+        "struct Monster {}\nstruct Orphan { monster: Monster }\n\
+         service MonsterApi { GET /monsters -> list[Monster] }",
+    );
+    assert_eq!(prune_unused(&mut spec), ["Orphan"]);
+    assert_eq!(names(&spec), ["Monster", "MonsterApi"]);
+
+    let generator =
+        humblegen::backend::rust::Generator::new(humblegen::Artifact::TypesOnly).unwrap();
+    let code = humblegen::testing::generate_files(&generator, &spec).unwrap();
+    assert!(!code[humblegen::testing::SINGLE_FILE].contains("Orphan"));
+}
+
+#[test]
+fn common_definitions_are_used_if_any_spec_uses_them() {
+    let common = "struct Picture {}\nstruct Stats {}\nstruct Unused {}";
+    let mut workspace = Workspace {
+        common: parse(common),
+        specs: vec![
+            WorkspaceSpec {
+                name: "monsters".to_owned(),
+                spec: parse_using_common(
+                    "struct Monster { picture: Picture }\nstruct Orphan { stats: Stats }\n\
+                     service MonsterApi { GET /monsters -> list[Monster] }",
+                    common,
+                ),
+            },
+            WorkspaceSpec {
+                name: "users".to_owned(),
+                spec: parse_using_common(
+                    "struct User { avatar: Picture }\nservice UserApi { GET /users -> list[User] }",
+                    common,
+                ),
+            },
+        ],
+    };
+    assert_eq!(
+        prune_unused_workspace(&mut workspace),
+        ["Stats", "Unused", "Orphan"]
+    );
+    assert_eq!(names(&workspace.common), ["Picture"]);
+    assert_eq!(names(&workspace.specs[0].spec), ["Monster", "MonsterApi"]);
+    assert_eq!(names(&workspace.specs[1].spec), ["User", "UserApi"]);
+}
+
+/// Parse `src` of a workspace spec, which uses the types of `common` without defining them.
+fn parse_using_common(src: &str, common: &str) -> humblegen::Spec {
+    let common_spec = parse(common);
+    let common_names = names(&common_spec);
+    let mut spec = parse(&format!("{}\n{}", src, common));
+    spec.0.retain(|item| !common_names.contains(&item.name()));
+    spec
+}
@@ -0,0 +1,109 @@
+use humblegen::wire_hash::{self, Representation};
+use humblegen::WireCasing;
+use std::collections::BTreeMap;
+
+fn hashes(spec: &str) -> BTreeMap<String, String> {
+    hashes_with(spec, Representation::default())
+}
+
+fn hashes_with(spec: &str, representation: Representation) -> BTreeMap<String, String> {
+    let spec = humblegen::parse(spec.as_bytes()).unwrap();
+    wire_hash::wire_hashes(&spec, representation)
+}
+
+#[test]
+fn types_of_the_same_shape_have_the_same_hash() {
+    let hashes = hashes(
+        r#"
+        /// A monster.
+        struct Monster { name: str, hit_points: u32 }
+        struct Villain { hit_points: u32, name: str }
+        struct Hero { name: str, hit_points: i32 }
+        "#,
+    );
+
+    assert_eq!(hashes["Monster"], hashes["Villain"]);
+    assert_ne!(hashes["Monster"], hashes["Hero"]);
+    assert_eq!(hashes["Monster"].len(), 16);
+}
+
+#[test]
+fn hashes_change_with_the_representation() {
+    let spec = r#"
+        struct Monster { name: str, @json_name("hp") hit_points: u32, optional level: u8 }
+        "#;
+    let renamed = r#"
+        struct Monster { name: str, hit_points: u32, optional level: u8 }
+        "#;
+    let required = r#"
+        struct Monster { name: str, @json_name("hp") hit_points: u32, level: u8 }
+        "#;
+
+    let hash = &hashes(spec)["Monster"];
+    assert_ne!(hash, &hashes(renamed)["Monster"]);
+    assert_ne!(hash, &hashes(required)["Monster"]);
+
+    let camel_case = Representation {
+        wire_casing: WireCasing::CamelCase,
+        ..Representation::default()
+    };
+    assert_eq!(hash, &hashes_with(spec, camel_case)["Monster"]);
+    assert_ne!(
+        hashes(renamed)["Monster"],
+        hashes_with(renamed, camel_case)["Monster"]
+    );
+}
+
+#[test]
+fn hashes_change_with_the_types_used() {
+    let spec = r#"
+        struct Lair { monsters: list[Monster] }
+        struct Monster { color: Color }
+        enum Color { Red, Green }
+        "#;
+    let changed = r#"
+        struct Lair { monsters: list[Monster] }
+        struct Monster { color: Color }
+        enum Color { Red, Blue }
+        "#;
+
+    let (hashes, changed) = (hashes(spec), self::hashes(changed));
+    assert_ne!(hashes["Color"], changed["Color"]);
+    assert_ne!(hashes["Monster"], changed["Monster"]);
+    assert_ne!(hashes["Lair"], changed["Lair"]);
+}
+
+#[test]
+fn recursive_types_are_hashed() {
+    let hashes = hashes(
+        r#"
+        struct Tree { children: list[Tree] }
+        struct Forest { children: list[Forest] }
+        struct Node { child: option[Leaf] }
+        struct Leaf { parent: Node }
+        "#,
+    );
+
+    assert_eq!(hashes["Tree"], hashes["Forest"]);
+    assert_ne!(hashes["Node"], hashes["Leaf"]);
+}
+
+#[test]
+fn manifest_maps_types_to_their_hashes() {
+    let spec = humblegen::parse(
+        r#"
+        struct Monster { name: str }
+        service Monsters { GET /monsters -> list[Monster] }
+        "#
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let manifest: BTreeMap<String, String> =
+        serde_json::from_str(&wire_hash::manifest(&spec, Representation::default())).unwrap();
+    assert_eq!(
+        manifest,
+        wire_hash::wire_hashes(&spec, Representation::default())
+    );
+    assert_eq!(manifest.keys().collect::<Vec<_>>(), vec!["Monster"]);
+}
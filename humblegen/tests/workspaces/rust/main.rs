@@ -0,0 +1,124 @@
+#[path = "protocol/mod.rs"]
+mod protocol;
+use humblegen_rt::hyper;
+use humblegen_rt::multipart::deser_multipart;
+use protocol::common::{ApiError, Picture};
+use protocol::{monsters, users};
+use std::sync::Arc;
+
+struct Monsters;
+
+#[humblegen_rt::async_trait(Sync)]
+impl monsters::MonsterApi for Monsters {
+    type Context = ();
+
+    async fn get_monsters_id(
+        &self,
+        _ctx: Self::Context,
+        id: i32,
+    ) -> monsters::Response<Result<monsters::Monster, ApiError>> {
+        Ok(match id {
+            1 => Ok(monsters::Monster {
+                name: "dragon".to_owned(),
+                picture: None,
+            }),
+            _ => Err(ApiError::NotFound),
+        })
+    }
+
+    async fn post_monsters_pictures(
+        &self,
+        _ctx: Self::Context,
+        post_body: Picture,
+    ) -> monsters::Response<Result<i32, ApiError>> {
+        Ok(Ok(post_body.data.len() as i32))
+    }
+}
+
+struct Users;
+
+#[humblegen_rt::async_trait(Sync)]
+impl users::UserApi for Users {
+    type Context = ();
+
+    async fn get_users_id(
+        &self,
+        _ctx: Self::Context,
+        _id: i32,
+    ) -> users::Response<Result<users::User, ApiError>> {
+        // the common `Picture` is shared, each spec has its own `Monster`
+        Ok(Ok(users::User {
+            name: "alice".to_owned(),
+            avatar: Picture {
+                title: "me".to_owned(),
+                data: vec![],
+            },
+            pet: users::Monster {
+                nickname: "puff".to_owned(),
+            },
+        }))
+    }
+}
+
+async fn get(services: &Arc<Vec<humblegen_rt::server::Service>>, path: &str) -> (u16, String) {
+    let req = hyper::Request::builder()
+        .uri(path)
+        .body(hyper::Body::empty())
+        .unwrap();
+    let resp = humblegen_rt::server::handle_request(Arc::clone(services), req).await;
+    let status = resp.status().as_u16();
+    let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+const BODY: &str = "--X-BOUNDARY\r\n\
+Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+Godzilla\r\n\
+--X-BOUNDARY\r\n\
+Content-Disposition: form-data; name=\"data\"; filename=\"g.png\"\r\n\
+Content-Type: application/octet-stream\r\n\r\n\
+PNG\r\n\
+--X-BOUNDARY--\r\n";
+
+#[tokio::main]
+async fn main() {
+    let monster_services = Arc::new(
+        monsters::Builder::new()
+            .add("/api", monsters::Handler::MonsterApi(Arc::new(Monsters)))
+            .into_services(),
+    );
+    let user_services = Arc::new(
+        users::Builder::new()
+            .add("/api", users::Handler::UserApi(Arc::new(Users)))
+            .into_services(),
+    );
+
+    assert_eq!(
+        get(&monster_services, "/api/monsters/1").await,
+        (200, r#"{"Ok":{"name":"dragon","picture":null}}"#.to_owned())
+    );
+    assert_eq!(
+        get(&monster_services, "/api/monsters/2").await,
+        (404, r#"{"Err":"NotFound"}"#.to_owned())
+    );
+    assert_eq!(
+        get(&user_services, "/api/users/1").await,
+        (
+            200,
+            r#"{"Ok":{"name":"alice","avatar":{"title":"me","data":""},"pet":{"nickname":"puff"}}}"#
+                .to_owned()
+        )
+    );
+
+    let mut req = hyper::Request::builder()
+        .method("POST")
+        .header(
+            hyper::header::CONTENT_TYPE,
+            "multipart/form-data; boundary=X-BOUNDARY",
+        )
+        .body(hyper::Body::from(BODY))
+        .unwrap();
+    let picture: Picture = deser_multipart(&mut req).await.unwrap();
+    assert_eq!(picture.title, "Godzilla");
+    assert_eq!(picture.data, b"PNG".to_vec());
+}
@@ -0,0 +1,45 @@
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = "An uploaded picture."]
+pub struct Picture {
+    #[doc = ""]
+    pub title: String,
+    #[doc = ""]
+    #[serde(deserialize_with = "::humblegen_rt::serialization_helpers::deser_bytes")]
+    #[serde(serialize_with = "::humblegen_rt::serialization_helpers::ser_bytes")]
+    pub data: Vec<u8>,
+}
+#[derive(Debug, Clone, serde :: Deserialize, serde :: Serialize)]
+#[doc = ""]
+pub enum ApiError {
+    #[doc = ""]
+    NotFound,
+    #[doc = ""]
+    Invalid(String),
+}
+impl ::humblegen_rt::wire_hash::WireHash for ApiError {
+    const WIRE_HASH: &'static str = "1abbbeb9152d150e";
+}
+impl ::humblegen_rt::wire_hash::WireHash for Picture {
+    const WIRE_HASH: &'static str = "e0025d3853d8eeaf";
+}
+#[doc = r" The wire hash of each type by its name, as JSON object, see `humblegen_rt::wire_hash`."]
+pub const WIRE_MANIFEST: &str =
+    "{\n  \"ApiError\": \"1abbbeb9152d150e\",\n  \"Picture\": \"e0025d3853d8eeaf\"\n}";
+impl ::humblegen_rt::server::ErrorStatus for ApiError {
+    fn status_code(&self) -> u16 {
+        match self {
+            ApiError::NotFound => 404u16,
+            ApiError::Invalid(..) => 200u16,
+        }
+    }
+}
+impl ::humblegen_rt::multipart::FromMultipart for Picture {
+    fn from_multipart(
+        mut form: ::humblegen_rt::multipart::MultipartForm,
+    ) -> Result<Self, ::humblegen_rt::service_protocol::ErrorResponse> {
+        Ok(Self {
+            title: form.text("title")?,
+            data: form.file("data")?,
+        })
+    }
+}
@@ -0,0 +1,3 @@
+pub mod common;
+pub mod monsters;
+pub mod users;